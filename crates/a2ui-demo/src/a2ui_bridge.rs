@@ -7,6 +7,8 @@
 //!   LLM_MODEL     - Model name (default: kimi-k2.5)
 //!   LLM_API_KEY   - API key (or MOONSHOT_API_KEY for backwards compat)
 //!   LLM_PORT      - Server port (default: 8081)
+//!   LLM_PROVIDER  - `openai` (default) or `replay` for offline runs
+//!   LLM_REPLAY_FILE - Comma-separated SSE fixtures replayed by `replay`
 
 use http_body_util::Full;
 use hyper::body::{Bytes, Incoming};
@@ -30,7 +32,9 @@ const MUREKA_API_URL: &str = "https://api.mureka.ai";
 include!("a2ui_bridge_impl/mureka.rs");
 include!("a2ui_bridge_impl/tools.rs");
 include!("a2ui_bridge_impl/types.rs");
+include!("a2ui_bridge_impl/provider.rs");
 include!("a2ui_bridge_impl/builder.rs");
+include!("a2ui_bridge_impl/streaming.rs");
 include!("a2ui_bridge_impl/server.rs");
 
 // ============================================================================
@@ -42,15 +46,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     env_logger::init();
 
     // LLM configuration from environment
-    let llm_api_url = std::env::var("LLM_API_URL")
-        .unwrap_or_else(|_| "https://api.moonshot.ai/v1/chat/completions".to_string());
-
-    let llm_model = std::env::var("LLM_MODEL")
-        .unwrap_or_else(|_| "kimi-k2.5".to_string());
-
-    let api_key = std::env::var("LLM_API_KEY")
-        .or_else(|_| std::env::var("MOONSHOT_API_KEY"))
-        .unwrap_or_else(|_| "not-needed".to_string());
+    let llm = provider_from_env()?;
 
     let port: u16 = std::env::var("LLM_PORT")
         .ok()
@@ -77,9 +73,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let (tx, _rx) = broadcast::channel::<String>(16);
 
     let state = Arc::new(ServerState {
-        api_key,
-        llm_api_url,
-        llm_model,
+        llm,
         #[cfg(feature = "mureka")]
         mureka_client,
         tx,
@@ -95,8 +89,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     println!("===========================================");
     println!();
     println!("Server:   http://127.0.0.1:{}", port);
-    println!("LLM API:  {}", state.llm_api_url);
-    println!("Model:    {}", state.llm_model);
+    println!("LLM API:  {} ({})", state.llm.endpoint(), state.llm.kind());
+    println!("Model:    {}", state.llm.model());
     #[cfg(feature = "mureka")]
     println!("Music:    {} (set MUREKA_API_KEY to enable)",
         if state.mureka_client.is_some() { "enabled" } else { "disabled" });
@@ -115,6 +109,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     println!("  LLM_MODEL    - Model name");
     println!("  LLM_API_KEY  - API key (or MOONSHOT_API_KEY)");
     println!("  LLM_PORT     - Server port (default: 8081)");
+    println!("  LLM_PROVIDER - openai (default) or replay");
+    println!("  LLM_REPLAY_FILE - SSE fixtures for the replay provider");
     println!();
     println!("Example:");
     println!("  curl -X POST http://127.0.0.1:{}/chat \\", port);
@@ -143,3 +139,173 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VOLUME_FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/llm/volume_control.sse");
+    const TEXT_FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/llm/text_reply.sse");
+
+    fn drain(rx: &mut broadcast::Receiver<String>) -> Vec<Value> {
+        let mut messages = Vec::new();
+        while let Ok(msg) = rx.try_recv() {
+            messages.extend(serde_json::from_str::<Vec<Value>>(&msg).unwrap());
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn test_replay_streams_fragmented_tool_calls() {
+        // 7-byte pieces split SSE events, JSON strings and the emoji in the label
+        let provider = ReplayProvider::from_files(&[VOLUME_FIXTURE]).unwrap().with_chunk_size(7);
+        let (tx, mut rx) = broadcast::channel::<String>(64);
+
+        let response = call_llm_stream(&provider, vec![], &tx, None).await.unwrap();
+        let message = &response.choices[0].message;
+
+        let names: Vec<&str> = message.tool_calls.as_ref().unwrap().iter()
+            .map(|tc| tc.function.name.as_str())
+            .collect();
+        assert_eq!(names, vec![
+            "create_text", "create_slider", "create_text", "create_row",
+            "set_data", "set_data", "render_ui",
+        ]);
+        assert!(message.reasoning_content.as_deref().unwrap().contains("volume control"));
+
+        let streamed = drain(&mut rx);
+        assert_eq!(streamed[0]["beginRendering"]["root"], "streaming-root");
+        let first_text = streamed.iter()
+            .filter_map(|m| m["surfaceUpdate"]["components"].as_array())
+            .flatten()
+            .find(|c| c["component"].get("Text").is_some())
+            .unwrap();
+        assert_eq!(first_text["id"], "volume-label");
+        assert_eq!(first_text["component"]["Text"]["text"]["literalString"], "🔊 Volume");
+    }
+
+    #[tokio::test]
+    async fn test_stream_assembles_surface_progressively() {
+        let provider = ReplayProvider::from_files(&[VOLUME_FIXTURE]).unwrap().with_chunk_size(7);
+        let (tx, mut rx) = broadcast::channel::<String>(64);
+        call_llm_stream(&provider, vec![], &tx, None).await.unwrap();
+
+        let streamed = drain(&mut rx);
+        let roots: Vec<Vec<&str>> = streamed.iter()
+            .filter_map(|m| m["surfaceUpdate"]["components"].as_array())
+            .flatten()
+            .filter(|c| c["id"] == STREAMING_ROOT)
+            .map(|c| c["component"]["Column"]["children"]["explicitList"].as_array().unwrap().iter()
+                .filter_map(|id| id.as_str())
+                .collect())
+            .collect();
+
+        // Grows one top-level component at a time, folds into the row, then
+        // switches to the render_ui root
+        assert_eq!(roots.first().unwrap(), &vec!["volume-label"]);
+        assert!(roots.contains(&vec!["volume-label", "volume-slider", "volume-value"]));
+        assert!(roots.contains(&vec!["volume-row"]));
+
+        let data: Vec<&str> = streamed.iter()
+            .filter_map(|m| m["dataModelUpdate"]["contents"].as_array())
+            .flatten()
+            .filter_map(|c| c["key"].as_str())
+            .collect();
+        assert_eq!(data, vec!["volume", "volumeDisplay"]);
+    }
+
+    #[test]
+    fn test_placeholder_until_arguments_complete() {
+        let mut assembler = StreamingAssembler::new();
+        let delta = |args: &str| json!({"index": 0, "function": {"name": "create_chart", "arguments": args}});
+
+        assert!(assembler.push_delta(&delta(r#"{"id": "sales-ch"#)).is_empty());
+
+        let update = assembler.push_delta(&delta(r#"art", "height": 200, "labels": ["Q1"#));
+        assert_eq!(update[0]["beginRendering"]["root"], STREAMING_ROOT);
+        let components = update[1]["surfaceUpdate"]["components"].as_array().unwrap();
+        assert_eq!(components[0]["id"], "sales-chart");
+        assert_eq!(components[0]["component"]["Skeleton"]["height"], 200.0);
+        assert_eq!(components[1]["component"]["Column"]["children"]["explicitList"], json!(["sales-chart"]));
+
+        // Still streaming: no new messages, one placeholder
+        assert!(assembler.push_delta(&delta(r#"", "Q2"]"#)).is_empty());
+
+        let update = assembler.push_delta(&delta(r#", "values": [1, 2]}"#));
+        let components = update[0]["surfaceUpdate"]["components"].as_array().unwrap();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0]["component"]["Chart"]["labels"], json!(["Q1", "Q2"]));
+
+        let surface = assembler.surface();
+        assert!(surface[1]["surfaceUpdate"]["components"].as_array().unwrap().iter()
+            .all(|c| c["component"].get("Skeleton").is_none()));
+        assert_eq!(assembler.tool_calls()[0].function.name, "create_chart");
+    }
+
+    #[test]
+    fn test_parse_partial_json() {
+        assert_eq!(parse_partial_json(r#"{"id": "volume-sl"#), Some(json!({})));
+        assert_eq!(parse_partial_json(r#"{"id": "a", "text": "Hi, th"#), Some(json!({"id": "a"})));
+        assert_eq!(
+            parse_partial_json(r#"{"id": "c", "series": [{"values": [1, 2]}, {"val"#),
+            Some(json!({"id": "c", "series": [{"values": [1, 2]}]}))
+        );
+        assert_eq!(parse_partial_json(r#"{"id": "q\"x, y", "#), Some(json!({"id": "q\"x, y"})));
+        assert_eq!(parse_partial_json(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_replay_builds_final_surface() {
+        let provider = ReplayProvider::from_files(&[VOLUME_FIXTURE]).unwrap();
+        let (tx, _rx) = broadcast::channel::<String>(64);
+
+        let response = call_llm_stream(&provider, vec![], &tx, None).await.unwrap();
+        let tool_calls = response.choices[0].message.tool_calls.as_ref().unwrap();
+        let a2ui = builder_from_tool_calls(tool_calls).build_a2ui_json();
+
+        assert_eq!(a2ui[0]["beginRendering"]["root"], "volume-row");
+        let ids: Vec<&str> = a2ui[1]["surfaceUpdate"]["components"].as_array().unwrap().iter()
+            .filter_map(|c| c["id"].as_str())
+            .collect();
+        assert_eq!(ids, vec!["volume-label", "volume-slider", "volume-value", "volume-row"]);
+
+        let contents = a2ui[2]["dataModelUpdate"]["contents"].as_array().unwrap();
+        assert_eq!(contents[0]["key"], "volume");
+        assert_eq!(contents[0]["valueNumber"], 50.0);
+        assert_eq!(contents[1]["valueString"], "50%");
+    }
+
+    #[tokio::test]
+    async fn test_replay_text_reply() {
+        let provider = ReplayProvider::from_files(&[TEXT_FIXTURE]).unwrap().with_chunk_size(5);
+        let (tx, mut rx) = broadcast::channel::<String>(64);
+
+        let response = call_llm_stream(&provider, vec![], &tx, None).await.unwrap();
+        let message = &response.choices[0].message;
+
+        assert!(message.tool_calls.is_none());
+        assert!(message.content.as_deref().unwrap().ends_with("just describe what you need! 🎛️"));
+        assert!(drain(&mut rx).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replay_turns_in_order() {
+        let provider = ReplayProvider::from_files(&[TEXT_FIXTURE, VOLUME_FIXTURE]).unwrap();
+        let (tx, _rx) = broadcast::channel::<String>(64);
+
+        let first = call_llm_stream(&provider, vec![], &tx, None).await.unwrap();
+        assert!(first.choices[0].message.tool_calls.is_none());
+
+        // The last transcript repeats once the list is exhausted
+        for _ in 0..2 {
+            let next = call_llm_stream(&provider, vec![], &tx, None).await.unwrap();
+            assert_eq!(next.choices[0].message.tool_calls.as_ref().unwrap().len(), 7);
+        }
+    }
+
+    #[test]
+    fn test_replay_missing_fixture() {
+        assert!(ReplayProvider::from_files(&["does/not/exist.sse"]).is_err());
+        assert!(ReplayProvider::from_files(&[]).is_err());
+    }
+}
@@ -2,8 +2,8 @@
 // A2UI Builder - Converts tool calls to A2UI JSON
 // ============================================================================
 
-struct A2uiBuilder {
-    components: Vec<Value>,
+pub(crate) struct A2uiBuilder {
+    pub(crate) components: Vec<Value>,
     data_contents: Vec<Value>,
     root_id: Option<String>,
     #[cfg(feature = "mureka")]
@@ -15,7 +15,7 @@ struct A2uiBuilder {
 }
 
 impl A2uiBuilder {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         A2uiBuilder {
             components: Vec::new(),
             data_contents: Vec::new(),
@@ -27,7 +27,7 @@ impl A2uiBuilder {
         }
     }
 
-    fn process_tool_call(&mut self, name: &str, args: &Value) {
+    pub(crate) fn process_tool_call(&mut self, name: &str, args: &Value) {
         match name {
             "create_text" => self.create_text(args),
             "create_button" => self.create_button(args),
@@ -367,7 +367,47 @@ impl A2uiBuilder {
         self.generated_audio = songs;
     }
 
-    fn build_a2ui_json(&self) -> Value {
+    /// IDs referenced as children by containers (Column, Row, Card, Button)
+    fn child_ids(&self) -> std::collections::HashSet<String> {
+        let mut child_ids = std::collections::HashSet::new();
+        for comp in &self.components {
+            let c = &comp["component"];
+            // Column children
+            if let Some(kids) = c["Column"]["children"]["explicitList"].as_array() {
+                for kid in kids {
+                    if let Some(id) = kid.as_str() { child_ids.insert(id.to_string()); }
+                }
+            }
+            // Row children
+            if let Some(kids) = c["Row"]["children"]["explicitList"].as_array() {
+                for kid in kids {
+                    if let Some(id) = kid.as_str() { child_ids.insert(id.to_string()); }
+                }
+            }
+            // Card child
+            if let Some(id) = c["Card"]["child"].as_str() { child_ids.insert(id.to_string()); }
+            // Button child
+            if let Some(id) = c["Button"]["child"].as_str() { child_ids.insert(id.to_string()); }
+        }
+        child_ids
+    }
+
+    /// Components not referenced as children by others, in creation order.
+    /// A redefined ID is listed once.
+    fn top_level_ids(&self) -> Vec<String> {
+        let child_ids = self.child_ids();
+        let mut top_level: Vec<String> = Vec::new();
+        for comp in &self.components {
+            if let Some(id) = comp["id"].as_str() {
+                if !child_ids.contains(id) && !top_level.iter().any(|t| t == id) {
+                    top_level.push(id.to_string());
+                }
+            }
+        }
+        top_level
+    }
+
+    pub(crate) fn build_a2ui_json(&self) -> Value {
         let root = self.root_id.as_deref().unwrap_or("root");
         let mut components = self.components.clone();
 
@@ -378,35 +418,7 @@ impl A2uiBuilder {
         });
 
         if !root_exists {
-            // Collect all IDs that are referenced as children by other components
-            let mut child_ids = std::collections::HashSet::new();
-            for comp in &components {
-                let c = &comp["component"];
-                // Column children
-                if let Some(kids) = c["Column"]["children"]["explicitList"].as_array() {
-                    for kid in kids {
-                        if let Some(id) = kid.as_str() { child_ids.insert(id.to_string()); }
-                    }
-                }
-                // Row children
-                if let Some(kids) = c["Row"]["children"]["explicitList"].as_array() {
-                    for kid in kids {
-                        if let Some(id) = kid.as_str() { child_ids.insert(id.to_string()); }
-                    }
-                }
-                // Card child
-                if let Some(id) = c["Card"]["child"].as_str() { child_ids.insert(id.to_string()); }
-                // Button child
-                if let Some(id) = c["Button"]["child"].as_str() { child_ids.insert(id.to_string()); }
-            }
-
-            // Top-level = components whose IDs are not referenced as children
-            let top_level: Vec<String> = components.iter()
-                .filter_map(|c| {
-                    let id = c["id"].as_str()?;
-                    if !child_ids.contains(id) { Some(id.to_string()) } else { None }
-                })
-                .collect();
+            let top_level = self.top_level_ids();
 
             warn!("Root '{}' not found, auto-creating Column with {} top-level children", root, top_level.len());
 
@@ -0,0 +1,201 @@
+// ============================================================================
+// LLM Providers - where streamed chat completions come from
+// ============================================================================
+
+/// Raw bytes of an OpenAI-style SSE response, in whatever pieces the
+/// transport delivers them
+type LlmByteStream = std::pin::Pin<Box<dyn futures_util::Stream<Item = Result<Vec<u8>, String>> + Send>>;
+
+type LlmStreamFuture<'a> = std::pin::Pin<Box<dyn std::future::Future<Output = Result<LlmByteStream, String>> + Send + 'a>>;
+
+/// Source of streamed chat completions with tool calls.
+///
+/// Implementations only deliver the SSE bytes; parsing `delta.tool_calls`
+/// and turning them into A2UI happens in `call_llm_stream`, so every
+/// provider exercises the same pipeline.
+trait LlmProvider: Send + Sync {
+    /// Short provider kind for logs and /status
+    fn kind(&self) -> &'static str;
+
+    /// Where responses come from (API URL or fixture path)
+    fn endpoint(&self) -> String;
+
+    /// Model name reported in /status
+    fn model(&self) -> &str;
+
+    /// Start a streaming chat completion
+    fn stream_chat(&self, messages: Vec<Value>, tools: Value) -> LlmStreamFuture<'_>;
+}
+
+/// Build the provider selected by the environment.
+///
+///   LLM_PROVIDER     - `openai` (default) or `replay`
+///   LLM_REPLAY_FILE  - Comma-separated SSE fixture files for `replay`
+///   LLM_REPLAY_CHUNK - Bytes per replayed piece (default: 64)
+fn provider_from_env() -> Result<Box<dyn LlmProvider>, String> {
+    match std::env::var("LLM_PROVIDER").as_deref().unwrap_or("openai") {
+        "openai" => {
+            let api_url = std::env::var("LLM_API_URL")
+                .unwrap_or_else(|_| "https://api.moonshot.ai/v1/chat/completions".to_string());
+
+            let model = std::env::var("LLM_MODEL")
+                .unwrap_or_else(|_| "kimi-k2.5".to_string());
+
+            let api_key = std::env::var("LLM_API_KEY")
+                .or_else(|_| std::env::var("MOONSHOT_API_KEY"))
+                .unwrap_or_else(|_| "not-needed".to_string());
+
+            Ok(Box::new(OpenAiProvider::new(api_url, model, api_key)))
+        }
+        "replay" => {
+            let files = std::env::var("LLM_REPLAY_FILE")
+                .map_err(|_| "LLM_PROVIDER=replay requires LLM_REPLAY_FILE".to_string())?;
+            let paths: Vec<&str> = files.split(',').map(str::trim).filter(|p| !p.is_empty()).collect();
+            let mut provider = ReplayProvider::from_files(&paths)?;
+            if let Some(chunk_size) = std::env::var("LLM_REPLAY_CHUNK").ok().and_then(|c| c.parse().ok()) {
+                provider = provider.with_chunk_size(chunk_size);
+            }
+            Ok(Box::new(provider))
+        }
+        other => Err(format!("Unknown LLM_PROVIDER: {} (expected openai or replay)", other)),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// OpenAI-compatible HTTP provider
+// ----------------------------------------------------------------------------
+
+struct OpenAiProvider {
+    api_url: String,
+    model: String,
+    api_key: String,
+    client: reqwest::Client,
+}
+
+impl OpenAiProvider {
+    fn new(api_url: String, model: String, api_key: String) -> Self {
+        OpenAiProvider {
+            api_url,
+            model,
+            api_key,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl LlmProvider for OpenAiProvider {
+    fn kind(&self) -> &'static str {
+        "openai"
+    }
+
+    fn endpoint(&self) -> String {
+        self.api_url.clone()
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn stream_chat(&self, messages: Vec<Value>, tools: Value) -> LlmStreamFuture<'_> {
+        Box::pin(async move {
+            use futures_util::StreamExt;
+
+            let request_body = json!({
+                "model": self.model,
+                "messages": messages,
+                "tools": tools,
+                "temperature": 1,
+                "max_tokens": 8192,
+                "stream": true
+            });
+
+            let response = self.client
+                .post(&self.api_url)
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .json(&request_body)
+                .send()
+                .await
+                .map_err(|e| format!("Request failed: {}", e))?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(format!("API error ({}): {}", status, body));
+            }
+
+            let stream = response
+                .bytes_stream()
+                .map(|chunk| chunk.map(|b| b.to_vec()).map_err(|e| format!("Stream error: {}", e)));
+
+            Ok(Box::pin(stream) as LlmByteStream)
+        })
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Replay provider - recorded SSE transcripts, no network
+// ----------------------------------------------------------------------------
+
+/// Replays captured SSE responses (the raw `data: {...}` lines of a streamed
+/// chat completion). Successive calls replay successive transcripts; the
+/// last one repeats once the list is exhausted.
+struct ReplayProvider {
+    paths: Vec<String>,
+    transcripts: Vec<Vec<u8>>,
+    /// Bytes per emitted piece; small values split events and UTF-8
+    /// sequences the way a slow network does
+    chunk_size: usize,
+    next: std::sync::atomic::AtomicUsize,
+}
+
+impl ReplayProvider {
+    fn from_files(paths: &[&str]) -> Result<Self, String> {
+        if paths.is_empty() {
+            return Err("Replay provider needs at least one fixture file".to_string());
+        }
+
+        let transcripts = paths.iter()
+            .map(|path| std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ReplayProvider {
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+            transcripts,
+            chunk_size: 64,
+            next: std::sync::atomic::AtomicUsize::new(0),
+        })
+    }
+
+    fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+}
+
+impl LlmProvider for ReplayProvider {
+    fn kind(&self) -> &'static str {
+        "replay"
+    }
+
+    fn endpoint(&self) -> String {
+        self.paths.join(",")
+    }
+
+    fn model(&self) -> &str {
+        "replay"
+    }
+
+    fn stream_chat(&self, _messages: Vec<Value>, _tools: Value) -> LlmStreamFuture<'_> {
+        let turn = self.next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let transcript = &self.transcripts[turn.min(self.transcripts.len() - 1)];
+        let pieces: Vec<Result<Vec<u8>, String>> = transcript
+            .chunks(self.chunk_size)
+            .map(|c| Ok(c.to_vec()))
+            .collect();
+
+        Box::pin(async move {
+            Ok(Box::pin(futures_util::stream::iter(pieces)) as LlmByteStream)
+        })
+    }
+}
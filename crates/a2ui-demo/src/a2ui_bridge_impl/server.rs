@@ -3,9 +3,7 @@
 // ============================================================================
 
 struct ServerState {
-    api_key: String,
-    llm_api_url: String,
-    llm_model: String,
+    llm: Box<dyn LlmProvider>,
     #[cfg(feature = "mureka")]
     mureka_client: Option<MurekaClient>,
     tx: broadcast::Sender<String>,
@@ -48,160 +46,110 @@ async fn call_llm(api_url: &str, model: &str, api_key: &str, messages: Vec<Value
     serde_json::from_str(&body).map_err(|e| format!("Failed to parse response: {} - Body: {}", e, body))
 }
 
-/// Streaming version of call_llm - broadcasts components as they arrive
+/// Streaming version of call_llm - broadcasts components as they arrive.
+/// Each tool call becomes its component as soon as its arguments are complete
+/// (see `StreamingAssembler`). When `live_file` is set, the accumulated surface
+/// is also mirrored there for the watch-server and /rpc polling.
 async fn call_llm_stream(
-    api_url: &str,
-    model: &str,
-    api_key: &str,
+    provider: &dyn LlmProvider,
     messages: Vec<Value>,
     tx: &broadcast::Sender<String>,
+    live_file: Option<&str>,
 ) -> Result<LlmResponse, String> {
     use futures_util::StreamExt;
 
-    let client = reqwest::Client::new();
+    let mut stream = provider.stream_chat(messages, get_a2ui_tools()).await?;
 
-    let request_body = json!({
-        "model": model,
-        "messages": messages,
-        "tools": get_a2ui_tools(),
-        "temperature": 1,
-        "max_tokens": 8192,
-        "stream": true
-    });
+    let mut assembler = StreamingAssembler::new();
+    let mut content = String::new();
+    let mut reasoning = String::new();
 
-    let response = client
-        .post(api_url)
-        .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
-
-    let status = response.status();
-    if !status.is_success() {
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("API error ({}): {}", status, body));
+    // Clear ui_live.json at start of new stream
+    if let Some(path) = live_file {
+        let _ = std::fs::write(path, "[]");
     }
 
-    // Accumulate tool calls from stream
-    let mut tool_calls: HashMap<i64, (String, String, String)> = HashMap::new(); // index -> (id, name, arguments)
-    let mut processed_indices: std::collections::HashSet<i64> = std::collections::HashSet::new();
-    let mut sent_begin = false;
-    let mut accumulated_components: Vec<Value> = Vec::new(); // For ui_live.json updates
+    // Raw bytes: a network chunk may end in the middle of a UTF-8 sequence,
+    // so only complete events are decoded
+    let mut buffer: Vec<u8> = Vec::new();
 
-    // Clear ui_live.json at start of new stream
-    let _ = std::fs::write("ui_live.json", "[]");
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        buffer.extend_from_slice(&chunk);
 
-    let mut stream = response.bytes_stream();
-    let mut buffer = String::new();
+        // Process complete SSE events
+        while let Some(pos) = buffer.windows(2).position(|w| w == b"\n\n") {
+            let event: Vec<u8> = buffer.drain(..pos + 2).collect();
+            let line = String::from_utf8_lossy(&event[..pos]).to_string();
 
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Stream error: {}", e))?;
-        buffer.push_str(&String::from_utf8_lossy(&chunk));
-
-        // Process complete SSE lines
-        while let Some(pos) = buffer.find("\n\n") {
-            let line = buffer[..pos].to_string();
-            buffer = buffer[pos + 2..].to_string();
-
-            if line.starts_with("data: ") {
-                let data = &line[6..];
-                if data == "[DONE]" {
-                    continue;
+            let Some(data) = line.strip_prefix("data: ") else {
+                continue;
+            };
+            if data == "[DONE]" {
+                continue;
+            }
+
+            let Ok(chunk_json) = serde_json::from_str::<Value>(data) else {
+                continue;
+            };
+
+            let choices = chunk_json.get("choices").and_then(|c| c.as_array());
+            for delta in choices.into_iter().flatten().filter_map(|choice| choice.get("delta")) {
+                if let Some(text) = delta.get("content").and_then(|c| c.as_str()) {
+                    content.push_str(text);
+                }
+                if let Some(text) = delta.get("reasoning_content").and_then(|c| c.as_str()) {
+                    reasoning.push_str(text);
                 }
 
-                if let Ok(chunk_json) = serde_json::from_str::<Value>(data) {
-                    // Extract delta tool_calls
-                    if let Some(choices) = chunk_json.get("choices").and_then(|c| c.as_array()) {
-                        for choice in choices {
-                            if let Some(delta) = choice.get("delta") {
-                                if let Some(calls) = delta.get("tool_calls").and_then(|t| t.as_array()) {
-                                    for call in calls {
-                                        let index = call.get("index").and_then(|i| i.as_i64()).unwrap_or(0);
-                                        let id = call.get("id").and_then(|s| s.as_str()).unwrap_or("");
-                                        let func = call.get("function");
-                                        let name = func.and_then(|f| f.get("name")).and_then(|n| n.as_str()).unwrap_or("");
-                                        let args_chunk = func.and_then(|f| f.get("arguments")).and_then(|a| a.as_str()).unwrap_or("");
-
-                                        let entry = tool_calls.entry(index).or_insert_with(|| (String::new(), String::new(), String::new()));
-
-                                        if !id.is_empty() {
-                                            entry.0 = id.to_string();
-                                        }
-                                        if !name.is_empty() {
-                                            entry.1 = name.to_string();
-                                        }
-                                        entry.2.push_str(args_chunk);
-
-                                        // Try to parse complete arguments and send component immediately
-                                        if !entry.1.is_empty() && !entry.2.is_empty() && !processed_indices.contains(&index) {
-                                            if let Ok(args) = serde_json::from_str::<Value>(&entry.2) {
-                                                // Mark as processed
-                                                processed_indices.insert(index);
-
-                                                // Send beginRendering on first component
-                                                if !sent_begin {
-                                                    sent_begin = true;
-                                                    let begin_msg = json!([
-                                                        {"beginRendering": {"surfaceId": "main", "root": "streaming-root"}}
-                                                    ]);
-                                                    let _ = tx.send(begin_msg.to_string());
-                                                    info!("Sent beginRendering");
-                                                }
+                let calls = delta.get("tool_calls").and_then(|t| t.as_array());
+                for call in calls.into_iter().flatten() {
+                    let update = assembler.push_delta(call);
+                    if update.is_empty() {
+                        continue;
+                    }
 
-                                                // Build component JSON directly based on tool name
-                                                let component = build_component_json(&entry.1, &args);
-                                                if let Some(comp) = component {
-                                                    let update_msg = json!([
-                                                        {"surfaceUpdate": {"surfaceId": "main", "components": [comp.clone()]}}
-                                                    ]);
-                                                    let _ = tx.send(update_msg.to_string());
-                                                    info!("Sent component: {}", entry.1);
-
-                                                    // Accumulate and write to ui_live.json for /rpc polling
-                                                    accumulated_components.push(comp);
-                                                    let a2ui = json!([
-                                                        {"beginRendering": {"surfaceId": "main", "root": "streaming-root"}},
-                                                        {"surfaceUpdate": {"surfaceId": "main", "components": accumulated_components}},
-                                                        {"dataModelUpdate": {"surfaceId": "main", "path": "/", "contents": []}}
-                                                    ]);
-                                                    let _ = std::fs::write("ui_live.json", serde_json::to_string(&a2ui).unwrap_or_default());
-                                                    debug!("Updated ui_live.json ({} components)", accumulated_components.len());
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                    let _ = tx.send(Value::Array(update).to_string());
+
+                    // Mirror the surface so far to ui_live.json for /rpc polling
+                    if let Some(path) = live_file {
+                        let _ = std::fs::write(path, assembler.surface().to_string());
+                        debug!("Updated {}", path);
                     }
                 }
             }
         }
     }
 
-    // Build final response from accumulated tool calls
+    // Build final response from accumulated tool calls, in call order
     // Note: Components have already been streamed, this is for compatibility
-    let tool_call_list: Vec<LlmToolCall> = tool_calls.into_iter()
-        .filter(|(_, (_, name, _))| !name.is_empty())
-        .map(|(_, (id, name, args))| LlmToolCall {
-            id,
-            function: LlmFunctionCall { name, arguments: args },
-        })
-        .collect();
+    let tool_call_list = assembler.tool_calls();
 
     Ok(LlmResponse {
         choices: vec![LlmChoice {
             message: LlmMessage {
-                content: None,
-                reasoning_content: None,
+                content: if content.is_empty() { None } else { Some(content) },
+                reasoning_content: if reasoning.is_empty() { None } else { Some(reasoning) },
                 tool_calls: if tool_call_list.is_empty() { None } else { Some(tool_call_list) },
             },
         }],
     })
 }
 
+/// Run every tool call through a fresh builder
+fn builder_from_tool_calls(tool_calls: &[LlmToolCall]) -> A2uiBuilder {
+    let mut builder = A2uiBuilder::new();
+
+    for tc in tool_calls {
+        let args: Value = serde_json::from_str(&tc.function.arguments)
+            .unwrap_or(json!({}));
+        debug!("Tool: {}({})", tc.function.name, tc.function.arguments);
+        builder.process_tool_call(&tc.function.name, &args);
+    }
+
+    builder
+}
+
 // ============================================================================
 // HTTP Handlers
 // ============================================================================
@@ -295,7 +243,7 @@ Example for music generation:
             messages.push(json!({"role": "user", "content": chat_req.message}));
 
             // Call LLM API with streaming (broadcasts components as they arrive)
-            match call_llm_stream(&state.llm_api_url, &state.llm_model, &state.api_key, messages.clone(), &state.tx).await {
+            match call_llm_stream(state.llm.as_ref(), messages.clone(), &state.tx, Some("ui_live.json")).await {
                 Ok(response) => {
                     if let Some(choice) = response.choices.first() {
                         // Log reasoning if present
@@ -307,14 +255,8 @@ Example for music generation:
                         if let Some(tool_calls) = &choice.message.tool_calls {
                             info!("Received {} tool calls", tool_calls.len());
 
-                            let mut builder = A2uiBuilder::new();
-
-                            for tc in tool_calls {
-                                let args: Value = serde_json::from_str(&tc.function.arguments)
-                                    .unwrap_or(json!({}));
-                                debug!("Tool: {}({})", tc.function.name, tc.function.arguments);
-                                builder.process_tool_call(&tc.function.name, &args);
-                            }
+                            #[cfg_attr(not(feature = "mureka"), allow(unused_mut))]
+                            let mut builder = builder_from_tool_calls(tool_calls);
 
                             // Handle pending music generation (only with mureka feature)
                             #[cfg(feature = "mureka")]
@@ -568,8 +510,9 @@ Example for music generation:
             let history = state.conversation.read().await;
             let status = json!({
                 "status": "running",
-                "llm_api_url": state.llm_api_url,
-                "model": state.llm_model,
+                "llm_provider": state.llm.kind(),
+                "llm_api_url": state.llm.endpoint(),
+                "model": state.llm.model(),
                 "conversation_turns": history.len() / 2,
                 "endpoints": {
                     "POST /chat": "Send message to generate UI",
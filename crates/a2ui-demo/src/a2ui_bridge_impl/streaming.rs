@@ -0,0 +1,278 @@
+// ============================================================================
+// Streaming Assembler - Builds the surface while tool calls are still arriving
+// ============================================================================
+
+/// Provisional root shown until `render_ui` names the real one
+const STREAMING_ROOT: &str = "streaming-root";
+
+/// One tool call as it accumulates from `delta.tool_calls`
+#[derive(Default)]
+struct StreamingCall {
+    id: String,
+    name: String,
+    arguments: String,
+    /// Arguments parsed and run through the builder
+    done: bool,
+    /// Skeleton placeholder shown while the arguments stream
+    placeholder: Option<String>,
+}
+
+/// Turns streamed tool-call fragments into incremental A2UI messages.
+///
+/// Each call goes through the same `A2uiBuilder` as the final surface as soon
+/// as its arguments are complete JSON. Component calls whose `id` is already
+/// known get an `MpSkeleton` placeholder in the meantime. Everything hangs off
+/// a provisional `streaming-root` Column listing the top-level components,
+/// which switches to the `render_ui` root once that call arrives.
+struct StreamingAssembler {
+    builder: A2uiBuilder,
+    calls: HashMap<i64, StreamingCall>,
+    /// Skeleton components still waiting for their real definition
+    placeholders: Vec<Value>,
+    last_root: Option<Value>,
+    sent_begin: bool,
+}
+
+impl StreamingAssembler {
+    fn new() -> Self {
+        StreamingAssembler {
+            builder: A2uiBuilder::new(),
+            calls: HashMap::new(),
+            placeholders: Vec::new(),
+            last_root: None,
+            sent_begin: false,
+        }
+    }
+
+    /// Feed one `delta.tool_calls` entry. Returns the A2UI messages that
+    /// bring the surface up to date (empty if nothing visible changed).
+    fn push_delta(&mut self, call: &Value) -> Vec<Value> {
+        let index = call.get("index").and_then(|i| i.as_i64()).unwrap_or(0);
+        let id = call.get("id").and_then(|s| s.as_str()).unwrap_or("");
+        let func = call.get("function");
+        let name = func.and_then(|f| f.get("name")).and_then(|n| n.as_str()).unwrap_or("");
+        let args_chunk = func.and_then(|f| f.get("arguments")).and_then(|a| a.as_str()).unwrap_or("");
+
+        let entry = self.calls.entry(index).or_default();
+        if !id.is_empty() {
+            entry.id = id.to_string();
+        }
+        if !name.is_empty() {
+            entry.name = name.to_string();
+        }
+        entry.arguments.push_str(args_chunk);
+
+        if entry.done || entry.name.is_empty() || entry.arguments.is_empty() {
+            return Vec::new();
+        }
+
+        match serde_json::from_str::<Value>(&entry.arguments) {
+            Ok(args) => {
+                entry.done = true;
+                let name = entry.name.clone();
+                let placeholder = entry.placeholder.take();
+                self.complete_call(&name, &args, placeholder)
+            }
+            Err(_) => {
+                if entry.placeholder.is_some() || !entry.name.starts_with("create_") {
+                    return Vec::new();
+                }
+                let Some(partial) = parse_partial_json(&entry.arguments) else {
+                    return Vec::new();
+                };
+                let Some(component_id) = partial["id"].as_str().map(String::from) else {
+                    return Vec::new();
+                };
+                entry.placeholder = Some(component_id.clone());
+                let name = entry.name.clone();
+                self.add_placeholder(&name, &component_id, &partial)
+            }
+        }
+    }
+
+    /// Run a finished call through the builder and emit what it added
+    fn complete_call(&mut self, name: &str, args: &Value, placeholder: Option<String>) -> Vec<Value> {
+        let first_component = self.builder.components.len();
+        let first_data = self.builder.data_contents.len();
+
+        self.builder.process_tool_call(name, args);
+        info!("Streamed tool call: {}", name);
+
+        if let Some(placeholder_id) = placeholder {
+            self.placeholders.retain(|p| p["id"].as_str() != Some(placeholder_id.as_str()));
+        }
+
+        let mut components = self.builder.components[first_component..].to_vec();
+        let mut messages = Vec::new();
+
+        if let Some(root) = self.root_if_changed() {
+            components.push(root);
+        }
+        if !components.is_empty() {
+            messages.extend(self.begin_if_needed());
+            messages.push(json!({"surfaceUpdate": {"surfaceId": "main", "components": components}}));
+        }
+
+        let contents = &self.builder.data_contents[first_data..];
+        if !contents.is_empty() {
+            messages.push(json!({"dataModelUpdate": {"surfaceId": "main", "path": "/", "contents": contents}}));
+        }
+
+        messages
+    }
+
+    fn add_placeholder(&mut self, name: &str, component_id: &str, partial: &Value) -> Vec<Value> {
+        // A redefinition keeps showing the previous version until it completes
+        if self.builder.components.iter().any(|c| c["id"].as_str() == Some(component_id)) {
+            return Vec::new();
+        }
+
+        let mut skeleton = json!({
+            "label": {"literalString": format!("Loading {}...", name.trim_start_matches("create_"))}
+        });
+        if name == "create_chart" {
+            skeleton["height"] = json!(partial["height"].as_f64().unwrap_or(300.0));
+        }
+
+        let placeholder = json!({"id": component_id, "component": {"Skeleton": skeleton}});
+        self.placeholders.push(placeholder.clone());
+        debug!("Placeholder for {}: {}", name, component_id);
+
+        let mut components = vec![placeholder];
+        if let Some(root) = self.root_if_changed() {
+            components.push(root);
+        }
+
+        let mut messages = self.begin_if_needed();
+        messages.push(json!({"surfaceUpdate": {"surfaceId": "main", "components": components}}));
+        messages
+    }
+
+    fn begin_if_needed(&mut self) -> Vec<Value> {
+        if self.sent_begin {
+            return Vec::new();
+        }
+        self.sent_begin = true;
+        info!("Sent beginRendering");
+        vec![json!({"beginRendering": {"surfaceId": "main", "root": STREAMING_ROOT}})]
+    }
+
+    /// Provisional root: the `render_ui` root once it exists, otherwise every
+    /// top-level component followed by the placeholders not yet nested anywhere
+    fn root_component(&self) -> Value {
+        let defined = |id: &str| self.builder.components.iter().any(|c| c["id"].as_str() == Some(id));
+
+        let children: Vec<String> = match self.builder.root_id.as_deref() {
+            Some(root) if defined(root) => vec![root.to_string()],
+            _ => {
+                let child_ids = self.builder.child_ids();
+                let mut children = self.builder.top_level_ids();
+                for placeholder in &self.placeholders {
+                    if let Some(id) = placeholder["id"].as_str() {
+                        if !defined(id) && !child_ids.contains(id) {
+                            children.push(id.to_string());
+                        }
+                    }
+                }
+                children
+            }
+        };
+
+        json!({
+            "id": STREAMING_ROOT,
+            "component": {
+                "Column": {
+                    "children": {"explicitList": children}
+                }
+            }
+        })
+    }
+
+    fn root_if_changed(&mut self) -> Option<Value> {
+        let root = self.root_component();
+        if self.last_root.as_ref() == Some(&root) {
+            return None;
+        }
+        self.last_root = Some(root.clone());
+        Some(root)
+    }
+
+    /// Everything streamed so far as one message list, for `ui_live.json`
+    fn surface(&self) -> Value {
+        let mut components = self.builder.components.clone();
+        components.extend(self.placeholders.iter().cloned());
+        components.push(self.root_component());
+
+        json!([
+            {"beginRendering": {"surfaceId": "main", "root": STREAMING_ROOT}},
+            {"surfaceUpdate": {"surfaceId": "main", "components": components}},
+            {"dataModelUpdate": {"surfaceId": "main", "path": "/", "contents": self.builder.data_contents}}
+        ])
+    }
+
+    /// The accumulated calls in stream order
+    fn tool_calls(&self) -> Vec<LlmToolCall> {
+        let mut indexed: Vec<(&i64, &StreamingCall)> = self.calls.iter()
+            .filter(|(_, call)| !call.name.is_empty())
+            .collect();
+        indexed.sort_by_key(|(index, _)| **index);
+
+        indexed.into_iter()
+            .map(|(_, call)| LlmToolCall {
+                id: call.id.clone(),
+                function: LlmFunctionCall {
+                    name: call.name.clone(),
+                    arguments: call.arguments.clone(),
+                },
+            })
+            .collect()
+    }
+}
+
+/// Best-effort parse of a JSON document whose tail is still streaming.
+///
+/// Cuts back to the last point outside a string where everything before it
+/// is complete (before a `,`, after a closing bracket) and closes
+/// the brackets still open. A string that is still being written is dropped
+/// rather than trusted, so `{"id": "volume-sl` yields `{}`.
+fn parse_partial_json(text: &str) -> Option<Value> {
+    let mut stack: Vec<u8> = Vec::new();
+    let mut cuts: Vec<(usize, Vec<u8>)> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (pos, byte) in text.bytes().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                stack.push(if byte == b'{' { b'}' } else { b']' });
+                // Only the outermost container may be cut back to empty
+                if stack.len() == 1 {
+                    cuts.push((pos + 1, stack.clone()));
+                }
+            }
+            b'}' | b']' => {
+                stack.pop();
+                cuts.push((pos + 1, stack.clone()));
+            }
+            b',' => cuts.push((pos, stack.clone())),
+            _ => {}
+        }
+    }
+
+    cuts.into_iter().rev().find_map(|(pos, open)| {
+        let mut candidate = text[..pos].to_string();
+        candidate.extend(open.iter().rev().map(|&b| b as char));
+        serde_json::from_str(&candidate).ok()
+    })
+}
@@ -2,7 +2,7 @@
 // A2UI Component Tools Definition
 // ============================================================================
 
-fn get_a2ui_tools() -> Value {
+pub(crate) fn get_a2ui_tools() -> Value {
     json!([
         {
             "type": "function",
@@ -234,61 +234,3 @@ fn get_a2ui_tools() -> Value {
     ])
 }
 
-
-fn build_component_json(name: &str, args: &Value) -> Option<Value> {
-    let id = args.get("id").and_then(|v| v.as_str()).unwrap_or("comp");
-
-    match name {
-        "create_text" => {
-            let text = args.get("text").and_then(|v| v.as_str()).unwrap_or("");
-            let usage_hint = args.get("usage_hint").and_then(|v| v.as_str());
-            let mut text_obj = json!({
-                "text": {"literalString": text}
-            });
-            if let Some(hint) = usage_hint {
-                text_obj["usageHint"] = json!(hint);
-            }
-            Some(json!({"id": id, "component": {"Text": text_obj}}))
-        }
-        "create_audio_player" => {
-            let url = args.get("url").and_then(|v| v.as_str()).unwrap_or("");
-            let title = args.get("title").and_then(|v| v.as_str()).unwrap_or("Audio");
-            let artist = args.get("artist").and_then(|v| v.as_str());
-            let mut audio_obj = json!({
-                "url": {"literalString": url},
-                "title": {"literalString": title}
-            });
-            if let Some(a) = artist {
-                audio_obj["artist"] = json!({"literalString": a});
-            }
-            Some(json!({"id": id, "component": {"AudioPlayer": audio_obj}}))
-        }
-        "create_column" => {
-            let children = args.get("children").and_then(|v| v.as_array())
-                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect::<Vec<_>>())
-                .unwrap_or_default();
-            Some(json!({"id": id, "component": {"Column": {"children": {"explicitList": children}}}}))
-        }
-        "create_row" => {
-            let children = args.get("children").and_then(|v| v.as_array())
-                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect::<Vec<_>>())
-                .unwrap_or_default();
-            Some(json!({"id": id, "component": {"Row": {"children": {"explicitList": children}}}}))
-        }
-        "create_button" => {
-            let label = args.get("label").and_then(|v| v.as_str()).unwrap_or("Button");
-            let label_id = format!("{}-label", id);
-            Some(json!({"id": id, "component": {"Button": {"child": label_id, "primary": true}}}))
-        }
-        "create_card" => {
-            let child = args.get("child").and_then(|v| v.as_str()).unwrap_or("");
-            Some(json!({"id": id, "component": {"Card": {"child": child}}}))
-        }
-        "render_ui" => {
-            // This is the final layout - handled separately
-            None
-        }
-        _ => None
-    }
-}
-
@@ -0,0 +1,415 @@
+//! A2UI MCP Server
+//!
+//! Exposes the A2UI component tools over the Model Context Protocol so that
+//! MCP-speaking agents (desktop assistants, IDE agents) can build Makepad UIs
+//! without going through an OpenAI-compatible chat completions API.
+//!
+//! Transports:
+//!   stdio            - newline-delimited JSON-RPC on stdin/stdout (default)
+//!   streamable HTTP  - POST/GET/DELETE /mcp
+//!
+//! The renderer side is the same as the A2UI bridge: with the HTTP transport a
+//! Makepad app connects to `/rpc` and `/live`, and user actions posted back to
+//! `/rpc` are surfaced to the MCP client as notifications and as the
+//! `a2ui://actions` resource. Over stdio nothing listens on a port; the
+//! rendered surface is mirrored to `ui_live.json` for the watch-server.
+//!
+//! Run: cargo run -p a2ui-demo --bin a2ui-mcp --features mcp
+//!
+//! Options:
+//!   --transport stdio|http  - MCP transport (default: stdio)
+//!   --port <port>           - HTTP port for /mcp, /rpc and /live (default: 8081, or MCP_PORT);
+//!                             only bound with `--transport http`
+
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Frame, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use log::{info, warn, error, debug};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+
+/// Tool definitions and the A2UI builder, shared verbatim with the bridge.
+/// The Mureka music paths are only driven by the bridge's chat loop, so they
+/// are dead code here.
+#[allow(dead_code)]
+mod bridge {
+    use super::*;
+
+    include!("a2ui_bridge_impl/mureka.rs");
+    include!("a2ui_bridge_impl/tools.rs");
+    include!("a2ui_bridge_impl/builder.rs");
+}
+
+use bridge::{get_a2ui_tools, A2uiBuilder};
+
+include!("a2ui_mcp_impl/server.rs");
+include!("a2ui_mcp_impl/transport.rs");
+
+// ============================================================================
+// Main
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum McpTransport {
+    Stdio,
+    Http,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    env_logger::init();
+
+    let mut transport = McpTransport::Stdio;
+    let mut port: u16 = std::env::var("MCP_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(8081);
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--transport" => match args.next().as_deref() {
+                Some("stdio") => transport = McpTransport::Stdio,
+                Some("http") => transport = McpTransport::Http,
+                other => return Err(format!("Unknown transport: {:?} (expected stdio or http)", other).into()),
+            },
+            "--port" => {
+                port = args.next()
+                    .and_then(|p| p.parse().ok())
+                    .ok_or("--port expects a number")?;
+            }
+            other => warn!("Ignoring unknown argument: {}", other),
+        }
+    }
+
+    let server = Arc::new(McpServer::new(Some("ui_live.json")));
+
+    // stdout carries the protocol in stdio mode, so the banner goes to stderr
+    eprintln!("===========================================");
+    eprintln!("  A2UI MCP Server");
+    eprintln!("===========================================");
+    eprintln!();
+
+    match transport {
+        McpTransport::Stdio => {
+            eprintln!("Transport: stdio");
+            eprintln!("Renderer:  ui_live.json (run the watch-server to view it)");
+            eprintln!();
+
+            run_stdio(server).await?;
+            info!("stdin closed, shutting down");
+        }
+        McpTransport::Http => {
+            let addr = SocketAddr::from(([127, 0, 0, 1], port));
+            let listener = TcpListener::bind(addr).await?;
+
+            eprintln!("Transport: streamable HTTP (http://127.0.0.1:{}/mcp)", port);
+            eprintln!("Renderer:  http://127.0.0.1:{}", port);
+            eprintln!();
+            eprintln!("Endpoints:");
+            eprintln!("  POST /mcp    - MCP JSON-RPC requests");
+            eprintln!("  GET  /mcp    - MCP server notifications (SSE)");
+            eprintln!("  DELETE /mcp  - End MCP session");
+            eprintln!("  POST /rpc    - A2A protocol (for Makepad)");
+            eprintln!("  GET  /live   - Live updates (SSE)");
+            eprintln!();
+
+            spawn_http(listener, server).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal in-process MCP client driving the dispatcher like a real client would
+    struct TestClient {
+        server: McpServer,
+        next_id: i64,
+    }
+
+    impl TestClient {
+        fn connect() -> Self {
+            let mut client = TestClient { server: McpServer::new(None), next_id: 1 };
+            let init = client.request("initialize", json!({
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": {"name": "test-harness", "version": "0.0.0"}
+            }));
+            assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+            assert!(client.server
+                .handle_message(&json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+                .is_none());
+            client
+        }
+
+        fn request(&mut self, method: &str, params: Value) -> Value {
+            let id = self.next_id;
+            self.next_id += 1;
+            let reply = self.server
+                .handle_message(&json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))
+                .expect("request must get a reply");
+            assert_eq!(reply["id"], id);
+            reply
+        }
+
+        fn call(&mut self, tool: &str, arguments: Value) -> Value {
+            self.request("tools/call", json!({"name": tool, "arguments": arguments}))["result"].clone()
+        }
+    }
+
+    #[test]
+    fn test_initialize_negotiates_version() {
+        let server = McpServer::new(None);
+        let reply = server.handle_message(&json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": {"protocolVersion": "2024-11-05"}
+        })).unwrap();
+        assert_eq!(reply["result"]["protocolVersion"], "2024-11-05");
+
+        let reply = server.handle_message(&json!({
+            "jsonrpc": "2.0", "id": 2, "method": "initialize",
+            "params": {"protocolVersion": "1999-01-01"}
+        })).unwrap();
+        assert_eq!(reply["result"]["protocolVersion"], MCP_PROTOCOL_VERSIONS[0]);
+        assert_eq!(reply["result"]["serverInfo"]["name"], "a2ui-mcp");
+    }
+
+    #[test]
+    fn test_tools_list() {
+        let mut client = TestClient::connect();
+        let tools = client.request("tools/list", json!({}))["result"]["tools"].clone();
+        let names: Vec<&str> = tools.as_array().unwrap().iter()
+            .filter_map(|t| t["name"].as_str())
+            .collect();
+
+        assert!(names.contains(&"create_text"));
+        assert!(names.contains(&"render_ui"));
+        assert!(names.contains(&"get_user_actions"));
+        assert!(!names.contains(&"generate_music"));
+
+        let slider = tools.as_array().unwrap().iter()
+            .find(|t| t["name"] == "create_slider")
+            .unwrap();
+        assert_eq!(slider["inputSchema"]["type"], "object");
+    }
+
+    #[test]
+    fn test_render_pushes_surface_to_live() {
+        let mut client = TestClient::connect();
+        let mut live = client.server.ui_tx.subscribe();
+
+        client.call("create_text", json!({"id": "title", "text": "Hello"}));
+        client.call("create_button", json!({"id": "ok", "label": "OK", "action": "confirm"}));
+        client.call("create_column", json!({"id": "root", "children": ["title", "ok"]}));
+        let result = client.call("render_ui", json!({"rootId": "root"}));
+        assert_eq!(result["isError"], false);
+
+        let pushed: Value = serde_json::from_str(&live.try_recv().unwrap()).unwrap();
+        assert_eq!(pushed[0]["beginRendering"]["root"], "root");
+        let ids: Vec<&str> = pushed[1]["surfaceUpdate"]["components"].as_array().unwrap().iter()
+            .filter_map(|c| c["id"].as_str())
+            .collect();
+        assert_eq!(ids, vec!["title", "ok-text", "ok", "root"]);
+
+        let read = client.request("resources/read", json!({"uri": SURFACE_RESOURCE_URI}));
+        let text = read["result"]["contents"][0]["text"].as_str().unwrap();
+        assert_eq!(serde_json::from_str::<Value>(text).unwrap(), pushed);
+    }
+
+    #[test]
+    fn test_redefined_component_replaces_previous() {
+        let mut client = TestClient::connect();
+        client.call("create_text", json!({"id": "title", "text": "Draft"}));
+        client.call("create_text", json!({"id": "title", "text": "Final"}));
+
+        let builder = client.server.builder.lock().unwrap();
+        assert_eq!(builder.components.len(), 1);
+        assert_eq!(builder.components[0]["component"]["Text"]["text"]["literalString"], "Final");
+    }
+
+    #[test]
+    fn test_user_action_notifies_subscribers() {
+        let mut client = TestClient::connect();
+        let mut notifications = client.server.notify_tx.subscribe();
+        client.request("resources/subscribe", json!({"uri": ACTIONS_RESOURCE_URI}));
+
+        client.server.record_action(json!({
+            "actionName": "confirm",
+            "sourceComponentId": "ok",
+            "resolvedContext": {}
+        }));
+
+        let log = notifications.try_recv().unwrap();
+        assert_eq!(log["method"], "notifications/message");
+        assert_eq!(log["params"]["data"]["actionName"], "confirm");
+        let updated = notifications.try_recv().unwrap();
+        assert_eq!(updated["method"], "notifications/resources/updated");
+        assert_eq!(updated["params"]["uri"], ACTIONS_RESOURCE_URI);
+
+        let actions = client.call("get_user_actions", json!({}));
+        let text = actions["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("confirm"));
+    }
+
+    #[test]
+    fn test_session_queue_keeps_notifications_between_polls() {
+        let mut client = TestClient::connect();
+        let sid = client.server.open_session();
+        client.request("resources/subscribe", json!({"uri": ACTIONS_RESOURCE_URI}));
+
+        // Nobody is polling GET /mcp while these arrive
+        client.server.record_action(json!({"actionName": "first"}));
+        client.server.record_action(json!({"actionName": "second"}));
+
+        let queued = client.server.take_notifications(&sid).unwrap();
+        let actions: Vec<&str> = queued.iter()
+            .filter(|n| n["method"] == "notifications/message")
+            .filter_map(|n| n["params"]["data"]["actionName"].as_str())
+            .collect();
+        assert_eq!(actions, vec!["first", "second"]);
+        assert_eq!(queued.len(), 4);
+        assert!(client.server.take_notifications(&sid).unwrap().is_empty());
+
+        assert!(client.server.close_session(&sid));
+        assert!(client.server.take_notifications(&sid).is_none());
+    }
+
+    #[test]
+    fn test_builder_tools_exclude_server_tools() {
+        assert!(is_builder_tool("create_text"));
+        assert!(is_builder_tool("render_ui"));
+        assert!(!is_builder_tool("reset_surface"));
+        assert!(!is_builder_tool("get_user_actions"));
+        assert!(!is_builder_tool("generate_music"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut client = TestClient::connect();
+        assert_eq!(client.request("nope/nothing", json!({}))["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(client.request("resources/read", json!({"uri": "a2ui://other"}))["error"]["code"], INVALID_PARAMS);
+        assert_eq!(client.call("create_spaceship", json!({}))["isError"], true);
+    }
+
+    #[test]
+    fn test_batch() {
+        let server = McpServer::new(None);
+        let reply = server.handle_message(&json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+            {"jsonrpc": "2.0", "method": "notifications/initialized"},
+            {"jsonrpc": "2.0", "id": 2, "method": "ping"}
+        ])).unwrap();
+        assert_eq!(reply.as_array().unwrap().len(), 2);
+    }
+
+    /// Serve the HTTP transport on an ephemeral local port
+    async fn serve() -> (Arc<McpServer>, SocketAddr) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = Arc::new(McpServer::new(None));
+        spawn_http(listener, server.clone());
+        (server, addr)
+    }
+
+    /// Send one HTTP/1.1 request and return the open connection
+    async fn send(addr: SocketAddr, method: &str, session: Option<&str>, body: &str) -> tokio::net::TcpStream {
+        use tokio::io::AsyncWriteExt;
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let session = session.map(|sid| format!("Mcp-Session-Id: {}\r\n", sid)).unwrap_or_default();
+        let request = format!(
+            "{} /mcp HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            method, session, body.len(), body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        stream
+    }
+
+    /// Read a whole response from a connection the server closes
+    async fn read_response(mut stream: tokio::net::TcpStream) -> String {
+        use tokio::io::AsyncReadExt;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    /// Read from an open stream until `needle` shows up
+    async fn read_until(stream: &mut tokio::net::TcpStream, received: &mut String, needle: &str) {
+        use tokio::io::AsyncReadExt;
+
+        let wait = async {
+            let mut buf = [0u8; 4096];
+            while !received.contains(needle) {
+                let n = stream.read(&mut buf).await.unwrap();
+                assert!(n > 0, "stream closed before {:?} arrived", needle);
+                received.push_str(&String::from_utf8_lossy(&buf[..n]));
+            }
+        };
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), wait).await
+            .unwrap_or_else(|_| panic!("timed out waiting for {:?}", needle));
+    }
+
+    fn session_header(response: &str) -> Option<String> {
+        response.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("Mcp-Session-Id").then(|| value.trim().to_string())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_http_initialize_in_batch_opens_session() {
+        let (_server, addr) = serve().await;
+
+        let ping = r#"{"jsonrpc": "2.0", "id": 1, "method": "ping"}"#;
+        let response = read_response(send(addr, "POST", None, ping).await).await;
+        assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+
+        let batch = r#"[{"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"protocolVersion": "2025-03-26"}},
+            {"jsonrpc": "2.0", "method": "notifications/initialized"}]"#;
+        let response = read_response(send(addr, "POST", None, batch).await).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        let sid = session_header(&response).expect("initialize in a batch must open a session");
+
+        let response = read_response(send(addr, "POST", Some(&sid), ping).await).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    }
+
+    #[tokio::test]
+    async fn test_http_notification_stream_stays_open() {
+        let (server, addr) = serve().await;
+        let sid = server.open_session();
+
+        // Queued before the stream opens, sent as soon as it does
+        server.record_action(json!({"actionName": "early"}));
+
+        let mut stream = send(addr, "GET", Some(&sid), "").await;
+        let mut received = String::new();
+        read_until(&mut stream, &mut received, "early").await;
+        assert!(received.starts_with("HTTP/1.1 200"), "{}", received);
+        assert!(received.to_lowercase().contains("content-type: text/event-stream"));
+
+        // Later notifications arrive on the same open response
+        server.record_action(json!({"actionName": "first"}));
+        read_until(&mut stream, &mut received, "first").await;
+        server.record_action(json!({"actionName": "second"}));
+        read_until(&mut stream, &mut received, "second").await;
+        assert_eq!(received.matches("\"actionName\":\"early\"").count(), 1);
+
+        let response = read_response(send(addr, "GET", Some("unknown"), "").await).await;
+        assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+    }
+}
@@ -0,0 +1,420 @@
+// ============================================================================
+// MCP Server - JSON-RPC dispatcher shared by the stdio and HTTP transports
+// ============================================================================
+
+/// Protocol revisions this server understands, newest first
+const MCP_PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+
+/// Resource holding the current A2UI surface messages
+const SURFACE_RESOURCE_URI: &str = "a2ui://surface/main";
+
+/// Resource holding user actions reported by the renderer
+const ACTIONS_RESOURCE_URI: &str = "a2ui://actions";
+
+/// Tools that only make sense inside the LLM bridge (Mureka polling is driven
+/// by the bridge's chat loop, not by individual tool calls)
+const MCP_EXCLUDED_TOOLS: &[&str] = &["generate_music"];
+
+/// Notifications kept per HTTP session while no GET /mcp stream drains them
+const SESSION_QUEUE_LIMIT: usize = 256;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+struct McpServer {
+    builder: Mutex<A2uiBuilder>,
+    /// User actions received from the renderer, oldest first
+    actions: Mutex<Vec<Value>>,
+    /// Resource URIs the client subscribed to
+    subscriptions: Mutex<HashSet<String>>,
+    /// Latest rendered A2UI messages, served on /rpc
+    latest_a2ui: Mutex<Option<Value>>,
+    /// A2UI updates for the /live SSE channel
+    ui_tx: broadcast::Sender<String>,
+    /// Server-initiated MCP notifications (JSON-RPC messages)
+    notify_tx: broadcast::Sender<Value>,
+    /// Active streamable HTTP sessions and the notifications queued for each
+    /// until its GET /mcp stream sends them
+    sessions: Mutex<HashMap<String, VecDeque<Value>>>,
+    /// File mirroring the rendered surface for the watch-server
+    live_file: Option<String>,
+}
+
+impl McpServer {
+    fn new(live_file: Option<&str>) -> Self {
+        let (ui_tx, _) = broadcast::channel::<String>(16);
+        let (notify_tx, _) = broadcast::channel::<Value>(64);
+        McpServer {
+            builder: Mutex::new(A2uiBuilder::new()),
+            actions: Mutex::new(Vec::new()),
+            subscriptions: Mutex::new(HashSet::new()),
+            latest_a2ui: Mutex::new(None),
+            ui_tx,
+            notify_tx,
+            sessions: Mutex::new(HashMap::new()),
+            live_file: live_file.map(String::from),
+        }
+    }
+
+    /// Handle a single JSON-RPC message or a batch.
+    /// Returns None when nothing needs to be sent back (notifications, responses).
+    fn handle_message(&self, message: &Value) -> Option<Value> {
+        if let Some(batch) = message.as_array() {
+            if batch.is_empty() {
+                return Some(rpc_error(Value::Null, INVALID_REQUEST, "Empty batch"));
+            }
+            let replies: Vec<Value> = batch.iter()
+                .filter_map(|m| self.handle_single(m))
+                .collect();
+            return if replies.is_empty() { None } else { Some(Value::Array(replies)) };
+        }
+        self.handle_single(message)
+    }
+
+    fn handle_single(&self, message: &Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let Some(method) = message["method"].as_str() else {
+            // Responses to server-initiated requests - we never send any
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            return Some(rpc_error(id.unwrap_or(Value::Null), INVALID_REQUEST, "Missing method"));
+        };
+        let params = message.get("params").cloned().unwrap_or(json!({}));
+
+        // Notifications carry no id and never get a reply
+        let Some(id) = id else {
+            self.handle_notification(method, &params);
+            return None;
+        };
+
+        debug!("MCP request: {}", method);
+
+        let result = match method {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({"tools": mcp_tools()})),
+            "tools/call" => self.call_tool(&params),
+            "resources/list" => Ok(json!({"resources": self.resources()})),
+            "resources/read" => self.read_resource(&params),
+            "resources/subscribe" => self.subscribe(&params, true),
+            "resources/unsubscribe" => self.subscribe(&params, false),
+            "logging/setLevel" => Ok(json!({})),
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err((code, msg)) => rpc_error(id, code, &msg),
+        })
+    }
+
+    fn handle_notification(&self, method: &str, _params: &Value) {
+        match method {
+            "notifications/initialized" => info!("MCP client initialized"),
+            "notifications/cancelled" => debug!("MCP client cancelled a request"),
+            _ => debug!("Ignoring notification: {}", method),
+        }
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params["protocolVersion"].as_str().unwrap_or("");
+        let version = MCP_PROTOCOL_VERSIONS.iter()
+            .find(|v| **v == requested)
+            .copied()
+            .unwrap_or(MCP_PROTOCOL_VERSIONS[0]);
+
+        if let Some(client) = params["clientInfo"]["name"].as_str() {
+            info!("MCP client: {} (protocol {})", client, version);
+        }
+
+        json!({
+            "protocolVersion": version,
+            "capabilities": {
+                "tools": {"listChanged": false},
+                "resources": {"subscribe": true, "listChanged": false},
+                "logging": {}
+            },
+            "serverInfo": {
+                "name": "a2ui-mcp",
+                "version": env!("CARGO_PKG_VERSION")
+            },
+            "instructions": "Build a UI by creating components with the create_* tools, set initial values with set_data, \
+                then call render_ui with the root component ID to show it in the connected Makepad renderer. \
+                User interactions are reported through the a2ui://actions resource."
+        })
+    }
+
+    fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params["name"].as_str()
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let args = params.get("arguments").cloned().unwrap_or(json!({}));
+        if !args.is_object() {
+            return Err((INVALID_PARAMS, "Tool arguments must be an object".to_string()));
+        }
+
+        match name {
+            "reset_surface" => {
+                *self.builder.lock().unwrap() = A2uiBuilder::new();
+                Ok(tool_text("Surface cleared", false))
+            }
+            "get_user_actions" => {
+                let actions = self.actions.lock().unwrap().clone();
+                Ok(tool_text(&serde_json::to_string_pretty(&actions).unwrap_or_default(), false))
+            }
+            "render_ui" => {
+                let a2ui = {
+                    let mut builder = self.builder.lock().unwrap();
+                    builder.process_tool_call(name, &args);
+                    builder.build_a2ui_json()
+                };
+                self.publish(a2ui.clone());
+                let summary = format!(
+                    "Rendered surface 'main' with {} components",
+                    a2ui[1]["surfaceUpdate"]["components"].as_array().map(|c| c.len()).unwrap_or(0)
+                );
+                Ok(json!({
+                    "content": [
+                        {"type": "text", "text": summary},
+                        {"type": "text", "text": a2ui.to_string()}
+                    ],
+                    "isError": false
+                }))
+            }
+            _ if is_builder_tool(name) => {
+                let mut builder = self.builder.lock().unwrap();
+                let before = builder.components.len();
+                builder.process_tool_call(name, &args);
+                replace_redefined_components(&mut builder.components, before);
+                let pending = builder.components.len();
+                let what = match args["id"].as_str() {
+                    Some(id) => format!("{} '{}'", name, id),
+                    None => name.to_string(),
+                };
+                Ok(tool_text(
+                    &format!("{} done ({} components pending; call render_ui to display)", what, pending),
+                    false,
+                ))
+            }
+            _ => Ok(tool_text(&format!("Unknown tool: {}", name), true)),
+        }
+    }
+
+    /// Push a rendered surface to connected renderers
+    fn publish(&self, a2ui: Value) {
+        let a2ui_str = serde_json::to_string_pretty(&a2ui).unwrap_or_default();
+
+        // Keep ui_live.json in sync for the watch-server, like the bridge does
+        if let Some(path) = &self.live_file {
+            if let Err(e) = std::fs::write(path, &a2ui_str) {
+                warn!("Failed to write {}: {}", path, e);
+            }
+        }
+
+        *self.latest_a2ui.lock().unwrap() = Some(a2ui);
+        let _ = self.ui_tx.send(a2ui_str);
+        self.resource_updated(SURFACE_RESOURCE_URI);
+    }
+
+    fn resources(&self) -> Value {
+        json!([
+            {
+                "uri": SURFACE_RESOURCE_URI,
+                "name": "A2UI surface",
+                "description": "A2UI messages for the currently rendered surface",
+                "mimeType": "application/json"
+            },
+            {
+                "uri": ACTIONS_RESOURCE_URI,
+                "name": "User actions",
+                "description": "Actions triggered by the user in the Makepad renderer, oldest first",
+                "mimeType": "application/json"
+            }
+        ])
+    }
+
+    fn read_resource(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let uri = params["uri"].as_str()
+            .ok_or((INVALID_PARAMS, "Missing resource uri".to_string()))?;
+
+        let contents = match uri {
+            SURFACE_RESOURCE_URI => self.latest_a2ui.lock().unwrap().clone().unwrap_or(json!([])),
+            ACTIONS_RESOURCE_URI => Value::Array(self.actions.lock().unwrap().clone()),
+            _ => return Err((INVALID_PARAMS, format!("Unknown resource: {}", uri))),
+        };
+
+        Ok(json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "application/json",
+                "text": contents.to_string()
+            }]
+        }))
+    }
+
+    fn subscribe(&self, params: &Value, subscribe: bool) -> Result<Value, (i64, String)> {
+        let uri = params["uri"].as_str()
+            .ok_or((INVALID_PARAMS, "Missing resource uri".to_string()))?;
+        if uri != SURFACE_RESOURCE_URI && uri != ACTIONS_RESOURCE_URI {
+            return Err((INVALID_PARAMS, format!("Unknown resource: {}", uri)));
+        }
+
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        if subscribe {
+            subscriptions.insert(uri.to_string());
+        } else {
+            subscriptions.remove(uri);
+        }
+        Ok(json!({}))
+    }
+
+    /// Record a user action forwarded by the renderer (A2A `message/send`)
+    fn record_action(&self, event: Value) {
+        info!("User action: {}", event["actionName"].as_str().unwrap_or("?"));
+        self.actions.lock().unwrap().push(event.clone());
+
+        self.notify(json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/message",
+            "params": {
+                "level": "info",
+                "logger": "a2ui.actions",
+                "data": event
+            }
+        }));
+        self.resource_updated(ACTIONS_RESOURCE_URI);
+    }
+
+    fn resource_updated(&self, uri: &str) {
+        if self.subscriptions.lock().unwrap().contains(uri) {
+            self.notify(json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/resources/updated",
+                "params": {"uri": uri}
+            }));
+        }
+    }
+
+    fn notify(&self, notification: Value) {
+        for (session_id, queue) in self.sessions.lock().unwrap().iter_mut() {
+            if queue.len() == SESSION_QUEUE_LIMIT {
+                warn!("Notification queue full for session {}, dropping oldest", session_id);
+                queue.pop_front();
+            }
+            queue.push_back(notification.clone());
+        }
+        // No receivers just means no client is listening right now
+        let _ = self.notify_tx.send(notification);
+    }
+
+    fn open_session(&self) -> String {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        let session_id = format!("{:016x}{:08x}", nanos, COUNTER.fetch_add(1, Ordering::Relaxed));
+        self.sessions.lock().unwrap().insert(session_id.clone(), VecDeque::new());
+        session_id
+    }
+
+    fn has_session(&self, session_id: &str) -> bool {
+        self.sessions.lock().unwrap().contains_key(session_id)
+    }
+
+    fn close_session(&self, session_id: &str) -> bool {
+        self.sessions.lock().unwrap().remove(session_id).is_some()
+    }
+
+    /// Drain the notifications queued for a session since the last drain.
+    /// Returns None for unknown sessions.
+    fn take_notifications(&self, session_id: &str) -> Option<Vec<Value>> {
+        self.sessions.lock().unwrap()
+            .get_mut(session_id)
+            .map(|queue| queue.drain(..).collect())
+    }
+}
+
+/// A2UI tools in MCP format (`inputSchema` instead of OpenAI's `parameters`)
+fn mcp_tools() -> Vec<Value> {
+    let mut tools: Vec<Value> = get_a2ui_tools()
+        .as_array()
+        .map(|arr| arr.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|tool| {
+            let func = &tool["function"];
+            let name = func["name"].as_str()?;
+            if MCP_EXCLUDED_TOOLS.contains(&name) {
+                return None;
+            }
+            Some(json!({
+                "name": name,
+                "description": func["description"],
+                "inputSchema": func["parameters"]
+            }))
+        })
+        .collect();
+
+    tools.push(json!({
+        "name": "reset_surface",
+        "description": "Discard all components and data created so far and start a new surface",
+        "inputSchema": {"type": "object", "properties": {}}
+    }));
+    tools.push(json!({
+        "name": "get_user_actions",
+        "description": "Return the actions the user triggered in the rendered UI (button clicks, etc.), oldest first",
+        "inputSchema": {"type": "object", "properties": {}}
+    }));
+
+    tools
+}
+
+/// Tools forwarded to the A2UI builder, computed once from the shared definitions
+fn is_builder_tool(name: &str) -> bool {
+    static BUILDER_TOOLS: std::sync::OnceLock<HashSet<String>> = std::sync::OnceLock::new();
+    BUILDER_TOOLS
+        .get_or_init(|| {
+            mcp_tools().iter()
+                .filter_map(|t| t["name"].as_str())
+                .filter(|name| !matches!(*name, "reset_surface" | "get_user_actions"))
+                .map(String::from)
+                .collect()
+        })
+        .contains(name)
+}
+
+/// Later tool calls may redefine a component; drop the stale definitions so
+/// the surface never carries duplicate IDs
+fn replace_redefined_components(components: &mut Vec<Value>, first_new: usize) {
+    let new_ids: HashSet<String> = components[first_new..].iter()
+        .filter_map(|c| c["id"].as_str().map(String::from))
+        .collect();
+    let mut index = 0;
+    components.retain(|c| {
+        let keep = index >= first_new
+            || !c["id"].as_str().map(|id| new_ids.contains(id)).unwrap_or(false);
+        index += 1;
+        keep
+    });
+}
+
+fn tool_text(text: &str, is_error: bool) -> Value {
+    json!({
+        "content": [{"type": "text", "text": text}],
+        "isError": is_error
+    })
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": message}
+    })
+}
@@ -0,0 +1,389 @@
+// ============================================================================
+// MCP Transports - stdio and streamable HTTP, plus the renderer endpoints
+// ============================================================================
+
+/// Serve MCP over stdin/stdout until stdin closes.
+/// Each line is one JSON-RPC message (or batch); notifications are interleaved
+/// with responses on stdout.
+async fn run_stdio(server: Arc<McpServer>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (out_tx, mut out_rx) = tokio::sync::mpsc::unbounded_channel::<Value>();
+
+    // Single writer so responses and notifications never interleave mid-line
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(msg) = out_rx.recv().await {
+            let line = format!("{}\n", msg);
+            if stdout.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+            let _ = stdout.flush().await;
+        }
+    });
+
+    // Forward server notifications (user actions, resource updates)
+    let mut notifications = server.notify_tx.subscribe();
+    let notify_out = out_tx.clone();
+    let forwarder = tokio::spawn(async move {
+        loop {
+            match notifications.recv().await {
+                Ok(msg) => {
+                    if notify_out.send(msg).is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => warn!("Dropped {} notifications", n),
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let reply = match serde_json::from_str::<Value>(line) {
+            Ok(message) => server.handle_message(&message),
+            Err(e) => Some(rpc_error(Value::Null, PARSE_ERROR, &format!("Parse error: {}", e))),
+        };
+
+        if let Some(reply) = reply {
+            if out_tx.send(reply).is_err() {
+                break;
+            }
+        }
+    }
+
+    forwarder.abort();
+    drop(out_tx);
+    let _ = writer.await;
+    Ok(())
+}
+
+/// Keepalive comment interval on open SSE streams
+const SSE_KEEPALIVE: tokio::time::Duration = tokio::time::Duration::from_secs(15);
+
+type HttpBody = BoxBody<Bytes, Infallible>;
+
+/// Accept HTTP connections on `listener` until the task is dropped
+fn spawn_http(listener: TcpListener, server: Arc<McpServer>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let (stream, remote_addr) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    error!("Accept failed: {}", e);
+                    continue;
+                }
+            };
+            let io = TokioIo::new(stream);
+            let server = server.clone();
+
+            debug!("Connection from {}", remote_addr);
+
+            tokio::task::spawn(async move {
+                if let Err(err) = http1::Builder::new()
+                    .serve_connection(io, service_fn(move |req| handle_http_request(req, server.clone())))
+                    .await
+                {
+                    error!("Error serving connection: {:?}", err);
+                }
+            });
+        }
+    })
+}
+
+/// HTTP entry point: streamable HTTP MCP on /mcp and the renderer-facing
+/// /rpc and /live endpoints
+async fn handle_http_request(
+    req: Request<Incoming>,
+    server: Arc<McpServer>,
+) -> Result<Response<HttpBody>, hyper::Error> {
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let session_id = req.headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+
+    debug!("{} {}", method, path);
+
+    match (method, path.as_str()) {
+        (Method::POST, "/mcp") => {
+            if let Some(ref sid) = session_id {
+                if !server.has_session(sid) {
+                    return Ok(json_response(StatusCode::NOT_FOUND, json!({"error": "Unknown session"})));
+                }
+            }
+
+            let body_bytes = http_body_util::BodyExt::collect(req.into_body())
+                .await
+                .map(|b| b.to_bytes())
+                .unwrap_or_default();
+
+            let message: Value = match serde_json::from_slice(&body_bytes) {
+                Ok(m) => m,
+                Err(e) => {
+                    return Ok(json_response(
+                        StatusCode::BAD_REQUEST,
+                        rpc_error(Value::Null, PARSE_ERROR, &format!("Parse error: {}", e)),
+                    ));
+                }
+            };
+
+            let is_initialize = contains_initialize(&message);
+            if !is_initialize && session_id.is_none() {
+                return Ok(json_response(
+                    StatusCode::BAD_REQUEST,
+                    rpc_error(Value::Null, INVALID_REQUEST, "Missing Mcp-Session-Id header"),
+                ));
+            }
+
+            match server.handle_message(&message) {
+                Some(reply) => {
+                    let mut response = json_response(StatusCode::OK, reply);
+                    if is_initialize {
+                        let sid = server.open_session();
+                        info!("MCP session opened: {}", sid);
+                        if let Ok(value) = sid.parse() {
+                            response.headers_mut().insert("Mcp-Session-Id", value);
+                        }
+                    }
+                    Ok(response)
+                }
+                // Only notifications/responses were posted
+                None => Ok(Response::builder()
+                    .status(StatusCode::ACCEPTED)
+                    .header("Access-Control-Allow-Origin", "*")
+                    .body(full_body(Bytes::new()))
+                    .unwrap()),
+            }
+        }
+
+        // Server-to-client notification stream
+        (Method::GET, "/mcp") => {
+            let Some(sid) = session_id.filter(|sid| server.has_session(sid)) else {
+                return Ok(json_response(StatusCode::BAD_REQUEST, json!({"error": "Missing or unknown Mcp-Session-Id"})));
+            };
+            info!("MCP notification stream opened for session {}", sid);
+
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "text/event-stream")
+                .header("Cache-Control", "no-cache")
+                .header("Access-Control-Allow-Origin", "*")
+                .body(notification_stream(server, sid))
+                .unwrap())
+        }
+
+        (Method::DELETE, "/mcp") => {
+            let closed = session_id.map(|sid| server.close_session(&sid)).unwrap_or(false);
+            let status = if closed { StatusCode::OK } else { StatusCode::NOT_FOUND };
+            Ok(Response::builder()
+                .status(status)
+                .header("Access-Control-Allow-Origin", "*")
+                .body(full_body(Bytes::new()))
+                .unwrap())
+        }
+
+        // A2A endpoint for the Makepad renderer: initial surface load and user actions
+        (Method::POST, "/rpc") => {
+            let body_bytes = http_body_util::BodyExt::collect(req.into_body())
+                .await
+                .map(|b| b.to_bytes())
+                .unwrap_or_default();
+            let request: Value = serde_json::from_slice(&body_bytes).unwrap_or(json!({}));
+
+            if request["method"].as_str() == Some("message/send") {
+                let events: Vec<Value> = request["params"]["message"]["parts"]
+                    .as_array()
+                    .map(|parts| parts.iter()
+                        .filter_map(|p| p["data"].get("a2uiEvent").cloned())
+                        .collect())
+                    .unwrap_or_default();
+                for event in events {
+                    server.record_action(event);
+                }
+
+                return Ok(json_response(StatusCode::OK, json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": {
+                        "kind": "task",
+                        "id": "a2ui-task",
+                        "contextId": "a2ui-ctx",
+                        "status": {"state": "completed"}
+                    }
+                })));
+            }
+
+            let ui_to_send = server.latest_a2ui.lock().unwrap().clone().unwrap_or_else(|| json!([
+                {"beginRendering": {"surfaceId": "main", "root": "welcome"}},
+                {"surfaceUpdate": {"surfaceId": "main", "components": [
+                    {"id": "welcome", "component": {"Column": {"children": {"explicitList": ["title", "subtitle"]}}}},
+                    {"id": "title", "component": {"Text": {"text": {"literalString": "A2UI MCP Server"}, "usageHint": "h1"}}},
+                    {"id": "subtitle", "component": {"Text": {"text": {"literalString": "Waiting for an MCP client to call render_ui"}, "usageHint": "caption"}}}
+                ]}},
+                {"dataModelUpdate": {"surfaceId": "main", "path": "/", "contents": []}}
+            ]));
+
+            let mut response_body = String::new();
+            let task_start = json!({
+                "jsonrpc": "2.0",
+                "result": {
+                    "kind": "task",
+                    "id": "a2ui-task",
+                    "contextId": "a2ui-ctx",
+                    "status": {"state": "running"}
+                }
+            });
+            response_body.push_str(&format!("data: {}\n\n", task_start));
+
+            for msg in ui_to_send.as_array().map(|a| a.as_slice()).unwrap_or_default() {
+                let wrapped = json!({
+                    "jsonrpc": "2.0",
+                    "result": {
+                        "kind": "event",
+                        "taskId": "a2ui-task",
+                        "data": msg
+                    }
+                });
+                response_body.push_str(&format!("data: {}\n\n", wrapped));
+            }
+
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "text/event-stream")
+                .header("Cache-Control", "no-cache")
+                .header("Access-Control-Allow-Origin", "*")
+                .body(full_body(response_body))
+                .unwrap())
+        }
+
+        // Live SSE endpoint for rendered surfaces
+        (Method::GET, "/live") => {
+            let mut rx = server.ui_tx.subscribe();
+            let mut sse_body = String::new();
+
+            match tokio::time::timeout(tokio::time::Duration::from_secs(60), rx.recv()).await {
+                Ok(Ok(content)) => {
+                    if let Ok(messages) = serde_json::from_str::<Vec<Value>>(&content) {
+                        for msg in messages {
+                            sse_body.push_str(&format!("data: {}\n\n", msg));
+                        }
+                    } else {
+                        sse_body.push_str(&format!("data: {}\n\n", content));
+                    }
+                    info!("Sent surface update via /live");
+                }
+                Ok(Err(_)) => {}
+                Err(_) => sse_body.push_str("data: {\"keepalive\": true}\n\n"),
+            }
+
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "text/event-stream")
+                .header("Cache-Control", "no-cache")
+                .header("Access-Control-Allow-Origin", "*")
+                .body(full_body(sse_body))
+                .unwrap())
+        }
+
+        // CORS preflight
+        (Method::OPTIONS, _) => {
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("Access-Control-Allow-Origin", "*")
+                .header("Access-Control-Allow-Methods", "GET, POST, DELETE, OPTIONS")
+                .header("Access-Control-Allow-Headers", "Content-Type, Accept, Authorization, Mcp-Session-Id, X-A2A-Extensions")
+                .header("Access-Control-Expose-Headers", "Mcp-Session-Id")
+                .body(full_body(Bytes::new()))
+                .unwrap())
+        }
+
+        _ => Ok(json_response(StatusCode::NOT_FOUND, json!({"error": "Not found"}))),
+    }
+}
+
+/// Whether a message, or any member of a batch, is an `initialize` request
+fn contains_initialize(message: &Value) -> bool {
+    let is_initialize = |m: &Value| m["method"].as_str() == Some("initialize");
+    match message.as_array() {
+        Some(batch) => batch.iter().any(is_initialize),
+        None => is_initialize(message),
+    }
+}
+
+/// Open SSE stream of the notifications queued for `session_id`. Stays open
+/// until the client disconnects or the session is deleted, with a comment
+/// line every `SSE_KEEPALIVE` so proxies keep the connection alive.
+fn notification_stream(server: Arc<McpServer>, session_id: String) -> HttpBody {
+    let (tx, rx) = tokio::sync::mpsc::channel::<Bytes>(16);
+
+    tokio::spawn(async move {
+        // Subscribe before the first drain so a notification racing it still
+        // wakes the loop; the session queue is what actually gets sent, so
+        // nothing is sent twice or lost between wakeups.
+        let mut wakeups = server.notify_tx.subscribe();
+        let mut keepalive = tokio::time::interval(SSE_KEEPALIVE);
+        keepalive.tick().await;
+
+        while let Some(pending) = server.take_notifications(&session_id) {
+            for notification in pending {
+                if tx.send(Bytes::from(format!("data: {}\n\n", notification))).await.is_err() {
+                    return;
+                }
+            }
+
+            tokio::select! {
+                woken = wakeups.recv() => match woken {
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = keepalive.tick() => {
+                    if tx.send(Bytes::from_static(b": keepalive\n\n")).await.is_err() {
+                        return;
+                    }
+                }
+                _ = tx.closed() => return,
+            }
+        }
+        info!("MCP notification stream closed for session {}", session_id);
+    });
+
+    SseBody { rx }.boxed()
+}
+
+/// Response body fed by a channel; ends when the sender is dropped
+struct SseBody {
+    rx: tokio::sync::mpsc::Receiver<Bytes>,
+}
+
+impl hyper::body::Body for SseBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        self.rx.poll_recv(cx).map(|chunk| chunk.map(|bytes| Ok(Frame::data(bytes))))
+    }
+}
+
+fn full_body(body: impl Into<Bytes>) -> HttpBody {
+    Full::new(body.into()).boxed()
+}
+
+fn json_response(status: StatusCode, body: Value) -> Response<HttpBody> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .header("Access-Control-Allow-Origin", "*")
+        .body(full_body(body.to_string()))
+        .unwrap()
+}
@@ -705,6 +705,10 @@ impl App {
                     had_error = true;
                     error_msg = e;
                 }
+                A2uiHostEvent::AuthRequired(reason) => {
+                    had_error = true;
+                    error_msg = format!("login required ({})", reason);
+                }
                 A2uiHostEvent::Disconnected => {
                     had_disconnect = true;
                 }
@@ -812,6 +816,9 @@ impl App {
                 A2uiHostEvent::Error(e) => {
                     log!("Live stream error: {}", e);
                 }
+                A2uiHostEvent::AuthRequired(reason) => {
+                    log!("Live stream login required: {}", reason);
+                }
                 A2uiHostEvent::Disconnected => {
                     log!("Live stream disconnected, will reconnect...");
                     self.live_host = None;
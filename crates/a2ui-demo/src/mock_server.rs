@@ -2,6 +2,10 @@
 //!
 //! Run: cargo run -p a2ui-demo --bin mock-a2a-server --features mock-server
 //! Then connect from Makepad app to http://localhost:8080/rpc
+//! (or discover it via http://localhost:8080/.well-known/agent.json)
+//!
+//! Options:
+//!   --auth  - Require credentials on /rpc (see `MockAuth` for what is accepted)
 
 use http_body_util::{BodyExt, Full};
 use hyper::body::{Bytes, Incoming};
@@ -9,11 +13,122 @@ use hyper::server::conn::http1;
 use hyper::service::service_fn;
 use hyper::{Method, Request, Response, StatusCode};
 use hyper_util::rt::TokioIo;
-use log::{info, error};
+use log::{info, warn, error};
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::net::TcpListener;
 
+const MOCK_BEARER_TOKEN: &str = "mock-token";
+const MOCK_API_KEY: &str = "mock-api-key";
+const MOCK_CLIENT_ID: &str = "mock-client";
+const MOCK_CLIENT_SECRET: &str = "mock-secret";
+
+/// Auth-checking mode for testing clients against protected agents.
+///
+/// When enabled, /rpc accepts any of:
+///   Authorization: Bearer mock-token        (static token)
+///   X-API-Key: mock-api-key                 (custom header)
+///   Authorization: Bearer <issued token>    (OAuth2 client credentials via
+///                                            POST /oauth/token with
+///                                            mock-client / mock-secret)
+/// Issued tokens expire after MOCK_TOKEN_TTL seconds (default 60) so
+/// refresh-on-401 can be exercised.
+struct MockAuth {
+    enabled: bool,
+    token_ttl: Duration,
+    issued: Mutex<HashMap<String, Instant>>,
+    next_token: AtomicU64,
+}
+
+impl MockAuth {
+    fn new(enabled: bool) -> Self {
+        let ttl = std::env::var("MOCK_TOKEN_TTL")
+            .ok()
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(60);
+
+        MockAuth {
+            enabled,
+            token_ttl: Duration::from_secs(ttl),
+            issued: Mutex::new(HashMap::new()),
+            next_token: AtomicU64::new(1),
+        }
+    }
+
+    fn is_authorized(&self, req: &Request<Incoming>) -> bool {
+        if !self.enabled {
+            return true;
+        }
+
+        let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+
+        if header("X-API-Key") == Some(MOCK_API_KEY) {
+            return true;
+        }
+
+        let Some(token) = header("Authorization").and_then(|v| v.strip_prefix("Bearer ")) else {
+            return false;
+        };
+
+        token == MOCK_BEARER_TOKEN
+            || self.issued.lock().unwrap()
+                .get(token)
+                .is_some_and(|expires_at| Instant::now() < *expires_at)
+    }
+
+    /// Client-credentials grant: form-encoded grant_type, client_id, client_secret
+    fn issue_token(&self, form: &str) -> Result<serde_json::Value, &'static str> {
+        let params: HashMap<String, String> = form
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(key, value)| (form_decode(key), form_decode(value)))
+            .collect();
+        let param = |name: &str| params.get(name).map(String::as_str);
+
+        if param("grant_type") != Some("client_credentials") {
+            return Err("unsupported_grant_type");
+        }
+        if param("client_id") != Some(MOCK_CLIENT_ID) || param("client_secret") != Some(MOCK_CLIENT_SECRET) {
+            return Err("invalid_client");
+        }
+
+        let token = format!("mock-oauth-{}", self.next_token.fetch_add(1, Ordering::Relaxed));
+        self.issued.lock().unwrap().insert(token.clone(), Instant::now() + self.token_ttl);
+        info!("Issued OAuth2 token {}", token);
+
+        Ok(serde_json::json!({
+            "access_token": token,
+            "token_type": "Bearer",
+            "expires_in": self.token_ttl.as_secs()
+        }))
+    }
+}
+
+/// Decode one `application/x-www-form-urlencoded` key or value: `+` is a
+/// space and `%XX` a byte. Malformed escapes are kept as they are.
+fn form_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() && bytes[i + 1].is_ascii_hexdigit() && bytes[i + 2].is_ascii_hexdigit() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap();
+                decoded.push(u8::from_str_radix(hex, 16).unwrap());
+                i += 2;
+            }
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
 /// Generate streaming A2UI messages for a Payment Page demo
 /// Messages are split to demonstrate progressive streaming rendering
 fn sample_messages() -> Vec<serde_json::Value> {
@@ -620,24 +735,123 @@ fn sample_messages() -> Vec<serde_json::Value> {
     ]
 }
 
+/// Agent card served at /.well-known/agent.json for client discovery
+fn sample_agent_card(auth: &MockAuth) -> serde_json::Value {
+    let mut card = serde_json::json!({
+        "name": "Mock Payment Agent",
+        "description": "Streams a sample A2UI payment page",
+        "url": "http://localhost:8080/rpc",
+        "version": "0.1.0",
+        "protocolVersion": "0.3.0",
+        "capabilities": {
+            "streaming": true,
+            "pushNotifications": false,
+            "extensions": [
+                {
+                    "uri": "https://a2ui.org/a2a-extension/a2ui/v0.8",
+                    "description": "Renders UI with A2UI messages",
+                    "required": true
+                }
+            ]
+        },
+        "defaultInputModes": ["text/plain", "application/json"],
+        "defaultOutputModes": ["application/json"],
+        "skills": [
+            {
+                "id": "payment-page",
+                "name": "Payment Page",
+                "description": "Builds a checkout form with cart summary and payment options",
+                "tags": ["payment", "checkout", "form"],
+                "examples": ["Show me the checkout page"]
+            }
+        ]
+    });
+
+    if auth.enabled {
+        card["securitySchemes"] = serde_json::json!({
+            "bearer": {"type": "http", "scheme": "bearer"},
+            "apiKey": {"type": "apiKey", "name": "X-API-Key", "in": "header"},
+            "oauth2": {
+                "type": "oauth2",
+                "flows": {
+                    "clientCredentials": {
+                        "tokenUrl": "http://localhost:8080/oauth/token",
+                        "scopes": {}
+                    }
+                }
+            }
+        });
+        card["security"] = serde_json::json!([{"bearer": []}, {"apiKey": []}, {"oauth2": []}]);
+    }
+
+    card
+}
+
 /// Handle incoming HTTP requests
-async fn handle_request(req: Request<Incoming>) -> Result<Response<Full<Bytes>>, Infallible> {
+async fn handle_request(
+    req: Request<Incoming>,
+    auth: Arc<MockAuth>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
     match (req.method(), req.uri().path()) {
         // CORS preflight
         (&Method::OPTIONS, _) => {
             let response = Response::builder()
                 .status(StatusCode::OK)
                 .header("Access-Control-Allow-Origin", "*")
-                .header("Access-Control-Allow-Methods", "POST, OPTIONS")
+                .header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
                 .header(
                     "Access-Control-Allow-Headers",
-                    "Content-Type, Accept, Authorization, X-A2A-Extensions",
+                    "Content-Type, Accept, Authorization, X-API-Key, X-A2A-Extensions",
                 )
                 .body(Full::new(Bytes::new()))
                 .unwrap();
             Ok(response)
         }
 
+        // Agent card for discovery
+        (&Method::GET, "/.well-known/agent.json") => {
+            info!("Serving agent card");
+            let response = Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "application/json")
+                .header("Access-Control-Allow-Origin", "*")
+                .body(Full::new(Bytes::from(sample_agent_card(&auth).to_string())))
+                .unwrap();
+            Ok(response)
+        }
+
+        // OAuth2 token endpoint (auth mode only)
+        (&Method::POST, "/oauth/token") if auth.enabled => {
+            let body_bytes = req.collect().await.unwrap().to_bytes();
+            let (status, body) = match auth.issue_token(&String::from_utf8_lossy(&body_bytes)) {
+                Ok(token) => (StatusCode::OK, token),
+                Err(error) => {
+                    warn!("Token request rejected: {}", error);
+                    (StatusCode::UNAUTHORIZED, serde_json::json!({"error": error}))
+                }
+            };
+
+            let response = Response::builder()
+                .status(status)
+                .header("Content-Type", "application/json")
+                .header("Access-Control-Allow-Origin", "*")
+                .body(Full::new(Bytes::from(body.to_string())))
+                .unwrap();
+            Ok(response)
+        }
+
+        // Missing or invalid credentials
+        (&Method::POST, "/rpc") if !auth.is_authorized(&req) => {
+            warn!("Rejected unauthenticated /rpc request");
+            let response = Response::builder()
+                .status(StatusCode::UNAUTHORIZED)
+                .header("WWW-Authenticate", "Bearer realm=\"mock-a2a\", error=\"invalid_token\"")
+                .header("Access-Control-Allow-Origin", "*")
+                .body(Full::new(Bytes::from("Unauthorized")))
+                .unwrap();
+            Ok(response)
+        }
+
         // Main RPC endpoint
         (&Method::POST, "/rpc") => {
             // Read request body
@@ -684,6 +898,8 @@ async fn handle_request(req: Request<Incoming>) -> Result<Response<Full<Bytes>>,
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     env_logger::init();
 
+    let auth = Arc::new(MockAuth::new(std::env::args().any(|arg| arg == "--auth")));
+
     let addr = SocketAddr::from(([127, 0, 0, 1], 8080));
     let listener = TcpListener::bind(addr).await?;
 
@@ -691,6 +907,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     println!("  Mock A2A Server - Payment Page Demo");
     println!("===========================================");
     println!("Listening on http://{}/rpc", addr);
+    println!("Agent card: http://{}/.well-known/agent.json", addr);
+    if auth.enabled {
+        println!();
+        println!("Auth required on /rpc:");
+        println!("  Authorization: Bearer {}", MOCK_BEARER_TOKEN);
+        println!("  X-API-Key: {}", MOCK_API_KEY);
+        println!("  OAuth2 client credentials: POST http://{}/oauth/token ({} / {})", addr, MOCK_CLIENT_ID, MOCK_CLIENT_SECRET);
+    }
     println!("Press Ctrl+C to stop");
     println!();
 
@@ -699,10 +923,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         info!("Connection from {}", remote_addr);
 
         let io = TokioIo::new(stream);
+        let auth = auth.clone();
 
         tokio::task::spawn(async move {
             if let Err(err) = http1::Builder::new()
-                .serve_connection(io, service_fn(handle_request))
+                .serve_connection(io, service_fn(move |req| handle_request(req, auth.clone())))
                 .await
             {
                 error!("Connection error: {:?}", err);
@@ -710,3 +935,85 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve `handle_request` with auth enabled on an ephemeral local port
+    async fn serve_with_auth() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let auth = Arc::new(MockAuth::new(true));
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let auth = auth.clone();
+                tokio::spawn(async move {
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service_fn(move |req| handle_request(req, auth.clone())))
+                        .await;
+                });
+            }
+        });
+        addr
+    }
+
+    /// POST `body` to `path` and return the raw response
+    async fn post(addr: SocketAddr, path: &str, headers: &str, body: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n{}Content-Length: {}\r\n\r\n{}",
+            path, headers, body.len(), body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[test]
+    fn test_form_decode() {
+        assert_eq!(form_decode("s3cr%26t%2B%25%3D"), "s3cr&t+%=");
+        assert_eq!(form_decode("client+id"), "client id");
+        assert_eq!(form_decode("caf%C3%A9"), "café");
+        // Malformed escapes pass through
+        assert_eq!(form_decode("100%"), "100%");
+        assert_eq!(form_decode("%zz%4"), "%zz%4");
+    }
+
+    #[test]
+    fn test_issue_token_decodes_form() {
+        let auth = MockAuth::new(true);
+        let token = auth.issue_token("grant_type=client_credentials&client_id=mock%2Dclient&client_secret=mock-secret").unwrap();
+        assert_eq!(token["token_type"], "Bearer");
+        assert!(token["access_token"].as_str().unwrap().starts_with("mock-oauth-"));
+
+        assert_eq!(auth.issue_token("grant_type=password&client_id=mock-client&client_secret=mock-secret"), Err("unsupported_grant_type"));
+        assert_eq!(auth.issue_token("grant_type=client_credentials&client_id=mock-client&client_secret=mock%2Bsecret"), Err("invalid_client"));
+    }
+
+    #[tokio::test]
+    async fn test_auth_mode_token_flow() {
+        let addr = serve_with_auth().await;
+        let rpc = r#"{"jsonrpc": "2.0", "id": 1, "method": "message/stream", "params": {}}"#;
+
+        let response = post(addr, "/rpc", "", rpc).await;
+        assert!(response.starts_with("HTTP/1.1 401"), "{}", response);
+        assert!(response.to_lowercase().contains("www-authenticate: bearer realm=\"mock-a2a\""));
+
+        let response = post(addr, "/rpc", "X-API-Key: mock-api-key\r\n", rpc).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+
+        let form = "grant_type=client_credentials&client_id=mock-client&client_secret=mock-secret";
+        let response = post(addr, "/oauth/token", "Content-Type: application/x-www-form-urlencoded\r\n", form).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        let body: serde_json::Value = serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        let token = body["access_token"].as_str().unwrap();
+
+        let response = post(addr, "/rpc", &format!("Authorization: Bearer {}\r\n", token), rpc).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        let response = post(addr, "/rpc", "Authorization: Bearer forged\r\n", rpc).await;
+        assert!(response.starts_with("HTTP/1.1 401"), "{}", response);
+    }
+}
@@ -193,6 +193,11 @@ impl StreamingApp {
                         .label(ids!(status_label))
                         .set_text(cx, &format!("Error: {}", e));
                 }
+                A2uiHostEvent::AuthRequired(reason) => {
+                    self.ui
+                        .label(ids!(status_label))
+                        .set_text(cx, &format!("Login required: {}", reason));
+                }
                 A2uiHostEvent::Disconnected => {
                     self.ui
                         .label(ids!(status_label))
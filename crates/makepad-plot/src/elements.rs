@@ -1,6 +1,7 @@
 // Drawing elements for plots
 
 use makepad_widgets::*;
+use crate::export::{record_primitive, ScenePrimitive};
 
 live_design! {
     use link::shaders::*;
@@ -333,11 +334,17 @@ live_design! {
                         // Radial gradient: v0 is center, v1/v2 are edges
                         let final_color = mix(self.gradient_outer_color, self.gradient_center_color, w);
                         return vec4(final_color.rgb * final_color.a * alpha, final_color.a * alpha);
-                    } else {
+                    }
+                    if self.gradient_type < 1.5 {
                         // Vertical gradient: top to bottom based on Y position
                         let final_color = mix(self.gradient_center_color, self.gradient_outer_color, p.y);
                         return vec4(final_color.rgb * final_color.a * alpha, final_color.a * alpha);
                     }
+                    // Per-vertex colors (Gouraud): v0, v1, v2 blended by
+                    // barycentric weight, without the edge fade so the
+                    // triangles of a mesh meet without seams
+                    let final_color = self.gradient_center_color * w + self.gradient_outer_color * u + self.gradient_vertex2_color * v;
+                    return vec4(final_color.rgb * final_color.a, final_color.a);
                 }
                 return vec4(self.color.rgb * self.color.a * alpha, self.color.a * alpha);
             }
@@ -416,8 +423,24 @@ impl DrawPlotLine {
         self.line_style = style as i32 as f32;
         self.dash_offset = dash_offset as f32;
 
+        let color = self.color;
+        record_primitive(|| ScenePrimitive::Line { from: p1, to: p2, width, style, dash_offset, color });
         self.draw_abs(cx, rect);
     }
+
+    /// Line from `start` to `end` with a V-shaped head of `head_size` pixels
+    /// at `end`, as arrow annotations are drawn
+    pub fn draw_arrow(&mut self, cx: &mut Cx2d, start: DVec2, end: DVec2, width: f64, head_size: f64) {
+        self.draw_line(cx, start, end, width);
+        let length = (end - start).length();
+        if length > 0.0 {
+            let dir = (end - start) * (1.0 / length);
+            let base = end - dir * head_size;
+            let perp = dvec2(-dir.y, dir.x) * head_size * 0.5;
+            self.draw_line(cx, end, base + perp, width);
+            self.draw_line(cx, end, base - perp, width);
+        }
+    }
 }
 
 #[derive(Live, LiveHook, LiveRegister)]
@@ -442,6 +465,8 @@ impl DrawPlotPoint {
             pos: dvec2(center.x - radius, center.y - radius),
             size: dvec2(radius * 2.0, radius * 2.0),
         };
+        let color = self.color;
+        record_primitive(|| ScenePrimitive::Marker { center, radius, style, color });
         self.draw_abs(cx, rect);
     }
 }
@@ -459,6 +484,8 @@ pub struct DrawPlotBar {
 impl DrawPlotBar {
     pub fn draw_bar(&mut self, cx: &mut Cx2d, rect: Rect) {
         self.gradient_enabled = 0.0;
+        let color = self.color;
+        record_primitive(|| ScenePrimitive::Rect { rect, color, gradient: None });
         self.draw_abs(cx, rect);
     }
 
@@ -466,6 +493,8 @@ impl DrawPlotBar {
         self.gradient_enabled = 1.0;
         self.gradient_bottom_color = bottom_color;
         self.gradient_top_color = top_color;
+        let color = self.color;
+        record_primitive(|| ScenePrimitive::Rect { rect, color, gradient: Some((bottom_color, top_color)) });
         self.draw_abs(cx, rect);
     }
 }
@@ -481,6 +510,14 @@ pub struct DrawPlotFill {
 }
 
 impl DrawPlotFill {
+    /// Draw a filled rect with the current color and gradient settings
+    pub fn draw_rect(&mut self, cx: &mut Cx2d, rect: Rect) {
+        let color = self.color;
+        let gradient = (self.gradient_enabled > 0.5).then_some((self.gradient_bottom_color, self.gradient_top_color));
+        record_primitive(|| ScenePrimitive::Rect { rect, color, gradient });
+        self.draw_abs(cx, rect);
+    }
+
     /// Draw a filled vertical strip (used for fill_between)
     pub fn draw_fill_strip(&mut self, cx: &mut Cx2d, x: f64, width: f64, y1: f64, y2: f64) {
         self.gradient_enabled = 0.0;
@@ -490,7 +527,7 @@ impl DrawPlotFill {
             pos: dvec2(x, top),
             size: dvec2(width, bottom - top),
         };
-        self.draw_abs(cx, rect);
+        self.draw_rect(cx, rect);
     }
 
     /// Draw a filled strip with vertical gradient
@@ -504,7 +541,7 @@ impl DrawPlotFill {
             pos: dvec2(x, top),
             size: dvec2(width, bottom - top),
         };
-        self.draw_abs(cx, rect);
+        self.draw_rect(cx, rect);
     }
 }
 
@@ -530,6 +567,10 @@ impl DrawPieSlice {
             pos: dvec2(center.x - radius, center.y - radius),
             size: dvec2(radius * 2.0, radius * 2.0),
         };
+        let color = self.color;
+        record_primitive(|| ScenePrimitive::Wedge {
+            center, radius, inner_ratio: 0.0, start: start_angle, end: end_angle, color, gradient: None,
+        });
         self.draw_abs(cx, rect);
     }
 
@@ -544,6 +585,11 @@ impl DrawPieSlice {
             pos: dvec2(center.x - radius, center.y - radius),
             size: dvec2(radius * 2.0, radius * 2.0),
         };
+        let color = self.color;
+        record_primitive(|| ScenePrimitive::Wedge {
+            center, radius, inner_ratio: 0.0, start: start_angle, end: end_angle, color,
+            gradient: Some((center_color, outer_color)),
+        });
         self.draw_abs(cx, rect);
     }
 }
@@ -574,6 +620,10 @@ impl DrawArc {
             pos: dvec2(center.x - outer_radius, center.y - outer_radius),
             size: dvec2(outer_radius * 2.0, outer_radius * 2.0),
         };
+        let color = self.color;
+        record_primitive(|| ScenePrimitive::Wedge {
+            center, radius: outer_radius, inner_ratio: inner_radius_ratio, start: start_angle, end: end_angle, color, gradient: None,
+        });
         self.draw_abs(cx, rect);
     }
 
@@ -590,6 +640,12 @@ impl DrawArc {
             pos: dvec2(center.x - outer_radius, center.y - outer_radius),
             size: dvec2(outer_radius * 2.0, outer_radius * 2.0),
         };
+        // Angular gradients are exported as radial ones
+        let color = self.color;
+        record_primitive(|| ScenePrimitive::Wedge {
+            center, radius: outer_radius, inner_ratio: inner_radius_ratio, start: start_angle, end: end_angle, color,
+            gradient: Some((inner_color, outer_color)),
+        });
         self.draw_abs(cx, rect);
     }
 }
@@ -612,6 +668,8 @@ impl DrawPlotPointGradient {
             pos: dvec2(center.x - radius, center.y - radius),
             size: dvec2(radius * 2.0, radius * 2.0),
         };
+        let color = self.color;
+        record_primitive(|| ScenePrimitive::Marker { center, radius, style: MarkerStyle::Circle, color });
         self.draw_abs(cx, rect);
     }
 
@@ -623,6 +681,11 @@ impl DrawPlotPointGradient {
             pos: dvec2(center.x - radius, center.y - radius),
             size: dvec2(radius * 2.0, radius * 2.0),
         };
+        let color = self.color;
+        record_primitive(|| ScenePrimitive::Wedge {
+            center, radius, inner_ratio: 0.0, start: 0.0, end: std::f64::consts::TAU, color,
+            gradient: Some((center_color, outer_color)),
+        });
         self.draw_abs(cx, rect);
     }
 }
@@ -640,9 +703,11 @@ pub struct DrawTriangle {
     #[live] pub v2x: f32,
     #[live] pub v2y: f32,
     #[live(0.0)] pub gradient_enabled: f32,
-    #[live(0.0)] pub gradient_type: f32, // 0 = center-to-vertices, 1 = top-to-bottom
+    #[live(0.0)] pub gradient_type: f32, // 0 = center-to-vertices, 1 = top-to-bottom, 2 = per vertex
     #[live] pub gradient_center_color: Vec4,
     #[live] pub gradient_outer_color: Vec4,
+    /// Color of the third vertex for per-vertex shading
+    #[live] pub gradient_vertex2_color: Vec4,
 }
 
 impl DrawTriangle {
@@ -676,6 +741,8 @@ impl DrawTriangle {
             pos: dvec2(min_x, min_y),
             size: dvec2(width, height),
         };
+        let color = self.color;
+        record_primitive(|| ScenePrimitive::Triangle { points: [p0, p1, p2], color });
         self.draw_abs(cx, rect);
     }
 
@@ -711,6 +778,58 @@ impl DrawTriangle {
             pos: dvec2(min_x, min_y),
             size: dvec2(width, height),
         };
+        // Exported flat, in the color halfway between center and vertices
+        let color = vec4(
+            (center_color.x + outer_color.x) * 0.5,
+            (center_color.y + outer_color.y) * 0.5,
+            (center_color.z + outer_color.z) * 0.5,
+            (center_color.w + outer_color.w) * 0.5,
+        );
+        record_primitive(|| ScenePrimitive::Triangle { points: [p0, p1, p2], color });
+        self.draw_abs(cx, rect);
+    }
+
+    /// Draw a triangle with a color per vertex, blended smoothly across it
+    /// (Gouraud shading)
+    pub fn draw_triangle_shaded(&mut self, cx: &mut Cx2d, points: [DVec2; 3], colors: [Vec4; 3]) {
+        let [p0, p1, p2] = points;
+        let min_x = p0.x.min(p1.x).min(p2.x);
+        let min_y = p0.y.min(p1.y).min(p2.y);
+        let max_x = p0.x.max(p1.x).max(p2.x);
+        let max_y = p0.y.max(p1.y).max(p2.y);
+
+        let width = max_x - min_x;
+        let height = max_y - min_y;
+
+        if width < 0.001 || height < 0.001 {
+            return;
+        }
+
+        self.v0x = ((p0.x - min_x) / width) as f32;
+        self.v0y = ((p0.y - min_y) / height) as f32;
+        self.v1x = ((p1.x - min_x) / width) as f32;
+        self.v1y = ((p1.y - min_y) / height) as f32;
+        self.v2x = ((p2.x - min_x) / width) as f32;
+        self.v2y = ((p2.y - min_y) / height) as f32;
+
+        self.gradient_enabled = 1.0;
+        self.gradient_type = 2.0;
+        self.gradient_center_color = colors[0];
+        self.gradient_outer_color = colors[1];
+        self.gradient_vertex2_color = colors[2];
+
+        let rect = Rect {
+            pos: dvec2(min_x, min_y),
+            size: dvec2(width, height),
+        };
+        // Exported flat, in the mean of the vertex colors
+        let color = vec4(
+            (colors[0].x + colors[1].x + colors[2].x) / 3.0,
+            (colors[0].y + colors[1].y + colors[2].y) / 3.0,
+            (colors[0].z + colors[1].z + colors[2].z) / 3.0,
+            (colors[0].w + colors[1].w + colors[2].w) / 3.0,
+        );
+        record_primitive(|| ScenePrimitive::Triangle { points, color });
         self.draw_abs(cx, rect);
     }
 }
@@ -0,0 +1,1645 @@
+// Scene capture and SVG/PNG export for plots
+//
+// Every plot primitive in `elements.rs` and `PlotLabel::draw_at` reports what
+// it draws through `record_primitive`. While a widget's `SceneCapture` is
+// active those calls build a `PlotScene`, a resolution independent list of
+// lines, markers, fills and text that can be written out as SVG or
+// rasterized to PNG on the CPU. Scenes can also be built directly (see
+// `HeadlessPlot`), so reports can be rendered without a window or GPU.
+// PNG text uses a 5x7 bitmap font covering ASCII, Greek and the math
+// symbols, so it approximates the on-screen glyphs; SVG keeps real text.
+
+use std::cell::RefCell;
+use std::f64::consts::{PI, TAU};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use makepad_widgets::*;
+
+use crate::elements::{LineStyle, MarkerStyle};
+use crate::math::*;
+use crate::text::TextAnchor;
+
+/// One drawing operation, in logical pixels from the plot's top left corner
+#[derive(Clone, Debug)]
+pub enum ScenePrimitive {
+    Line {
+        from: DVec2,
+        to: DVec2,
+        width: f64,
+        style: LineStyle,
+        /// Offset into the dash pattern at `from`
+        dash_offset: f64,
+        color: Vec4,
+    },
+    /// Marker of the given style; `radius` is half the marker's box
+    Marker {
+        center: DVec2,
+        radius: f64,
+        style: MarkerStyle,
+        color: Vec4,
+    },
+    /// Axis-aligned fill; `gradient` is (bottom, top)
+    Rect {
+        rect: Rect,
+        color: Vec4,
+        gradient: Option<(Vec4, Vec4)>,
+    },
+    /// Pie slice or ring segment. Angles are radians, clockwise from +x;
+    /// `gradient` is (inner, outer)
+    Wedge {
+        center: DVec2,
+        radius: f64,
+        /// Inner radius as a fraction of `radius`, 0 for a full slice
+        inner_ratio: f64,
+        start: f64,
+        end: f64,
+        color: Vec4,
+        gradient: Option<(Vec4, Vec4)>,
+    },
+    Triangle {
+        points: [DVec2; 3],
+        color: Vec4,
+    },
+    Text {
+        pos: DVec2,
+        text: String,
+        anchor: TextAnchor,
+        font_size: f64,
+        color: Vec4,
+    },
+}
+
+impl ScenePrimitive {
+    fn translate(&mut self, offset: DVec2) {
+        match self {
+            Self::Line { from, to, .. } => {
+                *from = *from + offset;
+                *to = *to + offset;
+            }
+            Self::Marker { center, .. } | Self::Wedge { center, .. } => *center = *center + offset,
+            Self::Rect { rect, .. } => rect.pos = rect.pos + offset,
+            Self::Triangle { points, .. } => {
+                for p in points.iter_mut() {
+                    *p = *p + offset;
+                }
+            }
+            Self::Text { pos, .. } => *pos = *pos + offset,
+        }
+    }
+}
+
+/// Retained drawing of a plot that can be exported without a GPU
+#[derive(Clone, Debug, Default)]
+pub struct PlotScene {
+    /// Logical size of the plot
+    pub size: DVec2,
+    pub primitives: Vec<ScenePrimitive>,
+}
+
+impl PlotScene {
+    pub fn new(size: DVec2) -> Self {
+        Self { size, primitives: Vec::new() }
+    }
+
+    pub fn push(&mut self, primitive: ScenePrimitive) {
+        self.primitives.push(primitive);
+    }
+
+    /// Solid line segment
+    pub fn line(&mut self, from: DVec2, to: DVec2, width: f64, color: Vec4) {
+        self.line_styled(from, to, width, LineStyle::Solid, color);
+    }
+
+    pub fn line_styled(&mut self, from: DVec2, to: DVec2, width: f64, style: LineStyle, color: Vec4) {
+        self.push(ScenePrimitive::Line { from, to, width, style, dash_offset: 0.0, color });
+    }
+
+    pub fn marker(&mut self, center: DVec2, radius: f64, style: MarkerStyle, color: Vec4) {
+        if style != MarkerStyle::None {
+            self.push(ScenePrimitive::Marker { center, radius, style, color });
+        }
+    }
+
+    pub fn rect(&mut self, rect: Rect, color: Vec4) {
+        self.push(ScenePrimitive::Rect { rect, color, gradient: None });
+    }
+
+    pub fn text(&mut self, pos: DVec2, text: &str, anchor: TextAnchor, font_size: f64, color: Vec4) {
+        self.push(ScenePrimitive::Text { pos, text: text.to_string(), anchor, font_size, color });
+    }
+
+    /// Text at `pos` with `$...$` spans laid out as math, using estimated
+    /// metrics, as `PlotLabel::draw_math_at` draws it
+    pub fn math_text(&mut self, pos: DVec2, text: &str, anchor: TextAnchor, font_size: f64, color: Vec4) {
+        if !has_math(text) {
+            self.text(pos, text, anchor, font_size, color);
+            return;
+        }
+        let layout = layout_math(&parse_rich(text), font_size, &mut approx_text_size);
+        let origin = pos + anchor.offset(layout.size);
+        for item in layout.items {
+            match item {
+                MathItem::Glyph { pos, text, font_size } => {
+                    self.push(ScenePrimitive::Text { pos: origin + pos, text, anchor: TextAnchor::TopLeft, font_size, color });
+                }
+                MathItem::Rule { from, to, width } => self.line(origin + from, origin + to, width, color),
+            }
+        }
+    }
+
+    /// SVG document for the scene
+    pub fn to_svg(&self, options: &ExportOptions) -> String {
+        write_svg(self, options)
+    }
+
+    /// PNG file bytes for the scene
+    pub fn to_png(&self, options: &ExportOptions) -> Vec<u8> {
+        let raster = rasterize(self, options);
+        encode_png(raster.width, raster.height, &raster.to_rgba8())
+    }
+
+    pub fn save_svg(&self, path: impl AsRef<Path>, options: &ExportOptions) -> Result<(), String> {
+        write_file(path.as_ref(), self.to_svg(options).as_bytes())
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>, options: &ExportOptions) -> Result<(), String> {
+        write_file(path.as_ref(), &self.to_png(options))
+    }
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// =============================================================================
+// Capture
+// =============================================================================
+
+thread_local! {
+    /// Scenes being captured (capture id, plot origin, scene), innermost last
+    static RECORDING: RefCell<Vec<(u64, DVec2, PlotScene)>> = const { RefCell::new(Vec::new()) };
+}
+
+static NEXT_CAPTURE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Add a primitive in absolute coordinates to every scene being captured.
+/// `make` only runs while a capture is active, so drawing is unaffected
+/// when nothing is exported.
+pub fn record_primitive(make: impl FnOnce() -> ScenePrimitive) {
+    RECORDING.with(|recording| {
+        let mut recording = recording.borrow_mut();
+        if recording.is_empty() {
+            return;
+        }
+        let primitive = make();
+        for (_, origin, scene) in recording.iter_mut() {
+            let mut primitive = primitive.clone();
+            primitive.translate(dvec2(-origin.x, -origin.y));
+            scene.push(primitive);
+        }
+    });
+}
+
+/// Keeps the scene of a widget's last drawn frame. Widgets call `begin`
+/// once their rect is known and `end` before leaving `draw_walk`.
+#[derive(Clone, Debug, Default)]
+pub struct SceneCapture {
+    /// Capture is off by default since it keeps a copy of every primitive
+    pub enabled: bool,
+    id: u64,
+    scene: Option<PlotScene>,
+}
+
+impl SceneCapture {
+    pub fn begin(&mut self, rect: Rect) {
+        if !self.enabled {
+            return;
+        }
+        if self.id == 0 {
+            self.id = NEXT_CAPTURE_ID.fetch_add(1, Ordering::Relaxed);
+        }
+        let id = self.id;
+        RECORDING.with(|recording| {
+            let mut recording = recording.borrow_mut();
+            recording.retain(|(other, _, _)| *other != id);
+            recording.push((id, rect.pos, PlotScene::new(rect.size)));
+        });
+    }
+
+    pub fn end(&mut self) {
+        if self.id == 0 {
+            return;
+        }
+        let id = self.id;
+        let scene = RECORDING.with(|recording| {
+            let mut recording = recording.borrow_mut();
+            let index = recording.iter().position(|(other, _, _)| *other == id)?;
+            Some(recording.remove(index).2)
+        });
+        if scene.is_some() {
+            self.scene = scene;
+        }
+    }
+
+    /// Scene of the last frame drawn while capture was enabled
+    pub fn scene(&self) -> Option<&PlotScene> {
+        self.scene.as_ref()
+    }
+}
+
+/// Output size, resolution and background for an export
+#[derive(Clone, Debug)]
+pub struct ExportOptions {
+    /// Output size in logical pixels; the scene is scaled to fit, keeping
+    /// its aspect ratio. `None` keeps the drawn size.
+    pub size: Option<DVec2>,
+    /// Dots per inch; 96 maps one logical pixel to one output pixel
+    pub dpi: f64,
+    /// Fill behind the plot; `None` leaves it transparent
+    pub background: Option<Vec4>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            size: None,
+            dpi: 96.0,
+            background: Some(vec4(1.0, 1.0, 1.0, 1.0)),
+        }
+    }
+}
+
+impl ExportOptions {
+    pub fn with_size(mut self, width: f64, height: f64) -> Self {
+        self.size = Some(dvec2(width, height));
+        self
+    }
+
+    pub fn with_dpi(mut self, dpi: f64) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    pub fn with_background(mut self, background: Option<Vec4>) -> Self {
+        self.background = background;
+        self
+    }
+
+    fn logical_size(&self, scene: &PlotScene) -> DVec2 {
+        self.size.unwrap_or(scene.size)
+    }
+
+    fn pixel_scale(&self) -> f64 {
+        if self.dpi > 0.0 { self.dpi / 96.0 } else { 1.0 }
+    }
+}
+
+/// Export for plot widgets. Enable capture, let the plot redraw, then
+/// export its last frame:
+///
+/// ```ignore
+/// plot.set_export_enabled(true);
+/// // ... after the next draw
+/// plot.save_png("chart.png", &ExportOptions::default().with_dpi(192.0))?;
+/// ```
+pub trait PlotExport {
+    fn scene_capture(&self) -> &SceneCapture;
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture;
+
+    fn set_export_enabled(&mut self, enabled: bool) {
+        self.scene_capture_mut().enabled = enabled;
+    }
+
+    fn export_scene(&self) -> Result<&PlotScene, String> {
+        self.scene_capture()
+            .scene()
+            .ok_or_else(|| "Plot has not been drawn since export was enabled".to_string())
+    }
+
+    fn export_svg(&self, options: &ExportOptions) -> Result<String, String> {
+        Ok(self.export_scene()?.to_svg(options))
+    }
+
+    fn export_png(&self, options: &ExportOptions) -> Result<Vec<u8>, String> {
+        Ok(self.export_scene()?.to_png(options))
+    }
+
+    fn save_svg(&self, path: impl AsRef<Path>, options: &ExportOptions) -> Result<(), String> {
+        self.export_scene()?.save_svg(path, options)
+    }
+
+    fn save_png(&self, path: impl AsRef<Path>, options: &ExportOptions) -> Result<(), String> {
+        self.export_scene()?.save_png(path, options)
+    }
+}
+
+// =============================================================================
+// Shapes shared by both backends
+// =============================================================================
+
+/// Marker outline in the same proportions as the `DrawPlotPoint` shader
+enum MarkerShape {
+    Circle(f64),
+    Polygon(Vec<DVec2>),
+    /// Stroked segments and their width
+    Strokes(Vec<(DVec2, DVec2)>, f64),
+}
+
+fn marker_shape(style: MarkerStyle, c: DVec2, r: f64) -> MarkerShape {
+    match style {
+        MarkerStyle::None | MarkerStyle::Circle => MarkerShape::Circle(0.9 * r),
+        MarkerStyle::Square => {
+            let h = 0.8 * r;
+            MarkerShape::Polygon(vec![
+                dvec2(c.x - h, c.y - h),
+                dvec2(c.x + h, c.y - h),
+                dvec2(c.x + h, c.y + h),
+                dvec2(c.x - h, c.y + h),
+            ])
+        }
+        MarkerStyle::TriangleUp => MarkerShape::Polygon(vec![
+            dvec2(c.x, c.y - 0.5 * r),
+            dvec2(c.x + 0.866 * r, c.y),
+            dvec2(c.x - 0.866 * r, c.y),
+        ]),
+        MarkerStyle::TriangleDown => MarkerShape::Polygon(vec![
+            dvec2(c.x, c.y + 0.5 * r),
+            dvec2(c.x - 0.866 * r, c.y),
+            dvec2(c.x + 0.866 * r, c.y),
+        ]),
+        MarkerStyle::Diamond => {
+            let h = 0.8 * r;
+            MarkerShape::Polygon(vec![
+                dvec2(c.x, c.y - h),
+                dvec2(c.x + h, c.y),
+                dvec2(c.x, c.y + h),
+                dvec2(c.x - h, c.y),
+            ])
+        }
+        MarkerStyle::Cross => {
+            let d = 0.9 * r / 2f64.sqrt();
+            MarkerShape::Strokes(vec![
+                (dvec2(c.x - d, c.y - d), dvec2(c.x + d, c.y + d)),
+                (dvec2(c.x - d, c.y + d), dvec2(c.x + d, c.y - d)),
+            ], 0.32 * r)
+        }
+        MarkerStyle::Plus => {
+            let d = 0.9 * r;
+            MarkerShape::Strokes(vec![
+                (dvec2(c.x - d, c.y), dvec2(c.x + d, c.y)),
+                (dvec2(c.x, c.y - d), dvec2(c.x, c.y + d)),
+            ], 0.32 * r)
+        }
+        MarkerStyle::Star => {
+            let points = (0..10u32)
+                .map(|i| {
+                    let angle = -PI / 10.0 + i as f64 * PI / 5.0;
+                    let radius = if i.is_multiple_of(2) { r } else { 0.4 * r };
+                    dvec2(c.x + radius * angle.cos(), c.y + radius * angle.sin())
+                })
+                .collect();
+            MarkerShape::Polygon(points)
+        }
+    }
+}
+
+/// Dash pattern of a line style as (on, off, ...) lengths
+fn dash_pattern(style: LineStyle) -> &'static [f64] {
+    match style {
+        LineStyle::Solid => &[],
+        LineStyle::Dashed => &[10.0, 5.0],
+        LineStyle::Dotted => &[2.0, 4.0],
+        LineStyle::DashDot => &[10.0, 4.0, 2.0, 4.0],
+    }
+}
+
+fn mix_color(a: Vec4, b: Vec4, t: f64) -> Vec4 {
+    let t = t.clamp(0.0, 1.0) as f32;
+    vec4(
+        a.x + (b.x - a.x) * t,
+        a.y + (b.y - a.y) * t,
+        a.z + (b.z - a.z) * t,
+        a.w + (b.w - a.w) * t,
+    )
+}
+
+// =============================================================================
+// SVG
+// =============================================================================
+
+fn num(v: f64) -> String {
+    let s = format!("{:.2}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { "0".to_string() } else { s.to_string() }
+}
+
+fn hex_color(c: Vec4) -> String {
+    let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", channel(c.x), channel(c.y), channel(c.z))
+}
+
+/// ` fill="#rrggbb"` or ` stroke=...`, with an opacity attribute when translucent
+fn paint(attr: &str, c: Vec4) -> String {
+    if c.w < 1.0 {
+        format!(" {}=\"{}\" {}-opacity=\"{}\"", attr, hex_color(c), attr, num(c.w.max(0.0) as f64))
+    } else {
+        format!(" {}=\"{}\"", attr, hex_color(c))
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn points_attr(points: &[DVec2]) -> String {
+    points.iter().map(|p| format!("{},{}", num(p.x), num(p.y))).collect::<Vec<_>>().join(" ")
+}
+
+fn stroke_attrs(width: f64, style: LineStyle, dash_offset: f64, color: Vec4) -> String {
+    let mut s = format!("{} stroke-width=\"{}\"", paint("stroke", color), num(width));
+    let pattern = dash_pattern(style);
+    if pattern.is_empty() {
+        s.push_str(" stroke-linecap=\"round\" stroke-linejoin=\"round\"");
+    } else {
+        let dashes = pattern.iter().map(|v| num(*v)).collect::<Vec<_>>().join(" ");
+        s.push_str(&format!(" stroke-dasharray=\"{}\"", dashes));
+        if dash_offset != 0.0 {
+            s.push_str(&format!(" stroke-dashoffset=\"{}\"", num(dash_offset)));
+        }
+    }
+    s
+}
+
+fn arc_point(center: DVec2, radius: f64, angle: f64) -> DVec2 {
+    dvec2(center.x + radius * angle.cos(), center.y + radius * angle.sin())
+}
+
+/// Path data for a full circle drawn as two half arcs
+fn circle_path(center: DVec2, radius: f64) -> String {
+    format!(
+        "M{} {}A{r} {r} 0 1 1 {} {}A{r} {r} 0 1 1 {} {}Z",
+        num(center.x + radius), num(center.y),
+        num(center.x - radius), num(center.y),
+        num(center.x + radius), num(center.y),
+        r = num(radius),
+    )
+}
+
+fn wedge_path(center: DVec2, radius: f64, inner: f64, start: f64, end: f64) -> String {
+    let span = end - start;
+    if span >= TAU - 1e-6 {
+        let mut d = circle_path(center, radius);
+        if inner > 0.0 {
+            d.push_str(&circle_path(center, inner));
+        }
+        return d;
+    }
+    let large = if span > PI { 1 } else { 0 };
+    let p0 = arc_point(center, radius, start);
+    let p1 = arc_point(center, radius, end);
+    let mut d = if inner > 0.0 {
+        format!("M{} {}", num(p0.x), num(p0.y))
+    } else {
+        format!("M{} {}L{} {}", num(center.x), num(center.y), num(p0.x), num(p0.y))
+    };
+    d.push_str(&format!("A{r} {r} 0 {} 1 {} {}", large, num(p1.x), num(p1.y), r = num(radius)));
+    if inner > 0.0 {
+        let q0 = arc_point(center, inner, end);
+        let q1 = arc_point(center, inner, start);
+        d.push_str(&format!(
+            "L{} {}A{r} {r} 0 {} 0 {} {}",
+            num(q0.x), num(q0.y), large, num(q1.x), num(q1.y), r = num(inner),
+        ));
+    }
+    d.push('Z');
+    d
+}
+
+fn write_svg(scene: &PlotScene, options: &ExportOptions) -> String {
+    let size = options.logical_size(scene);
+    let scale = options.pixel_scale();
+    let mut out = String::new();
+    out.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+        num(size.x * scale), num(size.y * scale), num(scene.size.x), num(scene.size.y),
+    ));
+    if let Some(bg) = options.background {
+        out.push_str(&format!(
+            "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\"{}/>\n",
+            num(scene.size.x), num(scene.size.y), paint("fill", bg),
+        ));
+    }
+
+    let mut gradient_id = 0;
+    let mut i = 0;
+    while i < scene.primitives.len() {
+        match &scene.primitives[i] {
+            ScenePrimitive::Line { from, to, width, style, dash_offset, color } => {
+                // Join consecutive segments of one series into a polyline
+                let mut points = vec![*from, *to];
+                while let Some(ScenePrimitive::Line { from: f, to: t, width: w, style: s, color: c, .. }) =
+                    scene.primitives.get(i + 1)
+                {
+                    let last = points[points.len() - 1];
+                    let joined = (f.x - last.x).abs() < 1e-6 && (f.y - last.y).abs() < 1e-6;
+                    if !joined || w != width || s != style || c != color {
+                        break;
+                    }
+                    points.push(*t);
+                    i += 1;
+                }
+                let stroke = stroke_attrs(*width, *style, *dash_offset, *color);
+                if points.len() == 2 {
+                    out.push_str(&format!(
+                        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"{}/>\n",
+                        num(from.x), num(from.y), num(to.x), num(to.y), stroke,
+                    ));
+                } else {
+                    out.push_str(&format!("<polyline points=\"{}\" fill=\"none\"{}/>\n", points_attr(&points), stroke));
+                }
+            }
+            ScenePrimitive::Marker { center, radius, style, color } => {
+                match marker_shape(*style, *center, *radius) {
+                    MarkerShape::Circle(r) => out.push_str(&format!(
+                        "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"{}/>\n",
+                        num(center.x), num(center.y), num(r), paint("fill", *color),
+                    )),
+                    MarkerShape::Polygon(points) => out.push_str(&format!(
+                        "<polygon points=\"{}\"{}/>\n", points_attr(&points), paint("fill", *color),
+                    )),
+                    MarkerShape::Strokes(segments, width) => {
+                        for (a, b) in segments {
+                            out.push_str(&format!(
+                                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"{} stroke-width=\"{}\"/>\n",
+                                num(a.x), num(a.y), num(b.x), num(b.y), paint("stroke", *color), num(width),
+                            ));
+                        }
+                    }
+                }
+            }
+            ScenePrimitive::Rect { rect, color, gradient } => {
+                let fill = match gradient {
+                    Some((bottom, top)) => {
+                        gradient_id += 1;
+                        out.push_str(&format!(
+                            "<defs><linearGradient id=\"g{}\" x1=\"0\" y1=\"1\" x2=\"0\" y2=\"0\">\
+                             <stop offset=\"0\" stop-color=\"{}\" stop-opacity=\"{}\"/>\
+                             <stop offset=\"1\" stop-color=\"{}\" stop-opacity=\"{}\"/>\
+                             </linearGradient></defs>\n",
+                            gradient_id, hex_color(*bottom), num(bottom.w as f64), hex_color(*top), num(top.w as f64),
+                        ));
+                        format!(" fill=\"url(#g{})\"", gradient_id)
+                    }
+                    None => paint("fill", *color),
+                };
+                out.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}/>\n",
+                    num(rect.pos.x), num(rect.pos.y), num(rect.size.x), num(rect.size.y), fill,
+                ));
+            }
+            ScenePrimitive::Wedge { center, radius, inner_ratio, start, end, color, gradient } => {
+                let inner = radius * inner_ratio.clamp(0.0, 1.0);
+                let fill = match gradient {
+                    Some((inner_color, outer_color)) => {
+                        gradient_id += 1;
+                        out.push_str(&format!(
+                            "<defs><radialGradient id=\"g{}\" gradientUnits=\"userSpaceOnUse\" cx=\"{}\" cy=\"{}\" r=\"{}\">\
+                             <stop offset=\"{}\" stop-color=\"{}\" stop-opacity=\"{}\"/>\
+                             <stop offset=\"1\" stop-color=\"{}\" stop-opacity=\"{}\"/>\
+                             </radialGradient></defs>\n",
+                            gradient_id, num(center.x), num(center.y), num(*radius),
+                            num(inner_ratio.clamp(0.0, 1.0)), hex_color(*inner_color), num(inner_color.w as f64),
+                            hex_color(*outer_color), num(outer_color.w as f64),
+                        ));
+                        format!(" fill=\"url(#g{})\"", gradient_id)
+                    }
+                    None => paint("fill", *color),
+                };
+                if end - start >= TAU - 1e-6 && inner <= 0.0 {
+                    out.push_str(&format!(
+                        "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"{}/>\n",
+                        num(center.x), num(center.y), num(*radius), fill,
+                    ));
+                } else {
+                    out.push_str(&format!(
+                        "<path d=\"{}\" fill-rule=\"evenodd\"{}/>\n",
+                        wedge_path(*center, *radius, inner, *start, *end), fill,
+                    ));
+                }
+            }
+            ScenePrimitive::Triangle { points, color } => {
+                out.push_str(&format!("<polygon points=\"{}\"{}/>\n", points_attr(points), paint("fill", *color)));
+            }
+            ScenePrimitive::Text { pos, text, anchor, font_size, color } => {
+                let (h_anchor, baseline) = svg_text_anchor(*anchor);
+                out.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" text-anchor=\"{}\" dominant-baseline=\"{}\"{}>{}</text>\n",
+                    num(pos.x), num(pos.y), num(*font_size), h_anchor, baseline, paint("fill", *color), escape_xml(text),
+                ));
+            }
+        }
+        i += 1;
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+fn svg_text_anchor(anchor: TextAnchor) -> (&'static str, &'static str) {
+    use TextAnchor::*;
+    let h = match anchor {
+        TopLeft | MiddleLeft | BottomLeft => "start",
+        TopCenter | Center | BottomCenter => "middle",
+        TopRight | MiddleRight | BottomRight => "end",
+    };
+    let v = match anchor {
+        TopLeft | TopCenter | TopRight => "hanging",
+        MiddleLeft | Center | MiddleRight => "central",
+        BottomLeft | BottomCenter | BottomRight => "auto",
+    };
+    (h, v)
+}
+
+// =============================================================================
+// Raster
+// =============================================================================
+
+/// Premultiplied RGBA canvas the scene is rasterized into
+struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 4]>,
+    scale: f64,
+    offset: DVec2,
+}
+
+impl Raster {
+    fn to_device(&self, p: DVec2) -> DVec2 {
+        dvec2(p.x * self.scale + self.offset.x, p.y * self.scale + self.offset.y)
+    }
+
+    /// Pixel range covering `[min, max]` in device space, clipped to the canvas
+    fn span(&self, min: DVec2, max: DVec2) -> Option<(usize, usize, usize, usize)> {
+        let x0 = min.x.floor().max(0.0) as usize;
+        let y0 = min.y.floor().max(0.0) as usize;
+        let x1 = (max.x.ceil().min(self.width as f64)).max(0.0) as usize;
+        let y1 = (max.y.ceil().min(self.height as f64)).max(0.0) as usize;
+        (x0 < x1 && y0 < y1).then_some((x0, y0, x1, y1))
+    }
+
+    fn blend(&mut self, x: usize, y: usize, color: Vec4, coverage: f64) {
+        let a = (color.w as f64 * coverage.clamp(0.0, 1.0)) as f32;
+        if a <= 0.0 {
+            return;
+        }
+        let dst = &mut self.pixels[y * self.width + x];
+        dst[0] = color.x * a + dst[0] * (1.0 - a);
+        dst[1] = color.y * a + dst[1] * (1.0 - a);
+        dst[2] = color.z * a + dst[2] * (1.0 - a);
+        dst[3] = a + dst[3] * (1.0 - a);
+    }
+
+    fn fill(&mut self, color: Vec4) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.blend(x, y, color, 1.0);
+            }
+        }
+    }
+
+    fn line(&mut self, from: DVec2, to: DVec2, width: f64, style: LineStyle, dash_offset: f64, color: Vec4) {
+        let a = self.to_device(from);
+        let b = self.to_device(to);
+        let half = width * self.scale * 0.5;
+        let edge = 1.0;
+        let pad = half + edge;
+        let Some((x0, y0, x1, y1)) = self.span(
+            dvec2(a.x.min(b.x) - pad, a.y.min(b.y) - pad),
+            dvec2(a.x.max(b.x) + pad, a.y.max(b.y) + pad),
+        ) else {
+            return;
+        };
+        let ba = b - a;
+        let len_sq = (ba.x * ba.x + ba.y * ba.y).max(1e-4);
+        let logical_len = (from - to).length();
+        let pattern = dash_pattern(style);
+        let period: f64 = pattern.iter().sum();
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let p = dvec2(x as f64 + 0.5, y as f64 + 0.5);
+                let pa = p - a;
+                let h = ((pa.x * ba.x + pa.y * ba.y) / len_sq).clamp(0.0, 1.0);
+                let dist = (pa - ba * h).length();
+                if dist > pad {
+                    continue;
+                }
+                if period > 0.0 {
+                    let mut along = (h * logical_len + dash_offset).rem_euclid(period);
+                    let mut on = false;
+                    for (k, seg) in pattern.iter().enumerate() {
+                        if along < *seg {
+                            on = k.is_multiple_of(2);
+                            break;
+                        }
+                        along -= seg;
+                    }
+                    if !on {
+                        continue;
+                    }
+                }
+                let coverage = 1.0 - smoothstep(half - edge, half + edge, dist);
+                self.blend(x, y, color, coverage);
+            }
+        }
+    }
+
+    fn rect(&mut self, rect: Rect, color: Vec4, gradient: Option<(Vec4, Vec4)>) {
+        let min = self.to_device(rect.pos);
+        let max = self.to_device(rect.pos + rect.size);
+        let Some((x0, y0, x1, y1)) = self.span(min, max) else {
+            return;
+        };
+        let height = (max.y - min.y).max(1e-9);
+        for y in y0..y1 {
+            let cov_y = overlap(y as f64, min.y, max.y);
+            let color = match gradient {
+                Some((bottom, top)) => mix_color(bottom, top, 1.0 - (y as f64 + 0.5 - min.y) / height),
+                None => color,
+            };
+            for x in x0..x1 {
+                self.blend(x, y, color, cov_y * overlap(x as f64, min.x, max.x));
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn wedge(&mut self, center: DVec2, radius: f64, inner_ratio: f64, start: f64, end: f64, color: Vec4, gradient: Option<(Vec4, Vec4)>) {
+        let c = self.to_device(center);
+        let outer = radius * self.scale;
+        let inner = outer * inner_ratio.clamp(0.0, 1.0);
+        let span = end - start;
+        let Some((x0, y0, x1, y1)) = self.span(c - dvec2(outer + 1.0, outer + 1.0), c + dvec2(outer + 1.0, outer + 1.0)) else {
+            return;
+        };
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let d = dvec2(x as f64 + 0.5, y as f64 + 0.5) - c;
+                let dist = d.length();
+                let mut coverage = (outer - dist + 0.5).clamp(0.0, 1.0);
+                if inner > 0.0 {
+                    coverage *= (dist - inner + 0.5).clamp(0.0, 1.0);
+                }
+                if coverage <= 0.0 {
+                    continue;
+                }
+                if span < TAU - 1e-6 && (d.y.atan2(d.x) - start).rem_euclid(TAU) > span {
+                    continue;
+                }
+                let color = match gradient {
+                    Some((inner_color, outer_color)) => {
+                        mix_color(inner_color, outer_color, (dist - inner) / (outer - inner).max(1e-9))
+                    }
+                    None => color,
+                };
+                self.blend(x, y, color, coverage);
+            }
+        }
+    }
+
+    fn polygon(&mut self, points: &[DVec2], color: Vec4) {
+        let points: Vec<DVec2> = points.iter().map(|p| self.to_device(*p)).collect();
+        let (mut min, mut max) = (points[0], points[0]);
+        for p in &points {
+            min = dvec2(min.x.min(p.x), min.y.min(p.y));
+            max = dvec2(max.x.max(p.x), max.y.max(p.y));
+        }
+        let Some((x0, y0, x1, y1)) = self.span(min - dvec2(1.0, 1.0), max + dvec2(1.0, 1.0)) else {
+            return;
+        };
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let p = dvec2(x as f64 + 0.5, y as f64 + 0.5);
+                let mut inside = false;
+                let mut dist = f64::MAX;
+                for k in 0..points.len() {
+                    let a = points[k];
+                    let b = points[(k + 1) % points.len()];
+                    if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+                        inside = !inside;
+                    }
+                    let ba = b - a;
+                    let h = ((p - a).x * ba.x + (p - a).y * ba.y) / (ba.x * ba.x + ba.y * ba.y).max(1e-9);
+                    dist = dist.min((p - a - ba * h.clamp(0.0, 1.0)).length());
+                }
+                let signed = if inside { dist } else { -dist };
+                self.blend(x, y, color, (0.5 + signed).clamp(0.0, 1.0));
+            }
+        }
+    }
+
+    fn marker(&mut self, center: DVec2, radius: f64, style: MarkerStyle, color: Vec4) {
+        match marker_shape(style, center, radius) {
+            MarkerShape::Circle(r) => self.wedge(center, r, 0.0, 0.0, TAU, color, None),
+            MarkerShape::Polygon(points) => self.polygon(&points, color),
+            MarkerShape::Strokes(segments, width) => {
+                for (a, b) in segments {
+                    self.line(a, b, width, LineStyle::Solid, 0.0, color);
+                }
+            }
+        }
+    }
+
+    /// Text in the built-in 5x7 bitmap font, sized to `font_size`
+    fn text(&mut self, pos: DVec2, text: &str, anchor: TextAnchor, font_size: f64, color: Vec4) {
+        // 6 units per advance, matching the 0.6em estimate used for math layout
+        let unit = font_size / 10.0;
+        let count = text.chars().count() as f64;
+        // Line box of 1.2em with the glyph baseline where math layout expects it
+        let size = dvec2((count * 6.0 - 1.0).max(0.0) * unit, 12.0 * unit);
+        let origin = pos + anchor.offset(size) + dvec2(0.0, 2.4 * unit);
+        for (i, ch) in text.chars().enumerate() {
+            let glyph = glyph(ch);
+            for (col, bits) in glyph.iter().enumerate() {
+                for row in 0..7 {
+                    if bits & (1 << row) != 0 {
+                        let cell = Rect {
+                            pos: origin + dvec2((i as f64 * 6.0 + col as f64) * unit, row as f64 * unit),
+                            size: dvec2(unit, unit),
+                        };
+                        self.rect(cell, color, None);
+                    }
+                }
+            }
+        }
+    }
+
+    fn to_rgba8(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.pixels.len() * 4);
+        for px in &self.pixels {
+            let a = px[3];
+            let unpremultiply = |v: f32| if a > 0.0 { (v / a).clamp(0.0, 1.0) } else { 0.0 };
+            out.push((unpremultiply(px[0]) * 255.0).round() as u8);
+            out.push((unpremultiply(px[1]) * 255.0).round() as u8);
+            out.push((unpremultiply(px[2]) * 255.0).round() as u8);
+            out.push((a.clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+        out
+    }
+}
+
+fn smoothstep(e0: f64, e1: f64, x: f64) -> f64 {
+    let t = ((x - e0) / (e1 - e0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Length of `[pixel, pixel + 1]` inside `[min, max]`
+fn overlap(pixel: f64, min: f64, max: f64) -> f64 {
+    ((pixel + 1.0).min(max) - pixel.max(min)).max(0.0)
+}
+
+fn rasterize(scene: &PlotScene, options: &ExportOptions) -> Raster {
+    let size = options.logical_size(scene);
+    let pixel_scale = options.pixel_scale();
+    let width = (size.x * pixel_scale).round().max(1.0) as usize;
+    let height = (size.y * pixel_scale).round().max(1.0) as usize;
+
+    // Fit the scene into the output, centered, like SVG's default viewBox
+    let fit = if scene.size.x > 0.0 && scene.size.y > 0.0 {
+        (width as f64 / scene.size.x).min(height as f64 / scene.size.y)
+    } else {
+        pixel_scale
+    };
+    let offset = dvec2(
+        (width as f64 - scene.size.x * fit) / 2.0,
+        (height as f64 - scene.size.y * fit) / 2.0,
+    );
+
+    let mut raster = Raster {
+        width,
+        height,
+        pixels: vec![[0.0; 4]; width * height],
+        scale: fit,
+        offset,
+    };
+    if let Some(bg) = options.background {
+        raster.fill(bg);
+    }
+
+    for primitive in &scene.primitives {
+        match primitive {
+            ScenePrimitive::Line { from, to, width, style, dash_offset, color } => {
+                raster.line(*from, *to, *width, *style, *dash_offset, *color)
+            }
+            ScenePrimitive::Marker { center, radius, style, color } => raster.marker(*center, *radius, *style, *color),
+            ScenePrimitive::Rect { rect, color, gradient } => raster.rect(*rect, *color, *gradient),
+            ScenePrimitive::Wedge { center, radius, inner_ratio, start, end, color, gradient } => {
+                raster.wedge(*center, *radius, *inner_ratio, *start, *end, *color, *gradient)
+            }
+            ScenePrimitive::Triangle { points, color } => raster.polygon(points, *color),
+            ScenePrimitive::Text { pos, text, anchor, font_size, color } => {
+                raster.text(*pos, text, *anchor, *font_size, *color)
+            }
+        }
+    }
+    raster
+}
+
+// =============================================================================
+// PNG encoding
+// =============================================================================
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    /// Write `count` bits of `value`, least significant first
+    fn bits(&mut self, value: u32, count: u32) {
+        self.acc |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Write a Huffman code, most significant bit first
+    fn code(&mut self, code: u32, len: u32) {
+        let mut reversed = 0;
+        for i in 0..len {
+            reversed |= ((code >> i) & 1) << (len - 1 - i);
+        }
+        self.bits(reversed, len);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+        self.bytes
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097,
+    6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+fn fixed_literal(w: &mut BitWriter, symbol: u32) {
+    match symbol {
+        0..=143 => w.code(0x30 + symbol, 8),
+        144..=255 => w.code(0x190 + symbol - 144, 9),
+        256..=279 => w.code(symbol - 256, 7),
+        _ => w.code(0xc0 + symbol - 280, 8),
+    }
+}
+
+/// Deflate with fixed Huffman codes, matching only against the previous
+/// pixel and the previous row. Plot images are mostly flat runs, which this
+/// compresses well without a general-purpose matcher.
+fn deflate(data: &[u8], distances: &[usize]) -> Vec<u8> {
+    let mut w = BitWriter { bytes: Vec::new(), acc: 0, count: 0 };
+    w.bits(1, 1); // final block
+    w.bits(1, 2); // fixed Huffman codes
+    let mut pos = 0;
+    while pos < data.len() {
+        let mut best = (0, 0);
+        for &dist in distances {
+            if dist == 0 || dist > pos || dist > 32768 {
+                continue;
+            }
+            let max = (data.len() - pos).min(258);
+            let mut len = 0;
+            while len < max && data[pos + len] == data[pos + len - dist] {
+                len += 1;
+            }
+            if len > best.0 {
+                best = (len, dist);
+            }
+        }
+        let (len, dist) = best;
+        if len >= 3 {
+            let li = LENGTH_BASE.iter().rposition(|&b| b as usize <= len).unwrap_or(0);
+            fixed_literal(&mut w, 257 + li as u32);
+            w.bits((len - LENGTH_BASE[li] as usize) as u32, LENGTH_EXTRA[li] as u32);
+            let di = DIST_BASE.iter().rposition(|&b| b as usize <= dist).unwrap_or(0);
+            w.code(di as u32, 5);
+            w.bits((dist - DIST_BASE[di] as usize) as u32, DIST_EXTRA[di] as u32);
+            pos += len;
+        } else {
+            fixed_literal(&mut w, data[pos] as u32);
+            pos += 1;
+        }
+    }
+    fixed_literal(&mut w, 256);
+    w.finish()
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// PNG file for 8-bit RGBA pixels
+fn encode_png(width: usize, height: usize, rgba: &[u8]) -> Vec<u8> {
+    let stride = width * 4;
+    let mut scanlines = Vec::with_capacity((stride + 1) * height);
+    for row in rgba.chunks(stride) {
+        scanlines.push(0); // no filter
+        scanlines.extend_from_slice(row);
+    }
+
+    let mut zlib = vec![0x78, 0x01];
+    zlib.extend(deflate(&scanlines, &[4, stride + 1]));
+    zlib.extend_from_slice(&adler32(&scanlines).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    header.extend_from_slice(&[8, 6, 0, 0, 0]); // 8-bit RGBA
+
+    let mut out = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+    png_chunk(&mut out, b"IHDR", &header);
+    png_chunk(&mut out, b"IDAT", &zlib);
+    png_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+// =============================================================================
+// Bitmap font
+// =============================================================================
+
+/// Columns of a 5x7 glyph, bit 0 at the top. Covers ASCII plus the
+/// symbols that tick formatters and math layout produce; anything else is
+/// drawn as '?'.
+fn glyph(ch: char) -> [u8; 5] {
+    let ch = lookalike(ch);
+    let code = ch as u32;
+    if (0x20..0x7f).contains(&code) {
+        return FONT_5X7[(code - 0x20) as usize];
+    }
+    match EXTRA_GLYPHS.binary_search_by_key(&ch, |(c, _)| *c) {
+        Ok(index) => EXTRA_GLYPHS[index].1,
+        Err(_) => FONT_5X7[('?' as u32 - 0x20) as usize],
+    }
+}
+
+/// Characters that share a glyph with another one at this size
+fn lookalike(ch: char) -> char {
+    match ch {
+        '\u{a0}' => ' ',
+        '\u{2212}' | '\u{2013}' | '\u{2014}' => '-',
+        '\u{2018}' | '\u{2019}' => '\'',
+        '\u{201c}' | '\u{201d}' => '"',
+        '\u{2217}' => '*',
+        '\u{223c}' => '~',
+        '\u{2223}' => '|',
+        '\u{27e8}' => '<',
+        '\u{27e9}' => '>',
+        '\u{210f}' => 'h',
+        '\u{2113}' => 'l',
+        '\u{211c}' => 'R',
+        '\u{2111}' => 'I',
+        '\u{2243}' | '\u{2245}' => '\u{2248}',
+        '\u{21a6}' => '\u{2192}',
+        '\u{2211}' => '\u{3a3}',
+        '\u{220f}' => '\u{3a0}',
+        '\u{222e}' => '\u{222b}',
+        // Greek letters drawn like Latin ones
+        '\u{391}' => 'A',
+        '\u{392}' => 'B',
+        '\u{395}' => 'E',
+        '\u{396}' => 'Z',
+        '\u{397}' => 'H',
+        '\u{399}' => 'I',
+        '\u{39a}' => 'K',
+        '\u{39c}' => 'M',
+        '\u{39d}' => 'N',
+        '\u{39f}' => 'O',
+        '\u{3a1}' => 'P',
+        '\u{3a4}' => 'T',
+        '\u{3a5}' => 'Y',
+        '\u{3a7}' => 'X',
+        '\u{3bf}' => 'o',
+        '\u{3bc}' => '\u{b5}',
+        '\u{3d1}' => '\u{3b8}',
+        '\u{3d5}' => '\u{3c6}',
+        '\u{3d6}' => '\u{3c0}',
+        '\u{3f1}' => '\u{3c1}',
+        '\u{3f5}' => '\u{3b5}',
+        _ => ch,
+    }
+}
+
+const FONT_5X7: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // space
+    [0x00, 0x00, 0x5f, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7f, 0x14, 0x7f, 0x14], // #
+    [0x24, 0x2a, 0x7f, 0x2a, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1c, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1c, 0x00], // )
+    [0x14, 0x08, 0x3e, 0x08, 0x14], // *
+    [0x08, 0x08, 0x3e, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3e, 0x51, 0x49, 0x45, 0x3e], // 0
+    [0x00, 0x42, 0x7f, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4b, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7f, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3c, 0x4a, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1e], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3e], // @
+    [0x7e, 0x11, 0x11, 0x11, 0x7e], // A
+    [0x7f, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3e, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7f, 0x41, 0x41, 0x22, 0x1c], // D
+    [0x7f, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7f, 0x09, 0x09, 0x09, 0x01], // F
+    [0x3e, 0x41, 0x49, 0x49, 0x7a], // G
+    [0x7f, 0x08, 0x08, 0x08, 0x7f], // H
+    [0x00, 0x41, 0x7f, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3f, 0x01], // J
+    [0x7f, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7f, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7f, 0x02, 0x0c, 0x02, 0x7f], // M
+    [0x7f, 0x04, 0x08, 0x10, 0x7f], // N
+    [0x3e, 0x41, 0x41, 0x41, 0x3e], // O
+    [0x7f, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3e, 0x41, 0x51, 0x21, 0x5e], // Q
+    [0x7f, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7f, 0x01, 0x01], // T
+    [0x3f, 0x40, 0x40, 0x40, 0x3f], // U
+    [0x1f, 0x20, 0x40, 0x20, 0x1f], // V
+    [0x3f, 0x40, 0x38, 0x40, 0x3f], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7f, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // backslash
+    [0x00, 0x41, 0x41, 0x7f, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7f, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7f], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7e, 0x09, 0x01, 0x02], // f
+    [0x0c, 0x52, 0x52, 0x52, 0x3e], // g
+    [0x7f, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7d, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3d, 0x00], // j
+    [0x7f, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7f, 0x40, 0x00], // l
+    [0x7c, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7c, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7c, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7c], // q
+    [0x7c, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3f, 0x44, 0x40, 0x20], // t
+    [0x3c, 0x40, 0x40, 0x20, 0x7c], // u
+    [0x1c, 0x20, 0x40, 0x20, 0x1c], // v
+    [0x3c, 0x40, 0x30, 0x40, 0x3c], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0c, 0x50, 0x50, 0x50, 0x3c], // y
+    [0x44, 0x64, 0x54, 0x4c, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7f, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
+
+/// Glyphs outside ASCII, sorted by character
+const EXTRA_GLYPHS: [(char, [u8; 5]); 91] = [
+    ('\u{ac}', [0x04, 0x04, 0x04, 0x04, 0x1c]), // ¬
+    ('\u{b0}', [0x06, 0x09, 0x09, 0x06, 0x00]), // °
+    ('\u{b1}', [0x44, 0x44, 0x5f, 0x44, 0x44]), // ±
+    ('\u{b5}', [0x7e, 0x20, 0x00, 0x30, 0x1e]), // µ
+    ('\u{b7}', [0x00, 0x00, 0x08, 0x00, 0x00]), // ·
+    ('\u{d7}', [0x22, 0x14, 0x08, 0x14, 0x22]), // ×
+    ('\u{f7}', [0x08, 0x08, 0x2a, 0x08, 0x08]), // ÷
+    ('\u{393}', [0x7f, 0x01, 0x01, 0x01, 0x01]), // Γ
+    ('\u{394}', [0x70, 0x4c, 0x43, 0x4c, 0x70]), // Δ
+    ('\u{398}', [0x3e, 0x49, 0x49, 0x49, 0x3e]), // Θ
+    ('\u{39b}', [0x70, 0x0c, 0x03, 0x0c, 0x70]), // Λ
+    ('\u{39e}', [0x41, 0x49, 0x49, 0x49, 0x41]), // Ξ
+    ('\u{3a0}', [0x7f, 0x01, 0x01, 0x01, 0x7f]), // Π
+    ('\u{3a3}', [0x63, 0x55, 0x49, 0x41, 0x41]), // Σ
+    ('\u{3a6}', [0x1c, 0x22, 0x7f, 0x22, 0x1c]), // Φ
+    ('\u{3a8}', [0x07, 0x08, 0x7f, 0x08, 0x07]), // Ψ
+    ('\u{3a9}', [0x4e, 0x71, 0x01, 0x71, 0x4e]), // Ω
+    ('\u{3b1}', [0x38, 0x44, 0x44, 0x38, 0x44]), // α
+    ('\u{3b2}', [0x7e, 0x25, 0x25, 0x1a, 0x00]), // β
+    ('\u{3b3}', [0x04, 0x08, 0x70, 0x08, 0x04]), // γ
+    ('\u{3b4}', [0x3a, 0x45, 0x45, 0x39, 0x00]), // δ
+    ('\u{3b5}', [0x28, 0x54, 0x54, 0x44, 0x00]), // ε
+    ('\u{3b6}', [0x11, 0x29, 0x25, 0x23, 0x41]), // ζ
+    ('\u{3b7}', [0x3c, 0x08, 0x04, 0x04, 0x78]), // η
+    ('\u{3b8}', [0x3c, 0x4a, 0x4a, 0x3c, 0x00]), // θ
+    ('\u{3b9}', [0x00, 0x3c, 0x40, 0x40, 0x00]), // ι
+    ('\u{3ba}', [0x7c, 0x10, 0x28, 0x44, 0x00]), // κ
+    ('\u{3bb}', [0x61, 0x12, 0x0c, 0x10, 0x60]), // λ
+    ('\u{3bd}', [0x1c, 0x20, 0x40, 0x20, 0x1c]), // ν
+    ('\u{3be}', [0x0a, 0x15, 0x55, 0x55, 0x21]), // ξ
+    ('\u{3c0}', [0x04, 0x7c, 0x04, 0x3c, 0x44]), // π
+    ('\u{3c1}', [0x78, 0x24, 0x24, 0x24, 0x18]), // ρ
+    ('\u{3c2}', [0x08, 0x14, 0x54, 0x54, 0x20]), // ς
+    ('\u{3c3}', [0x38, 0x44, 0x44, 0x4c, 0x34]), // σ
+    ('\u{3c4}', [0x04, 0x04, 0x3c, 0x44, 0x44]), // τ
+    ('\u{3c5}', [0x3c, 0x40, 0x40, 0x40, 0x3c]), // υ
+    ('\u{3c6}', [0x18, 0x24, 0x7f, 0x24, 0x18]), // φ
+    ('\u{3c7}', [0x44, 0x28, 0x10, 0x28, 0x44]), // χ
+    ('\u{3c8}', [0x0e, 0x10, 0x7f, 0x10, 0x0e]), // ψ
+    ('\u{3c9}', [0x38, 0x44, 0x30, 0x44, 0x38]), // ω
+    ('\u{2016}', [0x00, 0x7f, 0x00, 0x7f, 0x00]), // ‖
+    ('\u{2022}', [0x00, 0x1c, 0x1c, 0x1c, 0x00]), // •
+    ('\u{2026}', [0x40, 0x00, 0x40, 0x00, 0x40]), // …
+    ('\u{2032}', [0x00, 0x04, 0x03, 0x00, 0x00]), // ′
+    ('\u{2039}', [0x00, 0x08, 0x14, 0x22, 0x00]), // ‹
+    ('\u{203a}', [0x00, 0x22, 0x14, 0x08, 0x00]), // ›
+    ('\u{2135}', [0x79, 0x06, 0x0c, 0x10, 0x67]), // ℵ
+    ('\u{2190}', [0x08, 0x1c, 0x2a, 0x08, 0x08]), // ←
+    ('\u{2192}', [0x08, 0x08, 0x2a, 0x1c, 0x08]), // →
+    ('\u{2194}', [0x1c, 0x2a, 0x08, 0x2a, 0x1c]), // ↔
+    ('\u{21d0}', [0x08, 0x14, 0x36, 0x14, 0x14]), // ⇐
+    ('\u{21d2}', [0x14, 0x14, 0x36, 0x14, 0x08]), // ⇒
+    ('\u{21d4}', [0x1c, 0x36, 0x14, 0x36, 0x1c]), // ⇔
+    ('\u{2200}', [0x07, 0x1c, 0x64, 0x1c, 0x07]), // ∀
+    ('\u{2202}', [0x30, 0x49, 0x49, 0x4a, 0x3c]), // ∂
+    ('\u{2203}', [0x41, 0x49, 0x49, 0x49, 0x7f]), // ∃
+    ('\u{2205}', [0x1e, 0x31, 0x2d, 0x23, 0x1e]), // ∅
+    ('\u{2207}', [0x07, 0x19, 0x61, 0x19, 0x07]), // ∇
+    ('\u{2208}', [0x1c, 0x2a, 0x2a, 0x2a, 0x2a]), // ∈
+    ('\u{2209}', [0x1c, 0x6a, 0x3e, 0x2b, 0x2a]), // ∉
+    ('\u{2213}', [0x11, 0x11, 0x7d, 0x11, 0x11]), // ∓
+    ('\u{2218}', [0x00, 0x1c, 0x14, 0x1c, 0x00]), // ∘
+    ('\u{221a}', [0x10, 0x20, 0x70, 0x0c, 0x03]), // √
+    ('\u{221d}', [0x18, 0x24, 0x24, 0x18, 0x24]), // ∝
+    ('\u{221e}', [0x18, 0x24, 0x18, 0x24, 0x18]), // ∞
+    ('\u{2220}', [0x30, 0x28, 0x24, 0x22, 0x21]), // ∠
+    ('\u{2227}', [0x30, 0x0c, 0x02, 0x0c, 0x30]), // ∧
+    ('\u{2228}', [0x06, 0x18, 0x20, 0x18, 0x06]), // ∨
+    ('\u{2229}', [0x3c, 0x02, 0x02, 0x02, 0x3c]), // ∩
+    ('\u{222a}', [0x1e, 0x20, 0x20, 0x20, 0x1e]), // ∪
+    ('\u{222b}', [0x40, 0x40, 0x3e, 0x01, 0x01]), // ∫
+    ('\u{2248}', [0x24, 0x12, 0x24, 0x24, 0x12]), // ≈
+    ('\u{2260}', [0x54, 0x34, 0x1c, 0x16, 0x15]), // ≠
+    ('\u{2261}', [0x2a, 0x2a, 0x2a, 0x2a, 0x2a]), // ≡
+    ('\u{2264}', [0x00, 0x44, 0x4a, 0x51, 0x40]), // ≤
+    ('\u{2265}', [0x40, 0x51, 0x4a, 0x44, 0x00]), // ≥
+    ('\u{226a}', [0x04, 0x0a, 0x15, 0x0a, 0x11]), // ≪
+    ('\u{226b}', [0x11, 0x0a, 0x15, 0x0a, 0x04]), // ≫
+    ('\u{2282}', [0x1c, 0x22, 0x22, 0x22, 0x22]), // ⊂
+    ('\u{2283}', [0x22, 0x22, 0x22, 0x22, 0x1c]), // ⊃
+    ('\u{2286}', [0x4e, 0x51, 0x51, 0x51, 0x51]), // ⊆
+    ('\u{2287}', [0x51, 0x51, 0x51, 0x51, 0x4e]), // ⊇
+    ('\u{2295}', [0x1c, 0x2a, 0x3e, 0x2a, 0x1c]), // ⊕
+    ('\u{2297}', [0x1c, 0x36, 0x2a, 0x36, 0x1c]), // ⊗
+    ('\u{22a5}', [0x40, 0x40, 0x7e, 0x40, 0x40]), // ⊥
+    ('\u{22c6}', [0x04, 0x1c, 0x0e, 0x1c, 0x04]), // ⋆
+    ('\u{22ef}', [0x08, 0x00, 0x08, 0x00, 0x08]), // ⋯
+    ('\u{2308}', [0x00, 0x7f, 0x01, 0x01, 0x00]), // ⌈
+    ('\u{2309}', [0x00, 0x01, 0x01, 0x7f, 0x00]), // ⌉
+    ('\u{230a}', [0x00, 0x7f, 0x40, 0x40, 0x00]), // ⌊
+    ('\u{230b}', [0x00, 0x40, 0x40, 0x7f, 0x00]), // ⌋
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    const RED: Vec4 = Vec4 { x: 1.0, y: 0.0, z: 0.0, w: 1.0 };
+
+    /// Minimal inflate for the fixed-Huffman streams `deflate` writes
+    fn inflate_fixed(data: &[u8]) -> Vec<u8> {
+        let mut bit = 0usize;
+        let mut read = |count: usize| -> u32 {
+            let mut value = 0;
+            for i in 0..count {
+                value |= (((data[bit / 8] >> (bit % 8)) & 1) as u32) << i;
+                bit += 1;
+            }
+            value
+        };
+        assert_eq!(read(1), 1, "final block");
+        assert_eq!(read(2), 1, "fixed Huffman codes");
+
+        let mut out: Vec<u8> = Vec::new();
+        loop {
+            // Huffman codes are packed most significant bit first
+            let mut code = 0;
+            for _ in 0..7 {
+                code = (code << 1) | read(1);
+            }
+            let symbol = if code <= 23 {
+                256 + code
+            } else {
+                code = (code << 1) | read(1);
+                match code {
+                    48..=191 => code - 48,
+                    192..=199 => 280 + code - 192,
+                    _ => 144 + ((code << 1) | read(1)) - 400,
+                }
+            };
+            match symbol {
+                0..=255 => out.push(symbol as u8),
+                256 => return out,
+                _ => {
+                    let li = (symbol - 257) as usize;
+                    let len = LENGTH_BASE[li] as usize + read(LENGTH_EXTRA[li] as usize) as usize;
+                    let mut di = 0;
+                    for _ in 0..5 {
+                        di = (di << 1) | read(1);
+                    }
+                    let dist = DIST_BASE[di as usize] as usize + read(DIST_EXTRA[di as usize] as usize) as usize;
+                    for _ in 0..len {
+                        out.push(out[out.len() - dist]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_svg_elements() {
+        let mut scene = PlotScene::new(dvec2(200.0, 100.0));
+        // Three joined segments become one polyline, the fourth stands alone
+        scene.line(dvec2(0.0, 0.0), dvec2(10.0, 10.0), 2.0, RED);
+        scene.line(dvec2(10.0, 10.0), dvec2(20.0, 0.0), 2.0, RED);
+        scene.line(dvec2(20.0, 0.0), dvec2(30.0, 10.0), 2.0, RED);
+        scene.line_styled(dvec2(0.0, 50.0), dvec2(200.0, 50.0), 1.0, LineStyle::Dashed, RED);
+        scene.marker(dvec2(50.0, 50.0), 4.0, MarkerStyle::Circle, RED);
+        scene.marker(dvec2(60.0, 50.0), 4.0, MarkerStyle::Square, RED);
+        scene.marker(dvec2(70.0, 50.0), 4.0, MarkerStyle::None, RED);
+        scene.rect(Rect { pos: dvec2(5.0, 5.0), size: dvec2(20.0, 10.0) }, vec4(0.0, 0.0, 1.0, 0.5));
+        scene.text(dvec2(100.0, 90.0), "a < b & c", TextAnchor::BottomCenter, 12.0, RED);
+
+        let svg = scene.to_svg(&ExportOptions::default().with_dpi(192.0));
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"400\" height=\"200\" viewBox=\"0 0 200 100\">"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"200\" height=\"100\" fill=\"#ffffff\"/>"));
+        assert!(svg.contains("<polyline points=\"0,0 10,10 20,0 30,10\" fill=\"none\" stroke=\"#ff0000\" stroke-width=\"2\""));
+        assert!(svg.contains("<line x1=\"0\" y1=\"50\" x2=\"200\" y2=\"50\" stroke=\"#ff0000\" stroke-width=\"1\" stroke-dasharray="));
+        assert!(svg.contains("<circle cx=\"50\" cy=\"50\""));
+        assert_eq!(svg.matches("<polygon").count(), 1);
+        assert!(svg.contains("<rect x=\"5\" y=\"5\" width=\"20\" height=\"10\" fill=\"#0000ff\" fill-opacity=\"0.5\"/>"));
+        assert!(svg.contains("text-anchor=\"middle\" dominant-baseline=\"auto\" fill=\"#ff0000\">a &lt; b &amp; c</text>"));
+
+        let transparent = scene.to_svg(&ExportOptions::default().with_background(None));
+        assert!(!transparent.contains("<rect x=\"0\" y=\"0\""));
+    }
+
+    #[test]
+    fn test_svg_wedges() {
+        let mut scene = PlotScene::new(dvec2(100.0, 100.0));
+        let wedge = |inner_ratio: f64, end: f64| ScenePrimitive::Wedge {
+            center: dvec2(50.0, 50.0),
+            radius: 40.0,
+            inner_ratio,
+            start: 0.0,
+            end,
+            color: RED,
+            gradient: None,
+        };
+        scene.push(wedge(0.0, TAU));
+        scene.push(wedge(0.5, TAU));
+        scene.push(wedge(0.0, PI / 2.0));
+        scene.push(wedge(0.0, 1.5 * PI));
+
+        let svg = scene.to_svg(&ExportOptions::default());
+        assert!(svg.contains("<circle cx=\"50\" cy=\"50\" r=\"40\""));
+        // Full ring: outer and inner circle in one even-odd path
+        assert!(svg.contains("<path d=\"M90 50A40 40 0 1 1 10 50A40 40 0 1 1 90 50ZM70 50A20 20 0 1 1 30 50A20 20 0 1 1 70 50Z\""));
+        // Quarter slice from the centre, small arc; three-quarter slice, large arc
+        assert!(svg.contains("<path d=\"M50 50L90 50A40 40 0 0 1 50 90Z\""));
+        assert!(svg.contains("A40 40 0 1 1 50 10Z"));
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert_eq!(adler32(&[]), 1);
+    }
+
+    #[test]
+    fn test_deflate_round_trip() {
+        let mut data = Vec::new();
+        for row in 0..20u8 {
+            data.push(0);
+            for col in 0..40u8 {
+                data.extend_from_slice(&[row, col / 8, 200, 255]);
+            }
+        }
+        let stride = 40 * 4 + 1;
+        assert_eq!(inflate_fixed(&deflate(&data, &[4, stride])), data);
+        assert_eq!(inflate_fixed(&deflate(&[], &[4])), Vec::<u8>::new());
+    }
+
+    /// Check a PNG's signature, chunk CRCs and zlib checksum, returning its
+    /// size and RGBA pixels
+    fn decode_png(png: &[u8]) -> (usize, usize, Vec<u8>) {
+        assert_eq!(&png[..8], &[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a]);
+
+        // Walk the chunks, checking each CRC
+        let mut chunks = Vec::new();
+        let mut pos = 8;
+        while pos < png.len() {
+            let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+            let kind = &png[pos + 4..pos + 8];
+            let data = &png[pos + 8..pos + 8 + len];
+            let crc = u32::from_be_bytes(png[pos + 8 + len..pos + 12 + len].try_into().unwrap());
+            assert_eq!(crc32(&png[pos + 4..pos + 8 + len]), crc);
+            chunks.push((kind.to_vec(), data.to_vec()));
+            pos += 12 + len;
+        }
+        let kinds: Vec<&[u8]> = chunks.iter().map(|(kind, _)| kind.as_slice()).collect();
+        assert_eq!(kinds, [b"IHDR".as_slice(), b"IDAT", b"IEND"]);
+
+        let header = &chunks[0].1;
+        let width = u32::from_be_bytes(header[0..4].try_into().unwrap()) as usize;
+        let height = u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize;
+        assert_eq!(&header[8..], &[8, 6, 0, 0, 0]);
+
+        let zlib = &chunks[1].1;
+        let scanlines = inflate_fixed(&zlib[2..zlib.len() - 4]);
+        assert_eq!(u32::from_be_bytes(zlib[zlib.len() - 4..].try_into().unwrap()), adler32(&scanlines));
+        assert_eq!(scanlines.len(), (width * 4 + 1) * height);
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for row in scanlines.chunks(width * 4 + 1) {
+            assert_eq!(row[0], 0, "rows are written unfiltered");
+            rgba.extend_from_slice(&row[1..]);
+        }
+        (width, height, rgba)
+    }
+
+    #[test]
+    fn test_png_file() {
+        let mut scene = PlotScene::new(dvec2(4.0, 3.0));
+        scene.rect(Rect { pos: dvec2(0.0, 0.0), size: dvec2(4.0, 3.0) }, RED);
+        let png = scene.to_png(&ExportOptions::default().with_background(None));
+
+        let (width, height, rgba) = decode_png(&png);
+        assert_eq!((width, height), (4, 3));
+        assert_eq!(rgba, [255, 0, 0, 255].repeat(12));
+    }
+
+    #[test]
+    fn test_png_text() {
+        // At 10px one font unit is one pixel, and a baseline shift of -2.4
+        // puts the glyph cells on pixel boundaries
+        let mut scene = PlotScene::new(dvec2(17.0, 7.0));
+        scene.push(ScenePrimitive::Text {
+            pos: dvec2(0.0, -2.4),
+            text: "5\u{b5}s".to_string(),
+            anchor: TextAnchor::TopLeft,
+            font_size: 10.0,
+            color: vec4(0.0, 0.0, 0.0, 1.0),
+        });
+        let (width, height, rgba) = decode_png(&scene.to_png(&ExportOptions::default()));
+        assert_eq!((width, height), (17, 7));
+
+        let ink = |x: usize, y: usize| rgba[(y * width + x) * 4] < 128;
+        for (i, ch) in ['5', '\u{b5}', 's'].into_iter().enumerate() {
+            let columns = glyph(ch);
+            for (col, bits) in columns.iter().enumerate() {
+                for row in 0..7 {
+                    assert_eq!(ink(i * 6 + col, row), bits & (1 << row) != 0, "{} at ({}, {})", ch, col, row);
+                }
+            }
+        }
+        // The micro sign has its own glyph rather than the fallback
+        assert_ne!(glyph('\u{b5}'), glyph('?'));
+        assert_eq!(glyph('\u{3bc}'), glyph('\u{b5}'));
+    }
+
+    #[test]
+    fn test_glyph_coverage() {
+        // Everything the formatters and math layout emit has a glyph
+        let formatted = ["\u{b0}", "\u{2026}", "\u{203a}", "\u{2013}", "\u{2212}", "\u{b5}"];
+        let math = crate::math::OPERATORS.iter().chain(crate::math::SYMBOLS).map(|(_, symbol)| *symbol);
+        for text in formatted.into_iter().chain(math) {
+            for ch in text.chars() {
+                assert_ne!(glyph(ch), glyph('?'), "no glyph for {:?} ({:x})", ch, ch as u32);
+            }
+        }
+        assert!(EXTRA_GLYPHS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_png_scales_with_dpi() {
+        let scene = PlotScene::new(dvec2(30.0, 20.0));
+        let raster = rasterize(&scene, &ExportOptions::default().with_dpi(192.0));
+        assert_eq!((raster.width, raster.height), (60, 40));
+        // Opaque white background
+        assert!(raster.to_rgba8().iter().all(|&v| v == 255));
+    }
+
+    #[test]
+    fn test_scene_capture() {
+        let called = Cell::new(false);
+        record_primitive(|| {
+            called.set(true);
+            ScenePrimitive::Triangle { points: [dvec2(0.0, 0.0); 3], color: RED }
+        });
+        assert!(!called.get(), "primitives are only built while capturing");
+
+        let mut capture = SceneCapture { enabled: true, ..Default::default() };
+        capture.begin(Rect { pos: dvec2(10.0, 20.0), size: dvec2(100.0, 50.0) });
+        record_primitive(|| ScenePrimitive::Line {
+            from: dvec2(15.0, 25.0),
+            to: dvec2(60.0, 45.0),
+            width: 1.0,
+            style: LineStyle::Solid,
+            dash_offset: 0.0,
+            color: RED,
+        });
+        capture.end();
+
+        let scene = capture.scene().unwrap();
+        assert_eq!(scene.size, dvec2(100.0, 50.0));
+        match &scene.primitives[..] {
+            [ScenePrimitive::Line { from, to, .. }] => {
+                assert_eq!(*from, dvec2(5.0, 5.0));
+                assert_eq!(*to, dvec2(50.0, 25.0));
+            }
+            other => panic!("unexpected primitives {:?}", other),
+        }
+
+        // Capture stopped: nothing more is recorded
+        record_primitive(|| {
+            called.set(true);
+            ScenePrimitive::Triangle { points: [dvec2(0.0, 0.0); 3], color: RED }
+        });
+        assert!(!called.get());
+    }
+}
@@ -3,10 +3,14 @@
 pub mod plot;
 pub mod elements;
 pub mod text;
+pub mod math;
+pub mod export;
 
 pub use plot::*;
 pub use elements::*;
 pub use text::*;
+pub use math::*;
+pub use export::*;
 
 use makepad_widgets::*;
 
@@ -0,0 +1,714 @@
+// LaTeX math layout for plot text
+//
+// Covers the subset plots need: superscripts and subscripts, fractions,
+// roots, Greek letters, common operators, function names and `\mathrm`.
+// Layout is font-agnostic; callers pass a measure function and draw the
+// resulting glyph runs and rules themselves.
+
+use makepad_widgets::*;
+
+/// Parsed math expression
+#[derive(Clone, Debug, PartialEq)]
+pub enum MathNode {
+    /// Run of upright glyphs
+    Text(String),
+    /// Binary operator or relation, spaced on both sides after an operand
+    Op(String),
+    Row(Vec<MathNode>),
+    Scripts {
+        base: Box<MathNode>,
+        sup: Option<Box<MathNode>>,
+        sub: Option<Box<MathNode>>,
+    },
+    Frac {
+        num: Box<MathNode>,
+        den: Box<MathNode>,
+    },
+    Sqrt {
+        index: Option<Box<MathNode>>,
+        body: Box<MathNode>,
+    },
+    /// Horizontal space in ems, may be negative
+    Space(f64),
+}
+
+/// Glyph run or stroke produced by math layout, relative to the top-left
+/// of the layout box
+#[derive(Clone, Debug, PartialEq)]
+pub enum MathItem {
+    /// Text run; `pos` is the top-left of its line box
+    Glyph { pos: DVec2, text: String, font_size: f64 },
+    /// Fraction bar or radical stroke
+    Rule { from: DVec2, to: DVec2, width: f64 },
+}
+
+/// Positioned math expression
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MathLayout {
+    pub items: Vec<MathItem>,
+    pub size: DVec2,
+    /// Distance from the top of the box to the main baseline
+    pub baseline: f64,
+}
+
+/// True if `text` contains at least one `$...$` math span
+pub fn has_math(text: &str) -> bool {
+    find_dollar(text).is_some_and(|start| find_dollar(&text[start + 1..]).is_some())
+}
+
+/// Parse LaTeX math without `$` delimiters
+pub fn parse_math(latex: &str) -> MathNode {
+    let mut parser = Parser { chars: latex.chars().collect(), pos: 0 };
+    MathNode::Row(parser.parse_row(false))
+}
+
+/// Parse plain text with inline `$...$` math spans, matplotlib style.
+/// `\$` is a literal dollar sign and an unpaired `$` is kept as text.
+pub fn parse_rich(text: &str) -> MathNode {
+    let mut row = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(start) = find_dollar(rest) {
+        let Some(len) = find_dollar(&rest[start + 1..]) else {
+            break;
+        };
+        plain.push_str(&rest[..start].replace("\\$", "$"));
+        if !plain.is_empty() {
+            row.push(MathNode::Text(std::mem::take(&mut plain)));
+        }
+        row.push(parse_math(&rest[start + 1..start + 1 + len]));
+        rest = &rest[start + len + 2..];
+    }
+    plain.push_str(&rest.replace("\\$", "$"));
+    if !plain.is_empty() {
+        row.push(MathNode::Text(plain));
+    }
+    MathNode::Row(row)
+}
+
+/// Byte index of the first `$` not escaped by a backslash
+fn find_dollar(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    (0..bytes.len()).find(|&i| bytes[i] == b'$' && (i == 0 || bytes[i - 1] != b'\\'))
+}
+
+/// Lay out `node` at `font_size`. `measure` returns the width and line
+/// height of a text run at a given font size.
+pub fn layout_math(node: &MathNode, font_size: f64, measure: &mut dyn FnMut(&str, f64) -> DVec2) -> MathLayout {
+    let mut layouter = Layouter { measure };
+    let math_box = layouter.node(node, font_size);
+    let offset = dvec2(0.0, math_box.ascent);
+    MathLayout {
+        items: math_box.items.into_iter().map(|item| item.offset(offset)).collect(),
+        size: dvec2(math_box.width, math_box.ascent + math_box.descent),
+        baseline: math_box.ascent,
+    }
+}
+
+/// Text size estimated from the font size alone, for layout without a font
+pub fn approx_text_size(text: &str, font_size: f64) -> DVec2 {
+    dvec2(text.chars().count() as f64 * font_size * 0.6, font_size * 1.2)
+}
+
+// Fractions of the line height
+const GLYPH_BASELINE: f64 = 0.78;
+const GLYPH_ASCENT: f64 = 0.7;
+const GLYPH_DESCENT: f64 = 0.22;
+const MATH_AXIS: f64 = 0.25;
+
+// Fractions of the font size
+const SCRIPT_SCALE: f64 = 0.7;
+const FRAC_SCALE: f64 = 0.85;
+const INDEX_SCALE: f64 = 0.55;
+const OP_SPACE: f64 = 0.22;
+const FUNCTION_SPACE: f64 = 0.17;
+
+impl MathItem {
+    fn offset(self, offset: DVec2) -> Self {
+        match self {
+            MathItem::Glyph { pos, text, font_size } => MathItem::Glyph { pos: pos + offset, text, font_size },
+            MathItem::Rule { from, to, width } => MathItem::Rule { from: from + offset, to: to + offset, width },
+        }
+    }
+}
+
+/// Laid out node with items relative to its left edge on the baseline
+#[derive(Default)]
+struct MathBox {
+    width: f64,
+    ascent: f64,
+    descent: f64,
+    items: Vec<MathItem>,
+}
+
+impl MathBox {
+    /// Place `other` with its origin at `offset`, growing the extents
+    fn place(&mut self, other: MathBox, offset: DVec2) {
+        self.ascent = self.ascent.max(other.ascent - offset.y);
+        self.descent = self.descent.max(other.descent + offset.y);
+        self.items.extend(other.items.into_iter().map(|item| item.offset(offset)));
+    }
+
+    fn rule(&mut self, from: DVec2, to: DVec2, width: f64) {
+        self.items.push(MathItem::Rule { from, to, width });
+    }
+}
+
+struct Layouter<'a> {
+    measure: &'a mut dyn FnMut(&str, f64) -> DVec2,
+}
+
+impl Layouter<'_> {
+    fn line_height(&mut self, font_size: f64) -> f64 {
+        (self.measure)("x", font_size).y
+    }
+
+    fn node(&mut self, node: &MathNode, font_size: f64) -> MathBox {
+        match node {
+            MathNode::Text(text) | MathNode::Op(text) => self.text(text, font_size),
+            MathNode::Row(children) => self.row(children, font_size),
+            MathNode::Scripts { base, sup, sub } => self.scripts(base, sup.as_deref(), sub.as_deref(), font_size),
+            MathNode::Frac { num, den } => self.frac(num, den, font_size),
+            MathNode::Sqrt { index, body } => self.sqrt(index.as_deref(), body, font_size),
+            MathNode::Space(em) => MathBox { width: em * font_size, ..MathBox::default() },
+        }
+    }
+
+    fn text(&mut self, text: &str, font_size: f64) -> MathBox {
+        if text.is_empty() {
+            return MathBox::default();
+        }
+        let size = (self.measure)(text, font_size);
+        MathBox {
+            width: size.x,
+            ascent: GLYPH_ASCENT * size.y,
+            descent: GLYPH_DESCENT * size.y,
+            items: vec![MathItem::Glyph {
+                pos: dvec2(0.0, -GLYPH_BASELINE * size.y),
+                text: text.to_string(),
+                font_size,
+            }],
+        }
+    }
+
+    fn row(&mut self, children: &[MathNode], font_size: f64) -> MathBox {
+        let mut out = MathBox::default();
+        let mut after_operand = false;
+        for child in children {
+            let pad = match child {
+                MathNode::Op(_) if after_operand => OP_SPACE * font_size,
+                _ => 0.0,
+            };
+            let child_box = self.node(child, font_size);
+            let width = child_box.width;
+            out.place(child_box, dvec2(out.width + pad, 0.0));
+            out.width += width + 2.0 * pad;
+            after_operand = match child {
+                MathNode::Op(_) => false,
+                MathNode::Space(_) => after_operand,
+                MathNode::Text(text) => !text.ends_with(['(', '[', '{', ',']),
+                _ => true,
+            };
+        }
+        out
+    }
+
+    fn scripts(&mut self, base: &MathNode, sup: Option<&MathNode>, sub: Option<&MathNode>, font_size: f64) -> MathBox {
+        let mut out = self.node(base, font_size);
+        let line_height = self.line_height(font_size);
+        let script_size = font_size * SCRIPT_SCALE;
+        let base_width = out.width;
+        let base_ascent = out.ascent;
+        let base_descent = out.descent;
+        let sup = sup.map(|node| self.node(node, script_size));
+        let sub = sub.map(|node| self.node(node, script_size));
+
+        let rise = sup.as_ref().map_or(0.0, |s| (0.35 * line_height).max(base_ascent - 0.55 * s.ascent));
+        let mut fall = sub.as_ref().map_or(0.0, |s| (0.22 * line_height).max(base_descent - 0.3 * s.ascent));
+        if let (Some(sup), Some(sub)) = (&sup, &sub) {
+            // Keep a small gap between the two scripts
+            let gap = (rise - sup.descent) - (sub.ascent - fall);
+            let min_gap = 0.1 * line_height;
+            if gap < min_gap {
+                fall += min_gap - gap;
+            }
+        }
+
+        let mut width = base_width;
+        if let Some(sup) = sup {
+            let kern = 0.05 * font_size;
+            width = width.max(base_width + kern + sup.width);
+            out.place(sup, dvec2(base_width + kern, -rise));
+        }
+        if let Some(sub) = sub {
+            width = width.max(base_width + sub.width);
+            out.place(sub, dvec2(base_width, fall));
+        }
+        out.width = width;
+        out
+    }
+
+    fn frac(&mut self, num: &MathNode, den: &MathNode, font_size: f64) -> MathBox {
+        let line_height = self.line_height(font_size);
+        let child_size = font_size * FRAC_SCALE;
+        let num = self.node(num, child_size);
+        let den = self.node(den, child_size);
+        let axis = MATH_AXIS * line_height;
+        let thickness = (0.05 * font_size).max(1.0);
+        let gap = 0.1 * line_height;
+        let pad = 0.1 * font_size;
+        let width = num.width.max(den.width) + 2.0 * pad;
+
+        let mut out = MathBox { width, ..MathBox::default() };
+        let num_y = -(axis + thickness / 2.0 + gap + num.descent);
+        let den_y = -axis + thickness / 2.0 + gap + den.ascent;
+        let num_x = (width - num.width) / 2.0;
+        let den_x = (width - den.width) / 2.0;
+        out.place(num, dvec2(num_x, num_y));
+        out.place(den, dvec2(den_x, den_y));
+        out.rule(dvec2(0.0, -axis), dvec2(width, -axis), thickness);
+        out
+    }
+
+    fn sqrt(&mut self, index: Option<&MathNode>, body: &MathNode, font_size: f64) -> MathBox {
+        let line_height = self.line_height(font_size);
+        let body = self.node(body, font_size);
+        let thickness = (0.05 * font_size).max(1.0);
+        let gap = 0.12 * line_height;
+        let pad = 0.1 * font_size;
+        let top = -(body.ascent + gap + thickness / 2.0);
+        let bottom = body.descent.max(0.2 * line_height);
+        let height = bottom - top;
+        let sign_width = 0.45 * line_height;
+
+        let mut out = MathBox::default();
+        let mut lead = 0.0;
+        if let Some(index) = index {
+            let index = self.node(index, font_size * INDEX_SCALE);
+            lead = (index.width - 0.5 * sign_width).max(0.0);
+            let index_x = lead + 0.5 * sign_width - index.width;
+            let index_y = bottom - 0.55 * height - index.descent;
+            out.place(index, dvec2(index_x, index_y));
+        }
+
+        let points = [
+            dvec2(lead, bottom - 0.4 * height),
+            dvec2(lead + 0.2 * sign_width, bottom - 0.48 * height),
+            dvec2(lead + 0.5 * sign_width, bottom),
+            dvec2(lead + sign_width, top),
+            dvec2(lead + sign_width + body.width + pad, top),
+        ];
+        let width = lead + sign_width + body.width + pad;
+        out.place(body, dvec2(lead + sign_width + pad / 2.0, 0.0));
+        for (i, pair) in points.windows(2).enumerate() {
+            // Heavier down stroke, like a typeset radical
+            let stroke = if i == 1 { thickness * 1.8 } else { thickness };
+            out.rule(pair[0], pair[1], stroke);
+        }
+        out.width = width;
+        out.ascent = out.ascent.max(-top + thickness / 2.0);
+        out.descent = out.descent.max(bottom + thickness / 2.0);
+        out
+    }
+}
+
+enum Token {
+    Char(char),
+    Command(String),
+    Open,
+    Close,
+    Sup,
+    Sub,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        self.skip_whitespace();
+        let c = *self.chars.get(self.pos)?;
+        self.pos += 1;
+        Some(match c {
+            '{' => Token::Open,
+            '}' => Token::Close,
+            '^' => Token::Sup,
+            '_' => Token::Sub,
+            '\\' => {
+                let start = self.pos;
+                while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_alphabetic()) {
+                    self.pos += 1;
+                }
+                if self.pos == start && self.pos < self.chars.len() {
+                    // Single symbol command such as `\,` or `\{`
+                    self.pos += 1;
+                }
+                Token::Command(self.chars[start..self.pos].iter().collect())
+            }
+            c => Token::Char(c),
+        })
+    }
+
+    fn parse_row(&mut self, in_group: bool) -> Vec<MathNode> {
+        let mut row: Vec<MathNode> = Vec::new();
+        let mut space_pending = false;
+        while let Some(token) = self.next_token() {
+            match token {
+                Token::Close if in_group => break,
+                Token::Close => {}
+                Token::Sup | Token::Sub => {
+                    let script = self.parse_arg();
+                    let base = row.pop().unwrap_or(MathNode::Text(String::new()));
+                    row.push(attach_script(base, matches!(token, Token::Sup), script));
+                }
+                token => {
+                    let is_function = matches!(&token, Token::Command(name) if FUNCTIONS.contains(&name.as_str()));
+                    if let Some(node) = self.parse_atom(token) {
+                        if space_pending && !matches!(node, MathNode::Op(_)) {
+                            row.push(MathNode::Space(FUNCTION_SPACE));
+                        }
+                        row.push(node);
+                    }
+                    space_pending = is_function;
+                }
+            }
+        }
+        merge_text(row)
+    }
+
+    /// Argument of a command or script: a braced group or a single atom
+    fn parse_arg(&mut self) -> MathNode {
+        match self.next_token() {
+            Some(Token::Sup | Token::Sub | Token::Close) | None => MathNode::Text(String::new()),
+            Some(token) => self.parse_atom(token).unwrap_or(MathNode::Text(String::new())),
+        }
+    }
+
+    fn parse_atom(&mut self, token: Token) -> Option<MathNode> {
+        match token {
+            Token::Open => Some(MathNode::Row(self.parse_row(true))),
+            Token::Char(c) => Some(char_node(c)),
+            Token::Command(name) => self.command(&name),
+            Token::Close | Token::Sup | Token::Sub => None,
+        }
+    }
+
+    /// Raw text of a braced group, keeping spaces, for `\text{...}`
+    fn raw_group(&mut self) -> String {
+        self.skip_whitespace();
+        if self.chars.get(self.pos) != Some(&'{') {
+            return String::new();
+        }
+        self.pos += 1;
+        let mut depth = 0;
+        let mut text = String::new();
+        while let Some(&c) = self.chars.get(self.pos) {
+            self.pos += 1;
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => break,
+                '}' => depth -= 1,
+                '\\' => {
+                    if let Some(&escaped) = self.chars.get(self.pos) {
+                        self.pos += 1;
+                        text.push(escaped);
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            text.push(c);
+        }
+        text
+    }
+
+    fn command(&mut self, name: &str) -> Option<MathNode> {
+        let node = match name {
+            "frac" | "dfrac" | "tfrac" => {
+                let num = self.parse_arg();
+                let den = self.parse_arg();
+                MathNode::Frac { num: Box::new(num), den: Box::new(den) }
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                let index = if self.chars.get(self.pos) == Some(&'[') {
+                    self.pos += 1;
+                    let end = self.chars[self.pos..].iter().position(|&c| c == ']').map_or(self.chars.len(), |i| self.pos + i);
+                    let inner: String = self.chars[self.pos..end].iter().collect();
+                    self.pos = (end + 1).min(self.chars.len());
+                    Some(Box::new(parse_math(&inner)))
+                } else {
+                    None
+                };
+                MathNode::Sqrt { index, body: Box::new(self.parse_arg()) }
+            }
+            // No italic or bold faces, so font switches only group their argument
+            "mathrm" | "mathbf" | "mathit" | "mathsf" | "mathcal" | "boldsymbol" | "operatorname" => self.parse_arg(),
+            "text" | "textrm" | "textbf" | "textit" | "mbox" => MathNode::Text(self.raw_group()),
+            // Delimiters are drawn at text size
+            "left" | "right" | "big" | "Big" | "bigl" | "bigr" | "Bigl" | "Bigr" => {
+                return match self.next_token()? {
+                    Token::Char('.') => None,
+                    Token::Char(c) => Some(MathNode::Text(c.to_string())),
+                    Token::Command(name) => self.command(&name),
+                    _ => None,
+                };
+            }
+            "," => MathNode::Space(0.17),
+            ":" | ">" => MathNode::Space(0.22),
+            ";" => MathNode::Space(0.28),
+            "!" => MathNode::Space(-0.17),
+            " " => MathNode::Space(0.33),
+            "quad" => MathNode::Space(1.0),
+            "qquad" => MathNode::Space(2.0),
+            "{" | "}" | "$" | "%" | "&" | "#" | "_" => MathNode::Text(name.to_string()),
+            "\\" => return None,
+            _ => {
+                if let Some(&(_, symbol)) = OPERATORS.iter().find(|(n, _)| *n == name) {
+                    MathNode::Op(symbol.to_string())
+                } else if let Some(&(_, symbol)) = SYMBOLS.iter().find(|(n, _)| *n == name) {
+                    MathNode::Text(symbol.to_string())
+                } else if FUNCTIONS.contains(&name) {
+                    MathNode::Text(name.to_string())
+                } else {
+                    // Unknown commands stay visible rather than vanishing
+                    MathNode::Text(format!("\\{}", name))
+                }
+            }
+        };
+        Some(node)
+    }
+}
+
+fn char_node(c: char) -> MathNode {
+    match c {
+        '+' | '-' | '=' | '<' | '>' | '*' => MathNode::Op(c.to_string()),
+        '\'' => MathNode::Text("\u{2032}".to_string()),
+        c => MathNode::Text(c.to_string()),
+    }
+}
+
+fn attach_script(base: MathNode, is_sup: bool, script: MathNode) -> MathNode {
+    let script = Some(Box::new(script));
+    match base {
+        MathNode::Scripts { base, sup: None, sub } if is_sup => MathNode::Scripts { base, sup: script, sub },
+        MathNode::Scripts { base, sup, sub: None } if !is_sup => MathNode::Scripts { base, sup, sub: script },
+        base if is_sup => MathNode::Scripts { base: Box::new(base), sup: script, sub: None },
+        base => MathNode::Scripts { base: Box::new(base), sup: None, sub: script },
+    }
+}
+
+/// Join neighbouring text atoms so each run is measured and drawn once
+fn merge_text(row: Vec<MathNode>) -> Vec<MathNode> {
+    let mut out: Vec<MathNode> = Vec::with_capacity(row.len());
+    for node in row {
+        match (out.last_mut(), node) {
+            (Some(MathNode::Text(prev)), MathNode::Text(text)) => prev.push_str(&text),
+            (_, node) => out.push(node),
+        }
+    }
+    out
+}
+
+const FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan",
+    "sinh", "cosh", "tanh", "log", "ln", "lg", "exp", "min", "max", "sup",
+    "inf", "lim", "det", "dim", "gcd", "arg", "deg", "ker", "Pr", "mod",
+];
+
+pub(crate) const OPERATORS: &[(&str, &str)] = &[
+    ("pm", "\u{b1}"), ("mp", "\u{2213}"), ("times", "\u{d7}"), ("div", "\u{f7}"),
+    ("cdot", "\u{b7}"), ("ast", "\u{2217}"), ("star", "\u{22c6}"), ("circ", "\u{2218}"),
+    ("bullet", "\u{2022}"), ("cup", "\u{222a}"), ("cap", "\u{2229}"), ("wedge", "\u{2227}"),
+    ("vee", "\u{2228}"), ("oplus", "\u{2295}"), ("otimes", "\u{2297}"),
+    ("leq", "\u{2264}"), ("le", "\u{2264}"), ("geq", "\u{2265}"), ("ge", "\u{2265}"),
+    ("neq", "\u{2260}"), ("ne", "\u{2260}"), ("approx", "\u{2248}"), ("equiv", "\u{2261}"),
+    ("sim", "\u{223c}"), ("simeq", "\u{2243}"), ("cong", "\u{2245}"), ("propto", "\u{221d}"),
+    ("ll", "\u{226a}"), ("gg", "\u{226b}"), ("in", "\u{2208}"), ("notin", "\u{2209}"),
+    ("subset", "\u{2282}"), ("supset", "\u{2283}"), ("subseteq", "\u{2286}"), ("supseteq", "\u{2287}"),
+    ("to", "\u{2192}"), ("rightarrow", "\u{2192}"), ("leftarrow", "\u{2190}"), ("gets", "\u{2190}"),
+    ("leftrightarrow", "\u{2194}"), ("Rightarrow", "\u{21d2}"), ("Leftarrow", "\u{21d0}"),
+    ("Leftrightarrow", "\u{21d4}"), ("iff", "\u{21d4}"), ("mapsto", "\u{21a6}"),
+    ("perp", "\u{22a5}"), ("mid", "\u{2223}"),
+];
+
+pub(crate) const SYMBOLS: &[(&str, &str)] = &[
+    ("alpha", "\u{3b1}"), ("beta", "\u{3b2}"), ("gamma", "\u{3b3}"), ("delta", "\u{3b4}"),
+    ("epsilon", "\u{3b5}"), ("varepsilon", "\u{3b5}"), ("zeta", "\u{3b6}"), ("eta", "\u{3b7}"),
+    ("theta", "\u{3b8}"), ("vartheta", "\u{3d1}"), ("iota", "\u{3b9}"), ("kappa", "\u{3ba}"),
+    ("lambda", "\u{3bb}"), ("mu", "\u{3bc}"), ("nu", "\u{3bd}"), ("xi", "\u{3be}"),
+    ("pi", "\u{3c0}"), ("varpi", "\u{3d6}"), ("rho", "\u{3c1}"), ("varrho", "\u{3f1}"),
+    ("sigma", "\u{3c3}"), ("varsigma", "\u{3c2}"), ("tau", "\u{3c4}"), ("upsilon", "\u{3c5}"),
+    ("phi", "\u{3c6}"), ("varphi", "\u{3c6}"), ("chi", "\u{3c7}"), ("psi", "\u{3c8}"),
+    ("omega", "\u{3c9}"),
+    ("Gamma", "\u{393}"), ("Delta", "\u{394}"), ("Theta", "\u{398}"), ("Lambda", "\u{39b}"),
+    ("Xi", "\u{39e}"), ("Pi", "\u{3a0}"), ("Sigma", "\u{3a3}"), ("Upsilon", "\u{3a5}"),
+    ("Phi", "\u{3a6}"), ("Psi", "\u{3a8}"), ("Omega", "\u{3a9}"),
+    ("infty", "\u{221e}"), ("partial", "\u{2202}"), ("nabla", "\u{2207}"), ("sum", "\u{2211}"),
+    ("prod", "\u{220f}"), ("int", "\u{222b}"), ("oint", "\u{222e}"), ("forall", "\u{2200}"),
+    ("exists", "\u{2203}"), ("emptyset", "\u{2205}"), ("hbar", "\u{210f}"), ("ell", "\u{2113}"),
+    ("Re", "\u{211c}"), ("Im", "\u{2111}"), ("aleph", "\u{2135}"), ("angle", "\u{2220}"),
+    ("degree", "\u{b0}"), ("prime", "\u{2032}"), ("ldots", "\u{2026}"), ("dots", "\u{2026}"),
+    ("cdots", "\u{22ef}"), ("langle", "\u{27e8}"), ("rangle", "\u{27e9}"), ("lfloor", "\u{230a}"),
+    ("rfloor", "\u{230b}"), ("lceil", "\u{2308}"), ("rceil", "\u{2309}"), ("vert", "|"),
+    ("|", "\u{2016}"), ("neg", "\u{ac}"), ("surd", "\u{221a}"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> MathNode {
+        MathNode::Text(s.to_string())
+    }
+
+    fn layout(latex: &str) -> MathLayout {
+        layout_math(&parse_math(latex), 10.0, &mut approx_text_size)
+    }
+
+    fn glyph(layout: &MathLayout, wanted: &str) -> (DVec2, f64) {
+        layout
+            .items
+            .iter()
+            .find_map(|item| match item {
+                MathItem::Glyph { pos, text, font_size } if text == wanted => Some((*pos, *font_size)),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn rules(layout: &MathLayout) -> Vec<(DVec2, DVec2)> {
+        layout
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                MathItem::Rule { from, to, .. } => Some((*from, *to)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_has_math() {
+        assert!(has_math("speed $v$"));
+        assert!(!has_math("costs \\$5"));
+        assert!(!has_math("a lone $ sign"));
+        assert!(!has_math("plain"));
+    }
+
+    #[test]
+    fn test_parse_rich() {
+        assert_eq!(
+            parse_rich("Speed $v^2$ (m/s)"),
+            MathNode::Row(vec![
+                text("Speed "),
+                MathNode::Row(vec![MathNode::Scripts { base: Box::new(text("v")), sup: Some(Box::new(text("2"))), sub: None }]),
+                text(" (m/s)"),
+            ])
+        );
+        assert_eq!(parse_rich("\\$5 or $5"), MathNode::Row(vec![text("$5 or $5")]));
+    }
+
+    #[test]
+    fn test_parse_scripts() {
+        let both = MathNode::Row(vec![MathNode::Scripts {
+            base: Box::new(text("x")),
+            sup: Some(Box::new(text("2"))),
+            sub: Some(Box::new(text("i"))),
+        }]);
+        assert_eq!(parse_math("x_i^2"), both);
+        assert_eq!(parse_math("x^2_i"), both);
+        assert_eq!(
+            parse_math("e^{-t}"),
+            MathNode::Row(vec![MathNode::Scripts {
+                base: Box::new(text("e")),
+                sup: Some(Box::new(MathNode::Row(vec![MathNode::Op("-".to_string()), text("t")]))),
+                sub: None,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse_math("abc"), MathNode::Row(vec![text("abc")]));
+        assert_eq!(
+            parse_math("\\alpha \\leq \\beta"),
+            MathNode::Row(vec![text("\u{3b1}"), MathNode::Op("\u{2264}".to_string()), text("\u{3b2}")])
+        );
+        assert_eq!(parse_math("\\sin x"), MathNode::Row(vec![text("sin"), MathNode::Space(FUNCTION_SPACE), text("x")]));
+        assert_eq!(parse_math("\\text{m s}"), MathNode::Row(vec![text("m s")]));
+        assert_eq!(parse_math("\\mathrm{d}x"), MathNode::Row(vec![MathNode::Row(vec![text("d")]), text("x")]));
+        assert_eq!(parse_math("\\foo"), MathNode::Row(vec![text("\\foo")]));
+        assert_eq!(
+            parse_math("\\frac{a}{b}"),
+            MathNode::Row(vec![MathNode::Frac {
+                num: Box::new(MathNode::Row(vec![text("a")])),
+                den: Box::new(MathNode::Row(vec![text("b")])),
+            }])
+        );
+        assert_eq!(
+            parse_math("\\sqrt[3]{x}"),
+            MathNode::Row(vec![MathNode::Sqrt {
+                index: Some(Box::new(MathNode::Row(vec![text("3")]))),
+                body: Box::new(MathNode::Row(vec![text("x")])),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_layout_text_and_operators() {
+        let plain = layout("ab");
+        assert_eq!(plain.size, dvec2(12.0, 12.0 * (GLYPH_ASCENT + GLYPH_DESCENT)));
+        assert_eq!(plain.baseline, 12.0 * GLYPH_ASCENT);
+
+        // Binary operators get space on both sides, but not a leading sign
+        let sum = layout("a+b");
+        assert!((sum.size.x - (18.0 + 2.0 * OP_SPACE * 10.0)).abs() < 1e-9);
+        let negative = layout("-a");
+        assert!((negative.size.x - 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_layout_scripts() {
+        let layout = layout("x^2_i");
+        let (base, base_size) = glyph(&layout, "x");
+        let (sup, sup_size) = glyph(&layout, "2");
+        let (sub, _) = glyph(&layout, "i");
+        assert_eq!(base_size, 10.0);
+        assert_eq!(sup_size, 10.0 * SCRIPT_SCALE);
+        assert!(sup.y < base.y && sub.y > sup.y);
+        assert!(sup.x > base.x && sub.x > base.x);
+    }
+
+    #[test]
+    fn test_layout_frac() {
+        let layout = layout("\\frac{a}{b}");
+        let (num, _) = glyph(&layout, "a");
+        let (den, _) = glyph(&layout, "b");
+        let rules = rules(&layout);
+        assert_eq!(rules.len(), 1);
+        let bar_y = rules[0].0.y;
+        assert!(num.y < bar_y && bar_y < den.y);
+        assert!(layout.size.y > 12.0);
+        assert!(bar_y > 0.0 && bar_y < layout.size.y);
+    }
+
+    #[test]
+    fn test_layout_sqrt() {
+        let plain = layout("x");
+        let root = layout("\\sqrt{x}");
+        assert_eq!(rules(&root).len(), 4);
+        assert!(root.size.x > plain.size.x);
+        assert!(root.size.y > plain.size.y);
+        let indexed = layout("\\sqrt[3]{x}");
+        assert_eq!(glyph(&indexed, "3").1, 10.0 * INDEX_SCALE);
+    }
+}
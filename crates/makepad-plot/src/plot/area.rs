@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -27,6 +28,8 @@ pub struct AreaSeries {
     pub x: Vec<f64>,
     pub y: Vec<f64>,
     pub color: Vec4,
+    /// Streaming ring of the newest points, used instead of `x`/`y` when set
+    pub stream: Option<StreamBuffer>,
 }
 
 impl AreaSeries {
@@ -36,15 +39,55 @@ impl AreaSeries {
             x: Vec::new(),
             y: Vec::new(),
             color: get_color(0),
+            stream: None,
         }
     }
 
     pub fn with_data(mut self, x: Vec<f64>, y: Vec<f64>) -> Self {
         self.x = x;
         self.y = y;
+        self.stream = None;
         self
     }
 
+    /// Keep only the newest `capacity` points, starting from the current data
+    pub fn with_stream(mut self, capacity: usize) -> Self {
+        let points = self.x.iter().copied().zip(self.y.iter().copied());
+        self.stream = Some(StreamBuffer::from_points(capacity, points));
+        self.x = Vec::new();
+        self.y = Vec::new();
+        self
+    }
+
+    /// Append points, streaming `DEFAULT_STREAM_CAPACITY` points if the
+    /// series is not a stream yet
+    pub fn push_points(&mut self, points: &[(f64, f64)]) {
+        if self.stream.is_none() {
+            let x = std::mem::take(&mut self.x);
+            let y = std::mem::take(&mut self.y);
+            self.stream = Some(StreamBuffer::from_points(DEFAULT_STREAM_CAPACITY, x.into_iter().zip(y)));
+        }
+        if let Some(stream) = &mut self.stream {
+            stream.extend(points);
+        }
+    }
+
+    /// X values, from the stream if there is one
+    pub fn xs(&self) -> &[f64] {
+        match &self.stream {
+            Some(stream) => stream.x(),
+            None => &self.x,
+        }
+    }
+
+    /// Y values, from the stream if there is one
+    pub fn ys(&self) -> &[f64] {
+        match &self.stream {
+            Some(stream) => stream.y(),
+            None => &self.y,
+        }
+    }
+
     pub fn with_color(mut self, color: Vec4) -> Self {
         self.color = color;
         self
@@ -63,6 +106,11 @@ pub struct AreaChart {
     #[rust] y_label: String,
     #[rust] stacked: bool,
     #[rust] show_grid: bool,
+    #[rust] stream: StreamAxes,
+    #[rust(Axes::with_margins(60.0, 40.0, 20.0, 50.0))] axes: Axes,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl AreaChart {
@@ -72,6 +120,7 @@ impl AreaChart {
 
     pub fn add_series(&mut self, series: AreaSeries) {
         self.series.push(series);
+        self.follow_stream();
     }
 
     pub fn set_x_label(&mut self, label: impl Into<String>) {
@@ -86,6 +135,78 @@ impl AreaChart {
         self.stacked = stacked;
     }
 
+    /// Append points to series `series`, keeping its newest points in a ring
+    /// buffer, and scroll the axes to follow them. Call `redraw_if_dirty`
+    /// once per frame to draw the new batch.
+    pub fn push_points(&mut self, series: usize, points: &[(f64, f64)]) {
+        let Some(target) = self.series.get_mut(series) else { return };
+        target.push_points(points);
+        self.follow_stream();
+        self.stream.mark_dirty();
+    }
+
+    pub fn set_stream_window(&mut self, window: StreamWindow) {
+        self.stream.window = window;
+        self.follow_stream();
+    }
+
+    pub fn set_stream_y_scale(&mut self, y_scale: StreamYScale) {
+        self.stream.y_scale = y_scale;
+        self.follow_stream();
+    }
+
+    /// Redraw only if points were pushed since the last call
+    pub fn redraw_if_dirty(&mut self, cx: &mut Cx) -> bool {
+        let dirty = self.stream.take_dirty();
+        if dirty {
+            self.redraw(cx);
+        }
+        dirty
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.series.iter().any(|s| s.stream.is_some())
+    }
+
+    /// Move the axes to the stream window and the (stacked) data visible in it
+    fn follow_stream(&mut self) {
+        if !self.is_streaming() {
+            return;
+        }
+        let mut data = (f64::MAX, f64::MIN);
+        for xs in self.series.iter().map(|s| s.xs()).filter(|xs| !xs.is_empty()) {
+            data.0 = data.0.min(xs[0]);
+            data.1 = data.1.max(xs[xs.len() - 1]);
+        }
+        if data.0 > data.1 {
+            return;
+        }
+        self.axes.x.range = self.stream.x_range(self.axes.x.range, data);
+
+        // Areas fill down to zero, so zero stays in view
+        let (x_lo, x_hi) = self.axes.x.range;
+        let mut visible = (0.0f64, 0.0f64);
+        if self.stacked {
+            let xs = self.series[0].xs();
+            for (i, &x) in xs.iter().enumerate() {
+                if x >= x_lo && x <= x_hi {
+                    let sum: f64 = self.series.iter().filter_map(|s| s.ys().get(i)).sum();
+                    visible.1 = visible.1.max(sum);
+                }
+            }
+        } else {
+            for s in &self.series {
+                for (&x, &y) in s.xs().iter().zip(s.ys()) {
+                    if x >= x_lo && x <= x_hi && y.is_finite() {
+                        visible.0 = visible.0.min(y);
+                        visible.1 = visible.1.max(y);
+                    }
+                }
+            }
+        }
+        self.axes.y.range = self.stream.y_range(self.axes.y.range, visible);
+    }
+
     pub fn set_show_grid(&mut self, show: bool) {
         self.show_grid = show;
     }
@@ -102,12 +223,12 @@ impl AreaChart {
 
         if self.stacked {
             // For stacked, compute cumulative max
-            if !self.series.is_empty() && !self.series[0].x.is_empty() {
-                let n = self.series[0].x.len();
+            if !self.series.is_empty() && !self.series[0].xs().is_empty() {
+                let n = self.series[0].xs().len();
                 for i in 0..n {
                     let mut sum = 0.0;
                     for s in &self.series {
-                        if i < s.y.len() { sum += s.y[i]; }
+                        if i < s.ys().len() { sum += s.ys()[i]; }
                     }
                     if sum > y_max { y_max = sum; }
                 }
@@ -115,12 +236,12 @@ impl AreaChart {
         }
 
         for s in &self.series {
-            for &x in &s.x {
+            for &x in s.xs() {
                 if x < x_min { x_min = x; }
                 if x > x_max { x_max = x; }
             }
             if !self.stacked {
-                for &y in &s.y {
+                for &y in s.ys() {
                     if y > y_max { y_max = y; }
                 }
             }
@@ -137,58 +258,40 @@ impl AreaChart {
 impl Widget for AreaChart {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.series.is_empty() {
-            let padding_left = 60.0;
-            let padding_right = 20.0;
-            let padding_top = 40.0;
-            let padding_bottom = 50.0;
-
-            let plot_left = rect.pos.x + padding_left;
-            let plot_top = rect.pos.y + padding_top;
-            let plot_right = rect.pos.x + rect.size.x - padding_right;
-            let plot_bottom = rect.pos.y + rect.size.y - padding_bottom;
-            let plot_width = plot_right - plot_left;
-            let plot_height = plot_bottom - plot_top;
-
-            let (x_min, x_max, y_min, y_max) = self.get_bounds();
+            self.axes.set_show_grid(self.show_grid);
+            self.axes.update_plot_area(rect);
+            let plot_left = self.axes.plot_area.left;
+            let plot_bottom = self.axes.plot_area.bottom;
+            let plot_width = self.axes.plot_area.width();
+            let plot_height = self.axes.plot_area.height();
+
+            // Streams keep the ranges chosen on each push
+            let (x_min, x_max, y_min, y_max) = if self.is_streaming() {
+                let (x, y) = (self.axes.x.range, self.axes.y.range);
+                (x.0, x.1, y.0, y.1)
+            } else {
+                self.get_bounds()
+            };
             let x_range = (x_max - x_min).max(0.001);
             let y_range = (y_max - y_min).max(0.001);
+            self.axes.x.range = (x_min, x_min + x_range);
+            self.axes.y.range = (y_min, y_min + y_range);
+            let plot_right = self.axes.plot_area.right;
+            let baseline = 0.0f64.clamp(y_min, y_min + y_range);
 
-            // Draw grid
-            if self.show_grid {
-                self.draw_line.color = vec4(0.9, 0.9, 0.9, 1.0);
-                for i in 0..=5 {
-                    let t = i as f64 / 5.0;
-                    let x = plot_left + t * plot_width;
-                    let y = plot_top + t * plot_height;
-                    self.draw_line.draw_line(cx, dvec2(x, plot_top), dvec2(x, plot_bottom), 1.0);
-                    self.draw_line.draw_line(cx, dvec2(plot_left, y), dvec2(plot_right, y), 1.0);
-                }
-            }
-
-            // Draw axes
-            self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-            self.draw_line.draw_line(cx, dvec2(plot_left, plot_bottom), dvec2(plot_right, plot_bottom), 1.5);
-            self.draw_line.draw_line(cx, dvec2(plot_left, plot_top), dvec2(plot_left, plot_bottom), 1.5);
-
-            // Draw axis labels
-            self.label.draw_text.color = vec4(0.3, 0.3, 0.3, 1.0);
-            for i in 0..=5 {
-                let t = i as f64 / 5.0;
-                let x_val = x_min + t * x_range;
-                let y_val = y_min + (1.0 - t) * y_range;
-                let x = plot_left + t * plot_width;
-                let y = plot_top + t * plot_height;
-                self.label.draw_at(cx, dvec2(x, plot_bottom + 15.0), &format!("{:.0}", x_val), TextAnchor::TopCenter);
-                self.label.draw_at(cx, dvec2(plot_left - 10.0, y), &format!("{:.0}", y_val), TextAnchor::MiddleRight);
-            }
+            self.axes.draw_grid(cx, &mut self.draw_line);
+            self.axes.draw_axes(cx, &mut self.draw_line);
+            self.axes.draw_tick_labels(cx, &mut self.label);
 
             // Draw areas (from back to front for stacked)
-            let mut cumulative: Vec<f64> = vec![0.0; self.series.first().map(|s| s.x.len()).unwrap_or(0)];
+            let mut cumulative: Vec<f64> = vec![0.0; self.series.first().map(|s| s.xs().len()).unwrap_or(0)];
 
             for (_idx, series) in self.series.iter().enumerate() {
-                if series.x.len() < 2 { continue; }
+                let (xs, ys) = (series.xs(), series.ys());
+                if xs.len() < 2 { continue; }
 
                 // Create gradient colors: lighter at top, base color at bottom
                 let top_color = vec4(
@@ -205,15 +308,15 @@ impl Widget for AreaChart {
                 );
 
                 // Draw filled area using vertical strips with gradient
-                let n = series.x.len();
+                let n = xs.len();
                 let subdivisions = 4; // Subdivide each segment for smoother curves
                 for i in 0..n.saturating_sub(1) {
-                    let x1 = series.x[i];
-                    let x2 = series.x[i + 1];
-                    let y1 = if self.stacked { series.y[i] + cumulative[i] } else { series.y[i] };
-                    let y2 = if self.stacked { series.y[i + 1] + cumulative.get(i + 1).copied().unwrap_or(0.0) } else { series.y[i + 1] };
-                    let base1 = if self.stacked { cumulative[i] } else { y_min };
-                    let base2 = if self.stacked { cumulative.get(i + 1).copied().unwrap_or(0.0) } else { y_min };
+                    let x1 = xs[i];
+                    let x2 = xs[i + 1];
+                    let y1 = if self.stacked { ys[i] + cumulative[i] } else { ys[i] };
+                    let y2 = if self.stacked { ys[i + 1] + cumulative.get(i + 1).copied().unwrap_or(0.0) } else { ys[i + 1] };
+                    let base1 = if self.stacked { cumulative[i] } else { baseline };
+                    let base2 = if self.stacked { cumulative.get(i + 1).copied().unwrap_or(0.0) } else { baseline };
 
                     for s in 0..subdivisions {
                         let t1 = s as f64 / subdivisions as f64;
@@ -233,34 +336,40 @@ impl Widget for AreaChart {
                         let pby1 = plot_bottom - ((sb1 - y_min) / y_range) * plot_height;
                         let pby2 = plot_bottom - ((sb2 - y_min) / y_range) * plot_height;
 
-                        // Draw as filled rectangle with gradient
-                        let strip_width = (px2 - px1).max(1.0);
+                        // Draw as filled rectangle with gradient, cut at the plot edges
+                        let strip_left = px1.max(plot_left);
+                        let strip_right = (px1 + (px2 - px1).max(1.0)).min(plot_right);
+                        if strip_right <= strip_left {
+                            continue;
+                        }
                         let top_y = py1.min(py2);
                         let bottom_y = pby1.max(pby2);
 
-                        self.draw_fill.draw_fill_strip_gradient(cx, px1, strip_width, top_y, bottom_y, bottom_color, top_color);
+                        self.draw_fill.draw_fill_strip_gradient(cx, strip_left, strip_right - strip_left, top_y, bottom_y, bottom_color, top_color);
                     }
                 }
 
                 // Draw top line with solid color
                 self.draw_line.color = series.color;
                 for i in 0..n.saturating_sub(1) {
-                    let x1 = series.x[i];
-                    let x2 = series.x[i + 1];
-                    let y1 = if self.stacked { series.y[i] + cumulative[i] } else { series.y[i] };
-                    let y2 = if self.stacked { series.y[i + 1] + cumulative.get(i + 1).copied().unwrap_or(0.0) } else { series.y[i + 1] };
+                    let x1 = xs[i];
+                    let x2 = xs[i + 1];
+                    let y1 = if self.stacked { ys[i] + cumulative[i] } else { ys[i] };
+                    let y2 = if self.stacked { ys[i + 1] + cumulative.get(i + 1).copied().unwrap_or(0.0) } else { ys[i + 1] };
 
                     let px1 = plot_left + ((x1 - x_min) / x_range) * plot_width;
                     let px2 = plot_left + ((x2 - x_min) / x_range) * plot_width;
                     let py1 = plot_bottom - ((y1 - y_min) / y_range) * plot_height;
                     let py2 = plot_bottom - ((y2 - y_min) / y_range) * plot_height;
-                    self.draw_line.draw_line(cx, dvec2(px1, py1), dvec2(px2, py2), 2.0);
+                    if let Some((p1, p2)) = clip_segment_x(dvec2(px1, py1), dvec2(px2, py2), plot_left, plot_right) {
+                        self.draw_line.draw_line(cx, p1, p2, 2.0);
+                    }
                 }
 
                 // Update cumulative for stacked
                 if self.stacked {
-                    for i in 0..cumulative.len().min(series.y.len()) {
-                        cumulative[i] += series.y[i];
+                    for i in 0..cumulative.len().min(ys.len()) {
+                        cumulative[i] += ys[i];
                     }
                 }
             }
@@ -272,6 +381,7 @@ impl Widget for AreaChart {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -280,6 +390,16 @@ impl Widget for AreaChart {
     }
 }
 
+impl PlotExport for AreaChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl AreaChart {
     pub fn redraw(&mut self, cx: &mut Cx) {
         self.view.redraw(cx);
@@ -302,6 +422,19 @@ impl AreaChartRef {
     pub fn set_stacked(&self, stacked: bool) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_stacked(stacked); }
     }
+    pub fn push_points(&self, series: usize, points: &[(f64, f64)]) {
+        if let Some(mut inner) = self.borrow_mut() { inner.push_points(series, points); }
+    }
+    pub fn set_stream_window(&self, window: StreamWindow) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_stream_window(window); }
+    }
+    pub fn set_stream_y_scale(&self, y_scale: StreamYScale) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_stream_y_scale(y_scale); }
+    }
+    pub fn redraw_if_dirty(&self, cx: &mut Cx) -> bool {
+        if let Some(mut inner) = self.borrow_mut() { return inner.redraw_if_dirty(cx); }
+        false
+    }
     pub fn set_show_grid(&self, show: bool) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_show_grid(show); }
     }
@@ -324,6 +457,8 @@ pub struct StepSeries {
     pub y: Vec<f64>,
     pub color: Vec4,
     pub style: StepStyle,
+    /// Streaming ring of the newest points, used instead of `x`/`y` when set
+    pub stream: Option<StreamBuffer>,
 }
 
 impl StepSeries {
@@ -334,15 +469,55 @@ impl StepSeries {
             y: Vec::new(),
             color: get_color(0),
             style: StepStyle::Pre,
+            stream: None,
         }
     }
 
     pub fn with_data(mut self, x: Vec<f64>, y: Vec<f64>) -> Self {
         self.x = x;
         self.y = y;
+        self.stream = None;
+        self
+    }
+
+    /// Keep only the newest `capacity` points, starting from the current data
+    pub fn with_stream(mut self, capacity: usize) -> Self {
+        let points = self.x.iter().copied().zip(self.y.iter().copied());
+        self.stream = Some(StreamBuffer::from_points(capacity, points));
+        self.x = Vec::new();
+        self.y = Vec::new();
         self
     }
 
+    /// Append points, streaming `DEFAULT_STREAM_CAPACITY` points if the
+    /// series is not a stream yet
+    pub fn push_points(&mut self, points: &[(f64, f64)]) {
+        if self.stream.is_none() {
+            let x = std::mem::take(&mut self.x);
+            let y = std::mem::take(&mut self.y);
+            self.stream = Some(StreamBuffer::from_points(DEFAULT_STREAM_CAPACITY, x.into_iter().zip(y)));
+        }
+        if let Some(stream) = &mut self.stream {
+            stream.extend(points);
+        }
+    }
+
+    /// X values, from the stream if there is one
+    pub fn xs(&self) -> &[f64] {
+        match &self.stream {
+            Some(stream) => stream.x(),
+            None => &self.x,
+        }
+    }
+
+    /// Y values, from the stream if there is one
+    pub fn ys(&self) -> &[f64] {
+        match &self.stream {
+            Some(stream) => stream.y(),
+            None => &self.y,
+        }
+    }
+
     pub fn with_color(mut self, color: Vec4) -> Self {
         self.color = color;
         self
@@ -365,6 +540,11 @@ pub struct StepPlot {
     #[rust] y_label: String,
     #[rust] show_grid: bool,
     #[rust] show_markers: bool,
+    #[rust] stream: StreamAxes,
+    #[rust(Axes::with_margins(60.0, 40.0, 20.0, 50.0))] axes: Axes,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl StepPlot {
@@ -374,6 +554,7 @@ impl StepPlot {
 
     pub fn add_series(&mut self, series: StepSeries) {
         self.series.push(series);
+        self.follow_stream();
     }
 
     pub fn set_x_label(&mut self, label: impl Into<String>) {
@@ -392,6 +573,67 @@ impl StepPlot {
         self.show_markers = show;
     }
 
+    /// Append points to series `series`, keeping its newest points in a ring
+    /// buffer, and scroll the axes to follow them. Call `redraw_if_dirty`
+    /// once per frame to draw the new batch.
+    pub fn push_points(&mut self, series: usize, points: &[(f64, f64)]) {
+        let Some(target) = self.series.get_mut(series) else { return };
+        target.push_points(points);
+        self.follow_stream();
+        self.stream.mark_dirty();
+    }
+
+    pub fn set_stream_window(&mut self, window: StreamWindow) {
+        self.stream.window = window;
+        self.follow_stream();
+    }
+
+    pub fn set_stream_y_scale(&mut self, y_scale: StreamYScale) {
+        self.stream.y_scale = y_scale;
+        self.follow_stream();
+    }
+
+    /// Redraw only if points were pushed since the last call
+    pub fn redraw_if_dirty(&mut self, cx: &mut Cx) -> bool {
+        let dirty = self.stream.take_dirty();
+        if dirty {
+            self.redraw(cx);
+        }
+        dirty
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.series.iter().any(|s| s.stream.is_some())
+    }
+
+    /// Move the axes to the stream window and the data visible in it
+    fn follow_stream(&mut self) {
+        if !self.is_streaming() {
+            return;
+        }
+        let mut data = (f64::MAX, f64::MIN);
+        for xs in self.series.iter().map(|s| s.xs()).filter(|xs| !xs.is_empty()) {
+            data.0 = data.0.min(xs[0]);
+            data.1 = data.1.max(xs[xs.len() - 1]);
+        }
+        if data.0 > data.1 {
+            return;
+        }
+        self.axes.x.range = self.stream.x_range(self.axes.x.range, data);
+
+        let (x_lo, x_hi) = self.axes.x.range;
+        let mut visible = (f64::MAX, f64::MIN);
+        for s in &self.series {
+            for (&x, &y) in s.xs().iter().zip(s.ys()) {
+                if x >= x_lo && x <= x_hi && y.is_finite() {
+                    visible.0 = visible.0.min(y);
+                    visible.1 = visible.1.max(y);
+                }
+            }
+        }
+        self.axes.y.range = self.stream.y_range(self.axes.y.range, visible);
+    }
+
     pub fn clear(&mut self) {
         self.series.clear();
     }
@@ -403,11 +645,11 @@ impl StepPlot {
         let mut y_max = f64::MIN;
 
         for s in &self.series {
-            for &x in &s.x {
+            for &x in s.xs() {
                 if x < x_min { x_min = x; }
                 if x > x_max { x_max = x; }
             }
-            for &y in &s.y {
+            for &y in s.ys() {
                 if y < y_min { y_min = y; }
                 if y > y_max { y_max = y; }
             }
@@ -430,92 +672,80 @@ impl StepPlot {
 impl Widget for StepPlot {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.series.is_empty() {
-            let padding_left = 60.0;
-            let padding_right = 20.0;
-            let padding_top = 40.0;
-            let padding_bottom = 50.0;
-
-            let plot_left = rect.pos.x + padding_left;
-            let plot_top = rect.pos.y + padding_top;
-            let plot_right = rect.pos.x + rect.size.x - padding_right;
-            let plot_bottom = rect.pos.y + rect.size.y - padding_bottom;
-            let plot_width = plot_right - plot_left;
-            let plot_height = plot_bottom - plot_top;
-
-            let (x_min, x_max, y_min, y_max) = self.get_bounds();
+            self.axes.set_show_grid(self.show_grid);
+            self.axes.update_plot_area(rect);
+            let plot_left = self.axes.plot_area.left;
+            let plot_bottom = self.axes.plot_area.bottom;
+            let plot_width = self.axes.plot_area.width();
+            let plot_height = self.axes.plot_area.height();
+
+            // Streams keep the ranges chosen on each push
+            let (x_min, x_max, y_min, y_max) = if self.is_streaming() {
+                let (x, y) = (self.axes.x.range, self.axes.y.range);
+                (x.0, x.1, y.0, y.1)
+            } else {
+                self.get_bounds()
+            };
             let x_range = (x_max - x_min).max(0.001);
             let y_range = (y_max - y_min).max(0.001);
+            self.axes.x.range = (x_min, x_min + x_range);
+            self.axes.y.range = (y_min, y_min + y_range);
+            let plot_right = self.axes.plot_area.right;
 
-            // Draw grid
-            if self.show_grid {
-                self.draw_line.color = vec4(0.9, 0.9, 0.9, 1.0);
-                for i in 0..=5 {
-                    let t = i as f64 / 5.0;
-                    let x = plot_left + t * plot_width;
-                    let y = plot_top + t * plot_height;
-                    self.draw_line.draw_line(cx, dvec2(x, plot_top), dvec2(x, plot_bottom), 1.0);
-                    self.draw_line.draw_line(cx, dvec2(plot_left, y), dvec2(plot_right, y), 1.0);
-                }
-            }
-
-            // Draw axes
-            self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-            self.draw_line.draw_line(cx, dvec2(plot_left, plot_bottom), dvec2(plot_right, plot_bottom), 1.5);
-            self.draw_line.draw_line(cx, dvec2(plot_left, plot_top), dvec2(plot_left, plot_bottom), 1.5);
-
-            // Draw axis labels
-            self.label.draw_text.color = vec4(0.3, 0.3, 0.3, 1.0);
-            for i in 0..=5 {
-                let t = i as f64 / 5.0;
-                let x_val = x_min + t * x_range;
-                let y_val = y_min + (1.0 - t) * y_range;
-                let x = plot_left + t * plot_width;
-                let y = plot_top + t * plot_height;
-                self.label.draw_at(cx, dvec2(x, plot_bottom + 15.0), &format!("{:.1}", x_val), TextAnchor::TopCenter);
-                self.label.draw_at(cx, dvec2(plot_left - 10.0, y), &format!("{:.1}", y_val), TextAnchor::MiddleRight);
-            }
+            self.axes.draw_grid(cx, &mut self.draw_line);
+            self.axes.draw_axes(cx, &mut self.draw_line);
+            self.axes.draw_tick_labels(cx, &mut self.label);
 
             // Draw step lines
             for series in &self.series {
-                if series.x.len() < 2 { continue; }
+                let (xs, ys) = (series.xs(), series.ys());
+                if xs.len() < 2 { continue; }
 
                 self.draw_line.color = series.color;
-                let n = series.x.len();
+                let n = xs.len();
 
                 for i in 0..n.saturating_sub(1) {
-                    let x1 = series.x[i];
-                    let x2 = series.x[i + 1];
-                    let y1 = series.y[i];
-                    let y2 = series.y[i + 1];
+                    let x1 = xs[i];
+                    let x2 = xs[i + 1];
+                    let y1 = ys[i];
+                    let y2 = ys[i + 1];
 
                     let px1 = plot_left + ((x1 - x_min) / x_range) * plot_width;
                     let px2 = plot_left + ((x2 - x_min) / x_range) * plot_width;
                     let py1 = plot_bottom - ((y1 - y_min) / y_range) * plot_height;
                     let py2 = plot_bottom - ((y2 - y_min) / y_range) * plot_height;
 
+                    // Pieces running off a scrolled window stop at its edge
+                    let draw_line = &mut self.draw_line;
+                    let mut segment = |a: DVec2, b: DVec2| {
+                        if let Some((a, b)) = clip_segment_x(a, b, plot_left, plot_right) {
+                            draw_line.draw_line(cx, a, b, 2.0);
+                        }
+                    };
                     match series.style {
                         StepStyle::None => {
                             // Normal direct line
-                            self.draw_line.draw_line(cx, dvec2(px1, py1), dvec2(px2, py2), 2.0);
+                            segment(dvec2(px1, py1), dvec2(px2, py2));
                         }
                         StepStyle::Pre => {
                             // Vertical then horizontal
-                            self.draw_line.draw_line(cx, dvec2(px1, py1), dvec2(px1, py2), 2.0);
-                            self.draw_line.draw_line(cx, dvec2(px1, py2), dvec2(px2, py2), 2.0);
+                            segment(dvec2(px1, py1), dvec2(px1, py2));
+                            segment(dvec2(px1, py2), dvec2(px2, py2));
                         }
                         StepStyle::Post => {
                             // Horizontal then vertical
-                            self.draw_line.draw_line(cx, dvec2(px1, py1), dvec2(px2, py1), 2.0);
-                            self.draw_line.draw_line(cx, dvec2(px2, py1), dvec2(px2, py2), 2.0);
+                            segment(dvec2(px1, py1), dvec2(px2, py1));
+                            segment(dvec2(px2, py1), dvec2(px2, py2));
                         }
                         StepStyle::Mid => {
                             // Horizontal, vertical at midpoint, horizontal
                             let mid_x = (px1 + px2) / 2.0;
-                            self.draw_line.draw_line(cx, dvec2(px1, py1), dvec2(mid_x, py1), 2.0);
-                            self.draw_line.draw_line(cx, dvec2(mid_x, py1), dvec2(mid_x, py2), 2.0);
-                            self.draw_line.draw_line(cx, dvec2(mid_x, py2), dvec2(px2, py2), 2.0);
+                            segment(dvec2(px1, py1), dvec2(mid_x, py1));
+                            segment(dvec2(mid_x, py1), dvec2(mid_x, py2));
+                            segment(dvec2(mid_x, py2), dvec2(px2, py2));
                         }
                     }
                 }
@@ -523,8 +753,11 @@ impl Widget for StepPlot {
                 // Draw markers
                 if self.show_markers {
                     for i in 0..n {
-                        let px = plot_left + ((series.x[i] - x_min) / x_range) * plot_width;
-                        let py = plot_bottom - ((series.y[i] - y_min) / y_range) * plot_height;
+                        let px = plot_left + ((xs[i] - x_min) / x_range) * plot_width;
+                        let py = plot_bottom - ((ys[i] - y_min) / y_range) * plot_height;
+                        if px < plot_left || px > plot_right {
+                            continue;
+                        }
 
                         // Draw small circle
                         let segments = 12;
@@ -549,6 +782,7 @@ impl Widget for StepPlot {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -557,6 +791,16 @@ impl Widget for StepPlot {
     }
 }
 
+impl PlotExport for StepPlot {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl StepPlot {
     pub fn redraw(&mut self, cx: &mut Cx) {
         self.view.redraw(cx);
@@ -582,6 +826,19 @@ impl StepPlotRef {
     pub fn set_show_markers(&self, show: bool) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_show_markers(show); }
     }
+    pub fn push_points(&self, series: usize, points: &[(f64, f64)]) {
+        if let Some(mut inner) = self.borrow_mut() { inner.push_points(series, points); }
+    }
+    pub fn set_stream_window(&self, window: StreamWindow) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_stream_window(window); }
+    }
+    pub fn set_stream_y_scale(&self, y_scale: StreamYScale) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_stream_y_scale(y_scale); }
+    }
+    pub fn redraw_if_dirty(&self, cx: &mut Cx) -> bool {
+        if let Some(mut inner) = self.borrow_mut() { return inner.redraw_if_dirty(cx); }
+        false
+    }
     pub fn clear(&self) {
         if let Some(mut inner) = self.borrow_mut() { inner.clear(); }
     }
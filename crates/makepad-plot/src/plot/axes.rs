@@ -0,0 +1,707 @@
+// Shared Cartesian axes - scales, limits, margins, nice ticks and tick labels
+
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use crate::export::PlotScene;
+use super::*;
+
+/// Length of major tick marks in pixels (minor ticks are half as long)
+const TICK_LENGTH: f64 = 4.0;
+
+// =============================================================================
+// Nice numbers
+// =============================================================================
+
+/// Round `x` to 1, 2, 5 or 10 times a power of ten (Heckbert, "Nice Numbers
+/// for Graph Labels"). `round` picks the closest; otherwise the next one up.
+pub fn nice_number(x: f64, round: bool) -> f64 {
+    if !x.is_finite() || x <= 0.0 {
+        return 0.0;
+    }
+
+    let exp = x.log10().floor();
+    let base = 10.0_f64.powf(exp);
+    let fraction = x / base;
+
+    let nice = if round {
+        if fraction < 1.5 { 1.0 } else if fraction < 3.0 { 2.0 } else if fraction < 7.0 { 5.0 } else { 10.0 }
+    } else if fraction <= 1.0 {
+        1.0
+    } else if fraction <= 2.0 {
+        2.0
+    } else if fraction <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+
+    nice * base
+}
+
+/// Spacing of roughly `count` intervals across `[min, max]`, or 0.0 for an
+/// empty or non-finite range
+pub fn nice_step(min: f64, max: f64, count: usize) -> f64 {
+    nice_number((max - min).abs() / count.max(1) as f64, true)
+}
+
+/// Multiples of the nice step that fall inside `[min, max]`
+pub fn nice_ticks(min: f64, max: f64, count: usize) -> Vec<f64> {
+    let step = nice_step(min, max, count);
+    if step == 0.0 {
+        return if min.is_finite() { vec![min] } else { Vec::new() };
+    }
+
+    let (lo, hi) = (min.min(max), min.max(max));
+    let first = (lo / step - 1e-9).ceil() as i64;
+    let last = (hi / step + 1e-9).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+/// `[min, max]` widened outwards to the nearest nice ticks
+pub fn nice_bounds(min: f64, max: f64, count: usize) -> (f64, f64) {
+    let step = nice_step(min, max, count);
+    if step == 0.0 {
+        return (min, max);
+    }
+    ((min / step + 1e-9).floor() * step, (max / step - 1e-9).ceil() * step)
+}
+
+/// Minor ticks between the majors of a linear axis: fifths of a 1 or 5 step,
+/// quarters of a 2 step. Positions that coincide with major ticks are skipped.
+pub fn nice_minor_ticks(min: f64, max: f64, step: f64) -> Vec<f64> {
+    if !step.is_finite() || step <= 0.0 {
+        return Vec::new();
+    }
+
+    let mantissa = step / 10.0_f64.powf((step.log10() + 1e-9).floor());
+    let subdivisions: i64 = if (mantissa - 2.0).abs() < 0.5 { 4 } else { 5 };
+    let minor = step / subdivisions as f64;
+
+    let (lo, hi) = (min.min(max), min.max(max));
+    let first = (lo / minor - 1e-9).ceil() as i64;
+    let last = (hi / minor + 1e-9).floor() as i64;
+    (first..=last)
+        .filter(|i| i.rem_euclid(subdivisions) != 0)
+        .map(|i| i as f64 * minor)
+        .collect()
+}
+
+/// 2..9 times each power of ten inside `[min, max]` for log axes
+fn log_minor_ticks(min: f64, max: f64) -> Vec<f64> {
+    if min <= 0.0 || max <= 0.0 {
+        return Vec::new();
+    }
+
+    let (lo, hi) = (min.min(max), min.max(max));
+    let mut ticks = Vec::new();
+    for exp in lo.log10().floor() as i32..=hi.log10().ceil() as i32 {
+        let decade = 10.0_f64.powi(exp);
+        for m in 2..=9 {
+            let v = m as f64 * decade;
+            if v >= lo && v <= hi {
+                ticks.push(v);
+            }
+        }
+    }
+    ticks
+}
+
+// =============================================================================
+// Tick formatting
+// =============================================================================
+
+/// How tick values are turned into labels
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum TickFormatter {
+    /// As many decimals as the tick spacing needs: steps of 0.25 print "2.25",
+    /// steps of 1000 print "3000". Log and time axes keep their own labels.
+    #[default]
+    Auto,
+    /// Fixed number of decimals
+    Fixed(usize),
+    /// SI prefixes: "250m", "1.5k", "20M"
+    Si,
+    /// Values as percentages (0.25 -> "25%")
+    Percent,
+    /// Auto precision with locale separators, e.g. `'.'` and `','` for
+    /// "1.234,5"
+    Locale { thousands: char, decimal: char },
+}
+
+const SI_PREFIXES: [(f64, &str); 11] = [
+    (1e-15, "f"),
+    (1e-12, "p"),
+    (1e-9, "n"),
+    (1e-6, "µ"),
+    (1e-3, "m"),
+    (1.0, ""),
+    (1e3, "k"),
+    (1e6, "M"),
+    (1e9, "G"),
+    (1e12, "T"),
+    (1e15, "P"),
+];
+
+impl TickFormatter {
+    /// Format `value` for an axis whose ticks are `step` apart. A `step` of
+    /// 0.0 means the spacing is unknown and the value's own digits are used.
+    pub fn format(&self, value: f64, step: f64) -> String {
+        // i * step can land on -0.0 or 1e-17 instead of zero
+        let value = if value.abs() <= step.abs() * 1e-9 { 0.0 } else { value };
+
+        match self {
+            TickFormatter::Auto => format_decimals(value, auto_decimals(value, step)),
+            TickFormatter::Fixed(decimals) => format_decimals(value, *decimals),
+            TickFormatter::Si => format_si(value, step),
+            TickFormatter::Percent => {
+                let percent = value * 100.0;
+                format!("{}%", format_decimals(percent, auto_decimals(percent, step * 100.0)))
+            }
+            TickFormatter::Locale { thousands, decimal } => {
+                group_digits(&format_decimals(value, auto_decimals(value, step)), *thousands, *decimal)
+            }
+        }
+    }
+}
+
+/// Decimals needed to tell ticks `step` apart, or to show `value` (up to six
+/// decimals) when the step is unknown
+fn auto_decimals(value: f64, step: f64) -> usize {
+    if step > 0.0 && step.is_finite() {
+        let exp = (step.log10() + 1e-9).floor();
+        // A 2.5 mantissa (steps of 0.25, 25, ...) needs one more digit
+        let mantissa = step / 10.0_f64.powf(exp);
+        let extra = if (mantissa - mantissa.round()).abs() > 1e-6 { 1.0 } else { 0.0 };
+        return (extra - exp).clamp(0.0, 15.0) as usize;
+    }
+
+    let text = format!("{:.6}", value.abs());
+    let trimmed = text.trim_end_matches('0');
+    trimmed.split('.').nth(1).map_or(0, str::len)
+}
+
+fn format_decimals(value: f64, decimals: usize) -> String {
+    let text = format!("{:.*}", decimals, value);
+    // Rounding can leave "-0" or "-0.00"
+    if text.starts_with('-') && text[1..].chars().all(|c| c == '0' || c == '.') {
+        text[1..].to_string()
+    } else {
+        text
+    }
+}
+
+fn format_si(value: f64, step: f64) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return format_decimals(value, 0);
+    }
+
+    let (factor, prefix) = SI_PREFIXES
+        .iter()
+        .rev()
+        .find(|(factor, _)| value.abs() >= factor * (1.0 - 1e-9))
+        .copied()
+        .unwrap_or(SI_PREFIXES[0]);
+
+    let scaled = value / factor;
+    let text = format_decimals(scaled, auto_decimals(scaled, step / factor));
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    };
+    format!("{}{}", text, prefix)
+}
+
+/// Insert `thousands` every three integer digits and swap in `decimal`
+fn group_digits(text: &str, thousands: char, decimal: char) -> String {
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text),
+    };
+    let (int_part, frac_part) = match digits.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (digits, None),
+    };
+
+    let mut grouped = String::from(sign);
+    for (i, c) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i) % 3 == 0 {
+            grouped.push(thousands);
+        }
+        grouped.push(c);
+    }
+    if let Some(frac_part) = frac_part {
+        grouped.push(decimal);
+        grouped.push_str(frac_part);
+    }
+    grouped
+}
+
+// =============================================================================
+// Axes
+// =============================================================================
+
+/// One axis: scale, data limits and how its ticks look
+#[derive(Clone, Debug)]
+pub struct PlotAxis {
+    pub scale: ScaleType,
+    pub range: (f64, f64),
+    pub formatter: TickFormatter,
+    /// Target number of intervals between major ticks
+    pub tick_count: usize,
+    pub show_grid: bool,
+    pub show_minor: bool,
+    /// Off for categorical axes whose labels the chart draws itself
+    pub show_labels: bool,
+    /// Timezone in which time axes place and label their ticks
+    pub timezone: TimeZone,
+    /// Timestamps of positions 0, 1, 2, ... on an ordinal time axis. The
+    /// axis values are then indices, so gaps such as nights and weekends take
+    /// no space while labels still show calendar dates.
+    pub ordinal_times: Vec<f64>,
+    /// Draw tick labels as math: `$...$` spans are laid out as LaTeX and
+    /// log axes label powers of ten as `10^{n}` instead of "1e3"
+    pub math_labels: bool,
+}
+
+impl Default for PlotAxis {
+    fn default() -> Self {
+        Self {
+            scale: ScaleType::Linear,
+            range: (0.0, 1.0),
+            formatter: TickFormatter::Auto,
+            tick_count: 5,
+            show_grid: true,
+            show_minor: false,
+            show_labels: true,
+            timezone: TimeZone::Utc,
+            ordinal_times: Vec::new(),
+            math_labels: false,
+        }
+    }
+}
+
+impl PlotAxis {
+    /// Major tick values inside the range
+    pub fn ticks(&self) -> Vec<f64> {
+        let (min, max) = self.range;
+        match self.scale {
+            ScaleType::Time if !self.ordinal_times.is_empty() => {
+                ordinal_time_ticks(&self.ordinal_times, min, max, self.tick_count, &self.timezone)
+            }
+            ScaleType::Time => time_ticks(min, max, self.tick_count, &self.timezone),
+            _ => self.scale.generate_ticks(min, max, self.tick_count),
+        }
+    }
+
+    /// Minor tick values inside the range (linear and log axes only)
+    pub fn minor_ticks(&self) -> Vec<f64> {
+        match self.scale {
+            ScaleType::Linear => nice_minor_ticks(self.range.0, self.range.1, self.step()),
+            ScaleType::Log => log_minor_ticks(self.range.0, self.range.1),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Spacing of the major ticks on a linear axis, 0.0 otherwise
+    pub fn step(&self) -> f64 {
+        match self.scale {
+            ScaleType::Linear => nice_step(self.range.0, self.range.1, self.tick_count),
+            _ => 0.0,
+        }
+    }
+
+    /// Calendar interval between the major ticks of a time axis
+    pub fn time_interval(&self) -> TimeInterval {
+        let (min, max) = self.range;
+        if self.ordinal_times.is_empty() {
+            TimeInterval::for_span(max - min, self.tick_count)
+        } else {
+            ordinal_time_interval(&self.ordinal_times, min, max, self.tick_count)
+        }
+    }
+
+    /// Tick label for `value`
+    pub fn format(&self, value: f64) -> String {
+        match self.scale {
+            ScaleType::Time => self.format_time(value),
+            ScaleType::Log | ScaleType::SymLog if self.formatter == TickFormatter::Auto => {
+                if self.math_labels { self.scale.format_tick_math(value) } else { self.scale.format_tick(value) }
+            }
+            _ => self.formatter.format(value, self.step()),
+        }
+    }
+
+    fn format_time(&self, value: f64) -> String {
+        let time = if self.ordinal_times.is_empty() {
+            value
+        } else {
+            let index = value.round().clamp(0.0, (self.ordinal_times.len() - 1) as f64) as usize;
+            self.ordinal_times[index]
+        };
+        format_time(time, self.time_interval(), &self.timezone)
+    }
+
+    /// Position of `value` along the axis, 0.0 at the range start and 1.0 at the end
+    pub fn normalize(&self, value: f64) -> f64 {
+        let t_min = self.scale.transform(self.range.0);
+        let t_max = self.scale.transform(self.range.1);
+        (self.scale.transform(value) - t_min) / (t_max - t_min)
+    }
+
+    /// Inverse of [`PlotAxis::normalize`]
+    pub fn denormalize(&self, fraction: f64) -> f64 {
+        let t_min = self.scale.transform(self.range.0);
+        let t_max = self.scale.transform(self.range.1);
+        self.scale.inverse(t_min + fraction * (t_max - t_min))
+    }
+}
+
+/// Line segment of the grid or axes, in pixels
+#[derive(Clone, Copy, Debug)]
+pub struct AxisSegment {
+    pub from: DVec2,
+    pub to: DVec2,
+    pub width: f64,
+    pub color: Vec4,
+}
+
+impl AxisSegment {
+    fn new(from: DVec2, to: DVec2, width: f64, color: Vec4) -> Self {
+        Self { from, to, width, color }
+    }
+}
+
+/// Pixel margins between the widget rect and the plot area
+#[derive(Clone, Copy, Debug)]
+pub struct PlotMargins {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl Default for PlotMargins {
+    fn default() -> Self {
+        Self { left: 50.0, top: 30.0, right: 20.0, bottom: 30.0 }
+    }
+}
+
+/// Cartesian axes shared by the x/y charts. Owns both axes, the margins and
+/// the plot area they produce, maps data to pixels and draws the grid, the
+/// axis lines with tick marks and the tick labels.
+#[derive(Clone, Debug, Default)]
+pub struct Axes {
+    pub x: PlotAxis,
+    pub y: PlotAxis,
+    pub margins: PlotMargins,
+    pub plot_area: PlotArea,
+}
+
+impl Axes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Axes with the given margins (same order as [`PlotArea::new`])
+    pub fn with_margins(left: f64, top: f64, right: f64, bottom: f64) -> Self {
+        Self {
+            margins: PlotMargins { left, top, right, bottom },
+            ..Self::default()
+        }
+    }
+
+    /// Show or hide the grid on both axes
+    pub fn set_show_grid(&mut self, show: bool) {
+        self.x.show_grid = show;
+        self.y.show_grid = show;
+    }
+
+    /// Show or hide minor ticks (and minor grid lines) on both axes
+    pub fn set_show_minor(&mut self, show: bool) {
+        self.x.show_minor = show;
+        self.y.show_minor = show;
+    }
+
+    /// Lay out the plot area inside the widget rect
+    pub fn update_plot_area(&mut self, rect: Rect) {
+        self.plot_area = PlotArea::new(
+            rect.pos.x + self.margins.left,
+            rect.pos.y + self.margins.top,
+            rect.pos.x + rect.size.x - self.margins.right,
+            rect.pos.y + rect.size.y - self.margins.bottom,
+        );
+    }
+
+    pub fn x_to_pixel(&self, x: f64) -> f64 {
+        self.plot_area.left + self.x.normalize(x) * self.plot_area.width()
+    }
+
+    pub fn y_to_pixel(&self, y: f64) -> f64 {
+        self.plot_area.bottom - self.y.normalize(y) * self.plot_area.height()
+    }
+
+    pub fn data_to_pixel(&self, x: f64, y: f64) -> DVec2 {
+        dvec2(self.x_to_pixel(x), self.y_to_pixel(y))
+    }
+
+    pub fn pixel_to_data(&self, pos: DVec2) -> (f64, f64) {
+        let fx = (pos.x - self.plot_area.left) / self.plot_area.width();
+        let fy = (self.plot_area.bottom - pos.y) / self.plot_area.height();
+        (self.x.denormalize(fx), self.y.denormalize(fy))
+    }
+
+    /// Whether a pixel position lies inside the plot area
+    pub fn contains(&self, pos: DVec2) -> bool {
+        pos.x >= self.plot_area.left && pos.x <= self.plot_area.right
+            && pos.y >= self.plot_area.top && pos.y <= self.plot_area.bottom
+    }
+
+    /// Grid lines at the major ticks, with lighter minor lines underneath
+    pub fn grid_segments(&self) -> Vec<AxisSegment> {
+        let area = self.plot_area;
+        let minor = vec4(0.95, 0.95, 0.95, 1.0);
+        let major = vec4(0.9, 0.9, 0.9, 1.0);
+        let mut segments = Vec::new();
+
+        if self.x.show_grid {
+            if self.x.show_minor {
+                for x in self.x.minor_ticks() {
+                    let px = self.x_to_pixel(x);
+                    segments.push(AxisSegment::new(dvec2(px, area.top), dvec2(px, area.bottom), 0.5, minor));
+                }
+            }
+            for x in self.x.ticks() {
+                let px = self.x_to_pixel(x);
+                segments.push(AxisSegment::new(dvec2(px, area.top), dvec2(px, area.bottom), 0.5, major));
+            }
+        }
+
+        if self.y.show_grid {
+            if self.y.show_minor {
+                for y in self.y.minor_ticks() {
+                    let py = self.y_to_pixel(y);
+                    segments.push(AxisSegment::new(dvec2(area.left, py), dvec2(area.right, py), 0.5, minor));
+                }
+            }
+            for y in self.y.ticks() {
+                let py = self.y_to_pixel(y);
+                segments.push(AxisSegment::new(dvec2(area.left, py), dvec2(area.right, py), 0.5, major));
+            }
+        }
+        segments
+    }
+
+    /// Bottom and left axis lines with outward tick marks
+    pub fn axis_segments(&self) -> Vec<AxisSegment> {
+        let area = self.plot_area;
+        let color = vec4(0.3, 0.3, 0.3, 1.0);
+        let mut segments = vec![
+            AxisSegment::new(dvec2(area.left, area.bottom), dvec2(area.right, area.bottom), 1.0, color),
+            AxisSegment::new(dvec2(area.left, area.bottom), dvec2(area.left, area.top), 1.0, color),
+        ];
+
+        if self.x.show_labels {
+            for x in self.x.ticks() {
+                let px = self.x_to_pixel(x);
+                segments.push(AxisSegment::new(dvec2(px, area.bottom), dvec2(px, area.bottom + TICK_LENGTH), 1.0, color));
+            }
+            if self.x.show_minor {
+                for x in self.x.minor_ticks() {
+                    let px = self.x_to_pixel(x);
+                    segments.push(AxisSegment::new(dvec2(px, area.bottom), dvec2(px, area.bottom + TICK_LENGTH / 2.0), 0.5, color));
+                }
+            }
+        }
+
+        if self.y.show_labels {
+            for y in self.y.ticks() {
+                let py = self.y_to_pixel(y);
+                segments.push(AxisSegment::new(dvec2(area.left - TICK_LENGTH, py), dvec2(area.left, py), 1.0, color));
+            }
+            if self.y.show_minor {
+                for y in self.y.minor_ticks() {
+                    let py = self.y_to_pixel(y);
+                    segments.push(AxisSegment::new(dvec2(area.left - TICK_LENGTH / 2.0, py), dvec2(area.left, py), 0.5, color));
+                }
+            }
+        }
+        segments
+    }
+
+    /// Formatted labels below the x axis and left of the y axis, with their
+    /// anchor points and whether their axis draws them as math
+    pub fn tick_labels(&self) -> Vec<(DVec2, String, TextAnchor, bool)> {
+        let area = self.plot_area;
+        let mut labels = Vec::new();
+
+        if self.x.show_labels {
+            for x in self.x.ticks() {
+                let pos = dvec2(self.x_to_pixel(x), area.bottom + TICK_LENGTH + 2.0);
+                labels.push((pos, self.x.format(x), TextAnchor::TopCenter, self.x.math_labels));
+            }
+        }
+
+        if self.y.show_labels {
+            for y in self.y.ticks() {
+                let pos = dvec2(area.left - TICK_LENGTH - 2.0, self.y_to_pixel(y));
+                labels.push((pos, self.y.format(y), TextAnchor::MiddleRight, self.y.math_labels));
+            }
+        }
+        labels
+    }
+
+    pub fn draw_grid(&self, cx: &mut Cx2d, draw_line: &mut DrawPlotLine) {
+        for segment in self.grid_segments() {
+            draw_line.color = segment.color;
+            draw_line.draw_line(cx, segment.from, segment.to, segment.width);
+        }
+    }
+
+    pub fn draw_axes(&self, cx: &mut Cx2d, draw_line: &mut DrawPlotLine) {
+        for segment in self.axis_segments() {
+            draw_line.color = segment.color;
+            draw_line.draw_line(cx, segment.from, segment.to, segment.width);
+        }
+    }
+
+    pub fn draw_tick_labels(&self, cx: &mut Cx2d, label: &mut PlotLabel) {
+        label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
+        for (pos, text, anchor, math) in self.tick_labels() {
+            if math {
+                label.draw_math_at(cx, pos, &text, anchor);
+            } else {
+                label.draw_at(cx, pos, &text, anchor);
+            }
+        }
+    }
+
+    /// Grid, axes and tick labels into a scene, as `draw_grid`, `draw_axes`
+    /// and `draw_tick_labels` would draw them
+    pub fn add_to_scene(&self, scene: &mut PlotScene, font_size: f64) {
+        for segment in self.grid_segments().into_iter().chain(self.axis_segments()) {
+            scene.line(segment.from, segment.to, segment.width, segment.color);
+        }
+        for (pos, text, anchor, math) in self.tick_labels() {
+            let color = vec4(0.3, 0.3, 0.3, 1.0);
+            if math {
+                scene.math_text(pos, &text, anchor, font_size, color);
+            } else {
+                scene.text(pos, &text, anchor, font_size, color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_ticks(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?} vs {:?}", actual, expected);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} vs {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_nice_number() {
+        assert_eq!(nice_number(2.22, true), 2.0);
+        assert_eq!(nice_number(0.037, true), 0.05);
+        assert_eq!(nice_number(7.5, true), 10.0);
+        assert_eq!(nice_number(2.22, false), 5.0);
+        assert_eq!(nice_number(1.0, false), 1.0);
+        assert_eq!(nice_number(0.0, true), 0.0);
+        assert_eq!(nice_number(-3.0, true), 0.0);
+        assert_eq!(nice_number(f64::NAN, true), 0.0);
+    }
+
+    #[test]
+    fn test_nice_ticks() {
+        assert_ticks(&nice_ticks(0.0, 11.1, 5), &[0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_ticks(&nice_ticks(-0.33, 0.72, 5), &[-0.2, 0.0, 0.2, 0.4, 0.6]);
+        assert_ticks(&nice_ticks(1000.0, 0.0, 4), &[0.0, 200.0, 400.0, 600.0, 800.0, 1000.0]);
+        assert_eq!(nice_bounds(0.3, 9.2, 5), (0.0, 10.0));
+    }
+
+    #[test]
+    fn test_degenerate_range() {
+        assert_eq!(nice_step(3.0, 3.0, 5), 0.0);
+        assert_eq!(nice_ticks(3.0, 3.0, 5), vec![3.0]);
+        assert_eq!(nice_bounds(3.0, 3.0, 5), (3.0, 3.0));
+        assert!(nice_ticks(f64::NAN, f64::NAN, 5).is_empty());
+        assert!(nice_minor_ticks(3.0, 3.0, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_minor_ticks() {
+        // Fifths of a 5 step, majors skipped
+        assert_ticks(&nice_minor_ticks(0.0, 10.0, 5.0), &[1.0, 2.0, 3.0, 4.0, 6.0, 7.0, 8.0, 9.0]);
+        // Quarters of a 2 step
+        assert_ticks(&nice_minor_ticks(0.0, 4.0, 2.0), &[0.5, 1.0, 1.5, 2.5, 3.0, 3.5]);
+        assert_ticks(
+            &log_minor_ticks(1.0, 100.0),
+            &[2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0, 90.0],
+        );
+        assert!(log_minor_ticks(0.0, 100.0).is_empty());
+    }
+
+    #[test]
+    fn test_format_auto_and_fixed() {
+        let auto = TickFormatter::Auto;
+        assert_eq!(auto.format(2.25, 0.25), "2.25");
+        assert_eq!(auto.format(0.4, 0.2), "0.4");
+        assert_eq!(auto.format(3000.0, 1000.0), "3000");
+        assert_eq!(auto.format(1e-17, 0.2), "0.0");
+        assert_eq!(auto.format(-0.0, 0.5), "0.0");
+        assert_eq!(auto.format(1.5, 0.0), "1.5");
+        assert_eq!(TickFormatter::Fixed(2).format(1.23456, 1.0), "1.23");
+    }
+
+    #[test]
+    fn test_format_log_math_opt_in() {
+        let mut log = PlotAxis { scale: ScaleType::Log, range: (1.0, 1000.0), ..Default::default() };
+        // Plain text unless the axis asks for math
+        assert_eq!(log.format(1000.0), "1e3");
+        assert_eq!(log.format(0.01), "1e-2");
+        assert_eq!(log.format(50.0), "50");
+        log.math_labels = true;
+        assert_eq!(log.format(1000.0), "$10^{3}$");
+        assert_eq!(log.format(50.0), "50");
+
+        let symlog = PlotAxis { scale: ScaleType::SymLog, ..Default::default() };
+        assert_eq!(symlog.format(-100.0), "-1e2");
+        assert_eq!(ScaleType::SymLog.format_tick_math(-100.0), "$-10^{2}$");
+    }
+
+    #[test]
+    fn test_format_si() {
+        let si = TickFormatter::Si;
+        assert_eq!(si.format(1200.0, 100.0), "1.2k");
+        assert_eq!(si.format(3_400_000.0, 100_000.0), "3.4M");
+        assert_eq!(si.format(20e6, 5e6), "20M");
+        assert_eq!(si.format(0.25, 0.05), "250m");
+        assert_eq!(si.format(-1500.0, 500.0), "-1.5k");
+        assert_eq!(si.format(0.0, 100.0), "0");
+    }
+
+    #[test]
+    fn test_format_percent() {
+        let percent = TickFormatter::Percent;
+        assert_eq!(percent.format(0.25, 0.05), "25%");
+        assert_eq!(percent.format(1.0, 0.25), "100%");
+        assert_eq!(percent.format(0.125, 0.005), "12.5%");
+    }
+
+    #[test]
+    fn test_format_locale() {
+        let german = TickFormatter::Locale { thousands: '.', decimal: ',' };
+        assert_eq!(german.format(1234.5, 0.5), "1.234,5");
+        assert_eq!(german.format(-1_234_567.0, 1000.0), "-1.234.567");
+        assert_eq!(german.format(999.0, 1.0), "999");
+
+        let english = TickFormatter::Locale { thousands: ',', decimal: '.' };
+        assert_eq!(english.format(1_000_000.0, 250_000.0), "1,000,000");
+    }
+}
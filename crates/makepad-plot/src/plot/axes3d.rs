@@ -0,0 +1,243 @@
+// Bounding box, grid and tick labels shared by the 3D charts
+//
+// Data is mapped into a box spanning -1..1 along x and y and
+// -BOX_HALF_HEIGHT..BOX_HALF_HEIGHT along z. The three box faces furthest
+// from the eye carry the grid, so they always sit behind the data; ticks
+// are labelled along the front edges of the floor and the leftmost
+// vertical edge, as most 3D plotting tools do.
+
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use super::*;
+
+/// Half the height of the data box; x and y span -1..1
+pub const BOX_HALF_HEIGHT: f64 = 0.75;
+
+/// Distance of tick labels and axis titles outside the box, in box units
+const TICK_LABEL_OFFSET: f64 = 0.12;
+const AXIS_TITLE_OFFSET: f64 = 0.35;
+const TICK_MARK_LENGTH: f64 = 0.05;
+
+#[derive(Clone, Debug)]
+pub struct Axes3D {
+    pub x: PlotAxis,
+    pub y: PlotAxis,
+    pub z: PlotAxis,
+    pub x_label: String,
+    pub y_label: String,
+    pub z_label: String,
+    /// Draw the back panes, grid and labels at all
+    pub show_box: bool,
+    view: View3D,
+    center: DVec2,
+    scale: f64,
+}
+
+impl Default for Axes3D {
+    fn default() -> Self {
+        let axis = PlotAxis { tick_count: 4, ..PlotAxis::default() };
+        Self {
+            x: axis.clone(),
+            y: axis.clone(),
+            z: axis,
+            x_label: String::new(),
+            y_label: String::new(),
+            z_label: String::new(),
+            show_box: true,
+            view: View3D::new(),
+            center: DVec2::default(),
+            scale: 1.0,
+        }
+    }
+}
+
+impl Axes3D {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_ranges(&mut self, x: (f64, f64), y: (f64, f64), z: (f64, f64)) {
+        self.x.range = x;
+        self.y.range = y;
+        self.z.range = z;
+    }
+
+    pub fn set_labels(&mut self, x: impl Into<String>, y: impl Into<String>, z: impl Into<String>) {
+        self.x_label = x.into();
+        self.y_label = y.into();
+        self.z_label = z.into();
+    }
+
+    /// Fit the box into `rect` for the given camera; `zoom` scales it about
+    /// the center
+    pub fn layout(&mut self, rect: Rect, view: &View3D, zoom: f64) {
+        self.view = view.clone();
+        self.center = rect.pos + rect.size * 0.5;
+        self.scale = rect.size.x.min(rect.size.y) * 0.35 * zoom;
+    }
+
+    /// Box coordinates of a data point. Values on a degenerate axis land in
+    /// the middle of it.
+    pub fn to_box(&self, x: f64, y: f64, z: f64) -> Point3 {
+        let unit = |axis: &PlotAxis, v: f64| {
+            let t = axis.normalize(v);
+            if t.is_finite() { t * 2.0 - 1.0 } else { 0.0 }
+        };
+        [unit(&self.x, x), unit(&self.y, y), unit(&self.z, z) * BOX_HALF_HEIGHT]
+    }
+
+    /// Screen position of a point in box coordinates
+    pub fn project_box(&self, p: Point3) -> DVec2 {
+        let (sx, sy) = self.view.project(p[0], p[1], p[2]);
+        dvec2(self.center.x + sx * self.scale, self.center.y - sy * self.scale)
+    }
+
+    /// Screen position of a data point
+    pub fn project(&self, x: f64, y: f64, z: f64) -> DVec2 {
+        self.project_box(self.to_box(x, y, z))
+    }
+
+    /// Camera position in box coordinates
+    pub fn eye(&self) -> Point3 {
+        self.view.eye()
+    }
+
+    /// Sign of the box face furthest from the eye across `axis` (0 = x,
+    /// 1 = y, 2 = z): -1.0 for the low face, 1.0 for the high one
+    pub fn back_side(&self, axis: usize) -> f64 {
+        if self.eye()[axis] >= 0.0 { -1.0 } else { 1.0 }
+    }
+
+    /// Height of the floor, the face the z = low end of the range lies on
+    pub fn floor(&self) -> f64 {
+        -BOX_HALF_HEIGHT
+    }
+
+    fn extent(axis: usize) -> f64 {
+        if axis == 2 { BOX_HALF_HEIGHT } else { 1.0 }
+    }
+
+    /// Tick positions along `axis` in box coordinates
+    fn box_ticks(&self, axis: usize) -> Vec<(f64, String)> {
+        let plot_axis = [&self.x, &self.y, &self.z][axis];
+        plot_axis
+            .ticks()
+            .into_iter()
+            .filter_map(|v| {
+                let t = plot_axis.normalize(v);
+                t.is_finite().then(|| ((t * 2.0 - 1.0) * Self::extent(axis), plot_axis.format(v)))
+            })
+            .collect()
+    }
+
+    /// The vertical box edge (x and y signs) furthest left on screen, which
+    /// carries the z labels
+    fn z_edge(&self) -> (f64, f64) {
+        [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .into_iter()
+            .min_by(|a, b| {
+                let pa = self.project_box([a.0, a.1, 0.0]);
+                let pb = self.project_box([b.0, b.1, 0.0]);
+                pa.x.total_cmp(&pb.x)
+            })
+            .unwrap_or((-1.0, -1.0))
+    }
+
+    /// Where the x and y tick labels go: the floor edges nearest the eye
+    fn front_edges(&self) -> (f64, f64) {
+        (-self.back_side(0), -self.back_side(1))
+    }
+
+    /// Back panes with their grid lines, and tick marks on the labelled
+    /// edges. Draw before the data.
+    pub fn draw_back(&self, cx: &mut Cx2d, draw_line: &mut DrawPlotLine) {
+        if !self.show_box {
+            return;
+        }
+        let grid_color = vec4(0.85, 0.85, 0.85, 1.0);
+        let edge_color = vec4(0.55, 0.55, 0.55, 1.0);
+        for axis in 0..3 {
+            let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+            let level = self.back_side(axis) * Self::extent(axis);
+            let point = |u: f64, v: f64| {
+                let mut p = [0.0; 3];
+                p[axis] = level;
+                p[a] = u;
+                p[b] = v;
+                self.project_box(p)
+            };
+            let (ea, eb) = (Self::extent(a), Self::extent(b));
+
+            draw_line.color = grid_color;
+            for (u, _) in self.box_ticks(a) {
+                draw_line.draw_line(cx, point(u, -eb), point(u, eb), 1.0);
+            }
+            for (v, _) in self.box_ticks(b) {
+                draw_line.draw_line(cx, point(-ea, v), point(ea, v), 1.0);
+            }
+
+            draw_line.color = edge_color;
+            let corners = [point(-ea, -eb), point(ea, -eb), point(ea, eb), point(-ea, eb)];
+            for k in 0..4 {
+                draw_line.draw_line(cx, corners[k], corners[(k + 1) % 4], 1.0);
+            }
+        }
+
+        let (front_x, front_y) = self.front_edges();
+        let floor = self.floor();
+        for (x, _) in self.box_ticks(0) {
+            let from = self.project_box([x, front_y, floor]);
+            let to = self.project_box([x, front_y * (1.0 + TICK_MARK_LENGTH), floor]);
+            draw_line.draw_line(cx, from, to, 1.0);
+        }
+        for (y, _) in self.box_ticks(1) {
+            let from = self.project_box([front_x, y, floor]);
+            let to = self.project_box([front_x * (1.0 + TICK_MARK_LENGTH), y, floor]);
+            draw_line.draw_line(cx, from, to, 1.0);
+        }
+        let (edge_x, edge_y) = self.z_edge();
+        for (z, _) in self.box_ticks(2) {
+            let from = self.project_box([edge_x, edge_y, z]);
+            draw_line.draw_line(cx, from, from - dvec2(4.0, 0.0), 1.0);
+        }
+    }
+
+    /// Tick labels and axis titles. Draw after the data.
+    pub fn draw_labels(&self, cx: &mut Cx2d, label: &mut PlotLabel) {
+        if !self.show_box {
+            return;
+        }
+        let (front_x, front_y) = self.front_edges();
+        let floor = self.floor();
+
+        label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
+        for (x, text) in self.box_ticks(0) {
+            let pos = self.project_box([x, front_y * (1.0 + TICK_LABEL_OFFSET), floor]);
+            label.draw_at(cx, pos, &text, TextAnchor::Center);
+        }
+        for (y, text) in self.box_ticks(1) {
+            let pos = self.project_box([front_x * (1.0 + TICK_LABEL_OFFSET), y, floor]);
+            label.draw_at(cx, pos, &text, TextAnchor::Center);
+        }
+        let (edge_x, edge_y) = self.z_edge();
+        for (z, text) in self.box_ticks(2) {
+            let pos = self.project_box([edge_x, edge_y, z]);
+            label.draw_at(cx, pos - dvec2(6.0, 0.0), &text, TextAnchor::MiddleRight);
+        }
+
+        label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+        if !self.x_label.is_empty() {
+            let pos = self.project_box([0.0, front_y * (1.0 + AXIS_TITLE_OFFSET), floor]);
+            label.draw_at(cx, pos, &self.x_label, TextAnchor::Center);
+        }
+        if !self.y_label.is_empty() {
+            let pos = self.project_box([front_x * (1.0 + AXIS_TITLE_OFFSET), 0.0, floor]);
+            label.draw_at(cx, pos, &self.y_label, TextAnchor::Center);
+        }
+        if !self.z_label.is_empty() {
+            let pos = self.project_box([edge_x, edge_y, BOX_HALF_HEIGHT]);
+            label.draw_at(cx, pos - dvec2(0.0, 6.0), &self.z_label, TextAnchor::BottomCenter);
+        }
+    }
+}
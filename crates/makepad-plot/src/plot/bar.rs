@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -59,24 +60,12 @@ pub struct BarPlot {
     #[rust]
     bar_color: Option<Vec4>,
 
-    #[rust]
-    plot_area: PlotArea,
+    #[rust(Axes::with_margins(50.0, 30.0, 20.0, 40.0))]
+    axes: Axes,
 
     #[rust]
     title: String,
 
-    #[rust(50.0)]
-    left_margin: f64,
-
-    #[rust(40.0)]
-    bottom_margin: f64,
-
-    #[rust(20.0)]
-    right_margin: f64,
-
-    #[rust(30.0)]
-    top_margin: f64,
-
     #[rust(0.8)]
     bar_width_ratio: f64,
 
@@ -92,6 +81,9 @@ pub struct BarPlot {
 
     #[rust]
     show_bar_labels: bool,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for BarPlot {
@@ -101,19 +93,31 @@ impl Widget for BarPlot {
 
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
         let has_data = !self.values.is_empty() || !self.groups.is_empty();
         if rect.size.x > 0.0 && rect.size.y > 0.0 && has_data {
-            self.update_plot_area(rect);
-            self.draw_grid(cx);
-            self.draw_axes(cx);
+            self.update_axes(rect);
+            self.axes.draw_grid(cx, &mut self.draw_line);
+            self.axes.draw_axes(cx, &mut self.draw_line);
             self.draw_bars(cx);
             self.draw_labels(cx);
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 }
 
+impl PlotExport for BarPlot {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl BarPlot {
     /// Set bar data (simple mode - single series)
     pub fn set_data(&mut self, categories: Vec<String>, values: Vec<f64>) {
@@ -166,15 +170,32 @@ impl BarPlot {
         self.groups.clear();
     }
 
-    fn update_plot_area(&mut self, rect: Rect) {
-        let left_margin = if self.horizontal { 80.0 } else { self.left_margin };
-        let bottom_margin = if self.horizontal { self.bottom_margin } else { 40.0 };
-        self.plot_area = PlotArea::new(
-            rect.pos.x + left_margin,
-            rect.pos.y + self.top_margin,
-            rect.pos.x + rect.size.x - self.right_margin,
-            rect.pos.y + rect.size.y - bottom_margin,
-        );
+    /// Values run along y (x when horizontal) from zero to a nice bound; the
+    /// category axis is labelled by the chart
+    fn update_axes(&mut self, rect: Rect) {
+        let (v_min, v_max) = self.get_value_range();
+        let value_range = nice_bounds(v_min, v_max, self.axes.y.tick_count);
+
+        // Wider left margin for category names
+        self.axes.margins.left = if self.horizontal { 80.0 } else { 50.0 };
+
+        let (value_axis, category_axis) = if self.horizontal {
+            (&mut self.axes.x, &mut self.axes.y)
+        } else {
+            (&mut self.axes.y, &mut self.axes.x)
+        };
+        value_axis.range = value_range;
+        value_axis.show_grid = true;
+        value_axis.show_labels = true;
+        category_axis.range = (0.0, 1.0);
+        category_axis.show_grid = false;
+        category_axis.show_labels = false;
+
+        self.axes.update_plot_area(rect);
+    }
+
+    fn value_range(&self) -> (f64, f64) {
+        if self.horizontal { self.axes.x.range } else { self.axes.y.range }
     }
 
     fn get_value_range(&self) -> (f64, f64) {
@@ -204,59 +225,8 @@ impl BarPlot {
         }
     }
 
-    fn draw_grid(&mut self, cx: &mut Cx2d) {
-        self.draw_line.color = vec4(0.9, 0.9, 0.9, 1.0);
-
-        let (v_min, v_max) = self.get_value_range();
-        let v_ticks = self.generate_ticks(v_min, v_max, 5);
-
-        if self.horizontal {
-            // Vertical grid lines for horizontal bars
-            for v in &v_ticks {
-                let x_pixel = self.plot_area.left + (*v - v_min) / (v_max - v_min) * self.plot_area.width();
-                let p1 = dvec2(x_pixel, self.plot_area.top);
-                let p2 = dvec2(x_pixel, self.plot_area.bottom);
-                self.draw_line.draw_line(cx, p1, p2, 0.5);
-            }
-        } else {
-            // Horizontal grid lines for vertical bars
-            for v in &v_ticks {
-                let y_pixel = self.plot_area.bottom - (*v - v_min) / (v_max - v_min) * self.plot_area.height();
-                let p1 = dvec2(self.plot_area.left, y_pixel);
-                let p2 = dvec2(self.plot_area.right, y_pixel);
-                self.draw_line.draw_line(cx, p1, p2, 0.5);
-            }
-        }
-    }
-
-    fn draw_axes(&mut self, cx: &mut Cx2d) {
-        self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-
-        if self.horizontal {
-            // X axis (bottom)
-            let x1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-            let x2 = dvec2(self.plot_area.right, self.plot_area.bottom);
-            self.draw_line.draw_line(cx, x1, x2, 1.0);
-
-            // Y axis (left)
-            let y1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-            let y2 = dvec2(self.plot_area.left, self.plot_area.top);
-            self.draw_line.draw_line(cx, y1, y2, 1.0);
-        } else {
-            // X axis
-            let x1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-            let x2 = dvec2(self.plot_area.right, self.plot_area.bottom);
-            self.draw_line.draw_line(cx, x1, x2, 1.0);
-
-            // Y axis
-            let y1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-            let y2 = dvec2(self.plot_area.left, self.plot_area.top);
-            self.draw_line.draw_line(cx, y1, y2, 1.0);
-        }
-    }
-
     fn draw_bars(&mut self, cx: &mut Cx2d) {
-        let (v_min, v_max) = self.get_value_range();
+        let (v_min, v_max) = self.value_range();
 
         if !self.groups.is_empty() {
             self.draw_grouped_bars(cx, v_min, v_max);
@@ -272,18 +242,18 @@ impl BarPlot {
         }
 
         if self.horizontal {
-            let band_height = self.plot_area.height() / n as f64;
+            let band_height = self.axes.plot_area.height() / n as f64;
             let bar_height = band_height * self.bar_width_ratio;
 
             for (i, value) in self.values.iter().enumerate() {
                 let color = self.bar_color.unwrap_or_else(|| get_color(0));
                 self.draw_bar.color = color;
 
-                let y_center = self.plot_area.top + (i as f64 + 0.5) * band_height;
-                let bar_width = (*value - v_min) / (v_max - v_min) * self.plot_area.width();
+                let y_center = self.axes.plot_area.top + (i as f64 + 0.5) * band_height;
+                let bar_width = (*value - v_min) / (v_max - v_min) * self.axes.plot_area.width();
 
                 let rect = Rect {
-                    pos: dvec2(self.plot_area.left, y_center - bar_height / 2.0),
+                    pos: dvec2(self.axes.plot_area.left, y_center - bar_height / 2.0),
                     size: dvec2(bar_width, bar_height),
                 };
                 self.draw_bar.draw_bar(cx, rect);
@@ -292,20 +262,20 @@ impl BarPlot {
                 if self.show_bar_labels {
                     self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
                     let label = format!("{:.1}", value);
-                    self.label.draw_at(cx, dvec2(self.plot_area.left + bar_width + 5.0, y_center), &label, TextAnchor::MiddleLeft);
+                    self.label.draw_at(cx, dvec2(self.axes.plot_area.left + bar_width + 5.0, y_center), &label, TextAnchor::MiddleLeft);
                 }
             }
         } else {
-            let band_width = self.plot_area.width() / n as f64;
+            let band_width = self.axes.plot_area.width() / n as f64;
             let bar_width = band_width * self.bar_width_ratio;
 
             for (i, value) in self.values.iter().enumerate() {
                 let color = self.bar_color.unwrap_or_else(|| get_color(0));
                 self.draw_bar.color = color;
 
-                let x_center = self.plot_area.left + (i as f64 + 0.5) * band_width;
-                let bar_height = (*value - v_min) / (v_max - v_min) * self.plot_area.height();
-                let bar_top = self.plot_area.bottom - bar_height;
+                let x_center = self.axes.plot_area.left + (i as f64 + 0.5) * band_width;
+                let bar_height = (*value - v_min) / (v_max - v_min) * self.axes.plot_area.height();
+                let bar_top = self.axes.plot_area.bottom - bar_height;
 
                 let rect = Rect {
                     pos: dvec2(x_center - bar_width / 2.0, bar_top),
@@ -341,19 +311,19 @@ impl BarPlot {
         let num_cats = self.categories.len();
 
         if self.horizontal {
-            let band_height = self.plot_area.height() / num_cats as f64;
+            let band_height = self.axes.plot_area.height() / num_cats as f64;
             let bar_height = band_height * self.bar_width_ratio;
 
             for cat_idx in 0..num_cats {
-                let y_center = self.plot_area.top + (cat_idx as f64 + 0.5) * band_height;
-                let mut x_start = self.plot_area.left;
+                let y_center = self.axes.plot_area.top + (cat_idx as f64 + 0.5) * band_height;
+                let mut x_start = self.axes.plot_area.left;
 
                 for (group_idx, group) in self.groups.iter().enumerate() {
                     if let Some(&value) = group.values.get(cat_idx) {
                         let color = group.color.unwrap_or_else(|| get_color(group_idx));
                         self.draw_bar.color = color;
 
-                        let bar_width = (value - v_min) / (v_max - v_min) * self.plot_area.width();
+                        let bar_width = (value - v_min) / (v_max - v_min) * self.axes.plot_area.width();
                         let rect = Rect {
                             pos: dvec2(x_start, y_center - bar_height / 2.0),
                             size: dvec2(bar_width, bar_height),
@@ -364,19 +334,19 @@ impl BarPlot {
                 }
             }
         } else {
-            let band_width = self.plot_area.width() / num_cats as f64;
+            let band_width = self.axes.plot_area.width() / num_cats as f64;
             let bar_width = band_width * self.bar_width_ratio;
 
             for cat_idx in 0..num_cats {
-                let x_center = self.plot_area.left + (cat_idx as f64 + 0.5) * band_width;
-                let mut y_bottom = self.plot_area.bottom;
+                let x_center = self.axes.plot_area.left + (cat_idx as f64 + 0.5) * band_width;
+                let mut y_bottom = self.axes.plot_area.bottom;
 
                 for (group_idx, group) in self.groups.iter().enumerate() {
                     if let Some(&value) = group.values.get(cat_idx) {
                         let color = group.color.unwrap_or_else(|| get_color(group_idx));
                         self.draw_bar.color = color;
 
-                        let bar_height = (value - v_min) / (v_max - v_min) * self.plot_area.height();
+                        let bar_height = (value - v_min) / (v_max - v_min) * self.axes.plot_area.height();
                         let bar_top = y_bottom - bar_height;
                         let rect = Rect {
                             pos: dvec2(x_center - bar_width / 2.0, bar_top),
@@ -395,11 +365,11 @@ impl BarPlot {
         let num_groups = self.groups.len();
 
         if self.horizontal {
-            let band_height = self.plot_area.height() / num_cats as f64;
+            let band_height = self.axes.plot_area.height() / num_cats as f64;
             let group_height = band_height * self.bar_width_ratio / num_groups as f64;
 
             for cat_idx in 0..num_cats {
-                let y_start = self.plot_area.top + (cat_idx as f64 + 0.5) * band_height
+                let y_start = self.axes.plot_area.top + (cat_idx as f64 + 0.5) * band_height
                     - (band_height * self.bar_width_ratio) / 2.0;
 
                 for (group_idx, group) in self.groups.iter().enumerate() {
@@ -408,9 +378,9 @@ impl BarPlot {
                         self.draw_bar.color = color;
 
                         let y_pos = y_start + group_idx as f64 * group_height;
-                        let bar_width = (value - v_min) / (v_max - v_min) * self.plot_area.width();
+                        let bar_width = (value - v_min) / (v_max - v_min) * self.axes.plot_area.width();
                         let rect = Rect {
-                            pos: dvec2(self.plot_area.left, y_pos),
+                            pos: dvec2(self.axes.plot_area.left, y_pos),
                             size: dvec2(bar_width, group_height * 0.9),
                         };
                         self.draw_bar.draw_bar(cx, rect);
@@ -418,11 +388,11 @@ impl BarPlot {
                 }
             }
         } else {
-            let band_width = self.plot_area.width() / num_cats as f64;
+            let band_width = self.axes.plot_area.width() / num_cats as f64;
             let group_width = band_width * self.bar_width_ratio / num_groups as f64;
 
             for cat_idx in 0..num_cats {
-                let x_start = self.plot_area.left + (cat_idx as f64 + 0.5) * band_width
+                let x_start = self.axes.plot_area.left + (cat_idx as f64 + 0.5) * band_width
                     - (band_width * self.bar_width_ratio) / 2.0;
 
                 for (group_idx, group) in self.groups.iter().enumerate() {
@@ -431,8 +401,8 @@ impl BarPlot {
                         self.draw_bar.color = color;
 
                         let x_pos = x_start + group_idx as f64 * group_width;
-                        let bar_height = (value - v_min) / (v_max - v_min) * self.plot_area.height();
-                        let bar_top = self.plot_area.bottom - bar_height;
+                        let bar_height = (value - v_min) / (v_max - v_min) * self.axes.plot_area.height();
+                        let bar_top = self.axes.plot_area.bottom - bar_height;
                         let rect = Rect {
                             pos: dvec2(x_pos, bar_top),
                             size: dvec2(group_width * 0.9, bar_height),
@@ -448,51 +418,31 @@ impl BarPlot {
         self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
 
         let n = self.categories.len().max(self.values.len());
-        let (v_min, v_max) = self.get_value_range();
 
         if self.horizontal {
             // Category labels on Y axis
-            let band_height = self.plot_area.height() / n as f64;
+            let band_height = self.axes.plot_area.height() / n as f64;
             for (i, cat) in self.categories.iter().enumerate() {
-                let y = self.plot_area.top + (i as f64 + 0.5) * band_height;
-                self.label.draw_at(cx, dvec2(self.plot_area.left - 5.0, y), cat, TextAnchor::MiddleRight);
-            }
-
-            // Value tick labels on X axis
-            let v_ticks = self.generate_ticks(v_min, v_max, 5);
-            for v in &v_ticks {
-                let x_pixel = self.plot_area.left + (*v - v_min) / (v_max - v_min) * self.plot_area.width();
-                let label = format!("{:.0}", v);
-                self.label.draw_at(cx, dvec2(x_pixel, self.plot_area.bottom + 5.0), &label, TextAnchor::TopCenter);
+                let y = self.axes.plot_area.top + (i as f64 + 0.5) * band_height;
+                self.label.draw_at(cx, dvec2(self.axes.plot_area.left - 5.0, y), cat, TextAnchor::MiddleRight);
             }
         } else {
             // Category labels on X axis
-            let band_width = self.plot_area.width() / n as f64;
+            let band_width = self.axes.plot_area.width() / n as f64;
             for (i, cat) in self.categories.iter().enumerate() {
-                let x = self.plot_area.left + (i as f64 + 0.5) * band_width;
-                self.label.draw_at(cx, dvec2(x, self.plot_area.bottom + 5.0), cat, TextAnchor::TopCenter);
-            }
-
-            // Value tick labels on Y axis
-            let v_ticks = self.generate_ticks(v_min, v_max, 5);
-            for v in &v_ticks {
-                let y_pixel = self.plot_area.bottom - (*v - v_min) / (v_max - v_min) * self.plot_area.height();
-                let label = format!("{:.0}", v);
-                self.label.draw_at(cx, dvec2(self.plot_area.left - 5.0, y_pixel), &label, TextAnchor::MiddleRight);
+                let x = self.axes.plot_area.left + (i as f64 + 0.5) * band_width;
+                self.label.draw_at(cx, dvec2(x, self.axes.plot_area.bottom + 5.0), cat, TextAnchor::TopCenter);
             }
         }
 
+        self.axes.draw_tick_labels(cx, &mut self.label);
+
         // Title
         if !self.title.is_empty() {
-            let center_x = (self.plot_area.left + self.plot_area.right) / 2.0;
-            self.label.draw_at(cx, dvec2(center_x, self.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
+            let center_x = (self.axes.plot_area.left + self.axes.plot_area.right) / 2.0;
+            self.label.draw_at(cx, dvec2(center_x, self.axes.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
         }
     }
-
-    fn generate_ticks(&self, min: f64, max: f64, count: usize) -> Vec<f64> {
-        let step = (max - min) / count as f64;
-        (0..=count).map(|i| min + i as f64 * step).collect()
-    }
 }
 
 impl BarPlotRef {
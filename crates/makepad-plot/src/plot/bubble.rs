@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -82,6 +83,10 @@ pub struct BubbleChart {
     #[rust] max_bubble_radius: f64,
     #[rust] min_bubble_radius: f64,
     #[rust] use_gradient: bool,
+    #[rust(Axes::with_margins(60.0, 40.0, 40.0, 50.0))] axes: Axes,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl BubbleChart {
@@ -156,58 +161,31 @@ impl BubbleChart {
 impl Widget for BubbleChart {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 {
             // Set defaults
             if self.max_bubble_radius == 0.0 { self.max_bubble_radius = 40.0; }
             if self.min_bubble_radius == 0.0 { self.min_bubble_radius = 5.0; }
 
-            let padding_left = 60.0;
-            let padding_right = 40.0;
-            let padding_top = 40.0;
-            let padding_bottom = 50.0;
-
-            let plot_left = rect.pos.x + padding_left;
-            let plot_top = rect.pos.y + padding_top;
-            let plot_right = rect.pos.x + rect.size.x - padding_right;
-            let plot_bottom = rect.pos.y + rect.size.y - padding_bottom;
-            let plot_width = plot_right - plot_left;
-            let plot_height = plot_bottom - plot_top;
+            self.axes.set_show_grid(self.show_grid);
+            self.axes.update_plot_area(rect);
+            let plot_left = self.axes.plot_area.left;
+            let plot_right = self.axes.plot_area.right;
+            let plot_bottom = self.axes.plot_area.bottom;
+            let plot_width = self.axes.plot_area.width();
+            let plot_height = self.axes.plot_area.height();
 
             let (x_min, x_max, y_min, y_max, size_min, size_max) = self.get_data_bounds();
             let x_range = (x_max - x_min).max(0.001);
             let y_range = (y_max - y_min).max(0.001);
             let size_range = (size_max - size_min).max(0.001);
+            self.axes.x.range = (x_min, x_min + x_range);
+            self.axes.y.range = (y_min, y_min + y_range);
 
-            // Draw grid
-            if self.show_grid {
-                self.draw_line.color = vec4(0.9, 0.9, 0.9, 1.0);
-                for i in 0..=5 {
-                    let t = i as f64 / 5.0;
-                    let x = plot_left + t * plot_width;
-                    let y = plot_top + t * plot_height;
-                    self.draw_line.draw_line(cx, dvec2(x, plot_top), dvec2(x, plot_bottom), 1.0);
-                    self.draw_line.draw_line(cx, dvec2(plot_left, y), dvec2(plot_right, y), 1.0);
-                }
-            }
-
-            // Draw axes
-            self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-            self.draw_line.draw_line(cx, dvec2(plot_left, plot_bottom), dvec2(plot_right, plot_bottom), 1.5);
-            self.draw_line.draw_line(cx, dvec2(plot_left, plot_top), dvec2(plot_left, plot_bottom), 1.5);
-
-            // Draw axis tick labels
-            self.label.draw_text.color = vec4(0.3, 0.3, 0.3, 1.0);
-            for i in 0..=5 {
-                let t = i as f64 / 5.0;
-                let x_val = x_min + t * x_range;
-                let y_val = y_min + (1.0 - t) * y_range;
-                let x = plot_left + t * plot_width;
-                let y = plot_top + t * plot_height;
-
-                self.label.draw_at(cx, dvec2(x, plot_bottom + 15.0), &format!("{:.1}", x_val), TextAnchor::TopCenter);
-                self.label.draw_at(cx, dvec2(plot_left - 10.0, y), &format!("{:.1}", y_val), TextAnchor::MiddleRight);
-            }
+            self.axes.draw_grid(cx, &mut self.draw_line);
+            self.axes.draw_axes(cx, &mut self.draw_line);
+            self.axes.draw_tick_labels(cx, &mut self.label);
 
             // Draw bubbles
             for series in &self.series {
@@ -269,6 +247,7 @@ impl Widget for BubbleChart {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -277,6 +256,16 @@ impl Widget for BubbleChart {
     }
 }
 
+impl PlotExport for BubbleChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl BubbleChart {
     pub fn redraw(&mut self, cx: &mut Cx) {
         self.view.redraw(cx);
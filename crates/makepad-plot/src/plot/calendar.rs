@@ -0,0 +1,684 @@
+// Calendar heatmap - one cell per day colored through a colormap, laid out
+// GitHub-style as week columns or as twelve month blocks, with year
+// selection and hover values
+
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use crate::export::*;
+use super::*;
+use super::time_axis::MONTH_NAMES;
+use std::collections::BTreeMap;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::text::PlotLabel;
+
+    pub CalendarHeatmap = {{CalendarHeatmap}} {
+        width: Fill,
+        height: Fill,
+        label: <PlotLabel> {}
+    }
+}
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Weekday names from Sunday, as `(days since 1970-01-01 + 4) % 7` counts
+const WEEKDAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// Share of a cell's pitch left as the gap between cells
+const CELL_GAP: f64 = 0.15;
+
+const YEAR_BUTTON_WIDTH: f64 = 56.0;
+const YEAR_BUTTON_HEIGHT: f64 = 22.0;
+
+/// Swatches in the "Less … More" legend
+const LEGEND_STEPS: usize = 5;
+
+/// Offset of the tooltip from the pointer
+const TOOLTIP_OFFSET: f64 = 12.0;
+
+/// How the days of a year are arranged
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CalendarLayout {
+    /// One column per week and one row per weekday, like a contribution graph
+    #[default]
+    Week,
+    /// Twelve small month calendars
+    Month,
+}
+
+/// Actions emitted by the calendar
+#[derive(Clone, Debug, DefaultNone)]
+pub enum CalendarAction {
+    None,
+    /// A day was clicked; `time` is its midnight UTC in Unix seconds
+    DayClicked { date: String, time: f64, value: Option<f64> },
+    /// Another year was picked with the year buttons
+    YearChanged { year: i32 },
+}
+
+/// `YYYY-MM-DD` for days since 1970-01-01
+pub fn format_day(day: i64) -> String {
+    let (year, month, date) = civil_from_days(day);
+    format!("{:04}-{:02}-{:02}", year, month, date)
+}
+
+/// Weekday of a day, counted from `week_start` (0 = Sunday)
+fn weekday(day: i64, week_start: u32) -> i64 {
+    (day + 4 - week_start as i64).rem_euclid(7)
+}
+
+/// Day, column and row of every day of `year` in the week layout
+fn week_cells(year: i32, week_start: u32) -> Vec<(i64, usize, usize)> {
+    let first = days_from_civil(year as i64, 1, 1);
+    let last = days_from_civil(year as i64 + 1, 1, 1);
+    let offset = weekday(first, week_start);
+    (first..last)
+        .map(|day| {
+            let slot = (day - first + offset) as usize;
+            (day, slot / 7, slot % 7)
+        })
+        .collect()
+}
+
+/// Day, column and row of every day of `month` (1-12) in its month block
+fn month_cells(year: i32, month: u32, week_start: u32) -> Vec<(i64, usize, usize)> {
+    let first = days_from_civil(year as i64, month, 1);
+    let next = if month == 12 { days_from_civil(year as i64 + 1, 1, 1) } else { days_from_civil(year as i64, month + 1, 1) };
+    let offset = weekday(first, week_start);
+    (first..next)
+        .map(|day| {
+            let slot = (day - first + offset) as usize;
+            (day, slot % 7, slot / 7)
+        })
+        .collect()
+}
+
+/// Columns of month blocks that give the largest cells in `size`
+fn month_columns(size: DVec2) -> (usize, f64) {
+    // A block is 7 cells plus a one-cell gap wide and a header plus six
+    // weeks tall
+    [2, 3, 4, 6, 12]
+        .into_iter()
+        .map(|columns| {
+            let rows = 12 / columns;
+            let pitch = (size.x / (columns as f64 * 8.0)).min(size.y / (rows as f64 * 7.5));
+            (columns, pitch)
+        })
+        .fold((3, 0.0), |best, option| if option.1 > best.1 { option } else { best })
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct CalendarHeatmap {
+    #[deref] #[live] view: View,
+    #[live] draw_bar: DrawPlotBar,
+    #[live] draw_line: DrawPlotLine,
+    #[live] draw_fill: DrawPlotFill,
+    #[live] label: PlotLabel,
+    #[rust] title: String,
+    /// Value per day (days since 1970-01-01)
+    #[rust] days: BTreeMap<i64, f64>,
+    /// Year shown; the latest year with data when None
+    #[rust] year: Option<i32>,
+    #[rust] layout: CalendarLayout,
+    /// First day of the week, 0 = Sunday
+    #[rust] week_start: u32,
+    #[rust(Colormap::Greens)] colormap: Colormap,
+    /// Colormap limits; zero to the year's largest value when None
+    #[rust] value_range: Option<(f64, f64)>,
+    #[rust(vec4(0.92, 0.93, 0.94, 1.0))] empty_color: Vec4,
+    #[rust(true)] show_legend: bool,
+    #[rust(true)] show_year_buttons: bool,
+
+    /// Cells and year buttons of the last draw, for hit testing
+    #[rust] cells: Vec<(i64, Rect)>,
+    #[rust] year_buttons: Vec<(i32, Rect)>,
+    #[rust] grid_rect: Rect,
+    #[rust] hovered: Option<i64>,
+    #[rust] hovered_year: Option<i32>,
+    #[rust] pointer: DVec2,
+    #[rust] press: Option<DVec2>,
+    #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
+}
+
+impl CalendarHeatmap {
+    /// Values at Unix times in seconds; values on the same UTC day add up
+    pub fn set_data(&mut self, data: Vec<(f64, f64)>) {
+        let mut days = BTreeMap::new();
+        for (time, value) in data {
+            if time.is_finite() && value.is_finite() {
+                *days.entry((time / SECONDS_PER_DAY).floor() as i64).or_insert(0.0) += value;
+            }
+        }
+        if days != self.days {
+            self.days = days;
+            self.hovered = None;
+        }
+    }
+
+    /// Values by `(year, month, day)`; values on the same day add up
+    pub fn set_dates(&mut self, data: Vec<((i32, u32, u32), f64)>) {
+        let data = data
+            .into_iter()
+            .map(|((year, month, day), value)| (days_from_civil(year as i64, month, day) as f64 * SECONDS_PER_DAY, value))
+            .collect();
+        self.set_data(data);
+    }
+
+    pub fn set_year(&mut self, year: Option<i32>) {
+        if year != self.year {
+            self.year = year;
+            self.hovered = None;
+        }
+    }
+
+    pub fn set_layout(&mut self, layout: CalendarLayout) {
+        self.layout = layout;
+    }
+
+    /// Start weeks on Monday instead of Sunday
+    pub fn set_monday_first(&mut self, monday_first: bool) {
+        self.week_start = u32::from(monday_first);
+    }
+
+    pub fn set_colormap(&mut self, colormap: Colormap) {
+        self.colormap = colormap;
+    }
+
+    pub fn set_value_range(&mut self, range: Option<(f64, f64)>) {
+        self.value_range = range;
+    }
+
+    /// Fill of days without a value
+    pub fn set_empty_color(&mut self, color: Vec4) {
+        self.empty_color = color;
+    }
+
+    pub fn set_show_legend(&mut self, show: bool) {
+        self.show_legend = show;
+    }
+
+    pub fn set_show_year_buttons(&mut self, show: bool) {
+        self.show_year_buttons = show;
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    pub fn clear(&mut self) {
+        self.days.clear();
+        self.year = None;
+        self.hovered = None;
+    }
+
+    pub fn redraw(&mut self, cx: &mut Cx) {
+        self.view.redraw(cx);
+        self.area.redraw(cx);
+    }
+
+    /// Years with data, newest first
+    pub fn years(&self) -> Vec<i32> {
+        let mut years: Vec<i32> = self.days.keys().map(|&day| civil_from_days(day).0 as i32).collect();
+        years.dedup();
+        years.reverse();
+        years
+    }
+
+    /// Year on screen: the chosen one, else the latest with data, else this year
+    pub fn shown_year(&self) -> i32 {
+        self.year
+            .or_else(|| self.days.keys().next_back().map(|&day| civil_from_days(day).0 as i32))
+            .unwrap_or_else(|| {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0.0, |d| d.as_secs_f64());
+                civil_from_days((now / SECONDS_PER_DAY).floor() as i64).0 as i32
+            })
+    }
+
+    pub fn value(&self, day: i64) -> Option<f64> {
+        self.days.get(&day).copied()
+    }
+
+    fn value_range(&self, year: i32) -> (f64, f64) {
+        self.value_range.unwrap_or_else(|| {
+            let first = days_from_civil(year as i64, 1, 1);
+            let last = days_from_civil(year as i64 + 1, 1, 1);
+            self.days
+                .range(first..last)
+                .fold((0.0f64, f64::NEG_INFINITY), |(lo, hi), (_, &v)| (lo.min(v), hi.max(v)))
+        })
+    }
+
+    fn cell_color(&self, value: Option<f64>, norm: &Normalize) -> Vec4 {
+        match value {
+            Some(value) => self.colormap.sample(norm.normalize(value)),
+            None => self.empty_color,
+        }
+    }
+
+    fn draw_cell(&mut self, cx: &mut Cx2d, day: i64, rect: Rect, norm: &Normalize) {
+        self.draw_bar.color = self.cell_color(self.value(day), norm);
+        self.draw_bar.draw_bar(cx, rect);
+        self.cells.push((day, rect));
+    }
+
+    /// Week columns under month labels, with weekday labels on the left
+    fn draw_week_layout(&mut self, cx: &mut Cx2d, rect: Rect, year: i32, norm: &Normalize) {
+        let cells = week_cells(year, self.week_start);
+        let columns = cells.last().map_or(53, |&(_, column, _)| column + 1);
+        let (left, top) = (30.0, 18.0);
+        let pitch = ((rect.size.x - left) / columns as f64).min((rect.size.y - top) / 7.0).max(0.0);
+        let gap = pitch * CELL_GAP;
+        let width = left + pitch * columns as f64;
+        let origin = dvec2(rect.pos.x + left + (rect.size.x - width).max(0.0) * 0.5, rect.pos.y + top);
+        self.grid_rect = Rect { pos: origin, size: dvec2(pitch * columns as f64, pitch * 7.0) };
+
+        for &(day, column, row) in &cells {
+            let pos = origin + dvec2(column as f64, row as f64) * pitch;
+            self.draw_cell(cx, day, Rect { pos, size: dvec2(pitch - gap, pitch - gap) }, norm);
+        }
+
+        self.label.set_font_size(9.0);
+        self.label.set_color(vec4(0.4, 0.4, 0.4, 1.0));
+        // A month's label sits over its first week that starts inside it
+        let first = days_from_civil(year as i64, 1, 1);
+        let offset = weekday(first, self.week_start);
+        for (month, name) in MONTH_NAMES.iter().enumerate() {
+            let start = days_from_civil(year as i64, month as u32 + 1, 1) - first + offset;
+            let column = (start + 6) / 7;
+            if (column as usize) < columns {
+                self.label.draw_at(cx, origin + dvec2(column as f64 * pitch, -4.0), name, TextAnchor::BottomLeft);
+            }
+        }
+        for name in ["Mon", "Wed", "Fri"] {
+            let index = WEEKDAY_NAMES.iter().position(|n| *n == name).unwrap_or(0) as i64;
+            let row = (index - self.week_start as i64).rem_euclid(7);
+            let y = origin.y + (row as f64 + 0.5) * pitch - gap * 0.5;
+            self.label.draw_at(cx, dvec2(origin.x - 6.0, y), name, TextAnchor::MiddleRight);
+        }
+    }
+
+    /// Twelve month calendars, each under its month name
+    fn draw_month_layout(&mut self, cx: &mut Cx2d, rect: Rect, year: i32, norm: &Normalize) {
+        let (columns, pitch) = month_columns(rect.size);
+        let rows = 12 / columns;
+        let gap = pitch * CELL_GAP;
+        let block = dvec2(pitch * 8.0, pitch * 7.5);
+        let size = dvec2(block.x * columns as f64 - pitch, block.y * rows as f64);
+        let origin = rect.pos + dvec2((rect.size.x - size.x).max(0.0) * 0.5, 0.0);
+        self.grid_rect = Rect { pos: origin, size };
+
+        self.label.set_font_size(9.0);
+        for (month, name) in MONTH_NAMES.iter().enumerate() {
+            let corner = origin + dvec2((month % columns) as f64 * block.x, (month / columns) as f64 * block.y);
+            self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
+            self.label.draw_at(cx, corner + dvec2(0.0, pitch * 1.2), name, TextAnchor::BottomLeft);
+            for (day, column, row) in month_cells(year, month as u32 + 1, self.week_start) {
+                let pos = corner + dvec2(column as f64 * pitch, (row as f64 + 1.5) * pitch);
+                self.draw_cell(cx, day, Rect { pos, size: dvec2(pitch - gap, pitch - gap) }, norm);
+            }
+        }
+    }
+
+    /// Years with data stacked down the right edge, the shown one filled
+    fn draw_year_buttons(&mut self, cx: &mut Cx2d, rect: Rect, shown: i32) {
+        self.year_buttons.clear();
+        self.label.set_font_size(10.0);
+        let x = rect.pos.x + rect.size.x - YEAR_BUTTON_WIDTH;
+        for (i, year) in self.years().into_iter().enumerate() {
+            let button = Rect {
+                pos: dvec2(x, rect.pos.y + i as f64 * (YEAR_BUTTON_HEIGHT + 4.0)),
+                size: dvec2(YEAR_BUTTON_WIDTH, YEAR_BUTTON_HEIGHT),
+            };
+            if button.pos.y + button.size.y > rect.pos.y + rect.size.y {
+                break;
+            }
+            let selected = year == shown;
+            self.draw_fill.color = if selected {
+                get_color(0)
+            } else if self.hovered_year == Some(year) {
+                vec4(0.9, 0.91, 0.93, 1.0)
+            } else {
+                vec4(0.96, 0.96, 0.97, 1.0)
+            };
+            self.draw_fill.draw_rect(cx, button);
+            self.label.set_color(if selected { vec4(1.0, 1.0, 1.0, 1.0) } else { vec4(0.25, 0.25, 0.3, 1.0) });
+            self.label.draw_at(cx, button.pos + button.size * 0.5, &year.to_string(), TextAnchor::Center);
+            self.year_buttons.push((year, button));
+        }
+    }
+
+    /// "Less" and "More" around a row of colormap swatches
+    fn draw_legend(&mut self, cx: &mut Cx2d, right: f64, y: f64) {
+        let size = 10.0;
+        self.label.set_font_size(9.0);
+        self.label.set_color(vec4(0.4, 0.4, 0.4, 1.0));
+        self.label.draw_at(cx, dvec2(right, y + size * 0.5), "More", TextAnchor::MiddleRight);
+        let more = self.label.measure(cx, "More").x;
+        let mut x = right - more - 4.0 - LEGEND_STEPS as f64 * (size + 2.0);
+        self.label.draw_at(cx, dvec2(x - 4.0, y + size * 0.5), "Less", TextAnchor::MiddleRight);
+        for i in 0..LEGEND_STEPS {
+            self.draw_bar.color = self.colormap.sample(i as f64 / (LEGEND_STEPS - 1) as f64);
+            self.draw_bar.draw_bar(cx, Rect { pos: dvec2(x, y), size: dvec2(size, size) });
+            x += size + 2.0;
+        }
+    }
+
+    fn draw_hover(&mut self, cx: &mut Cx2d, bounds: Rect) {
+        let Some(day) = self.hovered else { return };
+        let Some(&(_, cell)) = self.cells.iter().find(|(d, _)| *d == day) else { return };
+        self.draw_line.color = vec4(0.2, 0.2, 0.25, 1.0);
+        let (a, b) = (cell.pos, cell.pos + cell.size);
+        self.draw_line.draw_line(cx, a, dvec2(b.x, a.y), 1.5);
+        self.draw_line.draw_line(cx, dvec2(b.x, a.y), b, 1.5);
+        self.draw_line.draw_line(cx, b, dvec2(a.x, b.y), 1.5);
+        self.draw_line.draw_line(cx, dvec2(a.x, b.y), a, 1.5);
+
+        let text = match self.value(day) {
+            Some(value) => format!("{}: {}", format_day(day), TickFormatter::Auto.format(value, 0.0)),
+            None => format!("{}: no data", format_day(day)),
+        };
+        self.label.set_font_size(10.0);
+        let padding = 6.0;
+        let size = self.label.measure(cx, &text) + dvec2(padding * 2.0, padding * 2.0);
+        let mut pos = self.pointer + dvec2(TOOLTIP_OFFSET, TOOLTIP_OFFSET);
+        if pos.x + size.x > bounds.pos.x + bounds.size.x {
+            pos.x = self.pointer.x - TOOLTIP_OFFSET - size.x;
+        }
+        if pos.y + size.y > bounds.pos.y + bounds.size.y {
+            pos.y = self.pointer.y - TOOLTIP_OFFSET - size.y;
+        }
+        self.draw_fill.color = vec4(0.7, 0.7, 0.72, 1.0);
+        self.draw_fill.draw_rect(cx, Rect { pos: pos - dvec2(1.0, 1.0), size: size + dvec2(2.0, 2.0) });
+        self.draw_fill.color = vec4(1.0, 1.0, 1.0, 0.95);
+        self.draw_fill.draw_rect(cx, Rect { pos, size });
+        self.label.set_color(vec4(0.15, 0.15, 0.2, 1.0));
+        self.label.draw_at(cx, pos + dvec2(padding, padding), &text, TextAnchor::TopLeft);
+    }
+
+    fn cell_at(&self, pos: DVec2) -> Option<i64> {
+        self.cells.iter().find(|(_, rect)| rect.contains(pos)).map(|&(day, _)| day)
+    }
+
+    fn year_at(&self, pos: DVec2) -> Option<i32> {
+        self.year_buttons.iter().find(|(_, rect)| rect.contains(pos)).map(|&(year, _)| year)
+    }
+}
+
+impl Widget for CalendarHeatmap {
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
+        self.cells.clear();
+        self.year_buttons.clear();
+
+        if rect.size.x > 0.0 && rect.size.y > 0.0 {
+            let year = self.shown_year();
+            let top = if self.title.is_empty() { 10.0 } else { 36.0 };
+            let buttons = self.show_year_buttons && self.years().len() > 1;
+            let right = if buttons { YEAR_BUTTON_WIDTH + 20.0 } else { 10.0 };
+            let bottom = if self.show_legend { 30.0 } else { 10.0 };
+            let body = Rect {
+                pos: rect.pos + dvec2(10.0, top),
+                size: dvec2((rect.size.x - 10.0 - right).max(0.0), (rect.size.y - top - bottom).max(0.0)),
+            };
+
+            let (vmin, vmax) = self.value_range(year);
+            let norm = Normalize::new(vmin, if vmax > vmin { vmax } else { vmin + 1.0 });
+            match self.layout {
+                CalendarLayout::Week => self.draw_week_layout(cx, body, year, &norm),
+                CalendarLayout::Month => self.draw_month_layout(cx, body, year, &norm),
+            }
+            if self.show_legend {
+                let grid = self.grid_rect;
+                self.draw_legend(cx, grid.pos.x + grid.size.x, grid.pos.y + grid.size.y + 8.0);
+            }
+            if buttons {
+                self.draw_year_buttons(cx, Rect { pos: dvec2(rect.pos.x, top + rect.pos.y), size: dvec2(rect.size.x - 10.0, body.size.y) }, year);
+            }
+            self.draw_hover(cx, rect);
+
+            if !self.title.is_empty() {
+                self.label.set_font_size(12.0);
+                self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+                self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + 10.0), &self.title, TextAnchor::TopCenter);
+            }
+        }
+
+        self.capture.end();
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        match event.hits(cx, self.area) {
+            Hit::FingerDown(fe) => {
+                self.press = Some(fe.abs);
+            }
+            Hit::FingerUp(fe) => {
+                if self.press.take().is_none() || !fe.is_over {
+                    return;
+                }
+                let uid = self.widget_uid();
+                if let Some(year) = self.year_at(fe.abs) {
+                    if year != self.shown_year() {
+                        self.set_year(Some(year));
+                        cx.widget_action(uid, &scope.path, CalendarAction::YearChanged { year });
+                        self.redraw(cx);
+                    }
+                } else if let Some(day) = self.cell_at(fe.abs) {
+                    let action = CalendarAction::DayClicked {
+                        date: format_day(day),
+                        time: day as f64 * SECONDS_PER_DAY,
+                        value: self.value(day),
+                    };
+                    cx.widget_action(uid, &scope.path, action);
+                }
+            }
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => {
+                self.pointer = fe.abs;
+                let day = self.cell_at(fe.abs);
+                let year = self.year_at(fe.abs);
+                cx.set_cursor(if day.is_some() || year.is_some() { MouseCursor::Hand } else { MouseCursor::Default });
+                if day != self.hovered || year != self.hovered_year || day.is_some() {
+                    self.hovered = day;
+                    self.hovered_year = year;
+                    self.redraw(cx);
+                }
+            }
+            Hit::FingerHoverOut(_) => {
+                cx.set_cursor(MouseCursor::Default);
+                let had_day = self.hovered.take().is_some();
+                let had_year = self.hovered_year.take().is_some();
+                if had_day || had_year {
+                    self.redraw(cx);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl PlotExport for CalendarHeatmap {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
+impl CalendarHeatmapRef {
+    pub fn set_data(&self, data: Vec<(f64, f64)>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_data(data); }
+    }
+    pub fn set_dates(&self, data: Vec<((i32, u32, u32), f64)>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_dates(data); }
+    }
+    pub fn set_year(&self, year: Option<i32>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_year(year); }
+    }
+    pub fn set_layout(&self, layout: CalendarLayout) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_layout(layout); }
+    }
+    pub fn set_monday_first(&self, monday_first: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_monday_first(monday_first); }
+    }
+    pub fn set_colormap(&self, colormap: Colormap) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_colormap(colormap); }
+    }
+    pub fn set_value_range(&self, range: Option<(f64, f64)>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_value_range(range); }
+    }
+    pub fn set_show_legend(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_show_legend(show); }
+    }
+    pub fn set_show_year_buttons(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_show_year_buttons(show); }
+    }
+    pub fn set_title(&self, title: impl Into<String>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
+    }
+    pub fn clear(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.clear(); }
+    }
+    pub fn redraw(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); }
+    }
+
+    /// Date (`YYYY-MM-DD`) and value of the day that was clicked, if any
+    pub fn day_clicked(&self, actions: &Actions) -> Option<(String, Option<f64>)> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .find_map(|action| match action.cast() {
+                CalendarAction::DayClicked { date, value, .. } => Some((date, value)),
+                _ => None,
+            })
+    }
+
+    /// Year picked with the year buttons, if any
+    pub fn year_changed(&self, actions: &Actions) -> Option<i32> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .find_map(|action| match action.cast() {
+                CalendarAction::YearChanged { year } => Some(year),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: i64, month: u32, date: u32) -> i64 {
+        days_from_civil(year, month, date)
+    }
+
+    #[test]
+    fn test_weekday() {
+        // 1970-01-01 was a Thursday
+        assert_eq!(weekday(0, 0), 4);
+        assert_eq!(weekday(0, 1), 3);
+        assert_eq!(weekday(-1, 0), 3);
+        assert_eq!(weekday(day(2024, 1, 1), 0), 1);
+        assert_eq!(weekday(day(2024, 1, 1), 1), 0);
+        assert_eq!(WEEKDAY_NAMES[weekday(day(2023, 1, 1), 0) as usize], "Sun");
+        assert_eq!(format_day(day(2024, 2, 29)), "2024-02-29");
+        assert_eq!(format_day(-1), "1969-12-31");
+    }
+
+    #[test]
+    fn test_week_cells() {
+        // 2024 is a leap year starting on a Monday
+        let sunday_first = week_cells(2024, 0);
+        assert_eq!(sunday_first.len(), 366);
+        assert_eq!(sunday_first[0], (day(2024, 1, 1), 0, 1));
+        assert_eq!(sunday_first[59], (day(2024, 2, 29), 8, 4));
+        assert_eq!(sunday_first[365], (day(2024, 12, 31), 52, 2));
+        let monday_first = week_cells(2024, 1);
+        assert_eq!(monday_first[0], (day(2024, 1, 1), 0, 0));
+        assert_eq!(monday_first[365], (day(2024, 12, 31), 52, 1));
+
+        // 2023 starts and ends on a Sunday
+        let sunday_first = week_cells(2023, 0);
+        assert_eq!(sunday_first.len(), 365);
+        assert_eq!((sunday_first[0].1, sunday_first[0].2), (0, 0));
+        assert_eq!((sunday_first[364].1, sunday_first[364].2), (52, 0));
+        let monday_first = week_cells(2023, 1);
+        assert_eq!((monday_first[0].1, monday_first[0].2), (0, 6));
+        assert_eq!((monday_first[364].1, monday_first[364].2), (52, 6));
+
+        // A leap year starting on a Saturday spills into a 54th column
+        assert_eq!(week_cells(2028, 0).last().map(|c| (c.1, c.2)), Some((53, 0)));
+        assert_eq!(week_cells(2028, 1).last().map(|c| (c.1, c.2)), Some((52, 6)));
+
+        // Rows follow the weekday and every day has a cell of its own
+        for year in 2020..2030 {
+            for week_start in [0, 1] {
+                let cells = week_cells(year, week_start);
+                let slots: Vec<(usize, usize)> = cells.iter().map(|c| (c.1, c.2)).collect();
+                assert!(cells.iter().all(|&(d, _, row)| row as i64 == weekday(d, week_start)));
+                assert!(slots.windows(2).all(|w| w[0] < w[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_month_cells() {
+        // February 2024 starts on a Thursday and has 29 days
+        let february = month_cells(2024, 2, 0);
+        assert_eq!(february.len(), 29);
+        assert_eq!(february[0], (day(2024, 2, 1), 4, 0));
+        assert_eq!(february[28], (day(2024, 2, 29), 4, 4));
+        assert_eq!(month_cells(2023, 2, 0).len(), 28);
+        assert_eq!(month_cells(2100, 2, 0).len(), 28);
+        assert_eq!(month_cells(2000, 2, 0).len(), 29);
+
+        // February 2015 starts on a Sunday: four rows from Sunday, five from Monday
+        assert_eq!(month_cells(2015, 2, 0).last().map(|c| c.2), Some(3));
+        assert_eq!(month_cells(2015, 2, 1).last().map(|c| c.2), Some(4));
+        assert_eq!(month_cells(2015, 2, 1)[0], (day(2015, 2, 1), 6, 0));
+
+        // December 2018 starts on a Saturday and needs all six rows; the
+        // block ends with the year
+        let december = month_cells(2018, 12, 0);
+        assert_eq!(december.len(), 31);
+        assert_eq!(december[30], (day(2018, 12, 31), 1, 5));
+        for year in 2020..2030 {
+            for month in 1..=12 {
+                for week_start in [0, 1] {
+                    let cells = month_cells(year, month, week_start);
+                    assert!(cells.iter().all(|&(d, column, row)| column as i64 == weekday(d, week_start) && row < 6));
+                    assert_eq!(cells[0].0, day(year as i64, month, 1));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_month_columns() {
+        // Four columns of three rows fill a 4:3 area best
+        assert_eq!(month_columns(dvec2(800.0, 600.0)), (4, 25.0));
+        // A wide strip takes all twelve in a row
+        let (columns, pitch) = month_columns(dvec2(1600.0, 200.0));
+        assert_eq!(columns, 12);
+        assert!((pitch - 1600.0 / 96.0).abs() < 1e-9);
+        assert_eq!(month_columns(dvec2(0.0, 0.0)), (3, 0.0));
+    }
+}
@@ -0,0 +1,375 @@
+// Chord diagram - flows between entities as ribbons inside a ring of arcs
+
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use crate::export::*;
+use super::*;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::text::PlotLabel;
+
+    pub ChordDiagram = {{ChordDiagram}} {
+        width: Fill,
+        height: Fill,
+        label: <PlotLabel> {}
+    }
+}
+
+/// Thickness of the outer ring as a fraction of its radius
+const RING_INNER_RATIO: f64 = 0.92;
+
+/// Segments per ribbon edge and per radian of arc cap
+const RIBBON_STEPS: usize = 32;
+const CAP_STEPS_PER_RADIAN: f64 = 24.0;
+
+/// Angular extent of one entity on the ring, split into one span per
+/// destination in row order
+#[derive(Clone, Debug, Default)]
+struct ChordGroup {
+    start: f64,
+    end: f64,
+    /// Angles of the span for the flow to each entity
+    spans: Vec<(f64, f64)>,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct ChordDiagram {
+    #[deref] #[live] view: View,
+    #[live] draw_arc: DrawArc,
+    #[live] draw_triangle: DrawTriangle,
+    #[live] label: PlotLabel,
+    #[rust] title: String,
+    #[rust] labels: Vec<String>,
+    /// `matrix[i][j]` is the flow from entity i to entity j
+    #[rust] matrix: Vec<Vec<f64>>,
+    #[rust] colors: Vec<Vec4>,
+    /// Gap between neighbouring arcs, in radians
+    #[rust(0.04)] gap_angle: f64,
+
+    #[rust] center: DVec2,
+    #[rust] radius: f64,
+    #[rust] groups: Vec<ChordGroup>,
+    /// Entity under the pointer; only its ribbons stay opaque
+    #[rust] hovered: Option<usize>,
+    #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
+}
+
+impl ChordDiagram {
+    /// Set entity names and the square flow matrix; missing cells count as zero
+    pub fn set_data(&mut self, labels: Vec<String>, matrix: Vec<Vec<f64>>) {
+        self.labels = labels;
+        self.matrix = matrix;
+        self.hovered = None;
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    /// Colors per entity; palette colors fill in the rest
+    pub fn set_colors(&mut self, colors: Vec<Vec4>) {
+        self.colors = colors;
+    }
+
+    pub fn set_gap_angle(&mut self, radians: f64) {
+        self.gap_angle = radians.clamp(0.0, 0.5);
+    }
+
+    pub fn clear(&mut self) {
+        self.labels.clear();
+        self.matrix.clear();
+        self.groups.clear();
+    }
+
+    pub fn redraw(&mut self, cx: &mut Cx) {
+        self.view.redraw(cx);
+        self.area.redraw(cx);
+    }
+
+    fn color(&self, index: usize) -> Vec4 {
+        self.colors.get(index).copied().unwrap_or_else(|| get_color(index))
+    }
+
+    fn flow(&self, from: usize, to: usize) -> f64 {
+        matrix_flow(&self.matrix, from, to)
+    }
+
+    fn layout_groups(&mut self, n: usize) -> bool {
+        self.groups = layout_chord_groups(&self.matrix, n, self.gap_angle);
+        !self.groups.is_empty()
+    }
+
+    fn ring_point(&self, angle: f64, radius: f64) -> DVec2 {
+        self.center + dvec2(angle.cos(), angle.sin()) * radius
+    }
+
+    /// Fill the circular segment between the chord and the arc from `a0` to `a1`
+    fn draw_cap(&mut self, cx: &mut Cx2d, a0: f64, a1: f64, radius: f64) {
+        let steps = (((a1 - a0).abs() * CAP_STEPS_PER_RADIAN).ceil() as usize).max(1);
+        let pivot = (self.ring_point(a0, radius) + self.ring_point(a1, radius)) * 0.5;
+        for k in 0..steps {
+            let t0 = a0 + (a1 - a0) * k as f64 / steps as f64;
+            let t1 = a0 + (a1 - a0) * (k + 1) as f64 / steps as f64;
+            let (p0, p1) = (self.ring_point(t0, radius), self.ring_point(t1, radius));
+            self.draw_triangle.draw_triangle(cx, pivot, p0, p1);
+        }
+    }
+
+    /// Ribbon joining span `s0..s1` to span `t0..t1`, bounded by quadratic
+    /// curves bent through the center
+    fn draw_ribbon(&mut self, cx: &mut Cx2d, (s0, s1): (f64, f64), (t0, t1): (f64, f64), radius: f64) {
+        let c = self.center;
+        let bezier = |p0: DVec2, p2: DVec2, t: f64| p0 * ((1.0 - t) * (1.0 - t)) + c * (2.0 * (1.0 - t) * t) + p2 * (t * t);
+        let (ps0, ps1) = (self.ring_point(s0, radius), self.ring_point(s1, radius));
+        let (pt0, pt1) = (self.ring_point(t0, radius), self.ring_point(t1, radius));
+        let mut prev = (ps0, ps1);
+        for k in 1..=RIBBON_STEPS {
+            let t = k as f64 / RIBBON_STEPS as f64;
+            let next = (bezier(ps0, pt1, t), bezier(ps1, pt0, t));
+            self.draw_triangle.draw_triangle(cx, prev.0, prev.1, next.1);
+            self.draw_triangle.draw_triangle(cx, prev.0, next.1, next.0);
+            prev = next;
+        }
+        self.draw_cap(cx, s0, s1, radius);
+        if (t0, t1) != (s0, s1) {
+            self.draw_cap(cx, t0, t1, radius);
+        }
+    }
+
+    fn group_at(&self, pos: DVec2) -> Option<usize> {
+        let offset = pos - self.center;
+        let distance = offset.length();
+        if distance < self.radius * RING_INNER_RATIO - 2.0 || distance > self.radius + 4.0 {
+            return None;
+        }
+        group_in_direction(&self.groups, offset)
+    }
+}
+
+/// Flow from `from` to `to`; missing, negative and non-finite cells count as zero
+fn matrix_flow(matrix: &[Vec<f64>], from: usize, to: usize) -> f64 {
+    matrix.get(from).and_then(|row| row.get(to)).copied().filter(|v| v.is_finite() && *v > 0.0).unwrap_or(0.0)
+}
+
+/// Lay the first `n` entities around the ring clockwise from 12 o'clock,
+/// each sized by its outgoing total. Empty when nothing flows.
+fn layout_chord_groups(matrix: &[Vec<f64>], n: usize, gap_angle: f64) -> Vec<ChordGroup> {
+    let grand_total: f64 = (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).map(|(i, j)| matrix_flow(matrix, i, j)).sum();
+    if grand_total <= 0.0 {
+        return Vec::new();
+    }
+    let gap = gap_angle.min(std::f64::consts::TAU / (2.0 * n as f64));
+    let per_unit = (std::f64::consts::TAU - gap * n as f64) / grand_total;
+    let mut angle = -std::f64::consts::FRAC_PI_2;
+    let mut groups = Vec::with_capacity(n);
+    for i in 0..n {
+        let start = angle;
+        let mut spans = Vec::with_capacity(n);
+        for j in 0..n {
+            let end = angle + matrix_flow(matrix, i, j) * per_unit;
+            spans.push((angle, end));
+            angle = end;
+        }
+        groups.push(ChordGroup { start, end: angle, spans });
+        angle += gap;
+    }
+    groups
+}
+
+/// Group whose arc lies in the direction of `offset` from the center
+fn group_in_direction(groups: &[ChordGroup], offset: DVec2) -> Option<usize> {
+    // Angles run from -PI/2 to 3PI/2, like the layout
+    let mut angle = offset.y.atan2(offset.x);
+    if angle < -std::f64::consts::FRAC_PI_2 {
+        angle += std::f64::consts::TAU;
+    }
+    groups.iter().position(|g| angle >= g.start && angle <= g.end)
+}
+
+impl Widget for ChordDiagram {
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
+
+        let n = self.labels.len().min(self.matrix.len());
+        if rect.size.x > 0.0 && rect.size.y > 0.0 && n >= 2 && self.layout_groups(n) {
+            let top = if self.title.is_empty() { 0.0 } else { 30.0 };
+            self.center = dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + top + (rect.size.y - top) / 2.0);
+            self.radius = (rect.size.x.min(rect.size.y - top) / 2.0 - 40.0).max(20.0);
+            let inner = self.radius * RING_INNER_RATIO - 1.0;
+
+            // Each pair gets one ribbon, colored by its larger direction
+            for i in 0..n {
+                for j in i..n {
+                    let (forward, back) = (self.flow(i, j), self.flow(j, i));
+                    if forward <= 0.0 && back <= 0.0 {
+                        continue;
+                    }
+                    let owner = if forward >= back { i } else { j };
+                    let lit = self.hovered.is_none_or(|h| h == i || h == j);
+                    let color = self.color(owner);
+                    let alpha = match self.hovered {
+                        None => 0.6,
+                        Some(_) if lit => 0.8,
+                        Some(_) => 0.08,
+                    };
+                    self.draw_triangle.color = vec4(color.x, color.y, color.z, alpha);
+                    let (source, target) = (self.groups[i].spans[j], self.groups[j].spans[i]);
+                    self.draw_ribbon(cx, source, target, inner);
+                }
+            }
+
+            for i in 0..n {
+                let color = self.color(i);
+                let alpha = if self.hovered.is_none_or(|h| h == i) { 1.0 } else { 0.5 };
+                self.draw_arc.color = vec4(color.x, color.y, color.z, alpha);
+                let (start, end) = (self.groups[i].start, self.groups[i].end);
+                if end > start {
+                    self.draw_arc.draw_arc(cx, self.center, self.radius, RING_INNER_RATIO, start, end);
+                }
+            }
+
+            self.label.set_font_size(10.0);
+            self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+            for i in 0..n {
+                let mid = (self.groups[i].start + self.groups[i].end) / 2.0;
+                let pos = self.ring_point(mid, self.radius + 8.0);
+                let anchor = match (mid.cos(), mid.sin()) {
+                    (x, _) if x > 0.3 => TextAnchor::MiddleLeft,
+                    (x, _) if x < -0.3 => TextAnchor::MiddleRight,
+                    (_, y) if y < 0.0 => TextAnchor::BottomCenter,
+                    _ => TextAnchor::TopCenter,
+                };
+                let text = self.labels[i].clone();
+                self.label.draw_at(cx, pos, &text, anchor);
+            }
+
+            if !self.title.is_empty() {
+                self.label.set_font_size(12.0);
+                self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + 10.0), &self.title, TextAnchor::TopCenter);
+            }
+        }
+
+        self.capture.end();
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        match event.hits(cx, self.area) {
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => {
+                let hovered = self.group_at(fe.abs);
+                if hovered != self.hovered {
+                    self.hovered = hovered;
+                    self.redraw(cx);
+                }
+            }
+            Hit::FingerHoverOut(_) => {
+                if self.hovered.take().is_some() {
+                    self.redraw(cx);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl PlotExport for ChordDiagram {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
+impl ChordDiagramRef {
+    pub fn set_data(&self, labels: Vec<String>, matrix: Vec<Vec<f64>>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_data(labels, matrix); }
+    }
+    pub fn set_title(&self, title: impl Into<String>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
+    }
+    pub fn set_colors(&self, colors: Vec<Vec4>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_colors(colors); }
+    }
+    pub fn set_gap_angle(&self, radians: f64) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_gap_angle(radians); }
+    }
+    pub fn clear(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.clear(); }
+    }
+    pub fn redraw(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, TAU};
+
+    fn width((start, end): (f64, f64)) -> f64 {
+        end - start
+    }
+
+    #[test]
+    fn test_arcs_fill_the_ring() {
+        let matrix = vec![vec![0.0, 5.0, 3.0], vec![2.0, 0.0, 1.0], vec![4.0, 0.0, 0.0]];
+        let groups = layout_chord_groups(&matrix, 3, 0.04);
+        assert_eq!(groups.len(), 3);
+
+        // Arcs and gaps go once round, starting at 12 o'clock
+        let arcs: f64 = groups.iter().map(|g| g.end - g.start).sum();
+        assert!((arcs + 3.0 * 0.04 - TAU).abs() < 1e-12);
+        assert!((groups[0].start + FRAC_PI_2).abs() < 1e-12);
+        assert!((groups[1].start - groups[0].end - 0.04).abs() < 1e-12);
+
+        // Arcs are sized by outgoing totals, spans by each flow
+        let per_unit = (TAU - 3.0 * 0.04) / 15.0;
+        assert!((groups[0].end - groups[0].start - 8.0 * per_unit).abs() < 1e-12);
+        assert!((width(groups[0].spans[1]) / width(groups[1].spans[0]) - 2.5).abs() < 1e-12);
+        assert_eq!(width(groups[2].spans[1]), 0.0);
+        for g in &groups {
+            assert_eq!(g.spans.first().unwrap().0, g.start);
+            assert_eq!(g.spans.last().unwrap().1, g.end);
+            assert!(g.spans.windows(2).all(|w| w[0].1 == w[1].0));
+        }
+    }
+
+    #[test]
+    fn test_bad_flows_and_wide_gaps() {
+        // Negative, NaN and missing cells are no flow
+        assert_eq!(matrix_flow(&[vec![-1.0, f64::NAN]], 0, 0), 0.0);
+        assert_eq!(matrix_flow(&[vec![-1.0, f64::NAN]], 0, 1), 0.0);
+        assert_eq!(matrix_flow(&[vec![1.0]], 3, 0), 0.0);
+        assert!(layout_chord_groups(&[vec![0.0, -2.0], vec![f64::NAN, 0.0]], 2, 0.04).is_empty());
+
+        // Gaps are capped so arcs keep at least half the ring
+        let matrix = vec![vec![1.0; 10]; 10];
+        let groups = layout_chord_groups(&matrix, 10, 0.5);
+        let arcs: f64 = groups.iter().map(|g| g.end - g.start).sum();
+        assert!((arcs - TAU / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_group_in_direction() {
+        let matrix = vec![vec![0.0, 1.0, 1.0], vec![1.0, 0.0, 1.0], vec![1.0, 1.0, 0.0]];
+        let groups = layout_chord_groups(&matrix, 3, 0.1);
+        for (i, g) in groups.iter().enumerate() {
+            let mid = (g.start + g.end) / 2.0;
+            assert_eq!(group_in_direction(&groups, dvec2(mid.cos(), mid.sin())), Some(i));
+        }
+        // The gap before 12 o'clock belongs to no group
+        let before_top = -FRAC_PI_2 - 0.05;
+        assert_eq!(group_in_direction(&groups, dvec2(before_top.cos(), before_top.sin())), None);
+    }
+}
@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -22,145 +23,341 @@ live_design! {
     }
 }
 
+/// Room right of the plot area taken by the colorbar and its labels
+const COLORBAR_SPACE: f64 = 70.0;
+
 #[derive(Live, LiveHook, Widget)]
 pub struct ContourPlot {
     #[deref] #[live] view: View,
     #[live] draw_line: DrawPlotLine,
     #[live] draw_fill: DrawPlotFill,
+    #[live] draw_triangle: DrawTriangle,
     #[live] label: PlotLabel,
     #[rust] title: String,
     #[rust] data: Vec<Vec<f64>>,
-    #[rust] x_range: (f64, f64),
-    #[rust] y_range: (f64, f64),
+    /// Column coordinates; evenly spread over the x range when empty
+    #[rust] x_coords: Vec<f64>,
+    /// Row coordinates; row 0 at the top of the y range when empty
+    #[rust] y_coords: Vec<f64>,
     #[rust] filled: bool,
     #[rust] colormap: Colormap,
     #[rust] n_levels: usize,
-    #[rust] plot_area: PlotArea,
+    /// Explicit contour levels, overriding `n_levels`
+    #[rust] levels: Vec<f64>,
+    #[rust] show_labels: bool,
+    #[rust] show_colorbar: bool,
+    #[rust] quiver: Option<QuiverField>,
+    #[rust] axes: Axes,
     #[live(50.0)] left_margin: f64,
     #[live(30.0)] right_margin: f64,
     #[live(30.0)] top_margin: f64,
     #[live(50.0)] bottom_margin: f64,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for ContourPlot {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) { self.view.handle_event(cx, event, scope); }
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.data.is_empty() {
-            self.plot_area = PlotArea::new(rect.pos.x + self.left_margin, rect.pos.y + self.top_margin,
-                rect.pos.x + rect.size.x - self.right_margin, rect.pos.y + rect.size.y - self.bottom_margin);
+            let right = self.right_margin + if self.show_colorbar { COLORBAR_SPACE } else { 0.0 };
+            self.axes.margins = PlotMargins { left: self.left_margin, top: self.top_margin, right, bottom: self.bottom_margin };
+            self.axes.set_show_grid(false);
+            self.axes.update_plot_area(rect);
             self.draw_contours(cx);
             self.draw_axis_labels(cx);
+            if let Some(quiver) = &self.quiver {
+                quiver.draw(cx, &mut self.draw_line, &self.axes);
+            }
+            if self.show_colorbar {
+                self.draw_colorbar(cx);
+            }
             self.draw_labels(cx);
         }
+        self.capture.end();
         DrawStep::done()
     }
 }
 
+impl PlotExport for ContourPlot {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl ContourPlot {
     pub fn set_title(&mut self, title: impl Into<String>) { self.title = title.into(); }
     pub fn set_data(&mut self, data: Vec<Vec<f64>>) { self.data = data; }
-    pub fn set_x_range(&mut self, min: f64, max: f64) { self.x_range = (min, max); }
-    pub fn set_y_range(&mut self, min: f64, max: f64) { self.y_range = (min, max); }
+    pub fn set_x_range(&mut self, min: f64, max: f64) { self.axes.x.range = (min, max); }
+    pub fn set_y_range(&mut self, min: f64, max: f64) { self.axes.y.range = (min, max); }
     pub fn set_filled(&mut self, filled: bool) { self.filled = filled; }
     pub fn set_colormap(&mut self, colormap: Colormap) { self.colormap = colormap; }
     pub fn set_n_levels(&mut self, n: usize) { self.n_levels = n; }
-    pub fn clear(&mut self) { self.data.clear(); self.n_levels = 0; }
+    /// Contour at exactly these values instead of `n_levels` even steps
+    pub fn set_levels(&mut self, levels: Vec<f64>) { self.levels = levels; }
+    /// Write each level's value into a gap in its contour lines
+    pub fn set_show_labels(&mut self, show: bool) { self.show_labels = show; }
+    pub fn set_show_colorbar(&mut self, show: bool) { self.show_colorbar = show; }
+    /// Draw a vector field over the contours
+    pub fn set_quiver(&mut self, quiver: Option<QuiverField>) { self.quiver = quiver; }
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.x_coords.clear();
+        self.y_coords.clear();
+        self.levels.clear();
+        self.quiver = None;
+        self.n_levels = 0;
+    }
+
+    /// Values `data[row][col]` sampled at `(x[col], y[row])`, which may be
+    /// unevenly spaced; the axes are fitted to the coordinates
+    pub fn set_grid(&mut self, x: Vec<f64>, y: Vec<f64>, data: Vec<Vec<f64>>) {
+        let span = |v: &[f64]| v.iter().fold((f64::MAX, f64::MIN), |(lo, hi), &c| (lo.min(c), hi.max(c)));
+        let (x_span, y_span) = (span(&x), span(&y));
+        if x_span.0 < x_span.1 { self.axes.x.range = x_span; }
+        if y_span.0 < y_span.1 { self.axes.y.range = y_span; }
+        self.x_coords = x;
+        self.y_coords = y;
+        self.data = data;
+    }
+
+    /// Grid coordinates, spreading rows and columns over the axis ranges
+    /// when none were given
+    fn grid_coords(&self) -> (Vec<f64>, Vec<f64>) {
+        let rows = self.data.len();
+        let cols = self.data.iter().map(Vec::len).min().unwrap_or(0);
+        let x = if self.x_coords.len() == cols { self.x_coords.clone() } else { spread_coords(cols, self.axes.x.range.0, self.axes.x.range.1) };
+        let y = if self.y_coords.len() == rows { self.y_coords.clone() } else { spread_coords(rows, self.axes.y.range.1, self.axes.y.range.0) };
+        (x, y)
+    }
+
+    /// Sorted levels inside the data range
+    fn contour_levels(&self, v_min: f64, v_max: f64) -> Vec<f64> {
+        contour_levels(&self.levels, self.n_levels, v_min, v_max)
+    }
+
+    /// Value range and levels of the current data
+    fn value_levels(&self) -> Option<(f64, f64, Vec<f64>)> {
+        let (x, y) = self.grid_coords();
+        let (v_min, v_max) = ContourGrid::new(&x, &y, &self.data).value_range()?;
+        Some((v_min, v_max, self.contour_levels(v_min, v_max)))
+    }
+
+    /// Fill color of the band from `lo` to `hi`
+    fn band_color(&self, lo: f64, hi: f64, v_min: f64, v_max: f64) -> Vec4 {
+        let mid = (lo.max(v_min) + hi.min(v_max)) / 2.0;
+        self.colormap.sample((mid - v_min) / (v_max - v_min).max(1e-10))
+    }
+
+    fn level_label(levels: &[f64], level: f64) -> String {
+        let step = levels.windows(2).map(|w| w[1] - w[0]).fold(f64::MAX, f64::min);
+        TickFormatter::Auto.format(level, if step < f64::MAX { step } else { 0.0 })
+    }
 
     fn draw_contours(&mut self, cx: &mut Cx2d) {
-        let rows = self.data.len(); if rows < 2 { return; }
-        let cols = self.data[0].len(); if cols < 2 { return; }
-        let (mut v_min, mut v_max) = (f64::MAX, f64::MIN);
-        for row in &self.data { for &v in row { v_min = v_min.min(v); v_max = v_max.max(v); } }
+        if self.data.len() < 2 || self.data.iter().map(Vec::len).min().unwrap_or(0) < 2 { return; }
+        let (x, y) = self.grid_coords();
+        let grid = ContourGrid::new(&x, &y, &self.data);
+        let Some((v_min, v_max)) = grid.value_range() else { return };
         let v_range = (v_max - v_min).max(1e-10);
-        let cell_w = self.plot_area.width() / (cols - 1) as f64;
-        let cell_h = self.plot_area.height() / (rows - 1) as f64;
+        let levels = self.contour_levels(v_min, v_max);
 
         if self.filled {
-            for row in 0..rows - 1 {
-                for col in 0..cols - 1 {
-                    let avg = (self.data[row][col] + self.data[row][col + 1] + self.data[row + 1][col] + self.data[row + 1][col + 1]) / 4.0;
-                    self.draw_fill.color = self.colormap.sample((avg - v_min) / v_range);
-                    self.draw_fill.draw_abs(cx, Rect { pos: dvec2(self.plot_area.left + col as f64 * cell_w, self.plot_area.top + row as f64 * cell_h), size: dvec2(cell_w, cell_h) });
+            // Open-ended outer bands catch everything below the first and
+            // above the last level
+            let mut edges = vec![f64::NEG_INFINITY];
+            edges.extend(levels.iter().copied());
+            edges.push(f64::INFINITY);
+            for band in edges.windows(2) {
+                let color = self.band_color(band[0], band[1], v_min, v_max);
+                self.draw_fill.color = color;
+                self.draw_triangle.color = color;
+                for piece in isoband(&grid, band[0], band[1]) {
+                    match piece {
+                        BandPiece::Cell { from, to } => {
+                            let (a, b) = (self.axes.data_to_pixel(from.x, from.y), self.axes.data_to_pixel(to.x, to.y));
+                            let pos = dvec2(a.x.min(b.x), a.y.min(b.y));
+                            self.draw_fill.draw_rect(cx, Rect { pos, size: dvec2((a.x - b.x).abs(), (a.y - b.y).abs()) });
+                        }
+                        BandPiece::Polygon(points) => {
+                            let points: Vec<DVec2> = points.iter().map(|p| self.axes.data_to_pixel(p.x, p.y)).collect();
+                            for i in 1..points.len() - 1 {
+                                self.draw_triangle.draw_triangle(cx, points[0], points[i], points[i + 1]);
+                            }
+                        }
+                    }
                 }
             }
         }
 
-        let n_levels = if self.n_levels > 0 { self.n_levels } else { 10 };
-        for lvl in 1..=n_levels {
-            let level = v_min + lvl as f64 * (v_max - v_min) / (n_levels + 1) as f64;
+        let mut labels = Vec::new();
+        for &level in &levels {
             self.draw_line.color = if self.filled { vec4(0.2, 0.2, 0.2, 0.8) } else { self.colormap.sample((level - v_min) / v_range) };
-            for row in 0..rows - 1 {
-                for col in 0..cols - 1 {
-                    let (v00, v10, v01, v11) = (self.data[row][col], self.data[row][col + 1], self.data[row + 1][col], self.data[row + 1][col + 1]);
-                    let case = ((v00 >= level) as u8) | (((v10 >= level) as u8) << 1) | (((v01 >= level) as u8) << 2) | (((v11 >= level) as u8) << 3);
-                    if case == 0 || case == 15 { continue; }
-                    let x0 = self.plot_area.left + col as f64 * cell_w;
-                    let y0 = self.plot_area.top + row as f64 * cell_h;
-                    let interp = |a: f64, b: f64| if (b - a).abs() < 1e-10 { 0.5 } else { (level - a) / (b - a) };
-                    let (tx, bx, ly, ry) = (x0 + interp(v00, v10) * cell_w, x0 + interp(v01, v11) * cell_w, y0 + interp(v00, v01) * cell_h, y0 + interp(v10, v11) * cell_h);
-                    match case {
-                        1 | 14 => self.draw_line.draw_line(cx, dvec2(x0, ly), dvec2(tx, y0), 1.5),
-                        2 | 13 => self.draw_line.draw_line(cx, dvec2(tx, y0), dvec2(x0 + cell_w, ry), 1.5),
-                        3 | 12 => self.draw_line.draw_line(cx, dvec2(x0, ly), dvec2(x0 + cell_w, ry), 1.5),
-                        4 | 11 => self.draw_line.draw_line(cx, dvec2(x0, ly), dvec2(bx, y0 + cell_h), 1.5),
-                        6 | 9 => self.draw_line.draw_line(cx, dvec2(tx, y0), dvec2(bx, y0 + cell_h), 1.5),
-                        7 | 8 => self.draw_line.draw_line(cx, dvec2(bx, y0 + cell_h), dvec2(x0 + cell_w, ry), 1.5),
-                        5 => { self.draw_line.draw_line(cx, dvec2(x0, ly), dvec2(tx, y0), 1.5); self.draw_line.draw_line(cx, dvec2(bx, y0 + cell_h), dvec2(x0 + cell_w, ry), 1.5); }
-                        10 => { self.draw_line.draw_line(cx, dvec2(tx, y0), dvec2(x0 + cell_w, ry), 1.5); self.draw_line.draw_line(cx, dvec2(x0, ly), dvec2(bx, y0 + cell_h), 1.5); }
-                        _ => {}
-                    }
-                }
+            let text = Self::level_label(&levels, level);
+            for line in isolines(&grid, level) {
+                let points: Vec<DVec2> = line.iter().map(|p| self.axes.data_to_pixel(p.x, p.y)).collect();
+                let length: f64 = points.windows(2).map(|w| (w[1] - w[0]).length()).sum();
+                // Long enough lines get their value written into a gap halfway along
+                let gap = if self.show_labels {
+                    let size = self.label.measure(cx, &text);
+                    (length > size.x * 3.0).then(|| {
+                        let half = size.x / 2.0 + 4.0;
+                        labels.push((point_along(&points, length / 2.0), text.clone()));
+                        (length / 2.0 - half, length / 2.0 + half)
+                    })
+                } else {
+                    None
+                };
+                draw_polyline(cx, &mut self.draw_line, &points, gap);
             }
         }
 
-        self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-        self.draw_line.draw_line(cx, dvec2(self.plot_area.left, self.plot_area.bottom), dvec2(self.plot_area.right, self.plot_area.bottom), 1.0);
-        self.draw_line.draw_line(cx, dvec2(self.plot_area.left, self.plot_area.bottom), dvec2(self.plot_area.left, self.plot_area.top), 1.0);
+        self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+        for (pos, text) in labels {
+            self.label.draw_at(cx, pos, &text, TextAnchor::Center);
+        }
     }
 
-    fn draw_axis_labels(&mut self, cx: &mut Cx2d) {
-        self.label.set_color(vec4(0.4, 0.4, 0.4, 1.0));
-        let n_ticks = 5;
-        // X-axis ticks
-        for i in 0..=n_ticks {
-            let frac = i as f64 / n_ticks as f64;
-            let x_pos = self.plot_area.left + frac * self.plot_area.width();
-            let val = self.x_range.0 + frac * (self.x_range.1 - self.x_range.0);
-            let txt = format!("{:.1}", val);
-            self.label.draw_at(cx, dvec2(x_pos, self.plot_area.bottom + 12.0), &txt, TextAnchor::Center);
-            // Tick mark
-            self.draw_line.color = vec4(0.4, 0.4, 0.4, 0.6);
-            self.draw_line.draw_line(cx, dvec2(x_pos, self.plot_area.bottom), dvec2(x_pos, self.plot_area.bottom + 4.0), 1.0);
+    /// Bands (filled) or a gradient with level ticks, labelled at the levels
+    fn draw_colorbar(&mut self, cx: &mut Cx2d) {
+        let Some((v_min, v_max, levels)) = self.value_levels() else { return };
+        let v_range = (v_max - v_min).max(1e-10);
+        let bar_width = 15.0;
+        let bar_x = self.axes.plot_area.right + 15.0;
+        let (top, bottom) = (self.axes.plot_area.top, self.axes.plot_area.bottom);
+        let to_y = |v: f64| bottom - ((v - v_min) / v_range).clamp(0.0, 1.0) * (bottom - top);
+
+        if self.filled {
+            let mut edges = vec![v_min];
+            edges.extend(levels.iter().copied().filter(|&l| l > v_min && l < v_max));
+            edges.push(v_max);
+            for band in edges.windows(2) {
+                self.draw_fill.color = self.band_color(band[0], band[1], v_min, v_max);
+                let (y0, y1) = (to_y(band[1]), to_y(band[0]));
+                self.draw_fill.draw_rect(cx, Rect { pos: dvec2(bar_x, y0), size: dvec2(bar_width, y1 - y0) });
+            }
+        } else {
+            let steps = 50;
+            let step_height = (bottom - top) / steps as f64;
+            for i in 0..steps {
+                self.draw_fill.color = self.colormap.sample(1.0 - (i as f64 + 0.5) / steps as f64);
+                self.draw_fill.draw_rect(cx, Rect { pos: dvec2(bar_x, top + i as f64 * step_height), size: dvec2(bar_width, step_height + 0.5) });
+            }
         }
-        // Y-axis ticks
-        for i in 0..=n_ticks {
-            let frac = i as f64 / n_ticks as f64;
-            let y_pos = self.plot_area.bottom - frac * self.plot_area.height();
-            let val = self.y_range.0 + frac * (self.y_range.1 - self.y_range.0);
-            let txt = format!("{:.1}", val);
-            self.label.draw_at(cx, dvec2(self.plot_area.left - 8.0, y_pos), &txt, TextAnchor::MiddleRight);
-            self.draw_line.color = vec4(0.4, 0.4, 0.4, 0.6);
-            self.draw_line.draw_line(cx, dvec2(self.plot_area.left - 4.0, y_pos), dvec2(self.plot_area.left, y_pos), 1.0);
+
+        // Level ticks, labelled where they do not crowd the previous label
+        self.draw_line.color = vec4(0.2, 0.2, 0.2, 0.8);
+        self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
+        let mut last_label_y = f64::MAX;
+        for &level in levels.iter().filter(|&&l| l >= v_min && l <= v_max) {
+            let y = to_y(level);
+            self.draw_line.draw_line(cx, dvec2(bar_x, y), dvec2(bar_x + bar_width, y), 1.0);
+            if last_label_y - y >= 12.0 {
+                let text = Self::level_label(&levels, level);
+                self.label.draw_at(cx, dvec2(bar_x + bar_width + 4.0, y), &text, TextAnchor::MiddleLeft);
+                last_label_y = y;
+            }
         }
     }
 
+    fn draw_axis_labels(&mut self, cx: &mut Cx2d) {
+        self.axes.draw_axes(cx, &mut self.draw_line);
+        self.axes.draw_tick_labels(cx, &mut self.label);
+    }
+
     fn draw_labels(&mut self, cx: &mut Cx2d) {
         self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
         if !self.title.is_empty() {
-            self.label.draw_at(cx, dvec2((self.plot_area.left + self.plot_area.right) / 2.0, self.plot_area.top - 15.0), &self.title, TextAnchor::Center);
+            self.label.draw_at(cx, dvec2((self.axes.plot_area.left + self.axes.plot_area.right) / 2.0, self.axes.plot_area.top - 15.0), &self.title, TextAnchor::Center);
         }
     }
 }
 
+/// `n` coordinates evenly spread from `from` to `to`
+fn spread_coords(n: usize, from: f64, to: f64) -> Vec<f64> {
+    (0..n).map(|i| from + (to - from) * i as f64 / (n.max(2) - 1) as f64).collect()
+}
+
+/// Explicit levels sorted and deduplicated, or `n_levels` (default 10)
+/// evenly spaced inside the data range when there are none
+fn contour_levels(explicit: &[f64], n_levels: usize, v_min: f64, v_max: f64) -> Vec<f64> {
+    if explicit.is_empty() {
+        return auto_levels(v_min, v_max, if n_levels > 0 { n_levels } else { 10 });
+    }
+    let mut levels: Vec<f64> = explicit.iter().copied().filter(|v| v.is_finite()).collect();
+    levels.sort_by(f64::total_cmp);
+    levels.dedup();
+    levels
+}
+
+/// Draw a pixel polyline, leaving out the stretch between the two arc
+/// lengths in `gap`
+fn draw_polyline(cx: &mut Cx2d, draw_line: &mut DrawPlotLine, points: &[DVec2], gap: Option<(f64, f64)>) {
+    for (from, to) in polyline_segments(points, gap) {
+        draw_line.draw_line(cx, from, to, 1.5);
+    }
+}
+
+/// Segments of a polyline without the stretch between the arc lengths in `gap`
+fn polyline_segments(points: &[DVec2], gap: Option<(f64, f64)>) -> Vec<(DVec2, DVec2)> {
+    let mut segments = Vec::new();
+    let mut travelled = 0.0;
+    for w in points.windows(2) {
+        let length = (w[1] - w[0]).length();
+        let (start, end) = (travelled, travelled + length);
+        travelled = end;
+        let Some((gap_start, gap_end)) = gap else {
+            segments.push((w[0], w[1]));
+            continue;
+        };
+        if length <= 0.0 || end <= gap_start || start >= gap_end {
+            segments.push((w[0], w[1]));
+            continue;
+        }
+        let at = |s: f64| w[0] + (w[1] - w[0]) * ((s - start) / length);
+        if start < gap_start {
+            segments.push((w[0], at(gap_start)));
+        }
+        if end > gap_end {
+            segments.push((at(gap_end), w[1]));
+        }
+    }
+    segments
+}
+
+/// Point `distance` pixels along a polyline
+fn point_along(points: &[DVec2], distance: f64) -> DVec2 {
+    let mut travelled = 0.0;
+    for w in points.windows(2) {
+        let length = (w[1] - w[0]).length();
+        if length > 0.0 && travelled + length >= distance {
+            return w[0] + (w[1] - w[0]) * ((distance - travelled) / length);
+        }
+        travelled += length;
+    }
+    points.last().copied().unwrap_or(dvec2(0.0, 0.0))
+}
+
 impl ContourPlotRef {
     pub fn set_title(&self, title: impl Into<String>) { if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); } }
     pub fn set_data(&self, data: Vec<Vec<f64>>) { if let Some(mut inner) = self.borrow_mut() { inner.set_data(data); } }
+    pub fn set_grid(&self, x: Vec<f64>, y: Vec<f64>, data: Vec<Vec<f64>>) { if let Some(mut inner) = self.borrow_mut() { inner.set_grid(x, y, data); } }
     pub fn set_x_range(&self, min: f64, max: f64) { if let Some(mut inner) = self.borrow_mut() { inner.set_x_range(min, max); } }
     pub fn set_y_range(&self, min: f64, max: f64) { if let Some(mut inner) = self.borrow_mut() { inner.set_y_range(min, max); } }
     pub fn set_filled(&self, filled: bool) { if let Some(mut inner) = self.borrow_mut() { inner.set_filled(filled); } }
     pub fn set_colormap(&self, colormap: Colormap) { if let Some(mut inner) = self.borrow_mut() { inner.set_colormap(colormap); } }
     pub fn set_n_levels(&self, n: usize) { if let Some(mut inner) = self.borrow_mut() { inner.set_n_levels(n); } }
+    pub fn set_levels(&self, levels: Vec<f64>) { if let Some(mut inner) = self.borrow_mut() { inner.set_levels(levels); } }
+    pub fn set_show_labels(&self, show: bool) { if let Some(mut inner) = self.borrow_mut() { inner.set_show_labels(show); } }
+    pub fn set_show_colorbar(&self, show: bool) { if let Some(mut inner) = self.borrow_mut() { inner.set_show_colorbar(show); } }
+    pub fn set_quiver(&self, quiver: Option<QuiverField>) { if let Some(mut inner) = self.borrow_mut() { inner.set_quiver(quiver); } }
     pub fn clear(&self) { if let Some(mut inner) = self.borrow_mut() { inner.clear(); } }
     pub fn redraw(&self, cx: &mut Cx) { if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); } }
 }
@@ -169,82 +366,147 @@ impl ContourPlotRef {
 // QuiverPlot Widget (Vector Field)
 // =============================================================================
 
+/// Arrows `(u, v)` anchored at `(x, y)`, drawn by `QuiverPlot` or over a
+/// `ContourPlot`
+#[derive(Clone, Debug, Default)]
+pub struct QuiverField {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub u: Vec<f64>,
+    pub v: Vec<f64>,
+    /// Pixels per unit of vector length; 0 fits the longest arrow to a tenth
+    /// of the plot
+    pub scale: f64,
+    /// Arrow color; fully transparent means the default blue
+    pub color: Vec4,
+}
+
+impl QuiverField {
+    pub fn new(x: Vec<f64>, y: Vec<f64>, u: Vec<f64>, v: Vec<f64>) -> Self {
+        Self { x, y, u, v, ..Default::default() }
+    }
+
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len().min(self.y.len()).min(self.u.len()).min(self.v.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.x.clear();
+        self.y.clear();
+        self.u.clear();
+        self.v.clear();
+    }
+
+    fn draw(&self, cx: &mut Cx2d, draw_line: &mut DrawPlotLine, axes: &Axes) {
+        let n = self.len();
+        if n == 0 { return; }
+        let max_mag = self.u.iter().zip(self.v.iter()).map(|(&u, &v)| (u * u + v * v).sqrt()).fold(0.0f64, f64::max);
+        let scale = if self.scale > 0.0 { self.scale } else if max_mag > 0.0 { 0.1 * axes.plot_area.width().min(axes.plot_area.height()) / max_mag } else { 1.0 };
+
+        let color = if self.color.w > 0.0 { self.color } else { vec4(0.12, 0.47, 0.71, 1.0) };
+        for i in 0..n {
+            let p1 = axes.data_to_pixel(self.x[i], self.y[i]);
+            let p2 = dvec2(p1.x + self.u[i] * scale, p1.y - self.v[i] * scale);
+            draw_line.color = color;
+            draw_line.draw_line(cx, p1, p2, 1.5);
+            let len = ((p2.x - p1.x).powi(2) + (p2.y - p1.y).powi(2)).sqrt();
+            if len > 1.0 {
+                let (dirx, diry) = ((p2.x - p1.x) / len, (p2.y - p1.y) / len);
+                let (perpx, perpy) = (-diry, dirx);
+                let (al, aa) = (5.0, 0.4);
+                draw_line.draw_line(cx, p2, dvec2(p2.x - dirx * al + perpx * al * aa, p2.y - diry * al + perpy * al * aa), 1.5);
+                draw_line.draw_line(cx, p2, dvec2(p2.x - dirx * al - perpx * al * aa, p2.y - diry * al - perpy * al * aa), 1.5);
+            }
+        }
+    }
+}
+
 #[derive(Live, LiveHook, Widget)]
 pub struct QuiverPlot {
     #[deref] #[live] view: View,
     #[live] draw_line: DrawPlotLine,
     #[live] label: PlotLabel,
     #[rust] title: String,
-    #[rust] x: Vec<f64>,
-    #[rust] y: Vec<f64>,
-    #[rust] u: Vec<f64>,
-    #[rust] v: Vec<f64>,
-    #[rust] scale: f64,
-    #[rust] arrow_color: Vec4,
-    #[rust] plot_area: PlotArea,
+    #[rust] field: QuiverField,
+    #[rust] axes: Axes,
     #[live(50.0)] left_margin: f64,
     #[live(30.0)] right_margin: f64,
     #[live(30.0)] top_margin: f64,
     #[live(50.0)] bottom_margin: f64,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for QuiverPlot {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) { self.view.handle_event(cx, event, scope); }
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
-        if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.x.is_empty() {
-            self.plot_area = PlotArea::new(rect.pos.x + self.left_margin, rect.pos.y + self.top_margin,
-                rect.pos.x + rect.size.x - self.right_margin, rect.pos.y + rect.size.y - self.bottom_margin);
+        self.capture.begin(rect);
+        if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.field.x.is_empty() {
+            self.update_axes(rect);
             self.draw_arrows(cx);
             self.draw_labels(cx);
         }
+        self.capture.end();
         DrawStep::done()
     }
 }
 
+impl PlotExport for QuiverPlot {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl QuiverPlot {
     pub fn set_title(&mut self, title: impl Into<String>) { self.title = title.into(); }
-    pub fn set_data(&mut self, x: Vec<f64>, y: Vec<f64>, u: Vec<f64>, v: Vec<f64>) { self.x = x; self.y = y; self.u = u; self.v = v; }
-    pub fn set_scale(&mut self, scale: f64) { self.scale = scale; }
-    pub fn set_color(&mut self, color: Vec4) { self.arrow_color = color; }
-    pub fn clear(&mut self) { self.x.clear(); self.y.clear(); self.u.clear(); self.v.clear(); }
-
-    fn draw_arrows(&mut self, cx: &mut Cx2d) {
-        let n = self.x.len().min(self.y.len()).min(self.u.len()).min(self.v.len());
-        if n == 0 { return; }
-        let (x_min, x_max) = (self.x.iter().cloned().fold(f64::MAX, f64::min), self.x.iter().cloned().fold(f64::MIN, f64::max));
-        let (y_min, y_max) = (self.y.iter().cloned().fold(f64::MAX, f64::min), self.y.iter().cloned().fold(f64::MIN, f64::max));
-        let (xr, yr) = ((x_max - x_min).max(1e-10), (y_max - y_min).max(1e-10));
-        let max_mag = self.u.iter().zip(self.v.iter()).map(|(&u, &v)| (u * u + v * v).sqrt()).fold(0.0f64, f64::max);
-        let scale = if self.scale > 0.0 { self.scale } else if max_mag > 0.0 { 0.1 * self.plot_area.width().min(self.plot_area.height()) / max_mag } else { 1.0 };
-        self.draw_line.color = if self.arrow_color.w > 0.0 { self.arrow_color } else { vec4(0.12, 0.47, 0.71, 1.0) };
+    pub fn set_data(&mut self, x: Vec<f64>, y: Vec<f64>, u: Vec<f64>, v: Vec<f64>) { self.field.x = x; self.field.y = y; self.field.u = u; self.field.v = v; }
+    pub fn set_field(&mut self, field: QuiverField) { self.field = field; }
+    pub fn set_scale(&mut self, scale: f64) { self.field.scale = scale; }
+    pub fn set_color(&mut self, color: Vec4) { self.field.color = color; }
+    pub fn clear(&mut self) { self.field.clear(); }
 
-        self.draw_line.draw_line(cx, dvec2(self.plot_area.left, self.plot_area.bottom), dvec2(self.plot_area.right, self.plot_area.bottom), 1.0);
-        self.draw_line.draw_line(cx, dvec2(self.plot_area.left, self.plot_area.bottom), dvec2(self.plot_area.left, self.plot_area.top), 1.0);
+    fn update_axes(&mut self, rect: Rect) {
+        self.axes.margins = PlotMargins { left: self.left_margin, top: self.top_margin, right: self.right_margin, bottom: self.bottom_margin };
+        self.axes.set_show_grid(false);
+        let (x, y) = (&self.field.x, &self.field.y);
+        let (x_min, x_max) = (x.iter().cloned().fold(f64::MAX, f64::min), x.iter().cloned().fold(f64::MIN, f64::max));
+        let (y_min, y_max) = (y.iter().cloned().fold(f64::MAX, f64::min), y.iter().cloned().fold(f64::MIN, f64::max));
+        self.axes.x.range = (x_min, x_min + (x_max - x_min).max(1e-10));
+        self.axes.y.range = (y_min, y_min + (y_max - y_min).max(1e-10));
+        self.axes.update_plot_area(rect);
+    }
 
-        let color = self.draw_line.color;
-        for i in 0..n {
-            let px = self.plot_area.left + (self.x[i] - x_min) / xr * self.plot_area.width();
-            let py = self.plot_area.bottom - (self.y[i] - y_min) / yr * self.plot_area.height();
-            let (dx, dy) = (self.u[i] * scale, -self.v[i] * scale);
-            let (p1, p2) = (dvec2(px, py), dvec2(px + dx, py + dy));
-            self.draw_line.color = color;
-            self.draw_line.draw_line(cx, p1, p2, 1.5);
-            let len = ((p2.x - p1.x).powi(2) + (p2.y - p1.y).powi(2)).sqrt();
-            if len > 1.0 {
-                let (dirx, diry) = ((p2.x - p1.x) / len, (p2.y - p1.y) / len);
-                let (perpx, perpy) = (-diry, dirx);
-                let (al, aa) = (5.0, 0.4);
-                self.draw_line.draw_line(cx, p2, dvec2(p2.x - dirx * al + perpx * al * aa, p2.y - diry * al + perpy * al * aa), 1.5);
-                self.draw_line.draw_line(cx, p2, dvec2(p2.x - dirx * al - perpx * al * aa, p2.y - diry * al - perpy * al * aa), 1.5);
-            }
-        }
+    fn draw_arrows(&mut self, cx: &mut Cx2d) {
+        if self.field.is_empty() { return; }
+        self.axes.draw_axes(cx, &mut self.draw_line);
+        self.field.draw(cx, &mut self.draw_line, &self.axes);
     }
 
     fn draw_labels(&mut self, cx: &mut Cx2d) {
+        self.axes.draw_tick_labels(cx, &mut self.label);
         self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
         if !self.title.is_empty() {
-            self.label.draw_at(cx, dvec2((self.plot_area.left + self.plot_area.right) / 2.0, self.plot_area.top - 15.0), &self.title, TextAnchor::Center);
+            self.label.draw_at(cx, dvec2((self.axes.plot_area.left + self.axes.plot_area.right) / 2.0, self.axes.plot_area.top - 15.0), &self.title, TextAnchor::Center);
         }
     }
 }
@@ -252,9 +514,65 @@ impl QuiverPlot {
 impl QuiverPlotRef {
     pub fn set_title(&self, title: impl Into<String>) { if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); } }
     pub fn set_data(&self, x: Vec<f64>, y: Vec<f64>, u: Vec<f64>, v: Vec<f64>) { if let Some(mut inner) = self.borrow_mut() { inner.set_data(x, y, u, v); } }
+    pub fn set_field(&self, field: QuiverField) { if let Some(mut inner) = self.borrow_mut() { inner.set_field(field); } }
     pub fn set_scale(&self, scale: f64) { if let Some(mut inner) = self.borrow_mut() { inner.set_scale(scale); } }
     pub fn set_color(&self, color: Vec4) { if let Some(mut inner) = self.borrow_mut() { inner.set_color(color); } }
     pub fn clear(&self) { if let Some(mut inner) = self.borrow_mut() { inner.clear(); } }
     pub fn redraw(&self, cx: &mut Cx) { if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); } }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spread_coords() {
+        assert_eq!(spread_coords(3, 0.0, 10.0), vec![0.0, 5.0, 10.0]);
+        // Rows run from the top of the y range down
+        assert_eq!(spread_coords(3, 4.0, 0.0), vec![4.0, 2.0, 0.0]);
+        assert_eq!(spread_coords(1, 0.0, 10.0), vec![0.0]);
+        assert!(spread_coords(0, 0.0, 10.0).is_empty());
+    }
+
+    #[test]
+    fn test_contour_levels() {
+        // Explicit levels win, sorted, deduplicated and finite
+        assert_eq!(contour_levels(&[3.0, f64::NAN, 1.0, 3.0, 2.0], 4, 0.0, 10.0), vec![1.0, 2.0, 3.0]);
+        // Otherwise evenly spaced strictly inside the range
+        assert_eq!(contour_levels(&[], 4, 0.0, 10.0), vec![2.0, 4.0, 6.0, 8.0]);
+        let default = contour_levels(&[], 0, 0.0, 1.0);
+        assert_eq!(default.len(), 10);
+        assert!(default.iter().all(|&l| l > 0.0 && l < 1.0));
+    }
+
+    #[test]
+    fn test_level_label() {
+        let levels = [0.0, 0.25, 0.5];
+        assert_eq!(ContourPlot::level_label(&levels, 0.25), "0.25");
+        assert_eq!(ContourPlot::level_label(&[10.0, 20.0], 20.0), "20");
+    }
+
+    #[test]
+    fn test_polyline_label_gap() {
+        let points = [dvec2(0.0, 0.0), dvec2(10.0, 0.0), dvec2(20.0, 0.0)];
+        assert_eq!(polyline_segments(&points, None).len(), 2);
+        // The gap from 5 to 15 cuts the end of the first segment and the
+        // start of the second
+        assert_eq!(
+            polyline_segments(&points, Some((5.0, 15.0))),
+            vec![(dvec2(0.0, 0.0), dvec2(5.0, 0.0)), (dvec2(15.0, 0.0), dvec2(20.0, 0.0))]
+        );
+        // A gap over a whole segment drops it
+        assert_eq!(polyline_segments(&points, Some((0.0, 10.0))), vec![(dvec2(10.0, 0.0), dvec2(20.0, 0.0))]);
+    }
+
+    #[test]
+    fn test_point_along() {
+        let points = [dvec2(0.0, 0.0), dvec2(10.0, 0.0), dvec2(10.0, 10.0)];
+        assert_eq!(point_along(&points, 5.0), dvec2(5.0, 0.0));
+        assert_eq!(point_along(&points, 15.0), dvec2(10.0, 5.0));
+        // Past the end stays at the last point
+        assert_eq!(point_along(&points, 50.0), dvec2(10.0, 10.0));
+        assert_eq!(point_along(&[], 1.0), dvec2(0.0, 0.0));
+    }
+}
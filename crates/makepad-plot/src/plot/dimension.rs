@@ -0,0 +1,147 @@
+// Multi-dimensional data - named columns of one value per row, with their
+// axes and brush filtering, shared by parallel coordinates and the scatter
+// matrix
+
+use super::*;
+
+/// One column of a multi-dimensional dataset. Row `i` of the dataset is
+/// the `i`th value of every dimension.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlotDimension {
+    pub name: String,
+    pub values: Vec<f64>,
+    pub scale: ScaleType,
+    /// Axis limits; the finite values' extent when None
+    pub range: Option<(f64, f64)>,
+}
+
+impl PlotDimension {
+    pub fn new(name: impl Into<String>, values: Vec<f64>) -> Self {
+        Self { name: name.into(), values, scale: ScaleType::Linear, range: None }
+    }
+
+    pub fn with_scale(mut self, scale: ScaleType) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Value of row `row`, None when missing or not finite (or not positive
+    /// on a log scale)
+    pub fn value(&self, row: usize) -> Option<f64> {
+        let value = *self.values.get(row)?;
+        let valid = value.is_finite() && (self.scale != ScaleType::Log || value > 0.0);
+        valid.then_some(value)
+    }
+
+    /// Axis over the range or the values' extent, widened by `padding` of
+    /// its span on both ends (in scale space, so log axes pad evenly)
+    pub fn axis(&self, padding: f64) -> PlotAxis {
+        let (min, max) = self.range.unwrap_or_else(|| {
+            let (lo, hi) = (0..self.values.len())
+                .filter_map(|row| self.value(row))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+            if lo.is_finite() { (lo, hi) } else if self.scale == ScaleType::Log { (1.0, 10.0) } else { (0.0, 1.0) }
+        });
+        let (mut t0, mut t1) = (self.scale.transform(min), self.scale.transform(max));
+        if t1 <= t0 {
+            (t0, t1) = (t0 - 0.5, t0 + 0.5);
+        }
+        let pad = (t1 - t0) * padding;
+        PlotAxis {
+            scale: self.scale,
+            range: (self.scale.inverse(t0 - pad), self.scale.inverse(t1 + pad)),
+            tick_count: 4,
+            ..PlotAxis::default()
+        }
+    }
+}
+
+/// Rows of a dataset: as many as its shortest dimension
+pub fn row_count(dimensions: &[PlotDimension]) -> usize {
+    dimensions.iter().map(|d| d.values.len()).min().unwrap_or(0)
+}
+
+/// Brushes of a per-dimension brush list as `(dimension, (min, max))`,
+/// skipping unbrushed dimensions
+pub fn active_brushes(brushes: &[Option<(f64, f64)>]) -> Vec<(usize, (f64, f64))> {
+    brushes.iter().enumerate().filter_map(|(i, brush)| Some((i, (*brush)?))).collect()
+}
+
+/// Rows whose value in every brushed dimension lies inside its brush; all
+/// rows when nothing is brushed. Brushes are `(dimension, (min, max))`.
+pub fn rows_in_brushes(dimensions: &[PlotDimension], brushes: &[(usize, (f64, f64))]) -> Vec<usize> {
+    (0..row_count(dimensions))
+        .filter(|&row| {
+            brushes.iter().all(|&(dimension, (min, max))| {
+                let value = dimensions.get(dimension).and_then(|d| d.value(row));
+                value.is_some_and(|v| v >= min && v <= max)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset() -> Vec<PlotDimension> {
+        vec![
+            PlotDimension::new("a", vec![1.0, 2.0, 3.0, 4.0, 5.0]),
+            PlotDimension::new("b", vec![10.0, f64::NAN, 30.0, 40.0, 50.0]),
+            PlotDimension::new("c", vec![-1.0, 1.0, 10.0, 100.0, 1000.0]).with_scale(ScaleType::Log),
+        ]
+    }
+
+    #[test]
+    fn test_row_count_is_shortest_dimension() {
+        let mut dimensions = dataset();
+        assert_eq!(row_count(&dimensions), 5);
+        dimensions.push(PlotDimension::new("d", vec![0.0, 0.0]));
+        assert_eq!(row_count(&dimensions), 2);
+        assert_eq!(row_count(&[]), 0);
+    }
+
+    #[test]
+    fn test_value_skips_missing_and_invalid() {
+        let dimensions = dataset();
+        assert_eq!(dimensions[0].value(4), Some(5.0));
+        assert_eq!(dimensions[0].value(5), None);
+        assert_eq!(dimensions[1].value(1), None);
+        // Non-positive values have no place on a log axis
+        assert_eq!(dimensions[2].value(0), None);
+        assert_eq!(dimensions[2].value(1), Some(1.0));
+    }
+
+    #[test]
+    fn test_rows_in_brushes() {
+        let dimensions = dataset();
+        // Nothing brushed selects every row
+        assert_eq!(rows_in_brushes(&dimensions, &[]), vec![0, 1, 2, 3, 4]);
+        // Brush bounds are inclusive
+        assert_eq!(rows_in_brushes(&dimensions, &[(0, (2.0, 4.0))]), vec![1, 2, 3]);
+        // A row must lie inside every brush
+        assert_eq!(rows_in_brushes(&dimensions, &[(0, (2.0, 5.0)), (1, (0.0, 45.0))]), vec![2, 3]);
+        // Missing values never match, even under a brush spanning everything
+        assert_eq!(rows_in_brushes(&dimensions, &[(1, (f64::NEG_INFINITY, f64::INFINITY))]), vec![0, 2, 3, 4]);
+        assert_eq!(rows_in_brushes(&dimensions, &[(2, (-10.0, 10.0))]), vec![1, 2]);
+        // A brush on an unknown dimension matches nothing
+        assert!(rows_in_brushes(&dimensions, &[(7, (0.0, 100.0))]).is_empty());
+    }
+
+    #[test]
+    fn test_active_brushes() {
+        let brushes = [None, Some((2.0, 4.0)), None, Some((0.0, 1.0))];
+        assert_eq!(active_brushes(&brushes), vec![(1, (2.0, 4.0)), (3, (0.0, 1.0))]);
+        assert!(active_brushes(&[None, None]).is_empty());
+
+        // Per-axis brushes as parallel coordinates keep them
+        let dimensions = dataset();
+        let brushes = [Some((1.0, 4.0)), Some((20.0, 50.0)), None];
+        assert_eq!(rows_in_brushes(&dimensions, &active_brushes(&brushes)), vec![2, 3]);
+    }
+}
@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -14,6 +15,7 @@ live_design! {
         height: Fill,
         flow: Down,
         spacing: 10.0,
+        label: <PlotLabel> {}
     }
 
     pub LinePlotDual = {{LinePlotDual}} {
@@ -23,14 +25,142 @@ live_design! {
     }
 }
 
+/// How subplots in a `SubplotGrid` share an axis (matplotlib `sharex`/`sharey`)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AxisSharing {
+    #[default]
+    None,
+    /// Every subplot in the grid
+    All,
+    /// Subplots starting in the same row
+    Row,
+    /// Subplots starting in the same column
+    Col,
+}
+
+impl AxisSharing {
+    fn group(self, cell: &SubplotCell) -> Option<usize> {
+        match self {
+            AxisSharing::None => None,
+            AxisSharing::All => Some(0),
+            AxisSharing::Row => Some(cell.row),
+            AxisSharing::Col => Some(cell.col),
+        }
+    }
+}
+
+/// Placement of a child plot in a `SubplotGrid`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubplotCell {
+    /// Id of the child widget inside the grid
+    pub id: LiveId,
+    pub row: usize,
+    pub col: usize,
+    pub row_span: usize,
+    pub col_span: usize,
+}
+
+/// Plot widgets a `SubplotGrid` can link
+pub trait Subplot {
+    fn subplot_axes(&self) -> &Axes;
+    fn subplot_axes_mut(&mut self) -> &mut Axes;
+    /// Label and color of each series, for the figure legend
+    fn legend_entries(&self) -> Vec<(String, Vec4)>;
+}
+
+fn with_subplot<R>(widget: &WidgetRef, f: impl FnOnce(&mut dyn Subplot) -> R) -> Option<R> {
+    if let Some(mut plot) = widget.borrow_mut::<LinePlot>() {
+        return Some(f(&mut *plot));
+    }
+    if let Some(mut plot) = widget.borrow_mut::<ScatterPlot>() {
+        return Some(f(&mut *plot));
+    }
+    None
+}
+
+fn spans_overlap(a: usize, a_span: usize, b: usize, b_span: usize) -> bool {
+    a < b + b_span && b < a + a_span
+}
+
+/// Range a shared axis group should take, given each member's current
+/// range and its range at the last sync. `None` when nothing moved.
+fn linked_range(current: &[(f64, f64)], synced: &[Option<(f64, f64)>]) -> Option<(f64, f64)> {
+    let moved: Vec<usize> = (0..current.len()).filter(|&i| synced[i] != Some(current[i])).collect();
+    if moved.len() == current.len() {
+        Some(current.iter().fold((f64::MAX, f64::MIN), |acc, r| (acc.0.min(r.0), acc.1.max(r.1))))
+    } else {
+        moved.first().map(|&i| current[i])
+    }
+}
+
+/// Whether `cell` hides its x and y tick labels: x when a linked plot sits
+/// below it, y when one sits to its left
+fn hidden_tick_labels(cells: &[SubplotCell], sharex: AxisSharing, sharey: AxisSharing, cell: &SubplotCell) -> (bool, bool) {
+    let x_group = sharex.group(cell);
+    let y_group = sharey.group(cell);
+    let hide_x = x_group.is_some() && cells.iter().any(|other| {
+        sharex.group(other) == x_group
+            && other.row >= cell.row + cell.row_span
+            && spans_overlap(other.col, other.col_span, cell.col, cell.col_span)
+    });
+    let hide_y = y_group.is_some() && cells.iter().any(|other| {
+        sharey.group(other) == y_group
+            && other.col + other.col_span <= cell.col
+            && spans_overlap(other.row, other.row_span, cell.row, cell.row_span)
+    });
+    (hide_x, hide_y)
+}
+
+/// Rect of `cell` in a `rows` x `cols` grid filling `grid`. Cells and
+/// spans past the last row or column are clamped into the grid.
+fn cell_rect(grid: Rect, rows: usize, cols: usize, spacing: DVec2, cell: &SubplotCell) -> Rect {
+    let cell_w = ((grid.size.x - spacing.x * (cols - 1) as f64) / cols as f64).max(0.0);
+    let cell_h = ((grid.size.y - spacing.y * (rows - 1) as f64) / rows as f64).max(0.0);
+    let row = cell.row.min(rows - 1);
+    let col = cell.col.min(cols - 1);
+    let row_span = cell.row_span.min(rows - row) as f64;
+    let col_span = cell.col_span.min(cols - col) as f64;
+    Rect {
+        pos: dvec2(
+            grid.pos.x + col as f64 * (cell_w + spacing.x),
+            grid.pos.y + row as f64 * (cell_h + spacing.y),
+        ),
+        size: dvec2(
+            col_span * cell_w + (col_span - 1.0) * spacing.x,
+            row_span * cell_h + (row_span - 1.0) * spacing.y,
+        ),
+    }
+}
+
+/// Figure holding child plots on a grid, like matplotlib `plt.subplots`.
+/// Children are declared inside the grid and placed with `add_subplot`;
+/// without placements the grid lays them out like a plain `View`.
+///
+/// ```ignore
+/// grid.set_grid(2, 2);
+/// grid.add_subplot_span(live_id!(price), 0, 0, 1, 2);
+/// grid.add_subplot(live_id!(volume), 1, 0);
+/// grid.add_subplot(live_id!(spread), 1, 1);
+/// grid.set_sharex(AxisSharing::Col);
+/// ```
 #[derive(Live, LiveHook, Widget)]
 pub struct SubplotGrid {
     #[deref] #[live] view: View,
     #[live] draw_bg: DrawColor,
+    #[live] draw_point: DrawPlotPoint,
+    #[live] label: PlotLabel,
     #[rust] rows: usize,
     #[rust] cols: usize,
     #[rust] h_spacing: f64,
     #[rust] v_spacing: f64,
+    #[rust] cells: Vec<SubplotCell>,
+    #[rust] sharex: AxisSharing,
+    #[rust] sharey: AxisSharing,
+    /// Ranges of each cell after the last sync, to spot pan and zoom
+    #[rust] synced_ranges: Vec<Option<[(f64, f64); 2]>>,
+    #[rust] title: String,
+    #[rust(LegendPosition::None)] legend_position: LegendPosition,
+    #[rust] area: Area,
 }
 
 impl SubplotGrid {
@@ -44,8 +174,157 @@ impl SubplotGrid {
         self.v_spacing = v;
     }
 
+    /// Place child `id` in one grid cell
+    pub fn add_subplot(&mut self, id: LiveId, row: usize, col: usize) {
+        self.add_subplot_span(id, row, col, 1, 1);
+    }
+
+    /// Place child `id` across `row_span` rows and `col_span` columns
+    pub fn add_subplot_span(&mut self, id: LiveId, row: usize, col: usize, row_span: usize, col_span: usize) {
+        self.cells.retain(|cell| cell.id != id);
+        self.cells.push(SubplotCell { id, row, col, row_span: row_span.max(1), col_span: col_span.max(1) });
+        self.synced_ranges.clear();
+    }
+
+    pub fn clear_subplots(&mut self) {
+        self.cells.clear();
+        self.synced_ranges.clear();
+    }
+
+    /// Link x ranges; linked plots pan and zoom together and only the
+    /// bottom plot of each column keeps its x tick labels
+    pub fn set_sharex(&mut self, sharing: AxisSharing) {
+        self.sharex = sharing;
+        self.synced_ranges.clear();
+    }
+
+    /// Link y ranges; only the leftmost plot of each row keeps its y tick labels
+    pub fn set_sharey(&mut self, sharing: AxisSharing) {
+        self.sharey = sharing;
+        self.synced_ranges.clear();
+    }
+
+    /// Figure title above all subplots
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    /// Figure legend with one entry per distinct series label across subplots.
+    /// Top positions put it under the title, bottom positions below the grid.
+    pub fn set_legend(&mut self, position: LegendPosition) {
+        self.legend_position = position;
+    }
+
     pub fn redraw(&mut self, cx: &mut Cx) {
         self.view.redraw(cx);
+        self.area.redraw(cx);
+    }
+
+    fn cell_widgets(&self) -> Vec<WidgetRef> {
+        self.cells.iter().map(|cell| self.view.widget(&[cell.id])).collect()
+    }
+
+    /// Align shared ranges and hide redundant tick labels. A range moved by
+    /// pan/zoom since the last sync is copied to the rest of its group;
+    /// when every member changed (first layout, new data) the group takes
+    /// their union. Returns true if any subplot was changed.
+    fn sync_axes(&mut self) -> bool {
+        let widgets = self.cell_widgets();
+        let current = |widgets: &[WidgetRef]| -> Vec<Option<[(f64, f64); 2]>> {
+            widgets.iter().map(|w| with_subplot(w, |plot| {
+                let axes = plot.subplot_axes();
+                [axes.x.range, axes.y.range]
+            })).collect()
+        };
+        let ranges = current(&widgets);
+        self.synced_ranges.resize(self.cells.len(), None);
+        let mut changed = false;
+
+        for (axis, sharing) in [(0, self.sharex), (1, self.sharey)] {
+            let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+            for (i, cell) in self.cells.iter().enumerate() {
+                let Some(key) = sharing.group(cell).filter(|_| ranges[i].is_some()) else {
+                    continue;
+                };
+                match groups.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, members)) => members.push(i),
+                    None => groups.push((key, vec![i])),
+                }
+            }
+
+            for (_, members) in &groups {
+                let range_of = |i: usize| ranges[i].map_or((0.0, 1.0), |r| r[axis]);
+                let current: Vec<(f64, f64)> = members.iter().map(|&i| range_of(i)).collect();
+                let synced: Vec<Option<(f64, f64)>> = members.iter().map(|&i| self.synced_ranges[i].map(|r| r[axis])).collect();
+                let Some(target) = linked_range(&current, &synced) else {
+                    continue;
+                };
+                for &i in members {
+                    if range_of(i) != target {
+                        with_subplot(&widgets[i], |plot| {
+                            let axes = plot.subplot_axes_mut();
+                            if axis == 0 { axes.x.range = target; } else { axes.y.range = target; }
+                        });
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        for (i, cell) in self.cells.iter().enumerate() {
+            let x_linked = self.sharex.group(cell).is_some();
+            let y_linked = self.sharey.group(cell).is_some();
+            let (hide_x, hide_y) = hidden_tick_labels(&self.cells, self.sharex, self.sharey, cell);
+            with_subplot(&widgets[i], |plot| {
+                let axes = plot.subplot_axes_mut();
+                if x_linked && axes.x.show_labels == hide_x {
+                    axes.x.show_labels = !hide_x;
+                    changed = true;
+                }
+                if y_linked && axes.y.show_labels == hide_y {
+                    axes.y.show_labels = !hide_y;
+                    changed = true;
+                }
+            });
+        }
+
+        self.synced_ranges = current(&widgets);
+        changed
+    }
+
+    fn legend_entries(&self) -> Vec<(String, Vec4)> {
+        let mut entries: Vec<(String, Vec4)> = Vec::new();
+        for widget in self.cell_widgets() {
+            for (label, color) in with_subplot(&widget, |plot| plot.legend_entries()).unwrap_or_default() {
+                if !label.is_empty() && !entries.iter().any(|(l, _)| *l == label) {
+                    entries.push((label, color));
+                }
+            }
+        }
+        entries
+    }
+
+    /// One-row legend centered vertically on `y`
+    fn draw_legend(&mut self, cx: &mut Cx2d, entries: &[(String, Vec4)], rect: Rect, y: f64) {
+        let marker_radius = 5.0;
+        let marker_text_gap = 6.0;
+        let entry_gap = 16.0;
+        self.label.set_font_size(10.0);
+        self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
+        let widths: Vec<f64> = entries.iter()
+            .map(|(label, _)| marker_radius * 2.0 + marker_text_gap + self.label.measure(cx, label).x)
+            .collect();
+        let total = widths.iter().sum::<f64>() + entry_gap * widths.len().saturating_sub(1) as f64;
+        let mut x = match self.legend_position {
+            LegendPosition::TopLeft | LegendPosition::BottomLeft => rect.pos.x + 10.0,
+            _ => rect.pos.x + rect.size.x - total - 10.0,
+        };
+        for ((label, color), width) in entries.iter().zip(widths) {
+            self.draw_point.color = *color;
+            self.draw_point.draw_point(cx, dvec2(x + marker_radius, y), marker_radius);
+            self.label.draw_at(cx, dvec2(x + marker_radius * 2.0 + marker_text_gap, y), label, TextAnchor::MiddleLeft);
+            x += width + entry_gap;
+        }
     }
 }
 
@@ -57,11 +336,55 @@ impl Widget for SubplotGrid {
         if self.h_spacing == 0.0 { self.h_spacing = 10.0; }
         if self.v_spacing == 0.0 { self.v_spacing = 10.0; }
 
-        self.view.draw_walk(cx, scope, walk)
+        if self.cells.is_empty() {
+            return self.view.draw_walk(cx, scope, walk);
+        }
+
+        self.sync_axes();
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        let mut grid = rect;
+
+        if !self.title.is_empty() {
+            self.label.set_font_size(13.0);
+            self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+            let title_height = self.label.measure(cx, &self.title).y;
+            self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + 6.0), &self.title, TextAnchor::TopCenter);
+            let band = title_height + 12.0;
+            grid.pos.y += band;
+            grid.size.y -= band;
+        }
+
+        let entries = self.legend_entries();
+        if self.legend_position != LegendPosition::None && !entries.is_empty() {
+            let band = 20.0;
+            let y = match self.legend_position {
+                LegendPosition::TopLeft | LegendPosition::TopRight => {
+                    grid.pos.y += band;
+                    grid.pos.y - band / 2.0
+                }
+                _ => grid.pos.y + grid.size.y - band / 2.0,
+            };
+            grid.size.y -= band;
+            self.draw_legend(cx, &entries, rect, y);
+        }
+
+        let spacing = dvec2(self.h_spacing, self.v_spacing);
+        let widgets = self.cell_widgets();
+        for (cell, widget) in self.cells.iter().zip(widgets) {
+            let cell_rect = cell_rect(grid, self.rows, self.cols, spacing, cell);
+            let _ = widget.draw_walk(cx, scope, Walk::abs_rect(cell_rect));
+        }
+
+        DrawStep::done()
     }
 
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         self.view.handle_event(cx, event, scope);
+
+        // Carry pan/zoom in one linked plot over to the others
+        if !self.cells.is_empty() && self.sync_axes() {
+            self.area.redraw(cx);
+        }
     }
 }
 
@@ -72,6 +395,27 @@ impl SubplotGridRef {
     pub fn set_spacing(&self, h: f64, v: f64) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_spacing(h, v); }
     }
+    pub fn add_subplot(&self, id: LiveId, row: usize, col: usize) {
+        if let Some(mut inner) = self.borrow_mut() { inner.add_subplot(id, row, col); }
+    }
+    pub fn add_subplot_span(&self, id: LiveId, row: usize, col: usize, row_span: usize, col_span: usize) {
+        if let Some(mut inner) = self.borrow_mut() { inner.add_subplot_span(id, row, col, row_span, col_span); }
+    }
+    pub fn clear_subplots(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.clear_subplots(); }
+    }
+    pub fn set_sharex(&self, sharing: AxisSharing) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_sharex(sharing); }
+    }
+    pub fn set_sharey(&self, sharing: AxisSharing) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_sharey(sharing); }
+    }
+    pub fn set_title(&self, title: impl Into<String>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
+    }
+    pub fn set_legend(&self, position: LegendPosition) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_legend(position); }
+    }
     pub fn redraw(&self, cx: &mut Cx) {
         if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); }
     }
@@ -94,12 +438,15 @@ pub struct LinePlotDual {
     #[rust] y2_label: String,
     #[rust] series_left: Vec<Series>,
     #[rust] series_right: Vec<Series>,
-    #[rust] x_range: (f64, f64),
-    #[rust] y_range: (f64, f64),
-    #[rust] y2_range: (f64, f64),
+    #[rust(Axes::with_margins(60.0, 40.0, 60.0, 50.0))] axes: Axes,
+    /// Right-hand y axis for `series_right`
+    #[rust] y2_axis: PlotAxis,
     #[rust] show_grid: bool,
     #[rust] show_legend: bool,
     #[rust] legend_position: LegendPosition,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl LinePlotDual {
@@ -130,8 +477,8 @@ impl LinePlotDual {
         if x_min != f64::MAX {
             let pad_x = (x_max - x_min).max(0.1) * 0.05;
             let pad_y = (y_max - y_min).max(0.1) * 0.1;
-            self.x_range = (x_min - pad_x, x_max + pad_x);
-            self.y_range = (y_min - pad_y, y_max + pad_y);
+            self.axes.x.range = (x_min - pad_x, x_max + pad_x);
+            self.axes.y.range = (y_min - pad_y, y_max + pad_y);
         }
     }
 
@@ -144,13 +491,13 @@ impl LinePlotDual {
 
         if y_min != f64::MAX {
             let pad = (y_max - y_min).max(0.1) * 0.1;
-            self.y2_range = (y_min - pad, y_max + pad);
+            self.y2_axis.range = (y_min - pad, y_max + pad);
         }
     }
 
-    pub fn set_xlim(&mut self, min: f64, max: f64) { self.x_range = (min, max); }
-    pub fn set_ylim(&mut self, min: f64, max: f64) { self.y_range = (min, max); }
-    pub fn set_y2lim(&mut self, min: f64, max: f64) { self.y2_range = (min, max); }
+    pub fn set_xlim(&mut self, min: f64, max: f64) { self.axes.x.range = (min, max); }
+    pub fn set_ylim(&mut self, min: f64, max: f64) { self.axes.y.range = (min, max); }
+    pub fn set_y2lim(&mut self, min: f64, max: f64) { self.y2_axis.range = (min, max); }
     pub fn set_grid(&mut self, show: bool) { self.show_grid = show; }
     pub fn set_legend(&mut self, pos: LegendPosition) { self.show_legend = true; self.legend_position = pos; }
     pub fn clear(&mut self) { self.series_left.clear(); self.series_right.clear(); }
@@ -160,46 +507,34 @@ impl LinePlotDual {
 impl Widget for LinePlotDual {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 {
-            let margin_left = 60.0;
-            let margin_right = 60.0;
-            let margin_top = 40.0;
-            let margin_bottom = 50.0;
-
+            self.axes.update_plot_area(rect);
+            let area = self.axes.plot_area;
             let plot_rect = Rect {
-                pos: dvec2(rect.pos.x + margin_left, rect.pos.y + margin_top),
-                size: dvec2(rect.size.x - margin_left - margin_right, rect.size.y - margin_top - margin_bottom),
+                pos: dvec2(area.left, area.top),
+                size: dvec2(area.width(), area.height()),
             };
 
             if plot_rect.size.x > 0.0 && plot_rect.size.y > 0.0 {
                 // Initialize ranges if needed
-                if self.x_range.0 >= self.x_range.1 { self.x_range = (0.0, 1.0); }
-                if self.y_range.0 >= self.y_range.1 { self.y_range = (0.0, 1.0); }
-                if self.y2_range.0 >= self.y2_range.1 { self.y2_range = (0.0, 1.0); }
-
-                // Draw grid
-                if self.show_grid {
-                    self.draw_line.color = vec4(0.9, 0.9, 0.9, 1.0);
-                    for i in 0..=5 {
-                        let t = i as f64 / 5.0;
-                        let x = plot_rect.pos.x + t * plot_rect.size.x;
-                        let y = plot_rect.pos.y + t * plot_rect.size.y;
-                        self.draw_line.draw_line(cx, dvec2(x, plot_rect.pos.y), dvec2(x, plot_rect.pos.y + plot_rect.size.y), 1.0);
-                        self.draw_line.draw_line(cx, dvec2(plot_rect.pos.x, y), dvec2(plot_rect.pos.x + plot_rect.size.x, y), 1.0);
-                    }
+                if self.axes.x.range.0 >= self.axes.x.range.1 { self.axes.x.range = (0.0, 1.0); }
+                if self.axes.y.range.0 >= self.axes.y.range.1 { self.axes.y.range = (0.0, 1.0); }
+                if self.y2_axis.range.0 >= self.y2_axis.range.1 { self.y2_axis.range = (0.0, 1.0); }
+
+                // Draw grid (left axis ticks) and axes
+                self.axes.set_show_grid(self.show_grid);
+                self.axes.draw_grid(cx, &mut self.draw_line);
+                self.axes.draw_axes(cx, &mut self.draw_line);
+
+                // Right y-axis with its own ticks
+                self.draw_line.draw_line(cx, dvec2(area.right, area.top), dvec2(area.right, area.bottom), 1.0);
+                for v in self.y2_axis.ticks() {
+                    let y = area.bottom - self.y2_axis.normalize(v) * area.height();
+                    self.draw_line.draw_line(cx, dvec2(area.right, y), dvec2(area.right + 4.0, y), 1.0);
                 }
 
-                // Draw axes
-                self.draw_line.color = vec4(0.2, 0.2, 0.2, 1.0);
-                self.draw_line.draw_line(cx, dvec2(plot_rect.pos.x, plot_rect.pos.y + plot_rect.size.y),
-                    dvec2(plot_rect.pos.x + plot_rect.size.x, plot_rect.pos.y + plot_rect.size.y), 1.5);
-                self.draw_line.draw_line(cx, dvec2(plot_rect.pos.x, plot_rect.pos.y),
-                    dvec2(plot_rect.pos.x, plot_rect.pos.y + plot_rect.size.y), 1.5);
-                // Right y-axis
-                self.draw_line.draw_line(cx, dvec2(plot_rect.pos.x + plot_rect.size.x, plot_rect.pos.y),
-                    dvec2(plot_rect.pos.x + plot_rect.size.x, plot_rect.pos.y + plot_rect.size.y), 1.5);
-
                 // Draw left series
                 for (idx, s) in self.series_left.iter().enumerate() {
                     let color = s.color.unwrap_or_else(|| get_color(idx));
@@ -207,10 +542,10 @@ impl Widget for LinePlotDual {
                     self.draw_line.color = color;
                     let n = s.x.len().min(s.y.len());
                     for i in 1..n {
-                        let x0 = plot_rect.pos.x + (s.x[i-1] - self.x_range.0) / (self.x_range.1 - self.x_range.0) * plot_rect.size.x;
-                        let y0 = plot_rect.pos.y + plot_rect.size.y - (s.y[i-1] - self.y_range.0) / (self.y_range.1 - self.y_range.0) * plot_rect.size.y;
-                        let x1 = plot_rect.pos.x + (s.x[i] - self.x_range.0) / (self.x_range.1 - self.x_range.0) * plot_rect.size.x;
-                        let y1 = plot_rect.pos.y + plot_rect.size.y - (s.y[i] - self.y_range.0) / (self.y_range.1 - self.y_range.0) * plot_rect.size.y;
+                        let x0 = plot_rect.pos.x + (s.x[i-1] - self.axes.x.range.0) / (self.axes.x.range.1 - self.axes.x.range.0) * plot_rect.size.x;
+                        let y0 = plot_rect.pos.y + plot_rect.size.y - (s.y[i-1] - self.axes.y.range.0) / (self.axes.y.range.1 - self.axes.y.range.0) * plot_rect.size.y;
+                        let x1 = plot_rect.pos.x + (s.x[i] - self.axes.x.range.0) / (self.axes.x.range.1 - self.axes.x.range.0) * plot_rect.size.x;
+                        let y1 = plot_rect.pos.y + plot_rect.size.y - (s.y[i] - self.axes.y.range.0) / (self.axes.y.range.1 - self.axes.y.range.0) * plot_rect.size.y;
                         self.draw_line.draw_line(cx, dvec2(x0, y0), dvec2(x1, y1), line_width);
                     }
                 }
@@ -222,37 +557,19 @@ impl Widget for LinePlotDual {
                     self.draw_line.color = color;
                     let n = s.x.len().min(s.y.len());
                     for i in 1..n {
-                        let x0 = plot_rect.pos.x + (s.x[i-1] - self.x_range.0) / (self.x_range.1 - self.x_range.0) * plot_rect.size.x;
-                        let y0 = plot_rect.pos.y + plot_rect.size.y - (s.y[i-1] - self.y2_range.0) / (self.y2_range.1 - self.y2_range.0) * plot_rect.size.y;
-                        let x1 = plot_rect.pos.x + (s.x[i] - self.x_range.0) / (self.x_range.1 - self.x_range.0) * plot_rect.size.x;
-                        let y1 = plot_rect.pos.y + plot_rect.size.y - (s.y[i] - self.y2_range.0) / (self.y2_range.1 - self.y2_range.0) * plot_rect.size.y;
+                        let x0 = plot_rect.pos.x + (s.x[i-1] - self.axes.x.range.0) / (self.axes.x.range.1 - self.axes.x.range.0) * plot_rect.size.x;
+                        let y0 = plot_rect.pos.y + plot_rect.size.y - (s.y[i-1] - self.y2_axis.range.0) / (self.y2_axis.range.1 - self.y2_axis.range.0) * plot_rect.size.y;
+                        let x1 = plot_rect.pos.x + (s.x[i] - self.axes.x.range.0) / (self.axes.x.range.1 - self.axes.x.range.0) * plot_rect.size.x;
+                        let y1 = plot_rect.pos.y + plot_rect.size.y - (s.y[i] - self.y2_axis.range.0) / (self.y2_axis.range.1 - self.y2_axis.range.0) * plot_rect.size.y;
                         self.draw_line.draw_line(cx, dvec2(x0, y0), dvec2(x1, y1), line_width);
                     }
                 }
 
-                // Draw axis labels
-                // Left Y-axis labels
-                for i in 0..=5 {
-                    let t = i as f64 / 5.0;
-                    let val = self.y_range.0 + t * (self.y_range.1 - self.y_range.0);
-                    let y = plot_rect.pos.y + plot_rect.size.y - t * plot_rect.size.y;
-                    self.label.draw_at(cx, dvec2(plot_rect.pos.x - 5.0, y), &format!("{:.1}", val), TextAnchor::MiddleRight);
-                }
-
-                // Right Y-axis labels
-                for i in 0..=5 {
-                    let t = i as f64 / 5.0;
-                    let val = self.y2_range.0 + t * (self.y2_range.1 - self.y2_range.0);
-                    let y = plot_rect.pos.y + plot_rect.size.y - t * plot_rect.size.y;
-                    self.label.draw_at(cx, dvec2(plot_rect.pos.x + plot_rect.size.x + 5.0, y), &format!("{:.1}", val), TextAnchor::MiddleLeft);
-                }
-
-                // X-axis labels
-                for i in 0..=5 {
-                    let t = i as f64 / 5.0;
-                    let val = self.x_range.0 + t * (self.x_range.1 - self.x_range.0);
-                    let x = plot_rect.pos.x + t * plot_rect.size.x;
-                    self.label.draw_at(cx, dvec2(x, plot_rect.pos.y + plot_rect.size.y + 15.0), &format!("{:.1}", val), TextAnchor::TopCenter);
+                // Draw axis labels (left y and x, then right y)
+                self.axes.draw_tick_labels(cx, &mut self.label);
+                for v in self.y2_axis.ticks() {
+                    let y = area.bottom - self.y2_axis.normalize(v) * area.height();
+                    self.label.draw_at(cx, dvec2(area.right + 6.0, y), &self.y2_axis.format(v), TextAnchor::MiddleLeft);
                 }
 
                 // Title
@@ -294,6 +611,7 @@ impl Widget for LinePlotDual {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -302,6 +620,16 @@ impl Widget for LinePlotDual {
     }
 }
 
+impl PlotExport for LinePlotDual {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl LinePlotDualRef {
     pub fn set_title(&self, title: impl Into<String>) { if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); } }
     pub fn set_xlabel(&self, label: impl Into<String>) { if let Some(mut inner) = self.borrow_mut() { inner.set_xlabel(label); } }
@@ -318,3 +646,67 @@ impl LinePlotDualRef {
     pub fn redraw(&self, cx: &mut Cx) { if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); } }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(id: LiveId, row: usize, col: usize, row_span: usize, col_span: usize) -> SubplotCell {
+        SubplotCell { id, row, col, row_span, col_span }
+    }
+
+    #[test]
+    fn test_cell_rect() {
+        let grid = Rect { pos: dvec2(10.0, 20.0), size: dvec2(210.0, 110.0) };
+        let spacing = dvec2(10.0, 10.0);
+        // 2x2 grid of 100x50 cells
+        let single = cell_rect(grid, 2, 2, spacing, &cell(live_id!(a), 1, 1, 1, 1));
+        assert_eq!(single.pos, dvec2(120.0, 80.0));
+        assert_eq!(single.size, dvec2(100.0, 50.0));
+        let wide = cell_rect(grid, 2, 2, spacing, &cell(live_id!(a), 0, 0, 1, 2));
+        assert_eq!(wide.pos, dvec2(10.0, 20.0));
+        assert_eq!(wide.size, dvec2(210.0, 50.0));
+        // Out of range cells and spans are clamped into the grid
+        let clamped = cell_rect(grid, 2, 2, spacing, &cell(live_id!(a), 5, 1, 3, 3));
+        assert_eq!(clamped.pos, dvec2(120.0, 80.0));
+        assert_eq!(clamped.size, dvec2(100.0, 50.0));
+    }
+
+    #[test]
+    fn test_linked_range() {
+        // First sync takes the union of the group
+        assert_eq!(linked_range(&[(0.0, 5.0), (2.0, 8.0)], &[None, None]), Some((0.0, 8.0)));
+        // A pan in one plot is copied to the rest
+        assert_eq!(linked_range(&[(1.0, 6.0), (0.0, 8.0)], &[Some((0.0, 8.0)), Some((0.0, 8.0))]), Some((1.0, 6.0)));
+        // Nothing moved
+        assert_eq!(linked_range(&[(0.0, 8.0), (0.0, 8.0)], &[Some((0.0, 8.0)), Some((0.0, 8.0))]), None);
+    }
+
+    #[test]
+    fn test_hidden_tick_labels() {
+        // Wide plot on top, two below
+        let cells = vec![
+            cell(live_id!(top), 0, 0, 1, 2),
+            cell(live_id!(left), 1, 0, 1, 1),
+            cell(live_id!(right), 1, 1, 1, 1),
+        ];
+        let hidden = |sharex, sharey| -> Vec<(bool, bool)> {
+            cells.iter().map(|c| hidden_tick_labels(&cells, sharex, sharey, c)).collect()
+        };
+        assert_eq!(hidden(AxisSharing::None, AxisSharing::None), vec![(false, false); 3]);
+        assert_eq!(
+            hidden(AxisSharing::All, AxisSharing::All),
+            vec![(true, false), (false, false), (false, true)]
+        );
+        // Column groups start at each cell's first column, so the wide plot
+        // only links with the left plot
+        assert_eq!(
+            hidden(AxisSharing::Col, AxisSharing::Row),
+            vec![(true, false), (false, false), (false, true)]
+        );
+        assert_eq!(
+            hidden(AxisSharing::Row, AxisSharing::Col),
+            vec![(false, false), (false, false), (false, false)]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -22,6 +23,7 @@ live_design! {
     }
 }
 
+#[derive(Clone, Debug)]
 pub struct Candle {
     pub timestamp: f64,  // X position (can be index or actual timestamp)
     pub open: f64,
@@ -46,23 +48,244 @@ impl Candle {
     }
 }
 
+/// Heikin-Ashi candles: each close is the mean of the bar's four prices and
+/// each open the midpoint of the previous Heikin-Ashi body, which smooths out
+/// noise so trends read as runs of one color
+pub fn heikin_ashi(candles: &[Candle]) -> Vec<Candle> {
+    let mut out: Vec<Candle> = Vec::with_capacity(candles.len());
+    for c in candles {
+        let close = (c.open + c.high + c.low + c.close) / 4.0;
+        let open = match out.last() {
+            Some(prev) => (prev.open + prev.close) / 2.0,
+            None => (c.open + c.close) / 2.0,
+        };
+        out.push(Candle {
+            timestamp: c.timestamp,
+            open,
+            high: c.high.max(open).max(close),
+            low: c.low.min(open).min(close),
+            close,
+            volume: c.volume,
+        });
+    }
+    out
+}
+
+/// Study computed from the candles. Overlays share the price pane; the
+/// others get a pane of their own below it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Indicator {
+    /// Simple moving average of the close
+    Sma(usize),
+    /// Exponential moving average of the close
+    Ema(usize),
+    /// Bollinger bands: SMA with `k` standard deviations either side
+    Bollinger { period: usize, k: f64 },
+    /// Volume-weighted average price since the first candle
+    Vwap,
+    /// Relative strength index in a 0-100 pane
+    Rsi(usize),
+    /// MACD line, signal line and histogram
+    Macd { fast: usize, slow: usize, signal: usize },
+}
+
+impl Indicator {
+    /// Whether the indicator is drawn over the candles rather than in a pane
+    pub fn is_overlay(&self) -> bool {
+        !matches!(self, Indicator::Rsi(_) | Indicator::Macd { .. })
+    }
+
+    /// Short legend label, e.g. "SMA 20" or "MACD 12, 26, 9"
+    pub fn label(&self) -> String {
+        match self {
+            Indicator::Sma(period) => format!("SMA {}", period),
+            Indicator::Ema(period) => format!("EMA {}", period),
+            Indicator::Bollinger { period, k } => format!("BB {}, {}", period, k),
+            Indicator::Vwap => "VWAP".to_string(),
+            Indicator::Rsi(period) => format!("RSI {}", period),
+            Indicator::Macd { fast, slow, signal } => format!("MACD {}, {}, {}", fast, slow, signal),
+        }
+    }
+}
+
+/// How each candle is drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CandleStyle {
+    /// Filled body between open and close with a high-low wick
+    #[default]
+    Candles,
+    /// Candles of the smoothed Heikin-Ashi prices
+    HeikinAshi,
+    /// High-low bar with the open ticked left and the close ticked right
+    Ohlc,
+}
+
+/// Horizontal line marking a price level, such as a stop or a target
+#[derive(Clone, Debug)]
+pub struct PriceLine {
+    pub price: f64,
+    pub color: Vec4,
+    pub label: String,
+}
+
+impl PriceLine {
+    pub fn new(price: f64) -> Self {
+        Self { price, color: vec4(0.45, 0.45, 0.45, 1.0), label: String::new() }
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+}
+
+/// Share of the plot height given to each indicator pane
+const PANE_FRACTION: f64 = 0.22;
+/// Vertical space between panes
+const PANE_GAP: f64 = 12.0;
+
+/// Overlay colors, skipping the green and red of the candles
+fn overlay_color(index: usize) -> Vec4 {
+    get_color([0, 1, 4, 5, 6][index % 5])
+}
+
+fn with_alpha(color: Vec4, alpha: f32) -> Vec4 {
+    vec4(color.x, color.y, color.z, alpha)
+}
+
+fn format_price(value: f64) -> String {
+    if value.abs() >= 10000.0 { format!("{:.0}", value) } else { format!("{:.2}", value) }
+}
+
+/// Polyline through the finite values, broken at NaN gaps
+fn draw_series(cx: &mut Cx2d, draw: &mut DrawPlotLine, xs: &[f64], values: &[f64], to_y: impl Fn(f64) -> f64, width: f64) {
+    for i in 1..xs.len().min(values.len()) {
+        let (a, b) = (values[i - 1], values[i]);
+        if a.is_finite() && b.is_finite() {
+            draw.draw_line(cx, dvec2(xs[i - 1], to_y(a)), dvec2(xs[i], to_y(b)), width);
+        }
+    }
+}
+
+/// Shade between two series where both are finite
+fn draw_fill_between(cx: &mut Cx2d, draw: &mut DrawTriangle, xs: &[f64], upper: &[f64], lower: &[f64], to_y: impl Fn(f64) -> f64) {
+    for i in 1..xs.len().min(upper.len()).min(lower.len()) {
+        let values = [upper[i - 1], upper[i], lower[i - 1], lower[i]];
+        if values.iter().any(|v| !v.is_finite()) {
+            continue;
+        }
+        let (u0, u1) = (dvec2(xs[i - 1], to_y(values[0])), dvec2(xs[i], to_y(values[1])));
+        let (l0, l1) = (dvec2(xs[i - 1], to_y(values[2])), dvec2(xs[i], to_y(values[3])));
+        draw.draw_triangle(cx, u0, u1, l1);
+        draw.draw_triangle(cx, u0, l1, l0);
+    }
+}
+
+/// Smallest and largest finite value across `series`
+fn finite_range<'a>(series: impl IntoIterator<Item = &'a Vec<f64>>) -> Option<(f64, f64)> {
+    let (mut min, mut max) = (f64::MAX, f64::MIN);
+    for &v in series.into_iter().flatten().filter(|v| v.is_finite()) {
+        min = min.min(v);
+        max = max.max(v);
+    }
+    (min <= max).then_some((min, max))
+}
+
+/// Bottom of the price pane, and the top and bottom of each study pane
+/// stacked below it, when `pane_count` panes share `full`
+fn pane_bands(full: &PlotArea, pane_count: usize) -> (f64, Vec<(f64, f64)>) {
+    if pane_count == 0 {
+        return (full.bottom, Vec::new());
+    }
+    let pane_height = full.height() * PANE_FRACTION.min(0.6 / pane_count as f64);
+    let first_top = full.bottom - pane_count as f64 * (pane_height + PANE_GAP) + PANE_GAP;
+    let bands = (0..pane_count)
+        .map(|i| {
+            let top = first_top + i as f64 * (pane_height + PANE_GAP);
+            (top, top + pane_height)
+        })
+        .collect();
+    (first_top - PANE_GAP, bands)
+}
+
+/// Indicator output ready to draw, one value per candle
+enum Study {
+    /// Lines over the candles; bands shade between their first and last line
+    Overlay { label: String, color: Vec4, lines: Vec<Vec<f64>>, band: bool },
+    Rsi { label: String, values: Vec<f64> },
+    Macd { label: String, macd: Vec<f64>, signal: Vec<f64>, histogram: Vec<f64> },
+}
+
+/// Studies of `indicators` over `candles`, overlays colored in order
+fn compute_studies(candles: &[Candle], indicators: &[Indicator]) -> Vec<Study> {
+    let close: Vec<f64> = candles.iter().map(|c| c.close).collect();
+    let mut overlays = 0;
+    let mut studies = Vec::new();
+    for indicator in indicators {
+        let label = indicator.label();
+        let mut overlay = |lines: Vec<Vec<f64>>, band: bool| {
+            overlays += 1;
+            Study::Overlay { label: label.clone(), color: overlay_color(overlays - 1), lines, band }
+        };
+        studies.push(match *indicator {
+            Indicator::Sma(period) => overlay(vec![sma(&close, period)], false),
+            Indicator::Ema(period) => overlay(vec![ema(&close, period)], false),
+            Indicator::Bollinger { period, k } => {
+                let bands = bollinger(&close, period, k);
+                overlay(vec![bands.upper, bands.middle, bands.lower], true)
+            }
+            Indicator::Vwap => {
+                let column = |f: fn(&Candle) -> f64| candles.iter().map(f).collect::<Vec<f64>>();
+                let volume = column(|c| c.volume.unwrap_or(f64::NAN));
+                overlay(vec![vwap(&column(|c| c.high), &column(|c| c.low), &close, &volume)], false)
+            }
+            Indicator::Rsi(period) => Study::Rsi { label, values: rsi(&close, period) },
+            Indicator::Macd { fast, slow, signal } => {
+                let m = macd(&close, fast, slow, signal);
+                Study::Macd { label, macd: m.macd, signal: m.signal, histogram: m.histogram }
+            }
+        });
+    }
+    studies
+}
+
 #[derive(Live, LiveHook, Widget)]
 pub struct CandlestickChart {
     #[deref] #[live] view: View,
     #[live] draw_fill: DrawPlotFill,
     #[live] draw_line: DrawPlotLine,
+    #[live] draw_triangle: DrawTriangle,
     #[live] label: PlotLabel,
     #[rust] title: String,
     #[rust] candles: Vec<Candle>,
-    #[rust] plot_area: PlotArea,
+    #[rust] axes: Axes,
     #[rust] bullish_color: Vec4,
     #[rust] bearish_color: Vec4,
     #[rust] show_volume: bool,
     #[rust] candle_width: f64,
-    #[rust(50.0)] left_margin: f64,
-    #[rust(30.0)] bottom_margin: f64,
-    #[rust(20.0)] right_margin: f64,
-    #[rust(30.0)] top_margin: f64,
+    /// Timestamps are Unix seconds and the x axis shows dates
+    #[rust] time_axis: bool,
+    /// Candles are spaced evenly, skipping gaps between sessions
+    #[rust] ordinal: bool,
+    #[rust] indicators: Vec<Indicator>,
+    #[rust] candle_style: CandleStyle,
+    #[rust] price_lines: Vec<PriceLine>,
+    #[rust] show_last_price: bool,
+    #[rust] crosshair: bool,
+    /// Axes of the indicator panes below the price pane, sharing its x axis
+    #[rust] panes: Vec<Axes>,
+    /// Pixel x of each candle as last drawn, for snapping the crosshair
+    #[rust] candle_x: Vec<f64>,
+    #[rust] pointer: Option<DVec2>,
+    #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl CandlestickChart {
@@ -91,49 +314,383 @@ impl CandlestickChart {
         self.candle_width = width;
     }
 
+    /// Label the x axis with calendar dates; candle timestamps must be Unix
+    /// seconds
+    pub fn set_time_axis(&mut self, enabled: bool) {
+        self.time_axis = enabled;
+    }
+
+    /// Place candles one slot apart so nights, weekends and holidays leave no
+    /// gap (business-day axis). Implies a time axis.
+    pub fn set_ordinal(&mut self, ordinal: bool) {
+        self.ordinal = ordinal;
+        if ordinal {
+            self.time_axis = true;
+        }
+    }
+
+    /// Timezone for placing and labelling date ticks
+    pub fn set_timezone(&mut self, timezone: TimeZone) {
+        self.axes.x.timezone = timezone;
+    }
+
+    /// Add an overlay or indicator pane, computed from the candles on every
+    /// draw
+    pub fn add_indicator(&mut self, indicator: Indicator) {
+        self.indicators.push(indicator);
+    }
+
+    pub fn clear_indicators(&mut self) {
+        self.indicators.clear();
+    }
+
+    pub fn set_candle_style(&mut self, style: CandleStyle) {
+        self.candle_style = style;
+    }
+
+    pub fn add_price_line(&mut self, line: PriceLine) {
+        self.price_lines.push(line);
+    }
+
+    pub fn clear_price_lines(&mut self) {
+        self.price_lines.clear();
+    }
+
+    /// Mark the latest close with a line and a price tag at the right edge
+    pub fn set_show_last_price(&mut self, show: bool) {
+        self.show_last_price = show;
+    }
+
+    /// Follow the pointer with a crosshair snapped to the nearest candle and
+    /// a readout of its open, high, low and close
+    pub fn set_crosshair(&mut self, enabled: bool) {
+        self.crosshair = enabled;
+        if !enabled {
+            self.pointer = None;
+        }
+    }
+
     pub fn clear(&mut self) {
         self.candles.clear();
     }
 
-    fn compute_ranges(&self) -> (f64, f64, f64, f64) {
-        if self.candles.is_empty() {
+    /// Candles as drawn in the chosen style
+    fn shown_candles(&self) -> Vec<Candle> {
+        match self.candle_style {
+            CandleStyle::HeikinAshi => heikin_ashi(&self.candles),
+            _ => self.candles.clone(),
+        }
+    }
+
+    fn compute_studies(&self) -> Vec<Study> {
+        compute_studies(&self.candles, &self.indicators)
+    }
+
+    fn compute_ranges(&self, shown: &[Candle], studies: &[Study]) -> (f64, f64, f64, f64) {
+        if shown.is_empty() {
             return (0.0, 1.0, 0.0, 1.0);
         }
 
-        let x_min = self.candles.first().map(|c| c.timestamp).unwrap_or(0.0);
-        let x_max = self.candles.last().map(|c| c.timestamp).unwrap_or(1.0);
+        let x_min = shown.first().map(|c| c.timestamp).unwrap_or(0.0);
+        let x_max = shown.last().map(|c| c.timestamp).unwrap_or(1.0);
 
         let mut y_min = f64::MAX;
         let mut y_max = f64::MIN;
-        for c in &self.candles {
+        for c in shown {
             if c.low < y_min { y_min = c.low; }
             if c.high > y_max { y_max = c.high; }
         }
+        for study in studies {
+            if let Study::Overlay { lines, .. } = study {
+                if let Some((lo, hi)) = finite_range(lines) {
+                    y_min = y_min.min(lo);
+                    y_max = y_max.max(hi);
+                }
+            }
+        }
+        for line in &self.price_lines {
+            y_min = y_min.min(line.price);
+            y_max = y_max.max(line.price);
+        }
 
         // Add padding
-        let y_range = y_max - y_min;
+        let y_range = if y_max > y_min { y_max - y_min } else { 1.0 };
         y_min -= y_range * 0.05;
         y_max += y_range * 0.05;
 
         (x_min, x_max, y_min, y_max)
     }
+
+    /// Split the plot area into the price pane and one pane per non-overlay
+    /// study, all sharing the price pane's x axis
+    fn layout_panes(&mut self, rect: Rect, pane_count: usize) {
+        self.axes.update_plot_area(rect);
+        self.panes.resize_with(pane_count, || {
+            let mut pane = Axes::new();
+            pane.y.tick_count = 2;
+            pane
+        });
+        if pane_count == 0 {
+            self.axes.x.show_labels = self.time_axis;
+            return;
+        }
+
+        let full = self.axes.plot_area;
+        let (price_bottom, bands) = pane_bands(&full, pane_count);
+        self.axes.plot_area.bottom = price_bottom;
+        self.axes.x.show_labels = false;
+        for (i, (pane, (top, bottom))) in self.panes.iter_mut().zip(bands).enumerate() {
+            pane.x = self.axes.x.clone();
+            pane.x.show_labels = self.time_axis && i + 1 == pane_count;
+            pane.plot_area = PlotArea::new(full.left, top, full.right, bottom);
+        }
+    }
+
+    /// Index of the candle under the crosshair, if the pointer is over a pane
+    fn crosshair_index(&self) -> Option<usize> {
+        let pointer = self.pointer.filter(|_| self.crosshair)?;
+        let bottom = self.panes.last().map_or(self.axes.plot_area.bottom, |pane| pane.plot_area.bottom);
+        let area = self.axes.plot_area;
+        if pointer.x < area.left || pointer.x > area.right || pointer.y < area.top || pointer.y > bottom {
+            return None;
+        }
+        (0..self.candle_x.len()).min_by(|&a, &b| {
+            (self.candle_x[a] - pointer.x).abs().total_cmp(&(self.candle_x[b] - pointer.x).abs())
+        })
+    }
+
+    fn draw_candles(&mut self, cx: &mut Cx2d, shown: &[Candle], candle_width: f64) {
+        let half = candle_width / 2.0;
+        for (candle, &x) in shown.iter().zip(&self.candle_x) {
+            let open_y = self.axes.y_to_pixel(candle.open);
+            let close_y = self.axes.y_to_pixel(candle.close);
+            let high_y = self.axes.y_to_pixel(candle.high);
+            let low_y = self.axes.y_to_pixel(candle.low);
+
+            let color = if candle.is_bullish() { self.bullish_color } else { self.bearish_color };
+
+            // Draw wick (high-low line)
+            self.draw_line.color = color;
+            self.draw_line.draw_line(cx, dvec2(x, high_y), dvec2(x, low_y), 1.0);
+
+            if self.candle_style == CandleStyle::Ohlc {
+                // Open ticked to the left, close to the right
+                self.draw_line.draw_line(cx, dvec2(x - half, open_y), dvec2(x, open_y), 1.5);
+                self.draw_line.draw_line(cx, dvec2(x, close_y), dvec2(x + half, close_y), 1.5);
+                continue;
+            }
+
+            // Draw body
+            let body_top = open_y.min(close_y);
+            let body_height = (open_y - close_y).abs().max(1.0);
+
+            self.draw_fill.color = color;
+            self.draw_fill.draw_rect(cx, Rect {
+                pos: dvec2(x - half, body_top),
+                size: dvec2(candle_width, body_height),
+            });
+        }
+    }
+
+    fn draw_overlays(&mut self, cx: &mut Cx2d, studies: &[Study]) {
+        let axes = &self.axes;
+        let to_y = |v: f64| axes.y_to_pixel(v);
+        let mut legend_x = axes.plot_area.left + 6.0;
+        let legend_y = axes.plot_area.top + 4.0;
+        for study in studies {
+            let Study::Overlay { label, color, lines, band } = study else { continue };
+            if *band && lines.len() >= 2 {
+                self.draw_triangle.color = with_alpha(*color, 0.08);
+                draw_fill_between(cx, &mut self.draw_triangle, &self.candle_x, &lines[0], &lines[lines.len() - 1], to_y);
+            }
+            self.draw_line.color = *color;
+            for (i, line) in lines.iter().enumerate() {
+                // Band edges are thinner than the middle line
+                let width = if *band && i != 1 { 1.0 } else { 1.5 };
+                draw_series(cx, &mut self.draw_line, &self.candle_x, line, to_y, width);
+            }
+
+            // Legend entry: a short stroke and the label
+            self.draw_line.draw_line(cx, dvec2(legend_x, legend_y + 6.0), dvec2(legend_x + 12.0, legend_y + 6.0), 2.0);
+            self.label.draw_text.color = vec4(0.25, 0.25, 0.25, 1.0);
+            self.label.draw_at(cx, dvec2(legend_x + 16.0, legend_y), label, TextAnchor::TopLeft);
+            legend_x += 28.0 + self.label.measure(cx, label).x;
+        }
+    }
+
+    fn draw_panes(&mut self, cx: &mut Cx2d, studies: &[Study], crosshair: Option<usize>) {
+        let panes = studies.iter().filter(|study| !matches!(study, Study::Overlay { .. }));
+        for (pane_index, study) in panes.enumerate() {
+            let mut pane = self.panes[pane_index].clone();
+            pane.y.range = match study {
+                Study::Rsi { .. } => (0.0, 100.0),
+                Study::Macd { macd, signal, histogram, .. } => {
+                    let (lo, hi) = finite_range([macd, signal, histogram]).unwrap_or((-1.0, 1.0));
+                    let pad = ((hi - lo) * 0.1).max(1e-9);
+                    ((lo - pad).min(0.0), (hi + pad).max(0.0))
+                }
+                Study::Overlay { .. } => continue,
+            };
+            pane.draw_grid(cx, &mut self.draw_line);
+            pane.draw_axes(cx, &mut self.draw_line);
+            pane.draw_tick_labels(cx, &mut self.label);
+
+            let area = pane.plot_area;
+            let to_y = |v: f64| pane.y_to_pixel(v);
+            let at = |values: &[f64]| {
+                let i = crosshair.unwrap_or(values.len().saturating_sub(1));
+                values.get(i).copied().filter(|v| v.is_finite())
+            };
+            let mut readout = Vec::new();
+            let label = match study {
+                Study::Rsi { label, values } => {
+                    // Overbought and oversold guides
+                    self.draw_fill.color = vec4(0.58, 0.40, 0.74, 0.06);
+                    self.draw_fill.draw_rect(cx, Rect {
+                        pos: dvec2(area.left, to_y(70.0)),
+                        size: dvec2(area.width(), to_y(30.0) - to_y(70.0)),
+                    });
+                    self.draw_line.color = vec4(0.6, 0.6, 0.6, 1.0);
+                    for level in [30.0, 70.0] {
+                        self.draw_line.draw_line_styled(cx, dvec2(area.left, to_y(level)), dvec2(area.right, to_y(level)), 1.0, LineStyle::Dashed, 0.0);
+                    }
+                    self.draw_line.color = get_color(4);
+                    draw_series(cx, &mut self.draw_line, &self.candle_x, values, to_y, 1.5);
+                    readout.extend(at(values).map(|v| (format!("{:.1}", v), get_color(4))));
+                    label
+                }
+                Study::Macd { label, macd, signal, histogram } => {
+                    let zero = to_y(0.0);
+                    self.draw_line.color = vec4(0.6, 0.6, 0.6, 1.0);
+                    self.draw_line.draw_line(cx, dvec2(area.left, zero), dvec2(area.right, zero), 1.0);
+                    let bar_width = (area.width() / self.candle_x.len().max(1) as f64 * 0.6).clamp(1.0, 12.0);
+                    for (&x, &h) in self.candle_x.iter().zip(histogram) {
+                        if !h.is_finite() {
+                            continue;
+                        }
+                        let color = if h >= 0.0 { self.bullish_color } else { self.bearish_color };
+                        self.draw_fill.color = with_alpha(color, 0.5);
+                        self.draw_fill.draw_rect(cx, Rect {
+                            pos: dvec2(x - bar_width / 2.0, zero.min(to_y(h))),
+                            size: dvec2(bar_width, (zero - to_y(h)).abs()),
+                        });
+                    }
+                    self.draw_line.color = get_color(0);
+                    draw_series(cx, &mut self.draw_line, &self.candle_x, macd, to_y, 1.5);
+                    self.draw_line.color = get_color(1);
+                    draw_series(cx, &mut self.draw_line, &self.candle_x, signal, to_y, 1.5);
+                    readout.extend(at(macd).map(|v| (format!("{:.3}", v), get_color(0))));
+                    readout.extend(at(signal).map(|v| (format!("{:.3}", v), get_color(1))));
+                    label
+                }
+                Study::Overlay { .. } => continue,
+            };
+
+            // Pane title with the values at the crosshair (or the latest)
+            let mut x = area.left + 6.0;
+            self.label.draw_text.color = vec4(0.25, 0.25, 0.25, 1.0);
+            self.label.draw_at(cx, dvec2(x, area.top + 2.0), label, TextAnchor::TopLeft);
+            x += self.label.measure(cx, label).x + 8.0;
+            for (text, color) in readout {
+                self.label.draw_text.color = color;
+                self.label.draw_at(cx, dvec2(x, area.top + 2.0), &text, TextAnchor::TopLeft);
+                x += self.label.measure(cx, &text).x + 8.0;
+            }
+            self.label.draw_text.color = vec4(0.2, 0.2, 0.2, 1.0);
+        }
+    }
+
+    /// Price tag at the right edge of the price pane
+    fn draw_price_tag(&mut self, cx: &mut Cx2d, price: f64, color: Vec4, text: &str) {
+        let area = self.axes.plot_area;
+        let y = self.axes.y_to_pixel(price);
+        let size = self.label.measure(cx, text);
+        self.draw_fill.color = color;
+        self.draw_fill.draw_rect(cx, Rect {
+            pos: dvec2(area.right - size.x - 8.0, y - size.y / 2.0 - 2.0),
+            size: dvec2(size.x + 8.0, size.y + 4.0),
+        });
+        self.label.draw_text.color = vec4(1.0, 1.0, 1.0, 1.0);
+        self.label.draw_at(cx, dvec2(area.right - 4.0, y), text, TextAnchor::MiddleRight);
+        self.label.draw_text.color = vec4(0.2, 0.2, 0.2, 1.0);
+    }
+
+    fn draw_price_lines(&mut self, cx: &mut Cx2d) {
+        let area = self.axes.plot_area;
+        for i in 0..self.price_lines.len() {
+            let line = self.price_lines[i].clone();
+            let y = self.axes.y_to_pixel(line.price);
+            self.draw_line.color = line.color;
+            self.draw_line.draw_line_styled(cx, dvec2(area.left, y), dvec2(area.right, y), 1.0, LineStyle::Dashed, 0.0);
+            let text = if line.label.is_empty() {
+                format_price(line.price)
+            } else {
+                format!("{} {}", line.label, format_price(line.price))
+            };
+            self.draw_price_tag(cx, line.price, line.color, &text);
+        }
+
+        if self.show_last_price {
+            if let Some(last) = self.candles.last() {
+                let (price, bullish) = (last.close, last.is_bullish());
+                let color = if bullish { self.bullish_color } else { self.bearish_color };
+                let y = self.axes.y_to_pixel(price);
+                self.draw_line.color = with_alpha(color, 0.7);
+                self.draw_line.draw_line_styled(cx, dvec2(area.left, y), dvec2(area.right, y), 1.0, LineStyle::Dotted, 0.0);
+                self.draw_price_tag(cx, price, color, &format_price(price));
+            }
+        }
+    }
+
+    fn draw_crosshair(&mut self, cx: &mut Cx2d, index: usize) {
+        let (Some(pointer), Some(&x)) = (self.pointer, self.candle_x.get(index)) else { return };
+        let area = self.axes.plot_area;
+        let bottom = self.panes.last().map_or(area.bottom, |pane| pane.plot_area.bottom);
+        self.draw_line.color = vec4(0.4, 0.4, 0.4, 0.8);
+        self.draw_line.draw_line_styled(cx, dvec2(x, area.top), dvec2(x, bottom), 1.0, LineStyle::Dashed, 0.0);
+
+        // Horizontal hair and value tag in whichever pane holds the pointer
+        let hovered = std::iter::once(&self.axes)
+            .chain(&self.panes)
+            .find(|axes| pointer.y >= axes.plot_area.top && pointer.y <= axes.plot_area.bottom)
+            .cloned();
+        if let Some(axes) = hovered {
+            self.draw_line.draw_line_styled(cx, dvec2(area.left, pointer.y), dvec2(area.right, pointer.y), 1.0, LineStyle::Dashed, 0.0);
+            let (_, value) = axes.pixel_to_data(pointer);
+            let text = format_price(value);
+            let size = self.label.measure(cx, &text);
+            self.draw_fill.color = vec4(0.3, 0.3, 0.3, 1.0);
+            self.draw_fill.draw_rect(cx, Rect {
+                pos: dvec2(area.left - size.x - 8.0, pointer.y - size.y / 2.0 - 2.0),
+                size: dvec2(size.x + 8.0, size.y + 4.0),
+            });
+            self.label.draw_text.color = vec4(1.0, 1.0, 1.0, 1.0);
+            self.label.draw_at(cx, dvec2(area.left - 4.0, pointer.y), &text, TextAnchor::MiddleRight);
+        }
+
+        // Readout of the snapped candle's prices
+        if let Some(candle) = self.candles.get(index) {
+            let color = if candle.is_bullish() { self.bullish_color } else { self.bearish_color };
+            let text = format!(
+                "O {}  H {}  L {}  C {}",
+                format_price(candle.open),
+                format_price(candle.high),
+                format_price(candle.low),
+                format_price(candle.close)
+            );
+            self.label.draw_text.color = color;
+            self.label.draw_at(cx, dvec2(area.right - 6.0, area.top + 4.0), &text, TextAnchor::TopRight);
+        }
+        self.label.draw_text.color = vec4(0.2, 0.2, 0.2, 1.0);
+    }
 }
 
 impl Widget for CandlestickChart {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
-        let rect = cx.walk_turtle(walk);
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 {
-            let plot_rect = Rect {
-                pos: dvec2(rect.pos.x + self.left_margin, rect.pos.y + self.top_margin),
-                size: dvec2(
-                    rect.size.x - self.left_margin - self.right_margin,
-                    rect.size.y - self.top_margin - self.bottom_margin
-                ),
-            };
-            self.plot_area = PlotArea::new(plot_rect.pos.x, plot_rect.pos.y,
-                plot_rect.pos.x + plot_rect.size.x, plot_rect.pos.y + plot_rect.size.y);
-
             // Initialize colors if not set
             if self.bullish_color == Vec4::default() {
                 self.bullish_color = vec4(0.17, 0.63, 0.17, 1.0); // Green
@@ -142,7 +699,10 @@ impl Widget for CandlestickChart {
                 self.bearish_color = vec4(0.84, 0.15, 0.16, 1.0); // Red
             }
 
-            let (x_min, x_max, y_min, y_max) = self.compute_ranges();
+            let shown = self.shown_candles();
+            let studies = self.compute_studies();
+            let pane_count = studies.iter().filter(|study| !matches!(study, Study::Overlay { .. })).count();
+            let (x_min, x_max, y_min, y_max) = self.compute_ranges(&shown, &studies);
 
             // Draw title
             if !self.title.is_empty() {
@@ -150,74 +710,80 @@ impl Widget for CandlestickChart {
                     &self.title, TextAnchor::TopCenter);
             }
 
-            // Draw axes
-            self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-            self.draw_line.draw_line(cx,
-                dvec2(plot_rect.pos.x, plot_rect.pos.y + plot_rect.size.y),
-                dvec2(plot_rect.pos.x + plot_rect.size.x, plot_rect.pos.y + plot_rect.size.y), 1.0);
-            self.draw_line.draw_line(cx,
-                dvec2(plot_rect.pos.x, plot_rect.pos.y),
-                dvec2(plot_rect.pos.x, plot_rect.pos.y + plot_rect.size.y), 1.0);
-
-            // Draw Y axis labels
-            let num_ticks = 5;
-            for i in 0..=num_ticks {
-                let t = i as f64 / num_ticks as f64;
-                let y_val = y_min + (y_max - y_min) * t;
-                let y_pos = plot_rect.pos.y + plot_rect.size.y - t * plot_rect.size.y;
-                self.label.draw_at(cx, dvec2(plot_rect.pos.x - 5.0, y_pos),
-                    &format!("{:.1}", y_val), TextAnchor::MiddleRight);
+            // Draw axes with price ticks; the x axis is labelled only for time
+            // data, and then only under the lowest pane
+            if self.ordinal {
+                self.axes.x.range = (-0.5, self.candles.len() as f64 - 0.5);
+                self.axes.x.ordinal_times = self.candles.iter().map(|c| c.timestamp).collect();
+            } else if x_max > x_min {
+                self.axes.x.range = (x_min, x_max);
+                self.axes.x.ordinal_times.clear();
+            } else {
+                self.axes.x.range = (x_min - 0.5, x_max + 0.5);
+                self.axes.x.ordinal_times.clear();
             }
+            self.axes.x.scale = if self.time_axis { ScaleType::Time } else { ScaleType::Linear };
+            self.axes.x.show_grid = false;
+            self.axes.y.range = (y_min, y_max);
+            self.layout_panes(rect, pane_count);
+            self.axes.draw_grid(cx, &mut self.draw_line);
+            self.axes.draw_axes(cx, &mut self.draw_line);
+            self.axes.draw_tick_labels(cx, &mut self.label);
+
+            self.candle_x = shown
+                .iter()
+                .enumerate()
+                .map(|(i, c)| self.axes.x_to_pixel(if self.ordinal { i as f64 } else { c.timestamp }))
+                .collect();
 
             // Calculate candle width based on number of candles
             let candle_width = if self.candle_width > 0.0 {
                 self.candle_width
-            } else if !self.candles.is_empty() {
-                (plot_rect.size.x / self.candles.len() as f64 * 0.7).min(20.0).max(3.0)
+            } else if !shown.is_empty() {
+                (self.axes.plot_area.width() / shown.len() as f64 * 0.7).clamp(3.0, 20.0)
             } else {
                 10.0
             };
 
-            // Draw candles
-            for candle in &self.candles {
-                let x = if x_max > x_min {
-                    plot_rect.pos.x + (candle.timestamp - x_min) / (x_max - x_min) * plot_rect.size.x
-                } else {
-                    plot_rect.pos.x + plot_rect.size.x / 2.0
-                };
-
-                let data_to_y = |v: f64| {
-                    plot_rect.pos.y + plot_rect.size.y - (v - y_min) / (y_max - y_min) * plot_rect.size.y
-                };
-
-                let open_y = data_to_y(candle.open);
-                let close_y = data_to_y(candle.close);
-                let high_y = data_to_y(candle.high);
-                let low_y = data_to_y(candle.low);
-
-                let color = if candle.is_bullish() { self.bullish_color } else { self.bearish_color };
-
-                // Draw wick (high-low line)
-                self.draw_line.color = color;
-                self.draw_line.draw_line(cx, dvec2(x, high_y), dvec2(x, low_y), 1.0);
-
-                // Draw body
-                let body_top = open_y.min(close_y);
-                let body_height = (open_y - close_y).abs().max(1.0);
-
-                self.draw_fill.color = color;
-                self.draw_fill.draw_abs(cx, Rect {
-                    pos: dvec2(x - candle_width / 2.0, body_top),
-                    size: dvec2(candle_width, body_height),
-                });
+            let crosshair = self.crosshair_index();
+            self.draw_overlays(cx, &studies);
+            self.draw_candles(cx, &shown, candle_width);
+            self.draw_price_lines(cx);
+            self.draw_panes(cx, &studies, crosshair);
+            if let Some(index) = crosshair {
+                self.draw_crosshair(cx, index);
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         self.view.handle_event(cx, event, scope);
+
+        if !self.crosshair {
+            return;
+        }
+        let pointer = match event.hits(cx, self.area) {
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => Some(fe.abs),
+            Hit::FingerHoverOut(_) => None,
+            _ => return,
+        };
+        if pointer != self.pointer {
+            self.pointer = pointer;
+            self.area.redraw(cx);
+        }
+    }
+}
+
+impl PlotExport for CandlestickChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
     }
 }
 
@@ -240,6 +806,36 @@ impl CandlestickChartRef {
     pub fn set_candle_width(&self, width: f64) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_candle_width(width); }
     }
+    pub fn set_time_axis(&self, enabled: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_time_axis(enabled); }
+    }
+    pub fn set_ordinal(&self, ordinal: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_ordinal(ordinal); }
+    }
+    pub fn set_timezone(&self, timezone: TimeZone) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_timezone(timezone); }
+    }
+    pub fn add_indicator(&self, indicator: Indicator) {
+        if let Some(mut inner) = self.borrow_mut() { inner.add_indicator(indicator); }
+    }
+    pub fn clear_indicators(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.clear_indicators(); }
+    }
+    pub fn set_candle_style(&self, style: CandleStyle) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_candle_style(style); }
+    }
+    pub fn add_price_line(&self, line: PriceLine) {
+        if let Some(mut inner) = self.borrow_mut() { inner.add_price_line(line); }
+    }
+    pub fn clear_price_lines(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.clear_price_lines(); }
+    }
+    pub fn set_show_last_price(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_show_last_price(show); }
+    }
+    pub fn set_crosshair(&self, enabled: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_crosshair(enabled); }
+    }
     pub fn clear(&self) {
         if let Some(mut inner) = self.borrow_mut() { inner.clear(); }
     }
@@ -281,10 +877,10 @@ pub struct WaterfallChart {
     #[rust] negative_color: Vec4,
     #[rust] total_color: Vec4,
     #[rust] connector_color: Vec4,
-    #[rust(50.0)] left_margin: f64,
-    #[rust(50.0)] bottom_margin: f64,
-    #[rust(20.0)] right_margin: f64,
-    #[rust(30.0)] top_margin: f64,
+    #[rust(Axes::with_margins(50.0, 30.0, 20.0, 50.0))] axes: Axes,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl WaterfallChart {
@@ -314,6 +910,7 @@ impl WaterfallChart {
 impl Widget for WaterfallChart {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.entries.is_empty() {
             // Initialize colors
@@ -330,12 +927,11 @@ impl Widget for WaterfallChart {
                 self.connector_color = vec4(0.5, 0.5, 0.5, 0.5);
             }
 
+            self.axes.update_plot_area(rect);
+            let area = self.axes.plot_area;
             let plot_rect = Rect {
-                pos: dvec2(rect.pos.x + self.left_margin, rect.pos.y + self.top_margin),
-                size: dvec2(
-                    rect.size.x - self.left_margin - self.right_margin,
-                    rect.size.y - self.top_margin - self.bottom_margin
-                ),
+                pos: dvec2(area.left, area.top),
+                size: dvec2(area.width(), area.height()),
             };
 
             // Calculate cumulative values and ranges
@@ -369,14 +965,13 @@ impl Widget for WaterfallChart {
                     &self.title, TextAnchor::TopCenter);
             }
 
-            // Draw axes
-            self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-            self.draw_line.draw_line(cx,
-                dvec2(plot_rect.pos.x, plot_rect.pos.y + plot_rect.size.y),
-                dvec2(plot_rect.pos.x + plot_rect.size.x, plot_rect.pos.y + plot_rect.size.y), 1.0);
-            self.draw_line.draw_line(cx,
-                dvec2(plot_rect.pos.x, plot_rect.pos.y),
-                dvec2(plot_rect.pos.x, plot_rect.pos.y + plot_rect.size.y), 1.0);
+            // Draw axes with value ticks; entries label the x axis
+            self.axes.x.show_grid = false;
+            self.axes.x.show_labels = false;
+            self.axes.y.range = (min_val, max_val);
+            self.axes.draw_grid(cx, &mut self.draw_line);
+            self.axes.draw_axes(cx, &mut self.draw_line);
+            self.axes.draw_tick_labels(cx, &mut self.label);
 
             // Draw zero line if in range
             if min_val < 0.0 && max_val > 0.0 {
@@ -425,7 +1020,7 @@ impl Widget for WaterfallChart {
                 let bar_height = (start_y - end_y).abs().max(1.0);
 
                 self.draw_fill.color = color;
-                self.draw_fill.draw_abs(cx, Rect {
+                self.draw_fill.draw_rect(cx, Rect {
                     pos: dvec2(x, bar_top),
                     size: dvec2(bar_width, bar_height),
                 });
@@ -444,6 +1039,7 @@ impl Widget for WaterfallChart {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -452,6 +1048,16 @@ impl Widget for WaterfallChart {
     }
 }
 
+impl PlotExport for WaterfallChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl WaterfallChartRef {
     pub fn set_title(&self, title: impl Into<String>) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
@@ -473,3 +1079,100 @@ impl WaterfallChartRef {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candles(closes: &[f64]) -> Vec<Candle> {
+        closes.iter().enumerate().map(|(i, &c)| Candle::new(i as f64, c, c + 1.0, c - 1.0, c).with_volume(10.0)).collect()
+    }
+
+    #[test]
+    fn test_heikin_ashi() {
+        let ha = heikin_ashi(&[Candle::new(0.0, 10.0, 12.0, 9.0, 11.0), Candle::new(1.0, 11.0, 13.0, 10.0, 12.0)]);
+        // First bar: close is the mean price, open the body midpoint
+        assert_eq!((ha[0].open, ha[0].high, ha[0].low, ha[0].close), (10.5, 12.0, 9.0, 10.5));
+        // Then each open is the midpoint of the previous Heikin-Ashi body
+        assert_eq!((ha[1].open, ha[1].high, ha[1].low, ha[1].close), (10.5, 13.0, 10.0, 11.5));
+        assert!(ha[1].is_bullish());
+        assert!(heikin_ashi(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_indicator_panes_and_labels() {
+        assert!(Indicator::Sma(20).is_overlay());
+        assert!(Indicator::Vwap.is_overlay());
+        assert!(!Indicator::Rsi(14).is_overlay());
+        assert!(!Indicator::Macd { fast: 12, slow: 26, signal: 9 }.is_overlay());
+        assert_eq!(Indicator::Bollinger { period: 20, k: 2.0 }.label(), "BB 20, 2");
+        assert_eq!(Indicator::Macd { fast: 12, slow: 26, signal: 9 }.label(), "MACD 12, 26, 9");
+    }
+
+    #[test]
+    fn test_compute_studies() {
+        let candles = candles(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let indicators = [
+            Indicator::Sma(2),
+            Indicator::Rsi(2),
+            Indicator::Bollinger { period: 2, k: 2.0 },
+            Indicator::Vwap,
+        ];
+        let studies = compute_studies(&candles, &indicators);
+        assert_eq!(studies.len(), 4);
+
+        // Overlays take the overlay colors in order, skipping pane studies
+        match &studies[0] {
+            Study::Overlay { label, color, lines, band } => {
+                assert_eq!(label, "SMA 2");
+                assert_eq!(*color, overlay_color(0));
+                assert!(!band);
+                assert!(lines[0][0].is_nan());
+                assert_eq!(&lines[0][1..], &[1.5, 2.5, 3.5, 4.5]);
+            }
+            _ => panic!("SMA is an overlay"),
+        }
+        assert!(matches!(&studies[1], Study::Rsi { values, .. } if values.len() == 5));
+        match &studies[2] {
+            Study::Overlay { color, lines, band, .. } => {
+                assert_eq!(*color, overlay_color(1));
+                assert!(band);
+                assert_eq!(lines.len(), 3);
+                // Upper band above the middle above the lower
+                assert!(lines[0][4] > lines[1][4] && lines[1][4] > lines[2][4]);
+            }
+            _ => panic!("Bollinger bands are an overlay"),
+        }
+        // Equal volumes: VWAP is the running mean of the typical price
+        match &studies[3] {
+            Study::Overlay { lines, .. } => assert!((lines[0][1] - 1.5).abs() < 1e-9),
+            _ => panic!("VWAP is an overlay"),
+        }
+    }
+
+    #[test]
+    fn test_pane_bands() {
+        let full = PlotArea::new(0.0, 0.0, 100.0, 500.0);
+        assert_eq!(pane_bands(&full, 0), (500.0, vec![]));
+
+        // Each pane takes PANE_FRACTION of the height, the last ends at the bottom
+        let (price_bottom, bands) = pane_bands(&full, 2);
+        assert_eq!(bands.len(), 2);
+        assert!((bands[0].1 - bands[0].0 - 110.0).abs() < 1e-9);
+        assert!((bands[1].1 - 500.0).abs() < 1e-9);
+        assert!((bands[1].0 - bands[0].1 - PANE_GAP).abs() < 1e-9);
+        assert!((bands[0].0 - price_bottom - PANE_GAP).abs() < 1e-9);
+
+        // Many panes share at most 60% of the height
+        let (price_bottom, bands) = pane_bands(&full, 6);
+        assert!((bands[0].1 - bands[0].0 - 50.0).abs() < 1e-9);
+        assert!(price_bottom > 0.0);
+    }
+
+    #[test]
+    fn test_finite_range_and_price_format() {
+        assert_eq!(finite_range(&[vec![f64::NAN, 2.0], vec![-1.0, f64::INFINITY]]), Some((-1.0, 2.0)));
+        assert_eq!(finite_range(&[vec![f64::NAN]]), None);
+        assert_eq!(format_price(12.345), "12.35");
+        assert_eq!(format_price(12345.6), "12346");
+    }
+}
@@ -0,0 +1,888 @@
+// Gantt chart - task bars over a time axis with progress, milestones,
+// dependency arrows, collapsible groups, a current-time line and
+// drag-to-reschedule
+
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use crate::export::*;
+use super::*;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::text::PlotLabel;
+
+    pub GanttChart = {{GanttChart}} {
+        width: Fill,
+        height: Fill,
+        label: <PlotLabel> {}
+    }
+}
+
+/// A press and release closer than this (pixels) count as a click
+const CLICK_SLOP: f64 = 4.0;
+
+/// Pointer distance (pixels) from a bar end that resizes instead of moving
+const EDGE_GRAB: f64 = 6.0;
+
+/// Rows never get taller than this, however few there are
+const MAX_ROW_HEIGHT: f64 = 30.0;
+
+/// Indent of task labels under a group header
+const GROUP_INDENT: f64 = 14.0;
+
+/// Horizontal run of a dependency arrow before it turns
+const ARROW_STUB: f64 = 8.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GanttTask {
+    /// Identifier used by dependencies and actions
+    pub id: String,
+    pub label: String,
+    /// Unix seconds
+    pub start: f64,
+    pub end: f64,
+    /// Fraction done, 0.0 to 1.0
+    pub progress: f64,
+    /// Tasks sharing a group sit under one collapsible header
+    pub group: Option<String>,
+    /// Ids of tasks that have to finish before this one starts
+    pub depends_on: Vec<String>,
+    /// Drawn as a diamond at `start` instead of a bar
+    pub milestone: bool,
+    pub color: Option<Vec4>,
+}
+
+impl GanttTask {
+    pub fn new(id: impl Into<String>, label: impl Into<String>, start: f64, end: f64) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            start,
+            end: end.max(start),
+            progress: 0.0,
+            group: None,
+            depends_on: Vec::new(),
+            milestone: false,
+            color: None,
+        }
+    }
+
+    pub fn milestone(id: impl Into<String>, label: impl Into<String>, at: f64) -> Self {
+        Self { milestone: true, ..Self::new(id, label, at, at) }
+    }
+
+    pub fn with_progress(mut self, progress: f64) -> Self {
+        self.progress = progress.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    pub fn with_dependency(mut self, id: impl Into<String>) -> Self {
+        self.depends_on.push(id.into());
+        self
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    fn is_milestone(&self) -> bool {
+        self.milestone || self.end <= self.start
+    }
+}
+
+/// Actions emitted by the Gantt chart
+#[derive(Clone, Debug, DefaultNone)]
+pub enum GanttAction {
+    None,
+    /// A task bar or milestone was clicked
+    TaskClicked { id: String },
+    /// A task was dragged to new times (Unix seconds)
+    Rescheduled { id: String, start: f64, end: f64 },
+    /// A group header was clicked open or shut
+    GroupToggled { group: String, collapsed: bool },
+}
+
+/// One line of the chart: a group header or a task
+#[derive(Clone, Debug, PartialEq)]
+enum GanttRow {
+    /// Header with the indices of its tasks, drawn as a summary bar
+    Group { name: String, tasks: Vec<usize>, collapsed: bool },
+    Task { index: usize, grouped: bool },
+}
+
+/// Rows in task order. A group's header takes the place of its first task,
+/// followed by all of its tasks unless it is collapsed.
+fn build_rows(tasks: &[GanttTask], collapsed: &[String]) -> Vec<GanttRow> {
+    let mut rows = Vec::new();
+    let mut seen: Vec<&str> = Vec::new();
+    for (index, task) in tasks.iter().enumerate() {
+        let Some(group) = task.group.as_deref() else {
+            rows.push(GanttRow::Task { index, grouped: false });
+            continue;
+        };
+        if seen.contains(&group) {
+            continue;
+        }
+        seen.push(group);
+        let members: Vec<usize> = (index..tasks.len()).filter(|&i| tasks[i].group.as_deref() == Some(group)).collect();
+        let shut = collapsed.iter().any(|c| c == group);
+        rows.push(GanttRow::Group { name: group.to_string(), tasks: members.clone(), collapsed: shut });
+        if !shut {
+            rows.extend(members.into_iter().map(|index| GanttRow::Task { index, grouped: true }));
+        }
+    }
+    rows
+}
+
+/// Span and duration-weighted progress of a group's tasks
+fn group_summary(tasks: &[GanttTask], members: &[usize]) -> (f64, f64, f64) {
+    let start = members.iter().map(|&i| tasks[i].start).fold(f64::INFINITY, f64::min);
+    let end = members.iter().map(|&i| tasks[i].end).fold(f64::NEG_INFINITY, f64::max);
+    let (done, total) = members.iter().fold((0.0, 0.0), |(done, total), &i| {
+        let duration = tasks[i].end - tasks[i].start;
+        (done + duration * tasks[i].progress, total + duration)
+    });
+    (start, end, if total > 0.0 { done / total } else { 0.0 })
+}
+
+/// Elbow path from the end of one bar to the start of a later row's bar.
+/// Turns once when there is room between them, otherwise doubles back
+/// along the row boundary `between_y`.
+fn dependency_path(from: (f64, f64), to: (f64, f64), between_y: f64) -> Vec<(f64, f64)> {
+    let turn = from.0 + ARROW_STUB;
+    if to.0 - from.0 >= ARROW_STUB * 2.0 {
+        vec![from, (turn, from.1), (turn, to.1), to]
+    } else {
+        let back = to.0 - ARROW_STUB;
+        vec![from, (turn, from.1), (turn, between_y), (back, between_y), (back, to.1), to]
+    }
+}
+
+/// Row showing task `index`: its own, or its group's when collapsed
+fn task_row(rows: &[GanttRow], index: usize) -> Option<usize> {
+    rows.iter().position(|row| match row {
+        GanttRow::Task { index: i, .. } => *i == index,
+        GanttRow::Group { tasks, collapsed: true, .. } => tasks.contains(&index),
+        GanttRow::Group { .. } => false,
+    })
+}
+
+/// Dependency arrows to draw as (from task, to task, late), in task and then
+/// dependency order. Unknown ids and dependencies sharing a row (inside a
+/// collapsed group) are left out. A task is late when it starts before its
+/// dependency ends.
+fn dependency_links(tasks: &[GanttTask], rows: &[GanttRow]) -> Vec<(usize, usize, bool)> {
+    let mut links = Vec::new();
+    for (index, task) in tasks.iter().enumerate() {
+        let Some(to_row) = task_row(rows, index) else { continue };
+        for dep in &task.depends_on {
+            let Some(from) = tasks.iter().position(|t| t.id == *dep) else { continue };
+            if task_row(rows, from).is_some_and(|from_row| from_row != to_row) {
+                links.push((from, index, task.start < tasks[from].end));
+            }
+        }
+    }
+    links
+}
+
+/// `time` rounded to a multiple of `snap` seconds, when snapping is on
+fn snap_time(time: f64, snap: f64) -> f64 {
+    if snap > 0.0 { (time / snap).round() * snap } else { time }
+}
+
+/// New times of a task that was at `start..end` when grabbed, after a drag
+/// of `dt` seconds. Moving snaps the start and keeps the duration; resizing
+/// snaps the dragged end and never passes the other one.
+fn drag_times(grab: GanttGrab, start: f64, end: f64, dt: f64, snap: f64) -> (f64, f64) {
+    match grab {
+        GanttGrab::Move => {
+            let s = snap_time(start + dt, snap);
+            (s, s + (end - start))
+        }
+        GanttGrab::Start => (snap_time(start + dt, snap).min(end), end),
+        GanttGrab::End => (start, snap_time(end + dt, snap).max(start)),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum GanttGrab {
+    Move,
+    Start,
+    End,
+}
+
+#[derive(Clone, Debug)]
+enum GanttDrag {
+    /// Rescheduling a task; `start` and `end` are its times at the press
+    Task { index: usize, grab: GanttGrab, start: f64, end: f64 },
+    /// Pressed on a group header
+    Group { name: String },
+    /// Panning the time axis from the view range at the press
+    Pan { range: (f64, f64) },
+}
+
+/// What lies under a pixel
+#[derive(Clone, Debug)]
+enum GanttHit {
+    Task { index: usize, grab: GanttGrab },
+    Group { name: String },
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct GanttChart {
+    #[deref] #[live] view: View,
+    #[live] draw_line: DrawPlotLine,
+    #[live] draw_bar: DrawPlotBar,
+    #[live] draw_fill: DrawPlotFill,
+    #[live] draw_triangle: DrawTriangle,
+    #[live] label: PlotLabel,
+    #[rust] title: String,
+    #[rust] tasks: Vec<GanttTask>,
+    /// Tasks as last passed to `set_tasks`, before any drags
+    #[rust] source: Vec<GanttTask>,
+    #[rust] collapsed: Vec<String>,
+    /// Width of the task label column, in pixels
+    #[rust(150.0)] label_width: f64,
+    /// Dragged times round to multiples of this many seconds (0 = off)
+    #[rust] snap: f64,
+    #[rust(true)] show_now: bool,
+    /// Fixed current time; the system clock when None
+    #[rust] now: Option<f64>,
+    /// Time range after pan and zoom; fitted to the tasks when None
+    #[rust] view_range: Option<(f64, f64)>,
+    #[rust] axes: Axes,
+
+    #[rust] rows: Vec<GanttRow>,
+    #[rust] row_height: f64,
+    #[rust] drag: Option<GanttDrag>,
+    #[rust] press: Option<DVec2>,
+    #[rust] hovered: Option<usize>,
+    #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
+}
+
+impl GanttChart {
+    /// Replace the tasks. The same tasks again keep any rescheduling done by
+    /// dragging, so they can be set on every draw.
+    pub fn set_tasks(&mut self, tasks: Vec<GanttTask>) {
+        if tasks == self.source {
+            return;
+        }
+        self.source = tasks.clone();
+        self.tasks = tasks;
+        self.drag = None;
+        self.hovered = None;
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    pub fn set_label_width(&mut self, width: f64) {
+        self.label_width = width.max(0.0);
+    }
+
+    /// Round dragged times to multiples of `seconds` (0 turns snapping off)
+    pub fn set_snap(&mut self, seconds: f64) {
+        self.snap = seconds.max(0.0);
+    }
+
+    pub fn set_show_now(&mut self, show: bool) {
+        self.show_now = show;
+    }
+
+    /// Put the current-time line at `time` instead of the system clock
+    pub fn set_now(&mut self, time: Option<f64>) {
+        self.now = time;
+    }
+
+    /// Show `min..max` (Unix seconds) instead of fitting the tasks
+    pub fn set_time_range(&mut self, min: f64, max: f64) {
+        if max > min {
+            self.view_range = Some((min, max));
+        }
+    }
+
+    pub fn set_timezone(&mut self, timezone: TimeZone) {
+        self.axes.x.timezone = timezone;
+    }
+
+    pub fn set_collapsed(&mut self, group: &str, collapsed: bool) {
+        self.collapsed.retain(|g| g != group);
+        if collapsed {
+            self.collapsed.push(group.to_string());
+        }
+    }
+
+    /// Undo pan and zoom
+    pub fn reset_view(&mut self) {
+        self.view_range = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.tasks.clear();
+        self.source.clear();
+        self.rows.clear();
+    }
+
+    pub fn redraw(&mut self, cx: &mut Cx) {
+        self.view.redraw(cx);
+        self.area.redraw(cx);
+    }
+
+    fn now_time(&self) -> f64 {
+        self.now.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0.0, |d| d.as_secs_f64())
+        })
+    }
+
+    /// Task span with a margin on both sides
+    fn fitted_range(&self) -> (f64, f64) {
+        let min = self.tasks.iter().map(|t| t.start).fold(f64::INFINITY, f64::min);
+        let max = self.tasks.iter().map(|t| t.end).fold(f64::NEG_INFINITY, f64::max);
+        if !min.is_finite() || !max.is_finite() {
+            return (0.0, 86400.0);
+        }
+        let pad = if max > min { (max - min) * 0.03 } else { 86400.0 };
+        (min - pad, max + pad)
+    }
+
+    /// Task color, or the palette color of its group in order of appearance
+    fn color(&self, index: usize) -> Vec4 {
+        let task = &self.tasks[index];
+        task.color.unwrap_or_else(|| {
+            let group = self.rows.iter().filter_map(|row| match row {
+                GanttRow::Group { name, .. } => Some(name),
+                GanttRow::Task { .. } => None,
+            }).position(|name| Some(name.as_str()) == task.group.as_deref());
+            get_color(group.map_or(0, |g| g + 1))
+        })
+    }
+
+    fn row_top(&self, row: usize) -> f64 {
+        self.axes.plot_area.top + row as f64 * self.row_height
+    }
+
+    fn row_of(&self, index: usize) -> Option<usize> {
+        task_row(&self.rows, index)
+    }
+
+    fn bar_rect(&self, row: usize, start: f64, end: f64) -> Rect {
+        let x0 = self.axes.x_to_pixel(start);
+        let x1 = self.axes.x_to_pixel(end);
+        Rect {
+            pos: dvec2(x0, self.row_top(row) + self.row_height * 0.2),
+            size: dvec2((x1 - x0).max(1.0), self.row_height * 0.6),
+        }
+    }
+
+    fn hit(&self, pos: DVec2) -> Option<GanttHit> {
+        let area = self.axes.plot_area;
+        if self.row_height <= 0.0 || pos.y < area.top || pos.x < area.left - self.label_width || pos.x > area.right {
+            return None;
+        }
+        let row = ((pos.y - area.top) / self.row_height).floor() as usize;
+        match self.rows.get(row)? {
+            GanttRow::Group { name, .. } => Some(GanttHit::Group { name: name.clone() }),
+            GanttRow::Task { index, .. } => {
+                let task = &self.tasks[*index];
+                let grab = if task.is_milestone() {
+                    let x = self.axes.x_to_pixel(task.start);
+                    if (pos.x - x).abs() > self.row_height * 0.35 { return None; }
+                    GanttGrab::Move
+                } else {
+                    let rect = self.bar_rect(row, task.start, task.end);
+                    if pos.x < rect.pos.x - EDGE_GRAB / 2.0 || pos.x > rect.pos.x + rect.size.x + EDGE_GRAB / 2.0 {
+                        return None;
+                    }
+                    // Narrow bars move rather than resize from the middle
+                    if rect.size.x > EDGE_GRAB * 3.0 && pos.x - rect.pos.x < EDGE_GRAB {
+                        GanttGrab::Start
+                    } else if rect.size.x > EDGE_GRAB * 3.0 && rect.pos.x + rect.size.x - pos.x < EDGE_GRAB {
+                        GanttGrab::End
+                    } else {
+                        GanttGrab::Move
+                    }
+                };
+                Some(GanttHit::Task { index: *index, grab })
+            }
+        }
+    }
+
+    fn draw_rows(&mut self, cx: &mut Cx2d, rect: Rect) {
+        let area = self.axes.plot_area;
+        for row in 0..self.rows.len() {
+            let top = self.row_top(row);
+            let shade = match self.rows[row] {
+                GanttRow::Group { .. } => Some(vec4(0.92, 0.93, 0.95, 1.0)),
+                GanttRow::Task { .. } if row % 2 == 1 => Some(vec4(0.97, 0.97, 0.98, 1.0)),
+                GanttRow::Task { .. } => None,
+            };
+            if let Some(color) = shade {
+                self.draw_fill.color = color;
+                self.draw_fill.draw_rect(cx, Rect { pos: dvec2(rect.pos.x, top), size: dvec2(area.right - rect.pos.x, self.row_height) });
+            }
+        }
+    }
+
+    fn draw_labels(&mut self, cx: &mut Cx2d) {
+        let area = self.axes.plot_area;
+        let left = area.left - self.label_width + 8.0;
+        self.label.set_font_size((self.row_height * 0.45).clamp(7.0, 11.0));
+        for row in 0..self.rows.len() {
+            let mid = self.row_top(row) + self.row_height / 2.0;
+            match self.rows[row].clone() {
+                GanttRow::Group { name, collapsed, .. } => {
+                    // Disclosure triangle: right when shut, down when open
+                    let s = 4.0;
+                    let c = dvec2(left + s, mid);
+                    self.draw_triangle.color = vec4(0.3, 0.3, 0.35, 1.0);
+                    if collapsed {
+                        self.draw_triangle.draw_triangle(cx, c + dvec2(-s * 0.6, -s), c + dvec2(-s * 0.6, s), c + dvec2(s, 0.0));
+                    } else {
+                        self.draw_triangle.draw_triangle(cx, c + dvec2(-s, -s * 0.6), c + dvec2(s, -s * 0.6), c + dvec2(0.0, s));
+                    }
+                    self.label.set_color(vec4(0.15, 0.15, 0.2, 1.0));
+                    self.label.draw_at(cx, dvec2(left + GROUP_INDENT, mid), &name, TextAnchor::MiddleLeft);
+                }
+                GanttRow::Task { index, grouped } => {
+                    let indent = if grouped { GROUP_INDENT } else { 0.0 };
+                    let text = self.tasks[index].label.clone();
+                    self.label.set_color(vec4(0.25, 0.25, 0.3, 1.0));
+                    self.label.draw_at(cx, dvec2(left + indent, mid), &text, TextAnchor::MiddleLeft);
+                }
+            }
+        }
+    }
+
+    fn draw_dependencies(&mut self, cx: &mut Cx2d) {
+        for (from, index, late) in dependency_links(&self.tasks, &self.rows) {
+            let (Some(from_row), Some(to_row)) = (self.row_of(from), self.row_of(index)) else { continue };
+            let start = (self.axes.x_to_pixel(self.tasks[from].end), self.row_top(from_row) + self.row_height / 2.0);
+            let end = (self.axes.x_to_pixel(self.tasks[index].start), self.row_top(to_row) + self.row_height / 2.0);
+            let between = if to_row > from_row { self.row_top(to_row) } else { self.row_top(to_row + 1) };
+            let path = dependency_path(start, end, between);
+
+            // A task starting before its dependency ends is flagged red
+            self.draw_line.color = if late { vec4(0.85, 0.25, 0.2, 0.9) } else { vec4(0.45, 0.47, 0.52, 0.9) };
+            let points: Vec<DVec2> = path.iter().map(|&(x, y)| dvec2(x, y)).collect();
+            for pair in points[..points.len() - 1].windows(2) {
+                self.draw_line.draw_line(cx, pair[0], pair[1], 1.0);
+            }
+            let n = points.len();
+            self.draw_line.draw_arrow(cx, points[n - 2], points[n - 1], 1.0, 6.0);
+        }
+    }
+
+    fn draw_bars(&mut self, cx: &mut Cx2d) {
+        for row in 0..self.rows.len() {
+            match self.rows[row].clone() {
+                GanttRow::Group { tasks, .. } => {
+                    // Summary bar with its progress
+                    let (start, end, progress) = group_summary(&self.tasks, &tasks);
+                    let mut rect = self.bar_rect(row, start, end);
+                    rect.pos.y += rect.size.y * 0.25;
+                    rect.size.y *= 0.5;
+                    self.draw_bar.color = vec4(0.55, 0.57, 0.62, 1.0);
+                    self.draw_bar.draw_bar(cx, rect);
+                    self.draw_bar.color = vec4(0.3, 0.32, 0.38, 1.0);
+                    self.draw_bar.draw_bar(cx, Rect { pos: rect.pos, size: dvec2(rect.size.x * progress, rect.size.y) });
+                }
+                GanttRow::Task { index, .. } => {
+                    let task = self.tasks[index].clone();
+                    let color = self.color(index);
+                    if task.is_milestone() {
+                        let c = dvec2(self.axes.x_to_pixel(task.start), self.row_top(row) + self.row_height / 2.0);
+                        let s = self.row_height * 0.3;
+                        self.draw_triangle.color = if self.hovered == Some(index) { color } else { vec4(color.x * 0.8, color.y * 0.8, color.z * 0.8, color.w) };
+                        self.draw_triangle.draw_triangle(cx, c + dvec2(0.0, -s), c + dvec2(s, 0.0), c + dvec2(-s, 0.0));
+                        self.draw_triangle.draw_triangle(cx, c + dvec2(0.0, s), c + dvec2(-s, 0.0), c + dvec2(s, 0.0));
+                        continue;
+                    }
+                    let rect = self.bar_rect(row, task.start, task.end);
+                    let background = if self.hovered == Some(index) { 0.5 } else { 0.35 };
+                    self.draw_bar.color = vec4(color.x, color.y, color.z, background);
+                    self.draw_bar.draw_bar(cx, rect);
+                    if task.progress > 0.0 {
+                        self.draw_bar.color = color;
+                        self.draw_bar.draw_bar(cx, Rect { pos: rect.pos, size: dvec2(rect.size.x * task.progress.clamp(0.0, 1.0), rect.size.y) });
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_now(&mut self, cx: &mut Cx2d) {
+        let now = self.now_time();
+        let area = self.axes.plot_area;
+        let (min, max) = self.axes.x.range;
+        if now < min || now > max {
+            return;
+        }
+        let x = self.axes.x_to_pixel(now);
+        self.draw_line.color = vec4(0.85, 0.2, 0.2, 0.9);
+        self.draw_line.draw_line_styled(cx, dvec2(x, area.top), dvec2(x, area.bottom), 1.5, LineStyle::Dashed, 0.0);
+        self.label.set_font_size(9.0);
+        self.label.set_color(vec4(0.85, 0.2, 0.2, 1.0));
+        self.label.draw_at(cx, dvec2(x, area.top - 2.0), "Now", TextAnchor::BottomCenter);
+    }
+
+    fn draw_tooltip(&mut self, cx: &mut Cx2d) {
+        let Some(index) = self.hovered else { return };
+        let Some(row) = self.row_of(index) else { return };
+        let task = &self.tasks[index];
+        let mut text = if task.is_milestone() {
+            self.axes.x.format(task.start)
+        } else {
+            format!("{} – {}", self.axes.x.format(task.start), self.axes.x.format(task.end))
+        };
+        if !task.is_milestone() && task.progress > 0.0 {
+            text.push_str(&format!(" ({:.0}%)", task.progress * 100.0));
+        }
+        let pos = dvec2(self.axes.x_to_pixel(task.start), self.row_top(row) + self.row_height * 0.2);
+        self.label.set_font_size(9.0);
+        self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+        self.label.draw_at(cx, pos, &text, TextAnchor::BottomLeft);
+    }
+
+    fn handle_pointer(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        match event.hits(cx, self.area) {
+            Hit::FingerDown(fe) => {
+                self.press = Some(fe.abs);
+                self.drag = match self.hit(fe.abs) {
+                    Some(GanttHit::Task { index, grab }) => {
+                        let task = &self.tasks[index];
+                        Some(GanttDrag::Task { index, grab, start: task.start, end: task.end })
+                    }
+                    Some(GanttHit::Group { name }) => Some(GanttDrag::Group { name }),
+                    None if self.axes.contains(fe.abs) => Some(GanttDrag::Pan { range: self.axes.x.range }),
+                    None => None,
+                };
+            }
+            Hit::FingerMove(fe) => {
+                let Some(press) = self.press else { return };
+                let width = self.axes.plot_area.width();
+                if width <= 0.0 || (fe.abs - press).length() <= CLICK_SLOP {
+                    return;
+                }
+                let (min, max) = self.axes.x.range;
+                let dt = (fe.abs.x - press.x) / width * (max - min);
+                match self.drag.clone() {
+                    Some(GanttDrag::Task { index, grab, start, end }) => {
+                        let (new_start, new_end) = drag_times(grab, start, end, dt, self.snap);
+                        self.tasks[index].start = new_start;
+                        self.tasks[index].end = new_end;
+                        self.redraw(cx);
+                    }
+                    Some(GanttDrag::Pan { range }) => {
+                        self.view_range = Some((range.0 - dt, range.1 - dt));
+                        self.redraw(cx);
+                    }
+                    _ => {}
+                }
+            }
+            Hit::FingerUp(fe) => {
+                let press = self.press.take();
+                let click = fe.is_over && press.is_some_and(|p| (fe.abs - p).length() <= CLICK_SLOP);
+                let uid = self.widget_uid();
+                match self.drag.take() {
+                    Some(GanttDrag::Task { index, .. }) if click => {
+                        let id = self.tasks[index].id.clone();
+                        cx.widget_action(uid, &scope.path, GanttAction::TaskClicked { id });
+                    }
+                    Some(GanttDrag::Task { index, start, end, .. }) => {
+                        let task = &self.tasks[index];
+                        if task.start != start || task.end != end {
+                            cx.widget_action(uid, &scope.path, GanttAction::Rescheduled { id: task.id.clone(), start: task.start, end: task.end });
+                        }
+                    }
+                    Some(GanttDrag::Group { name }) if click => {
+                        let collapsed = !self.collapsed.contains(&name);
+                        self.set_collapsed(&name, collapsed);
+                        self.hovered = None;
+                        cx.widget_action(uid, &scope.path, GanttAction::GroupToggled { group: name, collapsed });
+                        self.redraw(cx);
+                    }
+                    _ => {}
+                }
+            }
+            Hit::FingerScroll(fe) => {
+                let area = self.axes.plot_area;
+                if area.width() <= 0.0 {
+                    return;
+                }
+                // Zoom the time axis about the pointer
+                let factor = if fe.scroll.y > 0.0 { 0.9 } else { 1.1 };
+                let (min, max) = self.axes.x.range;
+                let anchor = min + (fe.abs.x - area.left) / area.width() * (max - min);
+                self.view_range = Some((anchor - (anchor - min) * factor, anchor + (max - anchor) * factor));
+                self.redraw(cx);
+            }
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => {
+                let hit = self.hit(fe.abs);
+                cx.set_cursor(match &hit {
+                    Some(GanttHit::Task { grab: GanttGrab::Move, .. }) => MouseCursor::Move,
+                    Some(GanttHit::Task { .. }) => MouseCursor::EwResize,
+                    Some(GanttHit::Group { .. }) => MouseCursor::Hand,
+                    None => MouseCursor::Default,
+                });
+                let hovered = match hit {
+                    Some(GanttHit::Task { index, .. }) => Some(index),
+                    _ => None,
+                };
+                if hovered != self.hovered {
+                    self.hovered = hovered;
+                    self.redraw(cx);
+                }
+            }
+            Hit::FingerHoverOut(_) => {
+                cx.set_cursor(MouseCursor::Default);
+                if self.hovered.take().is_some() {
+                    self.redraw(cx);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Widget for GanttChart {
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
+
+        if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.tasks.is_empty() {
+            let top = if self.title.is_empty() { 20.0 } else { 44.0 };
+            self.axes.margins = PlotMargins { left: self.label_width, top, right: 20.0, bottom: 30.0 };
+            self.axes.x.scale = ScaleType::Time;
+            self.axes.x.range = self.view_range.unwrap_or_else(|| self.fitted_range());
+            self.axes.y.show_labels = false;
+            self.axes.y.show_grid = false;
+            self.axes.update_plot_area(rect);
+
+            self.rows = build_rows(&self.tasks, &self.collapsed);
+            let area = self.axes.plot_area;
+            self.row_height = (area.height() / self.rows.len().max(1) as f64).min(MAX_ROW_HEIGHT);
+
+            if area.width() > 0.0 && area.height() > 0.0 {
+                self.draw_rows(cx, rect);
+                self.axes.draw_grid(cx, &mut self.draw_line);
+                self.axes.draw_axes(cx, &mut self.draw_line);
+                self.label.set_font_size(9.0);
+                self.axes.draw_tick_labels(cx, &mut self.label);
+                if self.show_now {
+                    self.draw_now(cx);
+                }
+                self.draw_dependencies(cx);
+                self.draw_bars(cx);
+                self.draw_labels(cx);
+                self.draw_tooltip(cx);
+            }
+
+            if !self.title.is_empty() {
+                self.label.set_font_size(12.0);
+                self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+                self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + 10.0), &self.title, TextAnchor::TopCenter);
+            }
+        }
+
+        self.capture.end();
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        if !self.tasks.is_empty() {
+            self.handle_pointer(cx, event, scope);
+        }
+    }
+}
+
+impl PlotExport for GanttChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
+impl GanttChartRef {
+    pub fn set_tasks(&self, tasks: Vec<GanttTask>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_tasks(tasks); }
+    }
+    pub fn set_title(&self, title: impl Into<String>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
+    }
+    pub fn set_label_width(&self, width: f64) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_label_width(width); }
+    }
+    pub fn set_snap(&self, seconds: f64) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_snap(seconds); }
+    }
+    pub fn set_show_now(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_show_now(show); }
+    }
+    pub fn set_now(&self, time: Option<f64>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_now(time); }
+    }
+    pub fn set_time_range(&self, min: f64, max: f64) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_time_range(min, max); }
+    }
+    pub fn set_timezone(&self, timezone: TimeZone) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_timezone(timezone); }
+    }
+    pub fn set_collapsed(&self, group: &str, collapsed: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_collapsed(group, collapsed); }
+    }
+    pub fn reset_view(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.reset_view(); }
+    }
+    pub fn clear(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.clear(); }
+    }
+    pub fn redraw(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); }
+    }
+
+    /// Id, start and end of a task dragged to new times, if any
+    pub fn rescheduled(&self, actions: &Actions) -> Option<(String, f64, f64)> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .find_map(|action| match action.cast() {
+                GanttAction::Rescheduled { id, start, end } => Some((id, start, end)),
+                _ => None,
+            })
+    }
+
+    /// Id of the task that was clicked, if any
+    pub fn task_clicked(&self, actions: &Actions) -> Option<String> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .find_map(|action| match action.cast() {
+                GanttAction::TaskClicked { id } => Some(id),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: f64 = 3600.0;
+
+    /// design -> build -> test -> ship, build and test grouped under "dev"
+    fn plan() -> Vec<GanttTask> {
+        vec![
+            GanttTask::new("design", "Design", 0.0, 10.0 * HOUR),
+            GanttTask::new("build", "Build", 10.0 * HOUR, 20.0 * HOUR).with_group("dev").with_dependency("design"),
+            GanttTask::new("test", "Test", 15.0 * HOUR, 30.0 * HOUR)
+                .with_group("dev")
+                .with_dependency("build")
+                .with_dependency("missing"),
+            GanttTask::milestone("ship", "Ship", 30.0 * HOUR).with_dependency("test").with_dependency("design"),
+        ]
+    }
+
+    #[test]
+    fn test_build_rows() {
+        let mut tasks = plan();
+        tasks.push(GanttTask::new("docs", "Docs", 0.0, HOUR).with_group("dev"));
+        let rows = build_rows(&tasks, &[]);
+        assert_eq!(
+            rows,
+            vec![
+                GanttRow::Task { index: 0, grouped: false },
+                GanttRow::Group { name: "dev".into(), tasks: vec![1, 2, 4], collapsed: false },
+                GanttRow::Task { index: 1, grouped: true },
+                GanttRow::Task { index: 2, grouped: true },
+                GanttRow::Task { index: 4, grouped: true },
+                GanttRow::Task { index: 3, grouped: false },
+            ]
+        );
+        let shut = build_rows(&tasks, &["dev".to_string()]);
+        assert_eq!(shut.len(), 3);
+        assert_eq!(task_row(&shut, 2), Some(1));
+        assert_eq!(task_row(&shut, 3), Some(2));
+        assert_eq!(task_row(&shut, 9), None);
+    }
+
+    #[test]
+    fn test_group_summary() {
+        let tasks = vec![
+            GanttTask::new("a", "A", 0.0, 10.0).with_progress(0.5),
+            GanttTask::new("b", "B", 10.0, 40.0).with_progress(1.0),
+            GanttTask::milestone("m", "M", 50.0),
+        ];
+        // Progress weighted by duration: (5 + 30) / 40; the milestone adds
+        // span but no work
+        assert_eq!(group_summary(&tasks, &[0, 1]), (0.0, 40.0, 0.875));
+        assert_eq!(group_summary(&tasks, &[0, 1, 2]), (0.0, 50.0, 0.875));
+        assert_eq!(group_summary(&tasks, &[2]), (50.0, 50.0, 0.0));
+    }
+
+    #[test]
+    fn test_dependency_links() {
+        let tasks = plan();
+        // Test starts before build ends, so it is late; ship waits for both
+        // of its dependencies. The unknown id draws nothing.
+        let open = build_rows(&tasks, &[]);
+        assert_eq!(dependency_links(&tasks, &open), vec![(0, 1, false), (1, 2, true), (2, 3, false), (0, 3, false)]);
+
+        // Collapsed, build and test share the group row: the link between
+        // them goes, the ones leaving the group stay
+        let shut = build_rows(&tasks, &["dev".to_string()]);
+        assert_eq!(dependency_links(&tasks, &shut), vec![(0, 1, false), (2, 3, false), (0, 3, false)]);
+
+        let mut moved = tasks.clone();
+        moved[3].start = 25.0 * HOUR;
+        assert!(dependency_links(&moved, &open).contains(&(2, 3, true)));
+    }
+
+    #[test]
+    fn test_dependency_path() {
+        // Room for one turn between the bars
+        assert_eq!(dependency_path((0.0, 10.0), (100.0, 40.0), 25.0), vec![(0.0, 10.0), (8.0, 10.0), (8.0, 40.0), (100.0, 40.0)]);
+        // Too close: out, along the row boundary, and back in
+        assert_eq!(
+            dependency_path((50.0, 10.0), (40.0, 40.0), 25.0),
+            vec![(50.0, 10.0), (58.0, 10.0), (58.0, 25.0), (32.0, 25.0), (32.0, 40.0), (40.0, 40.0)]
+        );
+    }
+
+    #[test]
+    fn test_drag_times() {
+        let (start, end) = (0.0, 2.0 * HOUR);
+        // Moving keeps the duration and snaps the start
+        assert_eq!(drag_times(GanttGrab::Move, start, end, 5000.0, HOUR), (HOUR, 3.0 * HOUR));
+        assert_eq!(drag_times(GanttGrab::Move, start, end, -1000.0, HOUR), (0.0, 2.0 * HOUR));
+        assert_eq!(drag_times(GanttGrab::Move, start, end, 1234.5, 0.0), (1234.5, 1234.5 + 2.0 * HOUR));
+        // Resizing snaps only the dragged end
+        assert_eq!(drag_times(GanttGrab::Start, start, end, 2000.0, HOUR), (HOUR, end));
+        assert_eq!(drag_times(GanttGrab::End, start, end, 2000.0, HOUR), (start, 3.0 * HOUR));
+        // ...and stops at the other one
+        assert_eq!(drag_times(GanttGrab::Start, start, end, 10.0 * HOUR, HOUR), (end, end));
+        assert_eq!(drag_times(GanttGrab::End, start, end, -10.0 * HOUR, 0.0), (start, start));
+    }
+}
@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -36,6 +37,9 @@ pub struct GaugeChart {
     #[rust] show_value: bool,
     #[rust] unit: String,
     #[rust] arc_width: f64,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl GaugeChart {
@@ -84,6 +88,7 @@ impl GaugeChart {
 impl Widget for GaugeChart {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 {
             // Initialize defaults
@@ -157,7 +162,7 @@ impl Widget for GaugeChart {
 
             // Draw center circle
             self.draw_fill.color = vec4(0.3, 0.3, 0.3, 1.0);
-            self.draw_fill.draw_abs(cx, Rect {
+            self.draw_fill.draw_rect(cx, Rect {
                 pos: dvec2(center.x - 8.0, center.y - 8.0),
                 size: dvec2(16.0, 16.0),
             });
@@ -177,6 +182,7 @@ impl Widget for GaugeChart {
             self.label.draw_at(cx, max_pos, &format!("{:.0}", self.max_value), TextAnchor::TopCenter);
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -185,6 +191,16 @@ impl Widget for GaugeChart {
     }
 }
 
+impl PlotExport for GaugeChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl GaugeChartRef {
     pub fn set_title(&self, title: impl Into<String>) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
@@ -248,6 +264,9 @@ pub struct FunnelChart {
     #[rust(20.0)] bottom_margin: f64,
     #[rust(30.0)] right_margin: f64,
     #[rust(30.0)] top_margin: f64,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl FunnelChart {
@@ -279,6 +298,7 @@ impl FunnelChart {
 impl Widget for FunnelChart {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.stages.is_empty() {
             let plot_rect = Rect {
@@ -296,7 +316,7 @@ impl Widget for FunnelChart {
             }
 
             let max_value = self.stages.iter().map(|s| s.value).fold(0.0f64, f64::max);
-            if max_value == 0.0 { return DrawStep::done(); }
+            if max_value == 0.0 { self.capture.end(); return DrawStep::done(); }
 
             let num_stages = self.stages.len();
             let stage_height = plot_rect.size.y / num_stages as f64;
@@ -333,7 +353,7 @@ impl Widget for FunnelChart {
                     let t = j as f64 / num_lines as f64;
                     let line_y = y + t * stage_height;
                     let line_width = width + (next_width - width) * t;
-                    self.draw_fill.draw_abs(cx, Rect {
+                    self.draw_fill.draw_rect(cx, Rect {
                         pos: dvec2(center_x - line_width / 2.0, line_y),
                         size: dvec2(line_width, 2.0),
                     });
@@ -360,6 +380,7 @@ impl Widget for FunnelChart {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -368,6 +389,16 @@ impl Widget for FunnelChart {
     }
 }
 
+impl PlotExport for FunnelChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl FunnelChartRef {
     pub fn set_title(&self, title: impl Into<String>) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
@@ -0,0 +1,691 @@
+// Geographic shapes: GeoJSON and TopoJSON loading, map projections and
+// polygon triangulation
+//
+// Features keep their Polygon and MultiPolygon geometry as rings of
+// (longitude, latitude) in degrees; other geometry types are skipped.
+// TopoJSON arcs are decoded (undoing the quantization transform) and
+// stitched back into rings, so both formats end up as the same `GeoData`.
+//
+// Projections map degrees to planar coordinates with y pointing north, and
+// back; the map widget fits them to the screen. Filled regions are cut into
+// triangles by ear clipping once per projection, after joining each hole to
+// its outer ring with a bridge edge.
+
+use serde_json::Value;
+
+/// Closed ring of (longitude, latitude) points, without the repeated first
+/// point
+pub type GeoRing = Vec<(f64, f64)>;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GeoPolygon {
+    pub exterior: GeoRing,
+    pub holes: Vec<GeoRing>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GeoFeature {
+    pub id: Option<String>,
+    /// String, number and boolean properties as text
+    pub properties: Vec<(String, String)>,
+    pub polygons: Vec<GeoPolygon>,
+}
+
+impl GeoFeature {
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Key matched against data values: the given property, or else the id
+    /// and then the "name" property
+    pub fn key(&self, property: Option<&str>) -> Option<&str> {
+        match property {
+            Some(property) => self.property(property),
+            None => self.id.as_deref().or_else(|| self.property("name")),
+        }
+    }
+
+    /// Name shown on hover: the "name" property, or else the id
+    pub fn name(&self) -> Option<&str> {
+        self.property("name").or(self.id.as_deref())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GeoData {
+    pub features: Vec<GeoFeature>,
+}
+
+impl GeoData {
+    /// Read a GeoJSON or TopoJSON file
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Self::parse(&text).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// Parse GeoJSON, or TopoJSON when the document is a "Topology"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+        if value.get("type").and_then(Value::as_str) == Some("Topology") {
+            Self::from_topojson(&value, None)
+        } else {
+            Self::from_geojson(&value)
+        }
+    }
+
+    /// Features of a FeatureCollection, a single Feature or a bare geometry
+    pub fn from_geojson(value: &Value) -> Result<Self, String> {
+        let mut features = Vec::new();
+        match value.get("type").and_then(Value::as_str) {
+            Some("FeatureCollection") => {
+                let list = value.get("features").and_then(Value::as_array).ok_or("FeatureCollection without features")?;
+                for feature in list {
+                    features.push(geojson_feature(feature)?);
+                }
+            }
+            Some("Feature") => features.push(geojson_feature(value)?),
+            Some(_) => features.push(GeoFeature { polygons: geojson_polygons(value)?, ..GeoFeature::default() }),
+            None => return Err("not GeoJSON: missing \"type\"".to_string()),
+        }
+        Ok(Self { features })
+    }
+
+    /// Geometries of the named object, or of every object when `object` is
+    /// None, as features
+    pub fn from_topojson(value: &Value, object: Option<&str>) -> Result<Self, String> {
+        let arcs = topojson_arcs(value)?;
+        let objects = value.get("objects").and_then(Value::as_object).ok_or("Topology without objects")?;
+        let mut features = Vec::new();
+        for (name, geometry) in objects {
+            if object.is_none_or(|object| object == name) {
+                topojson_features(geometry, &arcs, &mut features)?;
+            }
+        }
+        if let Some(object) = object.filter(|object| !objects.contains_key(*object)) {
+            return Err(format!("Topology has no object \"{}\"", object));
+        }
+        Ok(Self { features })
+    }
+
+    /// Smallest and largest longitude and latitude over all rings
+    pub fn bounds(&self) -> Option<((f64, f64), (f64, f64))> {
+        let points = self.features.iter().flat_map(|f| &f.polygons).flat_map(|p| &p.exterior);
+        point_bounds(points.copied())
+    }
+}
+
+/// ((min x, max x), (min y, max y)) of some points
+pub fn point_bounds(points: impl Iterator<Item = (f64, f64)>) -> Option<((f64, f64), (f64, f64))> {
+    points.filter(|(x, y)| x.is_finite() && y.is_finite()).fold(None, |bounds, (x, y)| match bounds {
+        None => Some(((x, x), (y, y))),
+        Some(((x0, x1), (y0, y1))) => Some(((x0.min(x), x1.max(x)), (y0.min(y), y1.max(y)))),
+    })
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+fn feature_meta(value: &Value) -> (Option<String>, Vec<(String, String)>) {
+    let id = value.get("id").and_then(scalar_text);
+    let properties = value
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|map| map.iter().filter_map(|(key, value)| Some((key.clone(), scalar_text(value)?))).collect())
+        .unwrap_or_default();
+    (id, properties)
+}
+
+fn geojson_feature(value: &Value) -> Result<GeoFeature, String> {
+    let (id, properties) = feature_meta(value);
+    let polygons = match value.get("geometry") {
+        Some(Value::Null) | None => Vec::new(),
+        Some(geometry) => geojson_polygons(geometry)?,
+    };
+    Ok(GeoFeature { id, properties, polygons })
+}
+
+fn geojson_polygons(geometry: &Value) -> Result<Vec<GeoPolygon>, String> {
+    let coordinates = geometry.get("coordinates");
+    match geometry.get("type").and_then(Value::as_str) {
+        Some("Polygon") => Ok(vec![geojson_polygon(coordinates.ok_or("Polygon without coordinates")?)?]),
+        Some("MultiPolygon") => coordinates
+            .and_then(Value::as_array)
+            .ok_or("MultiPolygon without coordinates")?
+            .iter()
+            .map(geojson_polygon)
+            .collect(),
+        Some("GeometryCollection") => {
+            let mut polygons = Vec::new();
+            for member in geometry.get("geometries").and_then(Value::as_array).into_iter().flatten() {
+                polygons.extend(geojson_polygons(member)?);
+            }
+            Ok(polygons)
+        }
+        Some(_) => Ok(Vec::new()),
+        None => Err("geometry without \"type\"".to_string()),
+    }
+}
+
+fn geojson_polygon(value: &Value) -> Result<GeoPolygon, String> {
+    let rings = value.as_array().ok_or("polygon is not a list of rings")?;
+    let mut rings = rings.iter().map(|ring| {
+        let points = ring.as_array().ok_or("ring is not a list of positions")?;
+        points.iter().map(position).collect::<Result<GeoRing, String>>().map(close_ring)
+    });
+    let exterior = rings.next().transpose()?.unwrap_or_default();
+    let holes = rings.collect::<Result<Vec<_>, _>>()?;
+    Ok(GeoPolygon { exterior, holes })
+}
+
+fn position(value: &Value) -> Result<(f64, f64), String> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([x, y, ..]) => match (x.as_f64(), y.as_f64()) {
+            (Some(x), Some(y)) => Ok((x, y)),
+            _ => Err("position is not numeric".to_string()),
+        },
+        _ => Err("position needs a longitude and a latitude".to_string()),
+    }
+}
+
+/// Drop the repeated closing point and consecutive duplicates
+fn close_ring(mut ring: GeoRing) -> GeoRing {
+    ring.dedup();
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    ring
+}
+
+/// Arcs in degrees, undoing delta encoding when the topology is quantized
+fn topojson_arcs(value: &Value) -> Result<Vec<GeoRing>, String> {
+    let transform = value.get("transform").map(|transform| {
+        let pair = |key: &str, default: f64| {
+            let list = transform.get(key).and_then(Value::as_array);
+            let at = |i: usize| list.and_then(|l| l.get(i)).and_then(Value::as_f64).unwrap_or(default);
+            (at(0), at(1))
+        };
+        (pair("scale", 1.0), pair("translate", 0.0))
+    });
+    let arcs = value.get("arcs").and_then(Value::as_array).ok_or("Topology without arcs")?;
+    arcs.iter()
+        .map(|arc| {
+            let points = arc.as_array().ok_or("arc is not a list of positions")?;
+            let mut cursor = (0.0, 0.0);
+            points
+                .iter()
+                .map(|point| {
+                    let (x, y) = position(point)?;
+                    Ok(match transform {
+                        Some(((sx, sy), (tx, ty))) => {
+                            cursor = (cursor.0 + x, cursor.1 + y);
+                            (cursor.0 * sx + tx, cursor.1 * sy + ty)
+                        }
+                        None => (x, y),
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Ring made of arcs, reversed for negative (one's complement) indices
+fn topojson_ring(value: &Value, arcs: &[GeoRing]) -> Result<GeoRing, String> {
+    let mut ring = GeoRing::new();
+    for index in value.as_array().ok_or("ring is not a list of arcs")? {
+        let index = index.as_i64().ok_or("arc index is not an integer")?;
+        let arc = arcs.get(if index < 0 { !index } else { index } as usize).ok_or_else(|| format!("no arc {}", index))?;
+        let skip = usize::from(!ring.is_empty());
+        if index < 0 {
+            ring.extend(arc.iter().rev().skip(skip));
+        } else {
+            ring.extend(arc.iter().skip(skip));
+        }
+    }
+    Ok(close_ring(ring))
+}
+
+fn topojson_polygon(value: &Value, arcs: &[GeoRing]) -> Result<GeoPolygon, String> {
+    let mut rings = value.as_array().ok_or("polygon is not a list of rings")?.iter().map(|ring| topojson_ring(ring, arcs));
+    let exterior = rings.next().transpose()?.unwrap_or_default();
+    let holes = rings.collect::<Result<Vec<_>, _>>()?;
+    Ok(GeoPolygon { exterior, holes })
+}
+
+fn topojson_features(geometry: &Value, arcs: &[GeoRing], features: &mut Vec<GeoFeature>) -> Result<(), String> {
+    let shape = geometry.get("arcs");
+    let polygons = match geometry.get("type").and_then(Value::as_str) {
+        Some("GeometryCollection") => {
+            for member in geometry.get("geometries").and_then(Value::as_array).into_iter().flatten() {
+                topojson_features(member, arcs, features)?;
+            }
+            return Ok(());
+        }
+        Some("Polygon") => vec![topojson_polygon(shape.ok_or("Polygon without arcs")?, arcs)?],
+        Some("MultiPolygon") => shape
+            .and_then(Value::as_array)
+            .ok_or("MultiPolygon without arcs")?
+            .iter()
+            .map(|polygon| topojson_polygon(polygon, arcs))
+            .collect::<Result<_, _>>()?,
+        _ => Vec::new(),
+    };
+    let (id, properties) = feature_meta(geometry);
+    features.push(GeoFeature { id, properties, polygons });
+    Ok(())
+}
+
+/// Map projection from degrees to planar coordinates, y pointing north
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GeoProjection {
+    /// Plate carrée: longitude and latitude as x and y
+    #[default]
+    Equirectangular,
+    /// Conformal; latitudes are clamped to ±85.05°
+    Mercator,
+    /// Equal-area conic around `center` (longitude, latitude) with two
+    /// standard parallels; unset values are fitted to the data
+    Albers { center: Option<(f64, f64)>, parallels: Option<(f64, f64)> },
+    /// Natural Earth pseudo-cylindrical projection for world maps
+    NaturalEarth,
+}
+
+impl GeoProjection {
+    /// Albers fitted to the data bounds
+    pub fn albers() -> Self {
+        GeoProjection::Albers { center: None, parallels: None }
+    }
+
+    /// Fill unset Albers parameters from a longitude and latitude box: the
+    /// center of the box, and parallels a sixth of its height inside it
+    pub fn fitted(self, bounds: Option<((f64, f64), (f64, f64))>) -> Self {
+        match self {
+            GeoProjection::Albers { center, parallels } => {
+                let ((lon0, lon1), (lat0, lat1)) = bounds.unwrap_or(((-180.0, 180.0), (-60.0, 80.0)));
+                let sixth = (lat1 - lat0) / 6.0;
+                GeoProjection::Albers {
+                    center: Some(center.unwrap_or(((lon0 + lon1) / 2.0, (lat0 + lat1) / 2.0))),
+                    parallels: Some(parallels.unwrap_or((lat0 + sixth, lat1 - sixth))),
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Planar (x, y) of a longitude and latitude in degrees
+    pub fn project(&self, lon: f64, lat: f64) -> (f64, f64) {
+        let (lambda, phi) = (lon.to_radians(), lat.to_radians());
+        match *self {
+            GeoProjection::Equirectangular => (lambda, phi),
+            GeoProjection::Mercator => {
+                let phi = phi.clamp(-85.05f64.to_radians(), 85.05f64.to_radians());
+                (lambda, (std::f64::consts::FRAC_PI_4 + phi / 2.0).tan().ln())
+            }
+            GeoProjection::Albers { center, parallels } => {
+                let (lon0, lat0) = center.unwrap_or((0.0, 0.0));
+                let (lat1, lat2) = parallels.unwrap_or((20.0, 50.0));
+                let (phi0, phi1, phi2) = (lat0.to_radians(), lat1.to_radians(), lat2.to_radians());
+                let lambda = wrap_radians(lambda - lon0.to_radians());
+                let n = (phi1.sin() + phi2.sin()) / 2.0;
+                if n.abs() < 1e-6 {
+                    // Parallels symmetric about the equator: cylindrical equal-area
+                    return (lambda * phi1.cos(), phi.sin() / phi1.cos());
+                }
+                let c = phi1.cos() * phi1.cos() + 2.0 * n * phi1.sin();
+                let rho = |phi: f64| (c - 2.0 * n * phi.sin()).max(0.0).sqrt() / n;
+                let theta = n * lambda;
+                (rho(phi) * theta.sin(), rho(phi0) - rho(phi) * theta.cos())
+            }
+            GeoProjection::NaturalEarth => (lambda * natural_earth_width(phi), natural_earth_height(phi)),
+        }
+    }
+
+    /// Longitude and latitude in degrees of a planar point; the inverse of
+    /// `project` for points it reaches (Mercator stops at ±85.05°)
+    pub fn unproject(&self, x: f64, y: f64) -> (f64, f64) {
+        let (lambda, phi) = match *self {
+            GeoProjection::Equirectangular => (x, y),
+            GeoProjection::Mercator => (x, 2.0 * y.exp().atan() - std::f64::consts::FRAC_PI_2),
+            GeoProjection::Albers { center, parallels } => {
+                let (lon0, lat0) = center.unwrap_or((0.0, 0.0));
+                let (lat1, lat2) = parallels.unwrap_or((20.0, 50.0));
+                let (phi0, phi1, phi2) = (lat0.to_radians(), lat1.to_radians(), lat2.to_radians());
+                let n = (phi1.sin() + phi2.sin()) / 2.0;
+                if n.abs() < 1e-6 {
+                    let lambda = wrap_radians(x / phi1.cos() + lon0.to_radians());
+                    return (lambda.to_degrees(), (y * phi1.cos()).clamp(-1.0, 1.0).asin().to_degrees());
+                }
+                let c = phi1.cos() * phi1.cos() + 2.0 * n * phi1.sin();
+                let dy = (c - 2.0 * n * phi0.sin()).max(0.0).sqrt() / n - y;
+                // rho carries the sign of n, like the forward radius
+                let rho = (x * x + dy * dy).sqrt().copysign(n);
+                let theta = (x * n.signum()).atan2(dy * n.signum());
+                let sin_phi = ((c - rho * rho * n * n) / (2.0 * n)).clamp(-1.0, 1.0);
+                (wrap_radians(theta / n + lon0.to_radians()), sin_phi.asin())
+            }
+            GeoProjection::NaturalEarth => {
+                // Newton's method on the latitude polynomial
+                let mut phi = y;
+                for _ in 0..25 {
+                    let delta = (natural_earth_height(phi) - y) / natural_earth_slope(phi);
+                    phi -= delta;
+                    if delta.abs() < 1e-12 {
+                        break;
+                    }
+                }
+                (x / natural_earth_width(phi), phi)
+            }
+        };
+        (lambda.to_degrees(), phi.to_degrees())
+    }
+}
+
+/// Natural Earth x scale at latitude `phi` (radians)
+fn natural_earth_width(phi: f64) -> f64 {
+    let phi2 = phi * phi;
+    let phi4 = phi2 * phi2;
+    0.8707 - 0.131979 * phi2 + phi4 * (-0.013791 + phi4 * (0.003971 * phi2 - 0.001529 * phi4))
+}
+
+/// Natural Earth y of latitude `phi` (radians)
+fn natural_earth_height(phi: f64) -> f64 {
+    let phi2 = phi * phi;
+    let phi4 = phi2 * phi2;
+    phi * (1.007226 + phi2 * (0.015085 + phi4 * (-0.044475 + 0.028874 * phi2 - 0.005916 * phi4)))
+}
+
+/// Derivative of `natural_earth_height`
+fn natural_earth_slope(phi: f64) -> f64 {
+    let phi2 = phi * phi;
+    let phi4 = phi2 * phi2;
+    1.007226 + phi2 * (3.0 * 0.015085 + phi4 * (-7.0 * 0.044475 + 9.0 * 0.028874 * phi2 - 11.0 * 0.005916 * phi4))
+}
+
+fn wrap_radians(angle: f64) -> f64 {
+    let tau = std::f64::consts::TAU;
+    angle - tau * ((angle + std::f64::consts::PI) / tau).floor()
+}
+
+fn signed_area(ring: &[(f64, f64)]) -> f64 {
+    let n = ring.len();
+    (0..n).map(|i| {
+        let (a, b) = (ring[i], ring[(i + 1) % n]);
+        a.0 * b.1 - b.0 * a.1
+    }).sum::<f64>() / 2.0
+}
+
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// Whether segments `a`–`b` and `c`–`d` cross at a point interior to both
+fn segments_cross(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> bool {
+    let (d1, d2) = (cross(a, b, c), cross(a, b, d));
+    let (d3, d4) = (cross(c, d, a), cross(c, d, b));
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+/// Even-odd test of a point against rings; holes count as outside
+pub fn point_in_rings<'a>(point: (f64, f64), rings: impl Iterator<Item = &'a [(f64, f64)]>) -> bool {
+    let mut inside = false;
+    for ring in rings {
+        let n = ring.len();
+        for i in 0..n {
+            let (a, b) = (ring[i], ring[(i + n - 1) % n]);
+            if (a.1 > point.1) != (b.1 > point.1) && point.0 < (b.0 - a.0) * (point.1 - a.1) / (b.1 - a.1) + a.0 {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Join each hole to the outer ring through a bridge edge to the nearest
+/// vertex it can see, leaving one ring that ear clipping can fill
+fn merge_holes(exterior: &[(f64, f64)], holes: &[Vec<(f64, f64)>]) -> Vec<(f64, f64)> {
+    let mut outline: Vec<(f64, f64)> = exterior.to_vec();
+    if signed_area(&outline) < 0.0 {
+        outline.reverse();
+    }
+    let mut holes: Vec<Vec<(f64, f64)>> = holes.iter().filter(|hole| hole.len() >= 3).cloned().collect();
+    for hole in &mut holes {
+        if signed_area(hole) > 0.0 {
+            hole.reverse();
+        }
+    }
+    // Rightmost holes first, so bridges to the outline stay clear of the rest
+    let rightmost = |hole: &Vec<(f64, f64)>| hole.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    holes.sort_by(|a, b| rightmost(b).total_cmp(&rightmost(a)));
+
+    for h in 0..holes.len() {
+        let hole = &holes[h];
+        let start = (0..hole.len()).max_by(|&a, &b| hole[a].0.total_cmp(&hole[b].0)).unwrap_or(0);
+        let m = hole[start];
+        let edges = |ring: &[(f64, f64)]| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])).collect::<Vec<_>>();
+        let mut blockers = edges(&outline);
+        for other in &holes[h..] {
+            blockers.extend(edges(other));
+        }
+        let mut candidates: Vec<usize> = (0..outline.len()).collect();
+        candidates.sort_by(|&a, &b| {
+            let da = (outline[a].0 - m.0).powi(2) + (outline[a].1 - m.1).powi(2);
+            let db = (outline[b].0 - m.0).powi(2) + (outline[b].1 - m.1).powi(2);
+            da.total_cmp(&db)
+        });
+        let visible = candidates
+            .iter()
+            .copied()
+            .find(|&i| {
+                let v = outline[i];
+                blockers.iter().all(|&(a, b)| !segments_cross(m, v, a, b))
+            })
+            .unwrap_or(candidates[0]);
+
+        let mut merged = Vec::with_capacity(outline.len() + hole.len() + 2);
+        merged.extend_from_slice(&outline[..=visible]);
+        merged.extend(hole[start..].iter().chain(&hole[..start]));
+        merged.push(m);
+        merged.extend_from_slice(&outline[visible..]);
+        outline = merged;
+    }
+    outline
+}
+
+fn in_triangle(p: (f64, f64), a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+/// Triangles filling a polygon with holes, by ear clipping. Rings may wind
+/// either way; degenerate input still terminates, possibly with slivers.
+pub fn triangulate_polygon(exterior: &[(f64, f64)], holes: &[Vec<(f64, f64)>]) -> Vec<[(f64, f64); 3]> {
+    if exterior.len() < 3 {
+        return Vec::new();
+    }
+    let ring = merge_holes(exterior, holes);
+    let mut indices: Vec<usize> = (0..ring.len()).collect();
+    let mut triangles = Vec::with_capacity(ring.len());
+    let mut i = 0;
+    let mut misses = 0;
+    while indices.len() > 3 {
+        let n = indices.len();
+        let (ia, ib, ic) = (indices[(i + n - 1) % n], indices[i], indices[(i + 1) % n]);
+        let (a, b, c) = (ring[ia], ring[ib], ring[ic]);
+        let convex = cross(a, b, c) > 0.0;
+        let ear = convex
+            && indices.iter().all(|&j| {
+                let p = ring[j];
+                j == ia || j == ib || j == ic || p == a || p == b || p == c || !in_triangle(p, a, b, c)
+            });
+        // After a full lap without an ear the rest is degenerate; clip anyway
+        if ear || misses >= n {
+            if cross(a, b, c).abs() > 0.0 {
+                triangles.push([a, b, c]);
+            }
+            indices.remove(i);
+            misses = 0;
+            // Step back: the previous vertex may have become an ear
+            i = if i == 0 { n - 2 } else { i - 1 };
+        } else {
+            i = (i + 1) % n;
+            misses += 1;
+        }
+    }
+    if indices.len() == 3 && cross(ring[indices[0]], ring[indices[1]], ring[indices[2]]).abs() > 0.0 {
+        triangles.push([ring[indices[0]], ring[indices[1]], ring[indices[2]]]);
+    }
+    triangles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn area(triangles: &[[(f64, f64); 3]]) -> f64 {
+        triangles.iter().map(|&[a, b, c]| cross(a, b, c).abs() / 2.0).sum()
+    }
+
+    #[test]
+    fn test_projections_round_trip() {
+        let projections = [
+            GeoProjection::Equirectangular,
+            GeoProjection::Mercator,
+            GeoProjection::NaturalEarth,
+            GeoProjection::albers(),
+            GeoProjection::albers().fitted(Some(((-125.0, -66.0), (24.0, 50.0)))),
+            // Southern parallels, and parallels symmetric about the equator
+            GeoProjection::Albers { center: Some((140.0, -30.0)), parallels: Some((-45.0, -15.0)) },
+            GeoProjection::Albers { center: Some((0.0, 0.0)), parallels: Some((-30.0, 30.0)) },
+        ];
+        for projection in projections {
+            for lon in (-17..=17).map(|i| i as f64 * 10.0 + 3.0) {
+                for lat in (-8..=8).map(|i| i as f64 * 10.0 + 1.5) {
+                    let (x, y) = projection.project(lon, lat);
+                    let back = projection.unproject(x, y);
+                    assert!(
+                        (back.0 - lon).abs() < 1e-7 && (back.1 - lat).abs() < 1e-7,
+                        "{:?}: ({}, {}) came back as {:?}",
+                        projection,
+                        lon,
+                        lat,
+                        back
+                    );
+                }
+            }
+        }
+        // Mercator stops short of the poles
+        let (x, y) = GeoProjection::Mercator.project(20.0, 89.0);
+        let (_, lat) = GeoProjection::Mercator.unproject(x, y);
+        assert!((lat - 85.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_albers_fitted() {
+        let fitted = GeoProjection::albers().fitted(Some(((-120.0, -60.0), (20.0, 50.0))));
+        assert_eq!(fitted, GeoProjection::Albers { center: Some((-90.0, 35.0)), parallels: Some((25.0, 45.0)) });
+        // The center projects onto the origin
+        let (x, y) = fitted.project(-90.0, 35.0);
+        assert!(x.abs() < 1e-12 && y.abs() < 1e-12);
+        assert_eq!(GeoProjection::Mercator.fitted(None), GeoProjection::Mercator);
+    }
+
+    /// Two quantized arcs forming a 2 by 1 rectangle once decoded
+    fn topology() -> Value {
+        json!({
+            "type": "Topology",
+            "transform": {"scale": [0.5, 0.25], "translate": [10, 20]},
+            "arcs": [
+                [[0, 0], [4, 0], [0, 4]],
+                [[4, 4], [-4, 0], [0, -4]]
+            ],
+            "objects": {
+                "shapes": {"type": "GeometryCollection", "geometries": [
+                    {"type": "Polygon", "arcs": [[0, 1]], "id": "a", "properties": {"name": "A", "pop": 5}},
+                    {"type": "MultiPolygon", "arcs": [[[-2, -1]]], "id": 7},
+                    {"type": "LineString", "arcs": [0]}
+                ]}
+            }
+        })
+    }
+
+    #[test]
+    fn test_topojson_arcs_decoded() {
+        // Deltas accumulate before the scale and translation apply
+        let arcs = topojson_arcs(&topology()).unwrap();
+        assert_eq!(arcs[0], vec![(10.0, 20.0), (12.0, 20.0), (12.0, 21.0)]);
+        assert_eq!(arcs[1], vec![(12.0, 21.0), (10.0, 21.0), (10.0, 20.0)]);
+
+        // Without a transform positions are absolute
+        let plain = json!({"arcs": [[[1, 2], [3, 4]]]});
+        assert_eq!(topojson_arcs(&plain).unwrap(), vec![vec![(1.0, 2.0), (3.0, 4.0)]]);
+    }
+
+    #[test]
+    fn test_topojson_features() {
+        let data = GeoData::parse(&topology().to_string()).unwrap();
+        assert_eq!(data.features.len(), 3);
+        let a = &data.features[0];
+        assert_eq!(a.id.as_deref(), Some("a"));
+        assert_eq!((a.name(), a.property("pop")), (Some("A"), Some("5")));
+        // Shared end points are joined once and the closing point dropped
+        assert_eq!(a.polygons[0].exterior, vec![(10.0, 20.0), (12.0, 20.0), (12.0, 21.0), (10.0, 21.0)]);
+        // Negative indices walk arcs backwards
+        let b = &data.features[1];
+        assert_eq!(b.id.as_deref(), Some("7"));
+        assert_eq!(b.polygons[0].exterior, vec![(10.0, 20.0), (10.0, 21.0), (12.0, 21.0), (12.0, 20.0)]);
+        // Lines carry no polygons
+        assert!(data.features[2].polygons.is_empty());
+        assert_eq!(data.bounds(), Some(((10.0, 12.0), (20.0, 21.0))));
+
+        let topology = topology();
+        assert_eq!(GeoData::from_topojson(&topology, Some("shapes")).unwrap(), data);
+        assert_eq!(GeoData::from_topojson(&topology, Some("roads")).unwrap_err(), "Topology has no object \"roads\"");
+        let mut broken = topology;
+        broken["objects"]["shapes"]["geometries"][0]["arcs"] = json!([[0, 5]]);
+        assert_eq!(GeoData::from_topojson(&broken, None).unwrap_err(), "no arc 5");
+    }
+
+    #[test]
+    fn test_geojson_features() {
+        let data = GeoData::parse(
+            r#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "properties": {"name": "Square"}, "geometry": {"type": "Polygon", "coordinates": [
+                    [[0, 0], [4, 0], [4, 4], [0, 4], [0, 0]],
+                    [[1, 1], [1, 2], [2, 2], [2, 1], [1, 1]]
+                ]}},
+                {"type": "Feature", "id": 3, "geometry": {"type": "Point", "coordinates": [1, 1]}}
+            ]}"#,
+        )
+        .unwrap();
+        let square = &data.features[0];
+        assert_eq!(square.key(None), Some("Square"));
+        assert_eq!(square.polygons[0].exterior.len(), 4);
+        assert_eq!(square.polygons[0].holes[0].len(), 4);
+        assert_eq!(data.features[1].key(None), Some("3"));
+        assert!(data.features[1].polygons.is_empty());
+        assert!(GeoData::parse(r#"{"features": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_triangulate_with_hole() {
+        let exterior = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+        let holes = vec![vec![(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)]];
+        let triangles = triangulate_polygon(&exterior, &holes);
+        assert!((area(&triangles) - 15.0).abs() < 1e-9);
+        // Either winding fills the same area
+        let reversed: Vec<(f64, f64)> = exterior.iter().rev().copied().collect();
+        assert!((area(&triangulate_polygon(&reversed, &holes)) - 15.0).abs() < 1e-9);
+        assert!(triangulate_polygon(&exterior[..2], &[]).is_empty());
+
+        let rings = || std::iter::once(&exterior[..]).chain(holes.iter().map(Vec::as_slice));
+        assert!(point_in_rings((3.0, 3.0), rings()));
+        assert!(!point_in_rings((1.5, 1.5), rings()));
+        assert!(!point_in_rings((5.0, 1.0), rings()));
+    }
+}
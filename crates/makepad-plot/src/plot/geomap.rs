@@ -0,0 +1,824 @@
+// Geographic map - GeoJSON/TopoJSON regions filled by value through a
+// colormap, with points or bubbles on top, pan, zoom and region hover
+
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use crate::export::*;
+use super::*;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::text::PlotLabel;
+
+    pub GeoMap = {{GeoMap}} {
+        width: Fill,
+        height: Fill,
+        label: <PlotLabel> {}
+    }
+}
+
+/// A press and release closer than this (pixels) count as a click
+const CLICK_SLOP: f64 = 4.0;
+
+/// Share of the map area the fitted shapes fill
+const FIT_MARGIN: f64 = 0.95;
+
+/// Zoom limits relative to the fitted view
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 64.0;
+
+const COLORBAR_WIDTH: f64 = 12.0;
+
+/// Offset of the tooltip from the pointer
+const TOOLTIP_OFFSET: f64 = 12.0;
+
+/// A marker at a longitude and latitude, sized like a bubble chart bubble
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeoPoint {
+    pub lon: f64,
+    pub lat: f64,
+    pub size: f64,
+    pub color: Option<Vec4>,
+    pub label: Option<String>,
+}
+
+impl GeoPoint {
+    pub fn new(lon: f64, lat: f64) -> Self {
+        Self { lon, lat, size: 1.0, color: None, label: None }
+    }
+
+    pub fn with_size(mut self, size: f64) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+/// Actions emitted by the map
+#[derive(Clone, Debug, DefaultNone)]
+pub enum GeoMapAction {
+    None,
+    /// A region was clicked; `key` is what values are matched against
+    RegionClicked { index: usize, key: Option<String> },
+    /// A point was clicked
+    PointClicked { index: usize },
+}
+
+/// A feature's rings and fill triangles in projected coordinates
+#[derive(Clone, Debug, Default)]
+struct ProjectedRegion {
+    rings: Vec<Vec<(f64, f64)>>,
+    triangles: Vec<[(f64, f64); 3]>,
+    bounds: Option<((f64, f64), (f64, f64))>,
+}
+
+/// Part of convex `poly` inside `rect` (Sutherland–Hodgman)
+fn clip_to_rect(poly: &[DVec2], rect: Rect) -> Vec<DVec2> {
+    let (x0, y0) = (rect.pos.x, rect.pos.y);
+    let (x1, y1) = (x0 + rect.size.x, y0 + rect.size.y);
+    // (clips x rather than y, limit, keeps the side above the limit)
+    let edges = [(true, x0, true), (true, x1, false), (false, y0, true), (false, y1, false)];
+    let mut out = poly.to_vec();
+    for (along_x, limit, keep_above) in edges {
+        if out.is_empty() {
+            break;
+        }
+        let coord = |p: DVec2| if along_x { p.x } else { p.y };
+        let inside = |p: DVec2| if keep_above { coord(p) >= limit } else { coord(p) <= limit };
+        let input = std::mem::take(&mut out);
+        for (i, &p) in input.iter().enumerate() {
+            let q = input[(i + 1) % input.len()];
+            if inside(p) {
+                out.push(p);
+            }
+            if inside(p) != inside(q) {
+                let t = (limit - coord(p)) / (coord(q) - coord(p));
+                out.push(p + (q - p) * t);
+            }
+        }
+    }
+    out
+}
+
+/// Part of segment `a`–`b` inside `rect` (Liang–Barsky)
+fn clip_segment(a: DVec2, b: DVec2, rect: Rect) -> Option<(DVec2, DVec2)> {
+    let d = b - a;
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    let checks = [
+        (-d.x, a.x - rect.pos.x),
+        (d.x, rect.pos.x + rect.size.x - a.x),
+        (-d.y, a.y - rect.pos.y),
+        (d.y, rect.pos.y + rect.size.y - a.y),
+    ];
+    for (p, q) in checks {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+    (t0 <= t1).then(|| (a + d * t0, a + d * t1))
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct GeoMap {
+    #[deref] #[live] view: View,
+    #[live] draw_line: DrawPlotLine,
+    #[live] draw_triangle: DrawTriangle,
+    #[live] draw_bubble: DrawPlotPointGradient,
+    #[live] draw_bar: DrawPlotBar,
+    #[live] draw_fill: DrawPlotFill,
+    #[live] label: PlotLabel,
+    #[rust] title: String,
+    #[rust] geo: GeoData,
+    /// File the shapes were last loaded from
+    #[rust] source_path: Option<String>,
+    #[rust] projection: GeoProjection,
+    /// Property holding each region's key; the id or "name" when None
+    #[rust] key_property: Option<String>,
+    #[rust] values: Vec<(String, f64)>,
+    #[rust] colormap: Colormap,
+    /// Colormap limits; the value range when None
+    #[rust] value_range: Option<(f64, f64)>,
+    #[rust(vec4(0.88, 0.89, 0.91, 1.0))] missing_color: Vec4,
+    #[rust(vec4(1.0, 1.0, 1.0, 1.0))] border_color: Vec4,
+    #[rust(true)] show_colorbar: bool,
+    #[rust] points: Vec<GeoPoint>,
+    #[rust(4.0)] min_point_radius: f64,
+    #[rust(20.0)] max_point_radius: f64,
+    #[rust(true)] use_gradient: bool,
+
+    /// Projected shapes and values, rebuilt when data or projection change
+    #[rust] regions: Vec<ProjectedRegion>,
+    #[rust] region_values: Vec<Option<f64>>,
+    #[rust] projected_points: Vec<(f64, f64)>,
+    #[rust(true)] dirty: bool,
+
+    #[rust(1.0)] zoom: f64,
+    #[rust] pan: DVec2,
+    /// Screen transform of the last draw: projected center, its pixel
+    /// position and pixels per projected unit
+    #[rust] plane_center: (f64, f64),
+    #[rust] origin: DVec2,
+    #[rust] scale: f64,
+    #[rust] map_rect: Rect,
+    #[rust] hovered: Option<usize>,
+    #[rust] hovered_point: Option<usize>,
+    #[rust] pointer: DVec2,
+    #[rust] press: Option<(DVec2, DVec2)>,
+    #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
+}
+
+impl GeoMap {
+    /// Replace the shapes; the same shapes again keep the projected cache
+    pub fn set_geo_data(&mut self, geo: GeoData) {
+        if self.source_path.is_none() && geo == self.geo {
+            return;
+        }
+        self.geo = geo;
+        self.source_path = None;
+        self.dirty = true;
+        self.hovered = None;
+    }
+
+    /// Load GeoJSON or TopoJSON from a file. Loading the same path again
+    /// keeps the shapes already loaded; a file that fails leaves the map
+    /// empty and is not read again until the path changes.
+    pub fn load_file(&mut self, path: &str) -> Result<(), String> {
+        if self.source_path.as_deref() == Some(path) {
+            return Ok(());
+        }
+        let loaded = GeoData::load(path);
+        let error = loaded.as_ref().err().cloned();
+        self.set_geo_data(loaded.unwrap_or_default());
+        self.source_path = Some(path.to_string());
+        error.map_or(Ok(()), Err)
+    }
+
+    pub fn set_projection(&mut self, projection: GeoProjection) {
+        if projection != self.projection {
+            self.projection = projection;
+            self.dirty = true;
+        }
+    }
+
+    /// Match values against this property instead of the id or "name"
+    pub fn set_key_property(&mut self, property: Option<String>) {
+        if property != self.key_property {
+            self.key_property = property;
+            self.dirty = true;
+        }
+    }
+
+    /// Values by region key; keys match exactly, or else ignoring case
+    pub fn set_values(&mut self, values: Vec<(String, f64)>) {
+        if values != self.values {
+            self.values = values;
+            self.dirty = true;
+        }
+    }
+
+    pub fn set_colormap(&mut self, colormap: Colormap) {
+        self.colormap = colormap;
+    }
+
+    pub fn set_value_range(&mut self, range: Option<(f64, f64)>) {
+        self.value_range = range;
+    }
+
+    /// Fill of regions without a value
+    pub fn set_missing_color(&mut self, color: Vec4) {
+        self.missing_color = color;
+    }
+
+    pub fn set_border_color(&mut self, color: Vec4) {
+        self.border_color = color;
+    }
+
+    pub fn set_show_colorbar(&mut self, show: bool) {
+        self.show_colorbar = show;
+    }
+
+    pub fn set_points(&mut self, points: Vec<GeoPoint>) {
+        if points != self.points {
+            self.points = points;
+            self.dirty = true;
+            self.hovered_point = None;
+        }
+    }
+
+    pub fn set_point_radius_range(&mut self, min: f64, max: f64) {
+        self.min_point_radius = min;
+        self.max_point_radius = max;
+    }
+
+    pub fn set_use_gradient(&mut self, use_gradient: bool) {
+        self.use_gradient = use_gradient;
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    /// Undo pan and zoom
+    pub fn reset_view(&mut self) {
+        self.zoom = 1.0;
+        self.pan = dvec2(0.0, 0.0);
+    }
+
+    pub fn clear(&mut self) {
+        self.geo = GeoData::default();
+        self.source_path = None;
+        self.values.clear();
+        self.points.clear();
+        self.dirty = true;
+    }
+
+    pub fn redraw(&mut self, cx: &mut Cx) {
+        self.view.redraw(cx);
+        self.area.redraw(cx);
+    }
+
+    /// Project and triangulate the shapes and match values to regions
+    fn rebuild(&mut self) {
+        let projection = self.projection.fitted(self.geo.bounds());
+        self.regions = self.geo.features.iter().map(|feature| project_region(feature, projection)).collect();
+        self.region_values = match_region_values(&self.geo.features, self.key_property.as_deref(), &self.values);
+        self.projected_points = self.points.iter().map(|p| projection.project(p.lon, p.lat)).collect();
+        self.dirty = false;
+    }
+
+    fn value_range(&self) -> (f64, f64) {
+        self.value_range.unwrap_or_else(|| {
+            self.region_values.iter().flatten().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)))
+        })
+    }
+
+    fn to_pixel(&self, (x, y): (f64, f64)) -> DVec2 {
+        self.origin + dvec2(x - self.plane_center.0, self.plane_center.1 - y) * self.scale
+    }
+
+    fn to_plane(&self, pos: DVec2) -> (f64, f64) {
+        let offset = (pos - self.origin) * (1.0 / self.scale);
+        (self.plane_center.0 + offset.x, self.plane_center.1 - offset.y)
+    }
+
+    /// Fit the projected shapes (or points when there are none) into the
+    /// map area, then apply pan and zoom
+    fn fit_view(&mut self) {
+        let bounds = point_bounds(self.regions.iter().flat_map(|r| r.rings.iter().flatten()).copied())
+            .or_else(|| point_bounds(self.projected_points.iter().copied()));
+        let (center, fit) = fit_plane(bounds, self.map_rect);
+        self.plane_center = center;
+        self.scale = fit * self.zoom;
+        self.origin = self.map_rect.pos + self.map_rect.size * 0.5 + self.pan;
+    }
+
+    fn point_radius(&self, index: usize) -> f64 {
+        let (lo, hi) = self.points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p.size), hi.max(p.size)));
+        let t = if hi > lo { (self.points[index].size - lo) / (hi - lo) } else { 0.5 };
+        self.min_point_radius + t * (self.max_point_radius - self.min_point_radius)
+    }
+
+    fn point_at(&self, pos: DVec2) -> Option<usize> {
+        // Topmost (last drawn) first
+        (0..self.points.len().min(self.projected_points.len())).rev().find(|&i| {
+            let center = self.to_pixel(self.projected_points[i]);
+            (pos - center).length() <= self.point_radius(i).max(4.0)
+        })
+    }
+
+    fn region_at(&self, pos: DVec2) -> Option<usize> {
+        if !self.map_rect.contains(pos) {
+            return None;
+        }
+        let (x, y) = self.to_plane(pos);
+        // Later features are drawn on top
+        self.regions.iter().enumerate().rev().find_map(|(i, region)| {
+            let ((x0, x1), (y0, y1)) = region.bounds?;
+            let inside = x >= x0 && x <= x1 && y >= y0 && y <= y1
+                && region_contains(region, (x, y));
+            inside.then_some(i)
+        })
+    }
+
+    fn draw_regions(&mut self, cx: &mut Cx2d) {
+        let rect = self.map_rect;
+        let (vmin, vmax) = self.value_range();
+        let norm = Normalize::new(vmin, vmax);
+        for i in 0..self.regions.len() {
+            let mut color = match self.region_values.get(i).copied().flatten() {
+                Some(value) => self.colormap.sample(norm.normalize(value)),
+                None => self.missing_color,
+            };
+            if self.hovered == Some(i) {
+                color = lighten(color, 0.25);
+            }
+            self.draw_triangle.color = color;
+            for k in 0..self.regions[i].triangles.len() {
+                let [a, b, c] = self.regions[i].triangles[k];
+                let (a, b, c) = (self.to_pixel(a), self.to_pixel(b), self.to_pixel(c));
+                let min = dvec2(a.x.min(b.x).min(c.x), a.y.min(b.y).min(c.y));
+                let max = dvec2(a.x.max(b.x).max(c.x), a.y.max(b.y).max(c.y));
+                if max.x < rect.pos.x || max.y < rect.pos.y || min.x > rect.pos.x + rect.size.x || min.y > rect.pos.y + rect.size.y {
+                    continue;
+                }
+                if rect.contains(min) && rect.contains(max) {
+                    self.draw_triangle.draw_triangle(cx, a, b, c);
+                } else {
+                    let clipped = clip_to_rect(&[a, b, c], rect);
+                    for j in 1..clipped.len().saturating_sub(1) {
+                        self.draw_triangle.draw_triangle(cx, clipped[0], clipped[j], clipped[j + 1]);
+                    }
+                }
+            }
+        }
+
+        // Borders on top of all fills, the hovered region's last and darker
+        let order = (0..self.regions.len()).filter(|&i| self.hovered != Some(i)).chain(self.hovered);
+        for i in order.collect::<Vec<_>>() {
+            let (color, width) = if self.hovered == Some(i) { (vec4(0.2, 0.2, 0.25, 1.0), 1.5) } else { (self.border_color, 0.75) };
+            self.draw_line.color = color;
+            for r in 0..self.regions[i].rings.len() {
+                let n = self.regions[i].rings[r].len();
+                for k in 0..n {
+                    let a = self.to_pixel(self.regions[i].rings[r][k]);
+                    let b = self.to_pixel(self.regions[i].rings[r][(k + 1) % n]);
+                    if let Some((a, b)) = clip_segment(a, b, rect) {
+                        self.draw_line.draw_line(cx, a, b, width);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Points drawn as bubble chart bubbles: gradient or translucent fill,
+    /// outline and a label above
+    fn draw_points(&mut self, cx: &mut Cx2d) {
+        for i in 0..self.points.len() {
+            let center = self.to_pixel(self.projected_points[i]);
+            if !self.map_rect.contains(center) {
+                continue;
+            }
+            let radius = self.point_radius(i);
+            let color = self.points[i].color.unwrap_or_else(|| get_color(0));
+            if self.use_gradient {
+                let (inner, outer) = gradient_pair(color);
+                self.draw_bubble.color = color;
+                self.draw_bubble.draw_point_gradient(cx, center, radius, vec4(inner.x, inner.y, inner.z, 0.9), vec4(outer.x, outer.y, outer.z, 0.85));
+            } else {
+                self.draw_bubble.color = vec4(color.x, color.y, color.z, 0.6);
+                self.draw_bubble.draw_point(cx, center, radius);
+            }
+
+            self.draw_line.color = if self.hovered_point == Some(i) { darken(color, 0.3) } else { color };
+            let segments = 32;
+            for k in 0..segments {
+                let a1 = k as f64 / segments as f64 * std::f64::consts::TAU;
+                let a2 = (k + 1) as f64 / segments as f64 * std::f64::consts::TAU;
+                let p1 = center + dvec2(a1.cos(), a1.sin()) * radius;
+                let p2 = center + dvec2(a2.cos(), a2.sin()) * radius;
+                self.draw_line.draw_line(cx, p1, p2, 1.5);
+            }
+
+            if let Some(text) = self.points[i].label.clone() {
+                self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+                self.label.draw_at(cx, center - dvec2(0.0, radius + 5.0), &text, TextAnchor::BottomCenter);
+            }
+        }
+    }
+
+    fn draw_colorbar(&mut self, cx: &mut Cx2d, rect: Rect) {
+        let (vmin, vmax) = self.value_range();
+        if !(vmin.is_finite() && vmax.is_finite()) {
+            return;
+        }
+        let x = rect.pos.x + rect.size.x - 50.0;
+        let top = self.map_rect.pos.y + self.map_rect.size.y * 0.15;
+        let height = self.map_rect.size.y * 0.7;
+        let steps = 50;
+        let step = height / steps as f64;
+        for i in 0..steps {
+            self.draw_bar.color = self.colormap.sample(1.0 - i as f64 / steps as f64);
+            self.draw_bar.draw_bar(cx, Rect { pos: dvec2(x, top + i as f64 * step), size: dvec2(COLORBAR_WIDTH, step + 1.0) });
+        }
+        self.label.set_font_size(9.0);
+        self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
+        let tick = (vmax - vmin) / 2.0;
+        for (t, value) in [(0.0, vmax), (0.5, (vmin + vmax) / 2.0), (1.0, vmin)] {
+            let text = TickFormatter::Auto.format(value, tick);
+            self.label.draw_at(cx, dvec2(x + COLORBAR_WIDTH + 3.0, top + height * t), &text, TextAnchor::MiddleLeft);
+        }
+    }
+
+    fn tooltip_lines(&self) -> Vec<String> {
+        if let Some(i) = self.hovered_point {
+            let Some(point) = self.points.get(i) else { return Vec::new() };
+            let mut lines: Vec<String> = point.label.iter().cloned().collect();
+            lines.push(format!("{:.2}°, {:.2}°", point.lat, point.lon));
+            return lines;
+        }
+        let Some(i) = self.hovered else { return Vec::new() };
+        let Some(feature) = self.geo.features.get(i) else { return Vec::new() };
+        let name = feature.name().unwrap_or("Region").to_string();
+        match self.region_values.get(i).copied().flatten() {
+            Some(value) => vec![name, TickFormatter::Auto.format(value, 0.0)],
+            None => vec![name],
+        }
+    }
+
+    fn draw_tooltip(&mut self, cx: &mut Cx2d) {
+        let lines = self.tooltip_lines();
+        if lines.is_empty() {
+            return;
+        }
+        self.label.set_font_size(10.0);
+        let padding = 6.0;
+        let sizes: Vec<DVec2> = lines.iter().map(|line| self.label.measure(cx, line)).collect();
+        let width = sizes.iter().map(|s| s.x).fold(0.0, f64::max) + padding * 2.0;
+        let height = sizes.iter().map(|s| s.y + 2.0).sum::<f64>() - 2.0 + padding * 2.0;
+
+        // Below-right of the pointer, flipped to stay inside the map
+        let rect = self.map_rect;
+        let mut pos = self.pointer + dvec2(TOOLTIP_OFFSET, TOOLTIP_OFFSET);
+        if pos.x + width > rect.pos.x + rect.size.x {
+            pos.x = self.pointer.x - TOOLTIP_OFFSET - width;
+        }
+        if pos.y + height > rect.pos.y + rect.size.y {
+            pos.y = self.pointer.y - TOOLTIP_OFFSET - height;
+        }
+
+        self.draw_fill.color = vec4(0.7, 0.7, 0.72, 1.0);
+        self.draw_fill.draw_rect(cx, Rect { pos: pos - dvec2(1.0, 1.0), size: dvec2(width + 2.0, height + 2.0) });
+        self.draw_fill.color = vec4(1.0, 1.0, 1.0, 0.95);
+        self.draw_fill.draw_rect(cx, Rect { pos, size: dvec2(width, height) });
+        self.label.set_color(vec4(0.15, 0.15, 0.2, 1.0));
+        let mut y = pos.y + padding;
+        for (line, size) in lines.iter().zip(&sizes) {
+            self.label.draw_at(cx, dvec2(pos.x + padding, y), line, TextAnchor::TopLeft);
+            y += size.y + 2.0;
+        }
+    }
+}
+
+/// A feature's polygons projected and cut into fill triangles
+fn project_region(feature: &GeoFeature, projection: GeoProjection) -> ProjectedRegion {
+    let project = |&(lon, lat): &(f64, f64)| projection.project(lon, lat);
+    let mut region = ProjectedRegion::default();
+    for polygon in &feature.polygons {
+        let exterior: Vec<(f64, f64)> = polygon.exterior.iter().map(project).collect();
+        let holes: Vec<Vec<(f64, f64)>> = polygon.holes.iter().map(|hole| hole.iter().map(project).collect()).collect();
+        region.triangles.extend(triangulate_polygon(&exterior, &holes));
+        region.rings.push(exterior);
+        region.rings.extend(holes);
+    }
+    region.bounds = point_bounds(region.rings.iter().flatten().copied());
+    region
+}
+
+/// Value of each feature by its key, matched exactly or else ignoring
+/// case; None when missing or not finite
+fn match_region_values(features: &[GeoFeature], key_property: Option<&str>, values: &[(String, f64)]) -> Vec<Option<f64>> {
+    features
+        .iter()
+        .map(|feature| {
+            let key = feature.key(key_property)?;
+            values
+                .iter()
+                .find(|(k, _)| k == key)
+                .or_else(|| values.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)))
+                .map(|(_, v)| *v)
+                .filter(|v| v.is_finite())
+        })
+        .collect()
+}
+
+/// Center of projected `bounds` and the pixels per unit that fit them into
+/// `rect`, leaving a margin
+fn fit_plane(bounds: Option<((f64, f64), (f64, f64))>, rect: Rect) -> ((f64, f64), f64) {
+    let ((x0, x1), (y0, y1)) = bounds.unwrap_or(((-1.0, 1.0), (-1.0, 1.0)));
+    let width = (x1 - x0).max(1e-9);
+    let height = (y1 - y0).max(1e-9);
+    let fit = (rect.size.x / width).min(rect.size.y / height) * FIT_MARGIN;
+    (((x0 + x1) / 2.0, (y0 + y1) / 2.0), fit)
+}
+
+fn region_contains(region: &ProjectedRegion, point: (f64, f64)) -> bool {
+    point_in_rings(point, region.rings.iter().map(Vec::as_slice))
+}
+
+impl Widget for GeoMap {
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
+
+        if self.dirty {
+            self.rebuild();
+        }
+        let has_values = self.region_values.iter().any(Option::is_some);
+        if rect.size.x > 0.0 && rect.size.y > 0.0 && (!self.regions.is_empty() || !self.points.is_empty()) {
+            let top = if self.title.is_empty() { 10.0 } else { 36.0 };
+            let right = if self.show_colorbar && has_values { 60.0 } else { 10.0 };
+            self.map_rect = Rect {
+                pos: rect.pos + dvec2(10.0, top),
+                size: dvec2((rect.size.x - 10.0 - right).max(0.0), (rect.size.y - top - 10.0).max(0.0)),
+            };
+            self.fit_view();
+
+            self.draw_regions(cx);
+            self.label.set_font_size(9.0);
+            self.draw_points(cx);
+            if self.show_colorbar && has_values {
+                self.draw_colorbar(cx, rect);
+            }
+            self.draw_tooltip(cx);
+
+            if !self.title.is_empty() {
+                self.label.set_font_size(12.0);
+                self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+                self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + 10.0), &self.title, TextAnchor::TopCenter);
+            }
+        }
+
+        self.capture.end();
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        if self.scale <= 0.0 {
+            return;
+        }
+        match event.hits(cx, self.area) {
+            Hit::FingerDown(fe) => {
+                self.press = Some((fe.abs, self.pan));
+            }
+            Hit::FingerMove(fe) => {
+                if let Some((start, pan)) = self.press {
+                    if (fe.abs - start).length() > CLICK_SLOP {
+                        self.pan = pan + (fe.abs - start);
+                        self.hovered = None;
+                        self.hovered_point = None;
+                        cx.set_cursor(MouseCursor::Move);
+                        self.redraw(cx);
+                    }
+                }
+            }
+            Hit::FingerUp(fe) => {
+                let Some((start, _)) = self.press.take() else { return };
+                if !fe.is_over || (fe.abs - start).length() > CLICK_SLOP {
+                    return;
+                }
+                let uid = self.widget_uid();
+                if let Some(index) = self.point_at(fe.abs) {
+                    cx.widget_action(uid, &scope.path, GeoMapAction::PointClicked { index });
+                } else if let Some(index) = self.region_at(fe.abs) {
+                    let key = self.geo.features.get(index).and_then(|f| f.key(self.key_property.as_deref())).map(str::to_string);
+                    cx.widget_action(uid, &scope.path, GeoMapAction::RegionClicked { index, key });
+                }
+            }
+            Hit::FingerScroll(fe) => {
+                // Zoom about the pointer
+                let factor = if fe.scroll.y > 0.0 { 0.9 } else { 1.1 };
+                let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+                let applied = zoom / self.zoom;
+                self.pan = (self.origin - fe.abs) * applied + fe.abs - (self.origin - self.pan);
+                self.zoom = zoom;
+                self.redraw(cx);
+            }
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => {
+                self.pointer = fe.abs;
+                let point = self.point_at(fe.abs);
+                let region = if point.is_some() { None } else { self.region_at(fe.abs) };
+                cx.set_cursor(if point.is_some() || region.is_some() { MouseCursor::Hand } else { MouseCursor::Default });
+                if point != self.hovered_point || region != self.hovered || point.or(region).is_some() {
+                    self.hovered_point = point;
+                    self.hovered = region;
+                    self.redraw(cx);
+                }
+            }
+            Hit::FingerHoverOut(_) => {
+                cx.set_cursor(MouseCursor::Default);
+                let had_region = self.hovered.take().is_some();
+                let had_point = self.hovered_point.take().is_some();
+                if had_region || had_point {
+                    self.redraw(cx);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl PlotExport for GeoMap {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
+impl GeoMapRef {
+    pub fn set_geo_data(&self, geo: GeoData) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_geo_data(geo); }
+    }
+    pub fn load_file(&self, path: &str) -> Result<(), String> {
+        match self.borrow_mut() {
+            Some(mut inner) => inner.load_file(path),
+            None => Ok(()),
+        }
+    }
+    pub fn set_projection(&self, projection: GeoProjection) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_projection(projection); }
+    }
+    pub fn set_key_property(&self, property: Option<String>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_key_property(property); }
+    }
+    pub fn set_values(&self, values: Vec<(String, f64)>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_values(values); }
+    }
+    pub fn set_colormap(&self, colormap: Colormap) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_colormap(colormap); }
+    }
+    pub fn set_value_range(&self, range: Option<(f64, f64)>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_value_range(range); }
+    }
+    pub fn set_points(&self, points: Vec<GeoPoint>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_points(points); }
+    }
+    pub fn set_show_colorbar(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_show_colorbar(show); }
+    }
+    pub fn set_title(&self, title: impl Into<String>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
+    }
+    pub fn reset_view(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.reset_view(); }
+    }
+    pub fn clear(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.clear(); }
+    }
+    pub fn redraw(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); }
+    }
+
+    /// Index and key of the region that was clicked, if any
+    pub fn region_clicked(&self, actions: &Actions) -> Option<(usize, Option<String>)> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .find_map(|action| match action.cast() {
+                GeoMapAction::RegionClicked { index, key } => Some((index, key)),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(id: &str, name: &str, exterior: GeoRing) -> GeoFeature {
+        GeoFeature {
+            id: Some(id.to_string()),
+            properties: vec![("name".to_string(), name.to_string())],
+            polygons: vec![GeoPolygon { exterior, holes: Vec::new() }],
+        }
+    }
+
+    fn values(pairs: &[(&str, f64)]) -> Vec<(String, f64)> {
+        pairs.iter().map(|&(k, v)| (k.to_string(), v)).collect()
+    }
+
+    #[test]
+    fn test_match_region_values() {
+        let features = vec![
+            feature("FR", "France", vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]),
+            feature("de", "Germany", vec![(1.0, 0.0), (2.0, 0.0), (2.0, 1.0)]),
+            feature("IT", "Italy", vec![(2.0, 0.0), (3.0, 0.0), (3.0, 1.0)]),
+        ];
+        // Ids match exactly first, then ignoring case; bad values are missing
+        let data = values(&[("DE", 2.0), ("FR", 1.0), ("fr", 9.0), ("IT", f64::NAN)]);
+        assert_eq!(match_region_values(&features, None, &data), vec![Some(1.0), Some(2.0), None]);
+        // A key property matches against that property instead
+        let data = values(&[("Italy", 3.0), ("FR", 1.0)]);
+        assert_eq!(match_region_values(&features, Some("name"), &data), vec![None, None, Some(3.0)]);
+    }
+
+    #[test]
+    fn test_project_region() {
+        let mut square = feature("sq", "Square", vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        square.polygons[0].holes.push(vec![(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)]);
+        let region = project_region(&square, GeoProjection::Equirectangular);
+        let projection = GeoProjection::Equirectangular;
+
+        // Exterior and hole are both rings, and both count for hit tests
+        assert_eq!(region.rings.len(), 2);
+        assert_eq!(region.rings[0][2], projection.project(4.0, 4.0));
+        let inside = projection.project(3.0, 3.0);
+        let in_hole = projection.project(1.5, 1.5);
+        assert!(region_contains(&region, inside));
+        assert!(!region_contains(&region, in_hole));
+        let ((x0, x1), (y0, y1)) = region.bounds.unwrap();
+        assert_eq!(((x0, y0), (x1, y1)), (projection.project(0.0, 0.0), projection.project(4.0, 4.0)));
+
+        // Fill triangles cover the square less the hole
+        let area: f64 = region.triangles.iter().map(|&[a, b, c]| ((b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)).abs() / 2.0).sum();
+        let (w, h) = (x1 - x0, y1 - y0);
+        assert!((area - w * h * 15.0 / 16.0).abs() < 1e-9 * w * h);
+    }
+
+    #[test]
+    fn test_fit_plane() {
+        let rect = Rect { pos: dvec2(0.0, 0.0), size: dvec2(400.0, 100.0) };
+        // The taller side limits the scale
+        let (center, fit) = fit_plane(Some(((0.0, 10.0), (-2.0, 2.0))), rect);
+        assert_eq!(center, (5.0, 0.0));
+        assert!((fit - 25.0 * FIT_MARGIN).abs() < 1e-12);
+        // Nothing to fit shows the unit square
+        assert_eq!(fit_plane(None, rect), ((0.0, 0.0), 50.0 * FIT_MARGIN));
+    }
+
+    #[test]
+    fn test_clip_to_rect() {
+        let rect = Rect { pos: dvec2(0.0, 0.0), size: dvec2(10.0, 10.0) };
+        let inside = [dvec2(1.0, 1.0), dvec2(5.0, 1.0), dvec2(1.0, 5.0)];
+        assert_eq!(clip_to_rect(&inside, rect), inside.to_vec());
+        // A triangle over the right edge is cut at x = 10
+        let clipped = clip_to_rect(&[dvec2(5.0, 2.0), dvec2(15.0, 2.0), dvec2(5.0, 8.0)], rect);
+        assert!(clipped.iter().all(|p| p.x <= 10.0 + 1e-12));
+        assert!(clipped.iter().any(|p| (p.x - 10.0).abs() < 1e-12));
+        assert!(clip_to_rect(&[dvec2(20.0, 20.0), dvec2(30.0, 20.0), dvec2(20.0, 30.0)], rect).is_empty());
+    }
+
+    #[test]
+    fn test_clip_segment() {
+        let rect = Rect { pos: dvec2(0.0, 0.0), size: dvec2(10.0, 10.0) };
+        assert_eq!(clip_segment(dvec2(-5.0, 5.0), dvec2(15.0, 5.0), rect), Some((dvec2(0.0, 5.0), dvec2(10.0, 5.0))));
+        assert_eq!(clip_segment(dvec2(2.0, 2.0), dvec2(3.0, 3.0), rect), Some((dvec2(2.0, 2.0), dvec2(3.0, 3.0))));
+        assert_eq!(clip_segment(dvec2(-5.0, -1.0), dvec2(15.0, -1.0), rect), None);
+    }
+}
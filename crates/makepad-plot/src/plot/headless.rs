@@ -0,0 +1,344 @@
+// Window-free line and scatter charts, built straight into a PlotScene
+
+use std::path::Path;
+
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use crate::export::*;
+use super::*;
+
+/// Line or scatter chart rendered without a window or GPU, for reports and
+/// CI. Laid out like `LinePlot`: same axes, palette, title and legend.
+///
+/// ```ignore
+/// HeadlessPlot::new(640.0, 400.0)
+///     .with_title("Latency")
+///     .with_series(Series::new("p50").with_data(x, y))
+///     .save_svg("latency.svg", &ExportOptions::default())?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct HeadlessPlot {
+    pub size: DVec2,
+    pub axes: Axes,
+    pub series: Vec<Series>,
+    pub title: String,
+    pub x_label: String,
+    pub y_label: String,
+    pub legend_position: LegendPosition,
+    /// Connect each series' points; off draws a scatter chart
+    pub show_lines: bool,
+    pub line_width: f64,
+    pub point_radius: f64,
+    pub font_size: f64,
+    x_range: Option<(f64, f64)>,
+    y_range: Option<(f64, f64)>,
+}
+
+impl HeadlessPlot {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            size: dvec2(width, height),
+            axes: Axes::new(),
+            series: Vec::new(),
+            title: String::new(),
+            x_label: String::new(),
+            y_label: String::new(),
+            legend_position: LegendPosition::TopRight,
+            show_lines: true,
+            line_width: 2.0,
+            point_radius: 4.0,
+            font_size: 10.0,
+            x_range: None,
+            y_range: None,
+        }
+    }
+
+    pub fn with_series(mut self, series: Series) -> Self {
+        self.series.push(series);
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_labels(mut self, x_label: impl Into<String>, y_label: impl Into<String>) -> Self {
+        self.x_label = x_label.into();
+        self.y_label = y_label.into();
+        self
+    }
+
+    pub fn with_legend(mut self, position: LegendPosition) -> Self {
+        self.legend_position = position;
+        self
+    }
+
+    /// Markers only, no connecting lines
+    pub fn scatter(mut self) -> Self {
+        self.show_lines = false;
+        self
+    }
+
+    /// Fixed x limits instead of fitting the data
+    pub fn with_x_range(mut self, min: f64, max: f64) -> Self {
+        self.x_range = Some((min, max));
+        self
+    }
+
+    /// Fixed y limits instead of fitting the data
+    pub fn with_y_range(mut self, min: f64, max: f64) -> Self {
+        self.y_range = Some((min, max));
+        self
+    }
+
+    /// Data limits padded by 5% (or a factor of 1.5 on log axes), like `LinePlot`
+    fn fitted_range(axis: &PlotAxis, values: impl Iterator<Item = f64>) -> (f64, f64) {
+        let (mut min, mut max) = (f64::MAX, f64::MIN);
+        for v in values.filter(|v| v.is_finite()) {
+            min = min.min(v);
+            max = max.max(v);
+        }
+        if min > max {
+            return axis.range;
+        }
+        if min == max {
+            return (min - 1.0, max + 1.0);
+        }
+        match axis.scale {
+            ScaleType::Log if min > 0.0 => (min / 1.5, max * 1.5),
+            ScaleType::Log => (min, max),
+            _ => {
+                let pad = (max - min) * 0.05;
+                (min - pad, max + pad)
+            }
+        }
+    }
+
+    /// Lay out the chart as a scene in logical pixels
+    pub fn scene(&self) -> PlotScene {
+        let mut scene = PlotScene::new(self.size);
+        let mut axes = self.axes.clone();
+        axes.x.range = self.x_range.unwrap_or_else(|| {
+            Self::fitted_range(&axes.x, self.series.iter().flat_map(|s| s.points().map(|(x, _)| x)))
+        });
+        axes.y.range = self.y_range.unwrap_or_else(|| {
+            Self::fitted_range(&axes.y, self.series.iter().flat_map(|s| s.points().map(|(_, y)| y)))
+        });
+        if !self.x_label.is_empty() {
+            axes.margins.bottom += self.font_size * 1.5;
+        }
+        axes.update_plot_area(Rect { pos: dvec2(0.0, 0.0), size: self.size });
+        axes.add_to_scene(&mut scene, self.font_size);
+
+        for (idx, series) in self.series.iter().enumerate() {
+            let color = series.color.unwrap_or_else(|| get_color(idx));
+            let points: Vec<DVec2> = series.points()
+                .filter(|(x, y)| x.is_finite() && y.is_finite())
+                .map(|(x, y)| axes.data_to_pixel(x, y))
+                .collect();
+
+            if self.show_lines {
+                let width = series.line_width.unwrap_or(self.line_width);
+                let mut dash_offset = 0.0;
+                for pair in points.windows(2) {
+                    scene.push(ScenePrimitive::Line {
+                        from: pair[0],
+                        to: pair[1],
+                        width,
+                        style: series.line_style,
+                        dash_offset,
+                        color,
+                    });
+                    dash_offset += (pair[1] - pair[0]).length();
+                }
+            }
+
+            let marker = match series.marker_style {
+                MarkerStyle::None if !self.show_lines => MarkerStyle::Circle,
+                style => style,
+            };
+            let radius = series.marker_size.unwrap_or(self.point_radius);
+            for p in &points {
+                if axes.contains(*p) {
+                    scene.marker(*p, radius, marker, color);
+                }
+            }
+        }
+
+        let area = axes.plot_area;
+        let text_color = vec4(0.3, 0.3, 0.3, 1.0);
+        if !self.title.is_empty() {
+            let center_x = (area.left + area.right) / 2.0;
+            scene.text(dvec2(center_x, area.top - 10.0), &self.title, TextAnchor::BottomCenter, self.font_size * 1.2, text_color);
+        }
+        if !self.x_label.is_empty() {
+            let center_x = (area.left + area.right) / 2.0;
+            scene.text(dvec2(center_x, self.size.y - 6.0), &self.x_label, TextAnchor::BottomCenter, self.font_size, text_color);
+        }
+        if !self.y_label.is_empty() {
+            scene.text(dvec2(6.0, area.top - 10.0), &self.y_label, TextAnchor::BottomLeft, self.font_size, text_color);
+        }
+
+        self.legend_into(&mut scene, &axes);
+        scene
+    }
+
+    fn legend_into(&self, scene: &mut PlotScene, axes: &Axes) {
+        if self.legend_position == LegendPosition::None || self.series.is_empty() {
+            return;
+        }
+        let padding = 8.0;
+        let line_height = 16.0;
+        let marker_size = 10.0;
+        let marker_text_gap = 6.0;
+        let legend_height = self.series.len() as f64 * line_height + padding * 2.0;
+        let longest = self.series.iter().map(|s| s.label.chars().count()).max().unwrap_or(0);
+        let legend_width = (padding * 2.0 + marker_size + marker_text_gap + longest as f64 * self.font_size * 0.6).max(100.0);
+
+        let area = axes.plot_area;
+        let (legend_x, legend_y) = match self.legend_position {
+            LegendPosition::TopRight => (area.right - legend_width - 10.0, area.top + 10.0),
+            LegendPosition::TopLeft => (area.left + 10.0, area.top + 10.0),
+            LegendPosition::BottomRight => (area.right - legend_width - 10.0, area.bottom - legend_height - 10.0),
+            LegendPosition::BottomLeft => (area.left + 10.0, area.bottom - legend_height - 10.0),
+            LegendPosition::None => return,
+        };
+
+        let rect = Rect { pos: dvec2(legend_x, legend_y), size: dvec2(legend_width, legend_height) };
+        scene.rect(rect, vec4(0.95, 0.95, 0.95, 0.9));
+        let border = vec4(0.8, 0.8, 0.8, 1.0);
+        let (l, t, r, b) = (legend_x, legend_y, legend_x + legend_width, legend_y + legend_height);
+        scene.line(dvec2(l, t), dvec2(r, t), 1.0, border);
+        scene.line(dvec2(l, b), dvec2(r, b), 1.0, border);
+        scene.line(dvec2(l, t), dvec2(l, b), 1.0, border);
+        scene.line(dvec2(r, t), dvec2(r, b), 1.0, border);
+
+        for (idx, series) in self.series.iter().enumerate() {
+            let color = series.color.unwrap_or_else(|| get_color(idx));
+            let entry_y = legend_y + padding + idx as f64 * line_height + line_height / 2.0;
+            scene.marker(dvec2(legend_x + padding + marker_size / 2.0, entry_y), marker_size / 2.0, MarkerStyle::Circle, color);
+            scene.text(
+                dvec2(legend_x + padding + marker_size + marker_text_gap, entry_y),
+                &series.label,
+                TextAnchor::MiddleLeft,
+                self.font_size,
+                vec4(0.3, 0.3, 0.3, 1.0),
+            );
+        }
+    }
+
+    pub fn to_svg(&self, options: &ExportOptions) -> String {
+        self.scene().to_svg(options)
+    }
+
+    pub fn to_png(&self, options: &ExportOptions) -> Vec<u8> {
+        self.scene().to_png(options)
+    }
+
+    pub fn save_svg(&self, path: impl AsRef<Path>, options: &ExportOptions) -> Result<(), String> {
+        self.scene().save_svg(path, options)
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>, options: &ExportOptions) -> Result<(), String> {
+        self.scene().save_png(path, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series_lines(scene: &PlotScene, color: Vec4) -> usize {
+        scene.primitives.iter()
+            .filter(|p| matches!(p, ScenePrimitive::Line { color: c, width, .. } if *c == color && *width == 2.0))
+            .count()
+    }
+
+    fn markers(scene: &PlotScene, color: Vec4) -> usize {
+        scene.primitives.iter()
+            .filter(|p| matches!(p, ScenePrimitive::Marker { color: c, .. } if *c == color))
+            .count()
+    }
+
+    fn texts(scene: &PlotScene) -> Vec<&str> {
+        scene.primitives.iter()
+            .filter_map(|p| match p {
+                ScenePrimitive::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn two_series() -> HeadlessPlot {
+        HeadlessPlot::new(400.0, 300.0)
+            .with_title("Latency")
+            .with_labels("time", "ms")
+            .with_series(Series::new("p50").with_data(vec![0.0, 1.0, 2.0], vec![1.0, 3.0, 2.0]))
+            .with_series(Series::new("p99").with_data(vec![0.0, 1.0, 2.0, 3.0], vec![4.0, 6.0, f64::NAN, 5.0]))
+    }
+
+    #[test]
+    fn test_line_chart_scene() {
+        let scene = two_series().scene();
+        assert_eq!(scene.size, dvec2(400.0, 300.0));
+
+        // Segments between consecutive finite points; the NaN is dropped
+        assert_eq!(series_lines(&scene, get_color(0)), 2);
+        assert_eq!(series_lines(&scene, get_color(1)), 2);
+        // Series without markers only show their legend marker
+        assert_eq!(markers(&scene, get_color(0)), 1);
+
+        let texts = texts(&scene);
+        for expected in ["Latency", "time", "ms", "p50", "p99"] {
+            assert!(texts.contains(&expected), "missing {:?} in {:?}", expected, texts);
+        }
+    }
+
+    #[test]
+    fn test_scatter_scene() {
+        let scene = two_series().scatter().with_legend(LegendPosition::None).scene();
+        assert_eq!(series_lines(&scene, get_color(0)), 0);
+        assert_eq!(markers(&scene, get_color(0)), 3);
+        assert_eq!(markers(&scene, get_color(1)), 3);
+        assert!(!texts(&scene).contains(&"p50"));
+    }
+
+    #[test]
+    fn test_fitted_range() {
+        let linear = PlotAxis::default();
+        assert_eq!(HeadlessPlot::fitted_range(&linear, [0.0, 10.0, 5.0].into_iter()), (-0.5, 10.5));
+        assert_eq!(HeadlessPlot::fitted_range(&linear, [2.0, 2.0].into_iter()), (1.0, 3.0));
+        assert_eq!(HeadlessPlot::fitted_range(&linear, [f64::NAN].into_iter()), linear.range);
+
+        let log = PlotAxis { scale: ScaleType::Log, ..Default::default() };
+        assert_eq!(HeadlessPlot::fitted_range(&log, [1.5, 15.0].into_iter()), (1.0, 22.5));
+    }
+
+    #[test]
+    fn test_fixed_ranges_place_points() {
+        let plot = HeadlessPlot::new(300.0, 200.0)
+            .with_legend(LegendPosition::None)
+            .with_x_range(0.0, 10.0)
+            .with_y_range(0.0, 10.0)
+            .with_series(Series::new("s").with_data(vec![0.0, 10.0], vec![0.0, 10.0]));
+        let scene = plot.scene();
+        let line = scene.primitives.iter()
+            .find_map(|p| match p {
+                ScenePrimitive::Line { from, to, color, .. } if *color == get_color(0) => Some((*from, *to)),
+                _ => None,
+            })
+            .unwrap();
+
+        let mut axes = plot.axes.clone();
+        axes.x.range = (0.0, 10.0);
+        axes.y.range = (0.0, 10.0);
+        axes.update_plot_area(Rect { pos: dvec2(0.0, 0.0), size: plot.size });
+        let area = axes.plot_area;
+        assert_eq!(line, (dvec2(area.left, area.bottom), dvec2(area.right, area.top)));
+
+        let svg = plot.to_svg(&ExportOptions::default());
+        assert!(svg.contains("<line") && svg.contains("</svg>"));
+    }
+}
@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -75,6 +76,9 @@ pub struct HeatmapChart {
 
     #[rust(30.0)]
     top_margin: f64,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for HeatmapChart {
@@ -84,6 +88,7 @@ impl Widget for HeatmapChart {
 
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.data.is_empty() {
             self.update_plot_area(rect);
             self.draw_cells(cx);
@@ -91,10 +96,21 @@ impl Widget for HeatmapChart {
             self.draw_colorbar(cx, rect);
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 }
 
+impl PlotExport for HeatmapChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl HeatmapChart {
     pub fn set_data(&mut self, data: Vec<Vec<f64>>) {
         self.data = data;
@@ -346,6 +362,9 @@ pub struct Heatmap {
     #[rust] show_values: bool,
     #[rust] min_value: Option<f64>,
     #[rust] max_value: Option<f64>,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Heatmap {
@@ -396,6 +415,7 @@ impl Heatmap {
 impl Widget for Heatmap {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.data.is_empty() {
             let padding_left = 60.0;
@@ -429,7 +449,7 @@ impl Widget for Heatmap {
                         let color = self.colormap.sample(normalized);
 
                         self.draw_fill.color = color;
-                        self.draw_fill.draw_abs(cx, Rect {
+                        self.draw_fill.draw_rect(cx, Rect {
                             pos: dvec2(x, y),
                             size: dvec2(cell_width - 1.0, cell_height - 1.0),
                         });
@@ -473,6 +493,7 @@ impl Widget for Heatmap {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -481,6 +502,16 @@ impl Widget for Heatmap {
     }
 }
 
+impl PlotExport for Heatmap {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl Heatmap {
     pub fn redraw(&mut self, cx: &mut Cx) {
         self.view.redraw(cx);
@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -54,6 +55,9 @@ pub struct HexbinChart {
     #[rust] color_high: Vec4,
     #[rust] title: String,
     #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl HexbinChart {
@@ -187,6 +191,7 @@ impl HexbinChart {
 impl Widget for HexbinChart {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 10.0 && rect.size.y > 10.0 {
             let padding = 30.0;
@@ -221,12 +226,23 @@ impl Widget for HexbinChart {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
     fn handle_event(&mut self, _cx: &mut Cx, _event: &Event, _scope: &mut Scope) {}
 }
 
+impl PlotExport for HexbinChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl HexbinChartRef {
     pub fn set_data(&self, points: Vec<HexbinPoint>) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_data(points); }
@@ -249,23 +265,30 @@ impl HexbinChartRef {
 
 pub struct SankeyNode {
     pub name: String,
-    pub layer: usize,
+    /// Fixed layer (column); automatic longest-path layering when None
+    pub layer: Option<usize>,
+    /// Size of a node without links; linked nodes are sized by their flow
     pub value: f64,
     pub color: Vec4,
-    // Layout computed values
-    y: f64,
-    height: f64,
 }
 
 impl SankeyNode {
     pub fn new(name: impl Into<String>, layer: usize, value: f64, color: Vec4) -> Self {
         Self {
             name: name.into(),
-            layer,
+            layer: Some(layer),
             value,
             color,
-            y: 0.0,
-            height: 0.0,
+        }
+    }
+
+    /// Node placed in a layer from the links around it
+    pub fn auto(name: impl Into<String>, color: Vec4) -> Self {
+        Self {
+            name: name.into(),
+            layer: None,
+            value: 0.0,
+            color,
         }
     }
 }
@@ -275,9 +298,6 @@ pub struct SankeyLink {
     pub source: usize,
     pub target: usize,
     pub value: f64,
-    // Layout computed values
-    source_y: f64,
-    target_y: f64,
 }
 
 impl SankeyLink {
@@ -286,12 +306,13 @@ impl SankeyLink {
             source,
             target,
             value,
-            source_y: 0.0,
-            target_y: 0.0,
         }
     }
 }
 
+/// Smallest horizontal run of a circular link beyond its nodes
+const LOOP_REACH: f64 = 12.0;
+
 #[derive(Live, LiveHook, Widget)]
 pub struct SankeyDiagram {
     #[redraw] #[live] draw_bg: DrawQuad,
@@ -305,140 +326,216 @@ pub struct SankeyDiagram {
     #[rust] links: Vec<SankeyLink>,
     #[rust] title: String,
     #[rust] area: Area,
+
+    #[rust(10.0)]
+    node_padding: f64,
+    #[rust(24.0)]
+    node_width: f64,
+    #[rust(24)]
+    iterations: usize,
+
+    /// Node under the pointer; its upstream and downstream flows are highlighted
+    #[rust] hovered: Option<usize>,
+    /// Node rectangles of the last draw, for hover testing
+    #[rust] node_rects: Vec<Rect>,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl SankeyDiagram {
     pub fn set_data(&mut self, nodes: Vec<SankeyNode>, links: Vec<SankeyLink>) {
         self.nodes = nodes;
         self.links = links;
-        self.compute_layout();
+        if self.hovered.is_some_and(|i| i >= self.nodes.len()) {
+            self.hovered = None;
+        }
     }
 
     pub fn set_title(&mut self, title: impl Into<String>) {
         self.title = title.into();
     }
 
-    fn compute_layout(&mut self) {
-        if self.nodes.is_empty() { return; }
+    /// Vertical gap between nodes in a column, in pixels
+    pub fn set_node_padding(&mut self, padding: f64) {
+        self.node_padding = padding.max(0.0);
+    }
+
+    pub fn set_node_width(&mut self, width: f64) {
+        self.node_width = width.max(1.0);
+    }
 
-        // Calculate incoming totals
-        let mut incoming_totals: Vec<f64> = vec![0.0; self.nodes.len()];
-        for link in &self.links {
-            incoming_totals[link.target] += link.value;
-        }
+    /// Relaxation passes used to straighten links
+    pub fn set_iterations(&mut self, iterations: usize) {
+        self.iterations = iterations;
+    }
 
-        // Update node values from incoming
-        for i in 0..self.nodes.len() {
-            if self.nodes[i].layer > 0 {
-                self.nodes[i].value = incoming_totals[i];
-            }
-        }
+    fn compute_layout(&self, height: f64) -> SankeyLayout {
+        let layers: Vec<Option<usize>> = self.nodes.iter().map(|n| n.layer).collect();
+        let values: Vec<f64> = self.nodes.iter().map(|n| n.value).collect();
+        let links: Vec<(usize, usize, f64)> = self.links.iter().map(|l| (l.source, l.target, l.value)).collect();
+        let options = SankeyLayoutOptions {
+            height,
+            node_padding: self.node_padding,
+            iterations: self.iterations,
+            ..Default::default()
+        };
+        sankey_layout(self.nodes.len(), &layers, &values, &links, options)
+    }
 
-        let max_layer = self.nodes.iter().map(|n| n.layer).max().unwrap_or(0);
-
-        // Layout each layer
-        for layer in 0..=max_layer {
-            let layer_nodes: Vec<usize> = self.nodes.iter()
-                .enumerate()
-                .filter(|(_, n)| n.layer == layer)
-                .map(|(i, _)| i)
-                .collect();
-
-            let total_value: f64 = layer_nodes.iter()
-                .map(|&i| if layer == 0 { self.nodes[i].value } else { incoming_totals[i] })
-                .sum();
-
-            let mut y = 0.0;
-            let gap_fraction = 0.08;
-
-            for &idx in &layer_nodes {
-                let node_value = if layer == 0 { self.nodes[idx].value } else { incoming_totals[idx] };
-                let height = if total_value > 0.0 {
-                    node_value / total_value * (1.0 - gap_fraction * (layer_nodes.len() - 1) as f64)
-                } else { 0.0 };
-                self.nodes[idx].y = y;
-                self.nodes[idx].height = height;
-                y += height + gap_fraction;
+    /// Links on some path into or out of `node`
+    fn connected_links(&self, node: usize) -> Vec<bool> {
+        let mut on_path = vec![false; self.links.len()];
+        for downstream in [true, false] {
+            let mut seen = vec![false; self.nodes.len()];
+            let mut stack = vec![node];
+            while let Some(current) = stack.pop() {
+                if std::mem::replace(&mut seen[current], true) {
+                    continue;
+                }
+                for (k, link) in self.links.iter().enumerate() {
+                    let (from, to) = if downstream { (link.source, link.target) } else { (link.target, link.source) };
+                    if from == current && to < self.nodes.len() {
+                        on_path[k] = true;
+                        stack.push(to);
+                    }
+                }
             }
         }
+        on_path
+    }
+}
 
-        // Compute source totals
-        let source_totals: Vec<f64> = self.nodes.iter().enumerate().map(|(i, node)| {
-            if node.layer == 0 { node.value } else { incoming_totals[i] }
-        }).collect();
-
-        // Compute link positions
-        let mut source_offsets: Vec<f64> = vec![0.0; self.nodes.len()];
-        let mut target_offsets: Vec<f64> = vec![0.0; self.nodes.len()];
-
-        for link in &mut self.links {
-            let source_idx = link.source;
-            let target_idx = link.target;
-
-            link.source_y = self.nodes[source_idx].y + source_offsets[source_idx];
-            link.target_y = self.nodes[target_idx].y + target_offsets[target_idx];
-
-            let source_total = source_totals[source_idx];
-            let target_total = incoming_totals[target_idx];
-
-            if source_total > 0.0 {
-                source_offsets[source_idx] += link.value / source_total * self.nodes[source_idx].height;
-            }
-            if target_total > 0.0 {
-                target_offsets[target_idx] += link.value / target_total * self.nodes[target_idx].height;
+/// Band of constant `width` along `points`, mitred at the corners
+fn draw_band(cx: &mut Cx2d, draw: &mut DrawTriangle, points: &[DVec2], width: f64) {
+    let half = width / 2.0;
+    let normal = |a: DVec2, b: DVec2| {
+        let d = b - a;
+        let len = d.length();
+        if len > 0.0 { dvec2(-d.y / len, d.x / len) } else { dvec2(0.0, 0.0) }
+    };
+    let offsets: Vec<DVec2> = (0..points.len())
+        .map(|i| {
+            let before = if i > 0 { normal(points[i - 1], points[i]) } else { normal(points[i], points[i + 1]) };
+            let after = if i + 1 < points.len() { normal(points[i], points[i + 1]) } else { before };
+            let sum = before + after;
+            let len = sum.length();
+            if len < 1e-9 {
+                return before * half;
             }
+            let miter = sum * (1.0 / len);
+            let cos = (miter.x * after.x + miter.y * after.y).max(0.25);
+            miter * (half / cos)
+        })
+        .collect();
+    for i in 0..points.len().saturating_sub(1) {
+        let (a, b) = (points[i], points[i + 1]);
+        let (oa, ob) = (offsets[i], offsets[i + 1]);
+        draw.draw_triangle(cx, a - oa, b - ob, b + ob);
+        draw.draw_triangle(cx, a - oa, b + ob, a + oa);
+    }
+}
+
+/// Polyline through `corners` with each inner corner rounded to `radius`
+fn rounded_path(corners: &[DVec2], radius: f64) -> Vec<DVec2> {
+    let mut points = Vec::new();
+    for (i, &corner) in corners.iter().enumerate() {
+        if i == 0 || i + 1 == corners.len() {
+            points.push(corner);
+            continue;
+        }
+        let (before, after) = (corners[i - 1] - corner, corners[i + 1] - corner);
+        let r = radius.min(before.length() / 2.0).min(after.length() / 2.0);
+        if r <= 0.0 {
+            points.push(corner);
+            continue;
+        }
+        let start = corner + before * (r / before.length());
+        let end = corner + after * (r / after.length());
+        for step in 0..=6 {
+            let t = step as f64 / 6.0;
+            points.push(start * ((1.0 - t) * (1.0 - t)) + corner * (2.0 * t * (1.0 - t)) + end * (t * t));
         }
     }
+    points
+}
+
+fn with_alpha(color: Vec4, alpha: f32) -> Vec4 {
+    vec4(color.x, color.y, color.z, alpha)
 }
 
 impl Widget for SankeyDiagram {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
-        let rect = cx.walk_turtle(walk);
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
+        self.node_rects.clear();
 
         if rect.size.x > 10.0 && rect.size.y > 10.0 && !self.nodes.is_empty() {
             let padding = 30.0;
-            let chart_x = rect.pos.x + padding;
             let chart_y = rect.pos.y + 30.0;
-            let chart_width = rect.size.x - padding * 2.0;
             let chart_height = rect.size.y - padding - 40.0;
-
-            if chart_width <= 0.0 || chart_height <= 0.0 { return DrawStep::done(); }
-
-            let max_layer = self.nodes.iter().map(|n| n.layer).max().unwrap_or(0);
-            let node_width = 24.0;
-            let layer_spacing = if max_layer > 0 {
-                (chart_width - node_width) / max_layer as f64
+            let layout = self.compute_layout(chart_height);
+
+            // Loops need room to turn beside the outermost columns
+            let loop_room = layout
+                .links
+                .iter()
+                .filter(|l| l.circular)
+                .map(|l| LOOP_REACH + l.width / 2.0 + (l.lane - layout.nodes_bottom))
+                .fold(0.0, f64::max);
+            let chart_x = rect.pos.x + padding + loop_room;
+            let chart_width = rect.size.x - padding * 2.0 - loop_room * 2.0;
+
+            if chart_width <= 0.0 || chart_height <= 0.0 { self.capture.end(); return DrawStep::done(); }
+
+            let node_width = self.node_width;
+            let layer_spacing = if layout.layer_count > 1 {
+                (chart_width - node_width) / (layout.layer_count - 1) as f64
             } else { chart_width };
+            let node_x = |layer: usize| chart_x + layer as f64 * layer_spacing;
 
-            // Precompute totals
-            let mut incoming_totals: Vec<f64> = vec![0.0; self.nodes.len()];
-            for link in &self.links {
-                incoming_totals[link.target] += link.value;
-            }
-
-            let source_totals: Vec<f64> = self.nodes.iter().enumerate().map(|(i, node)| {
-                if node.layer == 0 { node.value } else { incoming_totals[i] }
-            }).collect();
+            let highlight = self.hovered.map(|node| self.connected_links(node));
+            let link_alpha = |k: usize| match &highlight {
+                Some(on_path) if on_path[k] => 0.75,
+                Some(_) => 0.12,
+                None => 0.55,
+            };
 
             // Draw links
-            for link in &self.links {
-                let source = &self.nodes[link.source];
-                let target = &self.nodes[link.target];
-
-                let sx = chart_x + source.layer as f64 * layer_spacing + node_width;
-                let sy = chart_y + link.source_y * chart_height;
-                let tx = chart_x + target.layer as f64 * layer_spacing;
-                let ty = chart_y + link.target_y * chart_height;
-
-                let source_total = source_totals[link.source];
-                let target_total = incoming_totals[link.target];
-
-                let link_height_source = if source_total > 0.0 {
-                    (link.value / source_total) * source.height * chart_height
-                } else { 0.0 };
-                let link_height_target = if target_total > 0.0 {
-                    (link.value / target_total) * target.height * chart_height
-                } else { 0.0 };
+            for (k, link) in self.links.iter().enumerate() {
+                let placed = layout.links[k];
+                if placed.width <= 0.0 {
+                    continue;
+                }
+                let source = &layout.nodes[link.source];
+                let target = &layout.nodes[link.target];
+                let source_color = self.nodes[link.source].color;
+                let target_color = self.nodes[link.target].color;
+                let alpha = link_alpha(k);
+
+                let sx = node_x(source.layer) + node_width;
+                let sy = chart_y + placed.y0;
+                let tx = node_x(target.layer);
+                let ty = chart_y + placed.y1;
+                let h = placed.width;
+
+                if placed.circular {
+                    // Out to the right, along the lane below the nodes, back in from the left
+                    let reach = LOOP_REACH + h / 2.0 + (placed.lane - layout.nodes_bottom);
+                    let lane = chart_y + placed.lane;
+                    let corners = [
+                        dvec2(sx, sy + h / 2.0),
+                        dvec2(sx + reach, sy + h / 2.0),
+                        dvec2(sx + reach, lane),
+                        dvec2(tx - reach, lane),
+                        dvec2(tx - reach, ty + h / 2.0),
+                        dvec2(tx, ty + h / 2.0),
+                    ];
+                    self.draw_triangle.color = with_alpha(source_color, alpha);
+                    let path = rounded_path(&corners, reach.min(h + 8.0));
+                    draw_band(cx, &mut self.draw_triangle, &path, h);
+                    continue;
+                }
 
                 // Draw curved flow
                 let segments = 24;
@@ -454,44 +551,97 @@ impl Widget for SankeyDiagram {
                     let y1_top = sy + (ty - sy) * ease1;
                     let y2_top = sy + (ty - sy) * ease2;
 
-                    let h1 = link_height_source + (link_height_target - link_height_source) * ease1;
-                    let h2 = link_height_source + (link_height_target - link_height_source) * ease2;
-
                     let t_color = t1 as f32;
                     let color = vec4(
-                        source.color.x + (target.color.x - source.color.x) * t_color,
-                        source.color.y + (target.color.y - source.color.y) * t_color,
-                        source.color.z + (target.color.z - source.color.z) * t_color,
-                        0.55,
+                        source_color.x + (target_color.x - source_color.x) * t_color,
+                        source_color.y + (target_color.y - source_color.y) * t_color,
+                        source_color.z + (target_color.z - source_color.z) * t_color,
+                        alpha,
                     );
 
                     self.draw_triangle.color = color;
-                    self.draw_triangle.draw_triangle(cx, dvec2(x1, y1_top), dvec2(x2, y2_top), dvec2(x2, y2_top + h2));
-                    self.draw_triangle.draw_triangle(cx, dvec2(x1, y1_top), dvec2(x2, y2_top + h2), dvec2(x1, y1_top + h1));
+                    self.draw_triangle.draw_triangle(cx, dvec2(x1, y1_top), dvec2(x2, y2_top), dvec2(x2, y2_top + h));
+                    self.draw_triangle.draw_triangle(cx, dvec2(x1, y1_top), dvec2(x2, y2_top + h), dvec2(x1, y1_top + h));
                 }
             }
 
             // Draw nodes
-            for node in &self.nodes {
-                let x = chart_x + node.layer as f64 * layer_spacing;
-                let y = chart_y + node.y * chart_height;
-                let height = node.height * chart_height;
+            let lit: Option<Vec<bool>> = highlight.as_ref().map(|on_path| {
+                let mut lit = vec![false; self.nodes.len()];
+                for (link, _) in self.links.iter().zip(on_path).filter(|(_, &on)| on) {
+                    if link.source < lit.len() && link.target < lit.len() {
+                        lit[link.source] = true;
+                        lit[link.target] = true;
+                    }
+                }
+                if let Some(node) = self.hovered {
+                    lit[node] = true;
+                }
+                lit
+            });
+            for (i, placed) in layout.nodes.iter().enumerate() {
+                let x = node_x(placed.layer);
+                let y = chart_y + placed.y0;
+                let height = placed.y1 - placed.y0;
+                let node_rect = Rect { pos: dvec2(x, y), size: dvec2(node_width, height) };
+                self.node_rects.push(node_rect);
+                if height <= 0.0 {
+                    continue;
+                }
 
-                self.draw_triangle.color = node.color;
+                let dimmed = lit.as_ref().is_some_and(|lit| !lit[i]);
+                let color = self.nodes[i].color;
+                self.draw_triangle.color = if dimmed { with_alpha(color, 0.35) } else { color };
                 self.draw_triangle.draw_triangle(cx, dvec2(x, y), dvec2(x + node_width, y), dvec2(x + node_width, y + height));
                 self.draw_triangle.draw_triangle(cx, dvec2(x, y), dvec2(x + node_width, y + height), dvec2(x, y + height));
+
+                // Names outside the node, facing into the diagram
+                let last = layout.layer_count > 1 && placed.layer + 1 == layout.layer_count;
+                let (pos, anchor) = if last {
+                    (dvec2(x - 6.0, y + height / 2.0), TextAnchor::MiddleRight)
+                } else {
+                    (dvec2(x + node_width + 6.0, y + height / 2.0), TextAnchor::MiddleLeft)
+                };
+                self.label.draw_text.color = if dimmed { vec4(0.4, 0.4, 0.4, 0.5) } else { vec4(0.2, 0.2, 0.2, 1.0) };
+                let name = self.nodes[i].name.clone();
+                self.label.draw_at(cx, pos, &name, anchor);
             }
 
             // Draw title
             if !self.title.is_empty() {
+                self.label.draw_text.color = vec4(0.2, 0.2, 0.2, 1.0);
                 self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + 15.0), &self.title, TextAnchor::TopCenter);
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
-    fn handle_event(&mut self, _cx: &mut Cx, _event: &Event, _scope: &mut Scope) {}
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, _scope: &mut Scope) {
+        let hovered = match event.hits(cx, self.area) {
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => {
+                self.node_rects.iter().position(|rect| rect.size.y > 0.0 && rect.contains(fe.abs))
+            }
+            Hit::FingerHoverOut(_) => None,
+            _ => return,
+        };
+        if hovered != self.hovered {
+            self.hovered = hovered;
+            cx.set_cursor(if hovered.is_some() { MouseCursor::Hand } else { MouseCursor::Default });
+            self.area.redraw(cx);
+        }
+    }
+}
+
+impl PlotExport for SankeyDiagram {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
 }
 
 impl SankeyDiagramRef {
@@ -501,4 +651,13 @@ impl SankeyDiagramRef {
     pub fn set_title(&self, title: impl Into<String>) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
     }
+    pub fn set_node_padding(&self, padding: f64) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_node_padding(padding); }
+    }
+    pub fn set_node_width(&self, width: f64) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_node_width(width); }
+    }
+    pub fn set_iterations(&self, iterations: usize) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_iterations(iterations); }
+    }
 }
@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -52,37 +53,41 @@ pub struct HistogramChart {
     bins: Vec<HistogramBin>,
 
     #[rust]
-    num_bins: Option<usize>,
+    bin_rule: BinRule,
 
+    /// Density estimate over the bars, scaled to counts
     #[rust]
-    plot_area: PlotArea,
+    density: Vec<(f64, f64)>,
 
     #[rust]
-    title: String,
+    show_density: bool,
 
     #[rust]
-    x_label: String,
+    bandwidth: Bandwidth,
 
     #[rust]
-    y_label: String,
+    kernel: Kernel,
 
-    #[rust(true)]
-    show_grid: bool,
+    #[rust]
+    show_rug: bool,
 
     #[rust]
-    bar_color: Option<Vec4>,
+    axes: Axes,
 
-    #[rust(50.0)]
-    left_margin: f64,
+    #[rust]
+    title: String,
 
-    #[rust(30.0)]
-    bottom_margin: f64,
+    #[rust]
+    x_label: String,
 
-    #[rust(20.0)]
-    right_margin: f64,
+    #[rust]
+    y_label: String,
+
+    #[rust]
+    bar_color: Option<Vec4>,
 
-    #[rust(30.0)]
-    top_margin: f64,
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for HistogramChart {
@@ -92,18 +97,32 @@ impl Widget for HistogramChart {
 
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.bins.is_empty() {
-            self.update_plot_area(rect);
-            self.draw_grid(cx);
-            self.draw_axes(cx);
+            self.update_axes(rect);
+            self.axes.draw_grid(cx, &mut self.draw_line);
+            self.axes.draw_axes(cx, &mut self.draw_line);
             self.draw_bars(cx);
+            self.draw_density(cx);
+            self.draw_rug(cx);
             self.draw_labels(cx);
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 }
 
+impl PlotExport for HistogramChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl HistogramChart {
     pub fn set_values(&mut self, values: Vec<f64>) {
         self.values = values;
@@ -111,12 +130,38 @@ impl HistogramChart {
     }
 
     pub fn set_num_bins(&mut self, num_bins: usize) {
-        self.num_bins = Some(num_bins);
+        self.set_bin_rule(BinRule::Count(num_bins));
+    }
+
+    /// How the bin count is chosen; Sturges' rule by default
+    pub fn set_bin_rule(&mut self, rule: BinRule) {
+        self.bin_rule = rule;
         if !self.values.is_empty() {
             self.compute_bins();
         }
     }
 
+    /// Overlay a kernel density estimate, scaled to the bar heights
+    pub fn set_show_density(&mut self, show: bool) {
+        self.show_density = show;
+        self.compute_density();
+    }
+
+    pub fn set_bandwidth(&mut self, bandwidth: Bandwidth) {
+        self.bandwidth = bandwidth;
+        self.compute_density();
+    }
+
+    pub fn set_kernel(&mut self, kernel: Kernel) {
+        self.kernel = kernel;
+        self.compute_density();
+    }
+
+    /// Mark every value with a tick along the bottom of the plot
+    pub fn set_show_rug(&mut self, show: bool) {
+        self.show_rug = show;
+    }
+
     pub fn set_title(&mut self, title: impl Into<String>) {
         self.title = title.into();
     }
@@ -136,91 +181,50 @@ impl HistogramChart {
     pub fn clear(&mut self) {
         self.values.clear();
         self.bins.clear();
+        self.density.clear();
     }
 
     fn compute_bins(&mut self) {
-        if self.values.is_empty() {
-            self.bins.clear();
-            return;
-        }
-
-        let min = self.values.iter().cloned().fold(f64::MAX, f64::min);
-        let max = self.values.iter().cloned().fold(f64::MIN, f64::max);
-
-        let num_bins = self.num_bins.unwrap_or_else(|| {
-            let n = self.values.len() as f64;
-            (1.0 + 3.322 * n.log10()).ceil() as usize
-        }).max(1);
-
-        let bin_width = (max - min) / num_bins as f64;
-
-        self.bins = (0..num_bins).map(|i| {
-            let left = min + i as f64 * bin_width;
-            let right = if i == num_bins - 1 { max } else { min + (i + 1) as f64 * bin_width };
-            HistogramBin { left, right, count: 0 }
+        let edges = bin_edges(&self.values, self.bin_rule);
+        let counts = bin_counts(&self.values, &edges);
+        self.bins = counts.iter().enumerate().map(|(i, &count)| {
+            HistogramBin { left: edges[i], right: edges[i + 1], count }
         }).collect();
-
-        for &value in &self.values {
-            let bin_idx = ((value - min) / bin_width).floor() as usize;
-            let bin_idx = bin_idx.min(num_bins - 1);
-            self.bins[bin_idx].count += 1;
-        }
-    }
-
-    fn update_plot_area(&mut self, rect: Rect) {
-        self.plot_area = PlotArea::new(
-            rect.pos.x + self.left_margin,
-            rect.pos.y + self.top_margin,
-            rect.pos.x + rect.size.x - self.right_margin,
-            rect.pos.y + rect.size.y - self.bottom_margin,
-        );
+        self.compute_density();
     }
 
-    fn get_ranges(&self) -> ((f64, f64), (f64, f64)) {
-        if self.bins.is_empty() {
-            return ((0.0, 1.0), (0.0, 1.0));
+    /// Sample the density estimate across the bins. A density integrates
+    /// to one, so times the sample count and bin width it matches the bars.
+    fn compute_density(&mut self) {
+        self.density.clear();
+        let (Some(first), Some(last)) = (self.bins.first(), self.bins.last()) else { return };
+        if !self.show_density {
+            return;
         }
+        let bw = self.bandwidth.select(&self.values);
+        let n = self.values.iter().filter(|v| v.is_finite()).count() as f64;
+        let scale = n * (first.right - first.left);
+        let at = linspace(first.left, last.right, 100);
+        let density = kde(&self.values, &at, bw, self.kernel);
+        self.density = at.into_iter().zip(density).map(|(x, d)| (x, d * scale)).collect();
+    }
 
+    /// Fit the axes to the bins: x spans the bins, y runs from zero to a nice
+    /// value above the tallest bin
+    fn update_axes(&mut self, rect: Rect) {
         let x_min = self.bins.first().map(|b| b.left).unwrap_or(0.0);
         let x_max = self.bins.last().map(|b| b.right).unwrap_or(1.0);
-        let y_max = self.bins.iter().map(|b| b.count).max().unwrap_or(1) as f64 * 1.1;
+        let tallest = self.bins.iter().map(|b| b.count).max().unwrap_or(1) as f64;
+        let y_max = self.density.iter().map(|p| p.1).fold(tallest, f64::max) * 1.1;
 
-        ((x_min, x_max), (0.0, y_max))
+        self.axes.x.range = (x_min, x_max);
+        self.axes.x.show_grid = false;
+        self.axes.y.range = nice_bounds(0.0, y_max, self.axes.y.tick_count);
+        self.axes.update_plot_area(rect);
     }
 
     fn data_to_pixel(&self, x: f64, y: f64) -> DVec2 {
-        let ((x_min, x_max), (y_min, y_max)) = self.get_ranges();
-        let px = self.plot_area.left + (x - x_min) / (x_max - x_min) * self.plot_area.width();
-        let py = self.plot_area.bottom - (y - y_min) / (y_max - y_min) * self.plot_area.height();
-        dvec2(px, py)
-    }
-
-    fn draw_grid(&mut self, cx: &mut Cx2d) {
-        if !self.show_grid {
-            return;
-        }
-
-        self.draw_line.color = vec4(0.9, 0.9, 0.9, 1.0);
-        let (_, (y_min, y_max)) = self.get_ranges();
-
-        let y_ticks = self.generate_ticks(y_min, y_max, 5);
-        for y in &y_ticks {
-            let p1 = self.data_to_pixel(self.bins.first().map(|b| b.left).unwrap_or(0.0), *y);
-            let p2 = self.data_to_pixel(self.bins.last().map(|b| b.right).unwrap_or(1.0), *y);
-            self.draw_line.draw_line(cx, p1, p2, 0.5);
-        }
-    }
-
-    fn draw_axes(&mut self, cx: &mut Cx2d) {
-        self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-
-        let x1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-        let x2 = dvec2(self.plot_area.right, self.plot_area.bottom);
-        self.draw_line.draw_line(cx, x1, x2, 1.0);
-
-        let y1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-        let y2 = dvec2(self.plot_area.left, self.plot_area.top);
-        self.draw_line.draw_line(cx, y1, y2, 1.0);
+        self.axes.data_to_pixel(x, y)
     }
 
     fn draw_bars(&mut self, cx: &mut Cx2d) {
@@ -239,34 +243,35 @@ impl HistogramChart {
         }
     }
 
-    fn draw_labels(&mut self, cx: &mut Cx2d) {
-        self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
-
-        let ((x_min, x_max), (y_min, y_max)) = self.get_ranges();
-
-        let x_ticks = self.generate_ticks(x_min, x_max, 5);
-        for x in &x_ticks {
-            let p = self.data_to_pixel(*x, y_min);
-            let label = format!("{:.1}", x);
-            self.label.draw_at(cx, dvec2(p.x, p.y + 5.0), &label, TextAnchor::TopCenter);
+    fn draw_density(&mut self, cx: &mut Cx2d) {
+        let color = self.bar_color.unwrap_or_else(|| get_color(0));
+        self.draw_line.color = vec4(color.x * 0.6, color.y * 0.6, color.z * 0.6, 1.0);
+        for pair in self.density.windows(2) {
+            let p1 = self.data_to_pixel(pair[0].0, pair[0].1);
+            let p2 = self.data_to_pixel(pair[1].0, pair[1].1);
+            self.draw_line.draw_line(cx, p1, p2, 2.0);
         }
+    }
 
-        let y_ticks = self.generate_ticks(y_min, y_max, 5);
-        for y in &y_ticks {
-            let p = self.data_to_pixel(x_min, *y);
-            let label = format!("{:.0}", y);
-            self.label.draw_at(cx, dvec2(p.x - 5.0, p.y), &label, TextAnchor::MiddleRight);
+    fn draw_rug(&mut self, cx: &mut Cx2d) {
+        if !self.show_rug {
+            return;
         }
-
-        if !self.title.is_empty() {
-            let center_x = (self.plot_area.left + self.plot_area.right) / 2.0;
-            self.label.draw_at(cx, dvec2(center_x, self.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
+        let bottom = self.axes.plot_area.bottom;
+        self.draw_line.color = vec4(0.2, 0.2, 0.2, 0.5);
+        for &v in self.values.iter().filter(|v| v.is_finite()) {
+            let x = self.data_to_pixel(v, 0.0).x;
+            self.draw_line.draw_line(cx, dvec2(x, bottom), dvec2(x, bottom - 6.0), 1.0);
         }
     }
 
-    fn generate_ticks(&self, min: f64, max: f64, count: usize) -> Vec<f64> {
-        let step = (max - min) / count as f64;
-        (0..=count).map(|i| min + i as f64 * step).collect()
+    fn draw_labels(&mut self, cx: &mut Cx2d) {
+        self.axes.draw_tick_labels(cx, &mut self.label);
+
+        if !self.title.is_empty() {
+            let center_x = (self.axes.plot_area.left + self.axes.plot_area.right) / 2.0;
+            self.label.draw_at(cx, dvec2(center_x, self.axes.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
+        }
     }
 }
 
@@ -283,6 +288,36 @@ impl HistogramChartRef {
         }
     }
 
+    pub fn set_bin_rule(&self, rule: BinRule) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_bin_rule(rule);
+        }
+    }
+
+    pub fn set_show_density(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_show_density(show);
+        }
+    }
+
+    pub fn set_bandwidth(&self, bandwidth: Bandwidth) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_bandwidth(bandwidth);
+        }
+    }
+
+    pub fn set_kernel(&self, kernel: Kernel) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_kernel(kernel);
+        }
+    }
+
+    pub fn set_show_rug(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_show_rug(show);
+        }
+    }
+
     pub fn set_title(&self, title: impl Into<String>) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.set_title(title);
@@ -337,24 +372,14 @@ pub struct BoxPlotStats {
 
 impl BoxPlotStats {
     pub fn from_values(values: &[f64]) -> Option<Self> {
-        if values.is_empty() {
+        let sorted = sorted_finite(values);
+        if sorted.is_empty() {
             return None;
         }
 
-        let mut sorted: Vec<f64> = values.to_vec();
-        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-
-        let n = sorted.len();
-        let median = if n % 2 == 0 {
-            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
-        } else {
-            sorted[n / 2]
-        };
-
-        let q1_idx = n / 4;
-        let q3_idx = 3 * n / 4;
-        let q1 = sorted[q1_idx];
-        let q3 = sorted[q3_idx];
+        let q1 = quantile_sorted(&sorted, 0.25);
+        let median = quantile_sorted(&sorted, 0.5);
+        let q3 = quantile_sorted(&sorted, 0.75);
 
         let iqr = q3 - q1;
         let lower_fence = q1 - 1.5 * iqr;
@@ -423,32 +448,20 @@ pub struct BoxPlotChart {
     #[rust]
     items: Vec<BoxPlotItem>,
 
-    #[rust]
-    plot_area: PlotArea,
+    #[rust(Axes::with_margins(50.0, 30.0, 20.0, 40.0))]
+    axes: Axes,
 
     #[rust]
     title: String,
 
-    #[rust(true)]
-    show_grid: bool,
-
     #[rust(true)]
     show_outliers: bool,
 
-    #[rust(50.0)]
-    left_margin: f64,
-
-    #[rust(40.0)]
-    bottom_margin: f64,
-
-    #[rust(20.0)]
-    right_margin: f64,
-
-    #[rust(30.0)]
-    top_margin: f64,
-
     #[rust(0.6)]
     box_width_ratio: f64,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for BoxPlotChart {
@@ -458,18 +471,30 @@ impl Widget for BoxPlotChart {
 
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.items.is_empty() {
-            self.update_plot_area(rect);
-            self.draw_grid(cx);
-            self.draw_axes(cx);
+            self.update_axes(rect);
+            self.axes.draw_grid(cx, &mut self.draw_line);
+            self.axes.draw_axes(cx, &mut self.draw_line);
             self.draw_boxes(cx);
             self.draw_labels(cx);
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 }
 
+impl PlotExport for BoxPlotChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl BoxPlotChart {
     pub fn add_item(&mut self, item: BoxPlotItem) {
         self.items.push(item);
@@ -493,13 +518,12 @@ impl BoxPlotChart {
         self.show_outliers = show;
     }
 
-    fn update_plot_area(&mut self, rect: Rect) {
-        self.plot_area = PlotArea::new(
-            rect.pos.x + self.left_margin,
-            rect.pos.y + self.top_margin,
-            rect.pos.x + rect.size.x - self.right_margin,
-            rect.pos.y + rect.size.y - self.bottom_margin,
-        );
+    /// Categories run along x (labelled by the chart), values along y
+    fn update_axes(&mut self, rect: Rect) {
+        self.axes.x.show_grid = false;
+        self.axes.x.show_labels = false;
+        self.axes.y.range = self.get_y_range();
+        self.axes.update_plot_area(rect);
     }
 
     fn get_y_range(&self) -> (f64, f64) {
@@ -521,51 +545,21 @@ impl BoxPlotChart {
         (min - padding, max + padding)
     }
 
-    fn draw_grid(&mut self, cx: &mut Cx2d) {
-        if !self.show_grid {
-            return;
-        }
-
-        self.draw_line.color = vec4(0.9, 0.9, 0.9, 1.0);
-        let (y_min, y_max) = self.get_y_range();
-
-        let y_ticks = self.generate_ticks(y_min, y_max, 5);
-        for y in &y_ticks {
-            let y_pixel = self.plot_area.bottom - (*y - y_min) / (y_max - y_min) * self.plot_area.height();
-            let p1 = dvec2(self.plot_area.left, y_pixel);
-            let p2 = dvec2(self.plot_area.right, y_pixel);
-            self.draw_line.draw_line(cx, p1, p2, 0.5);
-        }
-    }
-
-    fn draw_axes(&mut self, cx: &mut Cx2d) {
-        self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-
-        let x1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-        let x2 = dvec2(self.plot_area.right, self.plot_area.bottom);
-        self.draw_line.draw_line(cx, x1, x2, 1.0);
-
-        let y1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-        let y2 = dvec2(self.plot_area.left, self.plot_area.top);
-        self.draw_line.draw_line(cx, y1, y2, 1.0);
-    }
-
     fn draw_boxes(&mut self, cx: &mut Cx2d) {
         let n = self.items.len();
         if n == 0 {
             return;
         }
 
-        let (y_min, y_max) = self.get_y_range();
-        let band_width = self.plot_area.width() / n as f64;
+        let band_width = self.axes.plot_area.width() / n as f64;
         let box_width = band_width * self.box_width_ratio;
 
         for (i, item) in self.items.iter().enumerate() {
             let color = item.color.unwrap_or_else(|| get_color(i));
-            let x_center = self.plot_area.left + (i as f64 + 0.5) * band_width;
+            let x_center = self.axes.plot_area.left + (i as f64 + 0.5) * band_width;
 
             let y_to_pixel = |y: f64| -> f64 {
-                self.plot_area.bottom - (y - y_min) / (y_max - y_min) * self.plot_area.height()
+                self.axes.y_to_pixel(y)
             };
 
             let q1_y = y_to_pixel(item.stats.q1);
@@ -639,35 +633,23 @@ impl BoxPlotChart {
         self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
 
         let n = self.items.len();
-        let band_width = self.plot_area.width() / n as f64;
+        let band_width = self.axes.plot_area.width() / n as f64;
 
         // Category labels
         for (i, item) in self.items.iter().enumerate() {
-            let x = self.plot_area.left + (i as f64 + 0.5) * band_width;
-            let y = self.plot_area.bottom + 5.0;
+            let x = self.axes.plot_area.left + (i as f64 + 0.5) * band_width;
+            let y = self.axes.plot_area.bottom + 5.0;
             self.label.draw_at(cx, dvec2(x, y), &item.label, TextAnchor::TopCenter);
         }
 
-        // Y axis tick labels
-        let (y_min, y_max) = self.get_y_range();
-        let y_ticks = self.generate_ticks(y_min, y_max, 5);
-        for y in &y_ticks {
-            let y_pixel = self.plot_area.bottom - (*y - y_min) / (y_max - y_min) * self.plot_area.height();
-            let label = format!("{:.0}", y);
-            self.label.draw_at(cx, dvec2(self.plot_area.left - 5.0, y_pixel), &label, TextAnchor::MiddleRight);
-        }
+        self.axes.draw_tick_labels(cx, &mut self.label);
 
         // Title
         if !self.title.is_empty() {
-            let center_x = (self.plot_area.left + self.plot_area.right) / 2.0;
-            self.label.draw_at(cx, dvec2(center_x, self.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
+            let center_x = (self.axes.plot_area.left + self.axes.plot_area.right) / 2.0;
+            self.label.draw_at(cx, dvec2(center_x, self.axes.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
         }
     }
-
-    fn generate_ticks(&self, min: f64, max: f64, count: usize) -> Vec<f64> {
-        let step = (max - min) / count as f64;
-        (0..=count).map(|i| min + i as f64 * step).collect()
-    }
 }
 
 impl BoxPlotChartRef {
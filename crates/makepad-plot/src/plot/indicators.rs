@@ -0,0 +1,246 @@
+// Technical indicators over price series
+//
+// Each function returns one value per input sample so results line up with
+// the candles they came from. Samples before an indicator has enough history
+// (its warm-up period) are NaN, which the charts skip when drawing.
+
+/// Simple moving average over the last `period` samples
+pub fn sma(values: &[f64], period: usize) -> Vec<f64> {
+    let mut out = vec![f64::NAN; values.len()];
+    if period == 0 {
+        return out;
+    }
+    let (mut sum, mut missing) = (0.0, 0);
+    for (i, &v) in values.iter().enumerate() {
+        if v.is_finite() { sum += v } else { missing += 1 }
+        if i >= period {
+            let old = values[i - period];
+            if old.is_finite() { sum -= old } else { missing -= 1 }
+        }
+        if i + 1 >= period && missing == 0 {
+            out[i] = sum / period as f64;
+        }
+    }
+    out
+}
+
+/// Exponential moving average with smoothing `2 / (period + 1)`, seeded with
+/// the simple average of the first `period` finite samples. Leading NaNs
+/// (such as another indicator's warm-up) are skipped; later gaps hold the
+/// previous value.
+pub fn ema(values: &[f64], period: usize) -> Vec<f64> {
+    let mut out = vec![f64::NAN; values.len()];
+    let Some(first) = values.iter().position(|v| v.is_finite()) else {
+        return out;
+    };
+    if period == 0 || first + period > values.len() {
+        return out;
+    }
+    let seed = &values[first..first + period];
+    if seed.iter().any(|v| !v.is_finite()) {
+        return out;
+    }
+    let alpha = 2.0 / (period as f64 + 1.0);
+    let mut current = seed.iter().sum::<f64>() / period as f64;
+    out[first + period - 1] = current;
+    for (slot, &v) in out.iter_mut().zip(values).skip(first + period) {
+        if v.is_finite() {
+            current += alpha * (v - current);
+        }
+        *slot = current;
+    }
+    out
+}
+
+/// Moving average with bands `k` standard deviations above and below
+#[derive(Clone, Debug, Default)]
+pub struct Bands {
+    pub middle: Vec<f64>,
+    pub upper: Vec<f64>,
+    pub lower: Vec<f64>,
+}
+
+/// Bollinger bands: SMA of `period` samples plus and minus `k` population
+/// standard deviations
+pub fn bollinger(values: &[f64], period: usize, k: f64) -> Bands {
+    let middle = sma(values, period);
+    let mut upper = vec![f64::NAN; values.len()];
+    let mut lower = vec![f64::NAN; values.len()];
+    for (i, &mean) in middle.iter().enumerate() {
+        if !mean.is_finite() {
+            continue;
+        }
+        let window = &values[i + 1 - period..=i];
+        let variance = window.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / period as f64;
+        let spread = k * variance.sqrt();
+        upper[i] = mean + spread;
+        lower[i] = mean - spread;
+    }
+    Bands { middle, upper, lower }
+}
+
+/// Volume-weighted average of the typical price `(high + low + close) / 3`,
+/// accumulated from the first sample (one session). NaN until some volume
+/// has traded.
+pub fn vwap(high: &[f64], low: &[f64], close: &[f64], volume: &[f64]) -> Vec<f64> {
+    let n = high.len().min(low.len()).min(close.len()).min(volume.len());
+    let (mut price_volume, mut total_volume) = (0.0, 0.0);
+    (0..n)
+        .map(|i| {
+            let typical = (high[i] + low[i] + close[i]) / 3.0;
+            if typical.is_finite() && volume[i].is_finite() && volume[i] > 0.0 {
+                price_volume += typical * volume[i];
+                total_volume += volume[i];
+            }
+            if total_volume > 0.0 { price_volume / total_volume } else { f64::NAN }
+        })
+        .collect()
+}
+
+/// Relative strength index (0-100) with Wilder's smoothing of average gains
+/// and losses over `period` changes
+pub fn rsi(values: &[f64], period: usize) -> Vec<f64> {
+    let mut out = vec![f64::NAN; values.len()];
+    if period == 0 || values.len() <= period {
+        return out;
+    }
+    let index = |gain: f64, loss: f64| {
+        if loss == 0.0 {
+            if gain == 0.0 { 50.0 } else { 100.0 }
+        } else {
+            100.0 - 100.0 / (1.0 + gain / loss)
+        }
+    };
+    let change = |i: usize| {
+        let d = values[i] - values[i - 1];
+        if d.is_finite() { d } else { 0.0 }
+    };
+    let (mut gain, mut loss) = (0.0, 0.0);
+    for i in 1..=period {
+        gain += change(i).max(0.0);
+        loss += (-change(i)).max(0.0);
+    }
+    gain /= period as f64;
+    loss /= period as f64;
+    out[period] = index(gain, loss);
+    let p = period as f64;
+    for (i, slot) in out.iter_mut().enumerate().skip(period + 1) {
+        gain = (gain * (p - 1.0) + change(i).max(0.0)) / p;
+        loss = (loss * (p - 1.0) + (-change(i)).max(0.0)) / p;
+        *slot = index(gain, loss);
+    }
+    out
+}
+
+/// MACD line (fast EMA minus slow EMA), its signal EMA and their difference
+#[derive(Clone, Debug, Default)]
+pub struct Macd {
+    pub macd: Vec<f64>,
+    pub signal: Vec<f64>,
+    pub histogram: Vec<f64>,
+}
+
+pub fn macd(values: &[f64], fast: usize, slow: usize, signal: usize) -> Macd {
+    let fast_ema = ema(values, fast);
+    let slow_ema = ema(values, slow);
+    let macd: Vec<f64> = fast_ema.iter().zip(&slow_ema).map(|(f, s)| f - s).collect();
+    let signal = ema(&macd, signal);
+    let histogram = macd.iter().zip(&signal).map(|(m, s)| m - s).collect();
+    Macd { macd, signal, histogram }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64], tolerance: f64) {
+        assert_eq!(actual.len(), expected.len());
+        for (i, (a, e)) in actual.iter().zip(expected).enumerate() {
+            if e.is_nan() {
+                assert!(a.is_nan(), "index {}: expected NaN, got {}", i, a);
+            } else {
+                assert!((a - e).abs() <= tolerance, "index {}: expected {}, got {}", i, e, a);
+            }
+        }
+    }
+
+    const NAN: f64 = f64::NAN;
+
+    #[test]
+    fn test_sma() {
+        assert_close(&sma(&[1.0, 2.0, 3.0, 4.0, 5.0], 3), &[NAN, NAN, 2.0, 3.0, 4.0], 1e-12);
+        // A gap blanks every window containing it
+        assert_close(&sma(&[1.0, NAN, 3.0, 4.0, 5.0, 6.0], 2), &[NAN, NAN, NAN, 3.5, 4.5, 5.5], 1e-12);
+        assert!(sma(&[1.0, 2.0], 0).iter().all(|v| v.is_nan()));
+        assert!(sma(&[1.0, 2.0], 3).iter().all(|v| v.is_nan()));
+    }
+
+    #[test]
+    fn test_ema() {
+        // Smoothing 0.5, seeded with the mean of the first three
+        assert_close(&ema(&[2.0, 4.0, 6.0, 8.0, 12.0], 3), &[NAN, NAN, 4.0, 6.0, 9.0], 1e-12);
+        // Leading NaNs are skipped and later gaps hold the value
+        assert_close(&ema(&[NAN, 2.0, 4.0, 6.0, NAN, 12.0], 3), &[NAN, NAN, NAN, 4.0, 4.0, 8.0], 1e-12);
+        assert!(ema(&[1.0, 2.0], 3).iter().all(|v| v.is_nan()));
+    }
+
+    #[test]
+    fn test_rsi() {
+        // Changes +1, -1, +2: averages 0.5/0.5 give 50, then Wilder
+        // smoothing gives gain 1.25, loss 0.25, RS 5
+        assert_close(&rsi(&[1.0, 2.0, 1.0, 3.0], 2), &[NAN, NAN, 50.0, 100.0 - 100.0 / 6.0], 1e-12);
+        assert_close(&rsi(&[1.0, 2.0, 3.0, 4.0], 2), &[NAN, NAN, 100.0, 100.0], 1e-12);
+        assert_close(&rsi(&[5.0, 5.0, 5.0], 2), &[NAN, NAN, 50.0], 1e-12);
+        assert_close(&rsi(&[4.0, 3.0, 2.0], 2), &[NAN, NAN, 0.0], 1e-12);
+
+        // Over 14 periods the first value is the plain ratio of summed
+        // gains to summed losses, the next one is Wilder smoothed
+        let closes = [
+            44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08,
+            45.89, 46.03, 45.61, 46.28, 46.28, 46.00,
+        ];
+        let changes: Vec<f64> = closes.windows(2).map(|w| w[1] - w[0]).collect();
+        let gains: f64 = changes[..14].iter().map(|c| c.max(0.0)).sum();
+        let losses: f64 = changes[..14].iter().map(|c| (-c).max(0.0)).sum();
+        let first = 100.0 - 100.0 / (1.0 + gains / losses);
+        let (gain, loss) = (gains / 14.0 * 13.0 / 14.0, (losses / 14.0 * 13.0 + 0.28) / 14.0);
+        let second = 100.0 - 100.0 / (1.0 + gain / loss);
+        let out = rsi(&closes, 14);
+        assert!(out[..14].iter().all(|v| v.is_nan()));
+        assert_close(&out[14..], &[first, second], 1e-9);
+        assert!(second < first && first > 70.0 && first < 71.0);
+    }
+
+    #[test]
+    fn test_macd_warm_up() {
+        let values: Vec<f64> = (0..20).map(|i| 10.0 + (i as f64 * 0.7).sin()).collect();
+        let out = macd(&values, 3, 5, 2);
+        // MACD needs the slow EMA, the signal needs two MACD values more
+        let first = |v: &[f64]| v.iter().position(|x| x.is_finite()).unwrap();
+        assert_eq!(first(&out.macd), 4);
+        assert_eq!(first(&out.signal), 5);
+        assert_eq!(first(&out.histogram), 5);
+        assert!(out.macd[4..].iter().chain(&out.signal[5..]).all(|v| v.is_finite()));
+        for i in 5..20 {
+            assert!((out.histogram[i] - (out.macd[i] - out.signal[i])).abs() < 1e-12);
+        }
+
+        // Flat prices have no momentum
+        let flat = macd(&[3.0; 12], 3, 5, 2);
+        assert!(flat.macd[4..].iter().chain(&flat.histogram[5..]).all(|&v| v == 0.0));
+    }
+
+    #[test]
+    fn test_bollinger_and_vwap() {
+        let bands = bollinger(&[1.0, 2.0, 3.0, 5.0], 3, 2.0);
+        let spread = 2.0 * (2.0f64 / 3.0).sqrt();
+        assert_close(&bands.middle, &[NAN, NAN, 2.0, 10.0 / 3.0], 1e-12);
+        assert_close(&bands.upper[..3], &[NAN, NAN, 2.0 + spread], 1e-12);
+        assert_close(&bands.lower[..3], &[NAN, NAN, 2.0 - spread], 1e-12);
+
+        // Typical prices 2 and 5 weighted 1:2, then a bar without volume
+        let out = vwap(&[3.0, 6.0, 9.0], &[1.0, 4.0, 7.0], &[2.0, 5.0, 8.0], &[1.0, 2.0, 0.0]);
+        assert_close(&out, &[2.0, 4.0, 4.0], 1e-12);
+        assert!(vwap(&[1.0], &[1.0], &[1.0], &[0.0])[0].is_nan());
+    }
+}
@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -45,19 +46,20 @@ pub struct LinePlot {
     fill_regions: Vec<FillRegion>,
 
     #[rust]
-    annotations: Vec<TextAnnotation>,
+    trend_lines: Vec<TrendLine>,
 
+    /// Tick marks for every point along the bottom and left edges
     #[rust]
-    arrow_annotations: Vec<ArrowAnnotation>,
+    show_rug: bool,
 
     #[rust]
-    plot_area: PlotArea,
+    annotations: Vec<TextAnnotation>,
 
     #[rust]
-    x_range: (f64, f64),
+    arrow_annotations: Vec<ArrowAnnotation>,
 
     #[rust]
-    y_range: (f64, f64),
+    axes: Axes,
 
     #[rust]
     title: String,
@@ -68,9 +70,6 @@ pub struct LinePlot {
     #[rust]
     y_label: String,
 
-    #[rust(true)]
-    show_grid: bool,
-
     #[rust(true)]
     show_points: bool,
 
@@ -80,27 +79,9 @@ pub struct LinePlot {
     #[rust(2.0)]
     line_width: f64,
 
-    #[rust(50.0)]
-    left_margin: f64,
-
-    #[rust(30.0)]
-    bottom_margin: f64,
-
-    #[rust(20.0)]
-    right_margin: f64,
-
-    #[rust(30.0)]
-    top_margin: f64,
-
     #[rust]
     legend_position: LegendPosition,
 
-    #[rust]
-    x_scale: ScaleType,
-
-    #[rust]
-    y_scale: ScaleType,
-
     // Pan/zoom state (disabled by default - enable with set_interactive(true))
     #[rust]
     interactive: bool,
@@ -129,26 +110,60 @@ pub struct LinePlot {
 
     #[rust]
     hspans: Vec<HSpan>,
+
+    // Hover crosshair/tooltip (disabled by default - enable with set_hover(true))
+    #[rust]
+    hover: PlotHover,
+
+    // Level of detail for large series (on by default)
+    #[rust]
+    lod: LodOptions,
+
+    #[rust]
+    lod_cache: LodCache,
+
+    // Auto-scrolling axes for streamed series
+    #[rust]
+    stream: StreamAxes,
+
+    /// Drawn area, for pan/zoom hit testing
+    #[rust]
+    area: Area,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for LinePlot {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         self.view.handle_event(cx, event, scope);
 
+        if self.hover.enabled {
+            let (action, redraw) = self.hover.handle_event(event, &self.axes, |pos| {
+                pick_nearest_x(&self.axes, &self.series, pos)
+            });
+            if let Some(action) = action {
+                cx.widget_action(self.widget_uid(), &scope.path, action);
+            }
+            if redraw {
+                self.redraw(cx);
+            }
+        }
+
         if !self.interactive {
             return;
         }
 
         // Handle pan/zoom events
-        match event.hits(cx, self.view.area()) {
+        match event.hits(cx, self.area) {
             Hit::FingerDown(fe) => {
                 self.is_dragging = true;
                 self.drag_start = fe.abs;
-                self.initial_x_range = self.x_range;
-                self.initial_y_range = self.y_range;
+                self.initial_x_range = self.axes.x.range;
+                self.initial_y_range = self.axes.y.range;
             }
             Hit::FingerMove(fe) => {
-                if self.is_dragging && self.plot_area.width() > 0.0 && self.plot_area.height() > 0.0 {
+                if self.is_dragging && self.axes.plot_area.width() > 0.0 && self.axes.plot_area.height() > 0.0 {
                     // Calculate the delta in data coordinates
                     let dx_pixels = fe.abs.x - self.drag_start.x;
                     let dy_pixels = fe.abs.y - self.drag_start.y;
@@ -157,15 +172,15 @@ impl Widget for LinePlot {
                     let x_range_size = self.initial_x_range.1 - self.initial_x_range.0;
                     let y_range_size = self.initial_y_range.1 - self.initial_y_range.0;
 
-                    let dx_data = -dx_pixels * x_range_size / self.plot_area.width();
-                    let dy_data = dy_pixels * y_range_size / self.plot_area.height();
+                    let dx_data = -dx_pixels * x_range_size / self.axes.plot_area.width();
+                    let dy_data = dy_pixels * y_range_size / self.axes.plot_area.height();
 
                     // Update ranges (pan)
-                    self.x_range = (
+                    self.axes.x.range = (
                         self.initial_x_range.0 + dx_data,
                         self.initial_x_range.1 + dx_data,
                     );
-                    self.y_range = (
+                    self.axes.y.range = (
                         self.initial_y_range.0 + dy_data,
                         self.initial_y_range.1 + dy_data,
                     );
@@ -185,25 +200,25 @@ impl Widget for LinePlot {
                 let mouse_y = fe.abs.y;
 
                 // Check if mouse is in plot area
-                if mouse_x >= self.plot_area.left && mouse_x <= self.plot_area.right
-                    && mouse_y >= self.plot_area.top && mouse_y <= self.plot_area.bottom
+                if mouse_x >= self.axes.plot_area.left && mouse_x <= self.axes.plot_area.right
+                    && mouse_y >= self.axes.plot_area.top && mouse_y <= self.axes.plot_area.bottom
                 {
                     // Calculate the data point under the mouse
-                    let rel_x = (mouse_x - self.plot_area.left) / self.plot_area.width();
-                    let rel_y = (self.plot_area.bottom - mouse_y) / self.plot_area.height();
+                    let rel_x = (mouse_x - self.axes.plot_area.left) / self.axes.plot_area.width();
+                    let rel_y = (self.axes.plot_area.bottom - mouse_y) / self.axes.plot_area.height();
 
-                    let data_x = self.x_range.0 + rel_x * (self.x_range.1 - self.x_range.0);
-                    let data_y = self.y_range.0 + rel_y * (self.y_range.1 - self.y_range.0);
+                    let data_x = self.axes.x.range.0 + rel_x * (self.axes.x.range.1 - self.axes.x.range.0);
+                    let data_y = self.axes.y.range.0 + rel_y * (self.axes.y.range.1 - self.axes.y.range.0);
 
                     // Zoom around the mouse position
-                    let new_x_range = (self.x_range.1 - self.x_range.0) * zoom_factor;
-                    let new_y_range = (self.y_range.1 - self.y_range.0) * zoom_factor;
+                    let new_x_range = (self.axes.x.range.1 - self.axes.x.range.0) * zoom_factor;
+                    let new_y_range = (self.axes.y.range.1 - self.axes.y.range.0) * zoom_factor;
 
-                    self.x_range = (
+                    self.axes.x.range = (
                         data_x - rel_x * new_x_range,
                         data_x + (1.0 - rel_x) * new_x_range,
                     );
-                    self.y_range = (
+                    self.axes.y.range = (
                         data_y - rel_y * new_y_range,
                         data_y + (1.0 - rel_y) * new_y_range,
                     );
@@ -223,31 +238,77 @@ impl Widget for LinePlot {
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
-        let rect = cx.walk_turtle(walk);
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
         if rect.size.x > 0.0 && rect.size.y > 0.0 {
-            self.update_plot_area(rect);
-            self.draw_grid(cx);
-            self.draw_axes(cx);
+            self.axes.update_plot_area(rect);
+            self.axes.draw_grid(cx, &mut self.draw_line);
+            self.axes.draw_axes(cx, &mut self.draw_line);
             self.draw_series(cx);
             self.draw_annotations(cx);
             self.draw_labels(cx);
             self.draw_legend(cx);
         }
 
+        // The hover overlay is not part of exported frames
+        self.capture.end();
+
+        if rect.size.x > 0.0 && rect.size.y > 0.0 {
+            let series = &self.series;
+            self.hover.draw(
+                cx,
+                &self.axes,
+                |i| series.get(i).and_then(|s| s.color).unwrap_or_else(|| get_color(i)),
+                &mut self.draw_line,
+                &mut self.draw_point,
+                &mut self.draw_fill,
+                &mut self.label,
+            );
+        }
+
         DrawStep::done()
     }
 }
 
+impl PlotExport for LinePlot {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
+impl Subplot for LinePlot {
+    fn subplot_axes(&self) -> &Axes {
+        &self.axes
+    }
+
+    fn subplot_axes_mut(&mut self) -> &mut Axes {
+        &mut self.axes
+    }
+
+    fn legend_entries(&self) -> Vec<(String, Vec4)> {
+        self.series.iter().enumerate()
+            .map(|(idx, s)| (s.label.clone(), s.color.unwrap_or_else(|| get_color(idx))))
+            .collect()
+    }
+}
+
 impl LinePlot {
     /// Add a data series to the plot
     pub fn add_series(&mut self, series: Series) {
         self.series.push(series);
+        self.lod_cache.clear();
         self.auto_range();
     }
 
-    /// Clear all series
+    /// Clear all series and the fits drawn over them
     pub fn clear(&mut self) {
         self.series.clear();
+        self.trend_lines.clear();
+        self.lod_cache.clear();
     }
 
     /// Set plot title
@@ -267,12 +328,12 @@ impl LinePlot {
 
     /// Set X range manually
     pub fn set_xlim(&mut self, min: f64, max: f64) {
-        self.x_range = (min, max);
+        self.axes.x.range = (min, max);
     }
 
     /// Set Y range manually
     pub fn set_ylim(&mut self, min: f64, max: f64) {
-        self.y_range = (min, max);
+        self.axes.y.range = (min, max);
     }
 
     /// Show or hide data points
@@ -299,6 +360,36 @@ impl LinePlot {
         self.auto_range();
     }
 
+    /// Overlay a least-squares polynomial fit of a series, with its 95%
+    /// confidence band. Returns the fit for its coefficients and R², or
+    /// `None` if the series has too few points for the degree.
+    pub fn add_regression(&mut self, series: usize, degree: usize) -> Option<PolyFit> {
+        let s = self.series.get(series)?;
+        let (x, y): (Vec<f64>, Vec<f64>) = s.points().unzip();
+        let fit = polyfit(&x, &y, degree)?;
+        let finite = x.iter().copied().filter(|x| x.is_finite());
+        let (lo, hi) = finite.fold((f64::MAX, f64::MIN), |(lo, hi), x| (lo.min(x), hi.max(x)));
+        let color = s.color.unwrap_or_else(|| get_color(series));
+        self.trend_lines.push(TrendLine { curve: fit.curve(lo, hi, 100, 0.95), color });
+        self.auto_range();
+        Some(fit)
+    }
+
+    /// Overlay a LOWESS smooth of a series, each point fitted to the nearest
+    /// `frac` (0 to 1) of the data, with its 95% confidence band
+    pub fn add_lowess(&mut self, series: usize, frac: f64) {
+        let Some(s) = self.series.get(series) else { return };
+        let (x, y): (Vec<f64>, Vec<f64>) = s.points().unzip();
+        let color = s.color.unwrap_or_else(|| get_color(series));
+        self.trend_lines.push(TrendLine { curve: lowess(&x, &y, frac, 2, 0.95), color });
+        self.auto_range();
+    }
+
+    /// Show or hide rug ticks marking each point's x and y along the axes
+    pub fn set_rug(&mut self, show: bool) {
+        self.show_rug = show;
+    }
+
     /// Add a text annotation at a specific data coordinate
     /// Add a plain text annotation at a specific data coordinate
     pub fn annotate(&mut self, text: impl Into<String>, x: f64, y: f64, color: Vec4, font_size: f64) {
@@ -391,11 +482,9 @@ impl LinePlot {
         let mut y_max = f64::MIN;
 
         for s in &self.series {
-            for &x in &s.x {
+            for (x, y) in s.points() {
                 x_min = x_min.min(x);
                 x_max = x_max.max(x);
-            }
-            for &y in &s.y {
                 y_min = y_min.min(y);
                 y_max = y_max.max(y);
             }
@@ -417,182 +506,122 @@ impl LinePlot {
             }
         }
 
+        for trend in &self.trend_lines {
+            let curve = &trend.curve;
+            for &x in &curve.x {
+                x_min = x_min.min(x);
+                x_max = x_max.max(x);
+            }
+            for &y in curve.lower.iter().chain(&curve.upper) {
+                y_min = y_min.min(y);
+                y_max = y_max.max(y);
+            }
+        }
+
         // Apply scale-aware padding
-        match self.x_scale {
+        match self.axes.x.scale {
             ScaleType::Log => {
                 // For log scale, use multiplicative padding
                 if x_min > 0.0 && x_max > 0.0 {
-                    self.x_range = (x_min / 1.5, x_max * 1.5);
+                    self.axes.x.range = (x_min / 1.5, x_max * 1.5);
                 } else {
-                    self.x_range = (x_min, x_max);
+                    self.axes.x.range = (x_min, x_max);
                 }
             }
             _ => {
                 let x_pad = (x_max - x_min) * 0.05;
-                self.x_range = (x_min - x_pad, x_max + x_pad);
+                self.axes.x.range = (x_min - x_pad, x_max + x_pad);
             }
         }
 
-        match self.y_scale {
+        match self.axes.y.scale {
             ScaleType::Log => {
                 // For log scale, use multiplicative padding
                 if y_min > 0.0 && y_max > 0.0 {
-                    self.y_range = (y_min / 1.5, y_max * 1.5);
+                    self.axes.y.range = (y_min / 1.5, y_max * 1.5);
                 } else {
-                    self.y_range = (y_min, y_max);
+                    self.axes.y.range = (y_min, y_max);
                 }
             }
             _ => {
                 let y_pad = (y_max - y_min) * 0.05;
-                self.y_range = (y_min - y_pad, y_max + y_pad);
+                self.axes.y.range = (y_min - y_pad, y_max + y_pad);
             }
         }
     }
 
-    fn update_plot_area(&mut self, rect: Rect) {
-        self.plot_area = PlotArea::new(
-            rect.pos.x + self.left_margin,
-            rect.pos.y + self.top_margin,
-            rect.pos.x + rect.size.x - self.right_margin,
-            rect.pos.y + rect.size.y - self.bottom_margin,
-        );
-    }
-
     fn data_to_pixel(&self, x: f64, y: f64) -> DVec2 {
-        // Apply scale transformations
-        let tx = self.x_scale.transform(x);
-        let ty = self.y_scale.transform(y);
-        let tx_min = self.x_scale.transform(self.x_range.0);
-        let tx_max = self.x_scale.transform(self.x_range.1);
-        let ty_min = self.y_scale.transform(self.y_range.0);
-        let ty_max = self.y_scale.transform(self.y_range.1);
-
-        let px = self.plot_area.left + (tx - tx_min) / (tx_max - tx_min) * self.plot_area.width();
-        let py = self.plot_area.bottom - (ty - ty_min) / (ty_max - ty_min) * self.plot_area.height();
-        dvec2(px, py)
-    }
-
-    fn draw_grid(&mut self, cx: &mut Cx2d) {
-        if !self.show_grid {
-            return;
-        }
-
-        self.draw_line.color = vec4(0.9, 0.9, 0.9, 1.0);
-
-        // Horizontal grid lines - use scale-aware tick generation
-        let y_ticks = self.y_scale.generate_ticks(self.y_range.0, self.y_range.1, 5);
-        for y in &y_ticks {
-            let p1 = self.data_to_pixel(self.x_range.0, *y);
-            let p2 = self.data_to_pixel(self.x_range.1, *y);
-            self.draw_line.draw_line(cx, p1, p2, 0.5);
-        }
-
-        // Vertical grid lines - use scale-aware tick generation
-        let x_ticks = self.x_scale.generate_ticks(self.x_range.0, self.x_range.1, 5);
-        for x in &x_ticks {
-            let p1 = self.data_to_pixel(*x, self.y_range.0);
-            let p2 = self.data_to_pixel(*x, self.y_range.1);
-            self.draw_line.draw_line(cx, p1, p2, 0.5);
-        }
-    }
-
-    fn draw_axes(&mut self, cx: &mut Cx2d) {
-        self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-
-        // X axis
-        let x1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-        let x2 = dvec2(self.plot_area.right, self.plot_area.bottom);
-        self.draw_line.draw_line(cx, x1, x2, 1.0);
-
-        // Y axis
-        let y1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-        let y2 = dvec2(self.plot_area.left, self.plot_area.top);
-        self.draw_line.draw_line(cx, y1, y2, 1.0);
+        self.axes.data_to_pixel(x, y)
     }
 
     fn draw_series(&mut self, cx: &mut Cx2d) {
         // 1. Draw horizontal spans (hspans) - background layer
         for hs in &self.hspans {
             self.draw_fill.color = hs.color;
-            let p1 = self.data_to_pixel(self.x_range.0, hs.y1);
-            let p2 = self.data_to_pixel(self.x_range.1, hs.y2);
+            let p1 = self.data_to_pixel(self.axes.x.range.0, hs.y1);
+            let p2 = self.data_to_pixel(self.axes.x.range.1, hs.y2);
             let top = p1.y.min(p2.y);
             let bottom = p1.y.max(p2.y);
-            self.draw_fill.draw_fill_strip(cx, self.plot_area.left, self.plot_area.width(), top, bottom);
+            self.draw_fill.draw_fill_strip(cx, self.axes.plot_area.left, self.axes.plot_area.width(), top, bottom);
         }
 
         // 2. Draw vertical spans (vspans) - background layer
         for vs in &self.vspans {
             self.draw_fill.color = vs.color;
-            let p1 = self.data_to_pixel(vs.x1, self.y_range.0);
-            let p2 = self.data_to_pixel(vs.x2, self.y_range.1);
+            let p1 = self.data_to_pixel(vs.x1, self.axes.y.range.0);
+            let p2 = self.data_to_pixel(vs.x2, self.axes.y.range.1);
             let left = p1.x.min(p2.x);
             let right = p1.x.max(p2.x);
-            self.draw_fill.draw_fill_strip(cx, left, right - left, self.plot_area.top, self.plot_area.bottom);
+            self.draw_fill.draw_fill_strip(cx, left, right - left, self.axes.plot_area.top, self.axes.plot_area.bottom);
         }
 
-        // 3. Draw fill regions (fill_between)
+        // 3. Draw fill regions (fill_between), then fitted trend lines over
+        // their confidence bands
         for fr in &self.fill_regions {
             self.draw_fill.color = fr.color;
-            if fr.x.len() >= 2 {
-                for i in 0..fr.x.len() - 1 {
-                    let x1 = fr.x[i];
-                    let x2 = fr.x[i + 1];
-                    let y1_a = fr.y1[i];
-                    let y1_b = fr.y1[i + 1];
-                    let y2_a = fr.y2[i];
-                    let y2_b = fr.y2[i + 1];
-
-                    // Draw a series of thin vertical strips to approximate the fill
-                    let steps = 4;
-                    for s in 0..steps {
-                        let t1 = s as f64 / steps as f64;
-                        let t2 = (s + 1) as f64 / steps as f64;
-                        let x_left = x1 + (x2 - x1) * t1;
-                        let x_right = x1 + (x2 - x1) * t2;
-                        let y1_left = y1_a + (y1_b - y1_a) * t1;
-                        let y2_left = y2_a + (y2_b - y2_a) * t1;
-                        let y1_right = y1_a + (y1_b - y1_a) * t2;
-                        let y2_right = y2_a + (y2_b - y2_a) * t2;
-
-                        let p_tl = self.data_to_pixel(x_left, y1_left);
-                        let p_bl = self.data_to_pixel(x_left, y2_left);
-                        let p_tr = self.data_to_pixel(x_right, y1_right);
-                        let p_br = self.data_to_pixel(x_right, y2_right);
-
-                        let left = p_tl.x.min(p_bl.x);
-                        let right = p_tr.x.max(p_br.x);
-                        let top = p_tl.y.min(p_tr.y).min(p_bl.y).min(p_br.y);
-                        let bottom = p_tl.y.max(p_tr.y).max(p_bl.y).max(p_br.y);
-
-                        self.draw_fill.draw_fill_strip(cx, left, right - left, top, bottom);
-                    }
-                }
+            draw_fill_between(cx, &mut self.draw_fill, &self.axes, &fr.x, &fr.y1, &fr.y2);
+        }
+        for trend in &self.trend_lines {
+            let (curve, color) = (&trend.curve, trend.color);
+            self.draw_fill.color = vec4(color.x, color.y, color.z, 0.15);
+            draw_fill_between(cx, &mut self.draw_fill, &self.axes, &curve.x, &curve.lower, &curve.upper);
+            self.draw_line.color = color;
+            let mut dash_offset = 0.0;
+            for i in 1..curve.x.len() {
+                let p1 = self.axes.data_to_pixel(curve.x[i - 1], curve.y[i - 1]);
+                let p2 = self.axes.data_to_pixel(curve.x[i], curve.y[i]);
+                self.draw_line.draw_line_styled(cx, p1, p2, 2.0, LineStyle::Dashed, dash_offset);
+                dash_offset += (p2 - p1).length();
             }
         }
 
         // 4. Draw horizontal reference lines (hlines)
         for hl in &self.hlines {
             self.draw_line.color = hl.color;
-            let p = self.data_to_pixel(self.x_range.0, hl.y);
+            let p = self.data_to_pixel(self.axes.x.range.0, hl.y);
             self.draw_line.draw_line_styled(cx,
-                dvec2(self.plot_area.left, p.y),
-                dvec2(self.plot_area.right, p.y),
+                dvec2(self.axes.plot_area.left, p.y),
+                dvec2(self.axes.plot_area.right, p.y),
                 hl.line_width, hl.line_style, 0.0);
         }
 
         // 5. Draw vertical reference lines (vlines)
         for vl in &self.vlines {
             self.draw_line.color = vl.color;
-            let p = self.data_to_pixel(vl.x, self.y_range.0);
+            let p = self.data_to_pixel(vl.x, self.axes.y.range.0);
             self.draw_line.draw_line_styled(cx,
-                dvec2(p.x, self.plot_area.top),
-                dvec2(p.x, self.plot_area.bottom),
+                dvec2(p.x, self.axes.plot_area.top),
+                dvec2(p.x, self.axes.plot_area.bottom),
                 vl.line_width, vl.line_style, 0.0);
         }
 
-        // 6. Draw data series
+        // 6. Draw data series, thinned to the current view
+        let key = LodKey::new(&self.axes, &self.series);
+        let selections = self.lod_cache.get_or_insert(key, || {
+            self.series.iter().map(|s| decimate_line(s, &self.axes, &self.lod)).collect()
+        });
         for (idx, series) in self.series.iter().enumerate() {
+            let selection = &selections[idx];
             let color = series.color.unwrap_or_else(|| get_color(idx));
             let line_width = series.line_width.unwrap_or(self.line_width);
             let marker_size = series.marker_size.unwrap_or(self.point_radius);
@@ -603,17 +632,16 @@ impl LinePlot {
             // Draw error bars first (behind the line)
             if series.yerr_minus.is_some() || series.yerr_plus.is_some() || series.xerr_minus.is_some() || series.xerr_plus.is_some() {
                 let cap_width = 4.0;
-                for i in 0..series.x.len() {
-                    let x = series.x[i];
-                    let y = series.y[i];
+                for &i in selection {
+                    let (x, y) = series.point(i);
 
                     // Y error bars
                     if let (Some(ref err_minus), Some(ref err_plus)) = (&series.yerr_minus, &series.yerr_plus) {
                         if i < err_minus.len() && i < err_plus.len() {
                             let y_low = y - err_minus[i];
                             let y_high = y + err_plus[i];
-                            let p_low = self.data_to_pixel(x, y_low);
-                            let p_high = self.data_to_pixel(x, y_high);
+                            let p_low = self.axes.data_to_pixel(x, y_low);
+                            let p_high = self.axes.data_to_pixel(x, y_high);
 
                             // Vertical line
                             self.draw_line.draw_line_styled(cx, p_low, p_high, 1.0, LineStyle::Solid, 0.0);
@@ -635,8 +663,8 @@ impl LinePlot {
                         if i < err_minus.len() && i < err_plus.len() {
                             let x_low = x - err_minus[i];
                             let x_high = x + err_plus[i];
-                            let p_low = self.data_to_pixel(x_low, y);
-                            let p_high = self.data_to_pixel(x_high, y);
+                            let p_low = self.axes.data_to_pixel(x_low, y);
+                            let p_high = self.axes.data_to_pixel(x_high, y);
 
                             // Horizontal line
                             self.draw_line.draw_line_styled(cx, p_low, p_high, 1.0, LineStyle::Solid, 0.0);
@@ -656,57 +684,64 @@ impl LinePlot {
             }
 
             // Draw lines with proper style
-            if series.x.len() >= 2 {
+            if selection.len() >= 2 {
                 let mut dash_offset = 0.0;
 
-                for i in 0..series.x.len() - 1 {
+                for pair in selection.windows(2) {
+                    let (x1, y1) = series.point(pair[0]);
+                    let (x2, y2) = series.point(pair[1]);
                     let (x1, y1, x2, y2) = match series.step_style {
                         StepStyle::None => {
                             // Normal line
-                            (series.x[i], series.y[i], series.x[i + 1], series.y[i + 1])
+                            (x1, y1, x2, y2)
                         }
                         StepStyle::Pre => {
                             // Step before: vertical then horizontal
                             // First draw vertical segment
-                            let p1 = self.data_to_pixel(series.x[i], series.y[i]);
-                            let p2 = self.data_to_pixel(series.x[i], series.y[i + 1]);
+                            let p1 = self.axes.data_to_pixel(x1, y1);
+                            let p2 = self.axes.data_to_pixel(x1, y2);
                             self.draw_line.draw_line_styled(cx, p1, p2, line_width, series.line_style, dash_offset);
                             let seg_len = ((p2.x - p1.x).powi(2) + (p2.y - p1.y).powi(2)).sqrt();
                             dash_offset += seg_len;
                             // Then horizontal
-                            (series.x[i], series.y[i + 1], series.x[i + 1], series.y[i + 1])
+                            (x1, y2, x2, y2)
                         }
                         StepStyle::Post => {
                             // Step after: horizontal then vertical
                             // First draw horizontal segment
-                            let p1 = self.data_to_pixel(series.x[i], series.y[i]);
-                            let p2 = self.data_to_pixel(series.x[i + 1], series.y[i]);
+                            let p1 = self.axes.data_to_pixel(x1, y1);
+                            let p2 = self.axes.data_to_pixel(x2, y1);
                             self.draw_line.draw_line_styled(cx, p1, p2, line_width, series.line_style, dash_offset);
                             let seg_len = ((p2.x - p1.x).powi(2) + (p2.y - p1.y).powi(2)).sqrt();
                             dash_offset += seg_len;
                             // Then vertical
-                            (series.x[i + 1], series.y[i], series.x[i + 1], series.y[i + 1])
+                            (x2, y1, x2, y2)
                         }
                         StepStyle::Mid => {
                             // Step in middle: half horizontal, vertical, half horizontal
-                            let mid_x = (series.x[i] + series.x[i + 1]) / 2.0;
+                            let mid_x = (x1 + x2) / 2.0;
                             // First half horizontal
-                            let p1 = self.data_to_pixel(series.x[i], series.y[i]);
-                            let p2 = self.data_to_pixel(mid_x, series.y[i]);
+                            let p1 = self.axes.data_to_pixel(x1, y1);
+                            let p2 = self.axes.data_to_pixel(mid_x, y1);
                             self.draw_line.draw_line_styled(cx, p1, p2, line_width, series.line_style, dash_offset);
                             dash_offset += ((p2.x - p1.x).powi(2) + (p2.y - p1.y).powi(2)).sqrt();
                             // Vertical
-                            let p3 = self.data_to_pixel(mid_x, series.y[i + 1]);
+                            let p3 = self.axes.data_to_pixel(mid_x, y2);
                             self.draw_line.draw_line_styled(cx, p2, p3, line_width, series.line_style, dash_offset);
                             dash_offset += ((p3.x - p2.x).powi(2) + (p3.y - p2.y).powi(2)).sqrt();
                             // Second half horizontal
-                            (mid_x, series.y[i + 1], series.x[i + 1], series.y[i + 1])
+                            (mid_x, y2, x2, y2)
                         }
                     };
 
-                    let p1 = self.data_to_pixel(x1, y1);
-                    let p2 = self.data_to_pixel(x2, y2);
-                    self.draw_line.draw_line_styled(cx, p1, p2, line_width, series.line_style, dash_offset);
+                    let p1 = self.axes.data_to_pixel(x1, y1);
+                    let p2 = self.axes.data_to_pixel(x2, y2);
+                    // Segments leaving a scrolled window stop at its edge
+                    let area = self.axes.plot_area;
+                    if let Some((c1, c2)) = clip_segment_x(p1, p2, area.left, area.right) {
+                        let clip_offset = dash_offset + (c1 - p1).length();
+                        self.draw_line.draw_line_styled(cx, c1, c2, line_width, series.line_style, clip_offset);
+                    }
 
                     // Update dash offset for continuous pattern
                     let seg_len = ((p2.x - p1.x).powi(2) + (p2.y - p1.y).powi(2)).sqrt();
@@ -725,37 +760,40 @@ impl LinePlot {
                     MarkerStyle::Circle
                 };
 
-                for i in 0..series.x.len() {
-                    let p = self.data_to_pixel(series.x[i], series.y[i]);
+                for &i in selection {
+                    let (x, y) = series.point(i);
+                    let p = self.axes.data_to_pixel(x, y);
+                    if p.x < self.axes.plot_area.left || p.x > self.axes.plot_area.right {
+                        continue;
+                    }
                     self.draw_point.draw_marker(cx, p, marker_size, marker);
                 }
             }
+
+            if self.show_rug {
+                let area = self.axes.plot_area;
+                self.draw_line.color = vec4(color.x, color.y, color.z, 0.5);
+                for &i in selection {
+                    let (x, y) = series.point(i);
+                    let p = self.axes.data_to_pixel(x, y);
+                    if p.x >= area.left && p.x <= area.right {
+                        self.draw_line.draw_line(cx, dvec2(p.x, area.bottom), dvec2(p.x, area.bottom - 6.0), 1.0);
+                    }
+                    if p.y >= area.top && p.y <= area.bottom {
+                        self.draw_line.draw_line(cx, dvec2(area.left, p.y), dvec2(area.left + 6.0, p.y), 1.0);
+                    }
+                }
+            }
         }
     }
 
     fn draw_labels(&mut self, cx: &mut Cx2d) {
-        self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
-
-        // X axis tick labels - use scale-aware tick generation and formatting
-        let x_ticks = self.x_scale.generate_ticks(self.x_range.0, self.x_range.1, 5);
-        for x in &x_ticks {
-            let p = self.data_to_pixel(*x, self.y_range.0);
-            let label = self.x_scale.format_tick(*x);
-            self.label.draw_at(cx, dvec2(p.x, p.y + 5.0), &label, TextAnchor::TopCenter);
-        }
-
-        // Y axis tick labels - use scale-aware tick generation and formatting
-        let y_ticks = self.y_scale.generate_ticks(self.y_range.0, self.y_range.1, 5);
-        for y in &y_ticks {
-            let p = self.data_to_pixel(self.x_range.0, *y);
-            let label = self.y_scale.format_tick(*y);
-            self.label.draw_at(cx, dvec2(p.x - 5.0, p.y), &label, TextAnchor::MiddleRight);
-        }
+        self.axes.draw_tick_labels(cx, &mut self.label);
 
         // Title
         if !self.title.is_empty() {
-            let center_x = (self.plot_area.left + self.plot_area.right) / 2.0;
-            self.label.draw_at(cx, dvec2(center_x, self.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
+            let center_x = (self.axes.plot_area.left + self.axes.plot_area.right) / 2.0;
+            self.label.draw_at(cx, dvec2(center_x, self.axes.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
         }
     }
 
@@ -766,7 +804,7 @@ impl LinePlot {
 
     /// Set X axis scale type
     pub fn set_x_scale(&mut self, scale: ScaleType) {
-        self.x_scale = scale;
+        self.axes.x.scale = scale;
         // Recalculate range with scale-aware padding
         if !self.series.is_empty() {
             self.auto_range();
@@ -775,18 +813,145 @@ impl LinePlot {
 
     /// Set Y axis scale type
     pub fn set_y_scale(&mut self, scale: ScaleType) {
-        self.y_scale = scale;
+        self.axes.y.scale = scale;
         // Recalculate range with scale-aware padding
         if !self.series.is_empty() {
             self.auto_range();
         }
     }
 
+    /// Set the tick label format of the X axis
+    pub fn set_x_tick_format(&mut self, formatter: TickFormatter) {
+        self.axes.x.formatter = formatter;
+    }
+
+    /// Set the tick label format of the Y axis
+    pub fn set_y_tick_format(&mut self, formatter: TickFormatter) {
+        self.axes.y.formatter = formatter;
+    }
+
+    /// Show or hide minor ticks and minor grid lines
+    pub fn set_minor_ticks(&mut self, show: bool) {
+        self.axes.set_show_minor(show);
+    }
+
+    /// Set the timezone of a time X axis (`ScaleType::Time`)
+    pub fn set_timezone(&mut self, timezone: TimeZone) {
+        self.axes.x.timezone = timezone;
+    }
+
+    /// Enable hover feedback: crosshair, snap markers on the point of each
+    /// series nearest the pointer's x, a tooltip, and `PlotAction`s
+    pub fn set_hover(&mut self, enabled: bool) {
+        self.hover.enabled = enabled;
+        if !enabled {
+            self.hover.clear();
+        }
+    }
+
+    /// Show or hide the hover crosshair
+    pub fn set_crosshair(&mut self, show: bool) {
+        self.hover.crosshair = show;
+    }
+
+    /// Set the hover tooltip colors and spacing
+    pub fn set_tooltip_style(&mut self, style: TooltipStyle) {
+        self.hover.style = style;
+    }
+
+    /// Add custom lines to the tooltip of each hovered point
+    pub fn set_tooltip_content(&mut self, content: impl Fn(&PickedPoint) -> Option<String> + 'static) {
+        self.hover.content = Some(Box::new(content));
+    }
+
+    /// Points currently under the pointer, closest first
+    pub fn hovered_points(&self) -> &[PickedPoint] {
+        &self.hover.picked
+    }
+
     /// Enable or disable pan/zoom interactivity
     pub fn set_interactive(&mut self, interactive: bool) {
         self.interactive = interactive;
     }
 
+    /// Enable or disable level-of-detail decimation; off draws every point
+    pub fn set_lod(&mut self, enabled: bool) {
+        self.lod.enabled = enabled;
+        self.lod_cache.clear();
+    }
+
+    /// Most points drawn per series; `None` derives it from the plot width
+    pub fn set_point_budget(&mut self, budget: Option<usize>) {
+        self.lod.point_budget = budget;
+        self.lod_cache.clear();
+    }
+
+    /// How series over the point budget are thinned
+    pub fn set_line_decimation(&mut self, method: LineDecimation) {
+        self.lod.line_method = method;
+        self.lod_cache.clear();
+    }
+
+    /// Append points to series `series`, keeping its newest points in a ring
+    /// buffer (see `Series::with_stream`), and scroll the axes to follow them.
+    /// Call `redraw_if_dirty` once per frame to draw the new batch.
+    pub fn push_points(&mut self, series: usize, points: &[(f64, f64)]) {
+        let Some(target) = self.series.get_mut(series) else { return };
+        target.push_points(points);
+        self.follow_stream();
+        self.stream.mark_dirty();
+    }
+
+    /// How the x axis follows streamed points
+    pub fn set_stream_window(&mut self, window: StreamWindow) {
+        self.stream.window = window;
+        self.follow_stream();
+    }
+
+    /// How the y axis follows streamed points
+    pub fn set_stream_y_scale(&mut self, y_scale: StreamYScale) {
+        self.stream.y_scale = y_scale;
+        self.follow_stream();
+    }
+
+    /// Redraw only if points were pushed since the last call, so idle frames
+    /// leave the drawn geometry alone
+    pub fn redraw_if_dirty(&mut self, cx: &mut Cx) -> bool {
+        let dirty = self.stream.take_dirty();
+        if dirty {
+            self.redraw(cx);
+        }
+        dirty
+    }
+
+    /// Move the axes to the stream window and the data visible in it
+    fn follow_stream(&mut self) {
+        if self.series.iter().all(|s| s.stream.is_none()) {
+            return;
+        }
+        let mut data = (f64::MAX, f64::MIN);
+        for s in self.series.iter().filter(|s| !s.is_empty()) {
+            data.0 = data.0.min(s.x_at(0));
+            data.1 = data.1.max(s.x_at(s.len() - 1));
+        }
+        if data.0 > data.1 {
+            return;
+        }
+        self.axes.x.range = self.stream.x_range(self.axes.x.range, data);
+
+        let (x_lo, x_hi) = self.axes.x.range;
+        let mut visible = (f64::MAX, f64::MIN);
+        for s in &self.series {
+            for (x, y) in s.points() {
+                if x >= x_lo && x <= x_hi && y.is_finite() {
+                    visible.0 = visible.0.min(y);
+                    visible.1 = visible.1.max(y);
+                }
+            }
+        }
+        self.axes.y.range = self.stream.y_range(self.axes.y.range, visible);
+    }
+
     /// Reset view to auto-fit all data
     pub fn reset_view(&mut self) {
         self.auto_range();
@@ -799,31 +964,8 @@ impl LinePlot {
             let start = self.data_to_pixel(arrow.start_x, arrow.start_y);
             let end = self.data_to_pixel(arrow.end_x, arrow.end_y);
 
-            // Draw arrow line
             self.draw_line.color = arrow.color;
-            self.draw_line.draw_line(cx, start, end, arrow.line_width);
-
-            // Draw arrowhead at the end point
-            let dx = end.x - start.x;
-            let dy = end.y - start.y;
-            let len = (dx * dx + dy * dy).sqrt();
-            if len > 0.0 {
-                let ux = dx / len;
-                let uy = dy / len;
-                let head_size = arrow.head_size;
-
-                // Arrowhead points
-                let head_base = dvec2(end.x - ux * head_size, end.y - uy * head_size);
-                let perp_x = -uy * head_size * 0.5;
-                let perp_y = ux * head_size * 0.5;
-
-                let left = dvec2(head_base.x + perp_x, head_base.y + perp_y);
-                let right = dvec2(head_base.x - perp_x, head_base.y - perp_y);
-
-                // Draw arrowhead as two lines forming a V
-                self.draw_line.draw_line(cx, end, left, arrow.line_width);
-                self.draw_line.draw_line(cx, end, right, arrow.line_width);
-            }
+            self.draw_line.draw_arrow(cx, start, end, arrow.line_width, arrow.head_size);
 
             // Draw optional text near the start
             if let Some(ref text) = arrow.text {
@@ -838,10 +980,9 @@ impl LinePlot {
         for ann in &annotations {
             let p = self.data_to_pixel(ann.x, ann.y);
             if ann.is_math {
-                // Render math annotations as plain text (LaTeX not available without math_widget)
                 self.math_label.set_color(ann.color);
                 self.math_label.set_font_size(ann.font_size);
-                self.math_label.draw_at(cx, p, &ann.text, TextAnchor::Center);
+                self.math_label.draw_latex_at(cx, p, &ann.text, TextAnchor::Center);
             } else {
                 // Use plain text label
                 self.label.set_color(ann.color);
@@ -867,20 +1008,20 @@ impl LinePlot {
         // Position legend based on setting
         let (legend_x, legend_y) = match self.legend_position {
             LegendPosition::TopRight => (
-                self.plot_area.right - legend_width - 10.0,
-                self.plot_area.top + 10.0,
+                self.axes.plot_area.right - legend_width - 10.0,
+                self.axes.plot_area.top + 10.0,
             ),
             LegendPosition::TopLeft => (
-                self.plot_area.left + 10.0,
-                self.plot_area.top + 10.0,
+                self.axes.plot_area.left + 10.0,
+                self.axes.plot_area.top + 10.0,
             ),
             LegendPosition::BottomRight => (
-                self.plot_area.right - legend_width - 10.0,
-                self.plot_area.bottom - legend_height - 10.0,
+                self.axes.plot_area.right - legend_width - 10.0,
+                self.axes.plot_area.bottom - legend_height - 10.0,
             ),
             LegendPosition::BottomLeft => (
-                self.plot_area.left + 10.0,
-                self.plot_area.bottom - legend_height - 10.0,
+                self.axes.plot_area.left + 10.0,
+                self.axes.plot_area.bottom - legend_height - 10.0,
             ),
             LegendPosition::None => return,
         };
@@ -925,6 +1066,41 @@ impl LinePlot {
     }
 }
 
+/// Fill between the curves `y1` and `y2` over `x` with thin vertical strips
+pub(super) fn draw_fill_between(cx: &mut Cx2d, draw_fill: &mut DrawPlotFill, axes: &Axes, x: &[f64], y1: &[f64], y2: &[f64]) {
+    let n = x.len().min(y1.len()).min(y2.len());
+    for i in 1..n {
+        let (x1, x2) = (x[i - 1], x[i]);
+        let (y1_a, y1_b) = (y1[i - 1], y1[i]);
+        let (y2_a, y2_b) = (y2[i - 1], y2[i]);
+
+        // Draw a series of thin vertical strips to approximate the fill
+        let steps = 4;
+        for s in 0..steps {
+            let t1 = s as f64 / steps as f64;
+            let t2 = (s + 1) as f64 / steps as f64;
+            let x_left = x1 + (x2 - x1) * t1;
+            let x_right = x1 + (x2 - x1) * t2;
+            let y1_left = y1_a + (y1_b - y1_a) * t1;
+            let y2_left = y2_a + (y2_b - y2_a) * t1;
+            let y1_right = y1_a + (y1_b - y1_a) * t2;
+            let y2_right = y2_a + (y2_b - y2_a) * t2;
+
+            let p_tl = axes.data_to_pixel(x_left, y1_left);
+            let p_bl = axes.data_to_pixel(x_left, y2_left);
+            let p_tr = axes.data_to_pixel(x_right, y1_right);
+            let p_br = axes.data_to_pixel(x_right, y2_right);
+
+            let left = p_tl.x.min(p_bl.x);
+            let right = p_tr.x.max(p_br.x);
+            let top = p_tl.y.min(p_tr.y).min(p_bl.y).min(p_br.y);
+            let bottom = p_tl.y.max(p_tr.y).max(p_bl.y).max(p_br.y);
+
+            draw_fill.draw_fill_strip(cx, left, right - left, top, bottom);
+        }
+    }
+}
+
 impl LinePlotRef {
     pub fn add_series(&self, series: Series) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -938,6 +1114,22 @@ impl LinePlotRef {
         }
     }
 
+    pub fn add_regression(&self, series: usize, degree: usize) -> Option<PolyFit> {
+        self.borrow_mut().and_then(|mut inner| inner.add_regression(series, degree))
+    }
+
+    pub fn add_lowess(&self, series: usize, frac: f64) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.add_lowess(series, frac);
+        }
+    }
+
+    pub fn set_rug(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_rug(show);
+        }
+    }
+
     pub fn set_title(&self, title: impl Into<String>) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.set_title(title);
@@ -974,12 +1166,123 @@ impl LinePlotRef {
         }
     }
 
+    pub fn set_x_tick_format(&self, formatter: TickFormatter) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_x_tick_format(formatter);
+        }
+    }
+
+    pub fn set_y_tick_format(&self, formatter: TickFormatter) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_y_tick_format(formatter);
+        }
+    }
+
+    pub fn set_minor_ticks(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_minor_ticks(show);
+        }
+    }
+
+    pub fn set_timezone(&self, timezone: TimeZone) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_timezone(timezone);
+        }
+    }
+
+    pub fn set_hover(&self, enabled: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_hover(enabled);
+        }
+    }
+
+    pub fn set_crosshair(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_crosshair(show);
+        }
+    }
+
+    pub fn set_tooltip_style(&self, style: TooltipStyle) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_tooltip_style(style);
+        }
+    }
+
+    pub fn set_tooltip_content(&self, content: impl Fn(&PickedPoint) -> Option<String> + 'static) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_tooltip_content(content);
+        }
+    }
+
+    /// Points now under the pointer, if the hover changed
+    pub fn hovered(&self, actions: &Actions) -> Option<Vec<PickedPoint>> {
+        if let Some(item) = actions.find_widget_action(self.widget_uid()) {
+            if let PlotAction::Hover(points) = item.cast() {
+                return Some(points);
+            }
+        }
+        None
+    }
+
+    /// The point that was clicked, if any
+    pub fn picked(&self, actions: &Actions) -> Option<PickedPoint> {
+        if let Some(item) = actions.find_widget_action(self.widget_uid()) {
+            if let PlotAction::Pick(point) = item.cast() {
+                return Some(point);
+            }
+        }
+        None
+    }
+
     pub fn set_interactive(&self, interactive: bool) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.set_interactive(interactive);
         }
     }
 
+    pub fn set_lod(&self, enabled: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_lod(enabled);
+        }
+    }
+
+    pub fn set_point_budget(&self, budget: Option<usize>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_point_budget(budget);
+        }
+    }
+
+    pub fn set_line_decimation(&self, method: LineDecimation) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_line_decimation(method);
+        }
+    }
+
+    pub fn push_points(&self, series: usize, points: &[(f64, f64)]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.push_points(series, points);
+        }
+    }
+
+    pub fn set_stream_window(&self, window: StreamWindow) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_stream_window(window);
+        }
+    }
+
+    pub fn set_stream_y_scale(&self, y_scale: StreamYScale) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_stream_y_scale(y_scale);
+        }
+    }
+
+    pub fn redraw_if_dirty(&self, cx: &mut Cx) -> bool {
+        if let Some(mut inner) = self.borrow_mut() {
+            return inner.redraw_if_dirty(cx);
+        }
+        false
+    }
+
     pub fn reset_view(&self) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.reset_view();
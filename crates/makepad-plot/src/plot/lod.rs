@@ -0,0 +1,400 @@
+// Level-of-detail decimation for large line and scatter series
+//
+// Plots draw a subset of each series picked for the current view: points
+// outside the visible x range are culled, lines keep the first, last,
+// minimum and maximum point of every pixel column (or an LTTB selection),
+// and scatter markers are thinned to one per occupied pixel cell. The
+// selections are cached per view so repeated frames at the same zoom level
+// cost nothing.
+
+use std::collections::{HashSet, VecDeque};
+
+use makepad_widgets::*;
+use super::*;
+
+/// How line series are thinned once they exceed the point budget
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LineDecimation {
+    /// First, last, min and max point per pixel column. Keeps every spike,
+    /// the drawn envelope matches the full data.
+    #[default]
+    MinMax,
+    /// Largest-Triangle-Three-Buckets, a visually faithful fixed-size sample
+    Lttb,
+}
+
+/// Level-of-detail settings of a line or scatter plot
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LodOptions {
+    /// Off draws every point
+    pub enabled: bool,
+    /// Most points drawn per series; `None` derives it from the plot width
+    /// for lines and uses 10 000 for scatter markers
+    pub point_budget: Option<usize>,
+    pub line_method: LineDecimation,
+}
+
+impl Default for LodOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            point_budget: None,
+            line_method: LineDecimation::MinMax,
+        }
+    }
+}
+
+const DEFAULT_SCATTER_BUDGET: usize = 10_000;
+const CACHED_VIEWS: usize = 8;
+
+/// View a selection was computed for
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LodKey {
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+    size: (f64, f64),
+    points: usize,
+    series: usize,
+    revision: u64,
+}
+
+impl LodKey {
+    pub fn new(axes: &Axes, series: &[Series]) -> Self {
+        Self {
+            x_range: axes.x.range,
+            y_range: axes.y.range,
+            size: (axes.plot_area.width(), axes.plot_area.height()),
+            points: series.iter().map(Series::len).sum(),
+            series: series.len(),
+            revision: series.iter().map(Series::revision).sum(),
+        }
+    }
+}
+
+/// Point indices to draw per series, for the last few views
+#[derive(Clone, Debug, Default)]
+pub struct LodCache {
+    views: VecDeque<(LodKey, Vec<Vec<usize>>)>,
+}
+
+impl LodCache {
+    /// Cached selection for `key`, computing and storing it on a miss
+    pub fn get_or_insert(&mut self, key: LodKey, compute: impl FnOnce() -> Vec<Vec<usize>>) -> &[Vec<usize>] {
+        if let Some(pos) = self.views.iter().position(|(k, _)| *k == key) {
+            // Most recently used views live at the front
+            let view = self.views.remove(pos).unwrap();
+            self.views.push_front(view);
+        } else {
+            self.views.push_front((key, compute()));
+            self.views.truncate(CACHED_VIEWS);
+        }
+        &self.views[0].1
+    }
+
+    /// Forget all selections, after the data changed
+    pub fn clear(&mut self) {
+        self.views.clear();
+    }
+}
+
+/// True if x never decreases, allowing binary search and per-column binning
+fn is_sorted_x(series: &Series) -> bool {
+    (1..series.len()).all(|i| series.x_at(i - 1) <= series.x_at(i))
+}
+
+/// First index in `[lo, hi)` whose x is not below `x`, for sorted series
+fn lower_bound(series: &Series, mut lo: usize, mut hi: usize, x: f64) -> usize {
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if series.x_at(mid) < x {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// Indices of `series` to draw as a line in the current view
+pub fn decimate_line(series: &Series, axes: &Axes, options: &LodOptions) -> Vec<usize> {
+    let n = series.len();
+    if !options.enabled {
+        return (0..n).collect();
+    }
+    let budget = options.point_budget.unwrap_or((axes.plot_area.width().max(1.0) * 4.0) as usize).max(3);
+
+    if !is_sorted_x(series) {
+        // No visible window to cut out; thin in drawing order
+        let all: Vec<usize> = (0..n).collect();
+        return if n > budget { lttb(series, &all, budget) } else { all };
+    }
+
+    // Visible window plus one point either side so lines reach the edges
+    let (x_min, x_max) = (axes.x.range.0.min(axes.x.range.1), axes.x.range.0.max(axes.x.range.1));
+    let start = lower_bound(series, 0, n, x_min).saturating_sub(1);
+    let end = (lower_bound(series, start, n, x_max) + 1).min(n);
+    let visible: Vec<usize> = (start..end).collect();
+    if visible.len() <= budget {
+        return visible;
+    }
+
+    let selected = match options.line_method {
+        LineDecimation::MinMax => min_max_per_column(series, axes, &visible),
+        LineDecimation::Lttb => lttb(series, &visible, budget),
+    };
+    if selected.len() > budget {
+        lttb(series, &selected, budget)
+    } else {
+        selected
+    }
+}
+
+/// First, last, lowest and highest point of each pixel column (M4)
+fn min_max_per_column(series: &Series, axes: &Axes, indices: &[usize]) -> Vec<usize> {
+    let mut out = Vec::new();
+    let mut column: Option<(i64, [usize; 4])> = None;
+    let flush = |out: &mut Vec<usize>, picks: [usize; 4]| {
+        let mut picks = picks;
+        picks.sort_unstable();
+        for (k, &i) in picks.iter().enumerate() {
+            if k == 0 || picks[k - 1] != i {
+                out.push(i);
+            }
+        }
+    };
+
+    for &i in indices {
+        let (x, y) = series.point(i);
+        if !x.is_finite() || !y.is_finite() {
+            continue;
+        }
+        let col = axes.x_to_pixel(x).floor() as i64;
+        match &mut column {
+            Some((c, picks)) if *c == col => {
+                // [first, last, min, max]
+                picks[1] = i;
+                if y < series.y_at(picks[2]) {
+                    picks[2] = i;
+                }
+                if y > series.y_at(picks[3]) {
+                    picks[3] = i;
+                }
+            }
+            _ => {
+                if let Some((_, picks)) = column.take() {
+                    flush(&mut out, picks);
+                }
+                column = Some((col, [i; 4]));
+            }
+        }
+    }
+    if let Some((_, picks)) = column {
+        flush(&mut out, picks);
+    }
+    out
+}
+
+/// Largest-Triangle-Three-Buckets over `indices`, keeping `threshold` points
+pub fn lttb(series: &Series, indices: &[usize], threshold: usize) -> Vec<usize> {
+    let n = indices.len();
+    if threshold >= n || threshold < 3 {
+        return indices.to_vec();
+    }
+
+    let mut out = Vec::with_capacity(threshold);
+    out.push(indices[0]);
+    let bucket_size = (n - 2) as f64 / (threshold - 2) as f64;
+    let mut a = 0;
+
+    for bucket in 0..threshold - 2 {
+        let start = (bucket as f64 * bucket_size) as usize + 1;
+        let end = (((bucket + 1) as f64 * bucket_size) as usize + 1).min(n - 1);
+
+        // Average of the next bucket is the third triangle vertex
+        let next_start = end;
+        let next_end = (((bucket + 2) as f64 * bucket_size) as usize + 1).min(n);
+        let count = (next_end - next_start).max(1) as f64;
+        let (mut avg_x, mut avg_y) = (0.0, 0.0);
+        for &i in &indices[next_start..next_end] {
+            let (x, y) = series.point(i);
+            avg_x += x;
+            avg_y += y;
+        }
+        avg_x /= count;
+        avg_y /= count;
+
+        let (ax, ay) = series.point(indices[a]);
+        let mut best = start;
+        let mut best_area = -1.0;
+        for (k, &i) in indices.iter().enumerate().take(end).skip(start) {
+            let (x, y) = series.point(i);
+            let area = ((ax - avg_x) * (y - ay) - (ax - x) * (avg_y - ay)).abs();
+            if area > best_area {
+                best_area = area;
+                best = k;
+            }
+        }
+        out.push(indices[best]);
+        a = best;
+    }
+
+    out.push(indices[n - 1]);
+    out
+}
+
+/// Indices of `series` to draw as markers of `radius` pixels in the current
+/// view: off-screen points are culled and, over budget, points sharing a
+/// pixel cell are drawn once, growing the cell until the budget is met
+pub fn cull_scatter(series: &Series, axes: &Axes, radius: f64, options: &LodOptions) -> Vec<usize> {
+    let n = series.len();
+    if !options.enabled {
+        return (0..n).collect();
+    }
+    let budget = options.point_budget.unwrap_or(DEFAULT_SCATTER_BUDGET).max(1);
+
+    let area = axes.plot_area;
+    let visible: Vec<(usize, DVec2)> = (0..n)
+        .filter_map(|i| {
+            let (x, y) = series.point(i);
+            if !x.is_finite() || !y.is_finite() {
+                return None;
+            }
+            let p = axes.data_to_pixel(x, y);
+            let inside = p.x >= area.left - radius && p.x <= area.right + radius
+                && p.y >= area.top - radius && p.y <= area.bottom + radius;
+            inside.then_some((i, p))
+        })
+        .collect();
+    if visible.len() <= budget {
+        return visible.into_iter().map(|(i, _)| i).collect();
+    }
+
+    let mut cell = 1.0;
+    loop {
+        let mut occupied = HashSet::new();
+        let kept: Vec<usize> = visible
+            .iter()
+            .filter(|(_, p)| occupied.insert(((p.x / cell).floor() as i64, (p.y / cell).floor() as i64)))
+            .map(|(i, _)| *i)
+            .collect();
+        if kept.len() <= budget {
+            return kept;
+        }
+        cell *= 2.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn axes(x_range: (f64, f64), y_range: (f64, f64), width: f64) -> Axes {
+        let mut axes = Axes::with_margins(0.0, 0.0, 0.0, 0.0);
+        axes.update_plot_area(Rect { pos: dvec2(0.0, 0.0), size: dvec2(width, 100.0) });
+        axes.x.range = x_range;
+        axes.y.range = y_range;
+        axes
+    }
+
+    fn wave(n: usize) -> Series {
+        let x: Vec<f64> = (0..n).map(|i| i as f64).collect();
+        let y: Vec<f64> = x.iter().map(|x| (x * 0.1).sin()).collect();
+        Series::new("wave").with_data(x, y)
+    }
+
+    #[test]
+    fn test_lttb_keeps_ends_and_count() {
+        let series = wave(1000);
+        let all: Vec<usize> = (0..1000).collect();
+        for threshold in [3, 10, 97, 500] {
+            let picked = lttb(&series, &all, threshold);
+            assert_eq!(picked.len(), threshold);
+            assert_eq!(picked[0], 0);
+            assert_eq!(picked[threshold - 1], 999);
+            assert!(picked.windows(2).all(|w| w[0] < w[1]));
+        }
+        // Under the threshold, or too small to bucket, nothing is dropped
+        assert_eq!(lttb(&series, &all[..50], 100), all[..50].to_vec());
+        assert_eq!(lttb(&series, &all, 2), all);
+    }
+
+    #[test]
+    fn test_lttb_keeps_spike() {
+        let mut series = wave(1000);
+        series.y[437] = 50.0;
+        let all: Vec<usize> = (0..1000).collect();
+        assert!(lttb(&series, &all, 50).contains(&437));
+    }
+
+    #[test]
+    fn test_decimate_line_window() {
+        let series = wave(1000);
+        let options = LodOptions::default();
+        // Visible window plus one point either side
+        let picked = decimate_line(&series, &axes((100.5, 200.5), (-1.0, 1.0), 400.0), &options);
+        assert_eq!(picked, (100..202).collect::<Vec<_>>());
+
+        let off = LodOptions { enabled: false, ..options };
+        assert_eq!(decimate_line(&series, &axes((100.0, 200.0), (-1.0, 1.0), 400.0), &off).len(), 1000);
+    }
+
+    #[test]
+    fn test_decimate_line_budget() {
+        let mut series = wave(100_000);
+        series.y[31_337] = -20.0;
+        let axes = axes((0.0, 99_999.0), (-20.0, 1.0), 100.0);
+        for line_method in [LineDecimation::MinMax, LineDecimation::Lttb] {
+            let options = LodOptions { line_method, ..LodOptions::default() };
+            let picked = decimate_line(&series, &axes, &options);
+            assert!(picked.len() <= 400, "{:?} kept {}", line_method, picked.len());
+            assert_eq!(picked[0], 0);
+            assert_eq!(*picked.last().unwrap(), 99_999);
+            assert!(picked.contains(&31_337), "{:?} lost the spike", line_method);
+        }
+    }
+
+    #[test]
+    fn test_cull_scatter() {
+        // Ten copies of a 10x10 grid, half of it off screen
+        let mut x = Vec::new();
+        let mut y = Vec::new();
+        for _ in 0..10 {
+            for i in 0..10 {
+                for j in 0..10 {
+                    x.push(i as f64 * 20.0);
+                    y.push(j as f64 * 10.0);
+                }
+            }
+        }
+        let series = Series::new("grid").with_data(x, y);
+        let axes = axes((0.0, 100.0), (0.0, 100.0), 100.0);
+        let options = LodOptions { point_budget: Some(100), ..LodOptions::default() };
+        let picked = cull_scatter(&series, &axes, 1.0, &options);
+        assert_eq!(picked.len(), 60);
+        assert!(picked.iter().all(|&i| series.x[i] <= 100.0));
+
+        let tight = LodOptions { point_budget: Some(10), ..options };
+        assert!(cull_scatter(&series, &axes, 1.0, &tight).len() <= 10);
+    }
+
+    #[test]
+    fn test_lod_cache() {
+        let series = vec![wave(10)];
+        let mut cache = LodCache::default();
+        let mut computed = 0;
+        for i in 0..CACHED_VIEWS + 1 {
+            let key = LodKey::new(&axes((0.0, i as f64 + 1.0), (-1.0, 1.0), 100.0), &series);
+            cache.get_or_insert(key, || {
+                computed += 1;
+                vec![vec![i]]
+            });
+        }
+        assert_eq!(computed, CACHED_VIEWS + 1);
+
+        // The newest view is still cached, the oldest was evicted
+        let newest = LodKey::new(&axes((0.0, CACHED_VIEWS as f64 + 1.0), (-1.0, 1.0), 100.0), &series);
+        assert_eq!(cache.get_or_insert(newest, || unreachable!()), &[vec![CACHED_VIEWS]]);
+        let oldest = LodKey::new(&axes((0.0, 1.0), (-1.0, 1.0), 100.0), &series);
+        assert_eq!(cache.get_or_insert(oldest, || vec![vec![42]]), &[vec![42]]);
+    }
+}
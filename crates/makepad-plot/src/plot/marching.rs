@@ -0,0 +1,418 @@
+// Marching squares over a rectilinear grid
+//
+// Isolines are traced cell by cell and joined into polylines through the
+// grid edges they cross. Isobands are built from the same per-cell chords:
+// the region above `lo` and the region below `hi` are each at most two
+// convex pieces of the cell, and the band is their intersection. Saddle
+// cells are resolved with the mean of the four corners, identically for
+// lines and bands, so fills and outlines always agree.
+
+use std::collections::HashMap;
+
+use makepad_widgets::*;
+
+/// Scalar field sampled on a rectilinear grid: `z[row][col]` lies at
+/// `(x[col], y[row])`. Coordinates may be unevenly spaced.
+#[derive(Clone, Copy, Debug)]
+pub struct ContourGrid<'a> {
+    pub x: &'a [f64],
+    pub y: &'a [f64],
+    pub z: &'a [Vec<f64>],
+}
+
+/// Part of one band inside a grid cell, in data coordinates
+#[derive(Clone, Debug, PartialEq)]
+pub enum BandPiece {
+    /// The whole cell lies in the band; opposite corners
+    Cell { from: DVec2, to: DVec2 },
+    /// Convex polygon cut out of the cell
+    Polygon(Vec<DVec2>),
+}
+
+/// Grid edge a contour crosses: horizontal edges run from `(col, row)` to
+/// `(col + 1, row)`, vertical ones from `(col, row)` to `(col, row + 1)`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum EdgeKey {
+    Horizontal(usize, usize),
+    Vertical(usize, usize),
+}
+
+struct Cell {
+    corners: [DVec2; 4],
+    values: [f64; 4],
+    edges: [EdgeKey; 4],
+    center: f64,
+}
+
+impl<'a> ContourGrid<'a> {
+    pub fn new(x: &'a [f64], y: &'a [f64], z: &'a [Vec<f64>]) -> Self {
+        Self { x, y, z }
+    }
+
+    fn rows(&self) -> usize {
+        self.z.len().min(self.y.len())
+    }
+
+    fn cols(&self) -> usize {
+        self.z.iter().map(Vec::len).min().unwrap_or(0).min(self.x.len())
+    }
+
+    /// Smallest and largest finite value
+    pub fn value_range(&self) -> Option<(f64, f64)> {
+        let (mut min, mut max) = (f64::MAX, f64::MIN);
+        for &v in self.z.iter().flatten().filter(|v| v.is_finite()) {
+            min = min.min(v);
+            max = max.max(v);
+        }
+        (min <= max).then_some((min, max))
+    }
+
+    /// Corners in order around the cell: (col, row), (col + 1, row),
+    /// (col + 1, row + 1), (col, row + 1). Cells touching a non-finite value
+    /// are skipped.
+    fn cell(&self, row: usize, col: usize) -> Option<Cell> {
+        let values = [
+            self.z[row][col],
+            self.z[row][col + 1],
+            self.z[row + 1][col + 1],
+            self.z[row + 1][col],
+        ];
+        if values.iter().any(|v| !v.is_finite()) {
+            return None;
+        }
+        let (x0, x1, y0, y1) = (self.x[col], self.x[col + 1], self.y[row], self.y[row + 1]);
+        Some(Cell {
+            corners: [dvec2(x0, y0), dvec2(x1, y0), dvec2(x1, y1), dvec2(x0, y1)],
+            values,
+            edges: [
+                EdgeKey::Horizontal(row, col),
+                EdgeKey::Vertical(row, col + 1),
+                EdgeKey::Horizontal(row + 1, col),
+                EdgeKey::Vertical(row, col),
+            ],
+            center: values.iter().sum::<f64>() / 4.0,
+        })
+    }
+
+    fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        let (rows, cols) = (self.rows(), self.cols());
+        (0..rows.saturating_sub(1))
+            .flat_map(move |row| (0..cols.saturating_sub(1)).map(move |col| (row, col)))
+            .filter_map(|(row, col)| self.cell(row, col))
+    }
+}
+
+impl Cell {
+    /// Where `level` crosses edge `k` (from corner `k` to corner `k + 1`)
+    fn crossing(&self, k: usize, level: f64) -> DVec2 {
+        let (a, b) = (self.values[k], self.values[(k + 1) % 4]);
+        let t = if (b - a).abs() < 1e-12 { 0.5 } else { ((level - a) / (b - a)).clamp(0.0, 1.0) };
+        self.corners[k] + (self.corners[(k + 1) % 4] - self.corners[k]) * t
+    }
+
+    /// Chords separating corners where `inside` holds from the rest, as pairs
+    /// of crossed edges. In saddle cells the second edge starts at the corner
+    /// the chord cuts off.
+    fn chords(&self, inside: [bool; 4], center_inside: bool) -> Vec<(usize, usize)> {
+        let count = inside.iter().filter(|&&b| b).count();
+        if count == 0 || count == 4 {
+            return Vec::new();
+        }
+        let saddle = inside[0] == inside[2] && inside[1] == inside[3] && inside[0] != inside[1];
+        if saddle {
+            // Cut off the corners on the side the center does not join up
+            (0..4)
+                .filter(|&k| inside[k] != center_inside)
+                .map(|k| ((k + 3) % 4, k))
+                .collect()
+        } else {
+            let crossed: Vec<usize> = (0..4).filter(|&k| inside[k] != inside[(k + 1) % 4]).collect();
+            vec![(crossed[0], crossed[1])]
+        }
+    }
+
+    /// Convex pieces of the cell where `inside` holds at `level`
+    fn region(&self, level: f64, inside: [bool; 4], center_inside: bool) -> Vec<Vec<DVec2>> {
+        let count = inside.iter().filter(|&&b| b).count();
+        if count == 0 {
+            return Vec::new();
+        }
+        let square = self.corners.to_vec();
+        if count == 4 {
+            return vec![square];
+        }
+        // Inside corners may sit on a chord when they equal the level, but
+        // their centroid only does when the piece has no area
+        let reference = (0..4).filter(|&k| inside[k]).fold(dvec2(0.0, 0.0), |acc, k| acc + self.corners[k]) * (1.0 / count as f64);
+        let chords = self.chords(inside, center_inside);
+        let size = (self.corners[2] - self.corners[0]).length();
+        let cut = |poly: &[DVec2], (e0, e1): (usize, usize), keep: DVec2| {
+            let (a, b) = (self.crossing(e0, level), self.crossing(e1, level));
+            if (b - a).length() <= size * 1e-9 {
+                // A corner equal to the level: the chord shrinks onto it and
+                // cuts away all of the piece or none of it
+                let corner = (0..4).min_by(|&i, &j| {
+                    (self.corners[i] - a).length().total_cmp(&(self.corners[j] - a).length())
+                });
+                return if corner.is_some_and(|k| inside[k]) { Vec::new() } else { poly.to_vec() };
+            }
+            clip_half_plane(poly, a, b, keep)
+        };
+        if chords.len() == 2 && !center_inside {
+            // Two separate corners: one triangle each
+            chords
+                .iter()
+                .map(|&chord| cut(&square, chord, self.corners[chord.1]))
+                .collect()
+        } else {
+            let poly = chords
+                .iter()
+                .fold(square.clone(), |poly, &chord| cut(&poly, chord, reference));
+            vec![poly]
+        }
+    }
+}
+
+/// Keep the part of convex `poly` on the same side of line `a`-`b` as
+/// `keep`; nothing if `keep` lies on the line
+fn clip_half_plane(poly: &[DVec2], a: DVec2, b: DVec2, keep: DVec2) -> Vec<DVec2> {
+    let dir = b - a;
+    let side = |p: DVec2| dir.x * (p.y - a.y) - dir.y * (p.x - a.x);
+    let keep_side = side(keep);
+    if keep_side == 0.0 || poly.is_empty() {
+        return Vec::new();
+    }
+    let sign = keep_side.signum();
+    let mut out = Vec::with_capacity(poly.len() + 1);
+    for (i, &p) in poly.iter().enumerate() {
+        let q = poly[(i + 1) % poly.len()];
+        let (sp, sq) = (side(p) * sign, side(q) * sign);
+        if sp >= 0.0 {
+            out.push(p);
+        }
+        if (sp > 0.0 && sq < 0.0) || (sp < 0.0 && sq > 0.0) {
+            out.push(p + (q - p) * (sp / (sp - sq)));
+        }
+    }
+    out
+}
+
+/// Intersection of two convex polygons
+fn clip_convex(subject: &[DVec2], clip: &[DVec2]) -> Vec<DVec2> {
+    let n = clip.len();
+    if n < 3 {
+        return Vec::new();
+    }
+    // A point inside the clip polygon tells which side of each edge to keep
+    let inner = clip.iter().fold(dvec2(0.0, 0.0), |acc, &p| acc + p) * (1.0 / n as f64);
+    let mut out = subject.to_vec();
+    for i in 0..n {
+        if out.len() < 3 {
+            return Vec::new();
+        }
+        out = clip_half_plane(&out, clip[i], clip[(i + 1) % n], inner);
+    }
+    out
+}
+
+fn polygon_area(poly: &[DVec2]) -> f64 {
+    let mut area = 0.0;
+    for (i, p) in poly.iter().enumerate() {
+        let q = poly[(i + 1) % poly.len()];
+        area += p.x * q.y - q.x * p.y;
+    }
+    area.abs() / 2.0
+}
+
+/// Isolines of `level` as connected polylines in data coordinates. Closed
+/// loops repeat their first point at the end.
+pub fn isolines(grid: &ContourGrid, level: f64) -> Vec<Vec<DVec2>> {
+    let mut points: HashMap<EdgeKey, DVec2> = HashMap::new();
+    let mut chords: Vec<(EdgeKey, EdgeKey)> = Vec::new();
+    for cell in grid.cells() {
+        let inside = cell.values.map(|v| v >= level);
+        for (e0, e1) in cell.chords(inside, cell.center >= level) {
+            points.insert(cell.edges[e0], cell.crossing(e0, level));
+            points.insert(cell.edges[e1], cell.crossing(e1, level));
+            chords.push((cell.edges[e0], cell.edges[e1]));
+        }
+    }
+
+    // Every crossed edge is shared by at most two cells, so each edge point
+    // joins at most two chords and the chords form simple chains
+    let mut touching: HashMap<EdgeKey, Vec<usize>> = HashMap::new();
+    for (i, &(a, b)) in chords.iter().enumerate() {
+        touching.entry(a).or_default().push(i);
+        touching.entry(b).or_default().push(i);
+    }
+
+    let mut used = vec![false; chords.len()];
+    let mut lines = Vec::new();
+    for start in 0..chords.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let mut chain = vec![chords[start].0, chords[start].1];
+        // Extend the tail, then flip and extend what was the head
+        for _ in 0..2 {
+            loop {
+                let tail = *chain.last().unwrap();
+                let next = touching[&tail].iter().copied().find(|&c| !used[c]);
+                let Some(next) = next else { break };
+                used[next] = true;
+                let (a, b) = chords[next];
+                chain.push(if a == tail { b } else { a });
+                if chain.last() == chain.first() {
+                    break;
+                }
+            }
+            if chain.last() == chain.first() {
+                break;
+            }
+            chain.reverse();
+        }
+        lines.push(chain.iter().map(|key| points[key]).collect());
+    }
+    lines
+}
+
+/// Filled region with `lo <= z < hi`, as pieces of grid cells in data
+/// coordinates
+pub fn isoband(grid: &ContourGrid, lo: f64, hi: f64) -> Vec<BandPiece> {
+    let mut pieces = Vec::new();
+    for cell in grid.cells() {
+        let above = cell.values.map(|v| v >= lo);
+        let below = cell.values.map(|v| v < hi);
+        if above.iter().all(|&b| !b) || below.iter().all(|&b| !b) {
+            continue;
+        }
+        if above.iter().all(|&b| b) && below.iter().all(|&b| b) {
+            pieces.push(BandPiece::Cell { from: cell.corners[0], to: cell.corners[2] });
+            continue;
+        }
+        let upper = cell.region(lo, above, cell.center >= lo);
+        let lower = cell.region(hi, below, cell.center < hi);
+        for a in &upper {
+            for b in &lower {
+                let poly = clip_convex(a, b);
+                if poly.len() >= 3 && polygon_area(&poly) > 0.0 {
+                    pieces.push(BandPiece::Polygon(poly));
+                }
+            }
+        }
+    }
+    pieces
+}
+
+/// `n` levels evenly spaced strictly inside `(min, max)`
+pub fn auto_levels(min: f64, max: f64, n: usize) -> Vec<f64> {
+    (1..=n).map(|i| min + i as f64 * (max - min) / (n + 1) as f64).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn band_area(pieces: &[BandPiece]) -> f64 {
+        pieces
+            .iter()
+            .map(|piece| match piece {
+                BandPiece::Cell { from, to } => ((to.x - from.x) * (to.y - from.y)).abs(),
+                BandPiece::Polygon(poly) => polygon_area(poly),
+            })
+            .sum()
+    }
+
+    fn near(a: DVec2, b: DVec2) -> bool {
+        (a - b).length() < 1e-9
+    }
+
+    #[test]
+    fn test_contour_closes() {
+        // Paraboloid peak in the middle of a 5x5 grid
+        let axis = [-2.0, -1.0, 0.0, 1.0, 2.0];
+        let z: Vec<Vec<f64>> = axis.iter().map(|y| axis.iter().map(|x| -(x * x + y * y)).collect()).collect();
+        let grid = ContourGrid::new(&axis, &axis, &z);
+        let lines = isolines(&grid, -1.5);
+        assert_eq!(lines.len(), 1);
+        // The plus shape of corners above the level crosses twelve edges
+        let line = &lines[0];
+        assert_eq!(line.len(), 13);
+        assert!(near(line[0], *line.last().unwrap()));
+        // Every point lies on a grid edge between the peak and the ring
+        for p in line {
+            assert!(p.x.fract() == 0.0 || p.y.fract() == 0.0);
+            assert!(p.length() > 0.5 && p.length() < 1.6);
+        }
+    }
+
+    #[test]
+    fn test_open_contour_spans_grid() {
+        // Plane rising to the right: one straight line from bottom to top
+        let x = [0.0, 1.0, 2.0];
+        let y = [0.0, 1.0, 2.0, 3.0];
+        let z: Vec<Vec<f64>> = y.iter().map(|_| x.to_vec()).collect();
+        let lines = isolines(&ContourGrid::new(&x, &y, &z), 0.5);
+        assert_eq!(lines.len(), 1);
+        let mut ys: Vec<f64> = lines[0].iter().map(|p| p.y).collect();
+        ys.sort_by(f64::total_cmp);
+        assert_eq!(ys, vec![0.0, 1.0, 2.0, 3.0]);
+        assert!(lines[0].iter().all(|p| p.x == 0.5));
+    }
+
+    #[test]
+    fn test_saddle_resolved_by_center() {
+        let axis = [0.0, 1.0];
+        let z = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        let grid = ContourGrid::new(&axis, &axis, &z);
+
+        // Center 0.5 is inside at level 0.5, so the high corners join and
+        // the chords cut off the low corners (1, 0) and (0, 1)
+        let lines = isolines(&grid, 0.5);
+        assert_eq!(lines.len(), 2);
+        let cuts_off = |line: &Vec<DVec2>, a: DVec2, b: DVec2| {
+            line.len() == 2 && ((near(line[0], a) && near(line[1], b)) || (near(line[0], b) && near(line[1], a)))
+        };
+        assert!(lines.iter().any(|l| cuts_off(l, dvec2(0.5, 0.0), dvec2(1.0, 0.5))));
+        assert!(lines.iter().any(|l| cuts_off(l, dvec2(0.0, 0.5), dvec2(0.5, 1.0))));
+
+        // Bands agree: one joined piece above, two corner triangles below
+        let above = isoband(&grid, 0.5, 2.0);
+        assert_eq!(above.len(), 1);
+        assert!((band_area(&above) - 0.75).abs() < 1e-9);
+        let below = isoband(&grid, -1.0, 0.5);
+        assert_eq!(below.len(), 2);
+        assert!((band_area(&below) - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bands_tile_grid() {
+        let x: [f64; 5] = [0.0, 0.5, 2.0, 3.0, 4.5];
+        let y: [f64; 4] = [0.0, 1.0, 1.5, 3.0];
+        let z: Vec<Vec<f64>> = y.iter().map(|y| x.iter().map(|x| (x * 1.3).sin() * (y * 0.9).cos()).collect()).collect();
+        let grid = ContourGrid::new(&x, &y, &z);
+        let (min, max) = grid.value_range().unwrap();
+        let mut levels = vec![min];
+        levels.extend(auto_levels(min, max, 6));
+        levels.push(max + 1.0);
+        let total: f64 = levels.windows(2).map(|w| band_area(&isoband(&grid, w[0], w[1]))).sum();
+        assert!((total - 4.5 * 3.0).abs() < 1e-9, "bands cover {}", total);
+    }
+
+    #[test]
+    fn test_non_finite_cells_skipped() {
+        let axis = [0.0, 1.0, 2.0];
+        let z = vec![vec![0.0, 1.0, 0.0], vec![0.0, f64::NAN, 0.0], vec![0.0, 1.0, 0.0]];
+        let grid = ContourGrid::new(&axis, &axis, &z);
+        assert_eq!(grid.value_range(), Some((0.0, 1.0)));
+        assert!(isolines(&grid, 0.5).is_empty());
+        assert!(isoband(&grid, -1.0, 2.0).is_empty());
+    }
+
+    #[test]
+    fn test_auto_levels() {
+        assert_eq!(auto_levels(0.0, 10.0, 4), vec![2.0, 4.0, 6.0, 8.0]);
+        assert!(auto_levels(0.0, 1.0, 0).is_empty());
+    }
+}
@@ -0,0 +1,482 @@
+// Shading and visibility for 3D triangle meshes
+//
+// Vertex normals come from central differences over the surface grid and
+// are lit by one directional light (Lambert diffuse plus a Blinn-Phong
+// highlight), so colors can be interpolated smoothly across each triangle.
+// Triangles are put in back-to-front order with a BSP tree: every triangle
+// that straddles another's plane is split along it, so intersecting and
+// folded surfaces come out right from any eye position, where sorting by
+// centroid depth cannot. A single height field needs no splitting: cells
+// are painted by decreasing grid distance from the eye, which is exact and
+// linear in the number of cells.
+
+use makepad_widgets::*;
+
+/// Point or direction in box coordinates
+pub type Point3 = [f64; 3];
+
+fn sub(a: Point3, b: Point3) -> Point3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: Point3, b: Point3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Point3, b: Point3) -> Point3 {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn normalize(a: Point3) -> Option<Point3> {
+    let length = dot(a, a).sqrt();
+    (length > 1e-12 && length.is_finite()).then(|| [a[0] / length, a[1] / length, a[2] / length])
+}
+
+fn lerp3(a: Point3, b: Point3, t: f64) -> Point3 {
+    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+}
+
+fn lerp_color(a: Vec4, b: Vec4, t: f64) -> Vec4 {
+    let t = t as f32;
+    vec4(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t, a.z + (b.z - a.z) * t, a.w + (b.w - a.w) * t)
+}
+
+// =============================================================================
+// Lighting
+// =============================================================================
+
+/// Directional light fixed to the data box
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Light3D {
+    /// Direction towards the light
+    pub direction: Point3,
+    /// Share of the base color kept in shadow
+    pub ambient: f64,
+    pub diffuse: f64,
+    /// Strength of the white highlight
+    pub specular: f64,
+    /// Highlight tightness; larger is smaller and sharper
+    pub shininess: f64,
+}
+
+impl Default for Light3D {
+    fn default() -> Self {
+        Self { direction: [-1.0, -1.0, 2.0], ambient: 0.35, diffuse: 0.65, specular: 0.2, shininess: 24.0 }
+    }
+}
+
+impl Light3D {
+    pub fn new(direction: Point3) -> Self {
+        Self { direction, ..Self::default() }
+    }
+
+    /// `color` lit at a vertex with `normal`, seen from direction `to_eye`.
+    /// Surfaces are two-sided: a normal facing away from the eye is flipped.
+    pub fn shade(&self, color: Vec4, normal: Point3, to_eye: Point3) -> Vec4 {
+        let (Some(mut n), Some(l)) = (normalize(normal), normalize(self.direction)) else {
+            return color;
+        };
+        let v = normalize(to_eye).unwrap_or(n);
+        if dot(n, v) < 0.0 {
+            n = [-n[0], -n[1], -n[2]];
+        }
+        let diffuse = dot(n, l).max(0.0);
+        let specular = match normalize([l[0] + v[0], l[1] + v[1], l[2] + v[2]]) {
+            Some(h) if diffuse > 0.0 => dot(n, h).max(0.0).powf(self.shininess) * self.specular,
+            _ => 0.0,
+        };
+        let k = (self.ambient + self.diffuse * diffuse) as f32;
+        let s = specular as f32;
+        vec4(
+            (color.x * k + s).min(1.0),
+            (color.y * k + s).min(1.0),
+            (color.z * k + s).min(1.0),
+            color.w,
+        )
+    }
+}
+
+/// Unit normal at each point of a grid surface, `points[row][col]`, from
+/// central differences (one-sided along the border). Normals point to
+/// positive z; points next to non-finite neighbours fall back to straight up.
+pub fn grid_normals(points: &[Vec<Point3>]) -> Vec<Vec<Point3>> {
+    let rows = points.len();
+    let cols = points.iter().map(Vec::len).min().unwrap_or(0);
+    let up = [0.0, 0.0, 1.0];
+    let mut normals = vec![vec![up; cols]; rows];
+    if rows < 2 || cols < 2 {
+        return normals;
+    }
+    for (i, row) in normals.iter_mut().enumerate() {
+        for (j, normal) in row.iter_mut().enumerate() {
+            let along_col = sub(points[i][(j + 1).min(cols - 1)], points[i][j.saturating_sub(1)]);
+            let along_row = sub(points[(i + 1).min(rows - 1)][j], points[i.saturating_sub(1)][j]);
+            if let Some(n) = normalize(cross(along_col, along_row)) {
+                *normal = if n[2] < 0.0 { [-n[0], -n[1], -n[2]] } else { n };
+            }
+        }
+    }
+    normals
+}
+
+// =============================================================================
+// Depth ordering
+// =============================================================================
+
+/// Triangle with a color per vertex. `edges[k]` marks the edge from point
+/// `k` to point `k + 1` as part of the mesh outline (wireframe), which
+/// survives splitting so hidden wires stay hidden.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShadedTriangle {
+    pub points: [Point3; 3],
+    pub colors: [Vec4; 3],
+    pub edges: [bool; 3],
+}
+
+impl ShadedTriangle {
+    pub fn new(points: [Point3; 3], colors: [Vec4; 3]) -> Self {
+        Self { points, colors, edges: [false; 3] }
+    }
+
+    pub fn with_edges(mut self, edges: [bool; 3]) -> Self {
+        self.edges = edges;
+        self
+    }
+
+    fn plane(&self) -> Option<(Point3, f64)> {
+        let n = normalize(cross(sub(self.points[1], self.points[0]), sub(self.points[2], self.points[0])))?;
+        Some((n, dot(n, self.points[0])))
+    }
+}
+
+/// Polygon vertex: position, color and whether the edge to the next vertex
+/// is an outline edge
+type PolyVertex = (Point3, Vec4, bool);
+
+/// Fan-triangulate a convex polygon; the fan diagonals are not outline edges
+fn triangulate(poly: &[PolyVertex], out: &mut Vec<ShadedTriangle>) {
+    for k in 1..poly.len().saturating_sub(1) {
+        let (a, b, c) = (poly[0], poly[k], poly[k + 1]);
+        let first = k == 1;
+        let last = k + 2 == poly.len();
+        out.push(ShadedTriangle {
+            points: [a.0, b.0, c.0],
+            colors: [a.1, b.1, c.1],
+            edges: [first && a.2, b.2, last && c.2],
+        });
+    }
+}
+
+/// Split `tri` by the plane `n . p = d` into the parts in front of and
+/// behind it
+fn split(tri: &ShadedTriangle, n: Point3, d: f64, dist: [f64; 3], front: &mut Vec<ShadedTriangle>, back: &mut Vec<ShadedTriangle>) {
+    let mut f: Vec<PolyVertex> = Vec::with_capacity(4);
+    let mut b: Vec<PolyVertex> = Vec::with_capacity(4);
+    for k in 0..3 {
+        let next = (k + 1) % 3;
+        let (p, c, edge) = (tri.points[k], tri.colors[k], tri.edges[k]);
+        let (dp, dn) = (dist[k], dist[next]);
+        if dp >= 0.0 { f.push((p, c, edge)); }
+        if dp <= 0.0 { b.push((p, c, edge)); }
+        if (dp > 0.0 && dn < 0.0) || (dp < 0.0 && dn > 0.0) {
+            let t = dp / (dp - dn);
+            let q = lerp3(p, tri.points[next], t);
+            let qc = lerp_color(c, tri.colors[next], t);
+            // Pin the new vertex onto the plane against rounding
+            let off = dot(n, q) - d;
+            let q = [q[0] - n[0] * off, q[1] - n[1] * off, q[2] - n[2] * off];
+            // The vertex before the cut now ends on the plane: its edge runs
+            // on to the cut point, while the cut edge itself is new
+            if dp > 0.0 {
+                f.push((q, qc, false));
+                b.push((q, qc, edge));
+            } else {
+                b.push((q, qc, false));
+                f.push((q, qc, edge));
+            }
+        } else if dp == 0.0 {
+            // On the plane: the edge belongs to whichever side the next
+            // vertex is on, and runs along the plane on the other
+            if dn > 0.0 {
+                if let Some(last) = b.last_mut() { last.2 = false; }
+            } else if dn < 0.0 {
+                if let Some(last) = f.last_mut() { last.2 = false; }
+            }
+        }
+    }
+    if f.len() >= 3 { triangulate(&f, front); }
+    if b.len() >= 3 { triangulate(&b, back); }
+}
+
+/// Triangles still to be placed in the tree, with the node they hang off
+/// and whether they go in front of it
+type BspWork = (Vec<ShadedTriangle>, Option<(usize, bool)>);
+
+struct BspNode {
+    normal: Point3,
+    offset: f64,
+    /// Triangles in the node's plane
+    coplanar: Vec<ShadedTriangle>,
+    front: Option<usize>,
+    back: Option<usize>,
+}
+
+/// Triangles in the order to paint them for an eye at `eye`: each one is
+/// drawn before any (part of a) triangle that can cover it. Triangles are
+/// split where they cross one another's planes; degenerate ones are dropped.
+pub fn depth_order(triangles: Vec<ShadedTriangle>, eye: Point3) -> Vec<ShadedTriangle> {
+    let mut nodes: Vec<BspNode> = Vec::new();
+    let mut work: Vec<BspWork> = vec![(triangles, None)];
+    while let Some((mut tris, parent)) = work.pop() {
+        tris.retain(|t| t.plane().is_some());
+        if tris.is_empty() {
+            continue;
+        }
+        let splitter = choose_splitter(&tris);
+        let (normal, offset) = tris[splitter].plane().unwrap();
+        let scale = tris.iter().flat_map(|t| t.points).map(|p| p[0].abs().max(p[1].abs()).max(p[2].abs())).fold(1.0, f64::max);
+        let eps = 1e-9 * scale;
+
+        let (mut coplanar, mut front, mut back) = (Vec::new(), Vec::new(), Vec::new());
+        for tri in tris {
+            let dist = tri.points.map(|p| {
+                let s = dot(normal, p) - offset;
+                if s.abs() <= eps { 0.0 } else { s }
+            });
+            if dist.iter().all(|&s| s == 0.0) {
+                coplanar.push(tri);
+            } else if dist.iter().all(|&s| s >= 0.0) {
+                front.push(tri);
+            } else if dist.iter().all(|&s| s <= 0.0) {
+                back.push(tri);
+            } else {
+                split(&tri, normal, offset, dist, &mut front, &mut back);
+            }
+        }
+
+        let index = nodes.len();
+        nodes.push(BspNode { normal, offset, coplanar, front: None, back: None });
+        if let Some((parent, is_front)) = parent {
+            if is_front { nodes[parent].front = Some(index) } else { nodes[parent].back = Some(index) }
+        }
+        work.push((front, Some((index, true))));
+        work.push((back, Some((index, false))));
+    }
+
+    // Far side first, then the node's plane, then the near side
+    enum Step { Visit(usize), Emit(usize) }
+    let mut out = Vec::new();
+    let mut stack = if nodes.is_empty() { Vec::new() } else { vec![Step::Visit(0)] };
+    while let Some(step) = stack.pop() {
+        match step {
+            Step::Emit(index) => out.append(&mut nodes[index].coplanar),
+            Step::Visit(index) => {
+                let node = &nodes[index];
+                let eye_in_front = dot(node.normal, eye) - node.offset >= 0.0;
+                let (near, far) = if eye_in_front { (node.front, node.back) } else { (node.back, node.front) };
+                if let Some(near) = near { stack.push(Step::Visit(near)); }
+                stack.push(Step::Emit(index));
+                if let Some(far) = far { stack.push(Step::Visit(far)); }
+            }
+        }
+    }
+    out
+}
+
+/// Among a few evenly spread candidates, the triangle whose plane splits
+/// the fewest others
+fn choose_splitter(tris: &[ShadedTriangle]) -> usize {
+    const CANDIDATES: usize = 5;
+    if tris.len() <= 2 {
+        return 0;
+    }
+    let step = (tris.len() / CANDIDATES).max(1);
+    (0..tris.len())
+        .step_by(step)
+        .take(CANDIDATES)
+        .min_by_key(|&c| {
+            let Some((n, d)) = tris[c].plane() else { return usize::MAX };
+            tris.iter()
+                .filter(|t| {
+                    let s = t.points.map(|p| dot(n, p) - d);
+                    let eps = 1e-9;
+                    s.iter().any(|&v| v > eps) && s.iter().any(|&v| v < -eps)
+                })
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// Back-to-front order of the triangles of one height field. `cells` holds
+/// each grid cell's `(row, col)` and triangles; `eye_cell` is the eye's
+/// position in the same grid units (column, row), possibly outside the grid.
+///
+/// Anything covering a point of the surface lies on the segment from it to
+/// the eye, whose footprint only crosses cells nearer the eye in both row and
+/// column, so painting by decreasing row plus column distance is exact.
+pub fn grid_depth_order(cells: Vec<(usize, usize, Vec<ShadedTriangle>)>, eye_cell: [f64; 2], eye: Point3) -> Vec<ShadedTriangle> {
+    let (eye_col, eye_row) = (eye_cell[0].floor(), eye_cell[1].floor());
+    let mut cells = cells;
+    cells.sort_by(|a, b| {
+        let distance = |&(row, col, _): &(usize, usize, Vec<ShadedTriangle>)| (row as f64 - eye_row).abs() + (col as f64 - eye_col).abs();
+        distance(b).total_cmp(&distance(a))
+    });
+    let mut out = Vec::new();
+    for (_, _, mut tris) in cells {
+        if let [a, b] = tris.as_slice() {
+            // The two halves of a folded cell: paint the one on the far side
+            // of the other's plane first
+            if let Some((n, d)) = b.plane() {
+                let free = a.points.iter().map(|&p| dot(n, p) - d).fold(0.0, |m: f64, s| if s.abs() > m.abs() { s } else { m });
+                if free * (dot(n, eye) - d) > 0.0 {
+                    tris.swap(0, 1);
+                }
+            }
+        }
+        out.extend(tris);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Vec4 = Vec4 { x: 1.0, y: 1.0, z: 1.0, w: 1.0 };
+
+    fn tri(points: [Point3; 3]) -> ShadedTriangle {
+        ShadedTriangle::new(points, [WHITE; 3])
+    }
+
+    fn area(t: &ShadedTriangle) -> f64 {
+        let c = cross(sub(t.points[1], t.points[0]), sub(t.points[2], t.points[0]));
+        dot(c, c).sqrt() / 2.0
+    }
+
+    /// Distance along the ray from `origin` to where it hits `t`
+    fn hit(t: &ShadedTriangle, origin: Point3, dir: Point3) -> Option<f64> {
+        let (e1, e2) = (sub(t.points[1], t.points[0]), sub(t.points[2], t.points[0]));
+        let p = cross(dir, e2);
+        let det = dot(e1, p);
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let s = sub(origin, t.points[0]);
+        let u = dot(s, p) / det;
+        let q = cross(s, e1);
+        let v = dot(dir, q) / det;
+        let d = dot(e2, q) / det;
+        (u > 1e-6 && v > 1e-6 && u + v < 1.0 - 1e-6 && d > 0.0).then_some(d)
+    }
+
+    /// Every ray from the eye must end on the last painted triangle it hits
+    fn assert_painted_back_to_front(order: &[ShadedTriangle], eye: Point3) {
+        let mut checked = 0;
+        for i in 0..40 {
+            for j in 0..40 {
+                let target = [-2.0 + i as f64 * 0.1037, -2.0 + j as f64 * 0.1013, 0.0];
+                let dir = sub(target, eye);
+                let hits: Vec<(usize, f64)> = order.iter().enumerate().filter_map(|(k, t)| hit(t, eye, dir).map(|d| (k, d))).collect();
+                if hits.len() < 2 {
+                    continue;
+                }
+                let nearest = hits.iter().min_by(|a, b| a.1.total_cmp(&b.1)).unwrap();
+                let last = hits.iter().max_by_key(|h| h.0).unwrap();
+                if (nearest.1 - last.1).abs() > 1e-9 {
+                    panic!("ray to {:?}: triangle {} painted over nearer {}", target, last.0, nearest.0);
+                }
+                checked += 1;
+            }
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn test_parallel_faces_sorted_back_to_front() {
+        let layer = |z: f64| tri([[-2.0, -2.0, z], [2.0, -2.0, z], [0.0, 2.0, z]]);
+        let tris = vec![layer(1.0), layer(2.0), layer(0.0)];
+        let z_order = |eye: Point3| -> Vec<f64> { depth_order(tris.clone(), eye).iter().map(|t| t.points[0][2]).collect() };
+        assert_eq!(z_order([0.3, 0.2, 10.0]), vec![0.0, 1.0, 2.0]);
+        assert_eq!(z_order([0.3, 0.2, -10.0]), vec![2.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_intersecting_faces_split() {
+        // Two triangles piercing each other: no order of the originals is
+        // right from above, so one of them must be cut
+        let flat = tri([[-2.0, -2.0, 0.0], [2.0, -2.0, 0.0], [0.0, 2.0, 0.0]]);
+        let tilted = tri([[-2.0, 0.0, -1.0], [2.0, 0.0, -1.0], [0.0, 1.0, 1.0]]);
+        let total = area(&flat) + area(&tilted);
+        for eye in [[0.5, -6.0, 8.0], [0.5, 6.0, 8.0], [-3.0, 0.4, -7.0]] {
+            let order = depth_order(vec![flat.clone(), tilted.clone()], eye);
+            assert!(order.len() > 2);
+            assert!((order.iter().map(area).sum::<f64>() - total).abs() < 1e-9);
+            assert_painted_back_to_front(&order, eye);
+        }
+    }
+
+    #[test]
+    fn test_split_keeps_outline_edges() {
+        let flat = tri([[-2.0, -2.0, 0.0], [2.0, -2.0, 0.0], [0.0, 2.0, 0.0]]).with_edges([true; 3]);
+        let wall = tri([[0.0, -5.0, -1.0], [0.0, 5.0, -1.0], [0.0, 0.0, 1.0]]);
+        let order = depth_order(vec![wall, flat], [3.0, 0.0, 5.0]);
+        // The cut runs through the flat triangle; only its three original
+        // sides stay outline edges
+        let outline: f64 = order
+            .iter()
+            .filter(|t| t.points.iter().all(|p| p[2] == 0.0))
+            .flat_map(|t| (0..3).filter(|&k| t.edges[k]).map(move |k| sub(t.points[(k + 1) % 3], t.points[k])))
+            .map(|e| dot(e, e).sqrt())
+            .sum();
+        assert!((outline - (4.0 + 2.0 * 20.0f64.sqrt())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_degenerate_dropped() {
+        let line = tri([[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [2.0, 2.0, 2.0]]);
+        assert!(depth_order(vec![line], [0.0, 0.0, 5.0]).is_empty());
+        assert!(depth_order(Vec::new(), [0.0, 0.0, 5.0]).is_empty());
+    }
+
+    #[test]
+    fn test_grid_depth_order() {
+        let cell = |row: usize, col: usize| {
+            let (x, y) = (col as f64, row as f64);
+            (row, col, vec![tri([[x, y, 0.0], [x + 1.0, y, 0.0], [x, y + 1.0, 0.0]])])
+        };
+        let cells: Vec<_> = (0..3).flat_map(|row| (0..3).map(move |col| cell(row, col))).collect();
+        // Eye beyond the (2, 2) corner: the far corner first, the near one last
+        let order = grid_depth_order(cells, [5.0, 5.0], [5.0, 5.0, 4.0]);
+        assert_eq!(order.len(), 9);
+        assert_eq!(order[0].points[0], [0.0, 0.0, 0.0]);
+        assert_eq!(order[8].points[0], [2.0, 2.0, 0.0]);
+        let distance = |t: &ShadedTriangle| (4.0 - t.points[0][0]) + (4.0 - t.points[0][1]);
+        assert!(order.windows(2).all(|w| distance(&w[0]) >= distance(&w[1])));
+    }
+
+    #[test]
+    fn test_grid_normals() {
+        // Plane z = x tilts its normal towards -x
+        let points: Vec<Vec<Point3>> = (0..3).map(|r| (0..4).map(|c| [c as f64, r as f64, c as f64]).collect()).collect();
+        let s = 0.5f64.sqrt();
+        for n in grid_normals(&points).iter().flatten() {
+            assert!((n[0] + s).abs() < 1e-12 && n[1].abs() < 1e-12 && (n[2] - s).abs() < 1e-12);
+        }
+        // Too small to difference
+        assert_eq!(grid_normals(&[vec![[0.0, 0.0, 1.0]]]), vec![vec![[0.0, 0.0, 1.0]]]);
+    }
+
+    #[test]
+    fn test_shade() {
+        let light = Light3D { specular: 0.0, ..Light3D::new([0.0, 0.0, 1.0]) };
+        let gray = vec4(0.5, 0.5, 0.5, 0.8);
+        let lit = light.shade(gray, [0.0, 0.0, 1.0], [0.0, 0.0, 1.0]);
+        let edge_on = light.shade(gray, [1.0, 0.0, 0.0], [1.0, 0.0, 0.0]);
+        assert!((lit.x - 0.5).abs() < 1e-6);
+        assert!((edge_on.x - 0.5 * 0.35).abs() < 1e-6);
+        assert_eq!(lit.w, 0.8);
+        // Two-sided: the back of a face seen from below is lit like its front
+        let below = light.shade(gray, [0.0, 0.0, -1.0], [0.0, 0.0, 1.0]);
+        assert_eq!(below, lit);
+    }
+}
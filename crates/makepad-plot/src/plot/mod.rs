@@ -2,6 +2,12 @@
 
 pub mod types;
 pub mod scale;
+pub mod axes;
+pub mod time_axis;
+pub mod picking;
+pub mod lod;
+pub mod stream;
+pub mod headless;
 pub mod colormap;
 pub mod line;
 pub mod bar;
@@ -11,21 +17,46 @@ pub mod histogram;
 pub mod stem;
 pub mod heatmap;
 pub mod polar;
+pub mod marching;
 pub mod contour;
+pub mod mesh3d;
+pub mod axes3d;
 pub mod surface3d;
 pub mod scatter3d;
 pub mod dual;
+pub mod indicators;
+pub mod stats;
 pub mod financial;
 pub mod gauge;
+pub mod squarify;
 pub mod treemap;
 pub mod bubble;
 pub mod area;
 pub mod stack;
+pub mod sankey_layout;
 pub mod hexbin;
+pub mod spec;
+pub mod spec_view;
+pub mod network_layout;
+pub mod network;
+pub mod chord;
+pub mod gantt;
+pub mod geo;
+pub mod geomap;
+pub mod calendar;
+pub mod dimension;
+pub mod parallel;
+pub mod splom;
 
 // Re-export everything for backwards compatibility
 pub use types::*;
 pub use scale::*;
+pub use axes::*;
+pub use time_axis::*;
+pub use picking::*;
+pub use lod::*;
+pub use stream::*;
+pub use headless::*;
 pub use colormap::*;
 pub use line::*;
 pub use bar::*;
@@ -35,17 +66,36 @@ pub use histogram::*;
 pub use stem::*;
 pub use heatmap::*;
 pub use polar::*;
+pub use marching::*;
 pub use contour::*;
+pub use mesh3d::*;
+pub use axes3d::*;
 pub use surface3d::*;
 pub use scatter3d::*;
 pub use dual::*;
+pub use indicators::*;
+pub use stats::*;
 pub use financial::*;
 pub use gauge::*;
+pub use squarify::*;
 pub use treemap::*;
 pub use bubble::*;
 pub use area::*;
 pub use stack::*;
+pub use sankey_layout::*;
 pub use hexbin::*;
+pub use spec::*;
+pub use spec_view::*;
+pub use network_layout::*;
+pub use network::*;
+pub use chord::*;
+pub use gantt::*;
+pub use geo::*;
+pub use geomap::*;
+pub use calendar::*;
+pub use dimension::*;
+pub use parallel::*;
+pub use splom::*;
 
 use makepad_widgets::*;
 
@@ -69,4 +119,12 @@ pub fn live_design(cx: &mut Cx) {
     area::live_design(cx);
     stack::live_design(cx);
     hexbin::live_design(cx);
+    spec_view::live_design(cx);
+    network::live_design(cx);
+    chord::live_design(cx);
+    gantt::live_design(cx);
+    geomap::live_design(cx);
+    calendar::live_design(cx);
+    parallel::live_design(cx);
+    splom::live_design(cx);
 }
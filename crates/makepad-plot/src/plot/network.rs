@@ -0,0 +1,675 @@
+// Network graph - labelled nodes and (optionally directed) edges, laid out
+// force-directed, in layers or radially, with drag, pan and zoom
+
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use crate::export::*;
+use super::*;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::text::PlotLabel;
+
+    pub NetworkGraph = {{NetworkGraph}} {
+        width: Fill,
+        height: Fill,
+        label: <PlotLabel> {}
+    }
+}
+
+/// A press and release closer than this (pixels) count as a click
+const CLICK_SLOP: f64 = 4.0;
+
+/// Zoom limits relative to the fitted view
+const MIN_ZOOM: f64 = 0.2;
+const MAX_ZOOM: f64 = 10.0;
+
+/// Arrowhead length and half width, in pixels
+const ARROW_LENGTH: f64 = 9.0;
+const ARROW_HALF_WIDTH: f64 = 4.0;
+
+/// Force-layout passes run after a node is dropped, so its neighbours follow
+const DROP_RELAX: ForceLayoutOptions = ForceLayoutOptions { iterations: 60, theta: 0.8, gravity: 0.05, temperature: 0.02 };
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkNode {
+    pub label: String,
+    /// Nodes in a group share a palette color
+    pub group: Option<usize>,
+    /// Relative size; the radius grows with its square root
+    pub size: f64,
+    pub color: Option<Vec4>,
+}
+
+impl NetworkNode {
+    pub fn new(label: impl Into<String>) -> Self {
+        Self { label: label.into(), group: None, size: 1.0, color: None }
+    }
+
+    pub fn with_group(mut self, group: usize) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    pub fn with_size(mut self, size: f64) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkEdge {
+    pub source: usize,
+    pub target: usize,
+    /// Pulls the ends together harder in the force layout and draws thicker
+    pub weight: f64,
+    pub label: Option<String>,
+}
+
+impl NetworkEdge {
+    pub fn new(source: usize, target: usize) -> Self {
+        Self { source, target, weight: 1.0, label: None }
+    }
+
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+/// How a `NetworkGraph` places its nodes
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GraphLayout {
+    /// Fruchterman–Reingold forces, Barnes–Hut for large graphs
+    #[default]
+    Force,
+    /// Sugiyama layers with edges pointing down; suits trees, org charts
+    /// and dependency graphs
+    Hierarchical,
+    /// Breadth-first rings around a root
+    Radial,
+}
+
+/// Actions emitted by the network graph
+#[derive(Clone, Debug, DefaultNone)]
+pub enum NetworkAction {
+    None,
+    /// A node was clicked (pressed and released without dragging)
+    NodeClicked { index: usize, label: String },
+}
+
+#[derive(Clone, Copy, Debug)]
+enum NetworkDrag {
+    /// Moving a node; `offset` is from the pointer to the node center
+    Node { index: usize, offset: DVec2 },
+    /// Panning from `start` with the pan at that moment
+    Pan { start: DVec2, pan: DVec2 },
+}
+
+/// Fit of the layout into the widget: screen center, layout center and
+/// pixels per layout unit
+#[derive(Clone, Copy, Debug, Default)]
+struct LayoutFrame {
+    center: DVec2,
+    layout_center: (f64, f64),
+    scale: DVec2,
+}
+
+impl LayoutFrame {
+    /// Fit layout `bounds` into `plot`; with `keep_aspect` both axes share
+    /// the smaller scale, otherwise they stretch to fill
+    fn fit(bounds: ((f64, f64), (f64, f64)), plot: Rect, keep_aspect: bool) -> Self {
+        let ((x0, y0), (x1, y1)) = bounds;
+        let span = |lo: f64, hi: f64, pixels: f64| if hi > lo { pixels / (hi - lo) } else { pixels };
+        let (mut sx, mut sy) = (span(x0, x1, plot.size.x), span(y0, y1, plot.size.y));
+        if keep_aspect {
+            let s = sx.min(sy);
+            sx = s;
+            sy = s;
+        }
+        // A single layer or column has no extent to fill
+        if x1 <= x0 { sx = 0.0; }
+        if y1 <= y0 { sy = 0.0; }
+        Self {
+            center: plot.pos + plot.size * 0.5,
+            layout_center: ((x0 + x1) / 2.0, (y0 + y1) / 2.0),
+            scale: dvec2(sx, sy),
+        }
+    }
+
+    fn to_screen(self, p: (f64, f64), zoom: f64, pan: DVec2) -> DVec2 {
+        let offset = dvec2((p.0 - self.layout_center.0) * self.scale.x, (p.1 - self.layout_center.1) * self.scale.y);
+        self.center + offset * zoom + pan
+    }
+
+    fn to_layout(self, pos: DVec2, zoom: f64, pan: DVec2) -> (f64, f64) {
+        let offset = (pos - self.center - pan) * (1.0 / zoom);
+        let unscale = |d: f64, s: f64| if s > 0.0 { d / s } else { 0.0 };
+        (self.layout_center.0 + unscale(offset.x, self.scale.x), self.layout_center.1 + unscale(offset.y, self.scale.y))
+    }
+}
+
+/// Radius in pixels of a node of `size`, area proportional to size
+fn scaled_radius(base: f64, size: f64) -> f64 {
+    let size = if size.is_finite() && size > 0.0 { size } else { 1.0 };
+    (base * size.sqrt()).max(2.0)
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct NetworkGraph {
+    #[deref] #[live] view: View,
+    #[live] draw_line: DrawPlotLine,
+    #[live] draw_point: DrawPlotPoint,
+    #[live] draw_triangle: DrawTriangle,
+    #[live] label: PlotLabel,
+    #[rust] title: String,
+    #[rust] nodes: Vec<NetworkNode>,
+    #[rust] edges: Vec<NetworkEdge>,
+    /// Legend names of the node groups
+    #[rust] group_names: Vec<String>,
+    #[rust] layout_kind: GraphLayout,
+    /// Center of the radial layout; the best-connected node when None
+    #[rust] root: Option<usize>,
+    #[rust(true)] directed: bool,
+    #[rust(true)] show_labels: bool,
+    #[rust(8.0)] node_radius: f64,
+
+    /// Node positions and edge bends in layout units; dragged nodes move here
+    #[rust] layout: NetworkLayout,
+    #[rust] layout_dirty: bool,
+    /// Bounds of the layout when it was computed, fitted to the widget
+    #[rust] layout_bounds: ((f64, f64), (f64, f64)),
+    /// Nodes placed by hand, which the force layout leaves alone
+    #[rust] pinned: Vec<bool>,
+
+    #[rust(1.0)] zoom: f64,
+    #[rust] pan: DVec2,
+    /// Fit of the layout into the widget
+    #[rust] frame: LayoutFrame,
+
+    #[rust] drag: Option<NetworkDrag>,
+    #[rust] press: Option<DVec2>,
+    /// Node under the pointer; its edges and neighbours are highlighted
+    #[rust] hovered: Option<usize>,
+    /// Node centers and radii of the last draw, for hit testing
+    #[rust] screen_nodes: Vec<(DVec2, f64)>,
+    #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
+}
+
+impl NetworkGraph {
+    /// Replace the graph. The same nodes and edges again keep the current
+    /// layout, so a graph can be set on every draw without losing drags.
+    pub fn set_data(&mut self, nodes: Vec<NetworkNode>, edges: Vec<NetworkEdge>) {
+        if nodes == self.nodes && edges == self.edges {
+            return;
+        }
+        self.nodes = nodes;
+        self.edges = edges;
+        self.pinned = vec![false; self.nodes.len()];
+        self.hovered = None;
+        self.drag = None;
+        self.layout_dirty = true;
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    /// Names for the node groups, shown as a legend
+    pub fn set_group_names(&mut self, names: Vec<String>) {
+        self.group_names = names;
+    }
+
+    pub fn set_layout(&mut self, layout: GraphLayout) {
+        if layout != self.layout_kind {
+            self.layout_kind = layout;
+            self.layout_dirty = true;
+        }
+    }
+
+    /// Node at the center of the radial layout
+    pub fn set_root(&mut self, root: Option<usize>) {
+        if root != self.root {
+            self.root = root;
+            self.layout_dirty |= self.layout_kind == GraphLayout::Radial;
+        }
+    }
+
+    /// Draw arrowheads at edge targets
+    pub fn set_directed(&mut self, directed: bool) {
+        self.directed = directed;
+    }
+
+    pub fn set_show_labels(&mut self, show: bool) {
+        self.show_labels = show;
+    }
+
+    /// Radius of a node of size 1, in pixels
+    pub fn set_node_radius(&mut self, radius: f64) {
+        self.node_radius = radius.max(1.0);
+    }
+
+    /// Undo pan, zoom and dragged nodes
+    pub fn reset_view(&mut self) {
+        self.zoom = 1.0;
+        self.pan = DVec2::default();
+        self.pinned = vec![false; self.nodes.len()];
+        self.layout_dirty = true;
+    }
+
+    pub fn redraw(&mut self, cx: &mut Cx) {
+        self.view.redraw(cx);
+        self.area.redraw(cx);
+    }
+
+    fn edge_list(&self) -> Vec<(usize, usize, f64)> {
+        self.edges.iter().map(|e| (e.source, e.target, e.weight)).collect()
+    }
+
+    fn compute_layout(&mut self) {
+        let n = self.nodes.len();
+        let edges = self.edge_list();
+        self.layout = match self.layout_kind {
+            GraphLayout::Force => NetworkLayout {
+                positions: force_layout(n, &edges, &[], &[], ForceLayoutOptions::default()),
+                bends: vec![Vec::new(); edges.len()],
+            },
+            GraphLayout::Hierarchical => hierarchical_layout(n, &edges),
+            GraphLayout::Radial => radial_layout(n, &edges, self.root),
+        };
+        self.layout_bounds = self.layout.bounds();
+        self.layout_dirty = false;
+    }
+
+    fn radius(&self, node: &NetworkNode) -> f64 {
+        scaled_radius(self.node_radius, node.size)
+    }
+
+    fn node_color(&self, index: usize) -> Vec4 {
+        let node = &self.nodes[index];
+        node.color.unwrap_or_else(|| get_color(node.group.unwrap_or(0)))
+    }
+
+    fn fit(&mut self, plot: Rect) {
+        self.frame = LayoutFrame::fit(self.layout_bounds, plot, self.layout_kind != GraphLayout::Hierarchical);
+    }
+
+    fn to_screen(&self, p: (f64, f64)) -> DVec2 {
+        self.frame.to_screen(p, self.zoom, self.pan)
+    }
+
+    fn to_layout(&self, pos: DVec2) -> (f64, f64) {
+        self.frame.to_layout(pos, self.zoom, self.pan)
+    }
+
+    /// Topmost node under `pos`
+    fn node_at(&self, pos: DVec2) -> Option<usize> {
+        self.screen_nodes.iter().rposition(|&(center, radius)| (pos - center).length() <= radius + 2.0)
+    }
+
+    fn draw_graph(&mut self, cx: &mut Cx2d, plot: Rect) {
+        self.fit(plot);
+        let screen: Vec<DVec2> = self.layout.positions.iter().map(|&p| self.to_screen(p)).collect();
+        let radii: Vec<f64> = self.nodes.iter().map(|n| self.radius(n)).collect();
+
+        // Hovering a node highlights its edges and neighbours
+        let mut lit = vec![self.hovered.is_none(); self.nodes.len()];
+        if let Some(h) = self.hovered {
+            lit[h] = true;
+            for e in &self.edges {
+                if e.source == h && e.target < lit.len() { lit[e.target] = true; }
+                if e.target == h && e.source < lit.len() { lit[e.source] = true; }
+            }
+        }
+
+        let max_weight = self.edges.iter().map(|e| e.weight).filter(|w| w.is_finite()).fold(0.0, f64::max);
+        let uniform = self.edges.iter().all(|e| e.weight == max_weight);
+        let edge_color = vec4(0.55, 0.57, 0.62, 0.8);
+
+        for (k, edge) in self.edges.iter().enumerate() {
+            let (s, t) = (edge.source, edge.target);
+            if s >= screen.len() || t >= screen.len() {
+                continue;
+            }
+            let incident = self.hovered.is_some_and(|h| h == s || h == t);
+            let color = match self.hovered {
+                Some(_) if incident => vec4(0.25, 0.27, 0.32, 0.95),
+                Some(_) => vec4(edge_color.x, edge_color.y, edge_color.z, 0.15),
+                None => edge_color,
+            };
+            let width = if uniform || max_weight <= 0.0 { 1.5 } else { 1.0 + 2.0 * (edge.weight / max_weight).clamp(0.0, 1.0) };
+            self.draw_line.color = color;
+            self.draw_triangle.color = color;
+
+            if s == t {
+                // Self-loop above the node
+                let r = radii[s];
+                let center = screen[s] - dvec2(0.0, r * 1.6);
+                let steps = 20;
+                for i in 0..steps {
+                    let a0 = std::f64::consts::TAU * i as f64 / steps as f64;
+                    let a1 = std::f64::consts::TAU * (i + 1) as f64 / steps as f64;
+                    let p0 = center + dvec2(a0.cos(), a0.sin()) * r * 0.8;
+                    let p1 = center + dvec2(a1.cos(), a1.sin()) * r * 0.8;
+                    self.draw_line.draw_line(cx, p0, p1, width);
+                }
+                continue;
+            }
+
+            let mut points = vec![screen[s]];
+            if let Some(bends) = self.layout.bends.get(k) {
+                points.extend(bends.iter().map(|&p| self.to_screen(p)));
+            }
+            points.push(screen[t]);
+
+            // Trim the ends to the node rims, leaving room for the arrowhead
+            let n = points.len();
+            let start_dir = points[1] - points[0];
+            if start_dir.length() > 0.0 {
+                points[0] = points[0] + start_dir * (radii[s] / start_dir.length());
+            }
+            let end_dir = points[n - 1] - points[n - 2];
+            let end_length = end_dir.length();
+            if end_length <= radii[t] {
+                continue;
+            }
+            let unit = end_dir * (1.0 / end_length);
+            let tip = points[n - 1] - unit * (radii[t] + 1.0);
+            points[n - 1] = if self.directed { tip - unit * ARROW_LENGTH * 0.8 } else { tip };
+
+            for pair in points.windows(2) {
+                self.draw_line.draw_line(cx, pair[0], pair[1], width);
+            }
+            if self.directed {
+                let base = tip - unit * ARROW_LENGTH;
+                let side = dvec2(-unit.y, unit.x) * (ARROW_HALF_WIDTH + width * 0.5);
+                self.draw_triangle.draw_triangle(cx, tip, base + side, base - side);
+            }
+
+            if let Some(text) = &edge.label {
+                let mid = n / 2;
+                let at = (points[mid - 1] + points[mid]) * 0.5;
+                self.label.set_font_size(9.0);
+                self.label.set_color(vec4(0.35, 0.35, 0.4, if self.hovered.is_none() || incident { 1.0 } else { 0.3 }));
+                self.label.draw_at(cx, at, text, TextAnchor::BottomCenter);
+            }
+        }
+
+        // Nodes over edges, with a light rim to separate them from lines
+        self.screen_nodes.clear();
+        for i in 0..self.nodes.len() {
+            let (p, r) = (screen[i], radii[i]);
+            self.screen_nodes.push((p, r));
+            let color = self.node_color(i);
+            let alpha = if lit[i] { 1.0 } else { 0.25 };
+            self.draw_point.color = vec4(1.0, 1.0, 1.0, alpha);
+            self.draw_point.draw_point(cx, p, r + 1.5);
+            self.draw_point.color = vec4(color.x, color.y, color.z, color.w * alpha);
+            self.draw_point.draw_point(cx, p, r);
+        }
+
+        if self.show_labels {
+            self.label.set_font_size(10.0);
+            for i in 0..self.nodes.len() {
+                let (p, r) = (screen[i], radii[i]);
+                self.label.set_color(vec4(0.2, 0.2, 0.2, if lit[i] { 1.0 } else { 0.3 }));
+                let text = self.nodes[i].label.clone();
+                self.label.draw_at(cx, p + dvec2(0.0, r + 3.0), &text, TextAnchor::TopCenter);
+            }
+        }
+    }
+
+    fn draw_legend(&mut self, cx: &mut Cx2d, rect: Rect, top: f64) {
+        self.label.set_font_size(10.0);
+        self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
+        let names = self.group_names.clone();
+        for (i, name) in names.iter().enumerate() {
+            let y = top + 8.0 + i as f64 * 16.0;
+            // Groups show the color of their first node
+            self.draw_point.color = match self.nodes.iter().position(|n| n.group == Some(i)) {
+                Some(node) => self.node_color(node),
+                None => get_color(i),
+            };
+            self.draw_point.draw_point(cx, dvec2(rect.pos.x + 15.0, y), 5.0);
+            self.label.draw_at(cx, dvec2(rect.pos.x + 25.0, y), name, TextAnchor::MiddleLeft);
+        }
+    }
+
+    fn handle_pointer(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        match event.hits(cx, self.area) {
+            Hit::FingerDown(fe) => {
+                self.press = Some(fe.abs);
+                self.drag = Some(match self.node_at(fe.abs) {
+                    Some(index) => NetworkDrag::Node { index, offset: self.screen_nodes[index].0 - fe.abs },
+                    None => NetworkDrag::Pan { start: fe.abs, pan: self.pan },
+                });
+            }
+            Hit::FingerMove(fe) => {
+                let moved = self.press.is_some_and(|p| (fe.abs - p).length() > CLICK_SLOP);
+                match self.drag {
+                    Some(NetworkDrag::Node { index, offset }) if moved => {
+                        if index < self.layout.positions.len() {
+                            self.layout.positions[index] = self.to_layout(fe.abs + offset);
+                            self.pinned[index] = true;
+                            self.redraw(cx);
+                        }
+                    }
+                    Some(NetworkDrag::Pan { start, pan }) => {
+                        self.pan = pan + (fe.abs - start);
+                        self.redraw(cx);
+                    }
+                    _ => {}
+                }
+            }
+            Hit::FingerUp(fe) => {
+                let press = self.press.take();
+                let drag = self.drag.take();
+                let click = fe.is_over && press.is_some_and(|p| (fe.abs - p).length() <= CLICK_SLOP);
+                match drag {
+                    Some(NetworkDrag::Node { index, .. }) if click => {
+                        let label = self.nodes.get(index).map(|n| n.label.clone()).unwrap_or_default();
+                        cx.widget_action(self.widget_uid(), &scope.path, NetworkAction::NodeClicked { index, label });
+                    }
+                    Some(NetworkDrag::Node { .. }) if self.layout_kind == GraphLayout::Force => {
+                        let edges = self.edge_list();
+                        self.layout.positions = force_layout(self.nodes.len(), &edges, &self.layout.positions, &self.pinned, DROP_RELAX);
+                        self.redraw(cx);
+                    }
+                    _ => {}
+                }
+            }
+            Hit::FingerScroll(fe) => {
+                let factor = if fe.scroll.y > 0.0 { 0.9 } else { 1.1 };
+                let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+                // Keep the point under the pointer in place
+                let anchor = (fe.abs - self.frame.center - self.pan) * (1.0 / self.zoom);
+                self.pan = fe.abs - self.frame.center - anchor * zoom;
+                self.zoom = zoom;
+                self.redraw(cx);
+            }
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => {
+                let hovered = self.node_at(fe.abs);
+                cx.set_cursor(if hovered.is_some() { MouseCursor::Hand } else { MouseCursor::Move });
+                if hovered != self.hovered {
+                    self.hovered = hovered;
+                    self.redraw(cx);
+                }
+            }
+            Hit::FingerHoverOut(_) => {
+                cx.set_cursor(MouseCursor::Default);
+                if self.hovered.take().is_some() {
+                    self.redraw(cx);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Widget for NetworkGraph {
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
+
+        if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.nodes.is_empty() {
+            if self.layout_dirty || self.layout.positions.len() != self.nodes.len() {
+                self.compute_layout();
+            }
+
+            let top = if self.title.is_empty() { rect.pos.y } else { rect.pos.y + 30.0 };
+            // Room for the largest node and the labels below the bottom row
+            let largest = self.nodes.iter().map(|n| self.radius(n)).fold(0.0, f64::max);
+            let inset = largest + 12.0;
+            let plot = Rect {
+                pos: dvec2(rect.pos.x + inset, top + inset),
+                size: dvec2((rect.size.x - inset * 2.0).max(0.0), (rect.pos.y + rect.size.y - top - inset * 2.0 - 14.0).max(0.0)),
+            };
+            self.draw_graph(cx, plot);
+
+            if !self.group_names.is_empty() {
+                self.draw_legend(cx, rect, top);
+            }
+            if !self.title.is_empty() {
+                self.label.set_font_size(12.0);
+                self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+                self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + 10.0), &self.title, TextAnchor::TopCenter);
+            }
+        }
+
+        self.capture.end();
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        if !self.nodes.is_empty() {
+            self.handle_pointer(cx, event, scope);
+        }
+    }
+}
+
+impl PlotExport for NetworkGraph {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
+impl NetworkGraphRef {
+    pub fn set_data(&self, nodes: Vec<NetworkNode>, edges: Vec<NetworkEdge>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_data(nodes, edges); }
+    }
+    pub fn set_title(&self, title: impl Into<String>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
+    }
+    pub fn set_group_names(&self, names: Vec<String>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_group_names(names); }
+    }
+    pub fn set_layout(&self, layout: GraphLayout) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_layout(layout); }
+    }
+    pub fn set_root(&self, root: Option<usize>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_root(root); }
+    }
+    pub fn set_directed(&self, directed: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_directed(directed); }
+    }
+    pub fn set_show_labels(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_show_labels(show); }
+    }
+    pub fn set_node_radius(&self, radius: f64) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_node_radius(radius); }
+    }
+    pub fn reset_view(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.reset_view(); }
+    }
+    pub fn redraw(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); }
+    }
+
+    /// Index and label of the node that was clicked, if any
+    pub fn node_clicked(&self, actions: &Actions) -> Option<(usize, String)> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .find_map(|action| match action.cast() {
+                NetworkAction::NodeClicked { index, label } => Some((index, label)),
+                NetworkAction::None => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: DVec2, b: DVec2) -> bool {
+        (a - b).length() < 1e-9
+    }
+
+    #[test]
+    fn test_frame_keeps_aspect() {
+        let plot = Rect { pos: dvec2(10.0, 20.0), size: dvec2(400.0, 200.0) };
+        let frame = LayoutFrame::fit(((-1.0, -1.0), (1.0, 1.0)), plot, true);
+        // The square layout fits the shorter side, centered in the plot
+        assert_eq!(frame.scale, dvec2(100.0, 100.0));
+        assert!(close(frame.to_screen((0.0, 0.0), 1.0, DVec2::default()), dvec2(210.0, 120.0)));
+        assert!(close(frame.to_screen((1.0, -1.0), 1.0, DVec2::default()), dvec2(310.0, 20.0)));
+
+        // Layers stretch to fill both sides
+        let frame = LayoutFrame::fit(((-1.0, -1.0), (1.0, 1.0)), plot, false);
+        assert_eq!(frame.scale, dvec2(200.0, 100.0));
+        assert!(close(frame.to_screen((-1.0, 1.0), 1.0, DVec2::default()), dvec2(10.0, 220.0)));
+    }
+
+    #[test]
+    fn test_frame_round_trip() {
+        let plot = Rect { pos: dvec2(0.0, 0.0), size: dvec2(300.0, 300.0) };
+        let frame = LayoutFrame::fit(((0.0, 2.0), (4.0, 5.0)), plot, true);
+        let (zoom, pan) = (2.5, dvec2(-30.0, 12.0));
+        let screen = frame.to_screen((3.0, 4.5), zoom, pan);
+        let (x, y) = frame.to_layout(screen, zoom, pan);
+        assert!((x - 3.0).abs() < 1e-9 && (y - 4.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_frame_flat_layout() {
+        // A single row has no height to fill and stays on the center line
+        let plot = Rect { pos: dvec2(0.0, 0.0), size: dvec2(200.0, 100.0) };
+        let frame = LayoutFrame::fit(((0.0, 3.0), (4.0, 3.0)), plot, false);
+        assert_eq!(frame.scale, dvec2(50.0, 0.0));
+        assert!(close(frame.to_screen((4.0, 3.0), 1.0, DVec2::default()), dvec2(200.0, 50.0)));
+        assert_eq!(frame.to_layout(dvec2(0.0, 80.0), 1.0, DVec2::default()), (0.0, 3.0));
+    }
+
+    #[test]
+    fn test_scaled_radius() {
+        assert_eq!(scaled_radius(8.0, 1.0), 8.0);
+        assert_eq!(scaled_radius(8.0, 4.0), 16.0);
+        // Missing or bad sizes draw as size 1, tiny ones stay visible
+        assert_eq!(scaled_radius(8.0, f64::NAN), 8.0);
+        assert_eq!(scaled_radius(8.0, -2.0), 8.0);
+        assert_eq!(scaled_radius(8.0, 0.01), 2.0);
+    }
+}
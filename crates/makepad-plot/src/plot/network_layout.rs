@@ -0,0 +1,778 @@
+// Node-link layouts: force-directed, layered (Sugiyama) and radial
+//
+// The force-directed layout follows Fruchterman and Reingold: every pair of
+// nodes repels with k²/d, linked nodes attract with d²/k, and the step size
+// cools linearly to zero. Larger graphs approximate the repulsion with a
+// Barnes–Hut quadtree. Nodes start on a golden-angle spiral, so a layout is
+// the same on every run.
+//
+// The layered layout is the Sugiyama pipeline: edges closing a cycle are
+// reversed, nodes are layered by longest path, edges spanning several
+// layers get a dummy node per layer, layers are ordered by barycenter
+// sweeps (keeping the ordering with the fewest crossings), and x positions
+// are the least-squares fit to the neighbours' positions that keeps nodes
+// one unit apart in their layer order. Dummy nodes become edge bends.
+//
+// The radial layout spreads a breadth-first tree over concentric rings, each
+// subtree getting a wedge in proportion to its number of leaves.
+//
+// Positions are in layout units with neighbouring nodes about one unit
+// apart; the caller scales them to the screen.
+
+/// Barnes–Hut takes over from exact repulsion above this many nodes
+const BARNES_HUT_MIN_NODES: usize = 64;
+/// Deepest quadtree level; coincident nodes share a cell below it
+const QUADTREE_MAX_DEPTH: usize = 24;
+/// Barycenter sweeps, alternating down and up
+const ORDER_SWEEPS: usize = 24;
+/// Down-and-up passes pulling x positions towards neighbours
+const POSITION_PASSES: usize = 8;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ForceLayoutOptions {
+    pub iterations: usize,
+    /// Barnes–Hut opening angle; smaller is more exact and slower
+    pub theta: f64,
+    /// Pull of every node towards the center, which keeps separate
+    /// components from drifting apart
+    pub gravity: f64,
+    /// Largest step of the first iteration, as a fraction of the layout width
+    pub temperature: f64,
+}
+
+impl Default for ForceLayoutOptions {
+    fn default() -> Self {
+        Self { iterations: 300, theta: 0.8, gravity: 0.05, temperature: 0.1 }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NetworkLayout {
+    pub positions: Vec<(f64, f64)>,
+    /// Points each edge passes through between its ends, from source to
+    /// target; empty for straight edges
+    pub bends: Vec<Vec<(f64, f64)>>,
+}
+
+impl NetworkLayout {
+    /// Smallest and largest x and y over nodes and bends
+    pub fn bounds(&self) -> ((f64, f64), (f64, f64)) {
+        let points = self.positions.iter().chain(self.bends.iter().flatten());
+        let mut min = (f64::MAX, f64::MAX);
+        let mut max = (f64::MIN, f64::MIN);
+        for &(x, y) in points {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+        if min.0 > max.0 { ((0.0, 0.0), (0.0, 0.0)) } else { (min, max) }
+    }
+}
+
+fn valid_edges(node_count: usize, edges: &[(usize, usize, f64)]) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+    edges.iter().copied().filter(move |&(s, t, _)| s < node_count && t < node_count && s != t)
+}
+
+/// Golden-angle spiral, about one unit between neighbours
+fn spiral(node_count: usize) -> Vec<(f64, f64)> {
+    let golden = std::f64::consts::PI * (3.0 - 5.0_f64.sqrt());
+    (0..node_count)
+        .map(|i| {
+            let r = (i as f64 + 0.5).sqrt();
+            let a = i as f64 * golden;
+            (r * a.cos(), r * a.sin())
+        })
+        .collect()
+}
+
+// =============================================================================
+// Force-directed
+// =============================================================================
+
+/// Force-directed positions for `node_count` nodes joined by `edges` of
+/// (source, target, weight). Starts from `start` when it has a position per
+/// node (to relax an existing layout) and never moves `pinned` nodes.
+/// Heavier edges pull harder; weights are relative to their mean.
+pub fn force_layout(
+    node_count: usize,
+    edges: &[(usize, usize, f64)],
+    start: &[(f64, f64)],
+    pinned: &[bool],
+    options: ForceLayoutOptions,
+) -> Vec<(f64, f64)> {
+    let mut pos = if start.len() == node_count { start.to_vec() } else { spiral(node_count) };
+    if node_count < 2 {
+        return pos;
+    }
+    let is_pinned = |i: usize| pinned.get(i).copied().unwrap_or(false);
+
+    let links: Vec<(usize, usize, f64)> = valid_edges(node_count, edges)
+        .map(|(s, t, w)| (s, t, if w.is_finite() && w > 0.0 { w } else { 1.0 }))
+        .collect();
+    let mean_weight = if links.is_empty() { 1.0 } else { links.iter().map(|l| l.2).sum::<f64>() / links.len() as f64 };
+
+    let k = 1.0;
+    let width = (node_count as f64).sqrt() * k;
+    let start_temperature = options.temperature * width;
+    let theta = if node_count >= BARNES_HUT_MIN_NODES { options.theta } else { 0.0 };
+    let mut disp = vec![(0.0, 0.0); node_count];
+
+    for iteration in 0..options.iterations {
+        disp.iter_mut().for_each(|d| *d = (0.0, 0.0));
+
+        if theta > 0.0 {
+            let tree = QuadTree::build(&pos);
+            for (i, d) in disp.iter_mut().enumerate() {
+                let f = tree.repulsion(&pos, i, k * k, theta);
+                d.0 += f.0;
+                d.1 += f.1;
+            }
+        } else {
+            for i in 0..node_count {
+                for j in i + 1..node_count {
+                    let (dx, dy) = separation(pos[i], pos[j], i, j);
+                    let d2 = dx * dx + dy * dy;
+                    let f = k * k / d2;
+                    disp[i].0 += dx * f;
+                    disp[i].1 += dy * f;
+                    disp[j].0 -= dx * f;
+                    disp[j].1 -= dy * f;
+                }
+            }
+        }
+
+        for &(s, t, w) in &links {
+            let (dx, dy) = (pos[s].0 - pos[t].0, pos[s].1 - pos[t].1);
+            let d = (dx * dx + dy * dy).sqrt();
+            let f = d / k * (w / mean_weight).clamp(0.1, 10.0);
+            disp[s].0 -= dx * f;
+            disp[s].1 -= dy * f;
+            disp[t].0 += dx * f;
+            disp[t].1 += dy * f;
+        }
+
+        let temperature = start_temperature * (1.0 - iteration as f64 / options.iterations as f64);
+        for (i, p) in pos.iter_mut().enumerate() {
+            if is_pinned(i) {
+                continue;
+            }
+            let d = (disp[i].0 - p.0 * options.gravity * k, disp[i].1 - p.1 * options.gravity * k);
+            let length = (d.0 * d.0 + d.1 * d.1).sqrt();
+            if length > 0.0 {
+                let step = length.min(temperature) / length;
+                p.0 += d.0 * step;
+                p.1 += d.1 * step;
+            }
+        }
+    }
+    pos
+}
+
+/// Offset from `b` to `a`, nudged apart in a direction fixed by the indices
+/// when the two coincide
+fn separation(a: (f64, f64), b: (f64, f64), i: usize, j: usize) -> (f64, f64) {
+    let (dx, dy) = (a.0 - b.0, a.1 - b.1);
+    if dx * dx + dy * dy > 1e-12 {
+        return (dx, dy);
+    }
+    let angle = (i * 31 + j * 17) as f64;
+    (angle.cos() * 1e-3, angle.sin() * 1e-3)
+}
+
+/// Quadtree of node positions for Barnes–Hut repulsion
+struct QuadTree {
+    cells: Vec<QuadCell>,
+    /// Leaf holding each node
+    leaf_of: Vec<usize>,
+}
+
+struct QuadCell {
+    center: (f64, f64),
+    half: f64,
+    mass: f64,
+    /// Sum of the positions inside, for the center of mass
+    sum: (f64, f64),
+    /// The one node in a leaf
+    body: Option<usize>,
+    children: Option<[usize; 4]>,
+}
+
+impl QuadCell {
+    fn new(center: (f64, f64), half: f64) -> Self {
+        Self { center, half, mass: 0.0, sum: (0.0, 0.0), body: None, children: None }
+    }
+
+    fn contains(&self, p: (f64, f64)) -> bool {
+        (p.0 - self.center.0).abs() <= self.half && (p.1 - self.center.1).abs() <= self.half
+    }
+
+    fn quadrant(&self, p: (f64, f64)) -> usize {
+        (p.0 >= self.center.0) as usize + 2 * (p.1 >= self.center.1) as usize
+    }
+}
+
+impl QuadTree {
+    fn build(pos: &[(f64, f64)]) -> Self {
+        let (mut min, mut max) = ((f64::MAX, f64::MAX), (f64::MIN, f64::MIN));
+        for &(x, y) in pos {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+        let center = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0);
+        let half = ((max.0 - min.0).max(max.1 - min.1) / 2.0).max(1e-6) * 1.001;
+        let mut tree = QuadTree { cells: vec![QuadCell::new(center, half)], leaf_of: vec![0; pos.len()] };
+        for (i, &p) in pos.iter().enumerate() {
+            tree.insert(0, i, p, pos, 0);
+        }
+        tree
+    }
+
+    fn insert(&mut self, cell: usize, body: usize, p: (f64, f64), pos: &[(f64, f64)], depth: usize) {
+        let c = &mut self.cells[cell];
+        c.mass += 1.0;
+        c.sum = (c.sum.0 + p.0, c.sum.1 + p.1);
+        if c.children.is_none() {
+            if c.body.is_none() && c.mass == 1.0 {
+                c.body = Some(body);
+                self.leaf_of[body] = cell;
+                return;
+            }
+            if depth >= QUADTREE_MAX_DEPTH {
+                self.leaf_of[body] = cell;
+                return;
+            }
+            let (center, half) = (c.center, c.half / 2.0);
+            let first = self.cells.len();
+            for q in 0..4 {
+                let dx = if q & 1 == 1 { half } else { -half };
+                let dy = if q & 2 == 2 { half } else { -half };
+                self.cells.push(QuadCell::new((center.0 + dx, center.1 + dy), half));
+            }
+            let c = &mut self.cells[cell];
+            c.children = Some([first, first + 1, first + 2, first + 3]);
+            if let Some(old) = c.body.take() {
+                let child = first + c.quadrant(pos[old]);
+                self.insert(child, old, pos[old], pos, depth + 1);
+            }
+        }
+        let c = &self.cells[cell];
+        let child = c.children.unwrap()[c.quadrant(p)];
+        self.insert(child, body, p, pos, depth + 1);
+    }
+
+    /// Repulsion on node `i` with strength `k2` / distance
+    fn repulsion(&self, pos: &[(f64, f64)], i: usize, k2: f64, theta: f64) -> (f64, f64) {
+        let p = pos[i];
+        let mut force = (0.0, 0.0);
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let cell = &self.cells[index];
+            if cell.mass == 0.0 {
+                continue;
+            }
+            let far = !cell.contains(p) && {
+                let com = (cell.sum.0 / cell.mass, cell.sum.1 / cell.mass);
+                let d = ((p.0 - com.0).powi(2) + (p.1 - com.1).powi(2)).sqrt();
+                2.0 * cell.half < theta * d
+            };
+            match cell.children {
+                Some(children) if !far => stack.extend(children),
+                _ => {
+                    // A leaf, or a cell far enough away to act as one body
+                    let (mut mass, mut sum) = (cell.mass, cell.sum);
+                    if self.leaf_of[i] == index {
+                        mass -= 1.0;
+                        sum = (sum.0 - p.0, sum.1 - p.1);
+                        if mass <= 0.0 {
+                            continue;
+                        }
+                    }
+                    let com = (sum.0 / mass, sum.1 / mass);
+                    let other = cell.body.filter(|&b| b != i).unwrap_or(index + pos.len());
+                    let (dx, dy) = separation(p, com, i, other);
+                    let d2 = dx * dx + dy * dy;
+                    force.0 += dx * k2 * mass / d2;
+                    force.1 += dy * k2 * mass / d2;
+                }
+            }
+        }
+        force
+    }
+}
+
+// =============================================================================
+// Layered (Sugiyama)
+// =============================================================================
+
+/// Layered layout with edges pointing down: layer `n` lies at y = `n`.
+/// Edges closing a cycle point up; every edge spanning more than one layer
+/// bends through one point per layer crossed.
+pub fn hierarchical_layout(node_count: usize, edges: &[(usize, usize, f64)]) -> NetworkLayout {
+    // Orient edges along a DAG, reversing those that close a cycle
+    let reversed = find_back_edges(node_count, edges);
+    let oriented: Vec<Option<(usize, usize)>> = edges
+        .iter()
+        .zip(&reversed)
+        .map(|(&(s, t, _), &rev)| {
+            (s < node_count && t < node_count && s != t).then_some(if rev { (t, s) } else { (s, t) })
+        })
+        .collect();
+
+    let layer = assign_layers(node_count, &oriented);
+    let layer_count = layer.iter().max().map_or(0, |&l| l + 1);
+
+    // Dummy nodes along edges spanning several layers
+    let mut node_layer = layer.clone();
+    let mut chains: Vec<Vec<usize>> = Vec::with_capacity(edges.len());
+    let mut down: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    let mut up: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    for edge in &oriented {
+        let mut chain = Vec::new();
+        if let Some((s, t)) = *edge {
+            let mut previous = s;
+            for l in layer[s] + 1..layer[t] {
+                let dummy = node_layer.len();
+                node_layer.push(l);
+                down.push(Vec::new());
+                up.push(Vec::new());
+                down[previous].push(dummy);
+                up[dummy].push(previous);
+                chain.push(dummy);
+                previous = dummy;
+            }
+            down[previous].push(t);
+            up[t].push(previous);
+        }
+        chains.push(chain);
+    }
+
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+    for (v, &l) in node_layer.iter().enumerate() {
+        layers[l].push(v);
+    }
+    order_layers(&mut layers, &down, &up);
+    let x = assign_x(&layers, &down, &up, node_layer.len());
+
+    let positions = (0..node_count).map(|v| (x[v], node_layer[v] as f64)).collect();
+    let bends = chains
+        .iter()
+        .zip(&reversed)
+        .map(|(chain, &rev)| {
+            let mut points: Vec<(f64, f64)> = chain.iter().map(|&d| (x[d], node_layer[d] as f64)).collect();
+            if rev {
+                points.reverse();
+            }
+            points
+        })
+        .collect();
+    NetworkLayout { positions, bends }
+}
+
+/// Edges to reverse so the rest form a DAG: the back edges of a depth-first
+/// search started from each node in order
+fn find_back_edges(node_count: usize, edges: &[(usize, usize, f64)]) -> Vec<bool> {
+    let mut outgoing: Vec<Vec<(usize, usize)>> = vec![Vec::new(); node_count];
+    for (k, &(s, t, _)) in edges.iter().enumerate() {
+        if s < node_count && t < node_count && s != t {
+            outgoing[s].push((t, k));
+        }
+    }
+    // 0 = unvisited, 1 = on the stack, 2 = done
+    let mut state = vec![0u8; node_count];
+    let mut back = vec![false; edges.len()];
+    for root in 0..node_count {
+        if state[root] != 0 {
+            continue;
+        }
+        let mut stack = vec![(root, 0)];
+        state[root] = 1;
+        while let Some(top) = stack.last_mut() {
+            let (v, next) = *top;
+            if let Some(&(t, k)) = outgoing[v].get(next) {
+                top.1 += 1;
+                match state[t] {
+                    0 => {
+                        state[t] = 1;
+                        stack.push((t, 0));
+                    }
+                    1 => back[k] = true,
+                    _ => {}
+                }
+            } else {
+                state[v] = 2;
+                stack.pop();
+            }
+        }
+    }
+    back
+}
+
+/// Longest-path layering; nodes that only have outgoing edges then move
+/// down to just above their highest successor, which shortens their edges
+fn assign_layers(node_count: usize, oriented: &[Option<(usize, usize)>]) -> Vec<usize> {
+    let mut indegree = vec![0usize; node_count];
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    for &(s, t) in oriented.iter().flatten() {
+        outgoing[s].push(t);
+        indegree[t] += 1;
+    }
+    let sources: Vec<usize> = (0..node_count).filter(|&v| indegree[v] == 0).collect();
+    let mut layer = vec![0usize; node_count];
+    let mut remaining = indegree.clone();
+    let mut queue = sources.clone();
+    while let Some(v) = queue.pop() {
+        for &t in &outgoing[v] {
+            layer[t] = layer[t].max(layer[v] + 1);
+            remaining[t] -= 1;
+            if remaining[t] == 0 {
+                queue.push(t);
+            }
+        }
+    }
+    for &v in &sources {
+        if let Some(lowest) = outgoing[v].iter().map(|&t| layer[t]).min() {
+            layer[v] = lowest - 1;
+        }
+    }
+    layer
+}
+
+/// Reorder each layer by barycenter sweeps, keeping the best ordering seen
+fn order_layers(layers: &mut [Vec<usize>], down: &[Vec<usize>], up: &[Vec<usize>]) {
+    let mut index = vec![0.0; down.len()];
+    let mut best = layers.to_vec();
+    let mut best_crossings = count_crossings(layers, down);
+    for sweep in 0..ORDER_SWEEPS {
+        let downward = sweep % 2 == 0;
+        let order: Vec<usize> = if downward { (1..layers.len()).collect() } else { (0..layers.len().saturating_sub(1)).rev().collect() };
+        for l in order {
+            let fixed = if downward { l - 1 } else { l + 1 };
+            for (i, &v) in layers[fixed].iter().enumerate() {
+                index[v] = i as f64;
+            }
+            let neighbours = if downward { up } else { down };
+            let keys: Vec<(f64, usize)> = layers[l]
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| {
+                    let n = &neighbours[v];
+                    let key = if n.is_empty() { i as f64 } else { n.iter().map(|&u| index[u]).sum::<f64>() / n.len() as f64 };
+                    (key, v)
+                })
+                .collect();
+            let mut sorted = keys;
+            sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+            layers[l] = sorted.into_iter().map(|(_, v)| v).collect();
+        }
+        let crossings = count_crossings(layers, down);
+        if crossings < best_crossings {
+            best_crossings = crossings;
+            best = layers.to_vec();
+        }
+    }
+    layers.clone_from_slice(&best);
+}
+
+/// Edge crossings between consecutive layers
+fn count_crossings(layers: &[Vec<usize>], down: &[Vec<usize>]) -> usize {
+    let mut index = vec![0usize; down.len()];
+    for layer in layers {
+        for (i, &v) in layer.iter().enumerate() {
+            index[v] = i;
+        }
+    }
+    let mut crossings = 0;
+    for layer in layers {
+        let segments: Vec<(usize, usize)> = layer
+            .iter()
+            .flat_map(|&v| down[v].iter().map(move |&t| (v, t)))
+            .map(|(v, t)| (index[v], index[t]))
+            .collect();
+        for (a, &(a0, a1)) in segments.iter().enumerate() {
+            for &(b0, b1) in &segments[a + 1..] {
+                if (a0 < b0 && a1 > b1) || (a0 > b0 && a1 < b1) {
+                    crossings += 1;
+                }
+            }
+        }
+    }
+    crossings
+}
+
+/// X positions pulled towards the mean of each node's neighbours, keeping
+/// nodes at least one unit apart in layer order
+fn assign_x(layers: &[Vec<usize>], down: &[Vec<usize>], up: &[Vec<usize>], count: usize) -> Vec<f64> {
+    let mut x = vec![0.0; count];
+    let widest = layers.iter().map(Vec::len).max().unwrap_or(0) as f64;
+    for layer in layers {
+        let offset = (widest - layer.len() as f64) / 2.0;
+        for (i, &v) in layer.iter().enumerate() {
+            x[v] = offset + i as f64;
+        }
+    }
+    for pass in 0..POSITION_PASSES * 2 {
+        let downward = pass % 2 == 0;
+        let order: Vec<usize> = if downward { (1..layers.len()).collect() } else { (0..layers.len().saturating_sub(1)).rev().collect() };
+        for l in order {
+            let desired: Vec<f64> = layers[l]
+                .iter()
+                .map(|&v| {
+                    let n = if downward { &up[v] } else { &down[v] };
+                    if n.is_empty() { x[v] } else { n.iter().map(|&u| x[u]).sum::<f64>() / n.len() as f64 }
+                })
+                .collect();
+            for (&v, placed) in layers[l].iter().zip(spaced_fit(&desired)) {
+                x[v] = placed;
+            }
+        }
+    }
+    let min = x.iter().copied().fold(f64::MAX, f64::min);
+    if min.is_finite() {
+        x.iter_mut().for_each(|v| *v -= min);
+    }
+    x
+}
+
+/// Least-squares fit to `desired` with each value at least one above the
+/// previous: with y = x - i the constraint is y non-decreasing, which is an
+/// isotonic regression solved by pooling adjacent violators
+fn spaced_fit(desired: &[f64]) -> Vec<f64> {
+    // Blocks of (mean, size)
+    let mut blocks: Vec<(f64, usize)> = Vec::with_capacity(desired.len());
+    for (i, &d) in desired.iter().enumerate() {
+        blocks.push((d - i as f64, 1));
+        while blocks.len() > 1 && blocks[blocks.len() - 2].0 > blocks[blocks.len() - 1].0 {
+            let (m2, n2) = blocks.pop().unwrap();
+            let (m1, n1) = blocks.pop().unwrap();
+            let n = n1 + n2;
+            blocks.push(((m1 * n1 as f64 + m2 * n2 as f64) / n as f64, n));
+        }
+    }
+    blocks
+        .into_iter()
+        .flat_map(|(mean, n)| std::iter::repeat_n(mean, n))
+        .enumerate()
+        .map(|(i, y)| y + i as f64)
+        .collect()
+}
+
+// =============================================================================
+// Radial
+// =============================================================================
+
+/// Breadth-first tree from `root` on rings one unit apart, edges taken as
+/// undirected. Without a root the best-connected node is used. Each further
+/// component hangs off the center on the first ring.
+pub fn radial_layout(node_count: usize, edges: &[(usize, usize, f64)], root: Option<usize>) -> NetworkLayout {
+    let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    for (s, t, _) in valid_edges(node_count, edges) {
+        neighbours[s].push(t);
+        neighbours[t].push(s);
+    }
+    for n in &mut neighbours {
+        n.sort_unstable();
+        n.dedup();
+    }
+
+    // Breadth-first trees, one per component, best-connected root first
+    let mut depth = vec![0usize; node_count];
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    let mut seen = vec![false; node_count];
+    let mut roots = Vec::new();
+    let mut candidates: Vec<usize> = (0..node_count).collect();
+    candidates.sort_by_key(|&v| (std::cmp::Reverse(neighbours[v].len()), v));
+    if let Some(r) = root.filter(|&r| r < node_count) {
+        candidates.retain(|&v| v != r);
+        candidates.insert(0, r);
+    }
+    for start in candidates {
+        if seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        roots.push(start);
+        let mut queue = std::collections::VecDeque::from([start]);
+        while let Some(v) = queue.pop_front() {
+            for &t in &neighbours[v] {
+                if !seen[t] {
+                    seen[t] = true;
+                    depth[t] = depth[v] + 1;
+                    children[v].push(t);
+                    queue.push_back(t);
+                }
+            }
+        }
+    }
+
+    // Leaves below each node, children before parents
+    let mut order: Vec<usize> = (0..node_count).collect();
+    order.sort_by_key(|&v| std::cmp::Reverse(depth[v]));
+    let mut leaves = vec![1.0; node_count];
+    for &v in &order {
+        if !children[v].is_empty() {
+            leaves[v] = children[v].iter().map(|&c| leaves[c]).sum();
+        }
+    }
+
+    // A lone tree keeps its root in the center; several share a virtual one
+    let ring_offset = if roots.len() > 1 { 1.0 } else { 0.0 };
+    let mut positions = vec![(0.0, 0.0); node_count];
+    let mut stack: Vec<(usize, f64, f64)> = Vec::new();
+    let total: f64 = roots.iter().map(|&r| leaves[r]).sum();
+    let mut angle = -std::f64::consts::FRAC_PI_2;
+    for &r in &roots {
+        let span = std::f64::consts::TAU * leaves[r] / total.max(1.0);
+        stack.push((r, angle, angle + span));
+        angle += span;
+    }
+    while let Some((v, a0, a1)) = stack.pop() {
+        let radius = depth[v] as f64 + ring_offset;
+        let mid = (a0 + a1) / 2.0;
+        positions[v] = (radius * mid.cos(), radius * mid.sin());
+        let mut a = a0;
+        for &c in &children[v] {
+            let span = (a1 - a0) * leaves[c] / leaves[v];
+            stack.push((c, a, a + span));
+            a += span;
+        }
+    }
+
+    NetworkLayout { positions, bends: vec![Vec::new(); edges.len()] }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+
+    /// Two triangles joined by one edge
+    fn barbell() -> Vec<(usize, usize, f64)> {
+        vec![(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0), (3, 4, 1.0), (4, 5, 1.0), (5, 3, 1.0), (2, 3, 1.0)]
+    }
+
+    /// Nodes of the layered layout and the bends standing in for dummy
+    /// nodes, by layer
+    fn rows(layout: &NetworkLayout) -> Vec<Vec<f64>> {
+        let mut rows: Vec<Vec<f64>> = Vec::new();
+        for &(x, y) in layout.positions.iter().chain(layout.bends.iter().flatten()) {
+            assert_eq!(y.fract(), 0.0);
+            let l = y as usize;
+            if rows.len() <= l {
+                rows.resize(l + 1, Vec::new());
+            }
+            rows[l].push(x);
+        }
+        rows
+    }
+
+    #[test]
+    fn test_force_layout_deterministic() {
+        let edges = barbell();
+        let options = ForceLayoutOptions::default();
+        let a = force_layout(6, &edges, &[], &[], options);
+        assert_eq!(a, force_layout(6, &edges, &[], &[], options));
+        assert!(a.iter().all(|p| p.0.is_finite() && p.1.is_finite()));
+        // Linked nodes end up closer than the two triangles' far corners
+        for &(s, t, _) in &edges {
+            assert!(distance(a[s], a[t]) < distance(a[0], a[5]));
+        }
+
+        // Barnes-Hut is just as repeatable, coincident starts included
+        let ring: Vec<(usize, usize, f64)> = (0..80).map(|i| (i, (i + 1) % 80, 1.0)).collect();
+        let stacked = vec![(0.0, 0.0); 80];
+        let b = force_layout(80, &ring, &stacked, &[], options);
+        assert_eq!(b, force_layout(80, &ring, &stacked, &[], options));
+        assert!(b.iter().all(|p| p.0.is_finite() && p.1.is_finite()));
+    }
+
+    #[test]
+    fn test_force_layout_pins_and_starts() {
+        let start = vec![(5.0, 5.0), (0.0, 0.0), (1.0, 0.0), (0.0, 1.0)];
+        let pinned = [true, false, false, false];
+        let pos = force_layout(4, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0)], &start, &pinned, ForceLayoutOptions::default());
+        assert_eq!(pos[0], (5.0, 5.0));
+        assert_ne!(pos[1], (0.0, 0.0));
+        // A lone node stays where the spiral puts it
+        assert_eq!(force_layout(1, &[], &[], &[], ForceLayoutOptions::default()), spiral(1));
+    }
+
+    #[test]
+    fn test_hierarchical_layout_layers() {
+        // 0 -> 1 -> 2 -> 3 with a shortcut 0 -> 3 and a side branch 1 -> 4
+        let edges = [(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (0, 3, 1.0), (1, 4, 1.0)];
+        let layout = hierarchical_layout(5, &edges);
+        assert_eq!(layout, hierarchical_layout(5, &edges));
+        let y: Vec<f64> = layout.positions.iter().map(|p| p.1).collect();
+        // The branch leaf sits on the first free layer below its parent
+        assert_eq!(y, vec![0.0, 1.0, 2.0, 3.0, 2.0]);
+        // The shortcut bends once per layer it crosses
+        let bends: Vec<usize> = layout.bends.iter().map(Vec::len).collect();
+        assert_eq!(bends, vec![0, 0, 0, 2, 0]);
+        assert_eq!(layout.bends[3][0].1, 1.0);
+        assert_eq!(layout.bends[3][1].1, 2.0);
+
+        // Nodes and bends keep at least a unit apart within each layer
+        for mut row in rows(&layout) {
+            row.sort_by(f64::total_cmp);
+            assert!(row.windows(2).all(|w| w[1] - w[0] >= 1.0 - 1e-9), "{:?}", row);
+        }
+        let ((x0, _), _) = layout.bounds();
+        assert!(x0.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hierarchical_layout_cycles() {
+        // 0 -> 1 -> 2 -> 3 -> 0: the closing edge points up and its bends run
+        // from its source at the bottom to its target at the top
+        let edges = [(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (3, 0, 1.0)];
+        let layout = hierarchical_layout(4, &edges);
+        let y: Vec<f64> = layout.positions.iter().map(|p| p.1).collect();
+        assert_eq!(y, vec![0.0, 1.0, 2.0, 3.0]);
+        let bend_y: Vec<f64> = layout.bends[3].iter().map(|p| p.1).collect();
+        assert_eq!(bend_y, vec![2.0, 1.0]);
+        for mut row in rows(&layout) {
+            row.sort_by(f64::total_cmp);
+            assert!(row.windows(2).all(|w| w[1] - w[0] >= 1.0 - 1e-9), "{:?}", row);
+        }
+
+        // Self loops and dangling edges are ignored
+        let layout = hierarchical_layout(2, &[(0, 0, 1.0), (0, 7, 1.0), (0, 1, 1.0)]);
+        assert_eq!(layout.positions.iter().map(|p| p.1).collect::<Vec<_>>(), vec![0.0, 1.0]);
+        assert_eq!(layout.bends.len(), 3);
+    }
+
+    #[test]
+    fn test_spaced_fit() {
+        assert_eq!(spaced_fit(&[0.0, 0.0, 0.0]), vec![-1.0, 0.0, 1.0]);
+        assert_eq!(spaced_fit(&[0.0, 3.0, 4.5]), vec![0.0, 3.0, 4.5]);
+        // Only the crowded pair is pushed apart, about its mean
+        assert_eq!(spaced_fit(&[0.0, 5.0, 5.5]), vec![0.0, 4.75, 5.75]);
+    }
+
+    #[test]
+    fn test_radial_layout_rings() {
+        // Star around 0 with a grandchild under 1, and a separate pair
+        let edges = [(0, 1, 1.0), (0, 2, 1.0), (0, 3, 1.0), (1, 4, 1.0)];
+        let layout = radial_layout(5, &edges, None);
+        assert_eq!(layout, radial_layout(5, &edges, None));
+        assert_eq!(layout.positions[0], (0.0, 0.0));
+        let radii: Vec<f64> = layout.positions.iter().map(|&p| distance(p, (0.0, 0.0))).collect();
+        for (r, expected) in radii.iter().zip([0.0, 1.0, 1.0, 1.0, 2.0]) {
+            assert!((r - expected).abs() < 1e-9);
+        }
+        // A leaf stays within its parent's wedge
+        let angle = |p: (f64, f64)| p.1.atan2(p.0);
+        assert!((angle(layout.positions[4]) - angle(layout.positions[1])).abs() < 1e-9);
+        assert_eq!(layout.bends, vec![Vec::new(); 4]);
+
+        // An explicit root takes the center; a second component moves every
+        // tree out one ring
+        let rooted = radial_layout(5, &edges, Some(4));
+        assert_eq!(rooted.positions[4], (0.0, 0.0));
+        let split = radial_layout(7, &[(0, 1, 1.0), (0, 2, 1.0), (5, 6, 1.0)], None);
+        assert!((distance(split.positions[0], (0.0, 0.0)) - 1.0).abs() < 1e-9);
+        assert!((distance(split.positions[1], (0.0, 0.0)) - 2.0).abs() < 1e-9);
+    }
+}
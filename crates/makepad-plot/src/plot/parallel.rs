@@ -0,0 +1,504 @@
+// Parallel coordinates - one vertical axis per dimension and one polyline
+// per row, with per-axis scales, drag-to-reorder axes and axis brushing
+
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use crate::export::*;
+use super::*;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::text::PlotLabel;
+
+    pub ParallelCoordinates = {{ParallelCoordinates}} {
+        width: Fill,
+        height: Fill,
+        label: <PlotLabel> {}
+    }
+}
+
+/// A press and release closer than this (pixels) count as a click
+const CLICK_SLOP: f64 = 4.0;
+
+/// How far from an axis (pixels) a press still grabs it
+const AXIS_GRAB: f64 = 12.0;
+
+/// Height of the band above the axes holding their names
+const NAME_BAND: f64 = 24.0;
+
+const BRUSH_WIDTH: f64 = 14.0;
+
+/// Actions emitted by parallel coordinates
+#[derive(Clone, Debug, DefaultNone)]
+pub enum ParallelAction {
+    None,
+    /// Brushes changed; `rows` pass every brush (all rows when none are set)
+    SelectionChanged { rows: Vec<usize> },
+    /// An axis was dragged to a new place; `order` lists dimensions left to right
+    AxesReordered { order: Vec<usize> },
+}
+
+/// What a press on the chart is doing
+#[derive(Clone, Copy, Debug)]
+enum Drag {
+    /// An axis name was grabbed; the axis follows the pointer
+    Reorder { dimension: usize, start: DVec2, moved: bool },
+    /// Brushing an axis from the value under the press
+    Brush { dimension: usize, start: DVec2, from: f64 },
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct ParallelCoordinates {
+    #[deref] #[live] view: View,
+    #[live] draw_line: DrawPlotLine,
+    #[live] draw_fill: DrawPlotFill,
+    #[live] label: PlotLabel,
+    #[rust] title: String,
+    #[rust] dimensions: Vec<PlotDimension>,
+    /// Axes of the dimensions, rebuilt when the data changes
+    #[rust] axes: Vec<PlotAxis>,
+    /// Dimensions from left to right
+    #[rust] order: Vec<usize>,
+    /// Brushed value range per dimension
+    #[rust] brushes: Vec<Option<(f64, f64)>>,
+    /// Dimension whose value colors the lines through the colormap
+    #[rust] color_by: Option<usize>,
+    #[rust] colormap: Colormap,
+    /// Color of the lines when not colored by a dimension; the first
+    /// palette color when None
+    #[rust] line_color: Option<Vec4>,
+    #[rust(1.2)] line_width: f64,
+    #[rust(vec4(0.75, 0.76, 0.78, 0.35))] filtered_color: Vec4,
+
+    #[rust] plot_rect: Rect,
+    #[rust] drag: Option<Drag>,
+    /// Pointer x of the axis being dragged
+    #[rust] drag_x: f64,
+    #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
+}
+
+impl ParallelCoordinates {
+    /// Replace the data. The axis order and brushes are kept while the
+    /// number of dimensions stays the same.
+    pub fn set_dimensions(&mut self, dimensions: Vec<PlotDimension>) {
+        if dimensions == self.dimensions {
+            return;
+        }
+        if dimensions.len() != self.dimensions.len() {
+            self.order = (0..dimensions.len()).collect();
+            self.brushes = vec![None; dimensions.len()];
+            self.drag = None;
+        }
+        self.axes = dimensions.iter().map(|d| d.axis(0.0)).collect();
+        self.dimensions = dimensions;
+    }
+
+    /// Color lines by this dimension's value instead of one color
+    pub fn set_color_by(&mut self, dimension: Option<usize>) {
+        self.color_by = dimension;
+    }
+
+    pub fn set_colormap(&mut self, colormap: Colormap) {
+        self.colormap = colormap;
+    }
+
+    pub fn set_line_color(&mut self, color: Option<Vec4>) {
+        self.line_color = color;
+    }
+
+    pub fn set_line_width(&mut self, width: f64) {
+        self.line_width = width;
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    /// Dimensions from left to right; ignored unless it lists each one once
+    pub fn set_order(&mut self, order: Vec<usize>) {
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        if sorted == (0..self.dimensions.len()).collect::<Vec<_>>() {
+            self.order = order;
+        }
+    }
+
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Brush a dimension to a value range, or clear its brush
+    pub fn set_brush(&mut self, dimension: usize, range: Option<(f64, f64)>) {
+        if let Some(brush) = self.brushes.get_mut(dimension) {
+            *brush = range.map(|(a, b)| (a.min(b), a.max(b)));
+        }
+    }
+
+    pub fn clear_brushes(&mut self) {
+        self.brushes.iter_mut().for_each(|brush| *brush = None);
+    }
+
+    /// Rows inside every brush; all rows when nothing is brushed
+    pub fn selected_rows(&self) -> Vec<usize> {
+        rows_in_brushes(&self.dimensions, &active_brushes(&self.brushes))
+    }
+
+    pub fn clear(&mut self) {
+        self.dimensions.clear();
+        self.axes.clear();
+        self.order.clear();
+        self.brushes.clear();
+        self.drag = None;
+    }
+
+    pub fn redraw(&mut self, cx: &mut Cx) {
+        self.view.redraw(cx);
+        self.area.redraw(cx);
+    }
+
+    /// Pixel x of a dimension's axis, following the pointer while dragged
+    fn axis_x(&self, dimension: usize) -> f64 {
+        if let Some(Drag::Reorder { dimension: dragged, moved: true, .. }) = self.drag {
+            if dragged == dimension {
+                return self.drag_x;
+            }
+        }
+        let slot = self.order.iter().position(|&d| d == dimension).unwrap_or(0);
+        slot_x(self.plot_rect, slot, self.order.len())
+    }
+
+    fn value_y(&self, dimension: usize, value: f64) -> f64 {
+        axis_y(self.plot_rect, &self.axes[dimension], value)
+    }
+
+    fn y_value(&self, dimension: usize, y: f64) -> f64 {
+        axis_value(self.plot_rect, &self.axes[dimension], y)
+    }
+
+    /// Dimension whose axis is nearest to `x`, if within grabbing distance
+    fn axis_at(&self, x: f64) -> Option<usize> {
+        self.order
+            .iter()
+            .map(|&d| (d, (self.axis_x(d) - x).abs()))
+            .filter(|&(_, distance)| distance <= AXIS_GRAB)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(d, _)| d)
+    }
+
+    /// Move the dragged axis to the slot nearest the pointer
+    fn reorder_to(&mut self, dimension: usize, x: f64) {
+        let n = self.order.len();
+        if n < 2 {
+            return;
+        }
+        let slot = nearest_slot(self.plot_rect, n, x);
+        if let Some(current) = self.order.iter().position(|&d| d == dimension) {
+            if current != slot {
+                self.order.remove(current);
+                self.order.insert(slot, dimension);
+            }
+        }
+    }
+
+    fn line_color(&self, row: usize) -> Vec4 {
+        let by_value = self.color_by.and_then(|d| {
+            let value = self.dimensions.get(d)?.value(row)?;
+            Some(self.colormap.sample(self.axes[d].normalize(value).clamp(0.0, 1.0)))
+        });
+        by_value.or(self.line_color).unwrap_or_else(|| get_color(0))
+    }
+
+    /// A row's polyline, broken where a value is missing
+    fn draw_row(&mut self, cx: &mut Cx2d, row: usize) {
+        let mut previous: Option<DVec2> = None;
+        for dimension in self.order.clone() {
+            let point = self.dimensions[dimension].value(row).map(|v| dvec2(self.axis_x(dimension), self.value_y(dimension, v)));
+            if let (Some(a), Some(b)) = (previous, point) {
+                self.draw_line.draw_line(cx, a, b, self.line_width);
+            }
+            previous = point;
+        }
+    }
+
+    /// Filtered rows faint underneath, selected rows in color on top
+    fn draw_rows(&mut self, cx: &mut Cx2d) {
+        let rows = row_count(&self.dimensions);
+        let selected = self.selected_rows();
+        if selected.len() < rows {
+            self.draw_line.color = self.filtered_color;
+            let mut next = selected.iter().peekable();
+            for row in 0..rows {
+                if next.peek() == Some(&&row) {
+                    next.next();
+                    continue;
+                }
+                self.draw_row(cx, row);
+            }
+        }
+        for row in selected {
+            self.draw_line.color = self.line_color(row);
+            self.draw_row(cx, row);
+        }
+    }
+
+    fn draw_axes(&mut self, cx: &mut Cx2d) {
+        let rect = self.plot_rect;
+        let (top, bottom) = (rect.pos.y, rect.pos.y + rect.size.y);
+        for dimension in self.order.clone() {
+            let x = self.axis_x(dimension);
+
+            if let Some((min, max)) = self.brushes[dimension] {
+                let (y0, y1) = (self.value_y(dimension, max), self.value_y(dimension, min));
+                let brush = Rect { pos: dvec2(x - BRUSH_WIDTH * 0.5, y0.max(top)), size: dvec2(BRUSH_WIDTH, (y1.min(bottom) - y0.max(top)).max(1.0)) };
+                self.draw_fill.color = vec4(0.25, 0.45, 0.85, 0.25);
+                self.draw_fill.draw_rect(cx, brush);
+            }
+
+            self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
+            self.draw_line.draw_line(cx, dvec2(x, top), dvec2(x, bottom), 1.0);
+            self.label.set_font_size(9.0);
+            self.label.set_color(vec4(0.35, 0.35, 0.35, 1.0));
+            for tick in self.axes[dimension].ticks() {
+                let y = self.value_y(dimension, tick);
+                self.draw_line.draw_line(cx, dvec2(x - 4.0, y), dvec2(x, y), 1.0);
+                let text = self.axes[dimension].format(tick);
+                self.label.draw_at(cx, dvec2(x - 6.0, y), &text, TextAnchor::MiddleRight);
+            }
+
+            let dragged = matches!(self.drag, Some(Drag::Reorder { dimension: d, .. }) if d == dimension);
+            self.label.set_font_size(10.0);
+            self.label.set_color(if dragged { get_color(0) } else { vec4(0.2, 0.2, 0.2, 1.0) });
+            let name = self.dimensions[dimension].name.clone();
+            self.label.draw_at(cx, dvec2(x, top - 8.0), &name, TextAnchor::BottomCenter);
+        }
+    }
+}
+
+/// Pixel x of slot `slot` of `n` axes spread across `rect`
+fn slot_x(rect: Rect, slot: usize, n: usize) -> f64 {
+    match n {
+        0 | 1 => rect.pos.x + rect.size.x * 0.5,
+        n => rect.pos.x + rect.size.x * slot as f64 / (n - 1) as f64,
+    }
+}
+
+/// Slot of `n` (at least two) whose axis is nearest pixel x `x`
+fn nearest_slot(rect: Rect, n: usize, x: f64) -> usize {
+    (((x - rect.pos.x) / rect.size.x) * (n - 1) as f64).round().clamp(0.0, (n - 1) as f64) as usize
+}
+
+/// Pixel y of `value` on `axis`, its minimum at the bottom of `rect`
+fn axis_y(rect: Rect, axis: &PlotAxis, value: f64) -> f64 {
+    rect.pos.y + rect.size.y * (1.0 - axis.normalize(value))
+}
+
+/// Value on `axis` at pixel y `y`, clamped to the axis range
+fn axis_value(rect: Rect, axis: &PlotAxis, y: f64) -> f64 {
+    let t = (1.0 - (y - rect.pos.y) / rect.size.y).clamp(0.0, 1.0);
+    axis.denormalize(t)
+}
+
+impl Widget for ParallelCoordinates {
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
+
+        if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.dimensions.is_empty() {
+            let top = NAME_BAND + if self.title.is_empty() { 10.0 } else { 36.0 };
+            self.plot_rect = Rect {
+                pos: rect.pos + dvec2(50.0, top),
+                size: dvec2((rect.size.x - 100.0).max(1.0), (rect.size.y - top - 16.0).max(1.0)),
+            };
+            self.draw_rows(cx);
+            self.draw_axes(cx);
+
+            if !self.title.is_empty() {
+                self.label.set_font_size(12.0);
+                self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+                self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + 10.0), &self.title, TextAnchor::TopCenter);
+            }
+        }
+
+        self.capture.end();
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        if self.dimensions.is_empty() {
+            return;
+        }
+        let rect = self.plot_rect;
+        match event.hits(cx, self.area) {
+            Hit::FingerDown(fe) => {
+                let Some(dimension) = self.axis_at(fe.abs.x) else { return };
+                if fe.abs.y < rect.pos.y && fe.abs.y >= rect.pos.y - NAME_BAND {
+                    self.drag = Some(Drag::Reorder { dimension, start: fe.abs, moved: false });
+                } else if fe.abs.y >= rect.pos.y && fe.abs.y <= rect.pos.y + rect.size.y {
+                    let from = self.y_value(dimension, fe.abs.y);
+                    self.drag = Some(Drag::Brush { dimension, start: fe.abs, from });
+                }
+            }
+            Hit::FingerMove(fe) => match self.drag {
+                Some(Drag::Reorder { dimension, start, moved }) => {
+                    if moved || (fe.abs - start).length() > CLICK_SLOP {
+                        self.drag = Some(Drag::Reorder { dimension, start, moved: true });
+                        self.drag_x = fe.abs.x.clamp(rect.pos.x, rect.pos.x + rect.size.x);
+                        self.reorder_to(dimension, self.drag_x);
+                        cx.set_cursor(MouseCursor::Move);
+                        self.redraw(cx);
+                    }
+                }
+                Some(Drag::Brush { dimension, start, from }) => {
+                    if (fe.abs.y - start.y).abs() > CLICK_SLOP {
+                        let to = self.y_value(dimension, fe.abs.y);
+                        self.set_brush(dimension, Some((from, to)));
+                        self.redraw(cx);
+                    }
+                }
+                None => {}
+            },
+            Hit::FingerUp(fe) => {
+                let uid = self.widget_uid();
+                match self.drag.take() {
+                    Some(Drag::Reorder { moved: true, .. }) => {
+                        cx.widget_action(uid, &scope.path, ParallelAction::AxesReordered { order: self.order.clone() });
+                        self.redraw(cx);
+                    }
+                    Some(Drag::Brush { dimension, start, .. }) => {
+                        // A click on an axis clears its brush
+                        if (fe.abs.y - start.y).abs() <= CLICK_SLOP {
+                            if self.brushes[dimension].is_none() {
+                                return;
+                            }
+                            self.brushes[dimension] = None;
+                        }
+                        cx.widget_action(uid, &scope.path, ParallelAction::SelectionChanged { rows: self.selected_rows() });
+                        self.redraw(cx);
+                    }
+                    _ => {}
+                }
+            }
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => {
+                let on_axis = self.axis_at(fe.abs.x).is_some();
+                let in_names = fe.abs.y < rect.pos.y && fe.abs.y >= rect.pos.y - NAME_BAND;
+                let in_axes = fe.abs.y >= rect.pos.y && fe.abs.y <= rect.pos.y + rect.size.y;
+                cx.set_cursor(match (on_axis, in_names, in_axes) {
+                    (true, true, _) => MouseCursor::Hand,
+                    (true, _, true) => MouseCursor::NsResize,
+                    _ => MouseCursor::Default,
+                });
+            }
+            Hit::FingerHoverOut(_) => {
+                cx.set_cursor(MouseCursor::Default);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl PlotExport for ParallelCoordinates {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
+impl ParallelCoordinatesRef {
+    pub fn set_dimensions(&self, dimensions: Vec<PlotDimension>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_dimensions(dimensions); }
+    }
+    pub fn set_color_by(&self, dimension: Option<usize>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_color_by(dimension); }
+    }
+    pub fn set_colormap(&self, colormap: Colormap) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_colormap(colormap); }
+    }
+    pub fn set_line_color(&self, color: Option<Vec4>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_line_color(color); }
+    }
+    pub fn set_order(&self, order: Vec<usize>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_order(order); }
+    }
+    pub fn set_brush(&self, dimension: usize, range: Option<(f64, f64)>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_brush(dimension, range); }
+    }
+    pub fn clear_brushes(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.clear_brushes(); }
+    }
+    pub fn selected_rows(&self) -> Vec<usize> {
+        self.borrow().map(|inner| inner.selected_rows()).unwrap_or_default()
+    }
+    pub fn set_title(&self, title: impl Into<String>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
+    }
+    pub fn clear(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.clear(); }
+    }
+    pub fn redraw(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); }
+    }
+
+    /// Rows selected by brushing, if the brushes changed
+    pub fn selection_changed(&self, actions: &Actions) -> Option<Vec<usize>> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .find_map(|action| match action.cast() {
+                ParallelAction::SelectionChanged { rows } => Some(rows),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect() -> Rect {
+        Rect { pos: dvec2(50.0, 60.0), size: dvec2(300.0, 200.0) }
+    }
+
+    #[test]
+    fn test_axes_normalize_values() {
+        // Each axis spans its own dimension's extent, minimum at the bottom
+        let linear = PlotDimension::new("a", vec![10.0, 30.0, 20.0]).axis(0.0);
+        assert_eq!(axis_y(rect(), &linear, 10.0), 260.0);
+        assert_eq!(axis_y(rect(), &linear, 30.0), 60.0);
+        assert_eq!(axis_y(rect(), &linear, 20.0), 160.0);
+
+        // Log axes place decades evenly
+        let log = PlotDimension::new("b", vec![1.0, 10.0, 100.0]).with_scale(ScaleType::Log).axis(0.0);
+        assert!((axis_y(rect(), &log, 10.0) - 160.0).abs() < 1e-9);
+
+        // Pixels map back to values, clamped to the axis
+        assert!((axis_value(rect(), &linear, 110.0) - 25.0).abs() < 1e-9);
+        assert!((axis_value(rect(), &log, 160.0) - 10.0).abs() < 1e-9);
+        assert_eq!(axis_value(rect(), &linear, 0.0), 30.0);
+        assert_eq!(axis_value(rect(), &linear, 500.0), 10.0);
+    }
+
+    #[test]
+    fn test_axis_slots() {
+        // Axes spread edge to edge; a lone axis sits in the middle
+        assert_eq!((0..4).map(|slot| slot_x(rect(), slot, 4)).collect::<Vec<_>>(), vec![50.0, 150.0, 250.0, 350.0]);
+        assert_eq!(slot_x(rect(), 0, 1), 200.0);
+
+        // A dragged axis lands in the nearest slot, clamped to the ends
+        assert_eq!(nearest_slot(rect(), 4, 190.0), 1);
+        assert_eq!(nearest_slot(rect(), 4, 210.0), 2);
+        assert_eq!(nearest_slot(rect(), 4, -100.0), 0);
+        assert_eq!(nearest_slot(rect(), 4, 900.0), 3);
+    }
+}
@@ -0,0 +1,487 @@
+// Hover picking - nearest points, crosshair, snap marker and tooltip
+
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use super::*;
+
+/// Default snap distance in pixels
+const DEFAULT_PICK_RADIUS: f64 = 20.0;
+
+/// Offset of the tooltip from the pointer
+const TOOLTIP_OFFSET: f64 = 12.0;
+
+/// A press and release closer than this (pixels) count as a click
+const CLICK_SLOP: f64 = 4.0;
+
+// =============================================================================
+// Picked points and actions
+// =============================================================================
+
+/// A data point found near the pointer
+#[derive(Clone, Debug, PartialEq)]
+pub struct PickedPoint {
+    pub series: usize,
+    pub series_label: String,
+    pub index: usize,
+    pub x: f64,
+    pub y: f64,
+    /// Distance from the pointer in pixels
+    pub distance: f64,
+}
+
+/// Actions emitted by Cartesian plots with hover enabled
+#[derive(Clone, Debug, DefaultNone)]
+pub enum PlotAction {
+    None,
+    /// The points under the pointer changed; nearest point per series,
+    /// closest first
+    Hover(Vec<PickedPoint>),
+    /// The pointer left the plot or is no longer near any point
+    HoverEnd,
+    /// The closest point was clicked
+    Pick(PickedPoint),
+}
+
+// =============================================================================
+// Spatial index
+// =============================================================================
+
+/// 2-D tree over the points of one series, for nearest-point queries on large
+/// scatter sets. Stores coordinates after the axis scale transform; queries
+/// weight each axis by pixels per unit so distances are measured on screen.
+#[derive(Clone, Debug, Default)]
+pub struct PointIndex {
+    /// Implicit balanced tree: the median of each slice is its root
+    nodes: Vec<(f64, f64, usize)>,
+}
+
+impl PointIndex {
+    pub fn build(series: &Series, x_scale: ScaleType, y_scale: ScaleType) -> Self {
+        let mut nodes: Vec<(f64, f64, usize)> = series
+            .points()
+            .enumerate()
+            .map(|(i, (x, y))| (x_scale.transform(x), y_scale.transform(y), i))
+            .filter(|(x, y, _)| x.is_finite() && y.is_finite())
+            .collect();
+        build_level(&mut nodes, 0);
+        Self { nodes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Index of the point closest to `(x, y)` and its squared weighted
+    /// distance, where `weight` scales the x and y differences
+    pub fn nearest(&self, x: f64, y: f64, weight: DVec2) -> Option<(usize, f64)> {
+        let mut best = None;
+        nearest_in(&self.nodes, 0, (x, y), weight, &mut best);
+        best
+    }
+}
+
+fn build_level(nodes: &mut [(f64, f64, usize)], depth: usize) {
+    if nodes.len() <= 1 {
+        return;
+    }
+    let mid = nodes.len() / 2;
+    if depth.is_multiple_of(2) {
+        nodes.select_nth_unstable_by(mid, |a, b| a.0.total_cmp(&b.0));
+    } else {
+        nodes.select_nth_unstable_by(mid, |a, b| a.1.total_cmp(&b.1));
+    }
+    let (left, rest) = nodes.split_at_mut(mid);
+    build_level(left, depth + 1);
+    build_level(&mut rest[1..], depth + 1);
+}
+
+fn nearest_in(nodes: &[(f64, f64, usize)], depth: usize, query: (f64, f64), weight: DVec2, best: &mut Option<(usize, f64)>) {
+    if nodes.is_empty() {
+        return;
+    }
+    let mid = nodes.len() / 2;
+    let (x, y, index) = nodes[mid];
+    let (dx, dy) = ((x - query.0) * weight.x, (y - query.1) * weight.y);
+    let dist = dx * dx + dy * dy;
+    if best.is_none_or(|(_, d)| dist < d) {
+        *best = Some((index, dist));
+    }
+
+    // Signed distance from the splitting line, on screen
+    let split = if depth.is_multiple_of(2) { -dx } else { -dy };
+    let (near, far) = if split < 0.0 {
+        (&nodes[..mid], &nodes[mid + 1..])
+    } else {
+        (&nodes[mid + 1..], &nodes[..mid])
+    };
+    nearest_in(near, depth + 1, query, weight, best);
+    if best.is_none_or(|(_, d)| split * split < d) {
+        nearest_in(far, depth + 1, query, weight, best);
+    }
+}
+
+// =============================================================================
+// Picking
+// =============================================================================
+
+/// Pixels per transformed data unit along each axis
+fn pixel_weight(axes: &Axes) -> DVec2 {
+    let span = |axis: &PlotAxis| axis.scale.transform(axis.range.1) - axis.scale.transform(axis.range.0);
+    dvec2(axes.plot_area.width() / span(&axes.x), axes.plot_area.height() / span(&axes.y))
+}
+
+fn picked(axes: &Axes, series_idx: usize, series: &Series, index: usize, pos: DVec2) -> PickedPoint {
+    let (x, y) = series.point(index);
+    PickedPoint {
+        series: series_idx,
+        series_label: series.label.clone(),
+        index,
+        x,
+        y,
+        distance: (axes.data_to_pixel(x, y) - pos).length(),
+    }
+}
+
+/// Nearest point of each series within `radius` pixels of `pos`, closest
+/// first. `indexes` holds one [`PointIndex`] per series.
+pub fn pick_nearest(axes: &Axes, series: &[Series], indexes: &[PointIndex], pos: DVec2, radius: f64) -> Vec<PickedPoint> {
+    if !axes.contains(pos) {
+        return Vec::new();
+    }
+
+    let weight = pixel_weight(axes);
+    let (qx, qy) = axes.pixel_to_data(pos);
+    let (qx, qy) = (axes.x.scale.transform(qx), axes.y.scale.transform(qy));
+
+    let mut points: Vec<PickedPoint> = series
+        .iter()
+        .zip(indexes.iter())
+        .enumerate()
+        .filter_map(|(series_idx, (s, index))| {
+            let (i, dist) = index.nearest(qx, qy, weight)?;
+            (dist <= radius * radius).then(|| picked(axes, series_idx, s, i, pos))
+        })
+        .collect();
+    points.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    points
+}
+
+/// Point of each series nearest to the pointer's x position, closest first;
+/// the usual pick for line charts with a vertical crosshair
+pub fn pick_nearest_x(axes: &Axes, series: &[Series], pos: DVec2) -> Vec<PickedPoint> {
+    if !axes.contains(pos) {
+        return Vec::new();
+    }
+
+    let mut points: Vec<PickedPoint> = series
+        .iter()
+        .enumerate()
+        .filter_map(|(series_idx, s)| {
+            (0..s.len())
+                .filter(|&i| s.x_at(i).is_finite() && s.y_at(i).is_finite())
+                .min_by(|&a, &b| {
+                    let da = (axes.x_to_pixel(s.x_at(a)) - pos.x).abs();
+                    let db = (axes.x_to_pixel(s.x_at(b)) - pos.x).abs();
+                    da.total_cmp(&db)
+                })
+                .map(|i| picked(axes, series_idx, s, i, pos))
+        })
+        .collect();
+    points.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    points
+}
+
+// =============================================================================
+// Hover feedback
+// =============================================================================
+
+/// Tooltip colors and spacing
+#[derive(Clone, Debug)]
+pub struct TooltipStyle {
+    pub background: Vec4,
+    pub border: Vec4,
+    pub text: Vec4,
+    pub padding: f64,
+    pub line_spacing: f64,
+}
+
+impl Default for TooltipStyle {
+    fn default() -> Self {
+        Self {
+            background: vec4(1.0, 1.0, 1.0, 0.95),
+            border: vec4(0.75, 0.75, 0.75, 1.0),
+            text: vec4(0.2, 0.2, 0.2, 1.0),
+            padding: 6.0,
+            line_spacing: 3.0,
+        }
+    }
+}
+
+/// Extra tooltip lines for a hovered point
+pub type TooltipContent = Box<dyn Fn(&PickedPoint) -> Option<String>>;
+
+/// Hover settings and state shared by Cartesian plots
+pub struct PlotHover {
+    pub enabled: bool,
+    pub crosshair: bool,
+    pub snap_marker: bool,
+    pub tooltip: bool,
+    /// Snap distance in pixels for nearest-point picking
+    pub radius: f64,
+    pub style: TooltipStyle,
+    pub content: Option<TooltipContent>,
+    /// Pointer position while it is over the plot area
+    pub pointer: Option<DVec2>,
+    pub picked: Vec<PickedPoint>,
+    /// Where the mouse went down inside the plot area
+    press: Option<DVec2>,
+}
+
+impl Default for PlotHover {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            crosshair: true,
+            snap_marker: true,
+            tooltip: true,
+            radius: DEFAULT_PICK_RADIUS,
+            style: TooltipStyle::default(),
+            content: None,
+            pointer: None,
+            picked: Vec::new(),
+            press: None,
+        }
+    }
+}
+
+impl PlotHover {
+    /// Store a new pointer position and pick. Returns the action to emit when
+    /// the set of picked points changed.
+    pub fn update(&mut self, pointer: Option<DVec2>, picked: Vec<PickedPoint>) -> Option<PlotAction> {
+        let same = picked.len() == self.picked.len()
+            && picked.iter().zip(self.picked.iter()).all(|(a, b)| a.series == b.series && a.index == b.index);
+        self.pointer = pointer;
+        if same {
+            self.picked = picked;
+            return None;
+        }
+
+        self.picked = picked;
+        if self.picked.is_empty() {
+            Some(PlotAction::HoverEnd)
+        } else {
+            Some(PlotAction::Hover(self.picked.clone()))
+        }
+    }
+
+    /// Forget the pointer; returns `HoverEnd` if points were picked
+    pub fn clear(&mut self) -> Option<PlotAction> {
+        self.update(None, Vec::new())
+    }
+
+    /// Track the pointer from raw mouse events, hit testing against the plot
+    /// area of `axes` (the plot view itself is never drawn, so it has no hit
+    /// area). `pick` finds the points near a position. Returns the action to
+    /// emit and whether the plot needs a redraw.
+    pub fn handle_event(&mut self, event: &Event, axes: &Axes, pick: impl Fn(DVec2) -> Vec<PickedPoint>) -> (Option<PlotAction>, bool) {
+        match event {
+            Event::MouseMove(me) => {
+                let pointer = axes.contains(me.abs).then_some(me.abs);
+                if pointer.is_none() && self.pointer.is_none() {
+                    return (None, false);
+                }
+                let picked = pointer.map(&pick).unwrap_or_default();
+                (self.update(pointer, picked), true)
+            }
+            Event::MouseDown(me) => {
+                self.press = axes.contains(me.abs).then_some(me.abs);
+                (None, false)
+            }
+            Event::MouseUp(me) => {
+                let clicked = self.press.take().is_some_and(|press| (me.abs - press).length() <= CLICK_SLOP);
+                let action = self.picked.first().filter(|_| clicked).cloned().map(PlotAction::Pick);
+                (action, false)
+            }
+            _ => (None, false),
+        }
+    }
+
+    /// Draw the crosshair, snap markers and tooltip. `color_of` gives the
+    /// color of a series.
+    pub fn draw(
+        &self,
+        cx: &mut Cx2d,
+        axes: &Axes,
+        color_of: impl Fn(usize) -> Vec4,
+        draw_line: &mut DrawPlotLine,
+        draw_point: &mut DrawPlotPoint,
+        draw_fill: &mut DrawPlotFill,
+        label: &mut PlotLabel,
+    ) {
+        let Some(pointer) = self.pointer else {
+            return;
+        };
+        if !self.enabled || !axes.contains(pointer) {
+            return;
+        }
+        let area = axes.plot_area;
+
+        if self.crosshair {
+            // Snap the vertical line to the closest point when there is one
+            let x = self.picked.first().map_or(pointer.x, |p| axes.x_to_pixel(p.x));
+            draw_line.color = vec4(0.5, 0.5, 0.5, 0.8);
+            draw_line.draw_line_styled(cx, dvec2(x, area.top), dvec2(x, area.bottom), 1.0, LineStyle::Dashed, 0.0);
+            draw_line.draw_line_styled(cx, dvec2(area.left, pointer.y), dvec2(area.right, pointer.y), 1.0, LineStyle::Dashed, 0.0);
+        }
+
+        if self.snap_marker {
+            for p in &self.picked {
+                let pos = axes.data_to_pixel(p.x, p.y);
+                draw_point.color = vec4(1.0, 1.0, 1.0, 1.0);
+                draw_point.draw_point(cx, pos, 6.0);
+                draw_point.color = color_of(p.series);
+                draw_point.draw_point(cx, pos, 4.0);
+            }
+        }
+
+        if self.tooltip && !self.picked.is_empty() {
+            self.draw_tooltip(cx, axes, pointer, draw_fill, label);
+        }
+    }
+
+    fn tooltip_lines(&self, axes: &Axes) -> Vec<String> {
+        // Line charts pick at one x; scatter picks can differ per series
+        let shared_x = self.picked.windows(2).all(|w| w[0].x == w[1].x);
+        let mut lines = Vec::new();
+        if let (true, Some(first)) = (shared_x, self.picked.first()) {
+            lines.push(format!("x: {}", axes.x.format(first.x)));
+        }
+        for p in &self.picked {
+            let name = if p.series_label.is_empty() { format!("Series {}", p.series + 1) } else { p.series_label.clone() };
+            if shared_x {
+                lines.push(format!("{}: {}", name, axes.y.format(p.y)));
+            } else {
+                lines.push(format!("{}: ({}, {})", name, axes.x.format(p.x), axes.y.format(p.y)));
+            }
+            if let Some(extra) = self.content.as_ref().and_then(|content| content(p)) {
+                lines.extend(extra.lines().map(str::to_string));
+            }
+        }
+        lines
+    }
+
+    fn draw_tooltip(&self, cx: &mut Cx2d, axes: &Axes, pointer: DVec2, draw_fill: &mut DrawPlotFill, label: &mut PlotLabel) {
+        let lines = self.tooltip_lines(axes);
+        let style = &self.style;
+        let sizes: Vec<DVec2> = lines.iter().map(|line| label.measure(cx, line)).collect();
+        let width = sizes.iter().map(|s| s.x).fold(0.0, f64::max) + style.padding * 2.0;
+        let height = sizes.iter().map(|s| s.y + style.line_spacing).sum::<f64>() - style.line_spacing + style.padding * 2.0;
+
+        // Below-right of the pointer, flipped to stay inside the plot area
+        let area = axes.plot_area;
+        let mut pos = pointer + dvec2(TOOLTIP_OFFSET, TOOLTIP_OFFSET);
+        if pos.x + width > area.right {
+            pos.x = pointer.x - TOOLTIP_OFFSET - width;
+        }
+        if pos.y + height > area.bottom {
+            pos.y = pointer.y - TOOLTIP_OFFSET - height;
+        }
+
+        draw_fill.gradient_enabled = 0.0;
+        draw_fill.color = style.border;
+        draw_fill.draw_rect(cx, Rect { pos: pos - dvec2(1.0, 1.0), size: dvec2(width + 2.0, height + 2.0) });
+        draw_fill.color = style.background;
+        draw_fill.draw_rect(cx, Rect { pos, size: dvec2(width, height) });
+
+        label.set_color(style.text);
+        let mut y = pos.y + style.padding;
+        for (line, size) in lines.iter().zip(sizes.iter()) {
+            label.draw_at(cx, dvec2(pos.x + style.padding, y), line, TextAnchor::TopLeft);
+            y += size.y + style.line_spacing;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random values in `[0, 1)`
+    fn lcg(seed: &mut u64) -> f64 {
+        *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (*seed >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn brute_force(x: &[f64], y: &[f64], query: (f64, f64), weight: DVec2) -> f64 {
+        x.iter()
+            .zip(y)
+            .map(|(px, py)| {
+                let (dx, dy) = ((px - query.0) * weight.x, (py - query.1) * weight.y);
+                dx * dx + dy * dy
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    fn check(x: Vec<f64>, y: Vec<f64>, queries: &[(f64, f64)], weight: DVec2) {
+        let series = Series::new("s").with_data(x.clone(), y.clone());
+        let index = PointIndex::build(&series, ScaleType::Linear, ScaleType::Linear);
+        assert_eq!(index.len(), x.len());
+
+        for &query in queries {
+            let (i, dist) = index.nearest(query.0, query.1, weight).unwrap();
+            let expected = brute_force(&x, &y, query, weight);
+            assert!((dist - expected).abs() <= 1e-12 * expected.max(1.0), "{:?}: {} vs {}", query, dist, expected);
+            // With ties any of the closest points is a valid answer
+            let (dx, dy) = ((x[i] - query.0) * weight.x, (y[i] - query.1) * weight.y);
+            assert!((dx * dx + dy * dy - dist).abs() <= 1e-12 * dist.max(1.0));
+        }
+    }
+
+    #[test]
+    fn test_nearest_matches_brute_force() {
+        let mut seed = 7;
+        let x: Vec<f64> = (0..500).map(|_| lcg(&mut seed) * 100.0).collect();
+        let y: Vec<f64> = (0..500).map(|_| lcg(&mut seed) * 10.0 - 5.0).collect();
+        let queries: Vec<(f64, f64)> = (0..200).map(|_| (lcg(&mut seed) * 120.0 - 10.0, lcg(&mut seed) * 14.0 - 7.0)).collect();
+
+        check(x.clone(), y.clone(), &queries, dvec2(1.0, 1.0));
+        // Screen weighting: x squeezed, y stretched
+        check(x, y, &queries, dvec2(0.2, 40.0));
+    }
+
+    #[test]
+    fn test_nearest_with_ties() {
+        // A grid with duplicated points; queries on cell centres are
+        // equidistant from four (or eight) points
+        let mut x = Vec::new();
+        let mut y = Vec::new();
+        for i in 0..10 {
+            for j in 0..10 {
+                for _ in 0..2 {
+                    x.push(i as f64);
+                    y.push(j as f64);
+                }
+            }
+        }
+        let queries: Vec<(f64, f64)> = (0..9).flat_map(|i| (0..9).map(move |j| (i as f64 + 0.5, j as f64 + 0.5))).collect();
+        check(x, y, &queries, dvec2(1.0, 1.0));
+    }
+
+    #[test]
+    fn test_empty_and_non_finite() {
+        let empty = PointIndex::build(&Series::new("empty"), ScaleType::Linear, ScaleType::Linear);
+        assert!(empty.is_empty());
+        assert_eq!(empty.nearest(0.0, 0.0, dvec2(1.0, 1.0)), None);
+
+        // NaN points and non-positive values on a log axis are left out
+        let series = Series::new("s").with_data(vec![1.0, f64::NAN, 10.0, -1.0], vec![1.0, 2.0, 1.0, 1.0]);
+        let index = PointIndex::build(&series, ScaleType::Log, ScaleType::Linear);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.nearest(1.9, 1.0, dvec2(1.0, 1.0)).map(|(i, _)| i), Some(2));
+    }
+}
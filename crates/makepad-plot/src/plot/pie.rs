@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -78,6 +79,9 @@ pub struct PieChart {
 
     #[rust]
     legend_position: LegendPosition,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for PieChart {
@@ -87,16 +91,28 @@ impl Widget for PieChart {
 
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.slices.is_empty() {
             self.draw_pie(cx, rect);
             self.draw_title(cx, rect);
             self.draw_legend(cx, rect);
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 }
 
+impl PlotExport for PieChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl PieChart {
     pub fn add_slice(&mut self, slice: PieSlice) {
         self.slices.push(slice);
@@ -338,6 +354,9 @@ pub struct DonutChart {
     #[rust] show_labels: bool,
     #[rust] show_percentages: bool,
     #[rust] use_gradient: bool,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl DonutChart {
@@ -381,6 +400,7 @@ impl DonutChart {
 impl Widget for DonutChart {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.slices.is_empty() {
             // Set defaults
@@ -389,7 +409,7 @@ impl Widget for DonutChart {
             self.use_gradient = true;
 
             let total: f64 = self.slices.iter().map(|s| s.value).sum();
-            if total <= 0.0 { return DrawStep::done(); }
+            if total <= 0.0 { self.capture.end(); return DrawStep::done(); }
 
             let center = dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + rect.size.y / 2.0);
             let outer_radius = (rect.size.x.min(rect.size.y) / 2.0 - 40.0).max(20.0);
@@ -456,6 +476,7 @@ impl Widget for DonutChart {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -464,6 +485,16 @@ impl Widget for DonutChart {
     }
 }
 
+impl PlotExport for DonutChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl DonutChart {
     pub fn redraw(&mut self, cx: &mut Cx) {
         self.view.redraw(cx);
@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -50,12 +51,16 @@ pub struct PolarPlot {
     #[rust] plot_center: DVec2,
     #[rust] plot_radius: f64,
     #[live(20.0)] margin: f64,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for PolarPlot {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) { self.view.handle_event(cx, event, scope); }
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.series.is_empty() {
             let size = rect.size.x.min(rect.size.y) - self.margin * 2.0;
             self.plot_radius = size / 2.0;
@@ -64,10 +69,21 @@ impl Widget for PolarPlot {
             self.draw_data(cx);
             self.draw_labels(cx);
         }
+        self.capture.end();
         DrawStep::done()
     }
 }
 
+impl PlotExport for PolarPlot {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl PolarPlot {
     pub fn set_title(&mut self, title: impl Into<String>) { self.title = title.into(); }
     pub fn add_series(&mut self, series: PolarSeries) { self.series.push(series); }
@@ -195,6 +211,9 @@ pub struct RadarChart {
     #[rust] show_grid: bool,
     #[rust] grid_levels: usize,
     #[rust] use_gradient: bool,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl RadarChart {
@@ -246,6 +265,7 @@ impl RadarChart {
 impl Widget for RadarChart {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 {
             let num_axes = self.axes.len();
@@ -253,6 +273,7 @@ impl Widget for RadarChart {
                 // Need at least 3 axes for radar chart
                 self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + rect.size.y / 2.0),
                     "Need at least 3 axes", TextAnchor::Center);
+                self.capture.end();
                 return DrawStep::done();
             }
 
@@ -384,6 +405,7 @@ impl Widget for RadarChart {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -392,6 +414,16 @@ impl Widget for RadarChart {
     }
 }
 
+impl PlotExport for RadarChart {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl RadarChartRef {
     pub fn set_title(&self, title: impl Into<String>) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
@@ -0,0 +1,450 @@
+// Sankey layout: layering, crossing reduction and link stacking
+//
+// Links that close a cycle are found first with a depth-first search and
+// set aside as circular; the rest form a DAG that is layered by longest
+// path, with sinks pushed to the last layer. Each node is as tall as the
+// larger of its inflow and outflow, using one pixels-per-unit scale for the
+// whole diagram so a link keeps its width from end to end. Layers are
+// ordered by barycenter sweeps, then node positions relax towards the
+// weighted centers of their neighbours while overlaps are pushed apart.
+// Finally links are stacked at each node in the order of the node at their
+// other end, which removes crossings between links sharing a node. Circular
+// links leave the source on the right, run along lanes below the nodes and
+// re-enter the target from the left.
+
+/// Layout settings, in pixels
+#[derive(Clone, Copy, Debug)]
+pub struct SankeyLayoutOptions {
+    /// Height available to nodes and circular-link lanes
+    pub height: f64,
+    /// Vertical gap between nodes in a layer
+    pub node_padding: f64,
+    /// Gap between the nodes and the first circular lane, and between lanes
+    pub lane_gap: f64,
+    /// Relaxation passes
+    pub iterations: usize,
+}
+
+impl Default for SankeyLayoutOptions {
+    fn default() -> Self {
+        Self { height: 400.0, node_padding: 10.0, lane_gap: 6.0, iterations: 24 }
+    }
+}
+
+/// Placement of one node
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SankeyNodeLayout {
+    pub layer: usize,
+    /// Larger of inflow and outflow, or the node's own value without links
+    pub value: f64,
+    pub y0: f64,
+    pub y1: f64,
+}
+
+/// Placement of one link; `y0` and `y1` are the top of the band where it
+/// leaves the source and enters the target
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SankeyLinkLayout {
+    pub width: f64,
+    pub y0: f64,
+    pub y1: f64,
+    /// Closes a cycle or points backwards; drawn as a loop below the nodes
+    pub circular: bool,
+    /// Center of the lane a circular link runs along
+    pub lane: f64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SankeyLayout {
+    pub layer_count: usize,
+    pub nodes: Vec<SankeyNodeLayout>,
+    pub links: Vec<SankeyLinkLayout>,
+    /// Bottom of the node area; circular lanes lie below it
+    pub nodes_bottom: f64,
+}
+
+/// Lay out `node_count` nodes joined by `links` of (source, target, value).
+/// `layers` fixes the layer of a node when set; `values` sizes nodes that
+/// have no links. Links with invalid ends or non-positive values get zero
+/// width.
+pub fn sankey_layout(
+    node_count: usize,
+    layers: &[Option<usize>],
+    values: &[f64],
+    links: &[(usize, usize, f64)],
+    options: SankeyLayoutOptions,
+) -> SankeyLayout {
+    let valid = |&(s, t, v): &(usize, usize, f64)| s < node_count && t < node_count && v.is_finite() && v > 0.0;
+    let mut circular: Vec<bool> = links.iter().map(|&(s, t, _)| s == t).collect();
+    find_cycles(node_count, links, &valid, &mut circular);
+
+    // Longest-path layering over the acyclic links
+    let mut layer: Vec<usize> = (0..node_count).map(|i| layers.get(i).copied().flatten().unwrap_or(0)).collect();
+    let fixed = |i: usize| layers.get(i).copied().flatten().is_some();
+    for &node in &topological_order(node_count, links, &valid, &circular) {
+        for (k, &(s, t, _)) in links.iter().enumerate() {
+            if s == node && valid(&links[k]) && !circular[k] && !fixed(t) {
+                layer[t] = layer[t].max(layer[s] + 1);
+            }
+        }
+    }
+    let max_layer = layer.iter().copied().max().unwrap_or(0);
+    // Sinks line up on the right
+    for (i, node_layer) in layer.iter_mut().enumerate() {
+        let has_in = links.iter().enumerate().any(|(k, l)| l.1 == i && valid(l) && !circular[k]);
+        let has_out = links.iter().enumerate().any(|(k, l)| l.0 == i && valid(l) && !circular[k]);
+        if has_in && !has_out && !fixed(i) {
+            *node_layer = max_layer;
+        }
+    }
+    // Fixed layers may send links backwards
+    for (k, &(s, t, _)) in links.iter().enumerate() {
+        if valid(&links[k]) && layer[t] <= layer[s] {
+            circular[k] = true;
+        }
+    }
+    let layer_count = layer.iter().copied().max().map_or(0, |m| m + 1);
+
+    // Node values: flow conservation across multiple inputs and outputs
+    let mut inflow = vec![0.0; node_count];
+    let mut outflow = vec![0.0; node_count];
+    for link in links.iter().filter(|l| valid(l)) {
+        outflow[link.0] += link.2;
+        inflow[link.1] += link.2;
+    }
+    let node_value: Vec<f64> = (0..node_count)
+        .map(|i| {
+            let flow = f64::max(inflow[i], outflow[i]);
+            if flow > 0.0 { flow } else { values.get(i).copied().filter(|v| v.is_finite()).unwrap_or(0.0).max(0.0) }
+        })
+        .collect();
+
+    // One scale for the whole diagram, leaving room for the circular lanes
+    let mut columns: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+    for i in 0..node_count {
+        columns[layer[i]].push(i);
+    }
+    let circular_total: f64 = links.iter().enumerate().filter(|(k, l)| circular[*k] && valid(l)).map(|(_, l)| l.2).sum();
+    let circular_count = links.iter().enumerate().filter(|(k, l)| circular[*k] && valid(l)).count();
+    let lane_space_fixed = circular_count as f64 * options.lane_gap;
+    let ky = columns
+        .iter()
+        .filter(|column| !column.is_empty())
+        .map(|column| {
+            let sum: f64 = column.iter().map(|&i| node_value[i]).sum();
+            let room = options.height - lane_space_fixed - options.node_padding * (column.len() - 1) as f64;
+            if sum + circular_total > 0.0 { (room / (sum + circular_total)).max(0.0) } else { f64::INFINITY }
+        })
+        .fold(f64::INFINITY, f64::min);
+    let ky = if ky.is_finite() { ky } else { 0.0 };
+    let nodes_bottom = options.height - lane_space_fixed - circular_total * ky;
+
+    // Acyclic neighbours, weighted by flow
+    let mut incoming: Vec<Vec<(usize, f64)>> = vec![Vec::new(); node_count];
+    let mut outgoing: Vec<Vec<(usize, f64)>> = vec![Vec::new(); node_count];
+    for (k, &(s, t, v)) in links.iter().enumerate() {
+        if valid(&links[k]) && !circular[k] {
+            outgoing[s].push((t, v));
+            incoming[t].push((s, v));
+        }
+    }
+
+    order_layers(&mut columns, &incoming, &outgoing);
+
+    // Initial positions: stacked from the top in barycenter order
+    let mut y0 = vec![0.0; node_count];
+    let height: Vec<f64> = node_value.iter().map(|v| v * ky).collect();
+    for column in &columns {
+        let mut y = 0.0;
+        for &i in column {
+            y0[i] = y;
+            y += height[i] + options.node_padding;
+        }
+    }
+
+    let center = |y0: &[f64], i: usize| y0[i] + height[i] / 2.0;
+    let pull = |y0: &[f64], neighbours: &[(usize, f64)]| {
+        let weight: f64 = neighbours.iter().map(|n| n.1).sum();
+        (weight > 0.0).then(|| neighbours.iter().map(|&(j, v)| center(y0, j) * v).sum::<f64>() / weight)
+    };
+    for iteration in 0..options.iterations {
+        let alpha = 0.99f64.powi(iteration as i32);
+        for (pass, neighbours) in [(0, &incoming), (1, &outgoing)] {
+            let order: Vec<usize> = if pass == 0 { (1..layer_count).collect() } else { (0..layer_count.saturating_sub(1)).rev().collect() };
+            for l in order {
+                for &i in &columns[l] {
+                    if let Some(target) = pull(&y0, &neighbours[i]) {
+                        y0[i] += (target - center(&y0, i)) * alpha;
+                    }
+                }
+                resolve_collisions(&mut columns[l], &mut y0, &height, nodes_bottom, options.node_padding);
+            }
+        }
+    }
+    for column in &mut columns {
+        resolve_collisions(column, &mut y0, &height, nodes_bottom, options.node_padding);
+    }
+
+    // Stack links at each end in the order of the node at the other end;
+    // circular links go below the rest
+    let mut link_layout: Vec<SankeyLinkLayout> = links
+        .iter()
+        .enumerate()
+        .map(|(k, l)| SankeyLinkLayout { width: if valid(l) { l.2 * ky } else { 0.0 }, circular: circular[k] && valid(l), ..Default::default() })
+        .collect();
+    let other_end = |k: usize, from_source: bool| {
+        let (s, t, _) = links[k];
+        let j = if from_source { t } else { s };
+        (circular[k], if circular[k] { -(layer[j] as f64) } else { center(&y0, j) })
+    };
+    for (i, &top) in y0.iter().enumerate() {
+        for from_source in [true, false] {
+            let mut ends: Vec<usize> = (0..links.len())
+                .filter(|&k| valid(&links[k]) && if from_source { links[k].0 == i } else { links[k].1 == i })
+                .collect();
+            ends.sort_by(|&a, &b| {
+                let (ca, ya) = other_end(a, from_source);
+                let (cb, yb) = other_end(b, from_source);
+                ca.cmp(&cb).then(ya.total_cmp(&yb))
+            });
+            let mut y = top;
+            for k in ends {
+                if from_source { link_layout[k].y0 = y } else { link_layout[k].y1 = y }
+                y += link_layout[k].width;
+            }
+        }
+    }
+
+    // Lanes: short loops nearest the nodes so loops nest without crossing
+    let mut loops: Vec<usize> = (0..links.len()).filter(|&k| link_layout[k].circular).collect();
+    loops.sort_by_key(|&k| layer[links[k].0].abs_diff(layer[links[k].1]));
+    let mut lane = nodes_bottom;
+    for k in loops {
+        lane += options.lane_gap;
+        link_layout[k].lane = lane + link_layout[k].width / 2.0;
+        lane += link_layout[k].width;
+    }
+
+    SankeyLayout {
+        layer_count,
+        nodes: (0..node_count)
+            .map(|i| SankeyNodeLayout { layer: layer[i], value: node_value[i], y0: y0[i], y1: y0[i] + height[i] })
+            .collect(),
+        links: link_layout,
+        nodes_bottom,
+    }
+}
+
+/// Mark links that close a cycle (point back to a node on the DFS stack)
+fn find_cycles(node_count: usize, links: &[(usize, usize, f64)], valid: &impl Fn(&(usize, usize, f64)) -> bool, circular: &mut [bool]) {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State { New, Active, Done }
+    let mut state = vec![State::New; node_count];
+    let mut out: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    for (k, link) in links.iter().enumerate() {
+        if valid(link) && !circular[k] {
+            out[link.0].push(k);
+        }
+    }
+    for root in 0..node_count {
+        if state[root] != State::New {
+            continue;
+        }
+        // Stack of (node, next outgoing link to visit)
+        let mut stack = vec![(root, 0)];
+        state[root] = State::Active;
+        while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+            if let Some(&k) = out[node].get(*next) {
+                *next += 1;
+                let target = links[k].1;
+                match state[target] {
+                    State::Active => circular[k] = true,
+                    State::New => {
+                        state[target] = State::Active;
+                        stack.push((target, 0));
+                    }
+                    State::Done => {}
+                }
+            } else {
+                state[node] = State::Done;
+                stack.pop();
+            }
+        }
+    }
+}
+
+/// Nodes ordered so every acyclic link points forwards
+fn topological_order(node_count: usize, links: &[(usize, usize, f64)], valid: &impl Fn(&(usize, usize, f64)) -> bool, circular: &[bool]) -> Vec<usize> {
+    let mut indegree = vec![0usize; node_count];
+    for (k, link) in links.iter().enumerate() {
+        if valid(link) && !circular[k] {
+            indegree[link.1] += 1;
+        }
+    }
+    let mut ready: Vec<usize> = (0..node_count).filter(|&i| indegree[i] == 0).rev().collect();
+    let mut order = Vec::with_capacity(node_count);
+    while let Some(node) = ready.pop() {
+        order.push(node);
+        for (k, link) in links.iter().enumerate() {
+            if link.0 == node && valid(link) && !circular[k] {
+                indegree[link.1] -= 1;
+                if indegree[link.1] == 0 {
+                    ready.push(link.1);
+                }
+            }
+        }
+    }
+    order
+}
+
+/// Reorder each layer by the mean position of its neighbours, sweeping
+/// right then left a few times
+fn order_layers(columns: &mut [Vec<usize>], incoming: &[Vec<(usize, f64)>], outgoing: &[Vec<(usize, f64)>]) {
+    let node_count = incoming.len();
+    let mut rank = vec![0.0; node_count];
+    let update_rank = |columns: &[Vec<usize>], rank: &mut [f64]| {
+        for column in columns {
+            for (r, &i) in column.iter().enumerate() {
+                rank[i] = r as f64;
+            }
+        }
+    };
+    update_rank(columns, &mut rank);
+    for _ in 0..4 {
+        for (neighbours, forward) in [(incoming, true), (outgoing, false)] {
+            let layers: Vec<usize> = if forward { (1..columns.len()).collect() } else { (0..columns.len().saturating_sub(1)).rev().collect() };
+            for l in layers {
+                let barycenter = |i: usize| {
+                    let weight: f64 = neighbours[i].iter().map(|n| n.1).sum();
+                    if weight > 0.0 {
+                        neighbours[i].iter().map(|&(j, v)| rank[j] * v).sum::<f64>() / weight
+                    } else {
+                        rank[i]
+                    }
+                };
+                let mut keyed: Vec<(f64, usize)> = columns[l].iter().map(|&i| (barycenter(i), i)).collect();
+                keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+                columns[l] = keyed.into_iter().map(|(_, i)| i).collect();
+                for (r, &i) in columns[l].iter().enumerate() {
+                    rank[i] = r as f64;
+                }
+            }
+        }
+    }
+}
+
+/// Sort a layer by position and push overlapping nodes apart, keeping them
+/// within `0..bottom`
+fn resolve_collisions(column: &mut [usize], y0: &mut [f64], height: &[f64], bottom: f64, padding: f64) {
+    column.sort_by(|&a, &b| y0[a].total_cmp(&y0[b]));
+    let mut y = 0.0;
+    for &i in column.iter() {
+        y0[i] = y0[i].max(y);
+        y = y0[i] + height[i] + padding;
+    }
+    let mut y = bottom;
+    for &i in column.iter().rev() {
+        y0[i] = y0[i].min(y - height[i]);
+        y = y0[i] - padding;
+    }
+    // Too tall to fit: keep the top edge
+    if let Some(&first) = column.first().filter(|&&first| y0[first] < 0.0) {
+        let shift = -y0[first];
+        for &i in column.iter() {
+            y0[i] += shift;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // coal, gas, solar -> plant -> industry, homes; coal also feeds industry
+    // directly and solar feeds homes
+    const LINKS: [(usize, usize, f64); 7] = [
+        (0, 3, 40.0),
+        (1, 3, 30.0),
+        (2, 3, 10.0),
+        (0, 4, 20.0),
+        (3, 4, 50.0),
+        (3, 5, 30.0),
+        (2, 5, 15.0),
+    ];
+
+    fn layout(links: &[(usize, usize, f64)], node_count: usize) -> SankeyLayout {
+        sankey_layout(node_count, &[], &[], links, SankeyLayoutOptions::default())
+    }
+
+    #[test]
+    fn test_dag_columns() {
+        let layout = layout(&LINKS, 6);
+        assert_eq!(layout.layer_count, 3);
+        let layers: Vec<usize> = layout.nodes.iter().map(|n| n.layer).collect();
+        assert_eq!(layers, vec![0, 0, 0, 1, 2, 2]);
+        let values: Vec<f64> = layout.nodes.iter().map(|n| n.value).collect();
+        assert_eq!(values, vec![60.0, 30.0, 25.0, 80.0, 70.0, 45.0]);
+        assert!(layout.links.iter().all(|l| !l.circular));
+        assert_eq!(layout.nodes_bottom, SankeyLayoutOptions::default().height);
+    }
+
+    #[test]
+    fn test_dag_node_spans_do_not_overlap() {
+        let options = SankeyLayoutOptions::default();
+        let layout = layout(&LINKS, 6);
+        for l in 0..layout.layer_count {
+            let mut spans: Vec<(f64, f64)> = layout.nodes.iter().filter(|n| n.layer == l).map(|n| (n.y0, n.y1)).collect();
+            spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+            for span in &spans {
+                assert!(span.0 >= -1e-9 && span.1 <= layout.nodes_bottom + 1e-9, "{:?} outside the node area", span);
+            }
+            for pair in spans.windows(2) {
+                assert!(pair[1].0 - pair[0].1 >= options.node_padding - 1e-9, "{:?} overlap in layer {}", pair, l);
+            }
+        }
+    }
+
+    #[test]
+    fn test_links_stack_within_nodes() {
+        let layout = layout(&LINKS, 6);
+        // One scale: a node is as tall as the links it sends or receives
+        let ky = layout.links[0].width / LINKS[0].2;
+        for (node, n) in layout.nodes.iter().enumerate() {
+            assert!(((n.y1 - n.y0) - n.value * ky).abs() < 1e-9);
+            for (k, &(s, t, _)) in LINKS.iter().enumerate() {
+                let link = &layout.links[k];
+                if s == node {
+                    assert!(link.y0 >= n.y0 - 1e-9 && link.y0 + link.width <= n.y1 + 1e-9);
+                }
+                if t == node {
+                    assert!(link.y1 >= n.y0 - 1e-9 && link.y1 + link.width <= n.y1 + 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cycle_runs_below_nodes() {
+        let links = [(0, 1, 10.0), (1, 2, 10.0), (2, 0, 5.0), (1, 1, 2.0)];
+        let layout = layout(&links, 3);
+        let layers: Vec<usize> = layout.nodes.iter().map(|n| n.layer).collect();
+        assert_eq!(layers, vec![0, 1, 2]);
+        let circular: Vec<bool> = layout.links.iter().map(|l| l.circular).collect();
+        assert_eq!(circular, vec![false, false, true, true]);
+        assert!(layout.nodes_bottom < SankeyLayoutOptions::default().height);
+        // The self loop spans fewer layers so it takes the inner lane
+        assert!(layout.links[3].lane > layout.nodes_bottom);
+        assert!(layout.links[2].lane > layout.links[3].lane);
+    }
+
+    #[test]
+    fn test_fixed_layers_and_invalid_links() {
+        let links = [(0, 1, 10.0), (1, 9, 5.0), (0, 2, -1.0), (0, 2, f64::NAN)];
+        let layout = sankey_layout(3, &[None, None, Some(3)], &[0.0, 0.0, 7.0], &links, SankeyLayoutOptions::default());
+        assert_eq!(layout.nodes[2].layer, 3);
+        assert_eq!(layout.layer_count, 4);
+        // Unlinked node sized by its own value
+        assert_eq!(layout.nodes[2].value, 7.0);
+        assert!(layout.links[1..].iter().all(|l| l.width == 0.0 && !l.circular));
+    }
+}
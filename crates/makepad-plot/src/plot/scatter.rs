@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -31,6 +32,9 @@ pub struct ScatterPlot {
     #[live]
     draw_line: DrawPlotLine,
 
+    #[live]
+    draw_fill: DrawPlotFill,
+
     #[live]
     label: PlotLabel,
 
@@ -41,13 +45,7 @@ pub struct ScatterPlot {
     use_gradient: bool,
 
     #[rust]
-    plot_area: PlotArea,
-
-    #[rust]
-    x_range: (f64, f64),
-
-    #[rust]
-    y_range: (f64, f64),
+    axes: Axes,
 
     #[rust]
     title: String,
@@ -58,24 +56,9 @@ pub struct ScatterPlot {
     #[rust]
     y_label: String,
 
-    #[rust(true)]
-    show_grid: bool,
-
     #[rust(5.0)]
     point_radius: f64,
 
-    #[rust(50.0)]
-    left_margin: f64,
-
-    #[rust(30.0)]
-    bottom_margin: f64,
-
-    #[rust(20.0)]
-    right_margin: f64,
-
-    #[rust(30.0)]
-    top_margin: f64,
-
     #[rust]
     legend_position: LegendPosition,
 
@@ -94,39 +77,79 @@ pub struct ScatterPlot {
 
     #[rust]
     initial_y_range: (f64, f64),
+
+    // Hover tooltip (disabled by default - enable with set_hover(true))
+    #[rust]
+    hover: PlotHover,
+
+    /// Nearest-point index per series, built on first hover after a data change
+    #[rust]
+    point_index: Option<Vec<PointIndex>>,
+
+    // Level of detail for large series (on by default)
+    #[rust]
+    lod: LodOptions,
+
+    #[rust]
+    lod_cache: LodCache,
+
+    /// Drawn area, for pan/zoom hit testing
+    #[rust]
+    area: Area,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for ScatterPlot {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         self.view.handle_event(cx, event, scope);
 
+        if self.hover.enabled {
+            let (x_scale, y_scale) = (self.axes.x.scale, self.axes.y.scale);
+            let series = &self.series;
+            let indexes: &[PointIndex] = self.point_index.get_or_insert_with(|| {
+                series.iter().map(|s| PointIndex::build(s, x_scale, y_scale)).collect()
+            });
+            let radius = self.hover.radius;
+            let (action, redraw) = self.hover.handle_event(event, &self.axes, |pos| {
+                pick_nearest(&self.axes, series, indexes, pos, radius)
+            });
+            if let Some(action) = action {
+                cx.widget_action(self.widget_uid(), &scope.path, action);
+            }
+            if redraw {
+                self.redraw(cx);
+            }
+        }
+
         if !self.interactive {
             return;
         }
 
-        match event.hits(cx, self.view.area()) {
+        match event.hits(cx, self.area) {
             Hit::FingerDown(fe) => {
                 self.is_dragging = true;
                 self.drag_start = fe.abs;
-                self.initial_x_range = self.x_range;
-                self.initial_y_range = self.y_range;
+                self.initial_x_range = self.axes.x.range;
+                self.initial_y_range = self.axes.y.range;
             }
             Hit::FingerMove(fe) => {
-                if self.is_dragging && self.plot_area.width() > 0.0 && self.plot_area.height() > 0.0 {
+                if self.is_dragging && self.axes.plot_area.width() > 0.0 && self.axes.plot_area.height() > 0.0 {
                     let dx_pixels = fe.abs.x - self.drag_start.x;
                     let dy_pixels = fe.abs.y - self.drag_start.y;
 
                     let x_range_size = self.initial_x_range.1 - self.initial_x_range.0;
                     let y_range_size = self.initial_y_range.1 - self.initial_y_range.0;
 
-                    let dx_data = -dx_pixels * x_range_size / self.plot_area.width();
-                    let dy_data = dy_pixels * y_range_size / self.plot_area.height();
+                    let dx_data = -dx_pixels * x_range_size / self.axes.plot_area.width();
+                    let dy_data = dy_pixels * y_range_size / self.axes.plot_area.height();
 
-                    self.x_range = (
+                    self.axes.x.range = (
                         self.initial_x_range.0 + dx_data,
                         self.initial_x_range.1 + dx_data,
                     );
-                    self.y_range = (
+                    self.axes.y.range = (
                         self.initial_y_range.0 + dy_data,
                         self.initial_y_range.1 + dy_data,
                     );
@@ -143,23 +166,23 @@ impl Widget for ScatterPlot {
                 let mouse_x = fe.abs.x;
                 let mouse_y = fe.abs.y;
 
-                if mouse_x >= self.plot_area.left && mouse_x <= self.plot_area.right
-                    && mouse_y >= self.plot_area.top && mouse_y <= self.plot_area.bottom
+                if mouse_x >= self.axes.plot_area.left && mouse_x <= self.axes.plot_area.right
+                    && mouse_y >= self.axes.plot_area.top && mouse_y <= self.axes.plot_area.bottom
                 {
-                    let rel_x = (mouse_x - self.plot_area.left) / self.plot_area.width();
-                    let rel_y = (self.plot_area.bottom - mouse_y) / self.plot_area.height();
+                    let rel_x = (mouse_x - self.axes.plot_area.left) / self.axes.plot_area.width();
+                    let rel_y = (self.axes.plot_area.bottom - mouse_y) / self.axes.plot_area.height();
 
-                    let data_x = self.x_range.0 + rel_x * (self.x_range.1 - self.x_range.0);
-                    let data_y = self.y_range.0 + rel_y * (self.y_range.1 - self.y_range.0);
+                    let data_x = self.axes.x.range.0 + rel_x * (self.axes.x.range.1 - self.axes.x.range.0);
+                    let data_y = self.axes.y.range.0 + rel_y * (self.axes.y.range.1 - self.axes.y.range.0);
 
-                    let new_x_range = (self.x_range.1 - self.x_range.0) * zoom_factor;
-                    let new_y_range = (self.y_range.1 - self.y_range.0) * zoom_factor;
+                    let new_x_range = (self.axes.x.range.1 - self.axes.x.range.0) * zoom_factor;
+                    let new_y_range = (self.axes.y.range.1 - self.axes.y.range.0) * zoom_factor;
 
-                    self.x_range = (
+                    self.axes.x.range = (
                         data_x - rel_x * new_x_range,
                         data_x + (1.0 - rel_x) * new_x_range,
                     );
-                    self.y_range = (
+                    self.axes.y.range = (
                         data_y - rel_y * new_y_range,
                         data_y + (1.0 - rel_y) * new_y_range,
                     );
@@ -178,30 +201,77 @@ impl Widget for ScatterPlot {
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
-        let rect = cx.walk_turtle(walk);
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
         if rect.size.x > 0.0 && rect.size.y > 0.0 {
-            self.update_plot_area(rect);
-            self.draw_grid(cx);
-            self.draw_axes(cx);
+            self.axes.update_plot_area(rect);
+            self.axes.draw_grid(cx, &mut self.draw_line);
+            self.axes.draw_axes(cx, &mut self.draw_line);
             self.draw_points(cx);
             self.draw_labels(cx);
             self.draw_legend(cx);
         }
 
+        // The hover overlay is not part of exported frames
+        self.capture.end();
+
+        if rect.size.x > 0.0 && rect.size.y > 0.0 {
+            let series = &self.series;
+            self.hover.draw(
+                cx,
+                &self.axes,
+                |i| series.get(i).and_then(|s| s.color).unwrap_or_else(|| get_color(i)),
+                &mut self.draw_line,
+                &mut self.draw_point,
+                &mut self.draw_fill,
+                &mut self.label,
+            );
+        }
+
         DrawStep::done()
     }
 }
 
+impl PlotExport for ScatterPlot {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
+impl Subplot for ScatterPlot {
+    fn subplot_axes(&self) -> &Axes {
+        &self.axes
+    }
+
+    fn subplot_axes_mut(&mut self) -> &mut Axes {
+        &mut self.axes
+    }
+
+    fn legend_entries(&self) -> Vec<(String, Vec4)> {
+        self.series.iter().enumerate()
+            .map(|(idx, s)| (s.label.clone(), s.color.unwrap_or_else(|| get_color(idx))))
+            .collect()
+    }
+}
+
 impl ScatterPlot {
     /// Add a data series to the plot
     pub fn add_series(&mut self, series: Series) {
         self.series.push(series);
+        self.point_index = None;
+        self.lod_cache.clear();
         self.auto_range();
     }
 
     /// Clear all series
     pub fn clear(&mut self) {
         self.series.clear();
+        self.point_index = None;
+        self.lod_cache.clear();
     }
 
     /// Set plot title
@@ -221,12 +291,12 @@ impl ScatterPlot {
 
     /// Set X range manually
     pub fn set_xlim(&mut self, min: f64, max: f64) {
-        self.x_range = (min, max);
+        self.axes.x.range = (min, max);
     }
 
     /// Set Y range manually
     pub fn set_ylim(&mut self, min: f64, max: f64) {
-        self.y_range = (min, max);
+        self.axes.y.range = (min, max);
     }
 
     /// Set point radius
@@ -244,6 +314,21 @@ impl ScatterPlot {
         self.use_gradient = use_gradient;
     }
 
+    /// Set the tick label format of the X axis
+    pub fn set_x_tick_format(&mut self, formatter: TickFormatter) {
+        self.axes.x.formatter = formatter;
+    }
+
+    /// Set the tick label format of the Y axis
+    pub fn set_y_tick_format(&mut self, formatter: TickFormatter) {
+        self.axes.y.formatter = formatter;
+    }
+
+    /// Show or hide minor ticks and minor grid lines
+    pub fn set_minor_ticks(&mut self, show: bool) {
+        self.axes.set_show_minor(show);
+    }
+
     fn auto_range(&mut self) {
         let mut x_min = f64::MAX;
         let mut x_max = f64::MIN;
@@ -251,11 +336,9 @@ impl ScatterPlot {
         let mut y_max = f64::MIN;
 
         for s in &self.series {
-            for &x in &s.x {
+            for (x, y) in s.points() {
                 x_min = x_min.min(x);
                 x_max = x_max.max(x);
-            }
-            for &y in &s.y {
                 y_min = y_min.min(y);
                 y_max = y_max.max(y);
             }
@@ -265,69 +348,27 @@ impl ScatterPlot {
         let x_pad = (x_max - x_min) * 0.1;
         let y_pad = (y_max - y_min) * 0.1;
 
-        self.x_range = (x_min - x_pad, x_max + x_pad);
-        self.y_range = (y_min - y_pad, y_max + y_pad);
-    }
-
-    fn update_plot_area(&mut self, rect: Rect) {
-        self.plot_area = PlotArea::new(
-            rect.pos.x + self.left_margin,
-            rect.pos.y + self.top_margin,
-            rect.pos.x + rect.size.x - self.right_margin,
-            rect.pos.y + rect.size.y - self.bottom_margin,
-        );
+        self.axes.x.range = (x_min - x_pad, x_max + x_pad);
+        self.axes.y.range = (y_min - y_pad, y_max + y_pad);
     }
 
     fn data_to_pixel(&self, x: f64, y: f64) -> DVec2 {
-        let px = self.plot_area.left + (x - self.x_range.0) / (self.x_range.1 - self.x_range.0) * self.plot_area.width();
-        let py = self.plot_area.bottom - (y - self.y_range.0) / (self.y_range.1 - self.y_range.0) * self.plot_area.height();
-        dvec2(px, py)
-    }
-
-    fn draw_grid(&mut self, cx: &mut Cx2d) {
-        if !self.show_grid {
-            return;
-        }
-
-        self.draw_line.color = vec4(0.9, 0.9, 0.9, 1.0);
-
-        // Horizontal grid lines
-        let y_ticks = self.generate_ticks(self.y_range.0, self.y_range.1, 5);
-        for y in &y_ticks {
-            let p1 = self.data_to_pixel(self.x_range.0, *y);
-            let p2 = self.data_to_pixel(self.x_range.1, *y);
-            self.draw_line.draw_line(cx, p1, p2, 0.5);
-        }
-
-        // Vertical grid lines
-        let x_ticks = self.generate_ticks(self.x_range.0, self.x_range.1, 5);
-        for x in &x_ticks {
-            let p1 = self.data_to_pixel(*x, self.y_range.0);
-            let p2 = self.data_to_pixel(*x, self.y_range.1);
-            self.draw_line.draw_line(cx, p1, p2, 0.5);
-        }
-    }
-
-    fn draw_axes(&mut self, cx: &mut Cx2d) {
-        self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-
-        // X axis
-        let x1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-        let x2 = dvec2(self.plot_area.right, self.plot_area.bottom);
-        self.draw_line.draw_line(cx, x1, x2, 1.0);
-
-        // Y axis
-        let y1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-        let y2 = dvec2(self.plot_area.left, self.plot_area.top);
-        self.draw_line.draw_line(cx, y1, y2, 1.0);
+        self.axes.data_to_pixel(x, y)
     }
 
     fn draw_points(&mut self, cx: &mut Cx2d) {
+        // Off-screen and overlapping markers are culled for large series
+        let key = LodKey::new(&self.axes, &self.series);
+        let radius = self.point_radius;
+        let selections = self.lod_cache.get_or_insert(key, || {
+            self.series.iter().map(|s| cull_scatter(s, &self.axes, radius, &self.lod)).collect()
+        });
         for (idx, series) in self.series.iter().enumerate() {
             let color = series.color.unwrap_or_else(|| get_color(idx));
 
-            for i in 0..series.x.len() {
-                let p = self.data_to_pixel(series.x[i], series.y[i]);
+            for &i in &selections[idx] {
+                let (x, y) = series.point(i);
+                let p = self.axes.data_to_pixel(x, y);
 
                 if self.use_gradient {
                     // Radial gradient using same-hue lighter/darker colors
@@ -343,28 +384,12 @@ impl ScatterPlot {
     }
 
     fn draw_labels(&mut self, cx: &mut Cx2d) {
-        self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
-
-        // X axis tick labels
-        let x_ticks = self.generate_ticks(self.x_range.0, self.x_range.1, 5);
-        for x in &x_ticks {
-            let p = self.data_to_pixel(*x, self.y_range.0);
-            let label = format!("{:.1}", x);
-            self.label.draw_at(cx, dvec2(p.x, p.y + 5.0), &label, TextAnchor::TopCenter);
-        }
-
-        // Y axis tick labels
-        let y_ticks = self.generate_ticks(self.y_range.0, self.y_range.1, 5);
-        for y in &y_ticks {
-            let p = self.data_to_pixel(self.x_range.0, *y);
-            let label = format!("{:.1}", y);
-            self.label.draw_at(cx, dvec2(p.x - 5.0, p.y), &label, TextAnchor::MiddleRight);
-        }
+        self.axes.draw_tick_labels(cx, &mut self.label);
 
         // Title
         if !self.title.is_empty() {
-            let center_x = (self.plot_area.left + self.plot_area.right) / 2.0;
-            self.label.draw_at(cx, dvec2(center_x, self.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
+            let center_x = (self.axes.plot_area.left + self.axes.plot_area.right) / 2.0;
+            self.label.draw_at(cx, dvec2(center_x, self.axes.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
         }
     }
 
@@ -382,20 +407,20 @@ impl ScatterPlot {
 
         let (legend_x, legend_y) = match self.legend_position {
             LegendPosition::TopRight => (
-                self.plot_area.right - legend_width - 10.0,
-                self.plot_area.top + 10.0,
+                self.axes.plot_area.right - legend_width - 10.0,
+                self.axes.plot_area.top + 10.0,
             ),
             LegendPosition::TopLeft => (
-                self.plot_area.left + 10.0,
-                self.plot_area.top + 10.0,
+                self.axes.plot_area.left + 10.0,
+                self.axes.plot_area.top + 10.0,
             ),
             LegendPosition::BottomRight => (
-                self.plot_area.right - legend_width - 10.0,
-                self.plot_area.bottom - legend_height - 10.0,
+                self.axes.plot_area.right - legend_width - 10.0,
+                self.axes.plot_area.bottom - legend_height - 10.0,
             ),
             LegendPosition::BottomLeft => (
-                self.plot_area.left + 10.0,
-                self.plot_area.bottom - legend_height - 10.0,
+                self.axes.plot_area.left + 10.0,
+                self.axes.plot_area.bottom - legend_height - 10.0,
             ),
             LegendPosition::None => return,
         };
@@ -433,23 +458,65 @@ impl ScatterPlot {
         }
     }
 
-    fn generate_ticks(&self, min: f64, max: f64, count: usize) -> Vec<f64> {
-        let step = (max - min) / count as f64;
-        (0..=count).map(|i| min + i as f64 * step).collect()
-    }
-
     /// Enable or disable interactive pan/zoom
     pub fn set_interactive(&mut self, interactive: bool) {
         self.interactive = interactive;
     }
 
+    /// Enable or disable marker culling; off draws every point
+    pub fn set_lod(&mut self, enabled: bool) {
+        self.lod.enabled = enabled;
+        self.lod_cache.clear();
+    }
+
+    /// Most markers drawn per series; `None` means 10 000
+    pub fn set_point_budget(&mut self, budget: Option<usize>) {
+        self.lod.point_budget = budget;
+        self.lod_cache.clear();
+    }
+
     /// Reset view to auto-fit all data
     pub fn reset_view(&mut self) {
         self.auto_range();
     }
 
+    /// Enable hover feedback: snap markers on the nearest point of each
+    /// series within the pick radius, a tooltip, and `PlotAction`s
+    pub fn set_hover(&mut self, enabled: bool) {
+        self.hover.enabled = enabled;
+        if !enabled {
+            self.hover.clear();
+        }
+    }
+
+    /// Show or hide the hover crosshair
+    pub fn set_crosshair(&mut self, show: bool) {
+        self.hover.crosshair = show;
+    }
+
+    /// Set how close (pixels) the pointer must be to pick a point
+    pub fn set_pick_radius(&mut self, radius: f64) {
+        self.hover.radius = radius;
+    }
+
+    /// Set the hover tooltip colors and spacing
+    pub fn set_tooltip_style(&mut self, style: TooltipStyle) {
+        self.hover.style = style;
+    }
+
+    /// Add custom lines to the tooltip of each hovered point
+    pub fn set_tooltip_content(&mut self, content: impl Fn(&PickedPoint) -> Option<String> + 'static) {
+        self.hover.content = Some(Box::new(content));
+    }
+
+    /// Points currently under the pointer, closest first
+    pub fn hovered_points(&self) -> &[PickedPoint] {
+        &self.hover.picked
+    }
+
     fn redraw(&mut self, cx: &mut Cx) {
         self.view.redraw(cx);
+        self.area.redraw(cx);
     }
 }
 
@@ -502,18 +569,98 @@ impl ScatterPlotRef {
         }
     }
 
+    pub fn set_lod(&self, enabled: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_lod(enabled);
+        }
+    }
+
+    pub fn set_point_budget(&self, budget: Option<usize>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_point_budget(budget);
+        }
+    }
+
     pub fn set_use_gradient(&self, use_gradient: bool) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.set_use_gradient(use_gradient);
         }
     }
 
+    pub fn set_x_tick_format(&self, formatter: TickFormatter) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_x_tick_format(formatter);
+        }
+    }
+
+    pub fn set_y_tick_format(&self, formatter: TickFormatter) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_y_tick_format(formatter);
+        }
+    }
+
+    pub fn set_minor_ticks(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_minor_ticks(show);
+        }
+    }
+
     pub fn reset_view(&self) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.reset_view();
         }
     }
 
+    pub fn set_hover(&self, enabled: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_hover(enabled);
+        }
+    }
+
+    pub fn set_crosshair(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_crosshair(show);
+        }
+    }
+
+    pub fn set_pick_radius(&self, radius: f64) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_pick_radius(radius);
+        }
+    }
+
+    pub fn set_tooltip_style(&self, style: TooltipStyle) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_tooltip_style(style);
+        }
+    }
+
+    pub fn set_tooltip_content(&self, content: impl Fn(&PickedPoint) -> Option<String> + 'static) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_tooltip_content(content);
+        }
+    }
+
+    /// Points now under the pointer, if the hover changed
+    pub fn hovered(&self, actions: &Actions) -> Option<Vec<PickedPoint>> {
+        if let Some(item) = actions.find_widget_action(self.widget_uid()) {
+            if let PlotAction::Hover(points) = item.cast() {
+                return Some(points);
+            }
+        }
+        None
+    }
+
+    /// The point that was clicked, if any
+    pub fn picked(&self, actions: &Actions) -> Option<PickedPoint> {
+        if let Some(item) = actions.find_widget_action(self.widget_uid()) {
+            if let PlotAction::Pick(point) = item.cast() {
+                return Some(point);
+            }
+        }
+        None
+    }
+
     pub fn redraw(&self, cx: &mut Cx) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.redraw(cx);
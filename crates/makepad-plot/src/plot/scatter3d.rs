@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -50,13 +51,14 @@ pub struct Scatter3D {
     #[rust] default_color: Vec4,
     #[rust] default_size: f64,
     #[rust] view3d: View3D,
-    #[rust] x_range: (f64, f64),
-    #[rust] y_range: (f64, f64),
-    #[rust] z_range: (f64, f64),
+    #[rust] axes3d: Axes3D,
     #[rust] zoom: f64,
     #[rust] drag_start: Option<DVec2>,
     #[rust] start_azimuth: f64,
     #[rust] start_elevation: f64,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Scatter3D {
@@ -88,9 +90,11 @@ impl Scatter3D {
         let pad_x = (x_max - x_min).max(0.1) * 0.1;
         let pad_y = (y_max - y_min).max(0.1) * 0.1;
         let pad_z = (z_max - z_min).max(0.1) * 0.1;
-        self.x_range = (x_min - pad_x, x_max + pad_x);
-        self.y_range = (y_min - pad_y, y_max + pad_y);
-        self.z_range = (z_min - pad_z, z_max + pad_z);
+        self.axes3d.set_ranges(
+            (x_min - pad_x, x_max + pad_x),
+            (y_min - pad_y, y_max + pad_y),
+            (z_min - pad_z, z_max + pad_z),
+        );
     }
 
     pub fn set_color(&mut self, c: Vec4) { self.default_color = c; }
@@ -98,6 +102,7 @@ impl Scatter3D {
     pub fn set_view(&mut self, view: View3D) { self.view3d = view; }
     pub fn set_azimuth(&mut self, az: f64) { self.view3d.azimuth = az; }
     pub fn set_elevation(&mut self, el: f64) { self.view3d.elevation = el; }
+    pub fn set_axis_labels(&mut self, x: impl Into<String>, y: impl Into<String>, z: impl Into<String>) { self.axes3d.set_labels(x, y, z); }
     pub fn clear(&mut self) { self.points.clear(); }
     pub fn redraw(&mut self, cx: &mut Cx) { self.view.redraw(cx); }
 }
@@ -105,6 +110,7 @@ impl Scatter3D {
 impl Widget for Scatter3D {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.points.is_empty() {
             // Initialize defaults
@@ -113,23 +119,12 @@ impl Widget for Scatter3D {
             if self.default_color == Vec4::default() { self.default_color = vec4(0.12, 0.47, 0.71, 1.0); }
             if self.zoom == 0.0 { self.zoom = 1.0; }
 
-            let cx_center = rect.pos.x + rect.size.x * 0.5;
-            let cy_center = rect.pos.y + rect.size.y * 0.5;
-            let scale = rect.size.x.min(rect.size.y) * 0.35 * self.zoom;
-
-            // Normalize to [-1, 1]
-            let x_scale = if self.x_range.1 != self.x_range.0 { 2.0 / (self.x_range.1 - self.x_range.0) } else { 1.0 };
-            let y_scale = if self.y_range.1 != self.y_range.0 { 2.0 / (self.y_range.1 - self.y_range.0) } else { 1.0 };
-            let z_scale = if self.z_range.1 != self.z_range.0 { 2.0 / (self.z_range.1 - self.z_range.0) } else { 1.0 };
-            let x_off = (self.x_range.0 + self.x_range.1) * 0.5;
-            let y_off = (self.y_range.0 + self.y_range.1) * 0.5;
-            let z_off = (self.z_range.0 + self.z_range.1) * 0.5;
+            self.axes3d.layout(rect, &self.view3d, self.zoom);
+            self.axes3d.draw_back(cx, &mut self.draw_line);
 
             // Sort points back to front
             let mut sorted: Vec<(f64, usize)> = self.points.iter().enumerate().map(|(i, p)| {
-                let x = (p.x - x_off) * x_scale;
-                let y = (p.y - y_off) * y_scale;
-                let z = (p.z - z_off) * z_scale;
+                let [x, y, z] = self.axes3d.to_box(p.x, p.y, p.z);
                 (self.view3d.depth(x, y, z), i)
             }).collect();
             sorted.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
@@ -137,44 +132,23 @@ impl Widget for Scatter3D {
             // Draw points
             for (_, idx) in sorted {
                 let p = &self.points[idx];
-                let x = (p.x - x_off) * x_scale;
-                let y = (p.y - y_off) * y_scale;
-                let z = (p.z - z_off) * z_scale;
-
-                let (sx, sy) = self.view3d.project(x, y, z);
-                let screen_x = cx_center + sx * scale;
-                let screen_y = cy_center - sy * scale;
-
                 let color = p.color.unwrap_or(self.default_color);
                 let size = p.size.unwrap_or(self.default_size);
 
                 self.draw_point.color = color;
-                self.draw_point.draw_point(cx, dvec2(screen_x, screen_y), size);
+                self.draw_point.draw_point(cx, self.axes3d.project(p.x, p.y, p.z), size);
             }
 
-            // Draw 3D axes
-            self.draw_line.color = vec4(0.5, 0.5, 0.5, 0.8);
-            let axis_len = 1.2;
-            let axes = [
-                ((-axis_len, 0.0, 0.0), (axis_len, 0.0, 0.0)),
-                ((0.0, -axis_len, 0.0), (0.0, axis_len, 0.0)),
-                ((0.0, 0.0, -axis_len * 0.5), (0.0, 0.0, axis_len)),
-            ];
-            for ((x0, y0, z0), (x1, y1, z1)) in axes {
-                let (sx0, sy0) = self.view3d.project(x0, y0, z0);
-                let (sx1, sy1) = self.view3d.project(x1, y1, z1);
-                self.draw_line.draw_line(cx,
-                    dvec2(cx_center + sx0 * scale, cy_center - sy0 * scale),
-                    dvec2(cx_center + sx1 * scale, cy_center - sy1 * scale),
-                    1.0);
-            }
+            self.axes3d.draw_labels(cx, &mut self.label);
 
             // Draw title
             if !self.title.is_empty() {
+                self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
                 self.label.draw_at(cx, dvec2(rect.pos.x + 10.0, rect.pos.y + 5.0), &self.title, TextAnchor::TopLeft);
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -209,6 +183,16 @@ impl Widget for Scatter3D {
     }
 }
 
+impl PlotExport for Scatter3D {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl Scatter3DRef {
     pub fn set_title(&self, title: impl Into<String>) { if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); } }
     pub fn set_data(&self, x: Vec<f64>, y: Vec<f64>, z: Vec<f64>) { if let Some(mut inner) = self.borrow_mut() { inner.set_data(x, y, z); } }
@@ -217,6 +201,7 @@ impl Scatter3DRef {
     pub fn set_view(&self, view: View3D) { if let Some(mut inner) = self.borrow_mut() { inner.set_view(view); } }
     pub fn set_azimuth(&self, az: f64) { if let Some(mut inner) = self.borrow_mut() { inner.set_azimuth(az); } }
     pub fn set_elevation(&self, el: f64) { if let Some(mut inner) = self.borrow_mut() { inner.set_elevation(el); } }
+    pub fn set_axis_labels(&self, x: impl Into<String>, y: impl Into<String>, z: impl Into<String>) { if let Some(mut inner) = self.borrow_mut() { inner.set_axis_labels(x, y, z); } }
     pub fn clear(&self) { if let Some(mut inner) = self.borrow_mut() { inner.clear(); } }
     pub fn redraw(&self, cx: &mut Cx) { if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); } }
 }
@@ -255,13 +240,14 @@ pub struct Line3D {
     #[rust] title: String,
     #[rust] series: Vec<Line3DSeries>,
     #[rust] view3d: View3D,
-    #[rust] x_range: (f64, f64),
-    #[rust] y_range: (f64, f64),
-    #[rust] z_range: (f64, f64),
+    #[rust] axes3d: Axes3D,
     #[rust] zoom: f64,
     #[rust] drag_start: Option<DVec2>,
     #[rust] start_azimuth: f64,
     #[rust] start_elevation: f64,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Line3D {
@@ -287,15 +273,18 @@ impl Line3D {
             let pad_x = (x_max - x_min).max(0.1) * 0.1;
             let pad_y = (y_max - y_min).max(0.1) * 0.1;
             let pad_z = (z_max - z_min).max(0.1) * 0.1;
-            self.x_range = (x_min - pad_x, x_max + pad_x);
-            self.y_range = (y_min - pad_y, y_max + pad_y);
-            self.z_range = (z_min - pad_z, z_max + pad_z);
+            self.axes3d.set_ranges(
+                (x_min - pad_x, x_max + pad_x),
+                (y_min - pad_y, y_max + pad_y),
+                (z_min - pad_z, z_max + pad_z),
+            );
         }
     }
 
     pub fn set_view(&mut self, view: View3D) { self.view3d = view; }
     pub fn set_azimuth(&mut self, az: f64) { self.view3d.azimuth = az; }
     pub fn set_elevation(&mut self, el: f64) { self.view3d.elevation = el; }
+    pub fn set_axis_labels(&mut self, x: impl Into<String>, y: impl Into<String>, z: impl Into<String>) { self.axes3d.set_labels(x, y, z); }
     pub fn clear(&mut self) { self.series.clear(); }
     pub fn redraw(&mut self, cx: &mut Cx) { self.view.redraw(cx); }
 }
@@ -303,40 +292,16 @@ impl Line3D {
 impl Widget for Line3D {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.series.is_empty() {
             // Initialize defaults
             if self.view3d.distance == 0.0 { self.view3d = View3D::new(); }
             if self.zoom == 0.0 { self.zoom = 1.0; }
 
-            let cx_center = rect.pos.x + rect.size.x * 0.5;
-            let cy_center = rect.pos.y + rect.size.y * 0.5;
-            let scale = rect.size.x.min(rect.size.y) * 0.35 * self.zoom;
-
-            // Normalize to [-1, 1]
-            let x_scale = if self.x_range.1 != self.x_range.0 { 2.0 / (self.x_range.1 - self.x_range.0) } else { 1.0 };
-            let y_scale = if self.y_range.1 != self.y_range.0 { 2.0 / (self.y_range.1 - self.y_range.0) } else { 1.0 };
-            let z_scale = if self.z_range.1 != self.z_range.0 { 2.0 / (self.z_range.1 - self.z_range.0) } else { 1.0 };
-            let x_off = (self.x_range.0 + self.x_range.1) * 0.5;
-            let y_off = (self.y_range.0 + self.y_range.1) * 0.5;
-            let z_off = (self.z_range.0 + self.z_range.1) * 0.5;
-
-            // Draw 3D axes first
-            self.draw_line.color = vec4(0.5, 0.5, 0.5, 0.8);
-            let axis_len = 1.2;
-            let axes = [
-                ((-axis_len, 0.0, 0.0), (axis_len, 0.0, 0.0)),
-                ((0.0, -axis_len, 0.0), (0.0, axis_len, 0.0)),
-                ((0.0, 0.0, -axis_len * 0.5), (0.0, 0.0, axis_len)),
-            ];
-            for ((x0, y0, z0), (x1, y1, z1)) in axes {
-                let (sx0, sy0) = self.view3d.project(x0, y0, z0);
-                let (sx1, sy1) = self.view3d.project(x1, y1, z1);
-                self.draw_line.draw_line(cx,
-                    dvec2(cx_center + sx0 * scale, cy_center - sy0 * scale),
-                    dvec2(cx_center + sx1 * scale, cy_center - sy1 * scale),
-                    1.0);
-            }
+            // Box and grid first, so the lines pass in front of them
+            self.axes3d.layout(rect, &self.view3d, self.zoom);
+            self.axes3d.draw_back(cx, &mut self.draw_line);
 
             // Draw series
             for s in &self.series {
@@ -344,29 +309,22 @@ impl Widget for Line3D {
                 let n = s.x.len().min(s.y.len()).min(s.z.len());
 
                 for i in 1..n {
-                    let x0 = (s.x[i-1] - x_off) * x_scale;
-                    let y0 = (s.y[i-1] - y_off) * y_scale;
-                    let z0 = (s.z[i-1] - z_off) * z_scale;
-                    let (sx0, sy0) = self.view3d.project(x0, y0, z0);
-
-                    let x1 = (s.x[i] - x_off) * x_scale;
-                    let y1 = (s.y[i] - y_off) * y_scale;
-                    let z1 = (s.z[i] - z_off) * z_scale;
-                    let (sx1, sy1) = self.view3d.project(x1, y1, z1);
-
-                    self.draw_line.draw_line(cx,
-                        dvec2(cx_center + sx0 * scale, cy_center - sy0 * scale),
-                        dvec2(cx_center + sx1 * scale, cy_center - sy1 * scale),
-                        s.width);
+                    let from = self.axes3d.project(s.x[i-1], s.y[i-1], s.z[i-1]);
+                    let to = self.axes3d.project(s.x[i], s.y[i], s.z[i]);
+                    self.draw_line.draw_line(cx, from, to, s.width);
                 }
             }
 
+            self.axes3d.draw_labels(cx, &mut self.label);
+
             // Draw title
             if !self.title.is_empty() {
+                self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
                 self.label.draw_at(cx, dvec2(rect.pos.x + 10.0, rect.pos.y + 5.0), &self.title, TextAnchor::TopLeft);
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -401,12 +359,23 @@ impl Widget for Line3D {
     }
 }
 
+impl PlotExport for Line3D {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl Line3DRef {
     pub fn set_title(&self, title: impl Into<String>) { if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); } }
     pub fn add_series(&self, s: Line3DSeries) { if let Some(mut inner) = self.borrow_mut() { inner.add_series(s); } }
     pub fn set_view(&self, view: View3D) { if let Some(mut inner) = self.borrow_mut() { inner.set_view(view); } }
     pub fn set_azimuth(&self, az: f64) { if let Some(mut inner) = self.borrow_mut() { inner.set_azimuth(az); } }
     pub fn set_elevation(&self, el: f64) { if let Some(mut inner) = self.borrow_mut() { inner.set_elevation(el); } }
+    pub fn set_axis_labels(&self, x: impl Into<String>, y: impl Into<String>, z: impl Into<String>) { if let Some(mut inner) = self.borrow_mut() { inner.set_axis_labels(x, y, z); } }
     pub fn clear(&self) { if let Some(mut inner) = self.borrow_mut() { inner.clear(); } }
     pub fn redraw(&self, cx: &mut Cx) { if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); } }
 }
@@ -0,0 +1,1379 @@
+// Declarative plot specification, deserialized from JSON
+//
+// A spec names its data, one or more layers of marks and how data fields map
+// onto visual channels, in the spirit of Vega-Lite:
+//
+//   {"data": {"values": [{"month": "Jan", "sales": 12}, ...]},
+//    "mark": "bar",
+//    "encoding": {"x": {"field": "month", "type": "nominal"},
+//                 "y": {"field": "sales"}}}
+//
+// `PlotSpec::compile` resolves fields, scales and colors into a
+// `CompiledPlot` in data units, which `PlotSpecView` draws. A spec with a
+// `facet` compiles to one plot per value of the facet field instead, all on
+// the scales of the whole data, drawn as a grid of small multiples.
+
+use std::collections::HashMap;
+
+use makepad_widgets::*;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use super::*;
+
+/// Radius range of points sized by a field, in pixels
+const SIZE_RANGE: (f64, f64) = (2.0, 12.0);
+/// Share of the category band (or of the closest x spacing) a bar group fills
+const BAR_FILL: f64 = 0.8;
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlotSpec {
+    pub title: Option<String>,
+    /// Data shared by every layer that has none of its own
+    pub data: Option<DataSpec>,
+    pub mark: Option<MarkSpec>,
+    /// Channels shared by every layer; a layer's own channels win
+    pub encoding: Encoding,
+    /// Marks drawn over each other on the same axes, first to last
+    pub layer: Vec<LayerSpec>,
+    pub annotations: Vec<AnnotationSpec>,
+    pub legend: Option<LegendSpec>,
+    /// Split the data into one panel per value of a field
+    pub facet: Option<FacetSpec>,
+}
+
+/// Small multiples: the same chart once per value of `field`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FacetSpec {
+    pub field: String,
+    /// Panels per row; a near-square grid when unset
+    pub columns: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LayerSpec {
+    pub data: Option<DataSpec>,
+    pub mark: Option<MarkSpec>,
+    pub encoding: Encoding,
+}
+
+/// Table of records: either `values`, a list of objects, or `columns`, one
+/// array per field
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DataSpec {
+    pub values: Vec<Map<String, Value>>,
+    pub columns: HashMap<String, Vec<Value>>,
+}
+
+impl DataSpec {
+    fn len(&self) -> usize {
+        self.columns.values().map(Vec::len).max().unwrap_or(0).max(self.values.len())
+    }
+
+    /// Records whose `field` reads as `group`; all of them when no record
+    /// has the field
+    fn filtered(&self, field: &str, group: &str) -> DataSpec {
+        if !self.has_field(field) {
+            return self.clone();
+        }
+        let keep: Vec<bool> = self.column(field).iter().map(|v| !v.is_null() && display_value(v) == group).collect();
+        let kept = |i: usize| keep.get(i).copied().unwrap_or(false);
+        DataSpec {
+            values: self.values.iter().enumerate().filter(|(i, _)| kept(*i)).map(|(_, row)| row.clone()).collect(),
+            columns: self
+                .columns
+                .iter()
+                .map(|(name, column)| (name.clone(), column.iter().enumerate().filter(|(i, _)| kept(*i)).map(|(_, v)| v.clone()).collect()))
+                .collect(),
+        }
+    }
+
+    fn has_field(&self, field: &str) -> bool {
+        self.columns.contains_key(field) || self.values.iter().any(|row| row.contains_key(field))
+    }
+
+    /// Values of `field` in record order, null where a record lacks it
+    fn column(&self, field: &str) -> Vec<Value> {
+        let mut column = match self.columns.get(field) {
+            Some(values) => values.clone(),
+            None => self.values.iter().map(|row| row.get(field).cloned().unwrap_or(Value::Null)).collect(),
+        };
+        column.resize(self.len(), Value::Null);
+        column
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MarkType {
+    Line,
+    Point,
+    Bar,
+    Area,
+    /// Horizontal or vertical reference lines at each datum
+    Rule,
+    Text,
+}
+
+/// A mark, either just its type (`"line"`) or a definition with styling
+#[derive(Clone, Debug)]
+pub enum MarkSpec {
+    Type(MarkType),
+    Def(MarkDef),
+}
+
+// By hand rather than untagged, so a misspelt mark type is reported as such
+impl<'de> Deserialize<'de> for MarkSpec {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let mark = if value.is_string() {
+            serde_json::from_value(value).map(MarkSpec::Type)
+        } else {
+            serde_json::from_value(value).map(MarkSpec::Def)
+        };
+        mark.map_err(serde::de::Error::custom)
+    }
+}
+
+impl MarkSpec {
+    pub fn def(&self) -> MarkDef {
+        match self {
+            MarkSpec::Type(kind) => MarkDef::new(*kind),
+            MarkSpec::Def(def) => def.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkDef {
+    #[serde(rename = "type")]
+    pub kind: MarkType,
+    /// Hex color, used when no color channel is encoded
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub opacity: Option<f64>,
+    #[serde(default)]
+    pub stroke_width: Option<f64>,
+    #[serde(default)]
+    pub stroke_dash: Option<DashSpec>,
+    /// Point radius in pixels, or font size for text
+    #[serde(default)]
+    pub size: Option<f64>,
+    #[serde(default)]
+    pub shape: Option<ShapeSpec>,
+    /// Draw markers on the vertices of a line
+    #[serde(default)]
+    pub point: bool,
+    /// Stack bars and areas of different colors instead of placing bars side
+    /// by side and areas over each other
+    #[serde(default)]
+    pub stack: bool,
+}
+
+impl MarkDef {
+    pub fn new(kind: MarkType) -> Self {
+        Self {
+            kind,
+            color: None,
+            opacity: None,
+            stroke_width: None,
+            stroke_dash: None,
+            size: None,
+            shape: None,
+            point: false,
+            stack: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DashSpec {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+    DashDot,
+}
+
+impl DashSpec {
+    pub fn line_style(self) -> LineStyle {
+        match self {
+            DashSpec::Solid => LineStyle::Solid,
+            DashSpec::Dashed => LineStyle::Dashed,
+            DashSpec::Dotted => LineStyle::Dotted,
+            DashSpec::DashDot => LineStyle::DashDot,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShapeSpec {
+    #[default]
+    Circle,
+    Square,
+    TriangleUp,
+    TriangleDown,
+    Diamond,
+    Cross,
+    Plus,
+    Star,
+}
+
+impl ShapeSpec {
+    pub fn marker_style(self) -> MarkerStyle {
+        match self {
+            ShapeSpec::Circle => MarkerStyle::Circle,
+            ShapeSpec::Square => MarkerStyle::Square,
+            ShapeSpec::TriangleUp => MarkerStyle::TriangleUp,
+            ShapeSpec::TriangleDown => MarkerStyle::TriangleDown,
+            ShapeSpec::Diamond => MarkerStyle::Diamond,
+            ShapeSpec::Cross => MarkerStyle::Cross,
+            ShapeSpec::Plus => MarkerStyle::Plus,
+            ShapeSpec::Star => MarkerStyle::Star,
+        }
+    }
+}
+
+/// Which data field (or constant) drives each visual channel
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Encoding {
+    pub x: Option<ChannelSpec>,
+    pub y: Option<ChannelSpec>,
+    /// Lower end of bars and area bands; zero when absent
+    pub y2: Option<ChannelSpec>,
+    /// Splits the data into groups, one color and legend entry each
+    pub color: Option<ChannelSpec>,
+    /// Point radius
+    pub size: Option<ChannelSpec>,
+    /// Label of text marks
+    pub text: Option<ChannelSpec>,
+}
+
+impl Encoding {
+    /// These channels, with `parent`'s filling the gaps
+    fn merged(&self, parent: &Encoding) -> Encoding {
+        let pick = |own: &Option<ChannelSpec>, inherited: &Option<ChannelSpec>| own.clone().or_else(|| inherited.clone());
+        Encoding {
+            x: pick(&self.x, &parent.x),
+            y: pick(&self.y, &parent.y),
+            y2: pick(&self.y2, &parent.y2),
+            color: pick(&self.color, &parent.color),
+            size: pick(&self.size, &parent.size),
+            text: pick(&self.text, &parent.text),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelSpec {
+    pub field: Option<String>,
+    /// Constant used for every datum instead of a field
+    pub value: Option<Value>,
+    /// How field values are read; inferred from the data when absent
+    #[serde(rename = "type")]
+    pub kind: Option<FieldType>,
+    /// Axis or legend title; the field name by default
+    pub title: Option<String>,
+    pub scale: Option<ScaleSpec>,
+    pub axis: Option<AxisSpec>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldType {
+    Quantitative,
+    /// Dates: Unix seconds, or ISO 8601 strings such as "2024-03-05" and
+    /// "2024-03-05T14:30:00Z"
+    Temporal,
+    /// Ordered categories, placed in order of first appearance
+    Ordinal,
+    Nominal,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScaleSpec {
+    #[serde(rename = "type")]
+    pub kind: Option<ScaleKind>,
+    /// Fixed `[min, max]` instead of fitting the data
+    pub domain: Option<[f64; 2]>,
+    /// Include zero in the fitted range; on by default for bars and areas
+    pub zero: Option<bool>,
+    /// Hex colors for the color channel, in the order groups appear
+    pub range: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScaleKind {
+    Linear,
+    Log,
+    Symlog,
+    Time,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AxisSpec {
+    pub title: Option<String>,
+    /// "auto", "si", "percent" or fixed decimals as ".2f"
+    pub format: Option<String>,
+    pub grid: Option<bool>,
+    pub tick_count: Option<usize>,
+    /// `right` puts a y channel on a second axis
+    pub orient: Option<AxisOrient>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AxisOrient {
+    Left,
+    Right,
+    Bottom,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LegendSpec {
+    pub position: Option<LegendOrient>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LegendOrient {
+    TopRight,
+    TopLeft,
+    BottomRight,
+    BottomLeft,
+    None,
+}
+
+/// Reference lines, shaded spans, labels and arrows in data coordinates.
+/// x positions are read like the x field, so dates and category names work.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AnnotationSpec {
+    #[serde(rename_all = "camelCase")]
+    Hline {
+        y: f64,
+        #[serde(default)]
+        color: Option<String>,
+        #[serde(default)]
+        stroke_width: Option<f64>,
+        #[serde(default)]
+        stroke_dash: Option<DashSpec>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Vline {
+        x: Value,
+        #[serde(default)]
+        color: Option<String>,
+        #[serde(default)]
+        stroke_width: Option<f64>,
+        #[serde(default)]
+        stroke_dash: Option<DashSpec>,
+    },
+    Hspan {
+        y1: f64,
+        y2: f64,
+        #[serde(default)]
+        color: Option<String>,
+    },
+    Vspan {
+        x1: Value,
+        x2: Value,
+        #[serde(default)]
+        color: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Text {
+        x: Value,
+        y: f64,
+        text: String,
+        #[serde(default)]
+        color: Option<String>,
+        #[serde(default)]
+        font_size: Option<f64>,
+    },
+    /// Arrow pointing at `(x, y)` from the label at `(textX, textY)`
+    #[serde(rename_all = "camelCase")]
+    Arrow {
+        x: Value,
+        y: f64,
+        text_x: Value,
+        text_y: f64,
+        #[serde(default)]
+        text: Option<String>,
+        #[serde(default)]
+        color: Option<String>,
+    },
+}
+
+// =============================================================================
+// Compiled form
+// =============================================================================
+
+/// A spec resolved to data coordinates, ready to draw
+#[derive(Clone, Debug)]
+pub struct CompiledPlot {
+    pub title: String,
+    pub x: PlotAxis,
+    pub y: PlotAxis,
+    /// Second y axis on the right, when any layer asks for it
+    pub y2: Option<PlotAxis>,
+    pub x_title: String,
+    pub y_title: String,
+    pub y2_title: String,
+    /// Labels of a categorical x axis, at 0, 1, 2, ...
+    pub x_categories: Vec<String>,
+    pub marks: Vec<CompiledMark>,
+    pub annotations: SpecAnnotations,
+    pub legend_position: LegendPosition,
+    /// Legend entries: group name and color
+    pub legend: Vec<(String, Vec4)>,
+}
+
+/// A faceted spec resolved to one plot per group
+#[derive(Clone, Debug)]
+pub struct CompiledFacets {
+    pub title: String,
+    pub columns: usize,
+    /// Group value and its plot, titled with the value
+    pub panels: Vec<(String, CompiledPlot)>,
+}
+
+/// One color group of one layer
+#[derive(Clone, Debug)]
+pub struct CompiledMark {
+    pub kind: MarkType,
+    pub label: String,
+    pub color: Vec4,
+    /// Plotted against the right y axis
+    pub right_axis: bool,
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    /// Lower ends of bars and bands
+    pub y2: Vec<f64>,
+    /// Point radius, or font size of text marks, per datum
+    pub size: Vec<f64>,
+    pub text: Vec<String>,
+    pub line_width: f64,
+    pub line_style: LineStyle,
+    pub marker: MarkerStyle,
+    /// Markers on line vertices
+    pub show_points: bool,
+    /// Bar width and offset from the x position, in x units
+    pub bar_width: f64,
+    pub bar_offset: f64,
+    /// A rule spans the plot vertically at each x rather than horizontally
+    /// at each y
+    pub vertical: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct SpecAnnotations {
+    pub hlines: Vec<HLine>,
+    pub vlines: Vec<VLine>,
+    pub hspans: Vec<HSpan>,
+    pub vspans: Vec<VSpan>,
+    pub texts: Vec<TextAnnotation>,
+    pub arrows: Vec<ArrowAnnotation>,
+}
+
+impl std::fmt::Debug for SpecAnnotations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpecAnnotations")
+            .field("hlines", &self.hlines.len())
+            .field("vlines", &self.vlines.len())
+            .field("hspans", &self.hspans.len())
+            .field("vspans", &self.vspans.len())
+            .field("texts", &self.texts.len())
+            .field("arrows", &self.arrows.len())
+            .finish()
+    }
+}
+
+impl PlotSpec {
+    pub fn from_json(json: &str) -> Result<PlotSpec, String> {
+        serde_json::from_str(json).map_err(|e| format!("invalid plot spec: {}", e))
+    }
+
+    pub fn from_value(value: Value) -> Result<PlotSpec, String> {
+        serde_json::from_value(value).map_err(|e| format!("invalid plot spec: {}", e))
+    }
+
+    /// The layers to draw, each with inherited data and channels filled in
+    fn layers(&self) -> Result<Vec<(MarkDef, &DataSpec, Encoding)>, String> {
+        let layers: Vec<(Option<&MarkSpec>, Option<&DataSpec>, Encoding)> = if self.layer.is_empty() {
+            vec![(self.mark.as_ref(), self.data.as_ref(), self.encoding.clone())]
+        } else {
+            self.layer
+                .iter()
+                .map(|l| (l.mark.as_ref().or(self.mark.as_ref()), l.data.as_ref().or(self.data.as_ref()), l.encoding.merged(&self.encoding)))
+                .collect()
+        };
+        layers
+            .into_iter()
+            .enumerate()
+            .map(|(i, (mark, data, encoding))| {
+                let mark = mark.ok_or_else(|| format!("layer {} has no mark", i))?;
+                let data = data.ok_or_else(|| format!("layer {} has no data", i))?;
+                Ok((mark.def(), data, encoding))
+            })
+            .collect()
+    }
+
+    /// Values of the facet field in the order they first appear
+    pub fn facet_groups(&self) -> Vec<String> {
+        let Some(facet) = &self.facet else { return Vec::new() };
+        let mut groups: Vec<String> = Vec::new();
+        let data = self.data.iter().chain(self.layer.iter().filter_map(|l| l.data.as_ref()));
+        for data in data.filter(|d| d.has_field(&facet.field)) {
+            for value in data.column(&facet.field).iter().filter(|v| !v.is_null()) {
+                let name = display_value(value);
+                if !groups.contains(&name) {
+                    groups.push(name);
+                }
+            }
+        }
+        groups
+    }
+
+    /// Compile one plot per facet group. Axes, categories, colors, bar
+    /// slots and annotations come from the whole data so the panels compare
+    /// directly; axis titles stay on the outer panels and the legend on the
+    /// first. Data without the facet field shows in every panel.
+    pub fn compile_facets(&self) -> Result<CompiledFacets, String> {
+        let facet = self.facet.as_ref().ok_or("spec has no facet")?;
+        let groups = self.facet_groups();
+        if groups.is_empty() {
+            return Err(format!("no data has the facet field \"{}\"", facet.field));
+        }
+        let whole = PlotSpec { facet: None, ..self.clone() }.compile()?;
+        let count = groups.len();
+        let columns = facet.columns.unwrap_or_else(|| (count as f64).sqrt().ceil() as usize).clamp(1, count);
+
+        let mut panels = Vec::with_capacity(count);
+        for (index, group) in groups.into_iter().enumerate() {
+            let spec = PlotSpec {
+                title: None,
+                data: self.data.as_ref().map(|d| d.filtered(&facet.field, &group)),
+                layer: self
+                    .layer
+                    .iter()
+                    .map(|l| LayerSpec { data: l.data.as_ref().map(|d| d.filtered(&facet.field, &group)), ..l.clone() })
+                    .collect(),
+                annotations: Vec::new(),
+                facet: None,
+                ..self.clone()
+            };
+            let mut plot = spec.compile()?;
+            for mark in &mut plot.marks {
+                if !whole.x_categories.is_empty() {
+                    for x in &mut mark.x {
+                        let category = plot.x_categories.get(*x as usize).filter(|_| x.is_finite() && *x >= 0.0);
+                        *x = category.and_then(|c| whole.x_categories.iter().position(|w| w == c)).map_or(f64::NAN, |i| i as f64);
+                    }
+                }
+                let same = whole.marks.iter().find(|m| m.kind == mark.kind && m.label == mark.label && m.right_axis == mark.right_axis);
+                if let Some(same) = same {
+                    mark.color = same.color;
+                    mark.bar_width = same.bar_width;
+                    mark.bar_offset = same.bar_offset;
+                }
+            }
+            // Bottom panels are those with none below them
+            let (bottom, column) = (index + columns >= count, index % columns);
+            panels.push((group.clone(), CompiledPlot {
+                title: group,
+                x: whole.x.clone(),
+                y: whole.y.clone(),
+                y2: whole.y2.clone(),
+                x_title: if bottom { whole.x_title.clone() } else { String::new() },
+                y_title: if column == 0 { whole.y_title.clone() } else { String::new() },
+                y2_title: if column + 1 == columns || index + 1 == count { whole.y2_title.clone() } else { String::new() },
+                x_categories: whole.x_categories.clone(),
+                marks: plot.marks,
+                annotations: whole.annotations.clone(),
+                legend_position: if index == 0 { whole.legend_position } else { LegendPosition::None },
+                legend: whole.legend.clone(),
+            }));
+        }
+        Ok(CompiledFacets { title: whole.title, columns, panels })
+    }
+
+    /// Resolve fields, scales and colors
+    pub fn compile(&self) -> Result<CompiledPlot, String> {
+        let layers = self.layers()?;
+
+        // The x channel is shared: its type comes from the first layer that
+        // has one, and categories from every layer in order
+        let x_channel = layers
+            .iter()
+            .find_map(|(_, _, enc)| enc.x.as_ref())
+            .ok_or("spec has no x channel")?;
+        let x_type = layers
+            .iter()
+            .find_map(|(_, data, enc)| enc.x.as_ref().and_then(|x| channel_type(x, data)))
+            .unwrap_or(FieldType::Quantitative);
+        let categorical = matches!(x_type, FieldType::Nominal | FieldType::Ordinal);
+        let mut categories: Vec<String> = Vec::new();
+        let mut palette = Palette::new(self.encoding.color.as_ref());
+
+        let mut marks = Vec::new();
+        let mut y_title = String::new();
+        let mut y2_title = String::new();
+        let mut y_channel: Option<ChannelSpec> = None;
+        let mut y2_channel: Option<ChannelSpec> = None;
+        for (index, (mark, data, enc)) in layers.iter().enumerate() {
+            let y = enc.y.as_ref();
+            let right_axis = y.and_then(|y| y.axis.as_ref()).and_then(|a| a.orient) == Some(AxisOrient::Right);
+            if let Some(y) = y {
+                let title = y.axis.as_ref().and_then(|a| a.title.clone()).or_else(|| y.title.clone()).or_else(|| y.field.clone());
+                let (slot, channel) = if right_axis { (&mut y2_title, &mut y2_channel) } else { (&mut y_title, &mut y_channel) };
+                if slot.is_empty() {
+                    *slot = title.unwrap_or_default();
+                }
+                if channel.is_none() {
+                    *channel = Some(y.clone());
+                }
+            }
+            let layer = compile_layer(index, mark, data, enc, x_type, &mut categories, &mut palette, right_axis)?;
+            marks.extend(layer);
+        }
+
+        // Bars and areas grow from zero unless they have explicit bottoms
+        let from_zero = |right: bool| {
+            marks.iter().any(|m| {
+                m.right_axis == right && matches!(m.kind, MarkType::Bar | MarkType::Area) && m.y2.iter().all(|&v| v == 0.0)
+            })
+        };
+        let mut x = PlotAxis::default();
+        configure_axis(&mut x, x_channel)?;
+        if categorical {
+            x.show_labels = false;
+            x.show_grid = false;
+        } else {
+            if x_type == FieldType::Temporal && x.scale == ScaleType::Linear {
+                x.scale = ScaleType::Time;
+            }
+            let values = marks.iter().filter(|m| m.kind != MarkType::Rule || m.vertical).flat_map(|m| {
+                m.x.iter().flat_map(move |&x| [x + m.bar_offset, x + m.bar_offset + m.bar_width])
+            });
+            x.range = fit_range(values, x_channel.scale.as_ref(), false, x.scale);
+        }
+
+        let mut y = PlotAxis::default();
+        if let Some(channel) = &y_channel {
+            configure_axis(&mut y, channel)?;
+        }
+        y.range = fit_range(y_values(&marks, false), y_channel.as_ref().and_then(|c| c.scale.as_ref()), from_zero(false), y.scale);
+
+        let y2 = match &y2_channel {
+            Some(channel) => {
+                let mut axis = PlotAxis::default();
+                configure_axis(&mut axis, channel)?;
+                axis.show_grid = false;
+                axis.range = fit_range(y_values(&marks, true), channel.scale.as_ref(), from_zero(true), axis.scale);
+                Some(axis)
+            }
+            None => None,
+        };
+
+        let x_title = x_channel.axis.as_ref().and_then(|a| a.title.clone())
+            .or_else(|| x_channel.title.clone())
+            .or_else(|| x_channel.field.clone())
+            .unwrap_or_default();
+
+        // Annotations may name categories no layer has
+        let annotations = compile_annotations(&self.annotations, x_type, &mut categories)?;
+        if categorical {
+            x.range = (-0.5, categories.len().max(1) as f64 - 0.5);
+        }
+
+        let legend = palette.entries();
+        let legend_position = match self.legend.as_ref().and_then(|l| l.position) {
+            Some(LegendOrient::TopRight) => LegendPosition::TopRight,
+            Some(LegendOrient::TopLeft) => LegendPosition::TopLeft,
+            Some(LegendOrient::BottomRight) => LegendPosition::BottomRight,
+            Some(LegendOrient::BottomLeft) => LegendPosition::BottomLeft,
+            Some(LegendOrient::None) => LegendPosition::None,
+            // Only worth a legend when there is something to tell apart
+            None if legend.len() > 1 => LegendPosition::TopRight,
+            None => LegendPosition::None,
+        };
+
+        Ok(CompiledPlot {
+            title: self.title.clone().unwrap_or_default(),
+            x,
+            y,
+            y2,
+            x_title,
+            y_title,
+            y2_title,
+            x_categories: categories,
+            marks,
+            annotations,
+            legend_position,
+            legend,
+        })
+    }
+}
+
+/// Colors of the groups in the order they appear, shared by all layers so
+/// the same group keeps its color everywhere
+struct Palette {
+    range: Vec<Vec4>,
+    groups: Vec<(String, Vec4)>,
+}
+
+impl Palette {
+    fn new(color: Option<&ChannelSpec>) -> Self {
+        let range = color
+            .and_then(|c| c.scale.as_ref())
+            .map(|s| s.range.iter().filter_map(|hex| parse_hex_color(hex).ok()).collect())
+            .unwrap_or_default();
+        Self { range, groups: Vec::new() }
+    }
+
+    /// Color of `group`, or `fixed` for a layer with a color of its own
+    fn color(&mut self, group: &str, fixed: Option<Vec4>) -> Vec4 {
+        if let Some((_, color)) = self.groups.iter().find(|(name, _)| name == group) {
+            return fixed.unwrap_or(*color);
+        }
+        let index = self.groups.len();
+        let color = fixed.unwrap_or_else(|| self.range.get(index).copied().unwrap_or_else(|| get_color(index)));
+        self.groups.push((group.to_string(), color));
+        color
+    }
+
+    fn entries(self) -> Vec<(String, Vec4)> {
+        self.groups.into_iter().filter(|(name, _)| !name.is_empty()).collect()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compile_layer(
+    index: usize,
+    mark: &MarkDef,
+    data: &DataSpec,
+    enc: &Encoding,
+    x_type: FieldType,
+    categories: &mut Vec<String>,
+    palette: &mut Palette,
+    right_axis: bool,
+) -> Result<Vec<CompiledMark>, String> {
+    let n = data.len();
+    let read = |channel: &ChannelSpec, kind: FieldType, categories: &mut Vec<String>| -> Result<Vec<f64>, String> {
+        match (&channel.field, &channel.value) {
+            (Some(field), _) => {
+                if !data.has_field(field) {
+                    return Err(format!("layer {} has no field \"{}\"", index, field));
+                }
+                Ok(data.column(field).iter().map(|v| to_number(v, kind, categories)).collect())
+            }
+            (None, Some(value)) => Ok(vec![to_number(value, kind, categories); n]),
+            (None, None) => Err(format!("layer {}: a channel needs a field or a value", index)),
+        }
+    };
+    let quantitative = |channel: &ChannelSpec| -> Result<FieldType, String> {
+        match channel_type(channel, data) {
+            Some(FieldType::Nominal) | Some(FieldType::Ordinal) => {
+                Err(format!("layer {}: y channels must be quantitative or temporal", index))
+            }
+            kind => Ok(kind.unwrap_or(FieldType::Quantitative)),
+        }
+    };
+
+    let xs = match &enc.x {
+        Some(channel) => Some(read(channel, x_type, categories)?),
+        None => None,
+    };
+    let ys = match &enc.y {
+        Some(channel) => Some(read(channel, quantitative(channel)?, categories)?),
+        None => None,
+    };
+    let (xs, ys, vertical) = match (mark.kind, xs, ys) {
+        (_, Some(xs), Some(ys)) => (xs, ys, false),
+        (MarkType::Rule, Some(xs), None) => (xs, vec![f64::NAN; n], true),
+        (MarkType::Rule, None, Some(ys)) => (vec![f64::NAN; n], ys, false),
+        _ => return Err(format!("layer {}: {:?} marks need x and y channels", index, mark.kind)),
+    };
+    let y2s = match &enc.y2 {
+        Some(channel) => read(channel, quantitative(channel)?, categories)?,
+        None => vec![0.0; n],
+    };
+    let sizes = match &enc.size {
+        Some(channel) if channel.field.is_some() => {
+            let raw = read(channel, FieldType::Quantitative, categories)?;
+            let max = raw.iter().copied().filter(|v| v.is_finite() && *v > 0.0).fold(0.0f64, f64::max);
+            // Area proportional to the value
+            raw.iter().map(|&v| if max > 0.0 && v > 0.0 { SIZE_RANGE.0 + (SIZE_RANGE.1 - SIZE_RANGE.0) * (v / max).sqrt() } else { SIZE_RANGE.0 }).collect()
+        }
+        Some(channel) => read(channel, FieldType::Quantitative, categories)?,
+        None => vec![mark.size.unwrap_or(if mark.kind == MarkType::Text { 11.0 } else { 4.0 }); n],
+    };
+    let texts: Vec<String> = match &enc.text {
+        Some(ChannelSpec { field: Some(field), .. }) => data.column(field).iter().map(display_value).collect(),
+        Some(ChannelSpec { value: Some(value), .. }) => vec![display_value(value); n],
+        _ => vec![String::new(); n],
+    };
+
+    // Split into color groups
+    let fixed = mark.color.as_deref().map(parse_hex_color).transpose()?;
+    let opacity = mark.opacity.unwrap_or(if mark.kind == MarkType::Area { 0.6 } else { 1.0 });
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    match &enc.color {
+        Some(ChannelSpec { field: Some(field), .. }) => {
+            if !data.has_field(field) {
+                return Err(format!("layer {} has no field \"{}\"", index, field));
+            }
+            for (i, value) in data.column(field).iter().enumerate() {
+                let name = display_value(value);
+                match groups.iter_mut().find(|(g, _)| *g == name) {
+                    Some((_, rows)) => rows.push(i),
+                    None => groups.push((name, vec![i])),
+                }
+            }
+        }
+        _ => {
+            let name = enc.y.as_ref().and_then(|y| y.title.clone().or_else(|| y.field.clone())).unwrap_or_default();
+            groups.push((name, (0..n).collect()));
+        }
+    }
+    let constant_color = match &enc.color {
+        Some(ChannelSpec { field: None, value: Some(Value::String(hex)), .. }) => Some(parse_hex_color(hex)?),
+        _ => None,
+    };
+
+    // Bars sit side by side within a band of the closest x spacing, or stack
+    let band = if matches!(x_type, FieldType::Nominal | FieldType::Ordinal) { 1.0 } else { min_spacing(&xs) } * BAR_FILL;
+    let dodge = mark.kind == MarkType::Bar && !mark.stack && groups.len() > 1;
+    let bar_width = if dodge { band / groups.len() as f64 } else { band };
+    let mut stack_tops: HashMap<u64, f64> = HashMap::new();
+
+    let mut marks = Vec::new();
+    for (g, (name, mut rows)) in groups.into_iter().enumerate() {
+        if mark.kind == MarkType::Line || mark.kind == MarkType::Area {
+            rows.sort_by(|&a, &b| xs[a].total_cmp(&xs[b]));
+        }
+        let color = palette.color(&name, constant_color.or(fixed));
+        let color = vec4(color.x, color.y, color.z, color.w * opacity as f32);
+
+        let mut y = Vec::with_capacity(rows.len());
+        let mut y2 = Vec::with_capacity(rows.len());
+        for &i in &rows {
+            if mark.stack && matches!(mark.kind, MarkType::Bar | MarkType::Area) {
+                let top = stack_tops.entry(xs[i].to_bits()).or_insert(0.0);
+                y2.push(*top);
+                *top += ys[i];
+                y.push(*top);
+            } else {
+                y.push(ys[i]);
+                y2.push(y2s[i]);
+            }
+        }
+
+        marks.push(CompiledMark {
+            kind: mark.kind,
+            label: name,
+            color,
+            right_axis,
+            x: rows.iter().map(|&i| xs[i]).collect(),
+            y,
+            y2,
+            size: rows.iter().map(|&i| sizes[i]).collect(),
+            text: rows.iter().map(|&i| texts[i].clone()).collect(),
+            line_width: mark.stroke_width.unwrap_or(if mark.kind == MarkType::Rule { 1.0 } else { 2.0 }),
+            line_style: mark.stroke_dash.unwrap_or_default().line_style(),
+            marker: mark.shape.unwrap_or_default().marker_style(),
+            show_points: mark.point,
+            bar_width,
+            bar_offset: if dodge { -band / 2.0 + g as f64 * bar_width } else { -band / 2.0 },
+            vertical,
+        });
+    }
+    Ok(marks)
+}
+
+fn compile_annotations(specs: &[AnnotationSpec], x_type: FieldType, categories: &mut Vec<String>) -> Result<SpecAnnotations, String> {
+    let line_color = vec4(0.4, 0.4, 0.4, 1.0);
+    let span_color = vec4(0.5, 0.5, 0.5, 0.15);
+    let color = |hex: &Option<String>, default: Vec4| -> Result<Vec4, String> {
+        hex.as_deref().map(parse_hex_color).transpose().map(|c| c.unwrap_or(default))
+    };
+    let mut out = SpecAnnotations::default();
+    for spec in specs {
+        match spec {
+            AnnotationSpec::Hline { y, color: c, stroke_width, stroke_dash } => out.hlines.push(HLine {
+                y: *y,
+                color: color(c, line_color)?,
+                line_width: stroke_width.unwrap_or(1.0),
+                line_style: stroke_dash.unwrap_or(DashSpec::Dashed).line_style(),
+            }),
+            AnnotationSpec::Vline { x, color: c, stroke_width, stroke_dash } => out.vlines.push(VLine {
+                x: to_number(x, x_type, categories),
+                color: color(c, line_color)?,
+                line_width: stroke_width.unwrap_or(1.0),
+                line_style: stroke_dash.unwrap_or(DashSpec::Dashed).line_style(),
+            }),
+            AnnotationSpec::Hspan { y1, y2, color: c } => {
+                out.hspans.push(HSpan { y1: *y1, y2: *y2, color: color(c, span_color)? })
+            }
+            AnnotationSpec::Vspan { x1, x2, color: c } => out.vspans.push(VSpan {
+                x1: to_number(x1, x_type, categories),
+                x2: to_number(x2, x_type, categories),
+                color: color(c, span_color)?,
+            }),
+            AnnotationSpec::Text { x, y, text, color: c, font_size } => out.texts.push(TextAnnotation {
+                text: text.clone(),
+                x: to_number(x, x_type, categories),
+                y: *y,
+                color: color(c, vec4(0.2, 0.2, 0.2, 1.0))?,
+                font_size: font_size.unwrap_or(11.0),
+                is_math: false,
+            }),
+            AnnotationSpec::Arrow { x, y, text_x, text_y, text, color: c } => {
+                let mut arrow = ArrowAnnotation::new(to_number(text_x, x_type, categories), *text_y, to_number(x, x_type, categories), *y)
+                    .with_color(color(c, vec4(0.2, 0.2, 0.2, 1.0))?);
+                arrow.text = text.clone();
+                out.arrows.push(arrow);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Scale type, tick format and grid from a channel's `scale` and `axis`
+fn configure_axis(axis: &mut PlotAxis, channel: &ChannelSpec) -> Result<(), String> {
+    if let Some(kind) = channel.scale.as_ref().and_then(|s| s.kind) {
+        axis.scale = match kind {
+            ScaleKind::Linear => ScaleType::Linear,
+            ScaleKind::Log => ScaleType::Log,
+            ScaleKind::Symlog => ScaleType::SymLog,
+            ScaleKind::Time => ScaleType::Time,
+        };
+    }
+    if let Some(spec) = &channel.axis {
+        if let Some(format) = &spec.format {
+            axis.formatter = parse_tick_format(format)?;
+        }
+        if let Some(grid) = spec.grid {
+            axis.show_grid = grid;
+        }
+        if let Some(count) = spec.tick_count {
+            axis.tick_count = count.max(1);
+        }
+    }
+    Ok(())
+}
+
+fn parse_tick_format(format: &str) -> Result<TickFormatter, String> {
+    match format {
+        "auto" => Ok(TickFormatter::Auto),
+        "si" => Ok(TickFormatter::Si),
+        "percent" => Ok(TickFormatter::Percent),
+        _ => format
+            .strip_prefix('.')
+            .and_then(|f| f.strip_suffix('f'))
+            .and_then(|digits| digits.parse().ok())
+            .map(TickFormatter::Fixed)
+            .ok_or_else(|| format!("unknown axis format \"{}\"", format)),
+    }
+}
+
+/// Y values a layer reaches on one side, bar and band bottoms included
+fn y_values(marks: &[CompiledMark], right: bool) -> Vec<f64> {
+    marks
+        .iter()
+        .filter(|m| m.right_axis == right && !(m.kind == MarkType::Rule && m.vertical))
+        .flat_map(|m| {
+            let bottoms = matches!(m.kind, MarkType::Bar | MarkType::Area);
+            m.y.iter().copied().chain(m.y2.iter().copied().filter(move |_| bottoms))
+        })
+        .collect()
+}
+
+/// Axis range for `values`: the scale's domain when given, otherwise the
+/// data padded by 5% (multiplicatively on log axes), from zero if asked
+fn fit_range(values: impl IntoIterator<Item = f64>, scale: Option<&ScaleSpec>, zero_default: bool, scale_type: ScaleType) -> (f64, f64) {
+    if let Some(domain) = scale.and_then(|s| s.domain) {
+        return (domain[0], domain[1]);
+    }
+    let log = scale_type == ScaleType::Log;
+    let (mut lo, mut hi) = values
+        .into_iter()
+        .filter(|v| v.is_finite() && (!log || *v > 0.0))
+        .fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    if lo > hi {
+        return (0.0, 1.0);
+    }
+    if log {
+        return (lo / 1.5, hi * 1.5);
+    }
+    let zero = scale.and_then(|s| s.zero).unwrap_or(zero_default);
+    if zero {
+        lo = lo.min(0.0);
+        hi = hi.max(0.0);
+    }
+    if hi == lo {
+        return (lo - 0.5, hi + 0.5);
+    }
+    let pad = (hi - lo) * 0.05;
+    let lo = if zero && lo == 0.0 { 0.0 } else { lo - pad };
+    let hi = if zero && hi == 0.0 { 0.0 } else { hi + pad };
+    (lo, hi)
+}
+
+/// Smallest gap between distinct finite values, 1.0 if there is none
+fn min_spacing(values: &[f64]) -> f64 {
+    let sorted = sorted_finite(values);
+    let gap = sorted.windows(2).map(|w| w[1] - w[0]).filter(|d| *d > 0.0).fold(f64::INFINITY, f64::min);
+    if gap.is_finite() { gap } else { 1.0 }
+}
+
+/// Declared type of a channel, or one inferred from its values: numbers are
+/// quantitative, date strings temporal and anything else nominal
+fn channel_type(channel: &ChannelSpec, data: &DataSpec) -> Option<FieldType> {
+    if channel.kind.is_some() {
+        return channel.kind;
+    }
+    let field = channel.field.as_ref()?;
+    let column = data.column(field);
+    let mut present = column.iter().filter(|v| !v.is_null()).peekable();
+    present.peek()?;
+    let mut all_numbers = true;
+    let mut all_dates = true;
+    for value in present {
+        all_numbers &= value.is_number();
+        all_dates &= value.as_str().is_some_and(|s| parse_datetime(s).is_some());
+    }
+    Some(if all_numbers {
+        FieldType::Quantitative
+    } else if all_dates {
+        FieldType::Temporal
+    } else {
+        FieldType::Nominal
+    })
+}
+
+/// Position of a value on an axis of type `kind`. Categories get the next
+/// free index the first time they are seen. Unreadable values are NaN.
+fn to_number(value: &Value, kind: FieldType, categories: &mut Vec<String>) -> f64 {
+    match kind {
+        FieldType::Nominal | FieldType::Ordinal => {
+            if value.is_null() {
+                return f64::NAN;
+            }
+            let name = display_value(value);
+            match categories.iter().position(|c| *c == name) {
+                Some(index) => index as f64,
+                None => {
+                    categories.push(name);
+                    (categories.len() - 1) as f64
+                }
+            }
+        }
+        FieldType::Temporal => match value {
+            Value::Number(n) => n.as_f64().unwrap_or(f64::NAN),
+            Value::String(s) => parse_datetime(s).unwrap_or(f64::NAN),
+            _ => f64::NAN,
+        },
+        FieldType::Quantitative => match value {
+            Value::Number(n) => n.as_f64().unwrap_or(f64::NAN),
+            Value::String(s) => s.trim().parse().unwrap_or(f64::NAN),
+            Value::Bool(b) => *b as u8 as f64,
+            _ => f64::NAN,
+        },
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Unix seconds of an ISO 8601 date or date-time in UTC: "2024-03-05",
+/// "2024-03-05T14:30", "2024-03-05 14:30:15.5Z"
+pub fn parse_datetime(text: &str) -> Option<f64> {
+    let text = text.trim().trim_end_matches('Z');
+    let (date, time) = match text.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (text, None),
+    };
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next().map_or(Some(1), |m| m.parse().ok())?;
+    let day: u32 = parts.next().map_or(Some(1), |d| d.parse().ok())?;
+    if date.len() < 4 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut seconds = 0.0;
+    if let Some(time) = time {
+        let mut fields = time.split(':');
+        let hours: f64 = fields.next()?.parse().ok()?;
+        let minutes: f64 = fields.next().map_or(Some(0.0), |m| m.parse().ok())?;
+        let secs: f64 = fields.next().map_or(Some(0.0), |s| s.parse().ok())?;
+        seconds = hours * 3600.0 + minutes * 60.0 + secs;
+    }
+    Some(days_from_civil(year, month, day) as f64 * 86400.0 + seconds)
+}
+
+/// Color from "#rgb", "#rrggbb" or "#rrggbbaa"
+pub fn parse_hex_color(text: &str) -> Result<Vec4, String> {
+    let hex = text.trim().trim_start_matches('#');
+    let digits: Vec<u8> = hex
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()
+        .ok_or_else(|| format!("invalid color \"{}\"", text))?;
+    let channel = |hi: u8, lo: u8| (hi * 16 + lo) as f32 / 255.0;
+    match digits[..] {
+        [r, g, b] => Ok(vec4(channel(r, r), channel(g, g), channel(b, b), 1.0)),
+        [r1, r2, g1, g2, b1, b2] => Ok(vec4(channel(r1, r2), channel(g1, g2), channel(b1, b2), 1.0)),
+        [r1, r2, g1, g2, b1, b2, a1, a2] => Ok(vec4(channel(r1, r2), channel(g1, g2), channel(b1, b2), channel(a1, a2))),
+        _ => Err(format!("invalid color \"{}\"", text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(json: &str) -> PlotSpec {
+        PlotSpec::from_json(json).unwrap()
+    }
+
+    fn assert_range(actual: (f64, f64), expected: (f64, f64)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-9 && (actual.1 - expected.1).abs() < 1e-9,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    const SALES: &str = r#"{"values": [
+        {"month": "Jan", "sales": 12, "store": "a"},
+        {"month": "Feb", "sales": 18, "store": "a"},
+        {"month": "Jan", "sales": 5, "store": "b"}
+    ]}"#;
+
+    #[test]
+    fn test_parse_marks() {
+        let line = spec(r#"{"mark": "line"}"#);
+        assert!(matches!(line.mark, Some(MarkSpec::Type(MarkType::Line))));
+        let bar = spec(r##"{"mark": {"type": "bar", "stack": true, "color": "#4c78a8"}}"##);
+        let def = bar.mark.unwrap().def();
+        assert_eq!(def.kind, MarkType::Bar);
+        assert!(def.stack);
+        assert_eq!(def.color.as_deref(), Some("#4c78a8"));
+
+        // A misspelt type is named in the error, in either form
+        for json in [r#"{"mark": "bars"}"#, r#"{"mark": {"type": "bars"}}"#] {
+            let err = PlotSpec::from_json(json).unwrap_err();
+            assert!(err.starts_with("invalid plot spec") && err.contains("bars"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_channel_types_inferred_from_data() {
+        let data: DataSpec = serde_json::from_str(
+            r#"{"columns": {"n": [1, 2.5, null], "d": ["2024-01-01", "2024-02-01T12:00"], "s": ["a", 3], "e": [null]}}"#,
+        )
+        .unwrap();
+        assert_eq!(data.len(), 3);
+        assert_eq!(data.column("d")[2], Value::Null);
+        let field = |name: &str| ChannelSpec { field: Some(name.to_string()), ..Default::default() };
+        assert_eq!(channel_type(&field("n"), &data), Some(FieldType::Quantitative));
+        assert_eq!(channel_type(&field("d"), &data), Some(FieldType::Temporal));
+        assert_eq!(channel_type(&field("s"), &data), Some(FieldType::Nominal));
+        assert_eq!(channel_type(&field("e"), &data), None);
+        let declared = ChannelSpec { kind: Some(FieldType::Ordinal), ..field("n") };
+        assert_eq!(channel_type(&declared, &data), Some(FieldType::Ordinal));
+
+        // Dates put the x axis on a time scale
+        let plot = spec(
+            r#"{"data": {"columns": {"day": ["2024-01-01", "2024-01-03"], "v": [1, 2]}},
+                "mark": "line", "encoding": {"x": {"field": "day"}, "y": {"field": "v"}}}"#,
+        )
+        .compile()
+        .unwrap();
+        assert_eq!(plot.x.scale, ScaleType::Time);
+        let start = parse_datetime("2024-01-01").unwrap();
+        assert_eq!(plot.marks[0].x, vec![start, start + 2.0 * 86400.0]);
+    }
+
+    #[test]
+    fn test_compile_dodged_bars() {
+        let plot = spec(&format!(
+            r#"{{"data": {}, "mark": "bar", "encoding": {{
+                "x": {{"field": "month"}}, "y": {{"field": "sales"}}, "color": {{"field": "store"}}}}}}"#,
+            SALES
+        ))
+        .compile()
+        .unwrap();
+        assert_eq!(plot.x_categories, vec!["Jan", "Feb"]);
+        assert_range(plot.x.range, (-0.5, 1.5));
+        assert!(!plot.x.show_labels);
+        // Bars start at zero; 5% padding above the tallest
+        assert_range(plot.y.range, (0.0, 18.9));
+        assert_eq!((plot.x_title.as_str(), plot.y_title.as_str()), ("month", "sales"));
+
+        let labels: Vec<&str> = plot.legend.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(labels, vec!["a", "b"]);
+        assert_eq!(plot.legend_position, LegendPosition::TopRight);
+        assert_eq!(plot.legend[0].1, plot.marks[0].color);
+
+        let (a, b) = (&plot.marks[0], &plot.marks[1]);
+        assert_eq!((a.x.clone(), a.y.clone()), (vec![0.0, 1.0], vec![12.0, 18.0]));
+        assert_eq!((b.x.clone(), b.y.clone()), (vec![0.0], vec![5.0]));
+        // Two groups share the 0.8 band side by side
+        assert_range((a.bar_width, a.bar_offset), (0.4, -0.4));
+        assert_range((b.bar_width, b.bar_offset), (0.4, 0.0));
+    }
+
+    #[test]
+    fn test_compile_stacked_bars() {
+        let plot = spec(&format!(
+            r#"{{"data": {}, "mark": {{"type": "bar", "stack": true}}, "encoding": {{
+                "x": {{"field": "month"}}, "y": {{"field": "sales"}}, "color": {{"field": "store"}}}}}}"#,
+            SALES
+        ))
+        .compile()
+        .unwrap();
+        let (a, b) = (&plot.marks[0], &plot.marks[1]);
+        assert_eq!((a.y2.clone(), a.y.clone()), (vec![0.0, 0.0], vec![12.0, 18.0]));
+        // b sits on top of a in January
+        assert_eq!((b.y2.clone(), b.y.clone()), (vec![12.0], vec![17.0]));
+        assert_range((a.bar_width, a.bar_offset), (0.8, -0.4));
+        assert_range((b.bar_width, b.bar_offset), (0.8, -0.4));
+        assert_range(plot.y.range, (0.0, 18.9));
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let compile = |encoding: &str| {
+            spec(&format!(r#"{{"data": {}, "mark": "point", "encoding": {}}}"#, SALES, encoding)).compile().unwrap_err()
+        };
+        assert_eq!(compile(r#"{"x": {"field": "month"}, "y": {"field": "profit"}}"#), "layer 0 has no field \"profit\"");
+        assert_eq!(compile(r#"{"y": {"field": "sales"}}"#), "spec has no x channel");
+        assert_eq!(
+            compile(r#"{"x": {"field": "sales"}, "y": {"field": "month"}}"#),
+            "layer 0: y channels must be quantitative or temporal"
+        );
+        assert_eq!(
+            compile(r#"{"x": {"field": "month"}, "y": {"field": "sales", "axis": {"format": ".xf"}}}"#),
+            "unknown axis format \".xf\""
+        );
+        assert_eq!(spec(r#"{"mark": "point"}"#).compile().unwrap_err(), "layer 0 has no data");
+    }
+
+    #[test]
+    fn test_parse_helpers() {
+        assert_eq!(parse_tick_format("si"), Ok(TickFormatter::Si));
+        assert_eq!(parse_tick_format(".2f"), Ok(TickFormatter::Fixed(2)));
+        assert!(parse_tick_format("2f").is_err());
+
+        assert_eq!(parse_datetime("1970-01-02"), Some(86400.0));
+        assert_eq!(parse_datetime("1970-01-01T01:30Z"), Some(5400.0));
+        assert_eq!(parse_datetime("1970-01-01 00:00:01.5"), Some(1.5));
+        // 2024 is a leap year
+        assert_eq!(
+            parse_datetime("2024-03-01").unwrap() - parse_datetime("2024-02-28").unwrap(),
+            2.0 * 86400.0
+        );
+        assert_eq!(parse_datetime("2024-13-01"), None);
+        assert_eq!(parse_datetime("March"), None);
+
+        assert_eq!(parse_hex_color("#f00"), Ok(vec4(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(parse_hex_color("00ff0080"), Ok(vec4(0.0, 1.0, 0.0, 128.0 / 255.0)));
+        assert!(parse_hex_color("#12345").is_err());
+        assert!(parse_hex_color("#ggg").is_err());
+    }
+
+    const REGIONS: &str = r#"{"values": [
+        {"region": "N", "x": 1, "y": 2},
+        {"region": "S", "x": 2, "y": 4},
+        {"region": "N", "x": 3, "y": 6},
+        {"region": "E", "x": 4, "y": 8},
+        {"region": "S", "x": 5, "y": 10}
+    ]}"#;
+
+    #[test]
+    fn test_facet_panels() {
+        let faceted = spec(&format!(
+            r#"{{"title": "By region", "data": {}, "facet": {{"field": "region"}},
+                "legend": {{"position": "topLeft"}},
+                "layer": [
+                    {{"mark": "point", "encoding": {{"x": {{"field": "x"}}, "y": {{"field": "y"}}}}}},
+                    {{"data": {{"values": [{{"target": 5}}]}}, "mark": "rule", "encoding": {{"y": {{"field": "target"}}}}}}
+                ]}}"#,
+            REGIONS
+        ));
+        assert_eq!(faceted.facet_groups(), vec!["N", "S", "E"]);
+        let facets = faceted.compile_facets().unwrap();
+        assert_eq!(facets.title, "By region");
+        // Three panels fill a 2-column grid
+        assert_eq!(facets.columns, 2);
+        let names: Vec<&str> = facets.panels.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["N", "S", "E"]);
+
+        let whole = PlotSpec { facet: None, ..faceted.clone() }.compile().unwrap();
+        for (name, panel) in &facets.panels {
+            assert_eq!(&panel.title, name);
+            assert_eq!(panel.x.range, whole.x.range);
+            assert_eq!(panel.y.range, whole.y.range);
+            // The rule's data has no region, so every panel shows it
+            assert_eq!(panel.marks[1].y, vec![5.0]);
+        }
+        let points: Vec<Vec<f64>> = facets.panels.iter().map(|(_, p)| p.marks[0].x.clone()).collect();
+        assert_eq!(points, vec![vec![1.0, 3.0], vec![2.0, 5.0], vec![4.0]]);
+
+        // x titles under the bottom row, y titles beside the first column,
+        // the legend in the first panel only
+        let titles: Vec<(&str, &str)> =
+            facets.panels.iter().map(|(_, p)| (p.x_title.as_str(), p.y_title.as_str())).collect();
+        assert_eq!(titles, vec![("", "y"), ("x", ""), ("x", "y")]);
+        let legends: Vec<LegendPosition> = facets.panels.iter().map(|(_, p)| p.legend_position).collect();
+        assert_eq!(legends, vec![LegendPosition::TopLeft, LegendPosition::None, LegendPosition::None]);
+
+        let wide = PlotSpec { facet: Some(FacetSpec { field: "region".into(), columns: Some(5) }), ..faceted.clone() };
+        assert_eq!(wide.compile_facets().unwrap().columns, 3);
+        let missing = PlotSpec { facet: Some(FacetSpec { field: "zone".into(), columns: None }), ..faceted };
+        assert!(missing.facet_groups().is_empty());
+        assert_eq!(missing.compile_facets().unwrap_err(), "no data has the facet field \"zone\"");
+    }
+
+    #[test]
+    fn test_facet_panels_share_categories_and_bars() {
+        let facets = spec(&format!(
+            r#"{{"data": {}, "mark": "bar", "facet": {{"field": "month"}}, "encoding": {{
+                "x": {{"field": "month"}}, "y": {{"field": "sales"}}, "color": {{"field": "store"}}}}}}"#,
+            SALES
+        ))
+        .compile_facets()
+        .unwrap();
+        let (jan, feb) = (&facets.panels[0].1, &facets.panels[1].1);
+        assert_eq!(feb.x_categories, vec!["Jan", "Feb"]);
+        assert_eq!(jan.marks.len(), 2);
+        // February alone would be category 0 with one undodged bar; it keeps
+        // the slot, width and color it has in the whole chart
+        assert_eq!(feb.marks.len(), 1);
+        assert_eq!(feb.marks[0].x, vec![1.0]);
+        assert_range((feb.marks[0].bar_width, feb.marks[0].bar_offset), (0.4, -0.4));
+        assert_eq!(feb.marks[0].color, jan.marks[0].color);
+    }
+}
@@ -0,0 +1,372 @@
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use crate::export::*;
+use super::*;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::text::PlotLabel;
+
+    pub PlotSpecView = {{PlotSpecView}} {
+        width: Fill,
+        height: Fill,
+        label: <PlotLabel> {}
+    }
+}
+
+/// Draws any [`PlotSpec`]: layered marks over shared axes, an optional
+/// right-hand y axis, annotations and a legend. A spec with a `facet` is
+/// drawn as a grid of panels, one per group, on shared scales.
+///
+/// ```ignore
+/// plot.set_spec_json(r#"{
+///     "data": {"values": [{"day": 1, "temp": 12}, {"day": 2, "temp": 15}]},
+///     "mark": "line",
+///     "encoding": {"x": {"field": "day"}, "y": {"field": "temp"}}
+/// }"#)?;
+/// ```
+#[derive(Live, LiveHook, Widget)]
+pub struct PlotSpecView {
+    #[deref] #[live] view: View,
+    #[live] draw_line: DrawPlotLine,
+    #[live] draw_point: DrawPlotPoint,
+    #[live] draw_bar: DrawPlotBar,
+    #[live] draw_fill: DrawPlotFill,
+    #[live] label: PlotLabel,
+    #[rust] plot: Option<CompiledPlot>,
+    #[rust] facets: Option<CompiledFacets>,
+    #[rust(Axes::with_margins(60.0, 40.0, 60.0, 50.0))] axes: Axes,
+
+    #[rust]
+    capture: SceneCapture,
+}
+
+impl PlotSpecView {
+    /// Compile and show `spec`; on error the previous plot stays
+    pub fn set_spec(&mut self, spec: &PlotSpec) -> Result<(), String> {
+        if spec.facet.is_some() {
+            self.facets = Some(spec.compile_facets()?);
+            self.plot = None;
+            return Ok(());
+        }
+        let plot = spec.compile()?;
+        self.axes.x = plot.x.clone();
+        self.axes.y = plot.y.clone();
+        self.plot = Some(plot);
+        self.facets = None;
+        Ok(())
+    }
+
+    pub fn set_spec_json(&mut self, json: &str) -> Result<(), String> {
+        self.set_spec(&PlotSpec::from_json(json)?)
+    }
+
+    pub fn clear(&mut self) {
+        self.plot = None;
+        self.facets = None;
+    }
+
+    pub fn redraw(&mut self, cx: &mut Cx) {
+        self.view.redraw(cx);
+    }
+
+    fn draw_plot(&mut self, cx: &mut Cx2d, rect: Rect, plot: &CompiledPlot) {
+        let area = self.axes.plot_area;
+        // Marks on the right axis map through a copy of the axes with its y
+        let right_axes = Axes { y: plot.y2.clone().unwrap_or_default(), ..self.axes.clone() };
+
+        // Shaded spans sit behind everything
+        for span in &plot.annotations.hspans {
+            self.draw_fill.color = span.color;
+            let (y1, y2) = (self.axes.y_to_pixel(span.y1), self.axes.y_to_pixel(span.y2));
+            self.draw_fill.draw_fill_strip(cx, area.left, area.width(), y1.min(y2), y1.max(y2));
+        }
+        for span in &plot.annotations.vspans {
+            self.draw_fill.color = span.color;
+            let (x1, x2) = (self.axes.x_to_pixel(span.x1), self.axes.x_to_pixel(span.x2));
+            self.draw_fill.draw_fill_strip(cx, x1.min(x2), (x2 - x1).abs(), area.top, area.bottom);
+        }
+
+        self.axes.draw_grid(cx, &mut self.draw_line);
+        self.axes.draw_axes(cx, &mut self.draw_line);
+
+        for mark in &plot.marks {
+            let axes = if mark.right_axis { &right_axes } else { &self.axes };
+            self.draw_mark(cx, axes, mark);
+        }
+
+        for line in &plot.annotations.hlines {
+            self.draw_line.color = line.color;
+            let y = self.axes.y_to_pixel(line.y);
+            self.draw_line.draw_line_styled(cx, dvec2(area.left, y), dvec2(area.right, y), line.line_width, line.line_style, 0.0);
+        }
+        for line in &plot.annotations.vlines {
+            self.draw_line.color = line.color;
+            let x = self.axes.x_to_pixel(line.x);
+            self.draw_line.draw_line_styled(cx, dvec2(x, area.top), dvec2(x, area.bottom), line.line_width, line.line_style, 0.0);
+        }
+
+        // Right y axis, drawn like LinePlotDual's
+        let axis_color = vec4(0.3, 0.3, 0.3, 1.0);
+        if let Some(y2) = &plot.y2 {
+            self.draw_line.color = axis_color;
+            self.draw_line.draw_line(cx, dvec2(area.right, area.top), dvec2(area.right, area.bottom), 1.0);
+            for v in y2.ticks() {
+                let y = right_axes.y_to_pixel(v);
+                self.draw_line.draw_line(cx, dvec2(area.right, y), dvec2(area.right + 4.0, y), 1.0);
+            }
+        }
+
+        // Categories replace the numeric x ticks
+        if !plot.x_categories.is_empty() {
+            self.draw_line.color = axis_color;
+            for i in 0..plot.x_categories.len() {
+                let x = self.axes.x_to_pixel(i as f64);
+                self.draw_line.draw_line(cx, dvec2(x, area.bottom), dvec2(x, area.bottom + 4.0), 1.0);
+            }
+        }
+
+        self.label.set_font_size(10.0);
+        self.axes.draw_tick_labels(cx, &mut self.label);
+        for (i, category) in plot.x_categories.iter().enumerate() {
+            let x = self.axes.x_to_pixel(i as f64);
+            self.label.draw_at(cx, dvec2(x, area.bottom + 6.0), category, TextAnchor::TopCenter);
+        }
+        if let Some(y2) = &plot.y2 {
+            for v in y2.ticks() {
+                let y = right_axes.y_to_pixel(v);
+                self.label.draw_at(cx, dvec2(area.right + 6.0, y), &y2.format(v), TextAnchor::MiddleLeft);
+            }
+        }
+
+        // Axis titles and the plot title
+        self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+        self.label.set_font_size(11.0);
+        if !plot.x_title.is_empty() {
+            self.label.draw_at(cx, dvec2(area.left + area.width() * 0.5, rect.pos.y + rect.size.y - 6.0), &plot.x_title, TextAnchor::BottomCenter);
+        }
+        if !plot.y_title.is_empty() {
+            self.label.draw_at(cx, dvec2(rect.pos.x + 6.0, area.top - 8.0), &plot.y_title, TextAnchor::BottomLeft);
+        }
+        if !plot.y2_title.is_empty() {
+            self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x - 6.0, area.top - 8.0), &plot.y2_title, TextAnchor::BottomRight);
+        }
+        if !plot.title.is_empty() {
+            self.label.set_font_size(13.0);
+            self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x * 0.5, rect.pos.y + 6.0), &plot.title, TextAnchor::TopCenter);
+        }
+
+        self.draw_annotations(cx, plot);
+        self.draw_legend(cx, plot);
+    }
+
+    /// Panels in rows of `facets.columns` under the overall title, each
+    /// drawn like a single plot with tighter margins
+    fn draw_facets(&mut self, cx: &mut Cx2d, rect: Rect, facets: &CompiledFacets) {
+        let top = if facets.title.is_empty() { 0.0 } else { 28.0 };
+        if !facets.title.is_empty() {
+            self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+            self.label.set_font_size(13.0);
+            self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x * 0.5, rect.pos.y + 6.0), &facets.title, TextAnchor::TopCenter);
+        }
+
+        let columns = facets.columns.max(1);
+        let rows = facets.panels.len().div_ceil(columns);
+        let cell = dvec2(rect.size.x / columns as f64, (rect.size.y - top) / rows.max(1) as f64);
+        let margins = self.axes.margins;
+        let right = if facets.panels.iter().any(|(_, p)| p.y2.is_some()) { 50.0 } else { 14.0 };
+        self.axes.margins = PlotMargins { left: 50.0, top: 26.0, right, bottom: 36.0 };
+        for (index, (_, plot)) in facets.panels.iter().enumerate() {
+            let pos = rect.pos + dvec2((index % columns) as f64 * cell.x, top + (index / columns) as f64 * cell.y);
+            let cell_rect = Rect { pos, size: cell };
+            self.axes.x = plot.x.clone();
+            self.axes.y = plot.y.clone();
+            self.axes.update_plot_area(cell_rect);
+            let area = self.axes.plot_area;
+            if area.width() > 0.0 && area.height() > 0.0 {
+                self.draw_plot(cx, cell_rect, plot);
+            }
+        }
+        self.axes.margins = margins;
+    }
+
+    fn draw_mark(&mut self, cx: &mut Cx2d, axes: &Axes, mark: &CompiledMark) {
+        let area = axes.plot_area;
+        let n = mark.x.len().min(mark.y.len());
+        match mark.kind {
+            MarkType::Area => {
+                self.draw_fill.color = mark.color;
+                draw_fill_between(cx, &mut self.draw_fill, axes, &mark.x, &mark.y, &mark.y2);
+            }
+            MarkType::Bar => {
+                self.draw_bar.color = mark.color;
+                for i in 0..n.min(mark.y2.len()) {
+                    let left = axes.x_to_pixel(mark.x[i] + mark.bar_offset);
+                    let right = axes.x_to_pixel(mark.x[i] + mark.bar_offset + mark.bar_width);
+                    let (top, bottom) = (axes.y_to_pixel(mark.y[i]), axes.y_to_pixel(mark.y2[i]));
+                    let rect = Rect {
+                        pos: dvec2(left.min(right), top.min(bottom)),
+                        size: dvec2((right - left).abs(), (bottom - top).abs()),
+                    };
+                    self.draw_bar.draw_bar(cx, rect);
+                }
+            }
+            MarkType::Line => {
+                self.draw_line.color = mark.color;
+                let mut dash_offset = 0.0;
+                for i in 1..n {
+                    let p1 = axes.data_to_pixel(mark.x[i - 1], mark.y[i - 1]);
+                    let p2 = axes.data_to_pixel(mark.x[i], mark.y[i]);
+                    if let Some((c1, c2)) = clip_segment_x(p1, p2, area.left, area.right) {
+                        let clip_offset = dash_offset + (c1 - p1).length();
+                        self.draw_line.draw_line_styled(cx, c1, c2, mark.line_width, mark.line_style, clip_offset);
+                    }
+                    dash_offset += (p2 - p1).length();
+                }
+                if mark.show_points {
+                    self.draw_points(cx, axes, mark);
+                }
+            }
+            MarkType::Point => self.draw_points(cx, axes, mark),
+            MarkType::Rule => {
+                self.draw_line.color = mark.color;
+                let values = if mark.vertical { &mark.x } else { &mark.y };
+                for &v in values {
+                    let (p1, p2) = if mark.vertical {
+                        let x = axes.x_to_pixel(v);
+                        (dvec2(x, area.top), dvec2(x, area.bottom))
+                    } else {
+                        let y = axes.y_to_pixel(v);
+                        (dvec2(area.left, y), dvec2(area.right, y))
+                    };
+                    self.draw_line.draw_line_styled(cx, p1, p2, mark.line_width, mark.line_style, 0.0);
+                }
+            }
+            MarkType::Text => {
+                self.label.set_color(mark.color);
+                for i in 0..n.min(mark.text.len()) {
+                    let font_size = mark.size.get(i).copied().unwrap_or(10.0);
+                    self.label.set_font_size(font_size);
+                    let p = axes.data_to_pixel(mark.x[i], mark.y[i]);
+                    self.label.draw_at(cx, p, &mark.text[i], TextAnchor::Center);
+                }
+            }
+        }
+    }
+
+    fn draw_points(&mut self, cx: &mut Cx2d, axes: &Axes, mark: &CompiledMark) {
+        self.draw_point.color = mark.color;
+        for i in 0..mark.x.len().min(mark.y.len()) {
+            let p = axes.data_to_pixel(mark.x[i], mark.y[i]);
+            if axes.contains(p) {
+                let radius = mark.size.get(i).copied().unwrap_or(4.0);
+                self.draw_point.draw_marker(cx, p, radius, mark.marker);
+            }
+        }
+    }
+
+    fn draw_annotations(&mut self, cx: &mut Cx2d, plot: &CompiledPlot) {
+        // Arrows first so text appears on top
+        for arrow in &plot.annotations.arrows {
+            let start = self.axes.data_to_pixel(arrow.start_x, arrow.start_y);
+            let end = self.axes.data_to_pixel(arrow.end_x, arrow.end_y);
+            self.draw_line.color = arrow.color;
+            self.draw_line.draw_arrow(cx, start, end, arrow.line_width, arrow.head_size);
+            if let Some(text) = &arrow.text {
+                self.label.set_color(arrow.color);
+                self.label.set_font_size(11.0);
+                self.label.draw_at(cx, start, text, TextAnchor::BottomCenter);
+            }
+        }
+
+        for text in &plot.annotations.texts {
+            self.label.set_color(text.color);
+            self.label.set_font_size(text.font_size);
+            self.label.draw_at(cx, self.axes.data_to_pixel(text.x, text.y), &text.text, TextAnchor::Center);
+        }
+    }
+
+    fn draw_legend(&mut self, cx: &mut Cx2d, plot: &CompiledPlot) {
+        if plot.legend_position == LegendPosition::None || plot.legend.is_empty() {
+            return;
+        }
+        let area = self.axes.plot_area;
+        let line_height = 18.0;
+        let legend_x = match plot.legend_position {
+            LegendPosition::TopLeft | LegendPosition::BottomLeft => area.left + 10.0,
+            _ => area.right - 90.0,
+        };
+        let legend_y = match plot.legend_position {
+            LegendPosition::TopLeft | LegendPosition::TopRight => area.top + 10.0,
+            _ => area.bottom - 10.0 - plot.legend.len() as f64 * line_height,
+        };
+
+        self.label.set_font_size(10.0);
+        self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
+        for (i, (name, color)) in plot.legend.iter().enumerate() {
+            let y = legend_y + i as f64 * line_height + line_height * 0.5;
+            self.draw_point.color = *color;
+            self.draw_point.draw_point(cx, dvec2(legend_x + 5.0, y), 5.0);
+            self.label.draw_at(cx, dvec2(legend_x + 16.0, y), name, TextAnchor::MiddleLeft);
+        }
+    }
+}
+
+impl Widget for PlotSpecView {
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
+
+        if rect.size.x > 0.0 && rect.size.y > 0.0 {
+            self.axes.update_plot_area(rect);
+            let area = self.axes.plot_area;
+            if area.width() > 0.0 && area.height() > 0.0 {
+                if let Some(plot) = self.plot.take() {
+                    self.draw_plot(cx, rect, &plot);
+                    self.plot = Some(plot);
+                }
+            }
+            if let Some(facets) = self.facets.take() {
+                self.draw_facets(cx, rect, &facets);
+                self.facets = Some(facets);
+            }
+        }
+
+        self.capture.end();
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+    }
+}
+
+impl PlotExport for PlotSpecView {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
+impl PlotSpecViewRef {
+    pub fn set_spec(&self, spec: &PlotSpec) -> Result<(), String> {
+        match self.borrow_mut() {
+            Some(mut inner) => inner.set_spec(spec),
+            None => Err("plot spec view is not available".to_string()),
+        }
+    }
+    pub fn set_spec_json(&self, json: &str) -> Result<(), String> {
+        match self.borrow_mut() {
+            Some(mut inner) => inner.set_spec_json(json),
+            None => Err("plot spec view is not available".to_string()),
+        }
+    }
+    pub fn clear(&self) { if let Some(mut inner) = self.borrow_mut() { inner.clear(); } }
+    pub fn redraw(&self, cx: &mut Cx) { if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); } }
+}
@@ -0,0 +1,464 @@
+// Scatter matrix (SPLOM) - a scatter plot for every pair of dimensions with
+// histograms on the diagonal, and rectangle brushing in any cell
+
+use makepad_widgets::*;
+use crate::elements::*;
+use crate::text::*;
+use crate::export::*;
+use super::*;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::text::PlotLabel;
+
+    pub ScatterMatrix = {{ScatterMatrix}} {
+        width: Fill,
+        height: Fill,
+        label: <PlotLabel> {}
+    }
+}
+
+/// A press and release closer than this (pixels) count as a click
+const CLICK_SLOP: f64 = 4.0;
+
+/// Space between neighbouring cells
+const CELL_GAP: f64 = 6.0;
+
+/// Share of each axis' span left empty at both ends so points clear the
+/// cell borders
+const AXIS_PADDING: f64 = 0.05;
+
+/// Actions emitted by the scatter matrix
+#[derive(Clone, Debug, DefaultNone)]
+pub enum ScatterMatrixAction {
+    None,
+    /// The brush changed; `rows` lie inside it (all rows when it was cleared)
+    SelectionChanged { rows: Vec<usize> },
+}
+
+/// Rectangle brushed in the cell plotting `y_dim` against `x_dim`, in data
+/// values
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MatrixBrush {
+    pub x_dim: usize,
+    pub y_dim: usize,
+    pub x: (f64, f64),
+    pub y: (f64, f64),
+}
+
+impl MatrixBrush {
+    /// The brush as one `(dimension, (min, max))` range per axis
+    pub fn ranges(&self) -> [(usize, (f64, f64)); 2] {
+        [(self.x_dim, self.x), (self.y_dim, self.y)]
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct ScatterMatrix {
+    #[deref] #[live] view: View,
+    #[live] draw_point: DrawPlotPoint,
+    #[live] draw_bar: DrawPlotBar,
+    #[live] draw_line: DrawPlotLine,
+    #[live] draw_fill: DrawPlotFill,
+    #[live] label: PlotLabel,
+    #[rust] title: String,
+    #[rust] dimensions: Vec<PlotDimension>,
+    /// Axes of the dimensions, rebuilt when the data changes
+    #[rust] axes: Vec<PlotAxis>,
+    /// Each row's position along each dimension's axis (0..1), None when
+    /// the value is missing
+    #[rust] positions: Vec<Vec<Option<f64>>>,
+    #[rust] brush: Option<MatrixBrush>,
+    /// Dimension whose value colors the points through the colormap
+    #[rust] color_by: Option<usize>,
+    #[rust] colormap: Colormap,
+    /// Color of the points when not colored by a dimension; the first
+    /// palette color when None
+    #[rust] point_color: Option<Vec4>,
+    #[rust(2.5)] point_radius: f64,
+    #[rust] bin_rule: BinRule,
+    #[rust(vec4(0.75, 0.76, 0.78, 0.5))] filtered_color: Vec4,
+
+    /// Top-left corner of the grid and each cell's pitch
+    #[rust] grid_origin: DVec2,
+    #[rust] cell_pitch: f64,
+    /// Cell and pointer position of a press
+    #[rust] press: Option<(usize, usize, DVec2)>,
+    #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
+}
+
+impl ScatterMatrix {
+    /// Replace the data; the brush is kept while the number of dimensions
+    /// stays the same
+    pub fn set_dimensions(&mut self, dimensions: Vec<PlotDimension>) {
+        if dimensions == self.dimensions {
+            return;
+        }
+        if dimensions.len() != self.dimensions.len() {
+            self.brush = None;
+            self.press = None;
+        }
+        self.axes = dimensions.iter().map(|d| d.axis(AXIS_PADDING)).collect();
+        let rows = row_count(&dimensions);
+        self.positions = dimensions
+            .iter()
+            .zip(&self.axes)
+            .map(|(dimension, axis)| (0..rows).map(|row| dimension.value(row).map(|v| axis.normalize(v))).collect())
+            .collect();
+        self.dimensions = dimensions;
+    }
+
+    /// Color points by this dimension's value instead of one color
+    pub fn set_color_by(&mut self, dimension: Option<usize>) {
+        self.color_by = dimension;
+    }
+
+    pub fn set_colormap(&mut self, colormap: Colormap) {
+        self.colormap = colormap;
+    }
+
+    pub fn set_point_color(&mut self, color: Option<Vec4>) {
+        self.point_color = color;
+    }
+
+    pub fn set_point_radius(&mut self, radius: f64) {
+        self.point_radius = radius;
+    }
+
+    /// How the diagonal histograms are binned
+    pub fn set_bin_rule(&mut self, rule: BinRule) {
+        self.bin_rule = rule;
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    pub fn set_brush(&mut self, brush: Option<MatrixBrush>) {
+        self.brush = brush.filter(|b| b.x_dim < self.dimensions.len() && b.y_dim < self.dimensions.len());
+    }
+
+    pub fn brush(&self) -> Option<MatrixBrush> {
+        self.brush
+    }
+
+    /// Rows inside the brush; all rows when there is none
+    pub fn selected_rows(&self) -> Vec<usize> {
+        let brushes: Vec<(usize, (f64, f64))> = self.brush.iter().flat_map(MatrixBrush::ranges).collect();
+        rows_in_brushes(&self.dimensions, &brushes)
+    }
+
+    pub fn clear(&mut self) {
+        self.dimensions.clear();
+        self.axes.clear();
+        self.positions.clear();
+        self.brush = None;
+        self.press = None;
+    }
+
+    pub fn redraw(&mut self, cx: &mut Cx) {
+        self.view.redraw(cx);
+        self.area.redraw(cx);
+    }
+
+    /// Plotting rect of the cell in `row` and `column`
+    fn cell_rect(&self, row: usize, column: usize) -> Rect {
+        Rect {
+            pos: self.grid_origin + dvec2(column as f64, row as f64) * self.cell_pitch,
+            size: dvec2(self.cell_pitch - CELL_GAP, self.cell_pitch - CELL_GAP),
+        }
+    }
+
+    /// Row and column of the cell under `pos`
+    fn cell_at(&self, pos: DVec2) -> Option<(usize, usize)> {
+        if self.cell_pitch <= 0.0 {
+            return None;
+        }
+        let offset = (pos - self.grid_origin) * (1.0 / self.cell_pitch);
+        let n = self.dimensions.len();
+        if offset.x < 0.0 || offset.y < 0.0 || offset.x >= n as f64 || offset.y >= n as f64 {
+            return None;
+        }
+        let (row, column) = (offset.y as usize, offset.x as usize);
+        self.cell_rect(row, column).contains(pos).then_some((row, column))
+    }
+
+    fn point_color(&self, row: usize) -> Vec4 {
+        let by_value = self.color_by.and_then(|d| Some(self.colormap.sample(self.positions.get(d)?[row]?.clamp(0.0, 1.0))));
+        by_value.or(self.point_color).unwrap_or_else(|| get_color(0))
+    }
+
+    fn draw_scatter_cell(&mut self, cx: &mut Cx2d, rect: Rect, x_dim: usize, y_dim: usize, rows: &[usize], color: Option<Vec4>) {
+        for &row in rows {
+            let (Some(tx), Some(ty)) = (self.positions[x_dim][row], self.positions[y_dim][row]) else { continue };
+            self.draw_point.color = color.unwrap_or_else(|| self.point_color(row));
+            let center = dvec2(rect.pos.x + tx * rect.size.x, rect.pos.y + (1.0 - ty) * rect.size.y);
+            self.draw_point.draw_point(cx, center, self.point_radius);
+        }
+    }
+
+    /// All rows' histogram, with the selected rows' drawn over it when a
+    /// brush is set
+    fn draw_histogram_cell(&mut self, cx: &mut Cx2d, rect: Rect, dimension: usize, selected: Option<&[usize]>) {
+        let all: Vec<f64> = self.positions[dimension].iter().flatten().copied().collect();
+        let edges = bin_edges(&all, self.bin_rule);
+        let counts = bin_counts(&all, &edges);
+        let max = counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+        let bar = |i: usize, count: usize| {
+            let (x0, x1) = (rect.pos.x + edges[i].clamp(0.0, 1.0) * rect.size.x, rect.pos.x + edges[i + 1].clamp(0.0, 1.0) * rect.size.x);
+            let height = count as f64 / max * rect.size.y * 0.9;
+            Rect { pos: dvec2(x0, rect.pos.y + rect.size.y - height), size: dvec2((x1 - x0 - 1.0).max(1.0), height) }
+        };
+
+        let base = self.point_color.unwrap_or_else(|| get_color(0));
+        self.draw_bar.color = if selected.is_some() { self.filtered_color } else { base };
+        for (i, &count) in counts.iter().enumerate() {
+            self.draw_bar.draw_bar(cx, bar(i, count));
+        }
+        if let Some(rows) = selected {
+            let values: Vec<f64> = rows.iter().filter_map(|&row| self.positions[dimension][row]).collect();
+            self.draw_bar.color = base;
+            for (i, count) in bin_counts(&values, &edges).into_iter().enumerate() {
+                self.draw_bar.draw_bar(cx, bar(i, count));
+            }
+        }
+    }
+
+    fn draw_brush(&mut self, cx: &mut Cx2d, brush: MatrixBrush) {
+        let rect = self.cell_rect(brush.y_dim, brush.x_dim);
+        let (x_axis, y_axis) = (&self.axes[brush.x_dim], &self.axes[brush.y_dim]);
+        let (x0, x1) = (x_axis.normalize(brush.x.0), x_axis.normalize(brush.x.1));
+        let (y0, y1) = (y_axis.normalize(brush.y.0), y_axis.normalize(brush.y.1));
+        let a = dvec2(rect.pos.x + x0 * rect.size.x, rect.pos.y + (1.0 - y1) * rect.size.y);
+        let b = dvec2(rect.pos.x + x1 * rect.size.x, rect.pos.y + (1.0 - y0) * rect.size.y);
+        self.draw_fill.color = vec4(0.25, 0.45, 0.85, 0.15);
+        self.draw_fill.draw_rect(cx, Rect { pos: a, size: b - a });
+        self.draw_line.color = vec4(0.25, 0.45, 0.85, 0.8);
+        self.draw_line.draw_line(cx, a, dvec2(b.x, a.y), 1.0);
+        self.draw_line.draw_line(cx, dvec2(b.x, a.y), b, 1.0);
+        self.draw_line.draw_line(cx, b, dvec2(a.x, b.y), 1.0);
+        self.draw_line.draw_line(cx, dvec2(a.x, b.y), a, 1.0);
+    }
+
+    /// Frames, dimension names on the diagonal and ticks along the bottom
+    /// and left edges
+    fn draw_frames(&mut self, cx: &mut Cx2d) {
+        let n = self.dimensions.len();
+        self.draw_line.color = vec4(0.8, 0.8, 0.82, 1.0);
+        for row in 0..n {
+            for column in 0..n {
+                let rect = self.cell_rect(row, column);
+                let (a, b) = (rect.pos, rect.pos + rect.size);
+                self.draw_line.draw_line(cx, a, dvec2(b.x, a.y), 1.0);
+                self.draw_line.draw_line(cx, dvec2(b.x, a.y), b, 1.0);
+                self.draw_line.draw_line(cx, b, dvec2(a.x, b.y), 1.0);
+                self.draw_line.draw_line(cx, dvec2(a.x, b.y), a, 1.0);
+            }
+        }
+
+        self.label.set_font_size(10.0);
+        self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+        let names: Vec<String> = self.dimensions.iter().map(|d| d.name.clone()).collect();
+        for (i, name) in names.iter().enumerate() {
+            let rect = self.cell_rect(i, i);
+            self.label.draw_at(cx, rect.pos + dvec2(4.0, 3.0), name, TextAnchor::TopLeft);
+        }
+
+        self.label.set_font_size(8.0);
+        self.label.set_color(vec4(0.4, 0.4, 0.4, 1.0));
+        for (i, axis) in self.axes.clone().into_iter().enumerate() {
+            let bottom = self.cell_rect(n - 1, i);
+            let left = self.cell_rect(i, 0);
+            for tick in axis.ticks() {
+                let t = axis.normalize(tick);
+                let text = axis.format(tick);
+                let x = bottom.pos.x + t * bottom.size.x;
+                self.label.draw_at(cx, dvec2(x, bottom.pos.y + bottom.size.y + 3.0), &text, TextAnchor::TopCenter);
+                let y = left.pos.y + (1.0 - t) * left.size.y;
+                self.label.draw_at(cx, dvec2(left.pos.x - 4.0, y), &text, TextAnchor::MiddleRight);
+            }
+        }
+    }
+
+    /// Brush spanning `start` to `end` in the cell at `row`, `column`
+    fn brush_between(&self, row: usize, column: usize, start: DVec2, end: DVec2) -> MatrixBrush {
+        let rect = self.cell_rect(row, column);
+        let fraction = |p: DVec2| {
+            let offset = p - rect.pos;
+            ((offset.x / rect.size.x).clamp(0.0, 1.0), (1.0 - offset.y / rect.size.y).clamp(0.0, 1.0))
+        };
+        let ((sx, sy), (ex, ey)) = (fraction(start), fraction(end));
+        let (x_axis, y_axis) = (&self.axes[column], &self.axes[row]);
+        MatrixBrush {
+            x_dim: column,
+            y_dim: row,
+            x: (x_axis.denormalize(sx.min(ex)), x_axis.denormalize(sx.max(ex))),
+            y: (y_axis.denormalize(sy.min(ey)), y_axis.denormalize(sy.max(ey))),
+        }
+    }
+}
+
+impl Widget for ScatterMatrix {
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
+
+        let n = self.dimensions.len();
+        if rect.size.x > 0.0 && rect.size.y > 0.0 && n > 0 {
+            let top = if self.title.is_empty() { 10.0 } else { 36.0 };
+            let (left, bottom) = (48.0, 24.0);
+            let size = dvec2(rect.size.x - left - 10.0, rect.size.y - top - bottom);
+            self.cell_pitch = (size.x.min(size.y) / n as f64).max(0.0);
+            let grid = self.cell_pitch * n as f64;
+            self.grid_origin = rect.pos + dvec2(left + (size.x - grid).max(0.0) * 0.5, top);
+
+            let all: Vec<usize> = (0..row_count(&self.dimensions)).collect();
+            let selected = self.brush.map(|_| self.selected_rows());
+            for row in 0..n {
+                for column in 0..n {
+                    let cell = self.cell_rect(row, column);
+                    if row == column {
+                        self.draw_histogram_cell(cx, cell, row, selected.as_deref());
+                    } else if let Some(selected) = &selected {
+                        // Filtered points underneath, selected ones on top
+                        let filtered = self.filtered_color;
+                        self.draw_scatter_cell(cx, cell, column, row, &all, Some(filtered));
+                        self.draw_scatter_cell(cx, cell, column, row, selected, None);
+                    } else {
+                        self.draw_scatter_cell(cx, cell, column, row, &all, None);
+                    }
+                }
+            }
+            if let Some(brush) = self.brush {
+                self.draw_brush(cx, brush);
+            }
+            self.draw_frames(cx);
+
+            if !self.title.is_empty() {
+                self.label.set_font_size(12.0);
+                self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+                self.label.draw_at(cx, dvec2(rect.pos.x + rect.size.x / 2.0, rect.pos.y + 10.0), &self.title, TextAnchor::TopCenter);
+            }
+        }
+
+        self.capture.end();
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        match event.hits(cx, self.area) {
+            Hit::FingerDown(fe) => {
+                // Brushes are drawn in the scatter cells only
+                self.press = self.cell_at(fe.abs).filter(|(row, column)| row != column).map(|(row, column)| (row, column, fe.abs));
+            }
+            Hit::FingerMove(fe) => {
+                if let Some((row, column, start)) = self.press {
+                    if (fe.abs - start).length() > CLICK_SLOP {
+                        self.brush = Some(self.brush_between(row, column, start, fe.abs));
+                        self.redraw(cx);
+                    }
+                }
+            }
+            Hit::FingerUp(fe) => {
+                let Some((_, _, start)) = self.press.take() else { return };
+                // A click clears the brush
+                if (fe.abs - start).length() <= CLICK_SLOP && self.brush.take().is_none() {
+                    return;
+                }
+                let rows = self.selected_rows();
+                cx.widget_action(self.widget_uid(), &scope.path, ScatterMatrixAction::SelectionChanged { rows });
+                self.redraw(cx);
+            }
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => {
+                let scatter = self.cell_at(fe.abs).is_some_and(|(row, column)| row != column);
+                cx.set_cursor(if scatter { MouseCursor::Crosshair } else { MouseCursor::Default });
+            }
+            Hit::FingerHoverOut(_) => {
+                cx.set_cursor(MouseCursor::Default);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl PlotExport for ScatterMatrix {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
+impl ScatterMatrixRef {
+    pub fn set_dimensions(&self, dimensions: Vec<PlotDimension>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_dimensions(dimensions); }
+    }
+    pub fn set_color_by(&self, dimension: Option<usize>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_color_by(dimension); }
+    }
+    pub fn set_colormap(&self, colormap: Colormap) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_colormap(colormap); }
+    }
+    pub fn set_point_color(&self, color: Option<Vec4>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_point_color(color); }
+    }
+    pub fn set_bin_rule(&self, rule: BinRule) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_bin_rule(rule); }
+    }
+    pub fn set_brush(&self, brush: Option<MatrixBrush>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_brush(brush); }
+    }
+    pub fn selected_rows(&self) -> Vec<usize> {
+        self.borrow().map(|inner| inner.selected_rows()).unwrap_or_default()
+    }
+    pub fn set_title(&self, title: impl Into<String>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); }
+    }
+    pub fn clear(&self) {
+        if let Some(mut inner) = self.borrow_mut() { inner.clear(); }
+    }
+    pub fn redraw(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); }
+    }
+
+    /// Rows selected by brushing, if the brush changed
+    pub fn selection_changed(&self, actions: &Actions) -> Option<Vec<usize>> {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(|action| action.widget_uid == uid)
+            .find_map(|action| match action.cast() {
+                ScatterMatrixAction::SelectionChanged { rows } => Some(rows),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matrix_brush_selects_rows() {
+        let dimensions = vec![
+            PlotDimension::new("x", vec![1.0, 2.0, 3.0, 4.0]),
+            PlotDimension::new("y", vec![40.0, 30.0, 20.0, 10.0]),
+        ];
+        let brush = MatrixBrush { x_dim: 0, y_dim: 1, x: (2.0, 4.0), y: (15.0, 35.0) };
+        assert_eq!(brush.ranges(), [(0, (2.0, 4.0)), (1, (15.0, 35.0))]);
+        // Rows inside the rectangle: x in 2..=4 and y in 15..=35
+        assert_eq!(rows_in_brushes(&dimensions, &brush.ranges()), vec![1, 2]);
+        // Swapping the cell's axes selects the same rows
+        let transposed = MatrixBrush { x_dim: 1, y_dim: 0, x: brush.y, y: brush.x };
+        assert_eq!(rows_in_brushes(&dimensions, &transposed.ranges()), vec![1, 2]);
+    }
+}
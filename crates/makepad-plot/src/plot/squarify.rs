@@ -0,0 +1,185 @@
+// Squarified treemap layout (Bruls, Huizing and van Wijk)
+//
+// Values are placed largest first in rows along the shorter side of the
+// remaining rectangle. A row keeps growing while that improves its worst
+// aspect ratio; once the next value would make it worse, the row is fixed
+// and the rest of the rectangle is filled the same way. Cells stay close to
+// square instead of the thin slivers of a single slice-and-dice pass.
+
+use makepad_widgets::*;
+
+/// Rectangles with areas proportional to `values`, tiling `rect`, in the
+/// order of `values`. Zero, negative and non-finite values get an empty
+/// rectangle at the corner of `rect`.
+pub fn squarify(values: &[f64], rect: Rect) -> Vec<Rect> {
+    let mut out = vec![Rect { pos: rect.pos, size: dvec2(0.0, 0.0) }; values.len()];
+    let weight = |v: f64| if v.is_finite() && v > 0.0 { v } else { 0.0 };
+    let total: f64 = values.iter().map(|&v| weight(v)).sum();
+    if total <= 0.0 || rect.size.x <= 0.0 || rect.size.y <= 0.0 {
+        return out;
+    }
+
+    let mut order: Vec<usize> = (0..values.len()).filter(|&i| weight(values[i]) > 0.0).collect();
+    order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+    let scale = rect.size.x * rect.size.y / total;
+    let area = |i: usize| values[i] * scale;
+
+    let mut free = rect;
+    let mut row: Vec<usize> = Vec::new();
+    let mut next = 0;
+    while next < order.len() {
+        let side = free.size.x.min(free.size.y);
+        let candidate = order[next];
+        let current = worst_ratio(row.iter().map(|&i| area(i)), side);
+        let grown = worst_ratio(row.iter().chain([&candidate]).map(|&i| area(i)), side);
+        if row.is_empty() || grown <= current {
+            row.push(candidate);
+            next += 1;
+        } else {
+            free = place_row(&row, area, free, &mut out);
+            row.clear();
+        }
+    }
+    if !row.is_empty() {
+        // The last row fills what is left exactly, absorbing rounding drift
+        place_row(&row, area, free, &mut out);
+        fit_last_row(&row, free, &mut out);
+    }
+    out
+}
+
+/// Largest aspect ratio of a row of `areas` laid along a side of length `side`
+fn worst_ratio(areas: impl Iterator<Item = f64> + Clone, side: f64) -> f64 {
+    let sum: f64 = areas.clone().sum();
+    if sum <= 0.0 || side <= 0.0 {
+        return f64::INFINITY;
+    }
+    let (side2, sum2) = (side * side, sum * sum);
+    areas
+        .map(|a| (side2 * a / sum2).max(sum2 / (side2 * a)))
+        .fold(0.0, f64::max)
+}
+
+/// Lay `row` along the shorter side of `free` and return what remains
+fn place_row(row: &[usize], area: impl Fn(usize) -> f64, free: Rect, out: &mut [Rect]) -> Rect {
+    let sum: f64 = row.iter().map(|&i| area(i)).sum();
+    if free.size.x >= free.size.y {
+        // Column on the left, cells stacked top to bottom
+        let width = (sum / free.size.y).min(free.size.x);
+        let mut y = free.pos.y;
+        for &i in row {
+            let height = area(i) / width;
+            out[i] = Rect { pos: dvec2(free.pos.x, y), size: dvec2(width, height) };
+            y += height;
+        }
+        Rect { pos: dvec2(free.pos.x + width, free.pos.y), size: dvec2(free.size.x - width, free.size.y) }
+    } else {
+        // Row along the top, cells side by side
+        let height = (sum / free.size.x).min(free.size.y);
+        let mut x = free.pos.x;
+        for &i in row {
+            let width = area(i) / height;
+            out[i] = Rect { pos: dvec2(x, free.pos.y), size: dvec2(width, height) };
+            x += width;
+        }
+        Rect { pos: dvec2(free.pos.x, free.pos.y + height), size: dvec2(free.size.x, free.size.y - height) }
+    }
+}
+
+/// Stretch the cells of the final row to exactly cover `rest`
+fn fit_last_row(row: &[usize], rest: Rect, out: &mut [Rect]) {
+    let stacked = rest.size.x >= rest.size.y;
+    let length: f64 = row.iter().map(|&i| if stacked { out[i].size.y } else { out[i].size.x }).sum();
+    if length <= 0.0 {
+        return;
+    }
+    let (mut offset, full) = (0.0, if stacked { rest.size.y } else { rest.size.x });
+    for &i in row {
+        let share = (if stacked { out[i].size.y } else { out[i].size.x }) / length * full;
+        out[i] = if stacked {
+            Rect { pos: dvec2(rest.pos.x, rest.pos.y + offset), size: dvec2(rest.size.x, share) }
+        } else {
+            Rect { pos: dvec2(rest.pos.x + offset, rest.pos.y), size: dvec2(share, rest.size.y) }
+        };
+        offset += share;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(r: &Rect) -> f64 {
+        r.size.x * r.size.y
+    }
+
+    fn overlap(a: &Rect, b: &Rect) -> f64 {
+        let w = (a.pos.x + a.size.x).min(b.pos.x + b.size.x) - a.pos.x.max(b.pos.x);
+        let h = (a.pos.y + a.size.y).min(b.pos.y + b.size.y) - a.pos.y.max(b.pos.y);
+        w.max(0.0) * h.max(0.0)
+    }
+
+    fn ratio(r: &Rect) -> f64 {
+        (r.size.x / r.size.y).max(r.size.y / r.size.x)
+    }
+
+    #[test]
+    fn test_tiles_parent_exactly() {
+        let rect = Rect { pos: dvec2(10.0, 20.0), size: dvec2(600.0, 400.0) };
+        let values = [6.0, 6.0, 4.0, 3.0, 2.0, 2.0, 1.0, 0.5, 13.0];
+        let total: f64 = values.iter().sum();
+        let cells = squarify(&values, rect);
+        assert_eq!(cells.len(), values.len());
+
+        for (cell, value) in cells.iter().zip(values) {
+            assert!((area(cell) - value / total * area(&rect)).abs() < 1e-6);
+            assert!(cell.pos.x >= rect.pos.x - 1e-9 && cell.pos.y >= rect.pos.y - 1e-9);
+            assert!(cell.pos.x + cell.size.x <= rect.pos.x + rect.size.x + 1e-9);
+            assert!(cell.pos.y + cell.size.y <= rect.pos.y + rect.size.y + 1e-9);
+        }
+        for (i, a) in cells.iter().enumerate() {
+            for b in &cells[i + 1..] {
+                assert!(overlap(a, b) < 1e-6);
+            }
+        }
+        let covered: f64 = cells.iter().map(area).sum();
+        assert!((covered - area(&rect)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_aspect_ratios_bounded() {
+        let rect = Rect { pos: dvec2(0.0, 0.0), size: dvec2(400.0, 300.0) };
+        // Equal values come out nearly square
+        let equal = squarify(&[1.0; 12], rect);
+        assert!(equal.iter().all(|r| ratio(r) < 1.5), "{:?}", equal.iter().map(ratio).collect::<Vec<_>>());
+
+        // The worked example from the paper stays within 3:1
+        let cells = squarify(&[6.0, 6.0, 4.0, 3.0, 2.0, 2.0, 1.0], Rect { pos: dvec2(0.0, 0.0), size: dvec2(6.0, 4.0) });
+        assert!(cells.iter().all(|r| ratio(r) <= 3.0), "{:?}", cells.iter().map(ratio).collect::<Vec<_>>());
+
+        // Much better than slicing a long tail into strips
+        let tail: Vec<f64> = (1..=30).map(|i| 1.0 / i as f64).collect();
+        let cells = squarify(&tail, rect);
+        let worst = cells.iter().map(ratio).fold(0.0, f64::max);
+        assert!(worst < 4.0, "worst ratio {}", worst);
+    }
+
+    #[test]
+    fn test_zero_weight_children() {
+        let rect = Rect { pos: dvec2(5.0, 5.0), size: dvec2(100.0, 50.0) };
+        let cells = squarify(&[5.0, 0.0, f64::NAN, -1.0, 5.0], rect);
+        for empty in &cells[1..4] {
+            assert_eq!(empty.pos, rect.pos);
+            assert_eq!(empty.size, dvec2(0.0, 0.0));
+        }
+        assert!((area(&cells[0]) - 2500.0).abs() < 1e-9);
+        assert!((area(&cells[4]) - 2500.0).abs() < 1e-9);
+        assert!(overlap(&cells[0], &cells[4]) < 1e-9);
+
+        // Nothing to place, or nowhere to place it
+        assert!(squarify(&[0.0, 0.0], rect).iter().all(|r| area(r) == 0.0));
+        assert!(squarify(&[], rect).is_empty());
+        let flat = Rect { pos: rect.pos, size: dvec2(100.0, 0.0) };
+        assert!(squarify(&[1.0, 2.0], flat).iter().all(|r| area(r) == 0.0));
+    }
+}
@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -110,6 +111,9 @@ pub struct Stackplot {
     #[rust] offset: StackOffset,
     #[rust] show_lines: bool,
     #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Stackplot {
@@ -260,6 +264,7 @@ impl Stackplot {
 impl Widget for Stackplot {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 10.0 && rect.size.y > 10.0 {
             let padding = 30.0;
@@ -269,13 +274,14 @@ impl Widget for Stackplot {
             let chart_h = rect.size.y - padding * 2.0;
 
             if self.series.is_empty() || chart_w < 10.0 || chart_h < 10.0 {
+                self.capture.end();
                 return DrawStep::done();
             }
 
             // Compute stacked data
             let stacked = self.compute_stacked();
             let n_points = stacked[0].len();
-            if n_points == 0 { return DrawStep::done(); }
+            if n_points == 0 { self.capture.end(); return DrawStep::done(); }
 
             // Find y range
             let mut y_min = f64::MAX;
@@ -338,12 +344,23 @@ impl Widget for Stackplot {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
     fn handle_event(&mut self, _cx: &mut Cx, _event: &Event, _scope: &mut Scope) {}
 }
 
+impl PlotExport for Stackplot {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl StackplotRef {
     pub fn set_data(&self, series: Vec<StackSeries>, x_labels: Vec<String>) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_data(series, x_labels); }
@@ -401,6 +418,9 @@ pub struct Streamgraph {
     #[rust] labels: Vec<String>,
     #[rust] title: String,
     #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Streamgraph {
@@ -421,6 +441,7 @@ impl Streamgraph {
 impl Widget for Streamgraph {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
 
         if rect.size.x > 10.0 && rect.size.y > 10.0 && !self.series.is_empty() {
             let padding = 30.0;
@@ -430,7 +451,7 @@ impl Widget for Streamgraph {
             let chart_h = rect.size.y - padding * 2.0;
 
             let n_points = self.series.iter().map(|s| s.values.len()).max().unwrap_or(0);
-            if n_points == 0 { return DrawStep::done(); }
+            if n_points == 0 { self.capture.end(); return DrawStep::done(); }
 
             // Calculate totals
             let mut totals: Vec<f64> = vec![0.0; n_points];
@@ -441,7 +462,7 @@ impl Widget for Streamgraph {
             }
 
             let max_total = totals.iter().cloned().fold(0.0_f64, f64::max);
-            if max_total == 0.0 { return DrawStep::done(); }
+            if max_total == 0.0 { self.capture.end(); return DrawStep::done(); }
 
             // Calculate baselines for centering (silhouette offset)
             let baselines: Vec<f64> = totals.iter().map(|&t| (max_total - t) / 2.0).collect();
@@ -495,12 +516,23 @@ impl Widget for Streamgraph {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
     fn handle_event(&mut self, _cx: &mut Cx, _event: &Event, _scope: &mut Scope) {}
 }
 
+impl PlotExport for Streamgraph {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl StreamgraphRef {
     pub fn set_data(&self, series: Vec<StreamSeries>, labels: Vec<String>) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_data(series, labels); }
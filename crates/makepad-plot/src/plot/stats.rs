@@ -0,0 +1,722 @@
+// Statistical transforms behind the distribution and regression charts
+//
+// Pure functions over sample slices. Non-finite samples are ignored
+// throughout, so NaN can mark missing data. Results that cannot be computed
+// (too few samples) come back as NaN, an empty vector or `None`.
+
+/// Finite samples in ascending order
+pub fn sorted_finite(values: &[f64]) -> Vec<f64> {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+pub fn mean(values: &[f64]) -> f64 {
+    let (sum, n) = values.iter().filter(|v| v.is_finite()).fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+    if n == 0 { f64::NAN } else { sum / n as f64 }
+}
+
+/// Sample standard deviation (divides by n - 1)
+pub fn std_dev(values: &[f64]) -> f64 {
+    let m = mean(values);
+    let (sum, n) = values.iter().filter(|v| v.is_finite()).fold((0.0, 0usize), |(s, n), v| (s + (v - m) * (v - m), n + 1));
+    if n < 2 { f64::NAN } else { (sum / (n - 1) as f64).sqrt() }
+}
+
+/// Quantile `q` (0 to 1) of samples already in ascending order, interpolating
+/// linearly between neighbouring samples (the default of R and NumPy)
+pub fn quantile_sorted(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let h = (sorted.len() - 1) as f64 * q.clamp(0.0, 1.0);
+    let (lo, hi) = (h.floor() as usize, h.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (h - lo as f64)
+}
+
+pub fn quantile(values: &[f64], q: f64) -> f64 {
+    quantile_sorted(&sorted_finite(values), q)
+}
+
+/// Several quantiles with one sort
+pub fn quantiles(values: &[f64], qs: &[f64]) -> Vec<f64> {
+    let sorted = sorted_finite(values);
+    qs.iter().map(|&q| quantile_sorted(&sorted, q)).collect()
+}
+
+pub fn median(values: &[f64]) -> f64 {
+    quantile(values, 0.5)
+}
+
+/// Interquartile range, Q3 - Q1
+pub fn iqr(values: &[f64]) -> f64 {
+    let sorted = sorted_finite(values);
+    quantile_sorted(&sorted, 0.75) - quantile_sorted(&sorted, 0.25)
+}
+
+/// Empirical cumulative distribution as the corners of its step function:
+/// each distinct value with the fraction of samples at or below it
+pub fn ecdf(values: &[f64]) -> Vec<(f64, f64)> {
+    let sorted = sorted_finite(values);
+    let n = sorted.len() as f64;
+    let mut steps: Vec<(f64, f64)> = Vec::new();
+    for (i, &v) in sorted.iter().enumerate() {
+        let fraction = (i + 1) as f64 / n;
+        match steps.last_mut() {
+            Some(last) if last.0 == v => last.1 = fraction,
+            _ => steps.push((v, fraction)),
+        }
+    }
+    steps
+}
+
+/// `n` evenly spaced values from `lo` to `hi` inclusive
+pub fn linspace(lo: f64, hi: f64, n: usize) -> Vec<f64> {
+    match n {
+        0 => Vec::new(),
+        1 => vec![lo],
+        _ => (0..n).map(|i| lo + (hi - lo) * i as f64 / (n - 1) as f64).collect(),
+    }
+}
+
+// =============================================================================
+// Binning
+// =============================================================================
+
+/// Upper limit on the bins a rule may ask for
+const MAX_BINS: usize = 1000;
+
+/// How many equal-width bins a histogram gets
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BinRule {
+    /// Exactly this many bins
+    Count(usize),
+    /// Bins of this width, starting at the smallest sample
+    Width(f64),
+    /// `log2(n) + 1` bins; suits small, roughly normal samples
+    #[default]
+    Sturges,
+    /// Width `3.49 σ n^(-1/3)`, optimal for normal data
+    Scott,
+    /// Width `2 IQR n^(-1/3)`, robust to outliers and heavy tails
+    FreedmanDiaconis,
+}
+
+impl BinRule {
+    /// Number of bins over the finite samples of `values`
+    pub fn bin_count(&self, values: &[f64]) -> usize {
+        let sorted = sorted_finite(values);
+        let Some((&min, &max)) = sorted.first().zip(sorted.last()) else { return 0 };
+        let n = sorted.len() as f64;
+        let sturges = (n.log2().ceil() as usize + 1).max(1);
+        let width = match *self {
+            BinRule::Count(count) => return count.clamp(1, MAX_BINS),
+            BinRule::Sturges => return sturges,
+            BinRule::Width(width) => width,
+            BinRule::Scott => 3.49 * std_dev(&sorted) * n.powf(-1.0 / 3.0),
+            BinRule::FreedmanDiaconis => {
+                2.0 * (quantile_sorted(&sorted, 0.75) - quantile_sorted(&sorted, 0.25)) * n.powf(-1.0 / 3.0)
+            }
+        };
+        if !(width > 0.0 && width.is_finite()) || max == min {
+            return sturges;
+        }
+        (((max - min) / width).ceil() as usize).clamp(1, MAX_BINS)
+    }
+}
+
+/// Edges of equal-width bins covering the finite samples, one more than the
+/// bin count. All-equal samples get a single bin one unit wide.
+pub fn bin_edges(values: &[f64], rule: BinRule) -> Vec<f64> {
+    let sorted = sorted_finite(values);
+    let Some((&min, &max)) = sorted.first().zip(sorted.last()) else { return Vec::new() };
+    if max == min {
+        return vec![min - 0.5, min + 0.5];
+    }
+    let count = rule.bin_count(&sorted);
+    match rule {
+        BinRule::Width(width) if width > 0.0 && count < MAX_BINS => {
+            (0..=count).map(|i| min + i as f64 * width).collect()
+        }
+        _ => linspace(min, max, count + 1),
+    }
+}
+
+/// Samples falling in each bin between consecutive `edges`; bins include
+/// their left edge, and the last one its right edge too
+pub fn bin_counts(values: &[f64], edges: &[f64]) -> Vec<usize> {
+    let bins = edges.len().saturating_sub(1);
+    let mut counts = vec![0; bins];
+    if bins == 0 {
+        return counts;
+    }
+    for &v in values.iter().filter(|v| v.is_finite()) {
+        if v < edges[0] || v > edges[bins] {
+            continue;
+        }
+        let bin = edges.partition_point(|&e| e <= v).saturating_sub(1).min(bins - 1);
+        counts[bin] += 1;
+    }
+    counts
+}
+
+// =============================================================================
+// Kernel density estimation
+// =============================================================================
+
+/// Smoothing kernel of a density estimate
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Kernel {
+    #[default]
+    Gaussian,
+    /// Parabolic; the most efficient kernel in theory
+    Epanechnikov,
+    Triangular,
+    /// Box of constant weight
+    Uniform,
+    /// Quartic, `(1 - u²)²`
+    Biweight,
+}
+
+impl Kernel {
+    /// Standard deviation of the kernel in its usual form on -1..1
+    fn spread(self) -> f64 {
+        match self {
+            Kernel::Gaussian => 1.0,
+            Kernel::Epanechnikov => (1.0f64 / 5.0).sqrt(),
+            Kernel::Triangular => (1.0f64 / 6.0).sqrt(),
+            Kernel::Uniform => (1.0f64 / 3.0).sqrt(),
+            Kernel::Biweight => (1.0f64 / 7.0).sqrt(),
+        }
+    }
+
+    /// Kernel density at `u`, scaled to unit standard deviation so that one
+    /// bandwidth means the same smoothing for every kernel
+    pub fn density(self, u: f64) -> f64 {
+        let s = self.spread();
+        let t = u * s;
+        let k = match self {
+            Kernel::Gaussian => return (-0.5 * u * u).exp() / (2.0 * std::f64::consts::PI).sqrt(),
+            _ if t.abs() > 1.0 => 0.0,
+            Kernel::Epanechnikov => 0.75 * (1.0 - t * t),
+            Kernel::Triangular => 1.0 - t.abs(),
+            Kernel::Uniform => 0.5,
+            Kernel::Biweight => 15.0 / 16.0 * (1.0 - t * t).powi(2),
+        };
+        k * s
+    }
+
+    /// Distance in bandwidths beyond which the density is (effectively) zero
+    fn reach(self) -> f64 {
+        match self {
+            Kernel::Gaussian => 8.0,
+            _ => 1.0 / self.spread(),
+        }
+    }
+}
+
+/// Bandwidth of a density estimate
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Bandwidth {
+    /// Silverman's rule of thumb, `0.9 min(σ, IQR / 1.34) n^(-1/5)`; copes
+    /// with skewed and multimodal data
+    Silverman,
+    /// Normal reference rule, `1.06 σ n^(-1/5)`; optimal for normal data
+    #[default]
+    Scott,
+    /// This kernel standard deviation, in data units
+    Fixed(f64),
+}
+
+impl Bandwidth {
+    /// Kernel standard deviation for `values`. Falls back to 1.0 when the
+    /// samples have no spread.
+    pub fn select(&self, values: &[f64]) -> f64 {
+        let sorted = sorted_finite(values);
+        let n = sorted.len() as f64;
+        let sigma = std_dev(&sorted);
+        let bw = match *self {
+            Bandwidth::Fixed(bw) => bw,
+            Bandwidth::Scott => 1.06 * sigma * n.powf(-0.2),
+            Bandwidth::Silverman => {
+                let iqr = quantile_sorted(&sorted, 0.75) - quantile_sorted(&sorted, 0.25);
+                let spread = if iqr > 0.0 { sigma.min(iqr / 1.34) } else { sigma };
+                0.9 * spread * n.powf(-0.2)
+            }
+        };
+        if bw > 0.0 && bw.is_finite() { bw } else { 1.0 }
+    }
+}
+
+/// Kernel density estimate of `values` at each point of `at`, with
+/// `bandwidth` the kernel standard deviation
+pub fn kde(values: &[f64], at: &[f64], bandwidth: f64, kernel: Kernel) -> Vec<f64> {
+    let sorted = sorted_finite(values);
+    if sorted.is_empty() || bandwidth <= 0.0 {
+        return vec![0.0; at.len()];
+    }
+    let norm = sorted.len() as f64 * bandwidth;
+    let reach = kernel.reach() * bandwidth;
+    at.iter()
+        .map(|&x| {
+            let start = sorted.partition_point(|&v| v < x - reach);
+            let end = sorted.partition_point(|&v| v <= x + reach);
+            sorted[start..end].iter().map(|&v| kernel.density((x - v) / bandwidth)).sum::<f64>() / norm
+        })
+        .collect()
+}
+
+// =============================================================================
+// Regression
+// =============================================================================
+
+/// Standard normal quantile (inverse CDF), by Acklam's rational
+/// approximation (relative error below 1.2e-9)
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2, 1.38357751867269e2, -3.066479806614716e1, 2.506628277459239];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2, 6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+    if p <= 0.0 { return f64::NEG_INFINITY; }
+    if p >= 1.0 { return f64::INFINITY; }
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < 0.02425 {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - 0.02425 {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Student t quantile with `dof` degrees of freedom: exact for one and two,
+/// otherwise the Cornish-Fisher expansion about the normal quantile
+pub fn t_quantile(p: f64, dof: f64) -> f64 {
+    if dof <= 0.0 || !dof.is_finite() {
+        return normal_quantile(p);
+    }
+    if dof == 1.0 {
+        return (std::f64::consts::PI * (p - 0.5)).tan();
+    }
+    if dof == 2.0 {
+        return (2.0 * p - 1.0) / (2.0 * p * (1.0 - p)).sqrt();
+    }
+    let z = normal_quantile(p);
+    let (z2, n) = (z * z, dof);
+    z + z * (z2 + 1.0) / (4.0 * n)
+        + z * ((5.0 * z2 + 16.0) * z2 + 3.0) / (96.0 * n * n)
+        + z * (((3.0 * z2 + 19.0) * z2 + 17.0) * z2 - 15.0) / (384.0 * n * n * n)
+        + z * ((((79.0 * z2 + 776.0) * z2 + 1482.0) * z2 - 1920.0) * z2 - 945.0) / (92160.0 * n * n * n * n)
+}
+
+/// Fitted curve sampled along x, with a confidence band for its mean
+#[derive(Clone, Debug, Default)]
+pub struct RegressionCurve {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub lower: Vec<f64>,
+    pub upper: Vec<f64>,
+}
+
+/// Least-squares polynomial fit
+#[derive(Clone, Debug)]
+pub struct PolyFit {
+    /// Coefficients of `x^0, x^1, ...`
+    pub coefficients: Vec<f64>,
+    pub r_squared: f64,
+    /// Standard deviation of the residuals
+    pub residual_std: f64,
+    /// Residual degrees of freedom, samples minus coefficients
+    pub dof: usize,
+    // The fit itself runs on x shifted and scaled to about -1..1, which
+    // keeps the normal equations well conditioned for higher degrees
+    center: f64,
+    scale: f64,
+    scaled: Vec<f64>,
+    /// `(XᵀX)⁻¹` in the scaled basis
+    inverse: Vec<Vec<f64>>,
+}
+
+/// Fit a polynomial of `degree` to the finite `(x, y)` pairs. `None` with
+/// fewer distinct x values than coefficients.
+pub fn polyfit(x: &[f64], y: &[f64], degree: usize) -> Option<PolyFit> {
+    let pairs: Vec<(f64, f64)> = x.iter().zip(y).map(|(&x, &y)| (x, y)).filter(|(x, y)| x.is_finite() && y.is_finite()).collect();
+    let terms = degree + 1;
+    if pairs.len() < terms {
+        return None;
+    }
+    let (lo, hi) = pairs.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.0), hi.max(p.0)));
+    let center = (lo + hi) / 2.0;
+    let scale = if hi > lo { (hi - lo) / 2.0 } else { 1.0 };
+    let powers = |x: f64| {
+        let t = (x - center) / scale;
+        (0..terms).scan(1.0, move |p, _| { let v = *p; *p *= t; Some(v) }).collect::<Vec<f64>>()
+    };
+
+    let mut xtx = vec![vec![0.0; terms]; terms];
+    let mut xty = vec![0.0; terms];
+    for &(x, y) in &pairs {
+        let row = powers(x);
+        for i in 0..terms {
+            xty[i] += row[i] * y;
+            for j in 0..terms {
+                xtx[i][j] += row[i] * row[j];
+            }
+        }
+    }
+    let inverse = invert(xtx)?;
+    let scaled: Vec<f64> = inverse.iter().map(|row| row.iter().zip(&xty).map(|(a, b)| a * b).sum()).collect();
+
+    let evaluate = |x: f64| powers(x).iter().zip(&scaled).map(|(p, c)| p * c).sum::<f64>();
+    let y_mean = pairs.iter().map(|p| p.1).sum::<f64>() / pairs.len() as f64;
+    let ss_res: f64 = pairs.iter().map(|&(x, y)| (y - evaluate(x)).powi(2)).sum();
+    let ss_tot: f64 = pairs.iter().map(|&(_, y)| (y - y_mean).powi(2)).sum();
+    let dof = pairs.len() - terms;
+
+    // Expand the scaled polynomial back into powers of x
+    let mut coefficients = vec![0.0; terms];
+    let mut basis = vec![1.0]; // coefficients of ((x - center) / scale)^k
+    for &c in &scaled {
+        for (k, b) in basis.iter().enumerate() {
+            coefficients[k] += c * b;
+        }
+        let mut next = vec![0.0; basis.len() + 1];
+        for (k, b) in basis.iter().enumerate() {
+            next[k + 1] += b / scale;
+            next[k] -= b * center / scale;
+        }
+        basis = next;
+    }
+
+    Some(PolyFit {
+        coefficients,
+        r_squared: if ss_tot > 0.0 { 1.0 - ss_res / ss_tot } else { 1.0 },
+        residual_std: if dof > 0 { (ss_res / dof as f64).sqrt() } else { 0.0 },
+        dof,
+        center,
+        scale,
+        scaled,
+        inverse,
+    })
+}
+
+/// Straight-line least-squares fit; `coefficients` are intercept and slope
+pub fn linear_fit(x: &[f64], y: &[f64]) -> Option<PolyFit> {
+    polyfit(x, y, 1)
+}
+
+impl PolyFit {
+    fn powers(&self, x: f64) -> Vec<f64> {
+        let t = (x - self.center) / self.scale;
+        (0..self.scaled.len()).scan(1.0, |p, _| { let v = *p; *p *= t; Some(v) }).collect()
+    }
+
+    pub fn predict(&self, x: f64) -> f64 {
+        self.powers(x).iter().zip(&self.scaled).map(|(p, c)| p * c).sum()
+    }
+
+    /// Confidence interval of the fitted mean at `x` for two-sided `level`
+    /// (such as 0.95)
+    pub fn confidence(&self, x: f64, level: f64) -> (f64, f64) {
+        let y = self.predict(x);
+        if self.dof == 0 {
+            return (y, y);
+        }
+        let row = self.powers(x);
+        let leverage: f64 = (0..row.len())
+            .map(|i| row[i] * (0..row.len()).map(|j| self.inverse[i][j] * row[j]).sum::<f64>())
+            .sum();
+        let half = t_quantile(0.5 + level / 2.0, self.dof as f64) * self.residual_std * leverage.max(0.0).sqrt();
+        (y - half, y + half)
+    }
+
+    /// The fit sampled at `n` points from `lo` to `hi`, with its band
+    pub fn curve(&self, lo: f64, hi: f64, n: usize, level: f64) -> RegressionCurve {
+        let x = linspace(lo, hi, n);
+        let y = x.iter().map(|&x| self.predict(x)).collect();
+        let (lower, upper) = x.iter().map(|&x| self.confidence(x, level)).unzip();
+        RegressionCurve { x, y, lower, upper }
+    }
+}
+
+/// Inverse of a small symmetric matrix by Gauss-Jordan elimination with
+/// partial pivoting; `None` when (numerically) singular
+fn invert(mut a: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let n = a.len();
+    let mut inv: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect();
+    let size = a.iter().flatten().fold(0.0f64, |m, v| m.max(v.abs()));
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() <= 1e-12 * size {
+            return None;
+        }
+        a.swap(col, pivot);
+        inv.swap(col, pivot);
+        let p = a[col][col];
+        for j in 0..n {
+            a[col][j] /= p;
+            inv[col][j] /= p;
+        }
+        for row in 0..n {
+            if row != col {
+                let f = a[row][col];
+                if f != 0.0 {
+                    for j in 0..n {
+                        a[row][j] -= f * a[col][j];
+                        inv[row][j] -= f * inv[col][j];
+                    }
+                }
+            }
+        }
+    }
+    Some(inv)
+}
+
+/// LOWESS smoother: at each sample, a straight line fitted to the nearest
+/// `frac` of the data with tricube weights, refitted `iterations` times with
+/// weights that discount outliers. Returns the smoothed value at every
+/// sample (sorted by x) with a `level` confidence band from the smoother's
+/// effective degrees of freedom.
+pub fn lowess(x: &[f64], y: &[f64], frac: f64, iterations: usize, level: f64) -> RegressionCurve {
+    let mut pairs: Vec<(f64, f64)> = x.iter().zip(y).map(|(&x, &y)| (x, y)).filter(|(x, y)| x.is_finite() && y.is_finite()).collect();
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let n = pairs.len();
+    if n == 0 {
+        return RegressionCurve::default();
+    }
+    let xs: Vec<f64> = pairs.iter().map(|p| p.0).collect();
+    let ys: Vec<f64> = pairs.iter().map(|p| p.1).collect();
+    let k = ((frac * n as f64).ceil() as usize).clamp(2.min(n), n);
+
+    // Window of the k nearest samples around each one
+    let mut windows = Vec::with_capacity(n);
+    let mut left = 0;
+    for i in 0..n {
+        while left + k < n && xs[i] - xs[left] > xs[left + k] - xs[i] {
+            left += 1;
+        }
+        windows.push(left..left + k);
+    }
+
+    let mut robustness = vec![1.0; n];
+    let mut fitted = vec![0.0; n];
+    // Per sample: the smoother weights applied to y, as (first index, weights)
+    let mut rows: Vec<Vec<f64>> = vec![Vec::new(); n];
+    for pass in 0..=iterations {
+        for i in 0..n {
+            let window = windows[i].clone();
+            let reach = (xs[i] - xs[window.start]).max(xs[window.end - 1] - xs[i]) * 1.000001;
+            let w: Vec<f64> = window.clone().map(|j| {
+                let d = if reach > 0.0 { (xs[j] - xs[i]).abs() / reach } else { 0.0 };
+                (1.0 - d * d * d).max(0.0).powi(3) * robustness[j]
+            }).collect();
+            let sw: f64 = w.iter().sum();
+            if sw <= 0.0 {
+                rows[i] = window.clone().map(|j| if j == i { 1.0 } else { 0.0 }).collect();
+            } else {
+                let xm = window.clone().zip(&w).map(|(j, w)| w * xs[j]).sum::<f64>() / sw;
+                let sxx: f64 = window.clone().zip(&w).map(|(j, w)| w * (xs[j] - xm).powi(2)).sum();
+                let spread = xs[window.end - 1] - xs[window.start];
+                let slope_ok = sxx > 1e-12 * sw * spread * spread && spread > 0.0;
+                rows[i] = window.clone().zip(&w).map(|(j, w)| {
+                    if slope_ok { w * (1.0 / sw + (xs[i] - xm) * (xs[j] - xm) / sxx) } else { w / sw }
+                }).collect();
+            }
+            fitted[i] = window.clone().zip(&rows[i]).map(|(j, l)| l * ys[j]).sum();
+        }
+        if pass == iterations {
+            break;
+        }
+        // Bisquare weights from the residuals, scaled by six median deviations
+        let residuals: Vec<f64> = (0..n).map(|i| ys[i] - fitted[i]).collect();
+        let scale = 6.0 * median(&residuals.iter().map(|r| r.abs()).collect::<Vec<_>>());
+        if scale <= 0.0 || !scale.is_finite() {
+            break;
+        }
+        for (r, weight) in residuals.iter().zip(&mut robustness) {
+            let u = r / scale;
+            *weight = if u.abs() < 1.0 { (1.0 - u * u).powi(2) } else { 0.0 };
+        }
+    }
+
+    // Residual variance over the smoother's residual degrees of freedom,
+    // n - 2 tr(L) + tr(LᵀL)
+    let trace: f64 = (0..n).map(|i| rows[i][i - windows[i].start]).sum();
+    let norms: Vec<f64> = rows.iter().map(|r| r.iter().map(|l| l * l).sum::<f64>()).collect();
+    let dof = n as f64 - 2.0 * trace + norms.iter().sum::<f64>();
+    let rss: f64 = (0..n).map(|i| (ys[i] - fitted[i]).powi(2)).sum();
+    let sigma = if dof > 0.0 { (rss / dof).sqrt() } else { 0.0 };
+    let t = if dof > 0.0 { t_quantile(0.5 + level / 2.0, dof) } else { 0.0 };
+
+    let half: Vec<f64> = norms.iter().map(|norm| t * sigma * norm.sqrt()).collect();
+    RegressionCurve {
+        lower: fitted.iter().zip(&half).map(|(f, h)| f - h).collect(),
+        upper: fitted.iter().zip(&half).map(|(f, h)| f + h).collect(),
+        x: xs,
+        y: fitted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KERNELS: [Kernel; 5] = [Kernel::Gaussian, Kernel::Epanechnikov, Kernel::Triangular, Kernel::Uniform, Kernel::Biweight];
+
+    /// Deterministic, skewed sample
+    fn sample(n: usize) -> Vec<f64> {
+        let mut state = 12345u64;
+        (0..n)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                let u = (state >> 11) as f64 / (1u64 << 53) as f64;
+                -u.ln() * 3.0 + 10.0
+            })
+            .collect()
+    }
+
+    /// Trapezoid rule over evenly spaced `xs`
+    fn integrate(xs: &[f64], ys: &[f64]) -> f64 {
+        xs.windows(2).zip(ys.windows(2)).map(|(x, y)| (x[1] - x[0]) * (y[0] + y[1]) / 2.0).sum()
+    }
+
+    #[test]
+    fn test_summary_statistics() {
+        let values = [4.0, f64::NAN, 1.0, 3.0, 2.0, f64::INFINITY];
+        assert_eq!(sorted_finite(&values), vec![1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(mean(&values), 2.5);
+        assert!((std_dev(&values) - (5.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(median(&values), 2.5);
+        assert_eq!(quantiles(&values, &[0.0, 0.25, 1.0]), vec![1.0, 1.75, 4.0]);
+        assert_eq!(iqr(&values), 1.5);
+        assert!(mean(&[]).is_nan() && std_dev(&[1.0]).is_nan() && median(&[]).is_nan());
+        assert_eq!(ecdf(&[3.0, 1.0, 3.0, 2.0]), vec![(1.0, 0.25), (2.0, 0.5), (3.0, 1.0)]);
+        assert_eq!(linspace(0.0, 1.0, 5), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(linspace(2.0, 3.0, 1), vec![2.0]);
+    }
+
+    #[test]
+    fn test_bin_counts_sum_to_input() {
+        let mut values = sample(500);
+        values.push(f64::NAN);
+        let finite = values.len() - 1;
+        for rule in [BinRule::Sturges, BinRule::Scott, BinRule::FreedmanDiaconis, BinRule::Count(7), BinRule::Width(0.75)] {
+            let edges = bin_edges(&values, rule);
+            assert!(edges.windows(2).all(|w| w[0] < w[1]), "{:?}", rule);
+            let counts = bin_counts(&values, &edges);
+            assert_eq!(counts.len(), edges.len() - 1);
+            assert_eq!(counts.iter().sum::<usize>(), finite, "{:?}", rule);
+        }
+        assert_eq!(BinRule::Sturges.bin_count(&values), 10);
+        assert_eq!(BinRule::Count(0).bin_count(&values), 1);
+    }
+
+    #[test]
+    fn test_bin_edges_inclusive() {
+        // Left edges are inside their bin, the last right edge too
+        let counts = bin_counts(&[0.0, 1.0, 1.5, 2.0, 3.0, -1.0], &[0.0, 1.0, 2.0]);
+        assert_eq!(counts, vec![1, 3]);
+        assert_eq!(bin_edges(&[5.0, 5.0], BinRule::Sturges), vec![4.5, 5.5]);
+        assert!(bin_edges(&[f64::NAN], BinRule::Sturges).is_empty());
+        assert!(bin_counts(&[1.0], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_kernels_have_unit_mass_and_variance() {
+        let us = linspace(-10.0, 10.0, 20001);
+        for kernel in KERNELS {
+            let density: Vec<f64> = us.iter().map(|&u| kernel.density(u)).collect();
+            let second: Vec<f64> = us.iter().zip(&density).map(|(u, d)| u * u * d).collect();
+            assert!((integrate(&us, &density) - 1.0).abs() < 1e-3, "{:?}", kernel);
+            assert!((integrate(&us, &second) - 1.0).abs() < 1e-3, "{:?}", kernel);
+        }
+    }
+
+    #[test]
+    fn test_kde_integrates_to_one() {
+        let values = sample(200);
+        let xs = linspace(-10.0, 60.0, 14001);
+        for kernel in KERNELS {
+            for bandwidth in [Bandwidth::Scott, Bandwidth::Silverman, Bandwidth::Fixed(0.3)] {
+                let bw = bandwidth.select(&values);
+                let density = kde(&values, &xs, bw, kernel);
+                assert!(density.iter().all(|&d| d >= 0.0));
+                let mass = integrate(&xs, &density);
+                assert!((mass - 1.0).abs() < 2e-3, "{:?} {:?}: {}", kernel, bandwidth, mass);
+            }
+        }
+        assert_eq!(kde(&[], &[1.0, 2.0], 1.0, Kernel::Gaussian), vec![0.0, 0.0]);
+        assert_eq!(Bandwidth::Scott.select(&[2.0, 2.0]), 1.0);
+    }
+
+    #[test]
+    fn test_polyfit_recovers_coefficients() {
+        // Far from the origin, where an unscaled fit loses precision
+        let x: Vec<f64> = linspace(100.0, 120.0, 21);
+        let y: Vec<f64> = x.iter().map(|x| 2.0 - 3.0 * x + 0.5 * x * x - 0.001 * x * x * x).collect();
+        let fit = polyfit(&x, &y, 3).unwrap();
+        for (c, expected) in fit.coefficients.iter().zip([2.0, -3.0, 0.5, -0.001]) {
+            assert!((c - expected).abs() < 1e-6 * expected.abs().max(1.0), "{:?}", fit.coefficients);
+        }
+        assert!((fit.r_squared - 1.0).abs() < 1e-12);
+        assert_eq!(fit.dof, 17);
+        assert!((fit.predict(110.0) - y[10]).abs() < 1e-6);
+
+        let line = linear_fit(&[0.0, 1.0, 2.0, f64::NAN], &[1.0, 3.0, 5.0, 0.0]).unwrap();
+        assert!((line.coefficients[0] - 1.0).abs() < 1e-12 && (line.coefficients[1] - 2.0).abs() < 1e-12);
+        assert!(polyfit(&[1.0, 2.0], &[1.0, 2.0], 2).is_none());
+        assert!(polyfit(&[1.0, 1.0, 1.0], &[1.0, 2.0, 3.0], 1).is_none());
+    }
+
+    #[test]
+    fn test_confidence_band() {
+        let x: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        let y: Vec<f64> = x.iter().map(|x| 1.0 + 0.5 * x + if (*x as i64) % 2 == 0 { 0.3 } else { -0.3 }).collect();
+        let fit = linear_fit(&x, &y).unwrap();
+        let curve = fit.curve(-5.0, 24.0, 30, 0.95);
+        let width: Vec<f64> = curve.upper.iter().zip(&curve.lower).map(|(u, l)| u - l).collect();
+        assert!(curve.lower.iter().zip(&curve.y).zip(&curve.upper).all(|((l, y), u)| l < y && y < u));
+        // Narrowest at the mean of x, wider towards the ends
+        let narrowest = width.iter().enumerate().min_by(|a, b| a.1.total_cmp(b.1)).unwrap().0;
+        assert!((curve.x[narrowest] - 9.5).abs() <= 0.5);
+        assert!(width[0] > width[narrowest] && width[29] > width[narrowest]);
+    }
+
+    #[test]
+    fn test_quantile_functions() {
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-6);
+        assert!((normal_quantile(0.001) + 3.090232).abs() < 1e-6);
+        assert_eq!(normal_quantile(0.5), 0.0);
+        assert!((t_quantile(0.975, 1.0) - 12.706205).abs() < 1e-5);
+        assert!((t_quantile(0.975, 2.0) - 4.302653).abs() < 1e-5);
+        assert!((t_quantile(0.975, 10.0) - 2.228139).abs() < 1e-3);
+        assert!((t_quantile(0.975, 100.0) - 1.983972).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_lowess() {
+        // Local lines reproduce a straight line exactly, even with robustness
+        let x: Vec<f64> = (0..30).map(|i| i as f64 * 0.5).collect();
+        let y: Vec<f64> = x.iter().map(|x| 3.0 - 2.0 * x).collect();
+        let curve = lowess(&x, &y, 0.3, 2, 0.95);
+        assert!(curve.y.iter().zip(&y).all(|(f, y)| (f - y).abs() < 1e-9));
+
+        // Robustness iterations discount a single outlier
+        let mut noisy = y.clone();
+        noisy[15] += 40.0;
+        let plain = lowess(&x, &noisy, 0.3, 0, 0.95);
+        let robust = lowess(&x, &noisy, 0.3, 3, 0.95);
+        assert!((robust.y[14] - y[14]).abs() < 0.1 * (plain.y[14] - y[14]).abs());
+
+        // Sorted by x, non-finite pairs dropped
+        let shuffled = lowess(&[2.0, 0.0, f64::NAN, 1.0], &[2.0, 0.0, 5.0, 1.0], 1.0, 0, 0.95);
+        assert_eq!(shuffled.x, vec![0.0, 1.0, 2.0]);
+        assert!(lowess(&[], &[], 0.5, 1, 0.95).x.is_empty());
+    }
+}
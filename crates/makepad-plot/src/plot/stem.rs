@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -41,13 +42,7 @@ pub struct StemPlot {
     series: Vec<Series>,
 
     #[rust]
-    plot_area: PlotArea,
-
-    #[rust]
-    x_range: (f64, f64),
-
-    #[rust]
-    y_range: (f64, f64),
+    axes: Axes,
 
     #[rust]
     title: String,
@@ -61,29 +56,17 @@ pub struct StemPlot {
     #[rust(0.0)]
     baseline: f64,
 
-    #[rust(true)]
-    show_grid: bool,
-
     #[rust(6.0)]
     marker_size: f64,
 
     #[rust(1.5)]
     stem_width: f64,
 
-    #[rust(50.0)]
-    left_margin: f64,
-
-    #[rust(30.0)]
-    bottom_margin: f64,
-
-    #[rust(20.0)]
-    right_margin: f64,
-
-    #[rust(30.0)]
-    top_margin: f64,
-
     #[rust]
     legend_position: LegendPosition,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for StemPlot {
@@ -93,19 +76,32 @@ impl Widget for StemPlot {
 
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.series.is_empty() {
-            self.update_plot_area(rect);
-            self.draw_grid(cx);
+            self.axes.update_plot_area(rect);
+            self.axes.x.show_grid = false;
+            self.axes.draw_grid(cx, &mut self.draw_line);
             self.draw_axes(cx);
             self.draw_stems(cx);
             self.draw_labels(cx);
             self.draw_legend(cx);
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 }
 
+impl PlotExport for StemPlot {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl StemPlot {
     pub fn add_series(&mut self, series: Series) {
         self.series.push(series);
@@ -129,11 +125,11 @@ impl StemPlot {
     }
 
     pub fn set_xlim(&mut self, min: f64, max: f64) {
-        self.x_range = (min, max);
+        self.axes.x.range = (min, max);
     }
 
     pub fn set_ylim(&mut self, min: f64, max: f64) {
-        self.y_range = (min, max);
+        self.axes.y.range = (min, max);
     }
 
     pub fn set_baseline(&mut self, baseline: f64) {
@@ -177,64 +173,22 @@ impl StemPlot {
         let x_pad = (x_max - x_min) * 0.05;
         let y_pad = (y_max - y_min) * 0.1;
 
-        self.x_range = (x_min - x_pad, x_max + x_pad);
-        self.y_range = (y_min - y_pad, y_max + y_pad);
-    }
-
-    fn update_plot_area(&mut self, rect: Rect) {
-        self.plot_area = PlotArea::new(
-            rect.pos.x + self.left_margin,
-            rect.pos.y + self.top_margin,
-            rect.pos.x + rect.size.x - self.right_margin,
-            rect.pos.y + rect.size.y - self.bottom_margin,
-        );
+        self.axes.x.range = (x_min - x_pad, x_max + x_pad);
+        self.axes.y.range = (y_min - y_pad, y_max + y_pad);
     }
 
     fn data_to_pixel(&self, x: f64, y: f64) -> DVec2 {
-        let x_norm = (x - self.x_range.0) / (self.x_range.1 - self.x_range.0);
-        let y_norm = (y - self.y_range.0) / (self.y_range.1 - self.y_range.0);
-
-        dvec2(
-            self.plot_area.left + x_norm * self.plot_area.width(),
-            self.plot_area.bottom - y_norm * self.plot_area.height(),
-        )
-    }
-
-    fn draw_grid(&mut self, cx: &mut Cx2d) {
-        if !self.show_grid {
-            return;
-        }
-
-        self.draw_line.color = vec4(0.9, 0.9, 0.9, 1.0);
-
-        // Horizontal grid lines
-        let y_step = (self.y_range.1 - self.y_range.0) / 5.0;
-        for i in 0..=5 {
-            let y = self.y_range.0 + i as f64 * y_step;
-            let p1 = self.data_to_pixel(self.x_range.0, y);
-            let p2 = self.data_to_pixel(self.x_range.1, y);
-            self.draw_line.draw_line(cx, p1, p2, 0.5);
-        }
+        self.axes.data_to_pixel(x, y)
     }
 
     fn draw_axes(&mut self, cx: &mut Cx2d) {
-        self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-
-        // X axis
-        let x1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-        let x2 = dvec2(self.plot_area.right, self.plot_area.bottom);
-        self.draw_line.draw_line(cx, x1, x2, 1.0);
-
-        // Y axis
-        let y1 = dvec2(self.plot_area.left, self.plot_area.bottom);
-        let y2 = dvec2(self.plot_area.left, self.plot_area.top);
-        self.draw_line.draw_line(cx, y1, y2, 1.0);
+        self.axes.draw_axes(cx, &mut self.draw_line);
 
         // Baseline (if different from y_range.0)
-        if self.baseline > self.y_range.0 && self.baseline < self.y_range.1 {
+        if self.baseline > self.axes.y.range.0 && self.baseline < self.axes.y.range.1 {
             self.draw_line.color = vec4(0.5, 0.5, 0.5, 0.5);
-            let p1 = self.data_to_pixel(self.x_range.0, self.baseline);
-            let p2 = self.data_to_pixel(self.x_range.1, self.baseline);
+            let p1 = self.data_to_pixel(self.axes.x.range.0, self.baseline);
+            let p2 = self.data_to_pixel(self.axes.x.range.1, self.baseline);
             self.draw_line.draw_line_styled(cx, p1, p2, 1.0, LineStyle::Dashed, 0.0);
         }
     }
@@ -269,30 +223,12 @@ impl StemPlot {
     }
 
     fn draw_labels(&mut self, cx: &mut Cx2d) {
-        self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
-
-        // X axis tick labels
-        let x_step = (self.x_range.1 - self.x_range.0) / 5.0;
-        for i in 0..=5 {
-            let x = self.x_range.0 + i as f64 * x_step;
-            let p = self.data_to_pixel(x, self.y_range.0);
-            let label = format!("{:.1}", x);
-            self.label.draw_at(cx, dvec2(p.x, p.y + 5.0), &label, TextAnchor::TopCenter);
-        }
-
-        // Y axis tick labels
-        let y_step = (self.y_range.1 - self.y_range.0) / 5.0;
-        for i in 0..=5 {
-            let y = self.y_range.0 + i as f64 * y_step;
-            let p = self.data_to_pixel(self.x_range.0, y);
-            let label = format!("{:.1}", y);
-            self.label.draw_at(cx, dvec2(p.x - 5.0, p.y), &label, TextAnchor::MiddleRight);
-        }
+        self.axes.draw_tick_labels(cx, &mut self.label);
 
         // Title
         if !self.title.is_empty() {
-            let center_x = (self.plot_area.left + self.plot_area.right) / 2.0;
-            self.label.draw_at(cx, dvec2(center_x, self.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
+            let center_x = (self.axes.plot_area.left + self.axes.plot_area.right) / 2.0;
+            self.label.draw_at(cx, dvec2(center_x, self.axes.plot_area.top - 10.0), &self.title, TextAnchor::BottomCenter);
         }
     }
 
@@ -303,10 +239,10 @@ impl StemPlot {
 
         // Calculate legend position
         let (legend_x, legend_y) = match self.legend_position {
-            LegendPosition::TopRight => (self.plot_area.right - 100.0, self.plot_area.top + 10.0),
-            LegendPosition::TopLeft => (self.plot_area.left + 10.0, self.plot_area.top + 10.0),
-            LegendPosition::BottomRight => (self.plot_area.right - 100.0, self.plot_area.bottom - 50.0),
-            LegendPosition::BottomLeft => (self.plot_area.left + 10.0, self.plot_area.bottom - 50.0),
+            LegendPosition::TopRight => (self.axes.plot_area.right - 100.0, self.axes.plot_area.top + 10.0),
+            LegendPosition::TopLeft => (self.axes.plot_area.left + 10.0, self.axes.plot_area.top + 10.0),
+            LegendPosition::BottomRight => (self.axes.plot_area.right - 100.0, self.axes.plot_area.bottom - 50.0),
+            LegendPosition::BottomLeft => (self.axes.plot_area.left + 10.0, self.axes.plot_area.bottom - 50.0),
             LegendPosition::None => return,
         };
 
@@ -416,12 +352,16 @@ pub struct ViolinPlot {
     #[rust] items: Vec<ViolinItem>,
     #[rust] show_box: bool,
     #[rust] show_median: bool,
-    #[rust] bandwidth: f64,
-    #[rust] plot_area: PlotArea,
+    #[rust] bandwidth: Bandwidth,
+    #[rust] kernel: Kernel,
+    #[rust] axes: Axes,
     #[live(40.0)] left_margin: f64,
     #[live(30.0)] right_margin: f64,
     #[live(30.0)] top_margin: f64,
     #[live(50.0)] bottom_margin: f64,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Widget for ViolinPlot {
@@ -431,15 +371,28 @@ impl Widget for ViolinPlot {
 
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
+        self.capture.begin(rect);
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.items.is_empty() {
-            self.update_plot_area(rect);
+            self.update_axes(rect);
+            self.axes.draw_grid(cx, &mut self.draw_line);
             self.draw_violins(cx);
             self.draw_labels(cx);
         }
+        self.capture.end();
         DrawStep::done()
     }
 }
 
+impl PlotExport for ViolinPlot {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl ViolinPlot {
     pub fn set_title(&mut self, title: impl Into<String>) { self.title = title.into(); }
     pub fn add_item(&mut self, item: ViolinItem) { self.items.push(item); }
@@ -448,11 +401,18 @@ impl ViolinPlot {
     }
     pub fn set_show_box(&mut self, show: bool) { self.show_box = show; }
     pub fn set_show_median(&mut self, show: bool) { self.show_median = show; }
+    /// Bandwidth rule of the density estimate, applied to all violins pooled
+    /// so their widths stay comparable
+    pub fn set_bandwidth(&mut self, bandwidth: Bandwidth) { self.bandwidth = bandwidth; }
+    pub fn set_kernel(&mut self, kernel: Kernel) { self.kernel = kernel; }
     pub fn clear(&mut self) { self.items.clear(); }
 
-    fn update_plot_area(&mut self, rect: Rect) {
-        self.plot_area = PlotArea::new(rect.pos.x + self.left_margin, rect.pos.y + self.top_margin,
-            rect.pos.x + rect.size.x - self.right_margin, rect.pos.y + rect.size.y - self.bottom_margin);
+    fn update_axes(&mut self, rect: Rect) {
+        self.axes.margins = PlotMargins { left: self.left_margin, top: self.top_margin, right: self.right_margin, bottom: self.bottom_margin };
+        self.axes.x.show_grid = false;
+        self.axes.x.show_labels = false;
+        self.axes.y.range = self.get_value_range();
+        self.axes.update_plot_area(rect);
     }
 
     fn get_value_range(&self) -> (f64, f64) {
@@ -462,34 +422,22 @@ impl ViolinPlot {
         (min - padding, max + padding)
     }
 
-    fn compute_kde(&self, values: &[f64], bw: f64, y_min: f64, y_max: f64, n: usize) -> Vec<(f64, f64)> {
-        let step = (y_max - y_min) / (n - 1) as f64;
-        (0..n).map(|i| {
-            let y = y_min + i as f64 * step;
-            let density: f64 = values.iter().map(|&v| (-(y - v).powi(2) / (2.0 * bw * bw)).exp()).sum();
-            (y, density / (values.len() as f64 * bw * (2.0 * std::f64::consts::PI).sqrt()))
-        }).collect()
-    }
-
     fn draw_violins(&mut self, cx: &mut Cx2d) {
         let n = self.items.len();
         if n == 0 { return; }
         let (y_min, y_max) = self.get_value_range();
-        let band_w = self.plot_area.width() / n as f64;
+        let band_w = self.axes.plot_area.width() / n as f64;
         let all: Vec<f64> = self.items.iter().flat_map(|i| i.values.iter().cloned()).collect();
-        let mean = all.iter().sum::<f64>() / all.len() as f64;
-        let std = (all.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / all.len() as f64).sqrt();
-        let bw = if self.bandwidth > 0.0 { self.bandwidth } else { 1.06 * std * (all.len() as f64).powf(-0.2) };
+        let bw = self.bandwidth.select(&all);
+        let at = linspace(y_min, y_max, 50);
 
-        self.draw_line.color = vec4(0.3, 0.3, 0.3, 1.0);
-        self.draw_line.draw_line(cx, dvec2(self.plot_area.left, self.plot_area.bottom), dvec2(self.plot_area.right, self.plot_area.bottom), 1.0);
-        self.draw_line.draw_line(cx, dvec2(self.plot_area.left, self.plot_area.bottom), dvec2(self.plot_area.left, self.plot_area.top), 1.0);
+        self.axes.draw_axes(cx, &mut self.draw_line);
 
         for (i, item) in self.items.iter().enumerate() {
             if item.values.is_empty() { continue; }
-            let x_c = self.plot_area.left + (i as f64 + 0.5) * band_w;
+            let x_c = self.axes.plot_area.left + (i as f64 + 0.5) * band_w;
             let max_w = band_w * 0.4;
-            let kde = self.compute_kde(&item.values, bw, y_min, y_max, 50);
+            let kde: Vec<(f64, f64)> = at.iter().copied().zip(kde(&item.values, &at, bw, self.kernel)).collect();
             let max_d = kde.iter().map(|(_, d)| *d).fold(0.0f64, f64::max);
             if max_d <= 0.0 { continue; }
             let color = item.color.unwrap_or_else(|| get_color(i));
@@ -497,31 +445,31 @@ impl ViolinPlot {
 
             for j in 0..kde.len() - 1 {
                 let (y1, d1) = kde[j]; let (y2, d2) = kde[j + 1];
-                let py1 = self.plot_area.bottom - (y1 - y_min) / (y_max - y_min) * self.plot_area.height();
-                let py2 = self.plot_area.bottom - (y2 - y_min) / (y_max - y_min) * self.plot_area.height();
+                let py1 = self.axes.y_to_pixel(y1);
+                let py2 = self.axes.y_to_pixel(y2);
                 let w = ((d1 + d2) / 2.0) / max_d * max_w;
-                self.draw_fill.draw_abs(cx, Rect { pos: dvec2(x_c - w, py2.min(py1)), size: dvec2(w * 2.0, (py1 - py2).abs()) });
+                self.draw_fill.draw_rect(cx, Rect { pos: dvec2(x_c - w, py2.min(py1)), size: dvec2(w * 2.0, (py1 - py2).abs()) });
             }
 
             self.draw_line.color = color;
             for j in 0..kde.len() - 1 {
                 let (y1, d1) = kde[j]; let (y2, d2) = kde[j + 1];
-                let py1 = self.plot_area.bottom - (y1 - y_min) / (y_max - y_min) * self.plot_area.height();
-                let py2 = self.plot_area.bottom - (y2 - y_min) / (y_max - y_min) * self.plot_area.height();
+                let py1 = self.axes.y_to_pixel(y1);
+                let py2 = self.axes.y_to_pixel(y2);
                 let w1 = d1 / max_d * max_w; let w2 = d2 / max_d * max_w;
                 self.draw_line.draw_line(cx, dvec2(x_c - w1, py1), dvec2(x_c - w2, py2), 1.5);
                 self.draw_line.draw_line(cx, dvec2(x_c + w1, py1), dvec2(x_c + w2, py2), 1.5);
             }
 
             if self.show_box {
-                let mut s = item.values.clone(); s.sort_by(|a, b| a.partial_cmp(b).unwrap());
-                let q1 = s[s.len() / 4]; let med = s[s.len() / 2]; let q3 = s[3 * s.len() / 4];
-                let py_q1 = self.plot_area.bottom - (q1 - y_min) / (y_max - y_min) * self.plot_area.height();
-                let py_m = self.plot_area.bottom - (med - y_min) / (y_max - y_min) * self.plot_area.height();
-                let py_q3 = self.plot_area.bottom - (q3 - y_min) / (y_max - y_min) * self.plot_area.height();
+                let s = sorted_finite(&item.values);
+                let q1 = quantile_sorted(&s, 0.25); let med = quantile_sorted(&s, 0.5); let q3 = quantile_sorted(&s, 0.75);
+                let py_q1 = self.axes.y_to_pixel(q1);
+                let py_m = self.axes.y_to_pixel(med);
+                let py_q3 = self.axes.y_to_pixel(q3);
                 let bw = max_w * 0.15;
                 self.draw_fill.color = vec4(0.3, 0.3, 0.3, 0.8);
-                self.draw_fill.draw_abs(cx, Rect { pos: dvec2(x_c - bw, py_q3), size: dvec2(bw * 2.0, py_q1 - py_q3) });
+                self.draw_fill.draw_rect(cx, Rect { pos: dvec2(x_c - bw, py_q3), size: dvec2(bw * 2.0, py_q1 - py_q3) });
                 self.draw_line.color = vec4(1.0, 1.0, 1.0, 1.0);
                 self.draw_line.draw_line(cx, dvec2(x_c - bw, py_m), dvec2(x_c + bw, py_m), 2.0);
             }
@@ -529,15 +477,15 @@ impl ViolinPlot {
     }
 
     fn draw_labels(&mut self, cx: &mut Cx2d) {
-        self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
+        self.axes.draw_tick_labels(cx, &mut self.label);
         if !self.title.is_empty() {
-            self.label.draw_at(cx, dvec2((self.plot_area.left + self.plot_area.right) / 2.0, self.plot_area.top - 15.0), &self.title, TextAnchor::Center);
+            self.label.draw_at(cx, dvec2((self.axes.plot_area.left + self.axes.plot_area.right) / 2.0, self.axes.plot_area.top - 15.0), &self.title, TextAnchor::Center);
         }
         let n = self.items.len();
-        let band_w = self.plot_area.width() / n.max(1) as f64;
+        let band_w = self.axes.plot_area.width() / n.max(1) as f64;
         for (i, item) in self.items.iter().enumerate() {
-            let x = self.plot_area.left + (i as f64 + 0.5) * band_w;
-            self.label.draw_at(cx, dvec2(x, self.plot_area.bottom + 15.0), &item.label, TextAnchor::Center);
+            let x = self.axes.plot_area.left + (i as f64 + 0.5) * band_w;
+            self.label.draw_at(cx, dvec2(x, self.axes.plot_area.bottom + 15.0), &item.label, TextAnchor::Center);
         }
     }
 }
@@ -546,6 +494,8 @@ impl ViolinPlotRef {
     pub fn set_title(&self, title: impl Into<String>) { if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); } }
     pub fn add_from_values(&self, label: impl Into<String>, values: &[f64]) { if let Some(mut inner) = self.borrow_mut() { inner.add_from_values(label, values); } }
     pub fn set_show_box(&self, show: bool) { if let Some(mut inner) = self.borrow_mut() { inner.set_show_box(show); } }
+    pub fn set_bandwidth(&self, bandwidth: Bandwidth) { if let Some(mut inner) = self.borrow_mut() { inner.set_bandwidth(bandwidth); } }
+    pub fn set_kernel(&self, kernel: Kernel) { if let Some(mut inner) = self.borrow_mut() { inner.set_kernel(kernel); } }
     pub fn clear(&self) { if let Some(mut inner) = self.borrow_mut() { inner.clear(); } }
     pub fn redraw(&self, cx: &mut Cx) { if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); } }
 }
@@ -0,0 +1,298 @@
+// Real-time streaming: fixed-capacity point buffers and auto-scrolling axes
+//
+// Live plots push new samples onto a `StreamBuffer` instead of rebuilding
+// their series. The buffer keeps the newest `capacity` points in contiguous
+// storage, compacting only once the dropped prefix reaches the capacity, so
+// pushes cost amortized O(1) and drawing still reads plain slices.
+// `StreamAxes` turns the buffered data into axis ranges each push: a sliding
+// or following x window and a y range that only moves when the data leaves
+// it (or shrinks well inside it), so the axis does not jitter every frame.
+
+use makepad_widgets::*;
+
+/// Points buffered when a non-streaming series first receives a push
+pub const DEFAULT_STREAM_CAPACITY: usize = 10_000;
+
+/// Fixed-capacity x/y ring of the newest points
+#[derive(Clone, Debug, Default)]
+pub struct StreamBuffer {
+    capacity: usize,
+    start: usize,
+    x: Vec<f64>,
+    y: Vec<f64>,
+    pushed: u64,
+}
+
+impl StreamBuffer {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            start: 0,
+            x: Vec::with_capacity(capacity * 2),
+            y: Vec::with_capacity(capacity * 2),
+            pushed: 0,
+        }
+    }
+
+    /// Buffer holding the last `capacity` of the given points
+    pub fn from_points(capacity: usize, points: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        let mut buffer = Self::new(capacity);
+        for (x, y) in points {
+            buffer.push(x, y);
+        }
+        buffer
+    }
+
+    pub fn push(&mut self, x: f64, y: f64) {
+        self.x.push(x);
+        self.y.push(y);
+        self.pushed += 1;
+        if self.x.len() - self.start > self.capacity {
+            self.start += 1;
+        }
+        // Drop the stale prefix in one move once it is as long as the window
+        if self.start >= self.capacity {
+            self.x.drain(..self.start);
+            self.y.drain(..self.start);
+            self.start = 0;
+        }
+    }
+
+    pub fn extend(&mut self, points: &[(f64, f64)]) {
+        for &(x, y) in points {
+            self.push(x, y);
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len() - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Buffered x values, oldest first
+    pub fn x(&self) -> &[f64] {
+        &self.x[self.start..]
+    }
+
+    /// Buffered y values, oldest first
+    pub fn y(&self) -> &[f64] {
+        &self.y[self.start..]
+    }
+
+    /// Total points ever pushed; changes whenever the contents do
+    pub fn pushed(&self) -> u64 {
+        self.pushed
+    }
+
+    pub fn clear(&mut self) {
+        self.x.clear();
+        self.y.clear();
+        self.start = 0;
+        self.pushed += 1;
+    }
+}
+
+/// How the x axis follows streamed data
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StreamWindow {
+    /// Span every buffered point
+    #[default]
+    All,
+    /// Fixed-width window ending at the newest x
+    Sliding(f64),
+    /// Keep the current span (as zoomed) and scroll it to the newest x
+    FollowLatest,
+}
+
+/// How the y axis follows the visible streamed data
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StreamYScale {
+    /// Leave the y range alone
+    Fixed,
+    /// Refit to the visible points on every push
+    #[default]
+    Fit,
+    /// Grow as soon as data leaves the range, adding `headroom` (a fraction
+    /// of the data span) on each side; shrink only once the data covers less
+    /// than `shrink_below` of the range
+    Hysteresis { headroom: f64, shrink_below: f64 },
+}
+
+impl StreamYScale {
+    /// Hysteresis with 10% headroom that shrinks below half coverage
+    pub fn hysteresis() -> Self {
+        StreamYScale::Hysteresis { headroom: 0.1, shrink_below: 0.5 }
+    }
+}
+
+/// Axis-following settings of a streaming plot, plus whether new points
+/// arrived since the last redraw
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StreamAxes {
+    pub window: StreamWindow,
+    pub y_scale: StreamYScale,
+    dirty: bool,
+}
+
+impl StreamAxes {
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// True once after each batch of pushes
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// X range for data spanning `data` (oldest, newest) given the current range
+    pub fn x_range(&self, current: (f64, f64), data: (f64, f64)) -> (f64, f64) {
+        let (oldest, newest) = data;
+        match self.window {
+            StreamWindow::All if newest > oldest => (oldest, newest),
+            StreamWindow::All => (oldest - 1.0, newest + 1.0),
+            StreamWindow::Sliding(width) => (newest - width.abs().max(f64::EPSILON), newest),
+            StreamWindow::FollowLatest => {
+                let span = (current.1 - current.0).abs();
+                let span = if span > 0.0 { span } else { (newest - oldest).max(1.0) };
+                (newest - span, newest)
+            }
+        }
+    }
+
+    /// Y range for visible data spanning `data` (min, max) given the current range
+    pub fn y_range(&self, current: (f64, f64), data: (f64, f64)) -> (f64, f64) {
+        let (min, max) = data;
+        if min > max {
+            return current;
+        }
+        let span = if max > min { max - min } else { min.abs().max(1.0) };
+        match self.y_scale {
+            StreamYScale::Fixed => current,
+            StreamYScale::Fit => (min - span * 0.05, max + span * 0.05),
+            StreamYScale::Hysteresis { headroom, shrink_below } => {
+                let (lo, hi) = (current.0.min(current.1), current.0.max(current.1));
+                let outside = min < lo || max > hi;
+                let sparse = (max - min) < (hi - lo) * shrink_below;
+                if outside || sparse || hi <= lo {
+                    (min - span * headroom, max + span * headroom)
+                } else {
+                    current
+                }
+            }
+        }
+    }
+}
+
+/// Part of the pixel segment `a`-`b` between the `left` and `right` edges,
+/// for lines that run off the side of a scrolling window
+pub fn clip_segment_x(a: DVec2, b: DVec2, left: f64, right: f64) -> Option<(DVec2, DVec2)> {
+    if a.x == b.x {
+        return (a.x >= left && a.x <= right).then_some((a, b));
+    }
+    let t_left = (left - a.x) / (b.x - a.x);
+    let t_right = (right - a.x) / (b.x - a.x);
+    let t0 = t_left.min(t_right).max(0.0);
+    let t1 = t_left.max(t_right).min(1.0);
+    (t0 <= t1).then(|| (a + (b - a) * t0, a + (b - a) * t1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_order_survives_wraparound() {
+        let mut buffer = StreamBuffer::new(5);
+        // Several compactions and a partial one
+        for i in 0..23 {
+            buffer.push(i as f64, -(i as f64));
+            let len = (i + 1).min(5);
+            assert_eq!(buffer.len(), len);
+            let oldest = i + 1 - len;
+            assert_eq!(buffer.x(), (oldest..=i).map(|v| v as f64).collect::<Vec<_>>());
+            assert_eq!(buffer.y(), (oldest..=i).map(|v| -(v as f64)).collect::<Vec<_>>());
+        }
+        assert_eq!(buffer.pushed(), 23);
+        assert_eq!(buffer.capacity(), 5);
+        // Storage never grows past twice the capacity
+        assert!(buffer.x.len() < 10);
+    }
+
+    #[test]
+    fn test_buffer_from_points_and_clear() {
+        let mut buffer = StreamBuffer::from_points(3, (0..10).map(|i| (i as f64, i as f64 * 2.0)));
+        assert_eq!(buffer.x(), &[7.0, 8.0, 9.0]);
+        assert_eq!(buffer.y(), &[14.0, 16.0, 18.0]);
+        buffer.extend(&[(10.0, 20.0)]);
+        assert_eq!(buffer.x(), &[8.0, 9.0, 10.0]);
+
+        let pushed = buffer.pushed();
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert_ne!(buffer.pushed(), pushed);
+        buffer.push(1.0, 1.0);
+        assert_eq!(buffer.x(), &[1.0]);
+
+        // Zero capacity still keeps the newest point
+        let mut single = StreamBuffer::new(0);
+        single.push(1.0, 1.0);
+        single.push(2.0, 2.0);
+        assert_eq!(single.x(), &[2.0]);
+    }
+
+    #[test]
+    fn test_x_range() {
+        let axes = |window| StreamAxes { window, ..StreamAxes::default() };
+        assert_eq!(axes(StreamWindow::All).x_range((0.0, 1.0), (3.0, 9.0)), (3.0, 9.0));
+        assert_eq!(axes(StreamWindow::All).x_range((0.0, 1.0), (3.0, 3.0)), (2.0, 4.0));
+        assert_eq!(axes(StreamWindow::Sliding(5.0)).x_range((0.0, 1.0), (3.0, 20.0)), (15.0, 20.0));
+        assert_eq!(axes(StreamWindow::FollowLatest).x_range((0.0, 4.0), (3.0, 20.0)), (16.0, 20.0));
+        assert_eq!(axes(StreamWindow::FollowLatest).x_range((0.0, 0.0), (3.0, 20.0)), (3.0, 20.0));
+    }
+
+    #[test]
+    fn test_y_range() {
+        let axes = |y_scale| StreamAxes { y_scale, ..StreamAxes::default() };
+        assert_eq!(axes(StreamYScale::Fixed).y_range((0.0, 1.0), (5.0, 9.0)), (0.0, 1.0));
+        assert_eq!(axes(StreamYScale::Fit).y_range((0.0, 1.0), (0.0, 10.0)), (-0.5, 10.5));
+        // Empty data keeps the range
+        assert_eq!(axes(StreamYScale::Fit).y_range((0.0, 1.0), (f64::MAX, f64::MIN)), (0.0, 1.0));
+
+        let hysteresis = axes(StreamYScale::hysteresis());
+        // Inside and covering most of the range: no change
+        assert_eq!(hysteresis.y_range((0.0, 10.0), (2.0, 9.0)), (0.0, 10.0));
+        // Leaving the range grows it with headroom
+        assert_eq!(hysteresis.y_range((0.0, 10.0), (0.0, 20.0)), (-2.0, 22.0));
+        // Shrinking well inside refits
+        assert_eq!(hysteresis.y_range((0.0, 10.0), (4.0, 6.0)), (3.8, 6.2));
+    }
+
+    #[test]
+    fn test_dirty_flag() {
+        let mut axes = StreamAxes::default();
+        assert!(!axes.take_dirty());
+        axes.mark_dirty();
+        assert!(axes.take_dirty());
+        assert!(!axes.take_dirty());
+    }
+
+    #[test]
+    fn test_clip_segment_x() {
+        let clipped = clip_segment_x(dvec2(-10.0, 0.0), dvec2(10.0, 20.0), 0.0, 5.0).unwrap();
+        assert_eq!(clipped, (dvec2(0.0, 10.0), dvec2(5.0, 15.0)));
+        // Reversed direction clips the same span
+        let reversed = clip_segment_x(dvec2(10.0, 20.0), dvec2(-10.0, 0.0), 0.0, 5.0).unwrap();
+        assert_eq!(reversed, (dvec2(5.0, 15.0), dvec2(0.0, 10.0)));
+        assert_eq!(clip_segment_x(dvec2(-10.0, 0.0), dvec2(-5.0, 0.0), 0.0, 5.0), None);
+        assert!(clip_segment_x(dvec2(2.0, 0.0), dvec2(2.0, 9.0), 0.0, 5.0).is_some());
+        assert_eq!(clip_segment_x(dvec2(7.0, 0.0), dvec2(7.0, 9.0), 0.0, 5.0), None);
+    }
+}
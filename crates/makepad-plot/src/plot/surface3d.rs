@@ -1,6 +1,7 @@
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -16,6 +17,9 @@ live_design! {
     }
 }
 
+/// Room right of the box taken by the colorbar and its labels
+const COLORBAR_SPACE: f64 = 70.0;
+
 
 /// Camera/view settings for 3D plots
 #[derive(Clone, Debug, Default)]
@@ -71,6 +75,15 @@ impl View3D {
         let y2 = y1 * el.cos() - z1 * el.sin();
         y2
     }
+
+    /// Camera position in the coordinates `project` takes: the point where
+    /// the perspective divide reaches zero
+    pub fn eye(&self) -> Point3 {
+        let az = self.azimuth.to_radians();
+        let el = self.elevation.to_radians();
+        let r = self.distance + 2.0;
+        [-r * el.cos() * az.sin(), -r * el.cos() * az.cos(), r * el.sin()]
+    }
 }
 
 // =============================================================================
@@ -82,6 +95,11 @@ impl View3D {
 // - Click and drag vertically: tilt view (elevation)
 // - Scroll wheel: zoom in/out
 //
+// Surfaces are split into triangles with a lit color at every vertex and
+// drawn back to front (see mesh3d), inside a box with grid, ticks and axis
+// labels (see axes3d). A colorbar maps heights to colors, and contours can be
+// projected onto the floor.
+//
 // Supports multiple charts via per-chart state tracking: each chart_id gets
 // its own data, view angles, zoom, and drag state stored in a HashMap.
 //
@@ -95,6 +113,9 @@ impl View3D {
 pub struct Surface3DChart {
     pub title: String,
     pub z_data: Vec<Vec<f64>>,
+    /// Further height fields over the same grid, drawn with the first one
+    /// so that intersections come out right
+    pub extra_surfaces: Vec<Vec<Vec<f64>>>,
     pub x_range: (f64, f64),
     pub y_range: (f64, f64),
     pub z_range: (f64, f64),
@@ -102,6 +123,13 @@ pub struct Surface3DChart {
     pub colormap: Colormap,
     pub show_wireframe: bool,
     pub show_surface: bool,
+    pub light: Light3D,
+    /// Light the surface; when off it is colored by height alone
+    pub shading: bool,
+    pub show_colorbar: bool,
+    /// Number of contour levels projected onto the floor, 0 for none
+    pub floor_contours: usize,
+    pub axes: Axes3D,
     pub zoom: f64,
     pub drag_start: Option<DVec2>,
     pub start_azimuth: f64,
@@ -114,6 +142,7 @@ impl Default for Surface3DChart {
         Self {
             title: String::new(),
             z_data: Vec::new(),
+            extra_surfaces: Vec::new(),
             x_range: (0.0, 1.0),
             y_range: (0.0, 1.0),
             z_range: (0.0, 1.0),
@@ -121,6 +150,11 @@ impl Default for Surface3DChart {
             colormap: Colormap::default(),
             show_wireframe: true,
             show_surface: false,
+            light: Light3D::default(),
+            shading: true,
+            show_colorbar: true,
+            floor_contours: 0,
+            axes: Axes3D::new(),
             zoom: 1.0,
             drag_start: None,
             start_azimuth: 0.0,
@@ -131,26 +165,107 @@ impl Default for Surface3DChart {
 }
 
 impl Surface3DChart {
+    /// Replace all surfaces with `z[row][col]`; x follows the columns and y
+    /// the rows
     pub fn set_data(&mut self, z: Vec<Vec<f64>>) {
         if z.is_empty() || z[0].is_empty() { return; }
-        let mut z_min = f64::MAX;
-        let mut z_max = f64::MIN;
-        for row in &z {
-            for &val in row {
-                if val < z_min { z_min = val; }
-                if val > z_max { z_max = val; }
-            }
-        }
         self.x_range = (0.0, (z[0].len() - 1) as f64);
         self.y_range = (0.0, (z.len() - 1) as f64);
-        self.z_range = (z_min, z_max);
         self.z_data = z;
+        self.extra_surfaces.clear();
+        self.update_z_range();
+    }
+
+    /// Add another surface over the same grid as the first
+    pub fn add_surface(&mut self, z: Vec<Vec<f64>>) {
+        if z.is_empty() || z[0].is_empty() { return; }
+        self.extra_surfaces.push(z);
+        self.update_z_range();
+    }
+
+    pub fn set_axis_labels(&mut self, x: impl Into<String>, y: impl Into<String>, z: impl Into<String>) {
+        self.axes.set_labels(x, y, z);
+    }
+
+    fn surfaces(&self) -> impl Iterator<Item = &Vec<Vec<f64>>> {
+        std::iter::once(&self.z_data).chain(&self.extra_surfaces)
+    }
+
+    fn update_z_range(&mut self) {
+        let (z_min, z_max) = self.surfaces()
+            .flatten()
+            .flatten()
+            .filter(|v| v.is_finite())
+            .fold((f64::MAX, f64::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+        if z_min <= z_max {
+            self.z_range = (z_min, z_max);
+        }
     }
 
     fn normalize_z(&self, z: f64) -> f64 {
         if self.z_range.1 == self.z_range.0 { return 0.5; }
         (z - self.z_range.0) / (self.z_range.1 - self.z_range.0)
     }
+
+    /// Columns and rows every surface has
+    fn grid_size(&self) -> (usize, usize) {
+        let cols = self.surfaces().flatten().map(Vec::len).min().unwrap_or(0);
+        let rows = self.surfaces().map(Vec::len).min().unwrap_or(0);
+        (cols, rows)
+    }
+
+    fn grid_x(&self, cols: usize) -> Vec<f64> {
+        let (x0, x1) = self.x_range;
+        (0..cols).map(|j| x0 + (x1 - x0) * j as f64 / (cols - 1).max(1) as f64).collect()
+    }
+
+    fn grid_y(&self, rows: usize) -> Vec<f64> {
+        let (y0, y1) = self.y_range;
+        (0..rows).map(|i| y0 + (y1 - y0) * i as f64 / (rows - 1).max(1) as f64).collect()
+    }
+
+    /// Box coordinates of each grid point; missing heights are NaN
+    fn box_points(&self, z: &[Vec<f64>], axes: &Axes3D) -> Vec<Vec<Point3>> {
+        let (cols, rows) = self.grid_size();
+        let (xs, ys) = (self.grid_x(cols), self.grid_y(rows));
+        z.iter().zip(&ys).map(|(row, &y)| {
+            row.iter().zip(&xs).map(|(&v, &x)| {
+                let mut p = axes.to_box(x, y, v);
+                if !v.is_finite() { p[2] = f64::NAN; }
+                p
+            }).collect()
+        }).collect()
+    }
+
+    /// Two lit triangles per grid cell with all four corners present, keyed
+    /// by (row, col). Cell outlines are flagged for the wireframe.
+    fn cells(&self, z: &[Vec<f64>], axes: &Axes3D) -> Vec<(usize, usize, Vec<ShadedTriangle>)> {
+        let (cols, rows) = self.grid_size();
+        let points = self.box_points(z, axes);
+        let normals = grid_normals(&points);
+        let eye = axes.eye();
+        let color = |i: usize, j: usize| {
+            let base = self.colormap.sample(self.normalize_z(z[i][j]).clamp(0.0, 1.0));
+            if !self.shading { return base; }
+            let p = points[i][j];
+            self.light.shade(base, normals[i][j], [eye[0] - p[0], eye[1] - p[1], eye[2] - p[2]])
+        };
+
+        let mut cells = Vec::new();
+        for i in 0..rows.saturating_sub(1) {
+            for j in 0..cols.saturating_sub(1) {
+                let corners = [(i, j), (i, j + 1), (i + 1, j + 1), (i + 1, j)];
+                if corners.iter().any(|&(r, c)| !z[r][c].is_finite()) { continue; }
+                let [p00, p01, p11, p10] = corners.map(|(r, c)| points[r][c]);
+                let [c00, c01, c11, c10] = corners.map(|(r, c)| color(r, c));
+                cells.push((i, j, vec![
+                    ShadedTriangle::new([p00, p01, p11], [c00, c01, c11]).with_edges([true, true, false]),
+                    ShadedTriangle::new([p00, p11, p10], [c00, c11, c10]).with_edges([false, true, true]),
+                ]));
+            }
+        }
+        cells
+    }
 }
 
 /// Wrapper for HashMap to work with Makepad's #[rust] derive
@@ -164,66 +279,39 @@ pub struct Surface3D {
     #[deref] #[live] view: View,
     #[live] draw_fill: DrawPlotFill,
     #[live] draw_line: DrawPlotLine,
+    #[live] draw_triangle: DrawTriangle,
     #[live] label: PlotLabel,
-    // Legacy single-chart fields (used when no chart_id is provided)
-    #[rust] title: String,
-    #[rust] z_data: Vec<Vec<f64>>,
-    #[rust] x_range: (f64, f64),
-    #[rust] y_range: (f64, f64),
-    #[rust] z_range: (f64, f64),
-    #[rust] view3d: View3D,
-    #[rust] colormap: Colormap,
-    #[rust] show_wireframe: bool,
-    #[rust] show_surface: bool,
-    #[rust] zoom: f64,
-    #[rust] drag_start: Option<DVec2>,
-    #[rust] start_azimuth: f64,
-    #[rust] start_elevation: f64,
-    #[rust] hit_rect: Rect,
+    // Single-chart state (used when no chart_id is provided)
+    #[rust] single: Surface3DChart,
     // Multi-chart support: per-chart state keyed by chart component ID
     #[rust] charts: Surface3DCharts,
     // Which chart is currently being dragged (by chart_id)
     #[rust] active_drag_id: String,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Surface3D {
-    pub fn set_title(&mut self, title: impl Into<String>) { self.title = title.into(); }
-
-    pub fn set_data(&mut self, z: Vec<Vec<f64>>) {
-        if z.is_empty() || z[0].is_empty() { return; }
-
-        // Calculate z range
-        let mut z_min = f64::MAX;
-        let mut z_max = f64::MIN;
-        for row in &z {
-            for &val in row {
-                if val < z_min { z_min = val; }
-                if val > z_max { z_max = val; }
-            }
-        }
-
-        self.z_data = z;
-        self.z_range = (z_min, z_max);
-        self.x_range = (0.0, (self.z_data[0].len() - 1) as f64);
-        self.y_range = (0.0, (self.z_data.len() - 1) as f64);
-    }
-
-    pub fn set_x_range(&mut self, min: f64, max: f64) { self.x_range = (min, max); }
-    pub fn set_y_range(&mut self, min: f64, max: f64) { self.y_range = (min, max); }
-    pub fn set_view(&mut self, view: View3D) { self.view3d = view; }
-    pub fn set_azimuth(&mut self, az: f64) { self.view3d.azimuth = az; }
-    pub fn set_elevation(&mut self, el: f64) { self.view3d.elevation = el; }
-    pub fn set_colormap(&mut self, cm: Colormap) { self.colormap = cm; }
-    pub fn set_wireframe(&mut self, show: bool) { self.show_wireframe = show; }
-    pub fn set_surface(&mut self, show: bool) { self.show_surface = show; }
-    pub fn clear(&mut self) { self.z_data.clear(); }
+    pub fn set_title(&mut self, title: impl Into<String>) { self.single.title = title.into(); }
+    pub fn set_data(&mut self, z: Vec<Vec<f64>>) { self.single.set_data(z); }
+    pub fn add_surface(&mut self, z: Vec<Vec<f64>>) { self.single.add_surface(z); }
+    pub fn set_x_range(&mut self, min: f64, max: f64) { self.single.x_range = (min, max); }
+    pub fn set_y_range(&mut self, min: f64, max: f64) { self.single.y_range = (min, max); }
+    pub fn set_view(&mut self, view: View3D) { self.single.view3d = view; }
+    pub fn set_azimuth(&mut self, az: f64) { self.single.view3d.azimuth = az; }
+    pub fn set_elevation(&mut self, el: f64) { self.single.view3d.elevation = el; }
+    pub fn set_colormap(&mut self, cm: Colormap) { self.single.colormap = cm; }
+    pub fn set_wireframe(&mut self, show: bool) { self.single.show_wireframe = show; }
+    pub fn set_surface(&mut self, show: bool) { self.single.show_surface = show; }
+    pub fn set_light(&mut self, light: Light3D) { self.single.light = light; }
+    pub fn set_shading(&mut self, shading: bool) { self.single.shading = shading; }
+    pub fn set_colorbar(&mut self, show: bool) { self.single.show_colorbar = show; }
+    pub fn set_floor_contours(&mut self, levels: usize) { self.single.floor_contours = levels; }
+    pub fn set_axis_labels(&mut self, x: impl Into<String>, y: impl Into<String>, z: impl Into<String>) { self.single.set_axis_labels(x, y, z); }
+    pub fn clear(&mut self) { self.single.z_data.clear(); self.single.extra_surfaces.clear(); }
     pub fn redraw(&mut self, cx: &mut Cx) { self.view.redraw(cx); }
 
-    fn normalize_z(&self, z: f64) -> f64 {
-        if self.z_range.1 == self.z_range.0 { return 0.5; }
-        (z - self.z_range.0) / (self.z_range.1 - self.z_range.0)
-    }
-
     // ── Multi-chart API ──────────────────────────────────────────────
 
     /// Get or create a per-chart instance by ID. Preserves interactive state
@@ -236,135 +324,157 @@ impl Surface3D {
     pub fn draw_chart_instance(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk, chart_id: &str) -> DrawStep {
         let rect = cx.walk_turtle(walk);
 
-        // Store hit_rect in the per-chart state
-        if let Some(chart) = self.charts.map.get_mut(chart_id) {
+        // Take the chart out of the map while drawing so self.draw_* stay borrowable
+        if let Some(mut chart) = self.charts.map.remove(chart_id) {
             chart.hit_rect = rect;
+            self.draw_chart(cx, rect, &chart);
+            self.charts.map.insert(chart_id.to_string(), chart);
         }
 
-        // Get chart data (need to clone to avoid borrow issues with self.draw_*)
-        let chart_data = match self.charts.map.get(chart_id) {
-            Some(c) if !c.z_data.is_empty() && rect.size.x > 0.0 && rect.size.y > 0.0 => {
-                Some((
-                    c.z_data.clone(),
-                    c.x_range, c.y_range, c.z_range,
-                    c.view3d.clone(),
-                    c.colormap.clone(),
-                    c.show_wireframe, c.show_surface,
-                    c.zoom,
-                    c.title.clone(),
-                ))
-            }
-            _ => None,
+        DrawStep::done()
+    }
+
+    fn draw_chart(&mut self, cx: &mut Cx2d, rect: Rect, chart: &Surface3DChart) {
+        let (cols, rows) = chart.grid_size();
+        if rect.size.x <= 0.0 || rect.size.y <= 0.0 || cols < 2 || rows < 2 {
+            return;
+        }
+        let view3d = if chart.view3d.distance == 0.0 { View3D::new() } else { chart.view3d.clone() };
+        let zoom = if chart.zoom == 0.0 { 1.0 } else { chart.zoom };
+        let show_surface = chart.show_surface;
+        let show_colorbar = show_surface && chart.show_colorbar;
+
+        let mut box_rect = rect;
+        if show_colorbar {
+            box_rect.size.x = (rect.size.x - COLORBAR_SPACE).max(0.0);
+        }
+        let mut axes = chart.axes.clone();
+        axes.set_ranges(chart.x_range, chart.y_range, chart.z_range);
+        axes.layout(box_rect, &view3d, zoom);
+
+        axes.draw_back(cx, &mut self.draw_line);
+        // Seen from above the floor is behind everything; from below, in front
+        let floor_behind = axes.back_side(2) < 0.0;
+        if floor_behind {
+            self.draw_floor_contours(cx, &axes, chart);
+        }
+        if show_surface {
+            self.draw_mesh(cx, &axes, chart);
+        } else {
+            self.draw_wireframe(cx, &axes, chart);
+        }
+        if !floor_behind {
+            self.draw_floor_contours(cx, &axes, chart);
+        }
+        axes.draw_labels(cx, &mut self.label);
+
+        if show_colorbar {
+            self.draw_colorbar(cx, rect, box_rect.pos.x + box_rect.size.x, &axes, chart);
+        }
+
+        // Draw title
+        if !chart.title.is_empty() {
+            self.label.set_color(vec4(0.2, 0.2, 0.2, 1.0));
+            self.label.draw_at(cx, dvec2(rect.pos.x + 10.0, rect.pos.y + 5.0), &chart.title, TextAnchor::TopLeft);
+        }
+    }
+
+    /// Lit triangles back to front, each followed by its wireframe edges so
+    /// nearer cells hide the wires behind them
+    fn draw_mesh(&mut self, cx: &mut Cx2d, axes: &Axes3D, chart: &Surface3DChart) {
+        let eye = axes.eye();
+        let triangles = if chart.extra_surfaces.is_empty() {
+            // One height field: exact and linear-time cell order
+            let (cols, rows) = chart.grid_size();
+            let eye_cell = [(eye[0] + 1.0) * 0.5 * (cols - 1) as f64, (eye[1] + 1.0) * 0.5 * (rows - 1) as f64];
+            grid_depth_order(chart.cells(&chart.z_data, axes), eye_cell, eye)
+        } else {
+            let all = chart.surfaces()
+                .flat_map(|z| chart.cells(z, axes))
+                .flat_map(|(_, _, tris)| tris)
+                .collect();
+            depth_order(all, eye)
         };
 
-        if let Some((z_data, x_range, y_range, z_range, view3d, colormap, show_wireframe, show_surface, zoom, title)) = chart_data {
-            let cx_center = rect.pos.x + rect.size.x * 0.5;
-            let cy_center = rect.pos.y + rect.size.y * 0.5;
-            let zoom_val = if zoom == 0.0 { 1.0 } else { zoom };
-            let scale = rect.size.x.min(rect.size.y) * 0.35 * zoom_val;
-
-            let rows = z_data.len();
-            let cols = z_data[0].len();
-
-            let x_scale = 2.0 / (cols - 1).max(1) as f64;
-            let y_scale = 2.0 / (rows - 1).max(1) as f64;
-            let z_scale = if z_range.1 != z_range.0 { 1.5 / (z_range.1 - z_range.0) } else { 1.0 };
-            let z_offset = (z_range.0 + z_range.1) * 0.5;
-
-            let normalize_z = |z: f64| -> f64 {
-                if z_range.1 == z_range.0 { 0.5 } else { (z - z_range.0) / (z_range.1 - z_range.0) }
-            };
-
-            // Draw filled surface quads with depth sorting
-            if show_surface {
-                let mut quads: Vec<(f64, usize, usize, Vec4)> = Vec::new();
-                for i in 0..rows-1 {
-                    for j in 0..cols-1 {
-                        let avg_z = (z_data[i][j] + z_data[i+1][j] + z_data[i][j+1] + z_data[i+1][j+1]) * 0.25;
-                        let t = normalize_z(avg_z);
-                        let color = colormap.sample(t);
-                        let cx_q = (j as f64 + 0.5) * x_scale - 1.0;
-                        let cy_q = (i as f64 + 0.5) * y_scale - 1.0;
-                        let cz_q = (avg_z - z_offset) * z_scale;
-                        let depth = view3d.depth(cx_q, cy_q, cz_q);
-                        quads.push((depth, i, j, color));
-                    }
-                }
-                quads.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
-
-                for (_, i, j, color) in quads {
-                    let corners = [(j, i), (j+1, i), (j+1, i+1), (j, i+1)];
-                    let mut pts: Vec<DVec2> = Vec::new();
-                    for &(cj, ci) in &corners {
-                        let x = cj as f64 * x_scale - 1.0;
-                        let y = ci as f64 * y_scale - 1.0;
-                        let z = (z_data[ci][cj] - z_offset) * z_scale;
-                        let (sx, sy) = view3d.project(x, y, z);
-                        pts.push(dvec2(cx_center + sx * scale, cy_center - sy * scale));
+        self.draw_line.color = vec4(0.0, 0.0, 0.0, 0.35);
+        for tri in &triangles {
+            let screen = tri.points.map(|p| axes.project_box(p));
+            self.draw_triangle.draw_triangle_shaded(cx, screen, tri.colors);
+            if chart.show_wireframe {
+                for k in 0..3 {
+                    if tri.edges[k] {
+                        self.draw_line.draw_line(cx, screen[k], screen[(k + 1) % 3], 1.0);
                     }
-                    self.draw_fill.color = color;
-                    let min_x = pts.iter().map(|p| p.x).fold(f64::MAX, f64::min);
-                    let max_x = pts.iter().map(|p| p.x).fold(f64::MIN, f64::max);
-                    let min_y = pts.iter().map(|p| p.y).fold(f64::MAX, f64::min);
-                    let max_y = pts.iter().map(|p| p.y).fold(f64::MIN, f64::max);
-                    self.draw_fill.draw_abs(cx, Rect {
-                        pos: dvec2(min_x, min_y),
-                        size: dvec2(max_x - min_x + 1.0, max_y - min_y + 1.0),
-                    });
                 }
             }
+        }
+    }
 
-            // Draw wireframe
-            if show_wireframe {
-                let wire_color = if show_surface { vec4(0.0, 0.0, 0.0, 0.5) } else { vec4(0.2, 0.4, 0.8, 1.0) };
-                self.draw_line.color = wire_color;
-                for i in 0..rows-1 {
-                    for j in 0..cols-1 {
-                        let corners = [(j, i), (j+1, i), (j+1, i+1), (j, i+1), (j, i)];
-                        for k in 0..4 {
-                            let (j0, i0) = corners[k];
-                            let (j1, i1) = corners[k+1];
-                            let x0 = j0 as f64 * x_scale - 1.0;
-                            let y0 = i0 as f64 * y_scale - 1.0;
-                            let z0 = (z_data[i0][j0] - z_offset) * z_scale;
-                            let (sx0, sy0) = view3d.project(x0, y0, z0);
-                            let x1 = j1 as f64 * x_scale - 1.0;
-                            let y1 = i1 as f64 * y_scale - 1.0;
-                            let z1 = (z_data[i1][j1] - z_offset) * z_scale;
-                            let (sx1, sy1) = view3d.project(x1, y1, z1);
-                            self.draw_line.draw_line(cx,
-                                dvec2(cx_center + sx0 * scale, cy_center - sy0 * scale),
-                                dvec2(cx_center + sx1 * scale, cy_center - sy1 * scale),
-                                1.0);
+    /// Grid lines only, without hidden line removal
+    fn draw_wireframe(&mut self, cx: &mut Cx2d, axes: &Axes3D, chart: &Surface3DChart) {
+        self.draw_line.color = vec4(0.2, 0.4, 0.8, 1.0);
+        for z in chart.surfaces() {
+            let points = chart.box_points(z, axes);
+            for (i, row) in points.iter().enumerate() {
+                for (j, &p) in row.iter().enumerate() {
+                    let neighbours = [row.get(j + 1), points.get(i + 1).and_then(|next| next.get(j))];
+                    for &q in neighbours.into_iter().flatten() {
+                        if p[2].is_finite() && q[2].is_finite() {
+                            self.draw_line.draw_line(cx, axes.project_box(p), axes.project_box(q), 1.0);
                         }
                     }
                 }
             }
+        }
+    }
 
-            // Draw 3D axes
-            self.draw_line.color = vec4(0.5, 0.5, 0.5, 0.8);
-            let axis_len = 1.2;
-            let axes = [
-                ((-axis_len, 0.0, 0.0), (axis_len, 0.0, 0.0)),
-                ((0.0, -axis_len, 0.0), (0.0, axis_len, 0.0)),
-                ((0.0, 0.0, -axis_len * 0.5), (0.0, 0.0, axis_len)),
-            ];
-            for ((x0, y0, z0), (x1, y1, z1)) in axes {
-                let (sx0, sy0) = view3d.project(x0, y0, z0);
-                let (sx1, sy1) = view3d.project(x1, y1, z1);
-                self.draw_line.draw_line(cx,
-                    dvec2(cx_center + sx0 * scale, cy_center - sy0 * scale),
-                    dvec2(cx_center + sx1 * scale, cy_center - sy1 * scale),
-                    1.0);
+    /// Contour lines of the first surface, flattened onto the floor
+    fn draw_floor_contours(&mut self, cx: &mut Cx2d, axes: &Axes3D, chart: &Surface3DChart) {
+        if chart.floor_contours == 0 {
+            return;
+        }
+        let (cols, rows) = chart.grid_size();
+        let (xs, ys) = (chart.grid_x(cols), chart.grid_y(rows));
+        let grid = ContourGrid::new(&xs, &ys, &chart.z_data);
+        let floor = axes.floor();
+        for level in auto_levels(chart.z_range.0, chart.z_range.1, chart.floor_contours) {
+            self.draw_line.color = chart.colormap.sample(chart.normalize_z(level));
+            for line in isolines(&grid, level) {
+                let screen: Vec<DVec2> = line.iter().map(|p| {
+                    let mut q = axes.to_box(p.x, p.y, level);
+                    q[2] = floor;
+                    axes.project_box(q)
+                }).collect();
+                for pair in screen.windows(2) {
+                    self.draw_line.draw_line(cx, pair[0], pair[1], 1.0);
+                }
             }
+        }
+    }
 
-            // Draw title
-            if !title.is_empty() {
-                self.label.draw_at(cx, dvec2(rect.pos.x + 10.0, rect.pos.y + 5.0), &title, TextAnchor::TopLeft);
-            }
+    /// Height scale right of the box, ticked like the z axis
+    fn draw_colorbar(&mut self, cx: &mut Cx2d, rect: Rect, box_right: f64, axes: &Axes3D, chart: &Surface3DChart) {
+        let bar_width = 15.0;
+        let bar_x = box_right + 15.0;
+        let top = rect.pos.y + rect.size.y * 0.15;
+        let bottom = rect.pos.y + rect.size.y * 0.85;
+
+        let steps = 50;
+        let step_height = (bottom - top) / steps as f64;
+        for i in 0..steps {
+            self.draw_fill.color = chart.colormap.sample(1.0 - (i as f64 + 0.5) / steps as f64);
+            self.draw_fill.draw_rect(cx, Rect { pos: dvec2(bar_x, top + i as f64 * step_height), size: dvec2(bar_width, step_height + 0.5) });
         }
 
-        DrawStep::done()
+        self.draw_line.color = vec4(0.2, 0.2, 0.2, 0.8);
+        self.label.set_color(vec4(0.3, 0.3, 0.3, 1.0));
+        for tick in axes.z.ticks() {
+            let t = chart.normalize_z(tick);
+            if !(0.0..=1.0).contains(&t) { continue; }
+            let y = bottom - t * (bottom - top);
+            self.draw_line.draw_line(cx, dvec2(bar_x, y), dvec2(bar_x + bar_width, y), 1.0);
+            self.label.draw_at(cx, dvec2(bar_x + bar_width + 4.0, y), &axes.z.format(tick), TextAnchor::MiddleLeft);
+        }
     }
 
     /// Handle events for all chart instances (multi-chart drag support).
@@ -423,133 +533,14 @@ impl Surface3D {
 impl Widget for Surface3D {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         let rect = cx.walk_turtle(walk);
-        self.hit_rect = rect;  // Store for manual hit testing
-
-        if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.z_data.is_empty() {
-            // Initialize defaults
-            if self.view3d.distance == 0.0 { self.view3d = View3D::new(); }
-            if !self.show_wireframe && !self.show_surface { self.show_wireframe = true; }
-            if self.zoom == 0.0 { self.zoom = 1.0; }
-
-            let cx_center = rect.pos.x + rect.size.x * 0.5;
-            let cy_center = rect.pos.y + rect.size.y * 0.5;
-            let scale = rect.size.x.min(rect.size.y) * 0.35 * self.zoom;
-
-            let rows = self.z_data.len();
-            let cols = self.z_data[0].len();
-
-            // Normalize coordinates to [-1, 1] range
-            let x_scale = 2.0 / (cols - 1).max(1) as f64;
-            let y_scale = 2.0 / (rows - 1).max(1) as f64;
-            let z_scale = if self.z_range.1 != self.z_range.0 {
-                1.5 / (self.z_range.1 - self.z_range.0)
-            } else { 1.0 };
-            let z_offset = (self.z_range.0 + self.z_range.1) * 0.5;
-
-            // Draw filled surface quads with depth sorting (painter's algorithm)
-            if self.show_surface {
-                // Collect all quads with their depth for sorting
-                let mut quads: Vec<(f64, usize, usize, Vec4)> = Vec::new();
-                for i in 0..rows-1 {
-                    for j in 0..cols-1 {
-                        let avg_z = (self.z_data[i][j] + self.z_data[i+1][j] +
-                                     self.z_data[i][j+1] + self.z_data[i+1][j+1]) * 0.25;
-                        let t = self.normalize_z(avg_z);
-                        let color = self.colormap.sample(t);
-
-                        // Calculate center point for depth
-                        let cx_q = (j as f64 + 0.5) * x_scale - 1.0;
-                        let cy_q = (i as f64 + 0.5) * y_scale - 1.0;
-                        let cz_q = (avg_z - z_offset) * z_scale;
-                        let depth = self.view3d.depth(cx_q, cy_q, cz_q);
-
-                        quads.push((depth, i, j, color));
-                    }
-                }
+        self.capture.begin(rect);
+        self.single.hit_rect = rect;  // Store for manual hit testing
 
-                // Sort by depth (back to front)
-                quads.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
-
-                // Draw quads in sorted order
-                for (_, i, j, color) in quads {
-                    // Get projected corners
-                    let corners = [(j, i), (j+1, i), (j+1, i+1), (j, i+1)];
-                    let mut pts: Vec<DVec2> = Vec::new();
-                    for &(cj, ci) in &corners {
-                        let x = cj as f64 * x_scale - 1.0;
-                        let y = ci as f64 * y_scale - 1.0;
-                        let z = (self.z_data[ci][cj] - z_offset) * z_scale;
-                        let (sx, sy) = self.view3d.project(x, y, z);
-                        pts.push(dvec2(cx_center + sx * scale, cy_center - sy * scale));
-                    }
-
-                    // Draw filled quad as two triangles using lines
-                    self.draw_fill.color = color;
-                    let min_x = pts.iter().map(|p| p.x).fold(f64::MAX, f64::min);
-                    let max_x = pts.iter().map(|p| p.x).fold(f64::MIN, f64::max);
-                    let min_y = pts.iter().map(|p| p.y).fold(f64::MAX, f64::min);
-                    let max_y = pts.iter().map(|p| p.y).fold(f64::MIN, f64::max);
-                    self.draw_fill.draw_abs(cx, Rect {
-                        pos: dvec2(min_x, min_y),
-                        size: dvec2(max_x - min_x + 1.0, max_y - min_y + 1.0),
-                    });
-                }
-            }
-
-            // Draw wireframe
-            if self.show_wireframe {
-                let wire_color = if self.show_surface { vec4(0.0, 0.0, 0.0, 0.5) } else { vec4(0.2, 0.4, 0.8, 1.0) };
-                self.draw_line.color = wire_color;
-
-                for i in 0..rows-1 {
-                    for j in 0..cols-1 {
-                        let corners = [(j, i), (j+1, i), (j+1, i+1), (j, i+1), (j, i)];
-                        for k in 0..4 {
-                            let (j0, i0) = corners[k];
-                            let (j1, i1) = corners[k+1];
-
-                            let x0 = j0 as f64 * x_scale - 1.0;
-                            let y0 = i0 as f64 * y_scale - 1.0;
-                            let z0 = (self.z_data[i0][j0] - z_offset) * z_scale;
-                            let (sx0, sy0) = self.view3d.project(x0, y0, z0);
-
-                            let x1 = j1 as f64 * x_scale - 1.0;
-                            let y1 = i1 as f64 * y_scale - 1.0;
-                            let z1 = (self.z_data[i1][j1] - z_offset) * z_scale;
-                            let (sx1, sy1) = self.view3d.project(x1, y1, z1);
-
-                            self.draw_line.draw_line(cx,
-                                dvec2(cx_center + sx0 * scale, cy_center - sy0 * scale),
-                                dvec2(cx_center + sx1 * scale, cy_center - sy1 * scale),
-                                1.0);
-                        }
-                    }
-                }
-            }
-
-            // Draw 3D axes
-            self.draw_line.color = vec4(0.5, 0.5, 0.5, 0.8);
-            let axis_len = 1.2;
-            let axes = [
-                ((-axis_len, 0.0, 0.0), (axis_len, 0.0, 0.0)),
-                ((0.0, -axis_len, 0.0), (0.0, axis_len, 0.0)),
-                ((0.0, 0.0, -axis_len * 0.5), (0.0, 0.0, axis_len)),
-            ];
-            for ((x0, y0, z0), (x1, y1, z1)) in axes {
-                let (sx0, sy0) = self.view3d.project(x0, y0, z0);
-                let (sx1, sy1) = self.view3d.project(x1, y1, z1);
-                self.draw_line.draw_line(cx,
-                    dvec2(cx_center + sx0 * scale, cy_center - sy0 * scale),
-                    dvec2(cx_center + sx1 * scale, cy_center - sy1 * scale),
-                    1.0);
-            }
-
-            // Draw title
-            if !self.title.is_empty() {
-                self.label.draw_at(cx, dvec2(rect.pos.x + 10.0, rect.pos.y + 5.0), &self.title, TextAnchor::TopLeft);
-            }
-        }
+        let chart = std::mem::take(&mut self.single);
+        self.draw_chart(cx, rect, &chart);
+        self.single = chart;
 
+        self.capture.end();
         DrawStep::done()
     }
 
@@ -561,33 +552,33 @@ impl Widget for Surface3D {
         }
 
         // Legacy single-chart path (used when Surface3D is used standalone)
-        let r = self.hit_rect;
+        let r = self.single.hit_rect;
         if r.size.x <= 0.0 || r.size.y <= 0.0 { return; }
 
         match event {
             Event::MouseDown(me) => {
                 if r.contains(me.abs) {
-                    self.drag_start = Some(me.abs);
-                    self.start_azimuth = self.view3d.azimuth;
-                    self.start_elevation = self.view3d.elevation;
+                    self.single.drag_start = Some(me.abs);
+                    self.single.start_azimuth = self.single.view3d.azimuth;
+                    self.single.start_elevation = self.single.view3d.elevation;
                 }
             }
             Event::MouseMove(me) => {
-                if let Some(start) = self.drag_start {
+                if let Some(start) = self.single.drag_start {
                     let delta = me.abs - start;
-                    self.view3d.azimuth = self.start_azimuth + delta.x * 0.5;
-                    self.view3d.elevation = (self.start_elevation - delta.y * 0.5).clamp(-89.0, 89.0);
+                    self.single.view3d.azimuth = self.single.start_azimuth + delta.x * 0.5;
+                    self.single.view3d.elevation = (self.single.start_elevation - delta.y * 0.5).clamp(-89.0, 89.0);
                     cx.redraw_all();
                 }
             }
             Event::MouseUp(_) => {
-                self.drag_start = None;
+                self.single.drag_start = None;
             }
             Event::Scroll(se) => {
                 if r.contains(se.abs) {
-                    if self.zoom == 0.0 { self.zoom = 1.0; }
+                    if self.single.zoom == 0.0 { self.single.zoom = 1.0; }
                     let zoom_delta = 1.0 + se.scroll.y * 0.001;
-                    self.zoom = (self.zoom * zoom_delta).clamp(0.2, 5.0);
+                    self.single.zoom = (self.single.zoom * zoom_delta).clamp(0.2, 5.0);
                     cx.redraw_all();
                 }
             }
@@ -596,9 +587,20 @@ impl Widget for Surface3D {
     }
 }
 
+impl PlotExport for Surface3D {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
+    }
+}
+
 impl Surface3DRef {
     pub fn set_title(&self, title: impl Into<String>) { if let Some(mut inner) = self.borrow_mut() { inner.set_title(title); } }
     pub fn set_data(&self, z: Vec<Vec<f64>>) { if let Some(mut inner) = self.borrow_mut() { inner.set_data(z); } }
+    pub fn add_surface(&self, z: Vec<Vec<f64>>) { if let Some(mut inner) = self.borrow_mut() { inner.add_surface(z); } }
     pub fn set_x_range(&self, min: f64, max: f64) { if let Some(mut inner) = self.borrow_mut() { inner.set_x_range(min, max); } }
     pub fn set_y_range(&self, min: f64, max: f64) { if let Some(mut inner) = self.borrow_mut() { inner.set_y_range(min, max); } }
     pub fn set_view(&self, view: View3D) { if let Some(mut inner) = self.borrow_mut() { inner.set_view(view); } }
@@ -607,7 +609,114 @@ impl Surface3DRef {
     pub fn set_colormap(&self, cm: Colormap) { if let Some(mut inner) = self.borrow_mut() { inner.set_colormap(cm); } }
     pub fn set_wireframe(&self, show: bool) { if let Some(mut inner) = self.borrow_mut() { inner.set_wireframe(show); } }
     pub fn set_surface(&self, show: bool) { if let Some(mut inner) = self.borrow_mut() { inner.set_surface(show); } }
+    pub fn set_light(&self, light: Light3D) { if let Some(mut inner) = self.borrow_mut() { inner.set_light(light); } }
+    pub fn set_shading(&self, shading: bool) { if let Some(mut inner) = self.borrow_mut() { inner.set_shading(shading); } }
+    pub fn set_colorbar(&self, show: bool) { if let Some(mut inner) = self.borrow_mut() { inner.set_colorbar(show); } }
+    pub fn set_floor_contours(&self, levels: usize) { if let Some(mut inner) = self.borrow_mut() { inner.set_floor_contours(levels); } }
+    pub fn set_axis_labels(&self, x: impl Into<String>, y: impl Into<String>, z: impl Into<String>) { if let Some(mut inner) = self.borrow_mut() { inner.set_axis_labels(x, y, z); } }
     pub fn clear(&self) { if let Some(mut inner) = self.borrow_mut() { inner.clear(); } }
     pub fn redraw(&self, cx: &mut Cx) { if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); } }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Chart holding `z` and axes fitted to it, as `draw_chart` sets them up
+    fn chart_with_axes(z: Vec<Vec<f64>>) -> (Surface3DChart, Axes3D) {
+        let mut chart = Surface3DChart::default();
+        chart.set_data(z);
+        let mut axes = Axes3D::new();
+        axes.set_ranges(chart.x_range, chart.y_range, chart.z_range);
+        (chart, axes)
+    }
+
+    #[test]
+    fn test_set_data_ranges() {
+        let mut chart = Surface3DChart::default();
+        chart.set_data(vec![vec![1.0, f64::NAN, 3.0], vec![2.0, 5.0, 0.5]]);
+        assert_eq!(chart.x_range, (0.0, 2.0));
+        assert_eq!(chart.y_range, (0.0, 1.0));
+        assert_eq!(chart.z_range, (0.5, 5.0));
+
+        // Further surfaces widen the height range over the same grid
+        chart.add_surface(vec![vec![-1.0, 0.0, 0.0], vec![0.0, 0.0, 9.0]]);
+        assert_eq!(chart.z_range, (-1.0, 9.0));
+        assert_eq!(chart.grid_size(), (3, 2));
+        assert_eq!(chart.normalize_z(4.0), 0.5);
+
+        // Empty grids are ignored
+        chart.set_data(vec![]);
+        assert_eq!(chart.x_range, (0.0, 2.0));
+        assert_eq!(chart.extra_surfaces.len(), 1);
+
+        // A flat surface sits mid-range
+        chart.set_data(vec![vec![2.0, 2.0], vec![2.0, 2.0]]);
+        assert_eq!(chart.normalize_z(2.0), 0.5);
+    }
+
+    #[test]
+    fn test_grid_spacing() {
+        let mut chart = Surface3DChart { x_range: (10.0, 20.0), y_range: (0.0, 1.0), ..Default::default() };
+        assert_eq!(chart.grid_x(3), vec![10.0, 15.0, 20.0]);
+        assert_eq!(chart.grid_y(5), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+        chart.y_range = (4.0, 4.0);
+        assert_eq!(chart.grid_y(1), vec![4.0]);
+    }
+
+    #[test]
+    fn test_cells_skip_missing_corners() {
+        let (chart, axes) = chart_with_axes(vec![vec![0.0, 1.0, 2.0], vec![1.0, 2.0, 3.0], vec![2.0, 3.0, f64::NAN]]);
+        let cells = chart.cells(&chart.z_data, &axes);
+
+        // Four cells, minus the one touching the missing corner
+        let keys: Vec<(usize, usize)> = cells.iter().map(|(i, j, _)| (*i, *j)).collect();
+        assert_eq!(keys, vec![(0, 0), (0, 1), (1, 0)]);
+        for (_, _, triangles) in &cells {
+            // Two triangles per cell; the shared diagonal is no wireframe edge
+            assert_eq!(triangles.len(), 2);
+            assert_eq!(triangles[0].edges, [true, true, false]);
+            assert_eq!(triangles[1].edges, [false, true, true]);
+        }
+
+        // Grid corners land on the box: x and y span -1..1, z the box height
+        let [p00, p01, p11] = cells[0].2[0].points;
+        assert_eq!(p00, [-1.0, -1.0, -BOX_HALF_HEIGHT]);
+        // Height 1 of 0..3 is a third of the way up
+        assert_eq!([p01[0], p01[1]], [0.0, -1.0]);
+        assert!((p01[2] + BOX_HALF_HEIGHT / 3.0).abs() < 1e-12);
+        assert_eq!([p11[0], p11[1]], [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_unshaded_colors_follow_height() {
+        let (mut chart, axes) = chart_with_axes(vec![vec![0.0, 1.0], vec![1.0, 2.0]]);
+        chart.shading = false;
+        let flat = chart.cells(&chart.z_data, &axes);
+        assert_eq!(
+            flat[0].2[0].colors,
+            [chart.colormap.sample(0.0), chart.colormap.sample(0.5), chart.colormap.sample(1.0)]
+        );
+
+        // Lighting changes the vertex colors of the tilted cell
+        chart.shading = true;
+        let lit = chart.cells(&chart.z_data, &axes);
+        assert_ne!(lit[0].2[0].colors, flat[0].2[0].colors);
+    }
+
+    #[test]
+    fn test_view_projection() {
+        let front = View3D { azimuth: 0.0, elevation: 0.0, distance: 3.0 };
+        // Looking along +y, x and z map to the screen scaled by perspective
+        let (x, y) = front.project(1.0, 0.0, 0.5);
+        assert!((x - 0.6).abs() < 1e-12 && (y - 0.3).abs() < 1e-12);
+        // Nearer points are less deep and drawn larger
+        assert!(front.depth(0.0, -1.0, 0.0) < front.depth(0.0, 1.0, 0.0));
+        assert!(front.project(1.0, -1.0, 0.0).0 > front.project(1.0, 1.0, 0.0).0);
+
+        // The eye sits where the perspective divide reaches zero
+        let view = View3D::new();
+        let eye = view.eye();
+        assert!((view.depth(eye[0], eye[1], eye[2]) + view.distance + 2.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,738 @@
+// Calendar-aware time axes - timezones, tick locators and adaptive labels
+//
+// Time values are Unix timestamps in seconds (UTC). Ticks are placed on
+// calendar boundaries in the axis timezone: whole minutes and hours, local
+// midnight, Mondays, the first of a month, quarter or year.
+
+use super::axes::nice_number;
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 3600;
+const DAY: i64 = 86400;
+const WEEK: i64 = 7 * DAY;
+
+pub(crate) const MONTH_NAMES: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// Upper bound on generated ticks, in case of a degenerate range
+const MAX_TIME_TICKS: usize = 1000;
+
+// =============================================================================
+// Calendar arithmetic
+// =============================================================================
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Hinnant's algorithm)
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// `(year, month, day)` for days since 1970-01-01
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Day of the week, 0 = Sunday
+fn weekday(days: i64) -> i64 {
+    // 1970-01-01 was a Thursday
+    (days + 4).rem_euclid(7)
+}
+
+/// Day number of the `n`th (1-based) Sunday of a month
+fn nth_sunday(year: i64, month: u32, n: i64) -> i64 {
+    let first = days_from_civil(year, month, 1);
+    first + (7 - weekday(first)) % 7 + (n - 1) * 7
+}
+
+/// Day number of the last Sunday of a month
+fn last_sunday(year: i64, month: u32) -> i64 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    let last = days_from_civil(next_year, next_month, 1) - 1;
+    last - weekday(last)
+}
+
+// =============================================================================
+// Timezones
+// =============================================================================
+
+/// Daylight saving rules for the built-in zones
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DstRule {
+    None,
+    /// Second Sunday of March to first Sunday of November, 02:00 local
+    Us,
+    /// Last Sunday of March to last Sunday of October, 01:00 UTC
+    Eu,
+    /// First Sunday of October to first Sunday of April, 02:00 standard time
+    Au,
+}
+
+/// IANA zone from the built-in table
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IanaZone {
+    pub name: &'static str,
+    /// Standard offset east of UTC in seconds
+    pub std_offset: i32,
+    pub dst: DstRule,
+}
+
+const IANA_ZONES: &[IanaZone] = &[
+    IanaZone { name: "Europe/London", std_offset: 0, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Dublin", std_offset: 0, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Lisbon", std_offset: 0, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Amsterdam", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Berlin", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Brussels", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Copenhagen", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Madrid", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Oslo", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Paris", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Prague", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Rome", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Stockholm", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Vienna", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Warsaw", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Zurich", std_offset: 3600, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Athens", std_offset: 7200, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Bucharest", std_offset: 7200, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Helsinki", std_offset: 7200, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Kyiv", std_offset: 7200, dst: DstRule::Eu },
+    IanaZone { name: "Europe/Istanbul", std_offset: 10800, dst: DstRule::None },
+    IanaZone { name: "Europe/Moscow", std_offset: 10800, dst: DstRule::None },
+    IanaZone { name: "America/New_York", std_offset: -18000, dst: DstRule::Us },
+    IanaZone { name: "America/Toronto", std_offset: -18000, dst: DstRule::Us },
+    IanaZone { name: "America/Chicago", std_offset: -21600, dst: DstRule::Us },
+    IanaZone { name: "America/Denver", std_offset: -25200, dst: DstRule::Us },
+    IanaZone { name: "America/Phoenix", std_offset: -25200, dst: DstRule::None },
+    IanaZone { name: "America/Los_Angeles", std_offset: -28800, dst: DstRule::Us },
+    IanaZone { name: "America/Vancouver", std_offset: -28800, dst: DstRule::Us },
+    IanaZone { name: "America/Anchorage", std_offset: -32400, dst: DstRule::Us },
+    IanaZone { name: "America/Sao_Paulo", std_offset: -10800, dst: DstRule::None },
+    IanaZone { name: "Pacific/Honolulu", std_offset: -36000, dst: DstRule::None },
+    IanaZone { name: "Asia/Dubai", std_offset: 14400, dst: DstRule::None },
+    IanaZone { name: "Asia/Kolkata", std_offset: 19800, dst: DstRule::None },
+    IanaZone { name: "Asia/Shanghai", std_offset: 28800, dst: DstRule::None },
+    IanaZone { name: "Asia/Hong_Kong", std_offset: 28800, dst: DstRule::None },
+    IanaZone { name: "Asia/Singapore", std_offset: 28800, dst: DstRule::None },
+    IanaZone { name: "Asia/Taipei", std_offset: 28800, dst: DstRule::None },
+    IanaZone { name: "Asia/Seoul", std_offset: 32400, dst: DstRule::None },
+    IanaZone { name: "Asia/Tokyo", std_offset: 32400, dst: DstRule::None },
+    IanaZone { name: "Australia/Brisbane", std_offset: 36000, dst: DstRule::None },
+    IanaZone { name: "Australia/Melbourne", std_offset: 36000, dst: DstRule::Au },
+    IanaZone { name: "Australia/Sydney", std_offset: 36000, dst: DstRule::Au },
+];
+
+/// Timezone used to place and label time ticks
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TimeZone {
+    #[default]
+    Utc,
+    /// Fixed offset east of UTC in seconds
+    Fixed(i32),
+    /// Named zone with daylight saving
+    Iana(IanaZone),
+}
+
+impl TimeZone {
+    /// Parse `"UTC"`, an offset such as `"+05:30"`, `"-0800"` or `"UTC+2"`,
+    /// or an IANA name from the built-in table such as `"Europe/Berlin"`
+    pub fn parse(name: &str) -> Result<TimeZone, String> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("utc") || name.eq_ignore_ascii_case("gmt") || name == "Etc/UTC" || name == "Z" {
+            return Ok(TimeZone::Utc);
+        }
+        if let Some(zone) = IANA_ZONES.iter().find(|zone| zone.name == name) {
+            return Ok(TimeZone::Iana(*zone));
+        }
+
+        let offset = name
+            .strip_prefix("UTC")
+            .or_else(|| name.strip_prefix("GMT"))
+            .unwrap_or(name);
+        parse_offset(offset)
+            .map(TimeZone::Fixed)
+            .ok_or_else(|| format!("Unknown timezone: {}", name))
+    }
+
+    /// Offset east of UTC in seconds at the instant `utc`
+    pub fn offset_at(&self, utc: i64) -> i64 {
+        match self {
+            TimeZone::Utc => 0,
+            TimeZone::Fixed(offset) => *offset as i64,
+            TimeZone::Iana(zone) => {
+                let std = zone.std_offset as i64;
+                if zone.dst != DstRule::None && is_dst(zone.dst, std, utc) {
+                    std + HOUR
+                } else {
+                    std
+                }
+            }
+        }
+    }
+
+    /// Wall-clock seconds since the local epoch for a UTC timestamp
+    pub fn to_local(self, utc: i64) -> i64 {
+        utc + self.offset_at(utc)
+    }
+
+    /// UTC timestamp for local wall-clock seconds. Times skipped by a DST
+    /// change map past the gap; repeated times map to their first occurrence.
+    pub fn to_utc(self, local: i64) -> i64 {
+        // DST changes are months apart, so the offsets a day either side are
+        // the only candidates
+        let before = local - self.offset_at(local - DAY);
+        let after = local - self.offset_at(local + DAY);
+        match (self.to_local(before) == local, self.to_local(after) == local) {
+            (true, true) => before.min(after),
+            (false, true) => after,
+            _ => before,
+        }
+    }
+}
+
+/// `"+05:30"`, `"-0800"`, `"+2"` -> seconds east of UTC
+fn parse_offset(text: &str) -> Option<i32> {
+    let (sign, rest) = match text.chars().next()? {
+        '+' => (1, &text[1..]),
+        '-' => (-1, &text[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes >= 60 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+fn is_dst(rule: DstRule, std: i64, utc: i64) -> bool {
+    let (year, _, _) = civil_from_days((utc + std).div_euclid(DAY));
+    match rule {
+        DstRule::None => false,
+        DstRule::Us => {
+            let start = nth_sunday(year, 3, 2) * DAY + 2 * HOUR - std;
+            let end = nth_sunday(year, 11, 1) * DAY + 2 * HOUR - (std + HOUR);
+            utc >= start && utc < end
+        }
+        DstRule::Eu => {
+            let start = last_sunday(year, 3) * DAY + HOUR;
+            let end = last_sunday(year, 10) * DAY + HOUR;
+            utc >= start && utc < end
+        }
+        DstRule::Au => {
+            // Southern hemisphere: daylight time spans the new year
+            let end = nth_sunday(year, 4, 1) * DAY + 2 * HOUR - std;
+            let start = nth_sunday(year, 10, 1) * DAY + 2 * HOUR - std;
+            utc < end || utc >= start
+        }
+    }
+}
+
+// =============================================================================
+// Tick intervals
+// =============================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TimeUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// Spacing between time ticks, e.g. 15 minutes or 3 months (a quarter)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeInterval {
+    pub unit: TimeUnit,
+    pub count: i64,
+}
+
+const fn interval(unit: TimeUnit, count: i64) -> TimeInterval {
+    TimeInterval { unit, count }
+}
+
+/// Candidate intervals from finest to coarsest; longer spans use whole years
+const TIME_INTERVALS: [TimeInterval; 20] = [
+    interval(TimeUnit::Second, 1),
+    interval(TimeUnit::Second, 5),
+    interval(TimeUnit::Second, 10),
+    interval(TimeUnit::Second, 15),
+    interval(TimeUnit::Second, 30),
+    interval(TimeUnit::Minute, 1),
+    interval(TimeUnit::Minute, 5),
+    interval(TimeUnit::Minute, 10),
+    interval(TimeUnit::Minute, 15),
+    interval(TimeUnit::Minute, 30),
+    interval(TimeUnit::Hour, 1),
+    interval(TimeUnit::Hour, 3),
+    interval(TimeUnit::Hour, 6),
+    interval(TimeUnit::Hour, 12),
+    interval(TimeUnit::Day, 1),
+    interval(TimeUnit::Day, 2),
+    interval(TimeUnit::Week, 1),
+    interval(TimeUnit::Month, 1),
+    interval(TimeUnit::Month, 3),
+    interval(TimeUnit::Month, 6),
+];
+
+impl TimeInterval {
+    /// Smallest interval giving at most about `count` ticks over `span` seconds
+    pub fn for_span(span: f64, count: usize) -> TimeInterval {
+        let target = span.abs() / count.max(1) as f64;
+        if let Some(interval) = TIME_INTERVALS.iter().find(|i| i.approx_seconds() >= target) {
+            return *interval;
+        }
+        let years = nice_number(target / TimeInterval { unit: TimeUnit::Year, count: 1 }.approx_seconds(), false);
+        TimeInterval { unit: TimeUnit::Year, count: (years as i64).max(1) }
+    }
+
+    /// Average length in seconds (months and years vary)
+    pub fn approx_seconds(&self) -> f64 {
+        let unit = match self.unit {
+            TimeUnit::Second => 1.0,
+            TimeUnit::Minute => MINUTE as f64,
+            TimeUnit::Hour => HOUR as f64,
+            TimeUnit::Day => DAY as f64,
+            TimeUnit::Week => WEEK as f64,
+            TimeUnit::Month => 2_629_746.0,
+            TimeUnit::Year => 31_556_952.0,
+        };
+        unit * self.count as f64
+    }
+
+    /// Start of the interval containing local time `local`
+    pub fn floor(&self, local: i64) -> i64 {
+        let count = self.count.max(1);
+        match self.unit {
+            TimeUnit::Second => local - local.rem_euclid(count),
+            TimeUnit::Minute => local - local.rem_euclid(count * MINUTE),
+            TimeUnit::Hour => local - local.rem_euclid(count * HOUR),
+            TimeUnit::Day => {
+                let days = local.div_euclid(DAY);
+                (days - days.rem_euclid(count)) * DAY
+            }
+            TimeUnit::Week => {
+                // Weeks start on Monday; 1970-01-05 was one
+                let days = local.div_euclid(DAY);
+                let weeks = (days - 4).div_euclid(7);
+                ((weeks - weeks.rem_euclid(count)) * 7 + 4) * DAY
+            }
+            TimeUnit::Month => {
+                let (year, month, _) = civil_from_days(local.div_euclid(DAY));
+                let month0 = (month as i64 - 1) / count * count;
+                days_from_civil(year, month0 as u32 + 1, 1) * DAY
+            }
+            TimeUnit::Year => {
+                let (year, _, _) = civil_from_days(local.div_euclid(DAY));
+                days_from_civil(year - year.rem_euclid(count), 1, 1) * DAY
+            }
+        }
+    }
+
+    /// Local time one interval after the interval start `local`
+    pub fn next(&self, local: i64) -> i64 {
+        let count = self.count.max(1);
+        match self.unit {
+            TimeUnit::Second => local + count,
+            TimeUnit::Minute => local + count * MINUTE,
+            TimeUnit::Hour => local + count * HOUR,
+            TimeUnit::Day => local + count * DAY,
+            TimeUnit::Week => local + count * WEEK,
+            TimeUnit::Month | TimeUnit::Year => {
+                let months = if self.unit == TimeUnit::Month { count } else { count * 12 };
+                let (year, month, _) = civil_from_days(local.div_euclid(DAY));
+                let total = year * 12 + month as i64 - 1 + months;
+                days_from_civil(total.div_euclid(12), total.rem_euclid(12) as u32 + 1, 1) * DAY
+            }
+        }
+    }
+
+    /// Coarsest unit a local time is aligned to, for labelling a lone value
+    pub fn aligned(local: i64) -> TimeInterval {
+        let (_, month, day) = civil_from_days(local.div_euclid(DAY));
+        let unit = if local.rem_euclid(MINUTE) != 0 {
+            TimeUnit::Second
+        } else if local.rem_euclid(DAY) != 0 {
+            TimeUnit::Minute
+        } else if day != 1 {
+            TimeUnit::Day
+        } else if month != 1 {
+            TimeUnit::Month
+        } else {
+            TimeUnit::Year
+        };
+        TimeInterval { unit, count: 1 }
+    }
+}
+
+// =============================================================================
+// Locators and formatting
+// =============================================================================
+
+/// Ticks on calendar boundaries of `tz` inside `[min, max]`.
+///
+/// Each tick is a local wall-clock time converted with [`TimeZone::to_utc`],
+/// so around DST changes sub-day ticks follow the local clock: a boundary
+/// skipped in spring lands on the first time after the gap (and is dropped if
+/// that duplicates the next tick), and the hour repeated in autumn is only
+/// ticked at its first occurrence, leaving a two-hour gap between ticks.
+pub fn time_ticks(min: f64, max: f64, count: usize, tz: &TimeZone) -> Vec<f64> {
+    let (min, max) = (min.min(max), min.max(max));
+    if !min.is_finite() || !max.is_finite() {
+        return Vec::new();
+    }
+
+    let interval = TimeInterval::for_span(max - min, count);
+    let mut local = interval.floor(tz.to_local(min.floor() as i64));
+    let mut ticks = Vec::new();
+    while ticks.len() < MAX_TIME_TICKS {
+        let utc = tz.to_utc(local) as f64;
+        if utc > max {
+            break;
+        }
+        // Skip ticks a DST change moved back onto the previous one
+        if utc >= min && ticks.last().is_none_or(|&last| utc > last) {
+            ticks.push(utc);
+        }
+        local = interval.next(local);
+    }
+    ticks
+}
+
+/// Ticks for an ordinal time axis, where position `i` shows `times[i]` and
+/// gaps (nights, weekends, holidays) take no space. A tick goes on the first
+/// position of each new calendar interval inside `[min, max]`.
+pub fn ordinal_time_ticks(times: &[f64], min: f64, max: f64, count: usize, tz: &TimeZone) -> Vec<f64> {
+    let Some((first, last)) = ordinal_bounds(times, min, max) else {
+        return Vec::new();
+    };
+
+    let interval = TimeInterval::for_span(times[last] - times[first], count);
+    let period = |i: usize| interval.floor(tz.to_local(times[i].floor() as i64));
+    let mut ticks: Vec<f64> = (first..=last)
+        .filter(|&i| i == 0 || period(i) != period(i - 1))
+        .map(|i| i as f64)
+        .collect();
+
+    // Irregular data can start a new period at nearly every position
+    let stride = ticks.len().div_ceil(count.max(1) * 2).max(1);
+    if stride > 1 {
+        ticks = ticks.into_iter().step_by(stride).collect();
+    }
+    ticks
+}
+
+/// Interval used to label an ordinal axis over `[min, max]`
+pub fn ordinal_time_interval(times: &[f64], min: f64, max: f64, count: usize) -> TimeInterval {
+    match ordinal_bounds(times, min, max) {
+        Some((first, last)) => TimeInterval::for_span(times[last] - times[first], count),
+        None => interval(TimeUnit::Day, 1),
+    }
+}
+
+/// Indices of `times` whose positions lie inside `[min, max]`
+fn ordinal_bounds(times: &[f64], min: f64, max: f64) -> Option<(usize, usize)> {
+    if times.is_empty() {
+        return None;
+    }
+    let (min, max) = (min.min(max), min.max(max));
+    let first = min.ceil().max(0.0) as usize;
+    let last = (max.floor().max(0.0) as usize).min(times.len() - 1);
+    (first <= last).then_some((first, last))
+}
+
+/// Label for a tick at `utc` on an axis ticking every `interval`. The label
+/// names what changes at that tick: `14:30` within a day, `Oct 3` at
+/// midnight or for day ticks, `2026-10` for months, `2026` at a year start.
+pub fn format_time(utc: f64, interval: TimeInterval, tz: &TimeZone) -> String {
+    let local = tz.to_local(utc.floor() as i64);
+    let days = local.div_euclid(DAY);
+    let secs = local.rem_euclid(DAY);
+    let (year, month, day) = civil_from_days(days);
+    let (hh, mm, ss) = (secs / HOUR, secs % HOUR / MINUTE, secs % MINUTE);
+    let date = format!("{} {}", MONTH_NAMES[month as usize - 1], day);
+
+    match interval.unit {
+        TimeUnit::Second | TimeUnit::Minute | TimeUnit::Hour if secs == 0 => date,
+        TimeUnit::Second => format!("{:02}:{:02}:{:02}", hh, mm, ss),
+        TimeUnit::Minute | TimeUnit::Hour => format!("{:02}:{:02}", hh, mm),
+        TimeUnit::Day | TimeUnit::Week if month == 1 && day == 1 => year.to_string(),
+        TimeUnit::Day | TimeUnit::Week => date,
+        TimeUnit::Month if month == 1 => year.to_string(),
+        TimeUnit::Month => format!("{}-{:02}", year, month),
+        TimeUnit::Year => year.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// UTC timestamp of a UTC date and time
+    fn utc(year: i64, month: u32, day: u32, hour: i64) -> i64 {
+        days_from_civil(year, month, day) * DAY + hour * HOUR
+    }
+
+    fn hourly_ticks(tz: &str, from: i64, to: i64) -> Vec<i64> {
+        let tz = TimeZone::parse(tz).unwrap();
+        let count = ((to - from) / HOUR) as usize;
+        time_ticks(from as f64, to as f64, count, &tz).into_iter().map(|t| t as i64).collect()
+    }
+
+    #[test]
+    fn test_civil_round_trip() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(civil_from_days(days_from_civil(2024, 2, 29)), (2024, 2, 29));
+        assert_eq!(civil_from_days(days_from_civil(2000, 3, 1) - 1), (2000, 2, 29));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        // 2026-03-08 and 2026-11-01 are the US switch Sundays
+        assert_eq!(weekday(days_from_civil(2026, 3, 8)), 0);
+        assert_eq!(nth_sunday(2026, 3, 2), days_from_civil(2026, 3, 8));
+        assert_eq!(nth_sunday(2026, 11, 1), days_from_civil(2026, 11, 1));
+        assert_eq!(last_sunday(2026, 3), days_from_civil(2026, 3, 29));
+        assert_eq!(last_sunday(2026, 10), days_from_civil(2026, 10, 25));
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(TimeZone::parse("UTC"), Ok(TimeZone::Utc));
+        assert_eq!(TimeZone::parse(" Z "), Ok(TimeZone::Utc));
+        assert_eq!(TimeZone::parse("+05:30"), Ok(TimeZone::Fixed(19800)));
+        assert_eq!(TimeZone::parse("-0800"), Ok(TimeZone::Fixed(-28800)));
+        assert_eq!(TimeZone::parse("UTC+2"), Ok(TimeZone::Fixed(7200)));
+        assert_eq!(TimeZone::parse("GMT-03:30"), Ok(TimeZone::Fixed(-12600)));
+        assert!(matches!(TimeZone::parse("Europe/Berlin"), Ok(TimeZone::Iana(zone)) if zone.std_offset == 3600));
+
+        assert!(TimeZone::parse("05:30").is_err());
+        assert!(TimeZone::parse("+15").is_err());
+        assert!(TimeZone::parse("+05:60").is_err());
+        assert!(TimeZone::parse("+ab").is_err());
+        assert!(TimeZone::parse("Mars/Olympus_Mons").is_err());
+    }
+
+    #[test]
+    fn test_dst_offsets() {
+        let new_york = TimeZone::parse("America/New_York").unwrap();
+        let spring = utc(2026, 3, 8, 7);
+        assert_eq!(new_york.offset_at(spring - 1), -5 * HOUR);
+        assert_eq!(new_york.offset_at(spring), -4 * HOUR);
+        let fall = utc(2026, 11, 1, 6);
+        assert_eq!(new_york.offset_at(fall - 1), -4 * HOUR);
+        assert_eq!(new_york.offset_at(fall), -5 * HOUR);
+
+        let berlin = TimeZone::parse("Europe/Berlin").unwrap();
+        let spring = utc(2026, 3, 29, 1);
+        assert_eq!(berlin.offset_at(spring - 1), HOUR);
+        assert_eq!(berlin.offset_at(spring), 2 * HOUR);
+        let fall = utc(2026, 10, 25, 1);
+        assert_eq!(berlin.offset_at(fall - 1), 2 * HOUR);
+        assert_eq!(berlin.offset_at(fall), HOUR);
+
+        let sydney = TimeZone::parse("Australia/Sydney").unwrap();
+        assert_eq!(sydney.offset_at(utc(2026, 1, 15, 0)), 11 * HOUR);
+        assert_eq!(sydney.offset_at(utc(2026, 7, 15, 0)), 10 * HOUR);
+    }
+
+    #[test]
+    fn test_to_utc_gap_and_overlap() {
+        let new_york = TimeZone::parse("America/New_York").unwrap();
+        let local = |d: u32, m: u32, h: i64, min: i64| days_from_civil(2026, m, d) * DAY + h * HOUR + min * MINUTE;
+        // 02:30 does not exist on the spring Sunday; it maps past the gap
+        assert_eq!(new_york.to_utc(local(8, 3, 2, 30)), utc(2026, 3, 8, 7) + 30 * MINUTE);
+        // 01:30 happens twice on the fall Sunday; the first (EDT) one wins
+        assert_eq!(new_york.to_utc(local(1, 11, 1, 30)), utc(2026, 11, 1, 5) + 30 * MINUTE);
+        assert_eq!(new_york.to_local(new_york.to_utc(local(1, 11, 12, 0))), local(1, 11, 12, 0));
+    }
+
+    #[test]
+    fn test_hourly_ticks_across_dst() {
+        // New York spring: 02:00 EST jumps to 03:00 EDT at 07:00 UTC
+        assert_eq!(
+            hourly_ticks("America/New_York", utc(2026, 3, 8, 5), utc(2026, 3, 8, 9)),
+            (5..=9).map(|h| utc(2026, 3, 8, h)).collect::<Vec<_>>()
+        );
+        // New York fall: 01:00 repeats, its second occurrence (06:00 UTC) is skipped
+        assert_eq!(
+            hourly_ticks("America/New_York", utc(2026, 11, 1, 3), utc(2026, 11, 1, 9)),
+            [3, 4, 5, 7, 8, 9].map(|h| utc(2026, 11, 1, h)).to_vec()
+        );
+        // Berlin spring: 02:00 CET jumps to 03:00 CEST at 01:00 UTC
+        assert_eq!(
+            hourly_ticks("Europe/Berlin", utc(2026, 3, 28, 23), utc(2026, 3, 29, 4)),
+            [utc(2026, 3, 28, 23), utc(2026, 3, 29, 0), utc(2026, 3, 29, 1), utc(2026, 3, 29, 2), utc(2026, 3, 29, 3), utc(2026, 3, 29, 4)].to_vec()
+        );
+        // Berlin fall: 02:00 repeats, its second occurrence (01:00 UTC) is skipped
+        assert_eq!(
+            hourly_ticks("Europe/Berlin", utc(2026, 10, 24, 22), utc(2026, 10, 25, 4)),
+            [utc(2026, 10, 24, 22), utc(2026, 10, 24, 23), utc(2026, 10, 25, 0), utc(2026, 10, 25, 2), utc(2026, 10, 25, 3), utc(2026, 10, 25, 4)].to_vec()
+        );
+    }
+
+    #[test]
+    fn test_daily_ticks_stay_on_local_midnight() {
+        for (zone, month, first_day) in [
+            ("America/New_York", 3, 5),
+            ("America/New_York", 10, 29),
+            ("Europe/Berlin", 3, 26),
+            ("Europe/Berlin", 10, 22),
+        ] {
+            let tz = TimeZone::parse(zone).unwrap();
+            let local_start = days_from_civil(2026, month, first_day) * DAY;
+            let (start, end) = (tz.to_utc(local_start), tz.to_utc(local_start + 7 * DAY));
+            let ticks = time_ticks(start as f64, end as f64, 8, &tz);
+            assert_eq!(ticks.len(), 8, "{} {:?}", zone, ticks);
+            for tick in &ticks {
+                assert_eq!(tz.to_local(*tick as i64).rem_euclid(DAY), 0, "{} {}", zone, tick);
+            }
+            // One of the seven days is an hour shorter or longer
+            let lengths: Vec<i64> = ticks.windows(2).map(|w| (w[1] - w[0]) as i64).collect();
+            assert_eq!(lengths.iter().filter(|&&l| l != DAY).count(), 1, "{} {:?}", zone, lengths);
+        }
+    }
+
+    #[test]
+    fn test_month_and_quarter_boundaries() {
+        let date = |y: i64, m: u32, d: u32| days_from_civil(y, m, d) * DAY;
+        let month = interval(TimeUnit::Month, 1);
+        let quarter = interval(TimeUnit::Month, 3);
+
+        assert_eq!(month.floor(date(2024, 2, 29) + 5 * HOUR), date(2024, 2, 1));
+        assert_eq!(month.next(date(2024, 2, 1)), date(2024, 3, 1));
+        assert_eq!(month.next(date(2026, 12, 1)), date(2027, 1, 1));
+        assert_eq!(quarter.floor(date(2026, 5, 17)), date(2026, 4, 1));
+        assert_eq!(quarter.floor(date(2026, 12, 31)), date(2026, 10, 1));
+        assert_eq!(quarter.next(date(2026, 10, 1)), date(2027, 1, 1));
+
+        // Weeks start on Monday
+        let monday = interval(TimeUnit::Week, 1).floor(date(2026, 10, 18));
+        assert_eq!(monday, date(2026, 10, 12));
+        assert_eq!(weekday(monday / DAY), 1);
+
+        let ticks = time_ticks(date(2026, 1, 15) as f64, date(2027, 1, 15) as f64, 4, &TimeZone::Utc);
+        let expected = [date(2026, 4, 1), date(2026, 7, 1), date(2026, 10, 1), date(2027, 1, 1)];
+        assert_eq!(ticks, expected.map(|t| t as f64).to_vec());
+        let labels: Vec<String> = ticks.iter().map(|&t| format_time(t, quarter, &TimeZone::Utc)).collect();
+        assert_eq!(labels, ["2026-04", "2026-07", "2026-10", "2027"]);
+
+        // Month ticks sit on local midnight of the first
+        let berlin = TimeZone::parse("Europe/Berlin").unwrap();
+        let ticks = time_ticks(date(2026, 1, 10) as f64, date(2026, 4, 10) as f64, 3, &berlin);
+        assert_eq!(ticks, [date(2026, 2, 1) - HOUR, date(2026, 3, 1) - HOUR, date(2026, 4, 1) - 2 * HOUR].map(|t| t as f64).to_vec());
+    }
+
+    #[test]
+    fn test_format_time_labels() {
+        let kolkata = TimeZone::parse("+05:30").unwrap();
+        let t = utc(2026, 10, 3, 9) as f64;
+        assert_eq!(format_time(t, interval(TimeUnit::Minute, 15), &kolkata), "14:30");
+        assert_eq!(format_time(t, interval(TimeUnit::Day, 1), &kolkata), "Oct 3");
+        assert_eq!(format_time(utc(2026, 10, 3, 0) as f64, interval(TimeUnit::Hour, 1), &TimeZone::Utc), "Oct 3");
+        assert_eq!(format_time(utc(2027, 1, 1, 0) as f64, interval(TimeUnit::Day, 1), &TimeZone::Utc), "2027");
+        assert_eq!(format_time(t + 7.0, interval(TimeUnit::Second, 1), &TimeZone::Utc), "09:00:07");
+    }
+
+    /// Midnight UTC of every weekday from `from` to `to` inclusive, except
+    /// `holidays`
+    fn business_days(from: (i64, u32, u32), to: (i64, u32, u32), holidays: &[(i64, u32, u32)]) -> Vec<f64> {
+        let holidays: Vec<i64> = holidays.iter().map(|&(y, m, d)| days_from_civil(y, m, d)).collect();
+        (days_from_civil(from.0, from.1, from.2)..=days_from_civil(to.0, to.1, to.2))
+            .filter(|&day| (1..=5).contains(&weekday(day)) && !holidays.contains(&day))
+            .map(|day| (day * DAY) as f64)
+            .collect()
+    }
+
+    fn positions(ticks: &[f64]) -> Vec<usize> {
+        ticks.iter().map(|&t| t as usize).collect()
+    }
+
+    #[test]
+    fn test_ordinal_ticks_skip_weekends() {
+        // Four trading weeks from Monday 2024-01-01
+        let times = business_days((2024, 1, 1), (2024, 1, 26), &[]);
+        assert_eq!(times.len(), 20);
+        let last = (times.len() - 1) as f64;
+
+        // Weekly ticks on each Monday, five positions apart with no room
+        // taken by the weekends
+        assert_eq!(ordinal_time_interval(&times, 0.0, last, 4), interval(TimeUnit::Week, 1));
+        let ticks = ordinal_time_ticks(&times, 0.0, last, 4, &TimeZone::Utc);
+        assert_eq!(positions(&ticks), vec![0, 5, 10, 15]);
+        assert!(ticks.iter().all(|&t| weekday(times[t as usize] as i64 / DAY) == 1));
+
+        // A view starting mid-week gets no tick until the next Monday
+        let ticks = ordinal_time_ticks(&times, 2.5, 12.0, 4, &TimeZone::Utc);
+        assert_eq!(positions(&ticks), vec![5, 10]);
+
+        assert!(ordinal_time_ticks(&times, 30.0, 40.0, 4, &TimeZone::Utc).is_empty());
+        assert!(ordinal_time_ticks(&[], 0.0, 10.0, 4, &TimeZone::Utc).is_empty());
+    }
+
+    #[test]
+    fn test_ordinal_ticks_around_holidays() {
+        // New Year's Day and a Monday holiday closed
+        let times = business_days((2023, 12, 1), (2024, 3, 29), &[(2024, 1, 1), (2024, 2, 19)]);
+        let last = (times.len() - 1) as f64;
+        let monthly = ordinal_time_interval(&times, 0.0, last, 4);
+        assert_eq!(monthly, interval(TimeUnit::Month, 1));
+
+        // January starts on its first trading day, the 2nd
+        let ticks = ordinal_time_ticks(&times, 0.0, last, 4, &TimeZone::Utc);
+        let dates: Vec<(i64, u32, u32)> = ticks.iter().map(|&t| civil_from_days(times[t as usize] as i64 / DAY)).collect();
+        assert_eq!(dates, vec![(2023, 12, 1), (2024, 1, 2), (2024, 2, 1), (2024, 3, 1)]);
+        let labels: Vec<String> = ticks.iter().map(|&t| format_time(times[t as usize], monthly, &TimeZone::Utc)).collect();
+        assert_eq!(labels, ["2023-12", "2024", "2024-02", "2024-03"]);
+
+        // Weekly ticks through February: the week of the Monday holiday
+        // starts on Tuesday
+        let february = ordinal_time_ticks(&times, 42.0, 62.0, 5, &TimeZone::Utc);
+        let dates: Vec<(i64, u32, u32)> = february.iter().map(|&t| civil_from_days(times[t as usize] as i64 / DAY)).collect();
+        assert_eq!(dates, vec![(2024, 2, 5), (2024, 2, 12), (2024, 2, 20), (2024, 2, 26)]);
+    }
+
+    #[test]
+    fn test_ordinal_ticks_across_dst() {
+        // Hourly bars in New York: Friday afternoon, then the Sunday evening
+        // session into Monday, after clocks went forward on 2024-03-10
+        let new_york = TimeZone::parse("America/New_York").unwrap();
+        let local = |day: u32, hour: i64| new_york.to_utc(days_from_civil(2024, 3, day) * DAY + hour * HOUR) as f64;
+        let mut times: Vec<f64> = (12..=16).map(|h| local(8, h)).collect();
+        times.extend((18..=23).map(|h| local(10, h)));
+        times.extend((0..=3).map(|h| local(11, h)));
+        let last = (times.len() - 1) as f64;
+        assert_eq!(ordinal_time_interval(&times, 0.0, last, 4), interval(TimeUnit::Day, 1));
+
+        // Days tick at local midnight: Friday, Sunday's session, and Monday
+        // 00:00 EDT (04:00 UTC)
+        let ticks = ordinal_time_ticks(&times, 0.0, last, 4, &new_york);
+        assert_eq!(positions(&ticks), vec![0, 5, 11]);
+        assert_eq!(times[11] as i64, utc(2024, 3, 11, 4));
+        assert_eq!(format_time(times[11], interval(TimeUnit::Day, 1), &new_york), "Mar 11");
+
+        // Standard time all year would put Monday's midnight an hour later
+        let est = TimeZone::Fixed(-5 * 3600);
+        assert_eq!(positions(&ordinal_time_ticks(&times, 0.0, last, 4, &est)), vec![0, 5, 12]);
+        // and UTC days roll over two bars into the Sunday session
+        assert_eq!(positions(&ordinal_time_ticks(&times, 0.0, last, 4, &TimeZone::Utc)), vec![0, 5, 7]);
+    }
+}
@@ -1,6 +1,9 @@
+// Treemap - squarified, nested rectangles with drill-down navigation
+
 use makepad_widgets::*;
 use crate::elements::*;
 use crate::text::*;
+use crate::export::*;
 use super::*;
 
 live_design! {
@@ -16,10 +19,25 @@ live_design! {
     }
 }
 
+/// Length of the zoom animation when drilling in or out, in seconds
+const ZOOM_DURATION: f64 = 0.35;
+
+/// A press and release closer than this (pixels) count as a click
+const CLICK_SLOP: f64 = 4.0;
+
+/// Labels shrink down to this font size before they are truncated
+const MIN_LABEL_FONT: f64 = 7.0;
+
+/// Height of the breadcrumb bar shown while drilled in
+const BREADCRUMB_HEIGHT: f64 = 22.0;
+
+#[derive(Clone, Debug, Default)]
 pub struct TreemapNode {
     pub label: String,
     pub value: f64,
     pub color: Option<Vec4>,
+    /// Nested nodes; a node with children is sized by their total
+    pub children: Vec<TreemapNode>,
 }
 
 impl TreemapNode {
@@ -28,6 +46,7 @@ impl TreemapNode {
             label: label.into(),
             value,
             color: None,
+            children: Vec::new(),
         }
     }
 
@@ -35,6 +54,120 @@ impl TreemapNode {
         self.color = Some(color);
         self
     }
+
+    pub fn with_children(mut self, children: Vec<TreemapNode>) -> Self {
+        self.children = children;
+        self
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Area weight: the value of a leaf, the sum of its children otherwise.
+    /// Negative and non-finite values count as zero.
+    pub fn total(&self) -> f64 {
+        if self.is_leaf() {
+            if self.value.is_finite() { self.value.max(0.0) } else { 0.0 }
+        } else {
+            self.children.iter().map(TreemapNode::total).sum()
+        }
+    }
+}
+
+/// Node at `path` (child indices from the top level)
+fn node_at<'a>(nodes: &'a [TreemapNode], path: &[usize]) -> Option<&'a TreemapNode> {
+    let (&first, rest) = path.split_first()?;
+    let node = nodes.get(first)?;
+    if rest.is_empty() { Some(node) } else { node_at(&node.children, rest) }
+}
+
+/// Labels of the nodes along `path`
+fn path_labels(nodes: &[TreemapNode], path: &[usize]) -> Vec<String> {
+    (1..=path.len())
+        .filter_map(|n| node_at(nodes, &path[..n]).map(|node| node.label.clone()))
+        .collect()
+}
+
+/// Fill color of a node: its own, a palette color at the top level, or a
+/// lighter shade of its parent's
+fn node_color(node: &TreemapNode, index: usize, parent: Option<Vec4>) -> Vec4 {
+    node.color.unwrap_or_else(|| match parent {
+        Some(color) => lighten(color, 0.12),
+        None => get_color(index),
+    })
+}
+
+fn text_color_on(color: Vec4) -> Vec4 {
+    let brightness = color.x * 0.299 + color.y * 0.587 + color.z * 0.114;
+    if brightness > 0.5 { vec4(0.0, 0.0, 0.0, 1.0) } else { vec4(1.0, 1.0, 1.0, 1.0) }
+}
+
+/// Actions emitted by the treemap
+#[derive(Clone, Debug, DefaultNone)]
+pub enum TreemapAction {
+    None,
+    /// A node was clicked. `path` holds child indices from the top level,
+    /// `labels` the labels along it.
+    Clicked { path: Vec<usize>, labels: Vec<String> },
+    /// The view drilled into a node, or back out to one through the
+    /// breadcrumbs; an empty path is the top level
+    Focus { path: Vec<usize>, labels: Vec<String> },
+}
+
+/// A laid-out node; `path` is relative to the focused node
+#[derive(Clone, Debug)]
+struct TreemapCell {
+    path: Vec<usize>,
+    rect: Rect,
+    color: Vec4,
+    leaf: bool,
+    /// Height of the header band of a parent, 0 when it has none
+    header: f64,
+}
+
+/// Zoom between the previous view and the newly focused node
+#[derive(Clone, Debug)]
+struct TreemapZoom {
+    zoom_in: bool,
+    /// Drilling in: where the focused node was drawn. Drilling out: where
+    /// the previous focus lies in the new layout, found on the next draw.
+    rect: Option<Rect>,
+    /// Drilling out: path of the previous focus below the new one
+    child: Vec<usize>,
+    start: Option<f64>,
+    progress: f64,
+}
+
+impl TreemapZoom {
+    /// Layout rectangle and where it is drawn at the current progress
+    fn transform(&self, plot: Rect) -> Option<(Rect, Rect)> {
+        let rect = self.rect?;
+        let t = 1.0 - (1.0 - self.progress).powi(3);
+        let lerp = |a: Rect, b: Rect| Rect {
+            pos: a.pos + (b.pos - a.pos) * t,
+            size: a.size + (b.size - a.size) * t,
+        };
+        Some(if self.zoom_in { (plot, lerp(rect, plot)) } else { (rect, lerp(plot, rect)) })
+    }
+}
+
+/// Map `rect` so that `source` lands on `target`
+fn map_rect(rect: Rect, source: Rect, target: Rect) -> Rect {
+    let sx = if source.size.x > 0.0 { target.size.x / source.size.x } else { 1.0 };
+    let sy = if source.size.y > 0.0 { target.size.y / source.size.y } else { 1.0 };
+    Rect {
+        pos: dvec2(target.pos.x + (rect.pos.x - source.pos.x) * sx, target.pos.y + (rect.pos.y - source.pos.y) * sy),
+        size: dvec2(rect.size.x * sx, rect.size.y * sy),
+    }
+}
+
+fn intersect(a: Rect, b: Rect) -> Option<Rect> {
+    let left = a.pos.x.max(b.pos.x);
+    let top = a.pos.y.max(b.pos.y);
+    let right = (a.pos.x + a.size.x).min(b.pos.x + b.size.x);
+    let bottom = (a.pos.y + a.size.y).min(b.pos.y + b.size.y);
+    (right > left && bottom > top).then(|| Rect { pos: dvec2(left, top), size: dvec2(right - left, bottom - top) })
 }
 
 #[derive(Live, LiveHook, Widget)]
@@ -46,6 +179,30 @@ pub struct Treemap {
     #[rust] title: String,
     #[rust] nodes: Vec<TreemapNode>,
     #[rust] show_labels: bool,
+
+    /// Space between a parent's edge and its children, by depth below the
+    /// focused node; the last entry repeats for deeper levels
+    #[rust(vec![3.0, 2.0])]
+    level_padding: Vec<f64>,
+
+    /// Height of the label band along the top of parent nodes
+    #[rust(18.0)]
+    header_height: f64,
+
+    /// Path of the node whose children fill the view
+    #[rust] focus: Vec<usize>,
+    #[rust] zoom: Option<TreemapZoom>,
+    #[rust] next_frame: NextFrame,
+
+    /// Layout of the last draw, in screen space, parents before children
+    #[rust] cells: Vec<TreemapCell>,
+    /// Breadcrumb segments and the focus depth each returns to
+    #[rust] breadcrumbs: Vec<(Rect, usize)>,
+    #[rust] press: Option<DVec2>,
+    #[rust] area: Area,
+
+    #[rust]
+    capture: SceneCapture,
 }
 
 impl Treemap {
@@ -53,87 +210,293 @@ impl Treemap {
         self.title = title.into();
     }
 
+    /// Replace the data. The drill-down is kept while its path still leads
+    /// to a node with children, so data can be refreshed in place.
     pub fn set_data(&mut self, nodes: Vec<TreemapNode>) {
         self.nodes = nodes;
+        if !self.focus.is_empty() && node_at(&self.nodes, &self.focus).is_none_or(TreemapNode::is_leaf) {
+            self.focus.clear();
+            self.zoom = None;
+        }
     }
 
     pub fn set_show_labels(&mut self, show: bool) {
         self.show_labels = show;
     }
+
+    pub fn set_level_padding(&mut self, padding: Vec<f64>) {
+        self.level_padding = padding;
+    }
+
+    pub fn set_header_height(&mut self, height: f64) {
+        self.header_height = height.max(0.0);
+    }
+
+    /// Path of the node currently filling the view
+    pub fn focus(&self) -> &[usize] {
+        &self.focus
+    }
+
+    /// Show the children of the node at `path`, zooming from the current
+    /// view; an empty path shows the top level. Paths that do not lead to a
+    /// node with children are ignored.
+    pub fn set_focus(&mut self, cx: &mut Cx, path: Vec<usize>) {
+        if path == self.focus || (!path.is_empty() && node_at(&self.nodes, &path).is_none_or(TreemapNode::is_leaf)) {
+            return;
+        }
+        let zoom = if path.starts_with(&self.focus) {
+            let relative = &path[self.focus.len()..];
+            let rect = self.cells.iter().find(|cell| cell.path == relative).map(|cell| cell.rect);
+            rect.map(|rect| TreemapZoom { zoom_in: true, rect: Some(rect), child: Vec::new(), start: None, progress: 0.0 })
+        } else if self.focus.starts_with(&path) {
+            let child = self.focus[path.len()..].to_vec();
+            Some(TreemapZoom { zoom_in: false, rect: None, child, start: None, progress: 0.0 })
+        } else {
+            None
+        };
+        self.focus = path;
+        self.zoom = zoom;
+        if self.zoom.is_some() {
+            self.next_frame = cx.new_next_frame();
+        }
+        self.redraw(cx);
+    }
+
+    /// Go back up one level
+    pub fn drill_up(&mut self, cx: &mut Cx) {
+        if let Some((_, parent)) = self.focus.split_last() {
+            let parent = parent.to_vec();
+            self.set_focus(cx, parent);
+        }
+    }
+
+    /// Handle a click at `pos`: breadcrumbs navigate out, nodes report the
+    /// click and drill into their top-level ancestor when it has children
+    fn click(&mut self, cx: &mut Cx, pos: DVec2) -> Vec<TreemapAction> {
+        if let Some(&(_, depth)) = self.breadcrumbs.iter().find(|(rect, _)| rect.contains(pos)) {
+            if depth >= self.focus.len() {
+                return Vec::new();
+            }
+            let path = self.focus[..depth].to_vec();
+            self.set_focus(cx, path.clone());
+            let labels = path_labels(&self.nodes, &path);
+            return vec![TreemapAction::Focus { path, labels }];
+        }
+
+        let Some(cell) = self.cells.iter().rev().find(|cell| cell.rect.contains(pos)) else {
+            return Vec::new();
+        };
+        let path: Vec<usize> = self.focus.iter().chain(&cell.path).copied().collect();
+        let mut actions = vec![TreemapAction::Clicked { labels: path_labels(&self.nodes, &path), path }];
+
+        let top: Vec<usize> = self.focus.iter().chain(&cell.path[..1]).copied().collect();
+        if node_at(&self.nodes, &top).is_some_and(|node| !node.is_leaf()) {
+            self.set_focus(cx, top.clone());
+            actions.push(TreemapAction::Focus { labels: path_labels(&self.nodes, &top), path: top });
+        }
+        actions
+    }
+
+    /// Squarify `nodes` into `rect`, recursing into children inside each
+    /// parent's padding and header band
+    fn layout(&self, nodes: &[TreemapNode], rect: Rect, path: &mut Vec<usize>, parent: Option<Vec4>, out: &mut Vec<TreemapCell>) {
+        let totals: Vec<f64> = nodes.iter().map(TreemapNode::total).collect();
+        let depth = path.len();
+        let padding = self.level_padding.get(depth).or(self.level_padding.last()).copied().unwrap_or(0.0);
+        for (i, (node, cell_rect)) in nodes.iter().zip(squarify(&totals, rect)).enumerate() {
+            if cell_rect.size.x < 1.0 || cell_rect.size.y < 1.0 {
+                continue;
+            }
+            let color = node_color(node, i, parent);
+            path.push(i);
+            let header = if !node.is_leaf() && self.show_labels && cell_rect.size.y > self.header_height * 2.0 {
+                self.header_height
+            } else {
+                0.0
+            };
+            out.push(TreemapCell { path: path.clone(), rect: cell_rect, color, leaf: node.is_leaf(), header });
+            if !node.is_leaf() {
+                let inner = Rect {
+                    pos: dvec2(cell_rect.pos.x + padding, cell_rect.pos.y + padding + header),
+                    size: dvec2(cell_rect.size.x - padding * 2.0, cell_rect.size.y - padding * 2.0 - header),
+                };
+                if inner.size.x > 2.0 && inner.size.y > 2.0 {
+                    self.layout(&node.children, inner, path, Some(color), out);
+                }
+            }
+            path.pop();
+        }
+    }
+
+    /// Largest font size, then shortest truncation, at which `text` fits in
+    /// `room`; None if not even one character does
+    fn fit_label(&mut self, cx: &mut Cx2d, text: &str, room: DVec2, max_font: f64) -> Option<(String, f64)> {
+        let base_scale = self.label.draw_text.font_scale;
+        let mut fitted = None;
+        let mut size = max_font.max(MIN_LABEL_FONT);
+        while size >= MIN_LABEL_FONT {
+            self.label.set_font_size(size);
+            let measured = self.label.measure(cx, text);
+            if measured.y > room.y {
+                size -= 1.0;
+                continue;
+            }
+            if measured.x <= room.x {
+                fitted = Some((text.to_string(), size));
+                break;
+            }
+            if size - 1.0 < MIN_LABEL_FONT {
+                let chars: Vec<char> = text.chars().collect();
+                fitted = (1..chars.len()).rev().find_map(|n| {
+                    let short: String = chars[..n].iter().collect::<String>() + "…";
+                    (self.label.measure(cx, &short).x <= room.x).then_some((short, size))
+                });
+                break;
+            }
+            size -= 1.0;
+        }
+        self.label.draw_text.font_scale = base_scale;
+        fitted
+    }
+
+    fn draw_fitted(&mut self, cx: &mut Cx2d, pos: DVec2, text: &str, font_size: f64, anchor: TextAnchor) {
+        let base_scale = self.label.draw_text.font_scale;
+        self.label.set_font_size(font_size);
+        self.label.draw_at(cx, pos, text, anchor);
+        self.label.draw_text.font_scale = base_scale;
+    }
+
+    fn draw_cells(&mut self, cx: &mut Cx2d) {
+        let cells = std::mem::take(&mut self.cells);
+        for cell in &cells {
+            let rect = cell.rect;
+            self.draw_fill.color = if cell.leaf { cell.color } else { darken(cell.color, 0.1) };
+            self.draw_fill.draw_rect(cx, Rect {
+                pos: rect.pos + dvec2(1.0, 1.0),
+                size: dvec2((rect.size.x - 2.0).max(0.0), (rect.size.y - 2.0).max(0.0)),
+            });
+
+            // Border
+            self.draw_line.color = vec4(1.0, 1.0, 1.0, 0.8);
+            let corners = [
+                rect.pos,
+                dvec2(rect.pos.x + rect.size.x, rect.pos.y),
+                rect.pos + rect.size,
+                dvec2(rect.pos.x, rect.pos.y + rect.size.y),
+            ];
+            for j in 0..4 {
+                self.draw_line.draw_line(cx, corners[j], corners[(j + 1) % 4], 1.0);
+            }
+
+            if !self.show_labels {
+                continue;
+            }
+            let label = node_at(&self.nodes, &[self.focus.as_slice(), &cell.path].concat()).map(|node| node.label.clone());
+            let Some(label) = label else { continue };
+            self.label.draw_text.color = text_color_on(self.draw_fill.color);
+            if cell.leaf {
+                let room = dvec2(rect.size.x - 8.0, rect.size.y - 4.0);
+                let max_font = (rect.size.x.min(rect.size.y) / 4.0).clamp(MIN_LABEL_FONT, 16.0);
+                if let Some((text, size)) = self.fit_label(cx, &label, room, max_font) {
+                    let center = rect.pos + rect.size * 0.5;
+                    self.draw_fitted(cx, center, &text, size, TextAnchor::Center);
+                }
+            } else if cell.header > 0.0 {
+                let room = dvec2(rect.size.x - 8.0, cell.header);
+                let max_font = (cell.header * 0.6).min(11.0);
+                if let Some((text, size)) = self.fit_label(cx, &label, room, max_font) {
+                    let pos = dvec2(rect.pos.x + 4.0, rect.pos.y + cell.header / 2.0 + 1.0);
+                    self.draw_fitted(cx, pos, &text, size, TextAnchor::MiddleLeft);
+                }
+            }
+        }
+        self.cells = cells;
+    }
+
+    /// "All › A › B" along the top of the plot area, one segment per level
+    fn draw_breadcrumbs(&mut self, cx: &mut Cx2d, bar: Rect) {
+        self.breadcrumbs.clear();
+        self.draw_fill.color = vec4(0.94, 0.94, 0.94, 1.0);
+        self.draw_fill.draw_rect(cx, bar);
+
+        let mut names = vec!["All".to_string()];
+        names.extend(path_labels(&self.nodes, &self.focus));
+        let separator = " › ";
+        let separator_width = self.label.measure(cx, separator).x;
+        let mut x = bar.pos.x + 6.0;
+        let y = bar.pos.y + bar.size.y / 2.0;
+        for (depth, name) in names.iter().enumerate() {
+            let current = depth == self.focus.len();
+            let size = self.label.measure(cx, name);
+            self.label.draw_text.color = if current { vec4(0.2, 0.2, 0.2, 1.0) } else { vec4(0.12, 0.4, 0.75, 1.0) };
+            self.label.draw_at(cx, dvec2(x, y), name, TextAnchor::MiddleLeft);
+            self.breadcrumbs.push((Rect { pos: dvec2(x, bar.pos.y), size: dvec2(size.x, bar.size.y) }, depth));
+            x += size.x;
+            if !current {
+                self.label.draw_text.color = vec4(0.5, 0.5, 0.5, 1.0);
+                self.label.draw_at(cx, dvec2(x, y), separator, TextAnchor::MiddleLeft);
+                x += separator_width;
+            }
+        }
+    }
 }
 
 impl Widget for Treemap {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
-        let rect = cx.walk_turtle(walk);
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.capture.begin(rect);
+        self.cells.clear();
+        self.breadcrumbs.clear();
 
         if rect.size.x > 0.0 && rect.size.y > 0.0 && !self.nodes.is_empty() {
             let padding = 20.0;
             let title_space = if self.title.is_empty() { 0.0 } else { 30.0 };
+            let crumb_space = if self.focus.is_empty() { 0.0 } else { BREADCRUMB_HEIGHT + 4.0 };
 
             let plot_left = rect.pos.x + padding;
             let plot_top = rect.pos.y + padding + title_space;
             let plot_width = rect.size.x - padding * 2.0;
             let plot_height = rect.size.y - padding * 2.0 - title_space;
 
-            let total: f64 = self.nodes.iter().map(|n| n.value).sum();
-            if total > 0.0 {
-                let area = plot_width * plot_height;
-                let mut x = plot_left;
-                let mut y = plot_top;
-                let mut remaining_width = plot_width;
-                let mut remaining_height = plot_height;
-                let horizontal = plot_width > plot_height;
-
-                for (i, node) in self.nodes.iter().enumerate() {
-                    let node_area = (node.value / total) * area;
-                    let (node_x, node_y, node_w, node_h) = if horizontal {
-                        let w = if remaining_height > 0.0 { node_area / remaining_height } else { 0.0 };
-                        let w = w.min(remaining_width);
-                        let result = (x, y, w, remaining_height);
-                        x += w;
-                        remaining_width -= w;
-                        result
-                    } else {
-                        let h = if remaining_width > 0.0 { node_area / remaining_width } else { 0.0 };
-                        let h = h.min(remaining_height);
-                        let result = (x, y, remaining_width, h);
-                        y += h;
-                        remaining_height -= h;
-                        result
-                    };
-
-                    if node_w > 2.0 && node_h > 2.0 {
-                        let color = node.color.unwrap_or_else(|| get_color(i));
-
-                        // Draw filled rectangle
-                        self.draw_fill.color = color;
-                        self.draw_fill.draw_abs(cx, Rect {
-                            pos: dvec2(node_x, node_y),
-                            size: dvec2(node_w - 2.0, node_h - 2.0),
-                        });
-
-                        // Draw border
-                        self.draw_line.color = vec4(1.0, 1.0, 1.0, 0.8);
-                        let corners = [
-                            dvec2(node_x, node_y),
-                            dvec2(node_x + node_w - 2.0, node_y),
-                            dvec2(node_x + node_w - 2.0, node_y + node_h - 2.0),
-                            dvec2(node_x, node_y + node_h - 2.0),
-                        ];
-                        for j in 0..4 {
-                            self.draw_line.draw_line(cx, corners[j], corners[(j + 1) % 4], 1.0);
-                        }
-
-                        // Draw label
-                        if self.show_labels && node_w > 40.0 && node_h > 25.0 {
-                            let center = dvec2(node_x + node_w / 2.0, node_y + node_h / 2.0);
-                            let brightness = color.x * 0.299 + color.y * 0.587 + color.z * 0.114;
-                            self.label.draw_text.color = if brightness > 0.5 { vec4(0.0, 0.0, 0.0, 1.0) } else { vec4(1.0, 1.0, 1.0, 1.0) };
-                            self.label.draw_at(cx, center, &node.label, TextAnchor::Center);
-                        }
-                    }
+            if crumb_space > 0.0 {
+                let bar = Rect { pos: dvec2(plot_left, plot_top), size: dvec2(plot_width, BREADCRUMB_HEIGHT) };
+                self.draw_breadcrumbs(cx, bar);
+            }
+
+            let plot = Rect {
+                pos: dvec2(plot_left, plot_top + crumb_space),
+                size: dvec2(plot_width, plot_height - crumb_space),
+            };
+
+            let mut parent = None;
+            for depth in 1..=self.focus.len() {
+                if let Some(node) = node_at(&self.nodes, &self.focus[..depth]) {
+                    parent = Some(node_color(node, self.focus[depth - 1], parent));
                 }
             }
+            let children = node_at(&self.nodes, &self.focus).map_or(self.nodes.as_slice(), |node| node.children.as_slice());
+            let mut cells = Vec::new();
+            if plot.size.x > 0.0 && plot.size.y > 0.0 {
+                self.layout(children, plot, &mut Vec::new(), parent, &mut cells);
+            }
+
+            // Place the layout along the zoom, clipped to the plot area
+            if let Some(zoom) = &mut self.zoom {
+                if !zoom.zoom_in && zoom.rect.is_none() {
+                    zoom.rect = cells.iter().find(|cell| cell.path == zoom.child).map(|cell| cell.rect);
+                }
+                if let Some((source, target)) = zoom.transform(plot) {
+                    cells = cells
+                        .into_iter()
+                        .filter_map(|cell| {
+                            let rect = intersect(map_rect(cell.rect, source, target), plot)?;
+                            Some(TreemapCell { rect, ..cell })
+                        })
+                        .collect();
+                }
+            }
+            self.cells = cells;
+            self.draw_cells(cx);
 
             // Draw title
             if !self.title.is_empty() {
@@ -142,11 +505,58 @@ impl Widget for Treemap {
             }
         }
 
+        self.capture.end();
         DrawStep::done()
     }
 
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         self.view.handle_event(cx, event, scope);
+
+        if let Some(nf) = self.next_frame.is_event(event) {
+            if let Some(zoom) = &mut self.zoom {
+                let start = *zoom.start.get_or_insert(nf.time);
+                zoom.progress = ((nf.time - start) / ZOOM_DURATION).min(1.0);
+                if zoom.progress >= 1.0 {
+                    self.zoom = None;
+                } else {
+                    self.next_frame = cx.new_next_frame();
+                }
+                self.redraw(cx);
+            }
+        }
+
+        match event.hits(cx, self.area) {
+            Hit::FingerDown(fe) => {
+                self.press = Some(fe.abs);
+            }
+            Hit::FingerUp(fe) => {
+                let press = self.press.take();
+                if fe.is_over && press.is_some_and(|p| (fe.abs - p).length() <= CLICK_SLOP) {
+                    for action in self.click(cx, fe.abs) {
+                        cx.widget_action(self.widget_uid(), &scope.path, action);
+                    }
+                }
+            }
+            Hit::FingerHoverOver(fe) => {
+                let over_crumb = self.breadcrumbs.iter().any(|(rect, depth)| *depth < self.focus.len() && rect.contains(fe.abs));
+                let over_cell = self.cells.iter().any(|cell| cell.rect.contains(fe.abs));
+                cx.set_cursor(if over_crumb || over_cell { MouseCursor::Hand } else { MouseCursor::Default });
+            }
+            Hit::FingerHoverOut(_) => {
+                cx.set_cursor(MouseCursor::Default);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl PlotExport for Treemap {
+    fn scene_capture(&self) -> &SceneCapture {
+        &self.capture
+    }
+
+    fn scene_capture_mut(&mut self) -> &mut SceneCapture {
+        &mut self.capture
     }
 }
 
@@ -166,8 +576,45 @@ impl TreemapRef {
     pub fn set_show_labels(&self, show: bool) {
         if let Some(mut inner) = self.borrow_mut() { inner.set_show_labels(show); }
     }
+    pub fn set_level_padding(&self, padding: Vec<f64>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_level_padding(padding); }
+    }
+    pub fn set_header_height(&self, height: f64) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_header_height(height); }
+    }
+    pub fn set_focus(&self, cx: &mut Cx, path: Vec<usize>) {
+        if let Some(mut inner) = self.borrow_mut() { inner.set_focus(cx, path); }
+    }
+    pub fn drill_up(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() { inner.drill_up(cx); }
+    }
     pub fn redraw(&self, cx: &mut Cx) {
         if let Some(mut inner) = self.borrow_mut() { inner.redraw(cx); }
     }
-}
 
+    /// Path and labels of the node that was clicked, if any
+    pub fn clicked(&self, actions: &Actions) -> Option<(Vec<usize>, Vec<String>)> {
+        self.actions(actions).find_map(|action| match action {
+            TreemapAction::Clicked { path, labels } => Some((path, labels)),
+            _ => None,
+        })
+    }
+
+    /// Path and labels of the newly focused node, if the view drilled in or out
+    pub fn focused(&self, actions: &Actions) -> Option<(Vec<usize>, Vec<String>)> {
+        self.actions(actions).find_map(|action| match action {
+            TreemapAction::Focus { path, labels } => Some((path, labels)),
+            _ => None,
+        })
+    }
+
+    /// A click can both report a node and drill into it, so look past the first action
+    fn actions<'a>(&self, actions: &'a Actions) -> impl Iterator<Item = TreemapAction> + 'a {
+        let uid = self.widget_uid();
+        actions
+            .iter()
+            .filter_map(|action| action.as_widget_action())
+            .filter(move |action| action.widget_uid == uid)
+            .map(|action| action.cast())
+    }
+}
@@ -1,4 +1,6 @@
 use makepad_widgets::*;
+use super::stream::{StreamBuffer, DEFAULT_STREAM_CAPACITY};
+use super::stats::RegressionCurve;
 
 // Re-export styling enums
 pub use crate::elements::{LineStyle, MarkerStyle};
@@ -116,6 +118,19 @@ pub struct Series {
     pub xerr_plus: Option<Vec<f64>>,
     pub yerr_minus: Option<Vec<f64>>,
     pub yerr_plus: Option<Vec<f64>>,
+    /// Single-precision point data used instead of `x`/`y` when set
+    pub columns: Option<SeriesColumns>,
+    /// Streaming ring of the newest points, used instead of both when set
+    pub stream: Option<StreamBuffer>,
+}
+
+/// Columnar `f32` point storage, half the memory of `f64` vectors for very
+/// large series. Read by line and scatter plots through the `Series` point
+/// accessors.
+#[derive(Clone, Debug, Default)]
+pub struct SeriesColumns {
+    pub x: Vec<f32>,
+    pub y: Vec<f32>,
 }
 
 impl Series {
@@ -134,15 +149,97 @@ impl Series {
             xerr_plus: None,
             yerr_minus: None,
             yerr_plus: None,
+            columns: None,
+            stream: None,
         }
     }
 
     pub fn with_data(mut self, x: Vec<f64>, y: Vec<f64>) -> Self {
         self.x = x;
         self.y = y;
+        self.columns = None;
+        self.stream = None;
+        self
+    }
+
+    /// Store points as `f32` columns; `x`/`y` stay empty
+    pub fn with_f32_data(mut self, x: Vec<f32>, y: Vec<f32>) -> Self {
+        self.x = Vec::new();
+        self.y = Vec::new();
+        self.columns = Some(SeriesColumns { x, y });
+        self.stream = None;
         self
     }
 
+    /// Keep only the newest `capacity` points, starting from the current data
+    pub fn with_stream(mut self, capacity: usize) -> Self {
+        self.stream = Some(StreamBuffer::from_points(capacity, self.points()));
+        self.x = Vec::new();
+        self.y = Vec::new();
+        self.columns = None;
+        self
+    }
+
+    /// Append points to the stream, turning the series into a stream of
+    /// `DEFAULT_STREAM_CAPACITY` points if it is not one yet
+    pub fn push_points(&mut self, points: &[(f64, f64)]) {
+        if self.stream.is_none() {
+            *self = std::mem::take(self).with_stream(DEFAULT_STREAM_CAPACITY);
+        }
+        if let Some(stream) = &mut self.stream {
+            stream.extend(points);
+        }
+    }
+
+    /// Changes whenever streamed points arrive, for caches keyed on the data
+    pub fn revision(&self) -> u64 {
+        self.stream.as_ref().map_or(0, |stream| stream.pushed())
+    }
+
+    /// Number of points, whichever storage holds them
+    pub fn len(&self) -> usize {
+        if let Some(stream) = &self.stream {
+            return stream.len();
+        }
+        match &self.columns {
+            Some(columns) => columns.x.len().min(columns.y.len()),
+            None => self.x.len().min(self.y.len()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn x_at(&self, index: usize) -> f64 {
+        if let Some(stream) = &self.stream {
+            return stream.x()[index];
+        }
+        match &self.columns {
+            Some(columns) => columns.x[index] as f64,
+            None => self.x[index],
+        }
+    }
+
+    pub fn y_at(&self, index: usize) -> f64 {
+        if let Some(stream) = &self.stream {
+            return stream.y()[index];
+        }
+        match &self.columns {
+            Some(columns) => columns.y[index] as f64,
+            None => self.y[index],
+        }
+    }
+
+    pub fn point(&self, index: usize) -> (f64, f64) {
+        (self.x_at(index), self.y_at(index))
+    }
+
+    /// All points in order
+    pub fn points(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        (0..self.len()).map(|i| self.point(i))
+    }
+
     pub fn with_color(mut self, color: Vec4) -> Self {
         self.color = Some(color);
         self
@@ -238,6 +335,13 @@ pub struct FillRegion {
     pub color: Vec4,
 }
 
+/// Fitted curve drawn dashed over its confidence band (for add_regression)
+#[derive(Clone)]
+pub struct TrendLine {
+    pub curve: RegressionCurve,
+    pub color: Vec4,
+}
+
 /// Text annotation on the plot
 #[derive(Clone)]
 pub struct TextAnnotation {
@@ -246,7 +350,7 @@ pub struct TextAnnotation {
     pub y: f64,
     pub color: Vec4,
     pub font_size: f64,
-    pub is_math: bool,  // If true, render as LaTeX using Math widget
+    pub is_math: bool,  // If true, render the whole text as LaTeX math
 }
 
 /// Arrow annotation pointing from one location to another
@@ -1,6 +1,9 @@
 // Text rendering for plot labels
 
 use makepad_widgets::*;
+use crate::elements::DrawPlotLine;
+use crate::export::{record_primitive, ScenePrimitive};
+use crate::math::*;
 
 live_design! {
     use link::theme::*;
@@ -30,35 +33,116 @@ pub enum TextAnchor {
     BottomRight,
 }
 
+impl TextAnchor {
+    /// Offset from the anchor point to the top-left of a box of `size`
+    pub fn offset(self, size: DVec2) -> DVec2 {
+        match self {
+            TextAnchor::TopLeft => dvec2(0.0, 0.0),
+            TextAnchor::TopCenter => dvec2(-size.x / 2.0, 0.0),
+            TextAnchor::TopRight => dvec2(-size.x, 0.0),
+            TextAnchor::MiddleLeft => dvec2(0.0, -size.y / 2.0),
+            TextAnchor::Center => dvec2(-size.x / 2.0, -size.y / 2.0),
+            TextAnchor::MiddleRight => dvec2(-size.x, -size.y / 2.0),
+            TextAnchor::BottomLeft => dvec2(0.0, -size.y),
+            TextAnchor::BottomCenter => dvec2(-size.x / 2.0, -size.y),
+            TextAnchor::BottomRight => dvec2(-size.x, -size.y),
+        }
+    }
+}
+
 #[derive(Live, LiveHook, LiveRegister)]
 pub struct PlotLabel {
     #[live] pub draw_text: DrawText,
+    /// Fraction bars and radicals in math text
+    #[live] pub draw_rule: DrawPlotLine,
 }
 
 impl PlotLabel {
+    /// Draw `text` anchored at `pos`, as is
     pub fn draw_at(&mut self, cx: &mut Cx2d, pos: DVec2, text: &str, anchor: TextAnchor) {
-        // Layout text to get dimensions
-        let layout = self.draw_text.layout(cx, 0.0, 0.0, None, false, Align::default(), text);
-        let text_width = layout.size_in_lpxs.width as f64 * self.draw_text.font_scale as f64;
-        let text_height = layout.size_in_lpxs.height as f64 * self.draw_text.font_scale as f64;
+        let draw_pos = pos + anchor.offset(self.measure(cx, text));
+
+        let font_size = self.font_size();
+        let color = self.draw_text.color;
+        record_primitive(|| ScenePrimitive::Text { pos, text: text.to_string(), anchor, font_size, color });
 
-        // Calculate offset based on anchor
-        let offset = match anchor {
-            TextAnchor::TopLeft => dvec2(0.0, 0.0),
-            TextAnchor::TopCenter => dvec2(-text_width / 2.0, 0.0),
-            TextAnchor::TopRight => dvec2(-text_width, 0.0),
-            TextAnchor::MiddleLeft => dvec2(0.0, -text_height / 2.0),
-            TextAnchor::Center => dvec2(-text_width / 2.0, -text_height / 2.0),
-            TextAnchor::MiddleRight => dvec2(-text_width, -text_height / 2.0),
-            TextAnchor::BottomLeft => dvec2(0.0, -text_height),
-            TextAnchor::BottomCenter => dvec2(-text_width / 2.0, -text_height),
-            TextAnchor::BottomRight => dvec2(-text_width, -text_height),
-        };
-
-        let draw_pos = pos + offset;
         self.draw_text.draw_abs(cx, draw_pos, text);
     }
 
+    /// Draw `text` anchored at `pos`, rendering spans between `$` signs as
+    /// LaTeX math, so titles, axis labels and legends can mix both
+    pub fn draw_math_at(&mut self, cx: &mut Cx2d, pos: DVec2, text: &str, anchor: TextAnchor) {
+        if has_math(text) {
+            self.draw_math(cx, pos, &parse_rich(text), anchor);
+        } else {
+            self.draw_at(cx, pos, text, anchor);
+        }
+    }
+
+    /// Draw LaTeX math anchored at `pos`; `$` delimiters are optional
+    pub fn draw_latex_at(&mut self, cx: &mut Cx2d, pos: DVec2, latex: &str, anchor: TextAnchor) {
+        let node = if has_math(latex) { parse_rich(latex) } else { parse_math(latex) };
+        self.draw_math(cx, pos, &node, anchor);
+    }
+
+    fn draw_math(&mut self, cx: &mut Cx2d, pos: DVec2, node: &MathNode, anchor: TextAnchor) {
+        let layout = self.layout_math(cx, node);
+        let origin = pos + anchor.offset(layout.size);
+        let base_scale = self.draw_text.font_scale;
+        let base_size = self.draw_text.text_style.font_size as f64;
+        let color = self.draw_text.color;
+        self.draw_rule.color = color;
+        for item in layout.items {
+            match item {
+                MathItem::Glyph { pos, text, font_size } => {
+                    let pos = origin + pos;
+                    self.draw_text.font_scale = (font_size / base_size) as f32;
+                    self.draw_text.draw_abs(cx, pos, &text);
+                    record_primitive(|| ScenePrimitive::Text { pos, text, anchor: TextAnchor::TopLeft, font_size, color });
+                }
+                MathItem::Rule { from, to, width } => {
+                    self.draw_rule.draw_line(cx, origin + from, origin + to, width);
+                }
+            }
+        }
+        self.draw_text.font_scale = base_scale;
+    }
+
+    fn layout_math(&mut self, cx: &mut Cx2d, node: &MathNode) -> MathLayout {
+        let base_scale = self.draw_text.font_scale;
+        let base_size = self.draw_text.text_style.font_size as f64;
+        let layout = layout_math(node, self.font_size(), &mut |text, font_size| {
+            self.draw_text.font_scale = (font_size / base_size) as f32;
+            self.measure_plain(cx, text)
+        });
+        self.draw_text.font_scale = base_scale;
+        layout
+    }
+
+    /// Width and height of `text` at the current font size
+    pub fn measure(&mut self, cx: &mut Cx2d, text: &str) -> DVec2 {
+        self.measure_plain(cx, text)
+    }
+
+    /// Size of `text` as [`PlotLabel::draw_math_at`] draws it
+    pub fn measure_math(&mut self, cx: &mut Cx2d, text: &str) -> DVec2 {
+        if has_math(text) {
+            return self.layout_math(cx, &parse_rich(text)).size;
+        }
+        self.measure_plain(cx, text)
+    }
+
+    fn measure_plain(&mut self, cx: &mut Cx2d, text: &str) -> DVec2 {
+        let layout = self.draw_text.layout(cx, 0.0, 0.0, None, false, Align::default(), text);
+        let scale = self.draw_text.font_scale as f64;
+        dvec2(layout.size_in_lpxs.width as f64 * scale, layout.size_in_lpxs.height as f64 * scale)
+    }
+
+    /// Effective font size, including `font_scale`
+    pub fn font_size(&self) -> f64 {
+        self.draw_text.text_style.font_size as f64 * self.draw_text.font_scale as f64
+    }
+
     pub fn set_color(&mut self, color: Vec4) {
         self.draw_text.color = color;
     }
@@ -4,26 +4,73 @@
 //! Uses SSE streaming for receiving progressive UI updates.
 
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::mpsc::Receiver;
+use std::sync::Arc;
 
-use log::{debug, warn};
+use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use uuid::Uuid;
 
+use super::agent_card::{agent_card_url, AgentCard};
+use super::auth::{call_with_auth, AuthError, AuthProvider, StaticTokenAuth};
 use super::message::A2uiMessage;
+use super::registry::ComponentRegistry;
 use super::sse::{SseClient, SseEvent};
 
 /// A2A extension URI for A2UI protocol
 pub const A2UI_EXTENSION_URI: &str = "https://a2ui.org/a2a-extension/a2ui/v0.8";
 
+/// A2UI extension versions this client can render, most preferred first
+pub const SUPPORTED_A2UI_EXTENSIONS: &[&str] = &[A2UI_EXTENSION_URI];
+
+/// Why a user action was not delivered
+#[derive(Debug, Clone, PartialEq)]
+pub enum SendActionError {
+    /// No task has been started yet
+    NoActiveTask,
+    /// No conversation context has been established yet
+    NoActiveContext,
+    /// The agent card does not accept JSON input
+    UnsupportedInputMode(String),
+    /// The request could not be serialized or sent
+    Failed(String),
+    /// The agent rejected the credentials and the user has to log in
+    Auth(AuthError),
+}
+
+impl fmt::Display for SendActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendActionError::NoActiveTask => write!(f, "No active task to send action to"),
+            SendActionError::NoActiveContext => write!(f, "No active context"),
+            SendActionError::UnsupportedInputMode(e) => write!(f, "{}", e),
+            SendActionError::Failed(e) => write!(f, "Failed to send action: {}", e),
+            SendActionError::Auth(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<SendActionError> for String {
+    fn from(e: SendActionError) -> Self {
+        e.to_string()
+    }
+}
+
 /// A2A client for communicating with agents
 pub struct A2aClient {
     url: String,
-    auth_token: Option<String>,
+    auth: Option<Arc<dyn AuthProvider>>,
     request_id: u64,
     task_id: Option<String>,
     context_id: Option<String>,
+    /// Negotiated A2UI extension URI
+    extension_uri: String,
+    /// Card the client was discovered from
+    agent_card: Option<AgentCard>,
+    /// Client capabilities sent as request metadata
+    client_capabilities: Option<Value>,
 }
 
 impl A2aClient {
@@ -31,16 +78,89 @@ impl A2aClient {
     pub fn new(url: impl Into<String>) -> Self {
         A2aClient {
             url: url.into(),
-            auth_token: None,
+            auth: None,
             request_id: 1,
             task_id: None,
             context_id: None,
+            extension_uri: A2UI_EXTENSION_URI.to_string(),
+            agent_card: None,
+            client_capabilities: None,
+        }
+    }
+
+    /// Fetch the agent card from `{base_url}/.well-known/agent.json` and
+    /// create a client for the endpoint it describes.
+    ///
+    /// Negotiates the A2UI extension version and advertises the standard
+    /// component catalog; see [`A2aClient::from_agent_card`].
+    pub fn discover(base_url: &str) -> Result<Self, String> {
+        let card_url = agent_card_url(base_url);
+        debug!("Fetching agent card from {}", card_url);
+
+        let body = ureq::get(&card_url)
+            .set("Accept", "application/json")
+            .call()
+            .map_err(|e| format!("Failed to fetch agent card: {}", e))?
+            .into_string()
+            .map_err(|e| format!("Failed to read agent card: {}", e))?;
+
+        let mut card = AgentCard::from_json(&body)?;
+
+        // Relative endpoint: resolve against the base URL
+        if card.url.starts_with('/') {
+            card.url = format!("{}{}", base_url.trim_end_matches('/'), card.url);
         }
+
+        Self::from_agent_card(card)
+    }
+
+    /// Create a client from an already fetched agent card.
+    ///
+    /// Fails if the agent speaks none of [`SUPPORTED_A2UI_EXTENSIONS`].
+    pub fn from_agent_card(card: AgentCard) -> Result<Self, String> {
+        let Some(extension_uri) = card.negotiate_a2ui_extension(SUPPORTED_A2UI_EXTENSIONS) else {
+            return Err(format!(
+                "Agent '{}' supports no compatible A2UI extension (offered: {:?})",
+                card.name,
+                card.a2ui_extensions()
+            ));
+        };
+
+        info!("Discovered agent '{}' at {} using {}", card.name, card.url, extension_uri);
+
+        let mut client = A2aClient::new(card.url.clone())
+            .with_component_catalog(&ComponentRegistry::with_standard_catalog());
+        client.extension_uri = extension_uri.to_string();
+        client.agent_card = Some(card);
+        Ok(client)
+    }
+
+    /// Advertise the components this client renders (the registry's
+    /// implemented types) in the metadata of every request
+    pub fn with_component_catalog(mut self, registry: &ComponentRegistry) -> Self {
+        let mut components: Vec<&str> = registry
+            .implemented_types()
+            .iter()
+            .map(|t| t.name())
+            .collect();
+        components.sort_unstable();
+
+        self.client_capabilities = Some(serde_json::json!({
+            "a2uiClientCapabilities": {
+                "supportedComponents": components
+            }
+        }));
+        self
     }
 
     /// Set authentication token
-    pub fn with_auth(mut self, token: impl Into<String>) -> Self {
-        self.auth_token = Some(token.into());
+    pub fn with_auth(self, token: impl Into<String>) -> Self {
+        self.with_auth_provider(Arc::new(StaticTokenAuth::new(token)))
+    }
+
+    /// Take credentials from a provider (OAuth2, API key headers, ...)
+    pub fn with_auth_provider(mut self, provider: Arc<dyn AuthProvider>) -> Self {
+        self.auth = Some(provider);
         self
     }
 
@@ -54,8 +174,31 @@ impl A2aClient {
         self.context_id.as_deref()
     }
 
+    /// Agent card, when the client was created by discovery
+    pub fn agent_card(&self) -> Option<&AgentCard> {
+        self.agent_card.as_ref()
+    }
+
+    /// A2UI extension URI sent with every request
+    pub fn extension_uri(&self) -> &str {
+        &self.extension_uri
+    }
+
+    /// Reject parts the discovered agent does not accept
+    fn check_input_mode(&self, mode: &str) -> Result<(), String> {
+        match &self.agent_card {
+            Some(card) if !card.accepts_input_mode(mode) => Err(format!(
+                "Agent '{}' does not accept {} input (accepts: {:?})",
+                card.name, mode, card.default_input_modes
+            )),
+            _ => Ok(()),
+        }
+    }
+
     /// Send a message and receive streaming A2UI updates
     pub fn message_stream(&mut self, content: &str) -> Result<A2aEventStream, String> {
+        self.check_input_mode("text/plain")?;
+
         let message_id = Uuid::new_v4().to_string();
         let context_id = self
             .context_id
@@ -68,7 +211,7 @@ impl A2aClient {
             method: "message/stream".to_string(),
             params: MessageParams {
                 configuration: None,
-                metadata: None,
+                metadata: self.client_capabilities.clone(),
                 message: Message {
                     message_id,
                     role: "user".to_string(),
@@ -76,7 +219,7 @@ impl A2aClient {
                         text: content.to_string(),
                     }],
                     context_id: context_id.clone(),
-                    extensions: vec![A2UI_EXTENSION_URI.to_string()],
+                    extensions: vec![self.extension_uri.clone()],
                 },
             },
             id: self.request_id,
@@ -90,10 +233,10 @@ impl A2aClient {
 
         // Build SSE client
         let mut client = SseClient::new(&self.url)
-            .header("X-A2A-Extensions", A2UI_EXTENSION_URI);
+            .header("X-A2A-Extensions", &self.extension_uri);
 
-        if let Some(token) = &self.auth_token {
-            client = client.auth(token);
+        if let Some(auth) = &self.auth {
+            client = client.auth_provider(auth.clone());
         }
 
         let rx = client.post(&body)?;
@@ -113,14 +256,29 @@ impl A2aClient {
         source_component_id: &str,
         context: HashMap<String, Value>,
     ) -> Result<(), String> {
+        self.try_send_action(action_name, source_component_id, context)
+            .map_err(String::from)
+    }
+
+    /// `send_action` that keeps the failure kind, so callers can tell a
+    /// login-required rejection from a missing task
+    pub fn try_send_action(
+        &mut self,
+        action_name: &str,
+        source_component_id: &str,
+        context: HashMap<String, Value>,
+    ) -> Result<(), SendActionError> {
         let Some(task_id) = &self.task_id else {
-            return Err("No active task to send action to".to_string());
+            return Err(SendActionError::NoActiveTask);
         };
 
         let Some(context_id) = &self.context_id else {
-            return Err("No active context".to_string());
+            return Err(SendActionError::NoActiveContext);
         };
 
+        self.check_input_mode("application/json")
+            .map_err(SendActionError::UnsupportedInputMode)?;
+
         let message_id = Uuid::new_v4().to_string();
 
         // Build A2UI event
@@ -137,7 +295,7 @@ impl A2aClient {
             method: "message/send".to_string(),
             params: MessageParams {
                 configuration: None,
-                metadata: None,
+                metadata: self.client_capabilities.clone(),
                 message: Message {
                     message_id,
                     role: "user".to_string(),
@@ -145,7 +303,7 @@ impl A2aClient {
                         data: serde_json::json!({ "a2uiEvent": a2ui_event }),
                     }],
                     context_id: context_id.clone(),
-                    extensions: vec![A2UI_EXTENSION_URI.to_string()],
+                    extensions: vec![self.extension_uri.clone()],
                 },
             },
             id: self.request_id,
@@ -154,19 +312,20 @@ impl A2aClient {
         self.request_id += 1;
 
         let body = serde_json::to_string(&request)
-            .map_err(|e| format!("Failed to serialize request: {}", e))?;
+            .map_err(|e| SendActionError::Failed(format!("could not serialize request: {}", e)))?;
 
         // Send non-streaming request
-        let mut req = ureq::post(&self.url)
-            .set("Content-Type", "application/json")
-            .set("X-A2A-Extensions", A2UI_EXTENSION_URI);
-
-        if let Some(token) = &self.auth_token {
-            req = req.set("Authorization", &format!("Bearer {}", token));
-        }
+        let build = || {
+            ureq::post(&self.url)
+                .set("Content-Type", "application/json")
+                .set("X-A2A-Extensions", &self.extension_uri)
+        };
 
-        req.send_string(&body)
-            .map_err(|e| format!("Failed to send action: {}", e))?;
+        call_with_auth(self.auth.as_deref(), build, Some(&body))
+            .map_err(|e| match e {
+                AuthError::Failed(e) => SendActionError::Failed(e),
+                login => SendActionError::Auth(login),
+            })?;
 
         Ok(())
     }
@@ -241,6 +400,9 @@ impl A2aEventStream {
                 Ok(SseEvent::Error(e)) => {
                     return Some(A2aStreamEvent::Error(e));
                 }
+                Ok(SseEvent::AuthRequired(reason)) => {
+                    return Some(A2aStreamEvent::AuthRequired(reason));
+                }
                 Ok(SseEvent::Done) => {
                     // Check for remaining pending messages before returning None
                     if let Some(msg) = self.pending_messages.pop() {
@@ -335,6 +497,8 @@ pub enum A2aStreamEvent {
     TaskStatus { task_id: String, state: String },
     /// Error
     Error(String),
+    /// Credentials were rejected and could not be refreshed
+    AuthRequired(String),
 }
 
 // ============================================================================
@@ -436,3 +600,21 @@ fn chrono_now() -> String {
         .unwrap_or_default();
     format!("{}", duration.as_secs())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_action_without_task_is_not_an_auth_error() {
+        let mut client = A2aClient::new("http://127.0.0.1:9");
+        let err = client.try_send_action("click", "btn", HashMap::new()).unwrap_err();
+        assert_eq!(err, SendActionError::NoActiveTask);
+        assert!(!matches!(err, SendActionError::Auth(_)));
+
+        client.set_task_id("task-1");
+        let err = client.try_send_action("click", "btn", HashMap::new()).unwrap_err();
+        assert_eq!(err, SendActionError::NoActiveContext);
+        assert_eq!(String::from(err), "No active context");
+    }
+}
@@ -0,0 +1,284 @@
+//! A2A Agent Card
+//!
+//! Agents describe themselves with a JSON card served at
+//! `/.well-known/agent.json`: where to send RPCs, which skills they offer,
+//! which protocol extensions (A2UI versions) they speak, accepted input
+//! modes and how clients must authenticate.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Well-known path of the agent card, relative to the agent's base URL
+pub const AGENT_CARD_PATH: &str = "/.well-known/agent.json";
+
+/// A2UI extension URI prefix shared by all protocol versions
+pub const A2UI_EXTENSION_PREFIX: &str = "https://a2ui.org/a2a-extension/a2ui/";
+
+/// Self-description published by an A2A agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentCard {
+    pub name: String,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// JSON-RPC endpoint
+    pub url: String,
+
+    #[serde(default)]
+    pub version: Option<String>,
+
+    /// A2A protocol version the agent implements
+    #[serde(default)]
+    pub protocol_version: Option<String>,
+
+    #[serde(default)]
+    pub capabilities: AgentCapabilities,
+
+    /// MIME types accepted in message parts unless a skill overrides them
+    #[serde(default)]
+    pub default_input_modes: Vec<String>,
+
+    #[serde(default)]
+    pub default_output_modes: Vec<String>,
+
+    #[serde(default)]
+    pub skills: Vec<AgentSkill>,
+
+    /// Named authentication schemes, referenced by `security`
+    #[serde(default)]
+    pub security_schemes: HashMap<String, SecurityScheme>,
+
+    /// Alternative scheme combinations; any one entry satisfies the agent
+    #[serde(default)]
+    pub security: Vec<HashMap<String, Vec<String>>>,
+}
+
+/// Optional protocol features
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentCapabilities {
+    #[serde(default)]
+    pub streaming: bool,
+
+    #[serde(default)]
+    pub push_notifications: bool,
+
+    #[serde(default)]
+    pub extensions: Vec<AgentExtension>,
+}
+
+/// Protocol extension supported by the agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentExtension {
+    pub uri: String,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Clients must use this extension to talk to the agent
+    #[serde(default)]
+    pub required: bool,
+
+    #[serde(default)]
+    pub params: Option<Value>,
+}
+
+/// Capability advertised by the agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSkill {
+    pub id: String,
+
+    pub name: String,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    #[serde(default)]
+    pub examples: Vec<String>,
+
+    /// Overrides the card's default input modes when present
+    #[serde(default)]
+    pub input_modes: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub output_modes: Option<Vec<String>>,
+}
+
+/// Authentication scheme (OpenAPI security scheme shape)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityScheme {
+    /// `http`, `apiKey`, `oauth2`, `openIdConnect` or `mutualTLS`
+    #[serde(rename = "type")]
+    pub scheme_type: String,
+
+    /// HTTP auth scheme, e.g. `bearer`
+    #[serde(default)]
+    pub scheme: Option<String>,
+
+    /// Header, query or cookie name for `apiKey`
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Where the `apiKey` goes: `header`, `query` or `cookie`
+    #[serde(default, rename = "in")]
+    pub location: Option<String>,
+
+    /// OAuth2 flows (token URLs, scopes)
+    #[serde(default)]
+    pub flows: Option<Value>,
+
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl AgentCard {
+    /// Parse an agent card from JSON
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid agent card: {}", e))
+    }
+
+    /// A2UI extension URIs advertised by the agent, in card order
+    pub fn a2ui_extensions(&self) -> Vec<&str> {
+        self.capabilities
+            .extensions
+            .iter()
+            .map(|ext| ext.uri.as_str())
+            .filter(|uri| uri.starts_with(A2UI_EXTENSION_PREFIX))
+            .collect()
+    }
+
+    /// Pick the first of `supported` (client preference order) that the agent
+    /// also advertises
+    pub fn negotiate_a2ui_extension<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+        let offered = self.a2ui_extensions();
+        supported.iter().copied().find(|uri| offered.contains(uri))
+    }
+
+    /// Whether the agent accepts message parts of the given MIME type.
+    /// An agent that lists no input modes accepts anything.
+    pub fn accepts_input_mode(&self, mode: &str) -> bool {
+        self.default_input_modes.is_empty() || self.default_input_modes.iter().any(|m| m == mode)
+    }
+
+    /// Find a skill by ID
+    pub fn skill(&self, id: &str) -> Option<&AgentSkill> {
+        self.skills.iter().find(|s| s.id == id)
+    }
+
+    /// Schemes a client can authenticate with, by name, across all
+    /// `security` alternatives (every declared scheme if none are listed)
+    pub fn auth_schemes(&self) -> Vec<(&str, &SecurityScheme)> {
+        let mut names: Vec<&str> = Vec::new();
+        for name in self.security.iter().flat_map(|requirement| requirement.keys()) {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        if names.is_empty() {
+            names = self.security_schemes.keys().map(String::as_str).collect();
+            names.sort_unstable();
+        }
+
+        names
+            .into_iter()
+            .filter_map(|name| self.security_schemes.get(name).map(|scheme| (name, scheme)))
+            .collect()
+    }
+}
+
+/// Agent card URL for a base URL such as `http://localhost:8080`.
+/// A trailing RPC path (`/rpc`) is not stripped; pass the agent's origin.
+pub fn agent_card_url(base_url: &str) -> String {
+    format!("{}{}", base_url.trim_end_matches('/'), AGENT_CARD_PATH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARD: &str = r#"{
+        "name": "Payment Agent",
+        "description": "Builds checkout pages",
+        "url": "http://localhost:8080/rpc",
+        "version": "1.0.0",
+        "protocolVersion": "0.3.0",
+        "capabilities": {
+            "streaming": true,
+            "extensions": [
+                {"uri": "https://a2ui.org/a2a-extension/a2ui/v0.9", "required": false},
+                {"uri": "https://a2ui.org/a2a-extension/a2ui/v0.8", "required": true},
+                {"uri": "https://example.com/other"}
+            ]
+        },
+        "defaultInputModes": ["text/plain", "application/json"],
+        "defaultOutputModes": ["application/json"],
+        "skills": [
+            {"id": "checkout", "name": "Checkout", "tags": ["payment"], "examples": ["Pay for my cart"]}
+        ],
+        "securitySchemes": {
+            "bearer": {"type": "http", "scheme": "bearer"},
+            "apiKey": {"type": "apiKey", "name": "X-API-Key", "in": "header"}
+        },
+        "security": [{"bearer": []}]
+    }"#;
+
+    #[test]
+    fn test_parse_agent_card() {
+        let card = AgentCard::from_json(CARD).unwrap();
+        assert_eq!(card.name, "Payment Agent");
+        assert_eq!(card.url, "http://localhost:8080/rpc");
+        assert!(card.capabilities.streaming);
+        assert_eq!(card.skill("checkout").unwrap().examples, vec!["Pay for my cart"]);
+        assert_eq!(card.security_schemes["apiKey"].location.as_deref(), Some("header"));
+    }
+
+    #[test]
+    fn test_minimal_agent_card() {
+        let card = AgentCard::from_json(r#"{"name": "Echo", "url": "http://localhost/rpc"}"#).unwrap();
+        assert!(card.a2ui_extensions().is_empty());
+        assert!(card.accepts_input_mode("text/plain"));
+        assert!(card.auth_schemes().is_empty());
+
+        assert!(AgentCard::from_json(r#"{"name": "No URL"}"#).is_err());
+    }
+
+    #[test]
+    fn test_negotiate_a2ui_extension() {
+        let card = AgentCard::from_json(CARD).unwrap();
+        assert_eq!(card.a2ui_extensions().len(), 2);
+
+        let v08 = "https://a2ui.org/a2a-extension/a2ui/v0.8";
+        let v09 = "https://a2ui.org/a2a-extension/a2ui/v0.9";
+        assert_eq!(card.negotiate_a2ui_extension(&[v08]), Some(v08));
+        assert_eq!(card.negotiate_a2ui_extension(&[v09, v08]), Some(v09));
+        assert_eq!(card.negotiate_a2ui_extension(&["https://a2ui.org/a2a-extension/a2ui/v0.7"]), None);
+    }
+
+    #[test]
+    fn test_input_modes_and_auth() {
+        let card = AgentCard::from_json(CARD).unwrap();
+        assert!(card.accepts_input_mode("application/json"));
+        assert!(!card.accepts_input_mode("image/png"));
+
+        // Only schemes referenced by `security` are offered
+        let schemes = card.auth_schemes();
+        assert_eq!(schemes.len(), 1);
+        assert_eq!(schemes[0].0, "bearer");
+        assert_eq!(schemes[0].1.scheme.as_deref(), Some("bearer"));
+    }
+
+    #[test]
+    fn test_agent_card_url() {
+        assert_eq!(agent_card_url("http://localhost:8080/"), "http://localhost:8080/.well-known/agent.json");
+    }
+}
@@ -0,0 +1,534 @@
+//! Authentication for A2A and SSE requests
+//!
+//! An [`AuthProvider`] supplies the headers for each request and gets a chance
+//! to refresh its credentials when the server answers 401. Providers:
+//!
+//! - [`StaticTokenAuth`] - fixed `Bearer` token
+//! - [`HeaderAuth`] - custom header map (API keys, gateway headers)
+//! - [`OAuth2ClientCredentials`] - OAuth2 client-credentials flow with cached,
+//!   expiring access tokens
+//!
+//! Mutual TLS is a transport setting and is not handled here.
+
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::{debug, info};
+use serde_json::Value;
+
+/// Refresh tokens this long before the server would reject them
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// Why an authenticated request did not go through
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthError {
+    /// Credentials are missing or rejected and cannot be renewed without the
+    /// user (interactive login). Carries the server's challenge or reason.
+    LoginRequired(String),
+    /// Any other failure (network, token endpoint, HTTP status)
+    Failed(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::LoginRequired(reason) => write!(f, "Login required: {}", reason),
+            AuthError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<AuthError> for String {
+    fn from(e: AuthError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Supplies credentials for outgoing requests
+pub trait AuthProvider: Send + Sync {
+    /// Headers to attach to the next request
+    fn headers(&self) -> Result<Vec<(String, String)>, AuthError>;
+
+    /// Called after the server rejected the current credentials with 401.
+    /// Returns `true` if new credentials were obtained and the request should
+    /// be retried once; `false` if there is nothing to refresh.
+    fn refresh(&self) -> Result<bool, AuthError> {
+        Ok(false)
+    }
+}
+
+// ============================================================================
+// Static token
+// ============================================================================
+
+/// Fixed `Authorization: Bearer <token>`
+pub struct StaticTokenAuth {
+    token: String,
+}
+
+impl StaticTokenAuth {
+    pub fn new(token: impl Into<String>) -> Self {
+        StaticTokenAuth { token: token.into() }
+    }
+}
+
+impl AuthProvider for StaticTokenAuth {
+    fn headers(&self) -> Result<Vec<(String, String)>, AuthError> {
+        Ok(vec![("Authorization".to_string(), format!("Bearer {}", self.token))])
+    }
+}
+
+// ============================================================================
+// Custom headers
+// ============================================================================
+
+/// Arbitrary headers sent with every request, e.g. `X-API-Key`
+#[derive(Default)]
+pub struct HeaderAuth {
+    headers: Vec<(String, String)>,
+}
+
+impl HeaderAuth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a header (replaces an earlier one with the same name)
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let key = key.into();
+        self.headers.retain(|(k, _)| !k.eq_ignore_ascii_case(&key));
+        self.headers.push((key, value.into()));
+        self
+    }
+}
+
+impl AuthProvider for HeaderAuth {
+    fn headers(&self) -> Result<Vec<(String, String)>, AuthError> {
+        Ok(self.headers.clone())
+    }
+}
+
+// ============================================================================
+// OAuth2 client credentials
+// ============================================================================
+
+struct CachedToken {
+    access_token: String,
+    token_type: String,
+    expires_at: Option<Instant>,
+}
+
+impl CachedToken {
+    fn is_valid(&self, now: Instant) -> bool {
+        match self.expires_at {
+            Some(at) => now + EXPIRY_MARGIN < at,
+            None => true,
+        }
+    }
+
+    fn authorization(&self) -> String {
+        format!("{} {}", self.token_type, self.access_token)
+    }
+}
+
+/// OAuth2 client-credentials grant (RFC 6749 section 4.4).
+///
+/// Fetches an access token on first use, reuses it until shortly before it
+/// expires and fetches a new one when the server rejects it.
+pub struct OAuth2ClientCredentials {
+    token_url: String,
+    client_id: String,
+    client_secret: String,
+    scope: Option<String>,
+    token: Mutex<Option<CachedToken>>,
+}
+
+impl OAuth2ClientCredentials {
+    pub fn new(
+        token_url: impl Into<String>,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+    ) -> Self {
+        OAuth2ClientCredentials {
+            token_url: token_url.into(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            scope: None,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Request the given space-separated scopes
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    fn fetch_token(&self) -> Result<CachedToken, AuthError> {
+        debug!("Requesting OAuth2 token from {}", self.token_url);
+
+        let mut form = vec![
+            ("grant_type", "client_credentials"),
+            ("client_id", self.client_id.as_str()),
+            ("client_secret", self.client_secret.as_str()),
+        ];
+        if let Some(scope) = &self.scope {
+            form.push(("scope", scope.as_str()));
+        }
+
+        let response = ureq::post(&self.token_url)
+            .set("Accept", "application/json")
+            .send_form(&form)
+            .map_err(|e| match e {
+                ureq::Error::Status(code, response) => AuthError::Failed(format!(
+                    "Token endpoint returned {}: {}",
+                    code,
+                    response.into_string().unwrap_or_default()
+                )),
+                e => AuthError::Failed(format!("Token request failed: {}", e)),
+            })?;
+
+        let body: Value = response
+            .into_json()
+            .map_err(|e| AuthError::Failed(format!("Invalid token response: {}", e)))?;
+
+        let token = parse_token_response(&body, Instant::now())?;
+        info!("Obtained OAuth2 access token (expires in {:?})", token.expires_at.map(|at| at - Instant::now()));
+        Ok(token)
+    }
+}
+
+impl AuthProvider for OAuth2ClientCredentials {
+    fn headers(&self) -> Result<Vec<(String, String)>, AuthError> {
+        let cached = self.token.lock().unwrap()
+            .as_ref()
+            .filter(|token| token.is_valid(Instant::now()))
+            .map(CachedToken::authorization);
+
+        // The token request blocks, so it runs without the lock held; requests
+        // racing a refresh may each fetch, and the last token fetched wins.
+        let header = match cached {
+            Some(header) => header,
+            None => {
+                let token = self.fetch_token()?;
+                let header = token.authorization();
+                *self.token.lock().unwrap() = Some(token);
+                header
+            }
+        };
+
+        Ok(vec![("Authorization".to_string(), header)])
+    }
+
+    fn refresh(&self) -> Result<bool, AuthError> {
+        let token = self.fetch_token()?;
+        *self.token.lock().unwrap() = Some(token);
+        Ok(true)
+    }
+}
+
+/// Parse a token endpoint response (`access_token`, `token_type`, `expires_in`)
+fn parse_token_response(body: &Value, now: Instant) -> Result<CachedToken, AuthError> {
+    let Some(access_token) = body["access_token"].as_str() else {
+        let error = body["error"].as_str().unwrap_or("missing access_token");
+        return Err(AuthError::Failed(format!("Token endpoint error: {}", error)));
+    };
+
+    // Token types are case-insensitive; servers often send "bearer"
+    let token_type = match body["token_type"].as_str() {
+        Some(t) if !t.eq_ignore_ascii_case("bearer") => t.to_string(),
+        _ => "Bearer".to_string(),
+    };
+
+    Ok(CachedToken {
+        access_token: access_token.to_string(),
+        token_type,
+        expires_at: body["expires_in"].as_u64().map(|secs| now + Duration::from_secs(secs)),
+    })
+}
+
+// ============================================================================
+// Request helper
+// ============================================================================
+
+/// Send a request (with `body`, or as a plain call) carrying the provider's
+/// headers. On 401 the provider may refresh once; if it cannot (or the retry
+/// is rejected too) the result is [`AuthError::LoginRequired`] with the
+/// server's `WWW-Authenticate` challenge.
+pub(crate) fn call_with_auth(
+    auth: Option<&dyn AuthProvider>,
+    build: impl Fn() -> ureq::Request,
+    body: Option<&str>,
+) -> Result<ureq::Response, AuthError> {
+    let mut refreshed = false;
+
+    loop {
+        let mut request = build();
+        if let Some(auth) = auth {
+            for (key, value) in auth.headers()? {
+                request = request.set(&key, &value);
+            }
+        }
+
+        let result = match body {
+            Some(body) => request.send_string(body),
+            None => request.call(),
+        };
+
+        match result {
+            Ok(response) => return Ok(response),
+            Err(ureq::Error::Status(401, response)) => {
+                let challenge = response
+                    .header("WWW-Authenticate")
+                    .unwrap_or("Unauthorized")
+                    .to_string();
+
+                match auth {
+                    Some(auth) if !refreshed && auth.refresh()? => {
+                        debug!("Credentials refreshed after 401, retrying");
+                        refreshed = true;
+                    }
+                    _ => return Err(AuthError::LoginRequired(challenge)),
+                }
+            }
+            Err(ureq::Error::Status(code, _)) => {
+                return Err(AuthError::Failed(format!("HTTP error: {}", code)));
+            }
+            Err(e) => return Err(AuthError::Failed(format!("HTTP request failed: {}", e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// A request as seen by the test server
+    #[derive(Debug, Clone, PartialEq)]
+    struct Seen {
+        path: String,
+        authorization: Option<String>,
+        body: String,
+    }
+
+    /// Local HTTP server answering each request with `respond`, which returns
+    /// the status and body. 401s carry a `WWW-Authenticate` challenge.
+    fn serve(
+        respond: impl Fn(&Seen) -> (u16, String) + Send + 'static,
+    ) -> (String, Arc<Mutex<Vec<Seen>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let (mut authorization, mut length) = (None, 0);
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let (name, value) = line.split_once(':').unwrap();
+                    if name.eq_ignore_ascii_case("Authorization") {
+                        authorization = Some(value.trim().to_string());
+                    } else if name.eq_ignore_ascii_case("Content-Length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+
+                let request = Seen { path, authorization, body: String::from_utf8(body).unwrap() };
+                let (status, body) = respond(&request);
+                log.lock().unwrap().push(request);
+
+                let challenge = if status == 401 { "WWW-Authenticate: Bearer realm=\"test\"\r\n" } else { "" };
+                let response = format!(
+                    "HTTP/1.1 {} Test\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+                    status, body.len(), challenge, body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        (url, seen)
+    }
+
+    /// Provider whose token only becomes valid after a refresh
+    struct RefreshingAuth {
+        token: Mutex<String>,
+        refreshes: AtomicUsize,
+        can_refresh: bool,
+    }
+
+    impl RefreshingAuth {
+        fn new(can_refresh: bool) -> Self {
+            RefreshingAuth { token: Mutex::new("stale".to_string()), refreshes: AtomicUsize::new(0), can_refresh }
+        }
+    }
+
+    impl AuthProvider for RefreshingAuth {
+        fn headers(&self) -> Result<Vec<(String, String)>, AuthError> {
+            Ok(vec![("Authorization".to_string(), format!("Bearer {}", self.token.lock().unwrap()))])
+        }
+
+        fn refresh(&self) -> Result<bool, AuthError> {
+            self.refreshes.fetch_add(1, Ordering::SeqCst);
+            *self.token.lock().unwrap() = "fresh".to_string();
+            Ok(self.can_refresh)
+        }
+    }
+
+    fn authorizations(seen: &Mutex<Vec<Seen>>) -> Vec<Option<String>> {
+        seen.lock().unwrap().iter().map(|s| s.authorization.clone()).collect()
+    }
+
+    #[test]
+    fn test_static_token() {
+        let auth = StaticTokenAuth::new("abc");
+        assert_eq!(auth.headers().unwrap(), vec![("Authorization".to_string(), "Bearer abc".to_string())]);
+        assert_eq!(auth.refresh(), Ok(false));
+    }
+
+    #[test]
+    fn test_header_auth_replaces_duplicates() {
+        let auth = HeaderAuth::new()
+            .header("X-API-Key", "old")
+            .header("X-Tenant", "acme")
+            .header("x-api-key", "new");
+
+        let headers = auth.headers().unwrap();
+        assert_eq!(headers.len(), 2);
+        assert!(headers.contains(&("x-api-key".to_string(), "new".to_string())));
+    }
+
+    #[test]
+    fn test_parse_token_response() {
+        let now = Instant::now();
+        let token = parse_token_response(
+            &json!({"access_token": "t1", "token_type": "bearer", "expires_in": 3600}),
+            now,
+        )
+        .unwrap();
+        assert_eq!(token.token_type, "Bearer");
+        assert!(token.is_valid(now));
+        assert!(token.is_valid(now + Duration::from_secs(3000)));
+        // Within the margin before expiry counts as expired
+        assert!(!token.is_valid(now + Duration::from_secs(3590)));
+
+        let forever = parse_token_response(&json!({"access_token": "t2"}), now).unwrap();
+        assert!(forever.is_valid(now + Duration::from_secs(1_000_000)));
+    }
+
+    #[test]
+    fn test_token_endpoint_error() {
+        let err = parse_token_response(&json!({"error": "invalid_client"}), Instant::now()).err();
+        assert_eq!(err, Some(AuthError::Failed("Token endpoint error: invalid_client".to_string())));
+    }
+
+    #[test]
+    fn test_auth_error_message() {
+        let message: String = AuthError::LoginRequired("Bearer realm=\"a2a\"".to_string()).into();
+        assert_eq!(message, "Login required: Bearer realm=\"a2a\"");
+    }
+
+    #[test]
+    fn test_call_succeeds_after_refresh() {
+        let (url, seen) = serve(|request| match request.authorization.as_deref() {
+            Some("Bearer fresh") => (200, "ok".to_string()),
+            _ => (401, "Unauthorized".to_string()),
+        });
+        let auth = RefreshingAuth::new(true);
+
+        let response = call_with_auth(Some(&auth), || ureq::post(&format!("{}/rpc", url)), Some("{}")).unwrap();
+        assert_eq!(response.into_string().unwrap(), "ok");
+        assert_eq!(auth.refreshes.load(Ordering::SeqCst), 1);
+        // The retry carries the refreshed credentials and the same body
+        assert_eq!(authorizations(&seen), vec![Some("Bearer stale".to_string()), Some("Bearer fresh".to_string())]);
+        assert!(seen.lock().unwrap().iter().all(|s| s.body == "{}"));
+    }
+
+    #[test]
+    fn test_second_401_requires_login() {
+        let (url, seen) = serve(|_| (401, "Unauthorized".to_string()));
+        let auth = RefreshingAuth::new(true);
+
+        let result = call_with_auth(Some(&auth), || ureq::post(&format!("{}/rpc", url)), Some("{}"));
+        assert_eq!(result.err(), Some(AuthError::LoginRequired("Bearer realm=\"test\"".to_string())));
+        // Refreshed and retried exactly once
+        assert_eq!(auth.refreshes.load(Ordering::SeqCst), 1);
+        assert_eq!(seen.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_401_without_refresh_requires_login() {
+        let (url, seen) = serve(|_| (401, "Unauthorized".to_string()));
+
+        // Nothing to refresh: no retry
+        let auth = RefreshingAuth::new(false);
+        let result = call_with_auth(Some(&auth), || ureq::get(&format!("{}/live", url)), None);
+        assert!(matches!(result, Err(AuthError::LoginRequired(_))));
+        assert_eq!(seen.lock().unwrap().len(), 1);
+
+        // No provider at all
+        let result = call_with_auth(None, || ureq::get(&format!("{}/live", url)), None);
+        assert!(matches!(result, Err(AuthError::LoginRequired(_))));
+        assert_eq!(authorizations(&seen), vec![Some("Bearer stale".to_string()), None]);
+    }
+
+    #[test]
+    fn test_other_status_fails() {
+        let (url, _) = serve(|_| (500, "boom".to_string()));
+        let result = call_with_auth(None, || ureq::get(&url), None);
+        assert_eq!(result.err(), Some(AuthError::Failed("HTTP error: 500".to_string())));
+    }
+
+    #[test]
+    fn test_oauth2_fetches_caches_and_refreshes() {
+        let issued = AtomicUsize::new(0);
+        let (url, seen) = serve(move |request| match request.path.as_str() {
+            "/token" => {
+                let n = issued.fetch_add(1, Ordering::SeqCst) + 1;
+                (200, format!(r#"{{"access_token": "tok-{}", "token_type": "bearer", "expires_in": 3600}}"#, n))
+            }
+            // The first token was revoked server-side
+            _ => match request.authorization.as_deref() {
+                Some("Bearer tok-1") | None => (401, "Unauthorized".to_string()),
+                Some(_) => (200, "ok".to_string()),
+            },
+        });
+        let auth = OAuth2ClientCredentials::new(format!("{}/token", url), "client id", "s3cr&t+%=")
+            .with_scope("a2a:read");
+
+        let call = || call_with_auth(Some(&auth), || ureq::post(&format!("{}/rpc", url)), Some("{}"));
+        assert_eq!(call().unwrap().into_string().unwrap(), "ok");
+        // The refreshed token is cached for the next call
+        assert_eq!(call().unwrap().into_string().unwrap(), "ok");
+
+        let seen = seen.lock().unwrap();
+        let paths: Vec<&str> = seen.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(paths, vec!["/token", "/rpc", "/token", "/rpc", "/rpc"]);
+        assert_eq!(seen[4].authorization.as_deref(), Some("Bearer tok-2"));
+
+        // Credentials are form-encoded, so reserved characters survive
+        let form: Vec<&str> = seen[0].body.split('&').collect();
+        assert!(form.contains(&"grant_type=client_credentials"));
+        assert!(form.contains(&"client_secret=s3cr%26t%2B%25%3D"));
+        assert!(form.contains(&"scope=a2a%3Aread"));
+    }
+}
@@ -1,10 +1,11 @@
+use std::collections::HashMap;
 use makepad_widgets::*;
 use makepad_plot::*;
 use crate::a2ui::message::*;
 use crate::a2ui::data_model::DataModel;
 use crate::a2ui::processor::resolve_string_value_scoped;
 
-use super::{get_bridge_color, resolve_title, parse_colormap};
+use super::{get_bridge_color, parse_hex_color, resolve_title, parse_colormap};
 
 pub fn render_waterfall(
     plot: &mut WaterfallChart,
@@ -227,6 +228,11 @@ pub fn render_surface3d(
     // Show surface with wireframe overlay for nice look
     instance.show_surface = true;
     instance.show_wireframe = true;
+    instance.set_axis_labels(
+        chart.x_label.clone().unwrap_or_default(),
+        chart.y_label.clone().unwrap_or_default(),
+        "",
+    );
 
     if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
         instance.title = title;
@@ -257,6 +263,11 @@ pub fn render_scatter3d(
         let z = chart.series[2].values.clone();
         plot.set_data(x, y, z);
     }
+    plot.set_axis_labels(
+        chart.x_label.clone().unwrap_or_default(),
+        chart.y_label.clone().unwrap_or_default(),
+        "",
+    );
 
     if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
         plot.set_title(title);
@@ -295,6 +306,11 @@ pub fn render_line3d(
         i += 3;
         series_idx += 1;
     }
+    plot.set_axis_labels(
+        chart.x_label.clone().unwrap_or_default(),
+        chart.y_label.clone().unwrap_or_default(),
+        "",
+    );
 
     if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
         plot.set_title(title);
@@ -303,3 +319,533 @@ pub fn render_line3d(
     let walk = Walk::new(Size::Fixed(chart.width), Size::Fixed(chart.height));
     let _ = plot.draw_walk(cx, scope, walk);
 }
+
+// ============================================================================
+// Declarative Plot Spec Bridge
+// ============================================================================
+
+pub fn render_spec(
+    plot: &mut PlotSpecView,
+    cx: &mut Cx2d,
+    scope: &mut Scope,
+    chart: &ChartComponent,
+    data_model: &DataModel,
+    current_scope: Option<&str>,
+) {
+    // The whole chart comes from `spec`; the component title fills in when
+    // the spec has none
+    let result = match &chart.spec {
+        Some(value) => PlotSpec::from_value(value.clone()).and_then(|mut spec| {
+            if spec.title.is_none() {
+                spec.title = resolve_title(&chart.title, data_model, current_scope);
+            }
+            plot.set_spec(&spec)
+        }),
+        None => Err("spec chart has no spec".to_string()),
+    };
+    if let Err(err) = result {
+        log!("[render_spec] {}", err);
+        plot.clear();
+    }
+
+    let walk = Walk::new(Size::Fixed(chart.width), Size::Fixed(chart.height));
+    let _ = plot.draw_walk(cx, scope, walk);
+}
+
+// ============================================================================
+// Network Graph Bridge
+// ============================================================================
+
+/// Draws a network chart and returns the node ids in widget order, so node
+/// clicks can be reported by id
+pub fn render_network(
+    plot: &mut NetworkGraph,
+    cx: &mut Cx2d,
+    scope: &mut Scope,
+    chart: &ChartComponent,
+    data_model: &DataModel,
+    current_scope: Option<&str>,
+) -> Vec<String> {
+    let (ids, nodes, edges, groups) = network_data(chart);
+    plot.set_data(nodes, edges);
+    plot.set_layout(match chart.layout.as_deref() {
+        Some("hierarchical") | Some("layered") => GraphLayout::Hierarchical,
+        Some("radial") => GraphLayout::Radial,
+        _ => GraphLayout::Force,
+    });
+    plot.set_directed(chart.directed.unwrap_or(true));
+    plot.set_group_names(if chart.show_legend == Some(false) { Vec::new() } else { groups });
+
+    if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
+        plot.set_title(title);
+    }
+
+    let walk = Walk::new(Size::Fixed(chart.width), Size::Fixed(chart.height));
+    let _ = plot.draw_walk(cx, scope, walk);
+    ids
+}
+
+/// Ids, nodes and edges of a network chart, and its group names in order of
+/// first appearance. Nodes are listed in `nodes` and referenced by id from
+/// `edges`; ids that only appear in edges become plain nodes. Each group gets
+/// a palette color, and ungrouped nodes the next one.
+fn network_data(chart: &ChartComponent) -> (Vec<String>, Vec<NetworkNode>, Vec<NetworkEdge>, Vec<String>) {
+    let mut groups: Vec<String> = Vec::new();
+    for name in chart.nodes.iter().filter_map(|node| node.group.as_ref()) {
+        if !groups.contains(name) {
+            groups.push(name.clone());
+        }
+    }
+    let ungrouped = groups.len();
+
+    let mut ids: Vec<String> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut nodes = Vec::new();
+    for node in &chart.nodes {
+        if index.contains_key(node.id.as_str()) {
+            continue;
+        }
+        let group = node.group.as_ref().and_then(|name| groups.iter().position(|g| g == name)).unwrap_or(ungrouped);
+        let color = node.color.as_deref().and_then(parse_hex_color).unwrap_or_else(|| get_bridge_color(chart, group));
+        index.insert(&node.id, ids.len());
+        ids.push(node.id.clone());
+        nodes.push(
+            NetworkNode::new(node.label.as_deref().unwrap_or(&node.id))
+                .with_group(group)
+                .with_size(node.size.unwrap_or(1.0))
+                .with_color(color),
+        );
+    }
+
+    let mut edges = Vec::with_capacity(chart.edges.len());
+    for edge in &chart.edges {
+        let mut endpoints = [0; 2];
+        for (slot, id) in endpoints.iter_mut().zip([&edge.source, &edge.target]) {
+            *slot = match index.get(id.as_str()) {
+                Some(&i) => i,
+                None => {
+                    index.insert(id, ids.len());
+                    ids.push(id.clone());
+                    nodes.push(NetworkNode::new(id.as_str()).with_group(ungrouped).with_color(get_bridge_color(chart, ungrouped)));
+                    ids.len() - 1
+                }
+            };
+        }
+        let mut network_edge = NetworkEdge::new(endpoints[0], endpoints[1]).with_weight(edge.value.unwrap_or(1.0));
+        if let Some(label) = &edge.label {
+            network_edge = network_edge.with_label(label);
+        }
+        edges.push(network_edge);
+    }
+
+    (ids, nodes, edges, groups)
+}
+
+// ============================================================================
+// Gantt Chart Bridge
+// ============================================================================
+
+/// Unix seconds of a task time, parsing date strings
+fn chart_time(time: &ChartTime) -> Option<f64> {
+    match time {
+        ChartTime::Seconds(seconds) => Some(*seconds).filter(|s| s.is_finite()),
+        ChartTime::Date(text) => parse_datetime(text),
+    }
+}
+
+/// Tasks of a gantt chart. Tasks with an unreadable start are dropped; a
+/// missing or unreadable end makes a milestone. Colors follow the group order
+/// like network nodes, ungrouped tasks taking the first palette color.
+fn gantt_tasks(chart: &ChartComponent) -> Vec<GanttTask> {
+    let mut groups: Vec<&str> = Vec::new();
+    let mut tasks = Vec::with_capacity(chart.tasks.len());
+    for task in &chart.tasks {
+        let Some(start) = chart_time(&task.start) else { continue };
+        let end = task.end.as_ref().and_then(chart_time).unwrap_or(start);
+        let group = task.group.as_deref().map(|name| {
+            if !groups.contains(&name) {
+                groups.push(name);
+            }
+            groups.iter().position(|g| *g == name).unwrap_or(0) + 1
+        });
+        let color = task.color.as_deref().and_then(parse_hex_color).unwrap_or_else(|| get_bridge_color(chart, group.unwrap_or(0)));
+        let label = task.label.as_deref().unwrap_or(&task.id);
+        let mut gantt_task = if task.milestone == Some(true) || end <= start {
+            GanttTask::milestone(&task.id, label, start)
+        } else {
+            GanttTask::new(&task.id, label, start, end).with_progress(task.progress.unwrap_or(0.0))
+        };
+        if let Some(name) = &task.group {
+            gantt_task = gantt_task.with_group(name);
+        }
+        for id in &task.depends_on {
+            gantt_task = gantt_task.with_dependency(id);
+        }
+        tasks.push(gantt_task.with_color(color));
+    }
+    tasks
+}
+
+pub fn render_gantt(
+    plot: &mut GanttChart,
+    cx: &mut Cx2d,
+    scope: &mut Scope,
+    chart: &ChartComponent,
+    data_model: &DataModel,
+    current_scope: Option<&str>,
+) {
+    let tasks = gantt_tasks(chart);
+
+    // Whole-day schedules snap drags to days
+    let whole_days = tasks.iter().all(|t| t.start % 86400.0 == 0.0 && t.end % 86400.0 == 0.0);
+    plot.set_snap(if whole_days { 86400.0 } else { 0.0 });
+    plot.set_tasks(tasks);
+    plot.set_now(chart.now.as_ref().and_then(chart_time));
+    plot.set_show_now(chart.show_now.unwrap_or(true));
+
+    if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
+        plot.set_title(title);
+    }
+
+    let walk = Walk::new(Size::Fixed(chart.width), Size::Fixed(chart.height));
+    let _ = plot.draw_walk(cx, scope, walk);
+}
+
+// ============================================================================
+// Geographic Map Bridge
+// ============================================================================
+
+pub fn render_map(
+    plot: &mut GeoMap,
+    cx: &mut Cx2d,
+    scope: &mut Scope,
+    chart: &ChartComponent,
+    data_model: &DataModel,
+    current_scope: Option<&str>,
+) {
+    // Regions come from the `geo` file, keyed by `labels` with values from
+    // the first series; markers are bubbles over them
+    match &chart.geo {
+        Some(path) => {
+            if let Err(err) = plot.load_file(path) {
+                log!("[render_map] {}", err);
+            }
+        }
+        None => plot.set_geo_data(GeoData::default()),
+    }
+    plot.set_projection(map_projection(chart.projection.as_deref()));
+    plot.set_key_property(chart.geo_key.clone());
+
+    let values = chart.series.first().map(|series| series.values.as_slice()).unwrap_or_default();
+    plot.set_values(chart.labels.iter().cloned().zip(values.iter().copied()).collect());
+    plot.set_colormap(parse_colormap(chart.colormap.as_deref().unwrap_or("blues")));
+    plot.set_show_colorbar(chart.show_legend != Some(false));
+
+    plot.set_points(map_markers(chart));
+
+    if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
+        plot.set_title(title);
+    }
+
+    let walk = Walk::new(Size::Fixed(chart.width), Size::Fixed(chart.height));
+    let _ = plot.draw_walk(cx, scope, walk);
+}
+
+/// Projection named by a map chart; equirectangular when unknown
+fn map_projection(name: Option<&str>) -> GeoProjection {
+    match name {
+        Some("mercator") => GeoProjection::Mercator,
+        Some("albers") => GeoProjection::albers(),
+        Some("naturalEarth") => GeoProjection::NaturalEarth,
+        _ => GeoProjection::Equirectangular,
+    }
+}
+
+/// Marker bubbles of a map chart, in the first palette color unless they
+/// have their own
+fn map_markers(chart: &ChartComponent) -> Vec<GeoPoint> {
+    chart
+        .markers
+        .iter()
+        .map(|marker| {
+            let color = marker.color.as_deref().and_then(parse_hex_color).unwrap_or_else(|| get_bridge_color(chart, 0));
+            let mut point = GeoPoint::new(marker.lon, marker.lat).with_size(marker.size.unwrap_or(1.0)).with_color(color);
+            if let Some(label) = &marker.label {
+                point = point.with_label(label);
+            }
+            point
+        })
+        .collect()
+}
+
+// ============================================================================
+// Calendar Heatmap Bridge
+// ============================================================================
+
+/// Unix times and values of a calendar chart: `labels` are the dates of the
+/// first series' values, and unreadable dates are dropped
+fn calendar_data(chart: &ChartComponent) -> Vec<(f64, f64)> {
+    let values = chart.series.first().map(|series| series.values.as_slice()).unwrap_or_default();
+    chart.labels.iter().zip(values).filter_map(|(date, &value)| Some((parse_datetime(date)?, value))).collect()
+}
+
+pub fn render_calendar(
+    plot: &mut CalendarHeatmap,
+    cx: &mut Cx2d,
+    scope: &mut Scope,
+    chart: &ChartComponent,
+    data_model: &DataModel,
+    current_scope: Option<&str>,
+) {
+    plot.set_data(calendar_data(chart));
+    // A fixed year hides the year buttons
+    plot.set_show_year_buttons(chart.year.is_none());
+    if chart.year.is_some() {
+        plot.set_year(chart.year);
+    }
+    plot.set_layout(match chart.layout.as_deref() {
+        Some("month") => CalendarLayout::Month,
+        _ => CalendarLayout::Week,
+    });
+    plot.set_monday_first(chart.monday_first == Some(true));
+    plot.set_colormap(parse_colormap(chart.colormap.as_deref().unwrap_or("greens")));
+    plot.set_show_legend(chart.show_legend != Some(false));
+
+    if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
+        plot.set_title(title);
+    }
+
+    let walk = Walk::new(Size::Fixed(chart.width), Size::Fixed(chart.height));
+    let _ = plot.draw_walk(cx, scope, walk);
+}
+
+// ============================================================================
+// Parallel Coordinates / Scatter Matrix Bridge
+// ============================================================================
+
+/// One dimension per series, named by the series name (or "Series N") and
+/// on a log scale when listed in `logAxes`, plus the index of the `colorBy`
+/// series
+fn chart_dimensions(chart: &ChartComponent) -> (Vec<PlotDimension>, Option<usize>) {
+    let dimensions: Vec<PlotDimension> = chart
+        .series
+        .iter()
+        .enumerate()
+        .map(|(i, series)| {
+            let name = series.name.clone().unwrap_or_else(|| format!("Series {}", i + 1));
+            let scale = if chart.log_axes.contains(&name) { ScaleType::Log } else { ScaleType::Linear };
+            PlotDimension::new(name, series.values.clone()).with_scale(scale)
+        })
+        .collect();
+    let color_by = chart.color_by.as_ref().and_then(|name| dimensions.iter().position(|d| &d.name == name));
+    (dimensions, color_by)
+}
+
+pub fn render_parallel(
+    plot: &mut ParallelCoordinates,
+    cx: &mut Cx2d,
+    scope: &mut Scope,
+    chart: &ChartComponent,
+    data_model: &DataModel,
+    current_scope: Option<&str>,
+) {
+    let (dimensions, color_by) = chart_dimensions(chart);
+    plot.set_dimensions(dimensions);
+    plot.set_color_by(color_by);
+    plot.set_colormap(parse_colormap(chart.colormap.as_deref().unwrap_or("viridis")));
+    plot.set_line_color(Some(get_bridge_color(chart, 0)));
+
+    if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
+        plot.set_title(title);
+    }
+
+    let walk = Walk::new(Size::Fixed(chart.width), Size::Fixed(chart.height));
+    let _ = plot.draw_walk(cx, scope, walk);
+}
+
+pub fn render_scatter_matrix(
+    plot: &mut ScatterMatrix,
+    cx: &mut Cx2d,
+    scope: &mut Scope,
+    chart: &ChartComponent,
+    data_model: &DataModel,
+    current_scope: Option<&str>,
+) {
+    let (dimensions, color_by) = chart_dimensions(chart);
+    plot.set_dimensions(dimensions);
+    plot.set_color_by(color_by);
+    plot.set_colormap(parse_colormap(chart.colormap.as_deref().unwrap_or("viridis")));
+    plot.set_point_color(Some(get_bridge_color(chart, 0)));
+
+    if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
+        plot.set_title(title);
+    }
+
+    let walk = Walk::new(Size::Fixed(chart.width), Size::Fixed(chart.height));
+    let _ = plot.draw_walk(cx, scope, walk);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart(json: &str) -> ChartComponent {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_network_data_resolves_ids() {
+        let chart = chart(r##"{
+            "chartType": "network",
+            "layout": "hierarchical",
+            "colors": ["#ff0000", "#00ff00", "#0000ff"],
+            "nodes": [
+                {"id": "app", "label": "App", "group": "bin"},
+                {"id": "core", "size": 2, "group": "lib"},
+                {"id": "app", "label": "Duplicate"},
+                {"id": "cli", "group": "bin", "color": "#ffffff"}
+            ],
+            "edges": [
+                {"source": "app", "target": "core", "value": 3, "label": "uses"},
+                {"source": "core", "target": "log"},
+                {"source": "cli", "target": "log"}
+            ]
+        }"##);
+        assert_eq!(chart.layout.as_deref(), Some("hierarchical"));
+        assert_eq!(chart.directed, None);
+
+        let (ids, nodes, edges, groups) = network_data(&chart);
+        // The duplicate is dropped; "log" only appears in edges and is added
+        // once, after the listed nodes
+        assert_eq!(ids, vec!["app", "core", "cli", "log"]);
+        assert_eq!(groups, vec!["bin", "lib"]);
+        let summary: Vec<(&str, Option<usize>, f64)> = nodes.iter().map(|n| (n.label.as_str(), n.group, n.size)).collect();
+        assert_eq!(summary, vec![("App", Some(0), 1.0), ("core", Some(1), 2.0), ("cli", Some(0), 1.0), ("log", Some(2), 1.0)]);
+
+        // Groups take palette colors in order, ungrouped nodes the next one,
+        // and a node's own color wins
+        let colors: Vec<Option<Vec4>> = nodes.iter().map(|n| n.color).collect();
+        assert_eq!(
+            colors,
+            vec![
+                Some(vec4(1.0, 0.0, 0.0, 1.0)),
+                Some(vec4(0.0, 1.0, 0.0, 1.0)),
+                Some(vec4(1.0, 1.0, 1.0, 1.0)),
+                Some(vec4(0.0, 0.0, 1.0, 1.0)),
+            ]
+        );
+
+        let links: Vec<(usize, usize, f64, Option<&str>)> =
+            edges.iter().map(|e| (e.source, e.target, e.weight, e.label.as_deref())).collect();
+        assert_eq!(links, vec![(0, 1, 3.0, Some("uses")), (1, 3, 1.0, None), (2, 3, 1.0, None)]);
+    }
+
+    #[test]
+    fn test_gantt_tasks() {
+        let chart = chart(r##"{
+            "chartType": "gantt",
+            "colors": ["#ff0000", "#00ff00"],
+            "now": "2024-03-04",
+            "tasks": [
+                {"id": "design", "label": "Design", "start": "2024-03-01", "end": "2024-03-05", "progress": 0.5, "group": "Phase 1"},
+                {"id": "build", "start": 1709683200, "end": 1710288000, "dependsOn": ["design"]},
+                {"id": "later", "start": "soon", "end": "2024-03-20"},
+                {"id": "ship", "start": "2024-03-13", "milestone": true},
+                {"id": "backwards", "start": "2024-03-14", "end": "2024-03-10", "group": "Phase 1", "color": "#0000ff"}
+            ]
+        }"##);
+        assert_eq!(chart.now.as_ref().and_then(chart_time), Some(1709510400.0));
+        let (red, green, blue) = (vec4(1.0, 0.0, 0.0, 1.0), vec4(0.0, 1.0, 0.0, 1.0), vec4(0.0, 0.0, 1.0, 1.0));
+        // The unreadable start is dropped and an end before the start makes
+        // a milestone; grouped tasks take the palette after the ungrouped one
+        assert_eq!(
+            gantt_tasks(&chart),
+            vec![
+                GanttTask::new("design", "Design", 1709251200.0, 1709596800.0).with_progress(0.5).with_group("Phase 1").with_color(green),
+                GanttTask::new("build", "build", 1709683200.0, 1710288000.0).with_dependency("design").with_color(red),
+                GanttTask::milestone("ship", "ship", 1710288000.0).with_color(red),
+                GanttTask::milestone("backwards", "backwards", 1710374400.0).with_group("Phase 1").with_color(blue),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_projection_and_markers() {
+        let chart = chart(r##"{
+            "chartType": "map",
+            "geo": "data/us-states.json",
+            "projection": "albers",
+            "colors": ["#ff0000"],
+            "markers": [
+                {"lon": -122.4, "lat": 37.8, "size": 3, "label": "HQ"},
+                {"lon": -74.0, "lat": 40.7, "color": "#0000ff"}
+            ]
+        }"##);
+        assert_eq!(map_projection(chart.projection.as_deref()), GeoProjection::Albers { center: None, parallels: None });
+        assert_eq!(map_projection(Some("naturalEarth")), GeoProjection::NaturalEarth);
+        assert_eq!(map_projection(Some("robinson")), GeoProjection::Equirectangular);
+        assert_eq!(map_projection(None), GeoProjection::Equirectangular);
+
+        let markers = map_markers(&chart);
+        let summary: Vec<(f64, f64, f64, Option<Vec4>, Option<&str>)> =
+            markers.iter().map(|m| (m.lon, m.lat, m.size, m.color, m.label.as_deref())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (-122.4, 37.8, 3.0, Some(vec4(1.0, 0.0, 0.0, 1.0)), Some("HQ")),
+                (-74.0, 40.7, 1.0, Some(vec4(0.0, 0.0, 1.0, 1.0)), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_calendar_data() {
+        let chart = chart(r##"{
+            "chartType": "calendar",
+            "layout": "month",
+            "year": 2024,
+            "mondayFirst": true,
+            "labels": ["2024-02-28", "2024-02-29", "someday", "2024-03-01T12:00", "2024-03-02"],
+            "series": [{"name": "Runs", "values": [4, 9, 1, 2]}]
+        }"##);
+        assert_eq!((chart.year, chart.monday_first), (Some(2024), Some(true)));
+        // The leap day reads, the unreadable label is dropped, and labels
+        // past the values are ignored
+        assert_eq!(calendar_data(&chart), vec![(1709078400.0, 4.0), (1709164800.0, 9.0), (1709294400.0, 2.0)]);
+    }
+
+    #[test]
+    fn test_chart_dimensions() {
+        let chart = chart(r##"{
+            "chartType": "parallel",
+            "colorBy": "accuracy",
+            "logAxes": ["lr"],
+            "series": [
+                {"name": "lr", "values": [0.001, 0.01, 0.1, 0]},
+                {"name": "accuracy", "values": [0.81, 0.9, 0.62, 0.85]},
+                {"values": [3, 1, 2, 4]}
+            ]
+        }"##);
+        let (dimensions, color_by) = chart_dimensions(&chart);
+        let names: Vec<&str> = dimensions.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["lr", "accuracy", "Series 3"]);
+        assert_eq!(dimensions[0].scale, ScaleType::Log);
+        assert_eq!(dimensions[1].scale, ScaleType::Linear);
+        assert_eq!(color_by, Some(1));
+
+        // Brushing accuracy from 0.8 up leaves the first, second and last
+        // runs; the last has no place on the log lr axis, so brushing lr too
+        // drops it
+        assert_eq!(rows_in_brushes(&dimensions, &[(1, (0.8, 1.0))]), vec![0, 1, 3]);
+        assert_eq!(rows_in_brushes(&dimensions, &[(1, (0.8, 1.0)), (0, (0.0, 1.0))]), vec![0, 1]);
+
+        let chart = chart(r##"{
+            "chartType": "scatterMatrix",
+            "colorBy": "missing",
+            "series": [{"name": "a", "values": [1, 2]}, {"name": "b", "values": [3, 4]}]
+        }"##);
+        let (dimensions, color_by) = chart_dimensions(&chart);
+        assert_eq!(dimensions.len(), 2);
+        assert_eq!(color_by, None);
+    }
+}
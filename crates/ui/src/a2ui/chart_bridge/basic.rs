@@ -5,6 +5,7 @@ use crate::a2ui::data_model::DataModel;
 use crate::a2ui::processor::resolve_string_value_scoped;
 
 use super::{get_bridge_color, parse_hex_color, resolve_title, parse_colormap};
+use std::collections::HashMap;
 
 // ============================================================================
 // Line Chart Bridge
@@ -47,6 +48,7 @@ pub fn render_line(
     if let Some(true) = chart.interactive {
         plot.set_interactive(true);
     }
+    plot.set_hover(chart.hover == Some(true));
 
     let walk = Walk::new(Size::Fixed(chart.width), Size::Fixed(chart.height));
     let _ = plot.draw_walk(cx, scope, walk);
@@ -120,6 +122,7 @@ pub fn render_scatter(
     if let Some(true) = chart.show_legend {
         plot.set_legend(LegendPosition::TopRight);
     }
+    plot.set_hover(chart.hover == Some(true));
 
     let walk = Walk::new(Size::Fixed(chart.width), Size::Fixed(chart.height));
     let _ = plot.draw_walk(cx, scope, walk);
@@ -348,6 +351,24 @@ pub fn render_candlestick(
         plot.set_data(candles);
     }
 
+    // The widget is shared by every candlestick chart, so options are reset
+    // on each render
+    plot.clear_indicators();
+    for indicator in chart.indicators.iter().filter_map(parse_indicator) {
+        plot.add_indicator(indicator);
+    }
+    plot.set_candle_style(match chart.candle_style.as_deref().map(str::to_lowercase).as_deref() {
+        Some("heikinashi") | Some("heikin-ashi") => CandleStyle::HeikinAshi,
+        Some("ohlc") => CandleStyle::Ohlc,
+        _ => CandleStyle::Candles,
+    });
+    plot.clear_price_lines();
+    for &price in &chart.price_lines {
+        plot.add_price_line(PriceLine::new(price));
+    }
+    plot.set_show_last_price(chart.show_last_price == Some(true));
+    plot.set_crosshair(chart.hover == Some(true));
+
     if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
         plot.set_title(title);
     }
@@ -356,6 +377,28 @@ pub fn render_candlestick(
     let _ = plot.draw_walk(cx, scope, walk);
 }
 
+/// Indicator for an A2UI request, with the customary default parameters;
+/// unknown types are ignored
+fn parse_indicator(spec: &ChartIndicator) -> Option<Indicator> {
+    let period = |default: usize| spec.period.filter(|&p| p > 0).unwrap_or(default);
+    match spec.kind.to_lowercase().as_str() {
+        "sma" => Some(Indicator::Sma(period(20))),
+        "ema" => Some(Indicator::Ema(period(20))),
+        "bollinger" | "bb" => Some(Indicator::Bollinger {
+            period: period(20),
+            k: spec.std_dev.filter(|k| *k > 0.0).unwrap_or(2.0),
+        }),
+        "vwap" => Some(Indicator::Vwap),
+        "rsi" => Some(Indicator::Rsi(period(14))),
+        "macd" => Some(Indicator::Macd {
+            fast: spec.fast.filter(|&p| p > 0).unwrap_or(12),
+            slow: spec.slow.filter(|&p| p > 0).unwrap_or(26),
+            signal: spec.signal.filter(|&p| p > 0).unwrap_or(9),
+        }),
+        _ => None,
+    }
+}
+
 // ============================================================================
 // Heatmap Chart Bridge
 // ============================================================================
@@ -409,15 +452,19 @@ pub fn render_treemap(
     data_model: &DataModel,
     current_scope: Option<&str>,
 ) {
-    // Treemap: labels = node names, first series = values
+    // Treemap: labels = node names, first series = values, parents = id or
+    // label each node nests under (empty for the top level)
     if let Some(first_series) = chart.series.first() {
         let count = chart.labels.len().min(first_series.values.len());
-        let mut nodes = Vec::with_capacity(count);
-        for i in 0..count {
-            let mut node = TreemapNode::new(&chart.labels[i], first_series.values[i]);
-            node = node.with_color(get_bridge_color(chart, i));
-            nodes.push(node);
+        let (nodes, cycles) = treemap_nodes(chart, &first_series.values[..count]);
+        for cycle in cycles {
+            log!("[render_treemap] parents form a cycle: {}", cycle);
         }
+        let nodes = nodes
+            .into_iter()
+            .enumerate()
+            .map(|(i, node)| node.with_color(get_bridge_color(chart, i)))
+            .collect();
         plot.set_data(nodes);
     }
 
@@ -431,6 +478,71 @@ pub fn render_treemap(
     let _ = plot.draw_walk(cx, scope, walk);
 }
 
+/// Treemap hierarchy of the first `values.len()` entries. Entries are keyed
+/// by their id when `ids` are given, otherwise by label, and a parent names
+/// the first entry with that key. Entries whose parent is missing, unknown
+/// or themselves sit at the top level. Parents that form a cycle are
+/// returned as "a -> b -> a", and each cycle is broken by moving its first
+/// entry found to the top level.
+fn treemap_nodes(chart: &ChartComponent, values: &[f64]) -> (Vec<TreemapNode>, Vec<String>) {
+    let count = values.len();
+    let labels = &chart.labels[..count];
+    let key = |i: usize| match chart.ids.get(i) {
+        Some(id) if !id.is_empty() => id.as_str(),
+        _ => labels[i].as_str(),
+    };
+    // An entry without a key cannot be named as a parent
+    let mut index = HashMap::new();
+    for i in 0..count {
+        if !key(i).is_empty() {
+            index.entry(key(i)).or_insert(i);
+        }
+    }
+    let mut parent: Vec<Option<usize>> = (0..count)
+        .map(|i| chart.parents.get(i).and_then(|p| index.get(p.as_str()).copied()).filter(|&p| p != i))
+        .collect();
+
+    // Walk up from every entry; meeting an entry of the current walk again
+    // means the walk went round a cycle
+    let mut cycles = Vec::new();
+    let mut done = vec![false; count];
+    for start in 0..count {
+        let mut path = Vec::new();
+        let mut i = start;
+        while !done[i] {
+            if let Some(pos) = path.iter().position(|&p| p == i) {
+                let cycle: Vec<&str> = path[pos..].iter().chain([&i]).map(|&j| labels[j].as_str()).collect();
+                cycles.push(cycle.join(" -> "));
+                parent[i] = None;
+                break;
+            }
+            path.push(i);
+            match parent[i] {
+                Some(p) => i = p,
+                None => break,
+            }
+        }
+        for i in path {
+            done[i] = true;
+        }
+    }
+
+    let mut children = vec![Vec::new(); count];
+    let mut roots = Vec::new();
+    for (i, parent) in parent.iter().enumerate() {
+        match parent {
+            Some(p) => children[*p].push(i),
+            None => roots.push(i),
+        }
+    }
+    fn build(i: usize, labels: &[String], values: &[f64], children: &[Vec<usize>]) -> TreemapNode {
+        let nested = children[i].iter().map(|&child| build(child, labels, values, children)).collect();
+        TreemapNode::new(&labels[i], values[i]).with_children(nested)
+    }
+    let nodes = roots.into_iter().map(|i| build(i, labels, values, &children)).collect();
+    (nodes, cycles)
+}
+
 // ============================================================================
 // Sankey Diagram Bridge
 // ============================================================================
@@ -444,71 +556,50 @@ pub fn render_sankey(
     current_scope: Option<&str>,
 ) {
     // A2UI sankey: labels = node names, series = flow matrix
-    // series[i].values[j] = flow from node i to node j
+    // series[i].values[j] = flow from node i to node j. Layers, node sizes
+    // and cycles are worked out by the diagram's layout.
     let node_count = chart.labels.len();
-    let mut nodes = Vec::with_capacity(node_count);
-    let mut links = Vec::new();
-
-    // Compute layers using topological ordering
-    // Simple heuristic: nodes with no incoming flow are layer 0,
-    // then layer = max(source_layer) + 1 for each downstream node
-    let mut layers = vec![0usize; node_count];
-    let mut has_incoming = vec![false; node_count];
+    let nodes = (0..node_count)
+        .map(|i| SankeyNode::auto(&chart.labels[i], get_bridge_color(chart, i)))
+        .collect();
 
+    let mut links = Vec::new();
     for (i, series) in chart.series.iter().enumerate() {
+        if i >= node_count { break; }
         for (j, &val) in series.values.iter().enumerate() {
             if val > 0.0 && i != j && j < node_count {
-                has_incoming[j] = true;
-            }
-        }
-    }
-
-    // Simple BFS layering
-    for _pass in 0..node_count {
-        for (i, series) in chart.series.iter().enumerate() {
-            for (j, &val) in series.values.iter().enumerate() {
-                if val > 0.0 && i != j && j < node_count {
-                    if layers[j] <= layers[i] {
-                        layers[j] = layers[i] + 1;
-                    }
-                }
+                links.push(SankeyLink::new(i, j, val));
             }
         }
     }
 
-    // Create nodes with auto-calculated values
-    for i in 0..node_count {
-        let mut value = 0.0f64;
-        // Calculate outgoing
-        if i < chart.series.len() {
-            for &v in &chart.series[i].values {
-                if v > 0.0 { value += v; }
-            }
-        }
-        // Calculate incoming
-        let mut incoming = 0.0;
-        for (src, series) in chart.series.iter().enumerate() {
-            if src < node_count && i < series.values.len() && series.values[i] > 0.0 && src != i {
-                incoming += series.values[i];
-            }
-        }
-        value = value.max(incoming).max(1.0);
+    plot.set_data(nodes, links);
 
-        let color = get_bridge_color(chart, i);
-        nodes.push(SankeyNode::new(&chart.labels[i], layers[i], value, color));
+    if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
+        plot.set_title(title);
     }
 
-    // Create links
-    for (i, series) in chart.series.iter().enumerate() {
-        if i >= node_count { break; }
-        for (j, &val) in series.values.iter().enumerate() {
-            if val > 0.0 && i != j && j < node_count {
-                links.push(SankeyLink::new(i, j, val));
-            }
-        }
-    }
+    let walk = Walk::new(Size::Fixed(chart.width), Size::Fixed(chart.height));
+    let _ = plot.draw_walk(cx, scope, walk);
+}
 
-    plot.set_data(nodes, links);
+// ============================================================================
+// Chord Diagram Bridge
+// ============================================================================
+
+pub fn render_chord(
+    plot: &mut ChordDiagram,
+    cx: &mut Cx2d,
+    scope: &mut Scope,
+    chart: &ChartComponent,
+    data_model: &DataModel,
+    current_scope: Option<&str>,
+) {
+    // A2UI chord: labels = entity names, series = flow matrix
+    // series[i].values[j] = flow from entity i to entity j
+    let matrix = chart.series.iter().map(|series| series.values.clone()).collect();
+    plot.set_data(chart.labels.clone(), matrix);
+    plot.set_colors((0..chart.labels.len()).map(|i| get_bridge_color(chart, i)).collect());
 
     if let Some(title) = resolve_title(&chart.title, data_model, current_scope) {
         plot.set_title(title);
@@ -522,3 +613,122 @@ pub fn render_sankey(
 // Histogram Bridge
 // ============================================================================
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart(json: &str) -> ChartComponent {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn shape(nodes: &[TreemapNode]) -> Vec<(String, f64, usize)> {
+        nodes.iter().map(|n| (n.label.clone(), n.value, n.children.len())).collect()
+    }
+
+    #[test]
+    fn test_parse_indicator_defaults() {
+        let chart = chart(r##"{
+            "chartType": "candlestick",
+            "series": [{"values": [10, 11]}, {"values": [12, 13]}, {"values": [9, 10]}, {"values": [11, 12]}],
+            "indicators": [
+                {"type": "SMA", "period": 5},
+                {"type": "ema", "period": 0},
+                {"type": "bb", "stdDev": 2.5},
+                {"type": "vwap"},
+                {"type": "rsi"},
+                {"type": "macd", "fast": 8, "slow": 21},
+                {"type": "ichimoku"}
+            ]
+        }"##);
+        let indicators: Vec<Indicator> = chart.indicators.iter().filter_map(parse_indicator).collect();
+        assert_eq!(
+            indicators,
+            vec![
+                Indicator::Sma(5),
+                Indicator::Ema(20),
+                Indicator::Bollinger { period: 20, k: 2.5 },
+                Indicator::Vwap,
+                Indicator::Rsi(14),
+                Indicator::Macd { fast: 8, slow: 21, signal: 9 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_treemap_nest_by_parent() {
+        let chart = chart(r##"{
+            "chartType": "treemap",
+            "labels": ["src", "docs", "main.rs", "lib.rs", "plot", "axes.rs"],
+            "parents": ["", "", "src", "src", "src", "plot"],
+            "series": [{"values": [0, 12, 30, 18, 0, 7]}]
+        }"##);
+        assert_eq!(chart.chart_type, ChartType::Treemap);
+        let (nodes, cycles) = treemap_nodes(&chart, &chart.series[0].values);
+        assert!(cycles.is_empty());
+        assert_eq!(shape(&nodes), vec![("src".to_string(), 0.0, 3), ("docs".to_string(), 12.0, 0)]);
+        assert_eq!(
+            shape(&nodes[0].children),
+            vec![("main.rs".to_string(), 30.0, 0), ("lib.rs".to_string(), 18.0, 0), ("plot".to_string(), 0.0, 1)]
+        );
+        assert_eq!(shape(&nodes[0].children[2].children), vec![("axes.rs".to_string(), 7.0, 0)]);
+    }
+
+    #[test]
+    fn test_treemap_bad_parents() {
+        // Unknown parents, self-parents and missing entries go to the top
+        // level; the c -> e -> c cycle is reported and broken at c
+        let chart = chart(r##"{
+            "chartType": "treemap",
+            "labels": ["a", "b", "c", "d", "e", "f"],
+            "parents": ["missing", "b", "e", "", "c"],
+            "series": [{"values": [1, 2, 3, 4, 5, 6]}]
+        }"##);
+        let (nodes, cycles) = treemap_nodes(&chart, &chart.series[0].values);
+        assert_eq!(cycles, vec!["c -> e -> c"]);
+        assert_eq!(
+            shape(&nodes),
+            vec![
+                ("a".to_string(), 1.0, 0),
+                ("b".to_string(), 2.0, 0),
+                ("c".to_string(), 3.0, 1),
+                ("d".to_string(), 4.0, 0),
+                ("f".to_string(), 6.0, 0),
+            ]
+        );
+        assert_eq!(shape(&nodes[2].children), vec![("e".to_string(), 5.0, 0)]);
+    }
+
+    #[test]
+    fn test_treemap_repeated_labels() {
+        // Without ids a repeated label names its first entry, so each entry
+        // appears exactly once
+        let repeated = chart(r##"{
+            "chartType": "treemap",
+            "labels": ["misc", "a", "misc", "b", "misc"],
+            "parents": ["", "misc", "a", "misc", "misc"],
+            "series": [{"values": [0, 0, 3, 4, 5]}]
+        }"##);
+        let (nodes, cycles) = treemap_nodes(&repeated, &repeated.series[0].values);
+        assert!(cycles.is_empty());
+        assert_eq!(shape(&nodes), vec![("misc".to_string(), 0.0, 3)]);
+        assert_eq!(
+            shape(&nodes[0].children),
+            vec![("a".to_string(), 0.0, 1), ("b".to_string(), 4.0, 0), ("misc".to_string(), 5.0, 0)]
+        );
+        assert_eq!(shape(&nodes[0].children[0].children), vec![("misc".to_string(), 3.0, 0)]);
+
+        // Ids tell apart entries with the same label
+        let with_ids = chart(r##"{
+            "chartType": "treemap",
+            "ids": ["ops", "ops/misc", "rnd", "rnd/misc"],
+            "labels": ["Ops", "misc", "R&D", "misc"],
+            "parents": ["", "ops", "", "rnd"],
+            "series": [{"values": [0, 5, 0, 7]}]
+        }"##);
+        let (nodes, _) = treemap_nodes(&with_ids, &with_ids.series[0].values);
+        assert_eq!(shape(&nodes), vec![("Ops".to_string(), 0.0, 1), ("R&D".to_string(), 0.0, 1)]);
+        assert_eq!(shape(&nodes[0].children), vec![("misc".to_string(), 5.0, 0)]);
+        assert_eq!(shape(&nodes[1].children), vec![("misc".to_string(), 7.0, 0)]);
+    }
+}
@@ -5,12 +5,14 @@
 
 use std::collections::HashMap;
 use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
 use std::thread;
 
 use makepad_widgets::*;
 use serde_json::Value;
 
-use super::a2a_client::{A2aClient, A2aStreamEvent, A2aEventStream};
+use super::a2a_client::{A2aClient, A2aStreamEvent, A2aEventStream, SendActionError};
+use super::auth::{AuthError, AuthProvider};
 use super::message::{A2uiMessage, UserAction};
 use super::processor::ProcessorEvent;
 use super::surface::{A2uiSurface, A2uiSurfaceAction};
@@ -35,6 +37,9 @@ pub enum A2uiHostEvent {
     TaskStatus { task_id: String, state: String },
     /// Error occurred
     Error(String),
+    /// Credentials were rejected and cannot be refreshed; the user has to
+    /// log in (carries the server's challenge)
+    AuthRequired(String),
     /// Disconnected from server
     Disconnected,
 }
@@ -42,11 +47,15 @@ pub enum A2uiHostEvent {
 /// A2UI Host manages streaming connection to an A2A server
 pub struct A2uiHost {
     config: A2uiHostConfig,
+    /// Overrides `config.auth_token` when set
+    auth: Option<Arc<dyn AuthProvider>>,
     client: Option<A2aClient>,
     event_receiver: Option<Receiver<A2uiHostEvent>>,
     event_sender: Option<Sender<A2uiHostEvent>>,
     is_connected: bool,
     pending_messages: Vec<A2uiMessage>,
+    /// Events raised on the UI thread (e.g. a rejected action)
+    local_events: Vec<A2uiHostEvent>,
 }
 
 impl A2uiHost {
@@ -55,18 +64,29 @@ impl A2uiHost {
         let (tx, rx) = mpsc::channel();
         A2uiHost {
             config,
+            auth: None,
             client: None,
             event_receiver: Some(rx),
             event_sender: Some(tx),
             is_connected: false,
             pending_messages: Vec::new(),
+            local_events: Vec::new(),
         }
     }
 
+    /// Authenticate with a provider (OAuth2, API key headers, ...) instead
+    /// of the static `auth_token`
+    pub fn with_auth_provider(mut self, provider: Arc<dyn AuthProvider>) -> Self {
+        self.auth = Some(provider);
+        self
+    }
+
     /// Connect to the A2A server and send initial message
     pub fn connect(&mut self, initial_message: &str) -> Result<(), String> {
         let mut client = A2aClient::new(&self.config.url);
-        if let Some(token) = &self.config.auth_token {
+        if let Some(auth) = &self.auth {
+            client = client.with_auth_provider(auth.clone());
+        } else if let Some(token) = &self.config.auth_token {
             client = client.with_auth(token);
         }
 
@@ -92,7 +112,9 @@ impl A2uiHost {
         use super::sse::{SseClient, SseEvent};
 
         let mut sse_client = SseClient::new(&self.config.url);
-        if let Some(token) = &self.config.auth_token {
+        if let Some(auth) = &self.auth {
+            sse_client = sse_client.auth_provider(auth.clone());
+        } else if let Some(token) = &self.config.auth_token {
             sse_client = sse_client.auth(token);
         }
 
@@ -127,6 +149,9 @@ impl A2uiHost {
                     SseEvent::Error(e) => {
                         let _ = tx.send(A2uiHostEvent::Error(e));
                     }
+                    SseEvent::AuthRequired(reason) => {
+                        let _ = tx.send(A2uiHostEvent::AuthRequired(reason));
+                    }
                     SseEvent::Done => {
                         break;
                     }
@@ -153,6 +178,7 @@ impl A2uiHost {
                     A2uiHostEvent::TaskStatus { task_id, state }
                 }
                 A2aStreamEvent::Error(e) => A2uiHostEvent::Error(e),
+                A2aStreamEvent::AuthRequired(reason) => A2uiHostEvent::AuthRequired(reason),
             };
 
             if tx.send(host_event).is_err() {
@@ -167,6 +193,10 @@ impl A2uiHost {
 
     /// Poll for pending events (non-blocking)
     pub fn poll(&mut self) -> Option<A2uiHostEvent> {
+        if !self.local_events.is_empty() {
+            return Some(self.local_events.remove(0));
+        }
+
         if let Some(rx) = &self.event_receiver {
             match rx.try_recv() {
                 Ok(event) => {
@@ -207,11 +237,15 @@ impl A2uiHost {
     pub fn send_action(&mut self, action: &UserAction) -> Result<(), String> {
         if let Some(client) = &mut self.client {
             let component_id = action.component_id.as_deref().unwrap_or("");
-            client.send_action(
+            let result = client.try_send_action(
                 &action.action.name,
                 component_id,
                 action.action.context.clone(),
-            )
+            );
+            if let Err(SendActionError::Auth(AuthError::LoginRequired(reason))) = &result {
+                self.local_events.push(A2uiHostEvent::AuthRequired(reason.clone()));
+            }
+            result.map_err(String::from)
         } else {
             Err("Not connected".to_string())
         }
@@ -248,6 +282,9 @@ pub fn process_host_events(
             A2uiHostEvent::Error(e) => {
                 log!("A2UI Host Error: {}", e);
             }
+            A2uiHostEvent::AuthRequired(reason) => {
+                log!("A2UI Host Login required: {}", reason);
+            }
             A2uiHostEvent::Connected => {
                 log!("A2UI Host Connected");
             }
@@ -150,6 +150,7 @@ pub enum ComponentType {
     Image(ImageComponent),
     Icon(IconComponent),
     Divider(DividerComponent),
+    Skeleton(SkeletonComponent),
 
     // Interactive components
     Button(ButtonComponent),
@@ -308,6 +309,19 @@ pub struct DividerComponent {
     pub orientation: Option<Orientation>,
 }
 
+/// Loading placeholder for content that is still being generated
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkeletonComponent {
+    /// Placeholder height in logical pixels (default 40)
+    #[serde(default)]
+    pub height: Option<f64>,
+
+    /// Optional caption describing what is loading
+    #[serde(default)]
+    pub label: Option<StringValue>,
+}
+
 // ============================================================================
 // Interactive Components
 // ============================================================================
@@ -464,7 +478,19 @@ pub struct ChartComponent {
     #[serde(default)]
     pub labels: Vec<String>,
 
+    /// Parent of each entry in `labels`, empty for top-level entries
+    /// (treemap hierarchies). Names an entry of `ids` when those are given,
+    /// otherwise a label.
+    #[serde(default)]
+    pub parents: Vec<String>,
+
+    /// Unique id of each entry in `labels`, for hierarchies where labels
+    /// repeat (e.g. "misc" under several parents)
+    #[serde(default)]
+    pub ids: Vec<String>,
+
     /// Data series - each series has a name and values
+    #[serde(default)]
     pub series: Vec<ChartSeries>,
 
     /// Chart width in logical pixels
@@ -491,6 +517,12 @@ pub struct ChartComponent {
     #[serde(default)]
     pub interactive: Option<bool>,
 
+    /// Show a crosshair and tooltip on hover and emit hover/pick actions
+    /// (line and scatter charts); candlestick charts show a crosshair with
+    /// an OHLC readout
+    #[serde(default)]
+    pub hover: Option<bool>,
+
     /// Colormap name (e.g., "viridis", "plasma", "inferno")
     #[serde(default)]
     pub colormap: Option<String>,
@@ -510,6 +542,239 @@ pub struct ChartComponent {
     /// Y-axis label
     #[serde(default)]
     pub y_label: Option<String>,
+
+    /// Technical indicators (candlestick chart)
+    #[serde(default)]
+    pub indicators: Vec<ChartIndicator>,
+
+    /// Candle style: "candles" (default), "heikinAshi" or "ohlc"
+    /// (candlestick chart)
+    #[serde(default)]
+    pub candle_style: Option<String>,
+
+    /// Prices marked with horizontal lines (candlestick chart)
+    #[serde(default)]
+    pub price_lines: Vec<f64>,
+
+    /// Mark the latest close (candlestick chart)
+    #[serde(default)]
+    pub show_last_price: Option<bool>,
+
+    /// Declarative plot spec with data, marks, encodings, scales and
+    /// annotations (spec chart; see `makepad_plot::PlotSpec`)
+    #[serde(default)]
+    pub spec: Option<serde_json::Value>,
+
+    /// Nodes (network chart); nodes named only by edges are added
+    /// automatically
+    #[serde(default)]
+    pub nodes: Vec<ChartNode>,
+
+    /// Edges between node ids (network chart)
+    #[serde(default)]
+    pub edges: Vec<ChartEdge>,
+
+    /// Node layout: "force" (default), "hierarchical" or "radial"
+    /// (network chart); "week" (default) or "month" (calendar chart)
+    #[serde(default)]
+    pub layout: Option<String>,
+
+    /// Draw arrowheads on edges, default true (network chart)
+    #[serde(default)]
+    pub directed: Option<bool>,
+
+    /// Tasks and milestones (gantt chart)
+    #[serde(default)]
+    pub tasks: Vec<ChartTask>,
+
+    /// Where the current-time line goes instead of the clock (gantt chart)
+    #[serde(default)]
+    pub now: Option<ChartTime>,
+
+    /// Draw the current-time line, default true (gantt chart)
+    #[serde(default)]
+    pub show_now: Option<bool>,
+
+    /// Path of a local GeoJSON or TopoJSON file with the regions (map chart)
+    #[serde(default)]
+    pub geo: Option<String>,
+
+    /// Map projection: "equirectangular" (default), "mercator", "albers" or
+    /// "naturalEarth" (map chart)
+    #[serde(default)]
+    pub projection: Option<String>,
+
+    /// Feature property that `labels` name regions by; the feature id or
+    /// "name" property when unset (map chart)
+    #[serde(default)]
+    pub geo_key: Option<String>,
+
+    /// Points drawn as bubbles over the regions (map chart)
+    #[serde(default)]
+    pub markers: Vec<ChartMarker>,
+
+    /// Year shown; the latest year with data, with buttons to switch years,
+    /// when unset (calendar chart)
+    #[serde(default)]
+    pub year: Option<i32>,
+
+    /// Start weeks on Monday instead of Sunday (calendar chart)
+    #[serde(default)]
+    pub monday_first: Option<bool>,
+
+    /// Name of the series whose values color the lines or points through
+    /// `colormap` (parallel and scatterMatrix charts)
+    #[serde(default)]
+    pub color_by: Option<String>,
+
+    /// Names of the series drawn on a log scale (parallel and scatterMatrix
+    /// charts)
+    #[serde(default)]
+    pub log_axes: Vec<String>,
+}
+
+/// A technical indicator requested for a candlestick chart. Parameters left
+/// out take the usual defaults (SMA/EMA 20, Bollinger 20 with 2 standard
+/// deviations, RSI 14, MACD 12/26/9).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartIndicator {
+    /// "sma", "ema", "bollinger", "vwap", "rsi" or "macd"
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    /// Lookback period (sma, ema, bollinger, rsi)
+    #[serde(default)]
+    pub period: Option<usize>,
+
+    /// Band width in standard deviations (bollinger)
+    #[serde(default)]
+    pub std_dev: Option<f64>,
+
+    /// Fast EMA period (macd)
+    #[serde(default)]
+    pub fast: Option<usize>,
+
+    /// Slow EMA period (macd)
+    #[serde(default)]
+    pub slow: Option<usize>,
+
+    /// Signal EMA period (macd)
+    #[serde(default)]
+    pub signal: Option<usize>,
+}
+
+/// A node of a network chart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartNode {
+    /// Identifier referenced by edges
+    pub id: String,
+
+    /// Display label (defaults to the id)
+    #[serde(default)]
+    pub label: Option<String>,
+
+    /// Group name; nodes in a group share a color and a legend entry
+    #[serde(default)]
+    pub group: Option<String>,
+
+    /// Relative node size (default 1)
+    #[serde(default)]
+    pub size: Option<f64>,
+
+    /// Node color as a hex string
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+/// An edge of a network chart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartEdge {
+    /// Source node id
+    pub source: String,
+
+    /// Target node id
+    pub target: String,
+
+    /// Edge weight (default 1)
+    #[serde(default)]
+    pub value: Option<f64>,
+
+    /// Label drawn at the middle of the edge
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// A task of a gantt chart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartTask {
+    /// Identifier referenced by dependencies and reschedule actions
+    pub id: String,
+
+    /// Display label (defaults to the id)
+    #[serde(default)]
+    pub label: Option<String>,
+
+    pub start: ChartTime,
+
+    /// End time; a task without one is a milestone
+    #[serde(default)]
+    pub end: Option<ChartTime>,
+
+    /// Fraction done, 0 to 1
+    #[serde(default)]
+    pub progress: Option<f64>,
+
+    /// Group name; grouped tasks sit under a collapsible header
+    #[serde(default)]
+    pub group: Option<String>,
+
+    /// Ids of tasks that have to finish first
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+
+    /// Draw as a milestone diamond at `start`
+    #[serde(default)]
+    pub milestone: Option<bool>,
+
+    /// Bar color as a hex string
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+/// A point on a map chart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartMarker {
+    /// Longitude in degrees
+    pub lon: f64,
+
+    /// Latitude in degrees
+    pub lat: f64,
+
+    /// Relative bubble size (default 1)
+    #[serde(default)]
+    pub size: Option<f64>,
+
+    /// Label drawn above the bubble
+    #[serde(default)]
+    pub label: Option<String>,
+
+    /// Bubble color as a hex string
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+/// A point in time: Unix seconds or a date string such as "2024-03-01" or
+/// "2024-03-01T09:30:00Z"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChartTime {
+    Seconds(f64),
+    Date(String),
 }
 
 fn default_chart_width() -> f64 {
@@ -572,6 +837,19 @@ pub enum ChartType {
     Surface3d,
     Scatter3d,
     Line3d,
+    // Declarative spec
+    Spec,
+    // Graphs
+    Network,
+    // Schedules
+    Gantt,
+    // Geographic
+    Map,
+    // Daily values
+    Calendar,
+    // Multi-dimensional
+    Parallel,
+    ScatterMatrix,
 }
 
 // Media Components
@@ -972,4 +1250,105 @@ mod tests {
             _ => panic!("Expected SurfaceUpdate"),
         }
     }
+
+    #[test]
+    fn test_parse_skeleton() {
+        let json = r##"{"surfaceUpdate": {"surfaceId": "main", "components": [
+            {"id": "chart", "component": {"Skeleton": {"height": 300, "label": {"literalString": "Loading chart"}}}},
+            {"id": "pending", "component": {"Skeleton": {}}}
+        ]}}"##;
+
+        let msg: A2uiMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            A2uiMessage::SurfaceUpdate(su) => {
+                match &su.components[0].component {
+                    ComponentType::Skeleton(skeleton) => {
+                        assert_eq!(skeleton.height, Some(300.0));
+                        assert_eq!(skeleton.label.as_ref().and_then(|l| l.as_literal()), Some("Loading chart"));
+                    }
+                    _ => panic!("Expected Skeleton"),
+                }
+                assert_eq!(su.components[1].component, ComponentType::Skeleton(SkeletonComponent::default()));
+            }
+            _ => panic!("Expected SurfaceUpdate"),
+        }
+    }
+
+    #[test]
+    fn test_parse_treemap_hierarchy() {
+        let json = r##"{"surfaceUpdate": {"surfaceId": "main", "components": [
+            {"id": "disk", "component": {"Chart": {
+                "chartType": "treemap",
+                "labels": ["src", "docs", "main.rs", "lib.rs"],
+                "parents": ["", "", "src", "src"],
+                "series": [{"values": [0, 12, 30, 18]}]
+            }}},
+            {"id": "budget", "component": {"Chart": {
+                "chartType": "treemap",
+                "ids": ["ops", "ops/misc", "rnd", "rnd/misc"],
+                "labels": ["Ops", "misc", "R&D", "misc"],
+                "parents": ["", "ops", "", "rnd"],
+                "series": [{"values": [0, 5, 0, 7]}]
+            }}}
+        ]}}"##;
+
+        let msg: A2uiMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            A2uiMessage::SurfaceUpdate(su) => {
+                match &su.components[0].component {
+                    ComponentType::Chart(chart) => {
+                        assert_eq!(chart.chart_type, ChartType::Treemap);
+                        assert_eq!(chart.parents, vec!["", "", "src", "src"]);
+                        assert!(chart.ids.is_empty());
+                    }
+                    _ => panic!("Expected Chart"),
+                }
+                match &su.components[1].component {
+                    ComponentType::Chart(chart) => {
+                        assert_eq!(chart.ids, vec!["ops", "ops/misc", "rnd", "rnd/misc"]);
+                        assert_eq!(chart.parents, vec!["", "ops", "", "rnd"]);
+                    }
+                    _ => panic!("Expected Chart"),
+                }
+            }
+            _ => panic!("Expected SurfaceUpdate"),
+        }
+    }
+
+    #[test]
+    fn test_parse_plot_spec() {
+        let json = r##"{"surfaceUpdate": {"surfaceId": "main", "components": [
+            {"id": "sales", "component": {"Chart": {
+                "chartType": "spec",
+                "spec": {
+                    "data": {"values": [{"month": "Jan", "sales": 12}, {"month": "Feb", "sales": 18}]},
+                    "mark": {"type": "bar", "color": "#4c78a8"},
+                    "encoding": {"x": {"field": "month"}, "y": {"field": "sales"}},
+                    "annotations": [{"type": "hline", "y": 15}]
+                }
+            }}}
+        ]}}"##;
+
+        let msg: A2uiMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            A2uiMessage::SurfaceUpdate(su) => match &su.components[0].component {
+                ComponentType::Chart(chart) => {
+                    assert_eq!(chart.chart_type, ChartType::Spec);
+                    assert!(chart.series.is_empty());
+                    let spec = chart.spec.as_ref().expect("spec");
+                    // The spec passes through untouched and compiles as the
+                    // bridge would
+                    let plot = makepad_plot::PlotSpec::from_value(spec.clone()).unwrap().compile().unwrap();
+                    assert_eq!(plot.x_categories, vec!["Jan", "Feb"]);
+                    assert_eq!(plot.marks.len(), 1);
+                    assert_eq!(plot.marks[0].y, vec![12.0, 18.0]);
+                    assert_eq!(plot.y.range.0, 0.0);
+                    assert_eq!(plot.annotations.hlines.len(), 1);
+                    assert_eq!(plot.annotations.hlines[0].y, 15.0);
+                }
+                _ => panic!("Expected Chart"),
+            },
+            _ => panic!("Expected SurfaceUpdate"),
+        }
+    }
 }
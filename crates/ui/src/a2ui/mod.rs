@@ -42,6 +42,8 @@ mod surface;
 mod value;
 mod sse;
 mod a2a_client;
+mod agent_card;
+mod auth;
 mod host;
 pub mod chart_bridge;
 
@@ -53,6 +55,8 @@ pub use surface::*;
 pub use value::*;
 pub use sse::*;
 pub use a2a_client::*;
+pub use agent_card::*;
+pub use auth::*;
 pub use host::*;
 
 use makepad_widgets::Cx;
@@ -572,4 +572,43 @@ mod tests {
         let path = StringValue::path("/user/name");
         assert_eq!(resolve_string_value(&path, &data_model), "Bob");
     }
+
+    #[test]
+    fn test_streamed_skeleton_replaced_by_component() {
+        let mut processor = A2uiMessageProcessor::with_standard_catalog();
+
+        // What the bridge sends while a create_chart call is still streaming:
+        // a placeholder under the final component id
+        processor.process_json(r##"[
+            {"beginRendering": {"surfaceId": "main", "root": "streaming-root"}},
+            {"surfaceUpdate": {"surfaceId": "main", "components": [
+                {"id": "sales-chart", "component": {"Skeleton": {"height": 200}}},
+                {"id": "streaming-root", "component": {"Column": {"children": {"explicitList": ["sales-chart"]}}}}
+            ]}}
+        ]"##).unwrap();
+        let surface = processor.get_surface("main").unwrap();
+        assert!(matches!(
+            &surface.get_component("sales-chart").unwrap().component,
+            ComponentType::Skeleton(skeleton) if skeleton.height == Some(200.0)
+        ));
+
+        // Once the arguments complete, the real component takes its place
+        let events = processor.process_json(r##"{"surfaceUpdate": {"surfaceId": "main", "components": [
+            {"id": "sales-chart", "component": {"Chart": {
+                "chartType": "bar", "labels": ["Q1", "Q2"], "series": [{"name": "Sales", "values": [1, 2]}]
+            }}}
+        ]}}"##).unwrap();
+        assert!(matches!(
+            &events[0],
+            ProcessorEvent::SurfaceUpdated(e) if e.updated_components == vec!["sales-chart".to_string()]
+        ));
+
+        let surface = processor.get_surface("main").unwrap();
+        match &surface.get_component("sales-chart").unwrap().component {
+            ComponentType::Chart(chart) => assert_eq!(chart.labels, vec!["Q1", "Q2"]),
+            other => panic!("Expected Chart, got {:?}", other),
+        }
+        assert!(surface.component_ids()
+            .all(|id| !matches!(surface.get_component(id).unwrap().component, ComponentType::Skeleton(_))));
+    }
 }
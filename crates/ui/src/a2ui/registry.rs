@@ -18,6 +18,7 @@ pub enum A2uiComponentType {
     Image,
     Icon,
     Divider,
+    Skeleton,
 
     // Interactive
     Button,
@@ -48,6 +49,7 @@ impl A2uiComponentType {
             A2uiComponentType::Image => "Image",
             A2uiComponentType::Icon => "Icon",
             A2uiComponentType::Divider => "Divider",
+            A2uiComponentType::Skeleton => "Skeleton",
             A2uiComponentType::Button => "Button",
             A2uiComponentType::TextField => "TextField",
             A2uiComponentType::CheckBox => "CheckBox",
@@ -71,6 +73,7 @@ impl A2uiComponentType {
             "Image" => Some(A2uiComponentType::Image),
             "Icon" => Some(A2uiComponentType::Icon),
             "Divider" => Some(A2uiComponentType::Divider),
+            "Skeleton" => Some(A2uiComponentType::Skeleton),
             "Button" => Some(A2uiComponentType::Button),
             "TextField" => Some(A2uiComponentType::TextField),
             "CheckBox" => Some(A2uiComponentType::CheckBox),
@@ -95,6 +98,7 @@ impl A2uiComponentType {
             A2uiComponentType::Image,
             A2uiComponentType::Icon,
             A2uiComponentType::Divider,
+            A2uiComponentType::Skeleton,
             A2uiComponentType::Button,
             A2uiComponentType::TextField,
             A2uiComponentType::CheckBox,
@@ -220,6 +224,13 @@ impl ComponentRegistry {
             implemented: true,
         });
 
+        registry.register(ComponentMapping {
+            a2ui_type: A2uiComponentType::Skeleton,
+            makepad_widget: "MpSkeleton",
+            description: "Loading placeholder for streaming content",
+            implemented: true,
+        });
+
         // Interactive components
         registry.register(ComponentMapping {
             a2ui_type: A2uiComponentType::Button,
@@ -351,6 +362,7 @@ pub fn component_type_of(component: &super::message::ComponentType) -> A2uiCompo
         ComponentType::Image(_) => A2uiComponentType::Image,
         ComponentType::Icon(_) => A2uiComponentType::Icon,
         ComponentType::Divider(_) => A2uiComponentType::Divider,
+        ComponentType::Skeleton(_) => A2uiComponentType::Skeleton,
         ComponentType::Button(_) => A2uiComponentType::Button,
         ComponentType::TextField(_) => A2uiComponentType::TextField,
         ComponentType::CheckBox(_) => A2uiComponentType::CheckBox,
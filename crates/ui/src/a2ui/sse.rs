@@ -8,9 +8,12 @@
 
 use std::io::{BufRead, BufReader};
 use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
+use super::auth::{call_with_auth, AuthError, AuthProvider};
+
 /// SSE event parsed from stream
 #[derive(Debug, Clone)]
 pub enum SseEvent {
@@ -20,6 +23,8 @@ pub enum SseEvent {
     Comment(String),
     /// Connection error
     Error(String),
+    /// Server rejected the credentials and they could not be refreshed
+    AuthRequired(String),
     /// Stream ended
     Done,
 }
@@ -84,6 +89,7 @@ impl Default for SseParser {
 pub struct SseClient {
     url: String,
     headers: Vec<(String, String)>,
+    auth: Option<Arc<dyn AuthProvider>>,
 }
 
 impl SseClient {
@@ -91,6 +97,7 @@ impl SseClient {
         SseClient {
             url: url.into(),
             headers: Vec::new(),
+            auth: None,
         }
     }
 
@@ -105,17 +112,24 @@ impl SseClient {
         self.header("Authorization", format!("Bearer {}", token.into()))
     }
 
+    /// Take credentials from a provider, refreshing them on 401
+    pub fn auth_provider(mut self, provider: Arc<dyn AuthProvider>) -> Self {
+        self.auth = Some(provider);
+        self
+    }
+
     /// Send POST request and return SSE event receiver
     pub fn post(self, body: &str) -> Result<Receiver<SseEvent>, String> {
         let (tx, rx) = mpsc::channel();
         let url = self.url.clone();
         let headers = self.headers.clone();
+        let auth = self.auth.clone();
         let body = body.to_string();
 
         // Spawn thread to handle streaming response
         thread::spawn(move || {
-            if let Err(e) = Self::stream_post(&url, &headers, &body, &tx) {
-                let _ = tx.send(SseEvent::Error(e));
+            if let Err(e) = Self::stream_post(&url, &headers, auth.as_deref(), &body, &tx) {
+                let _ = tx.send(Self::error_event(e));
             }
             let _ = tx.send(SseEvent::Done);
         });
@@ -128,11 +142,12 @@ impl SseClient {
         let (tx, rx) = mpsc::channel();
         let url = self.url.clone();
         let headers = self.headers.clone();
+        let auth = self.auth.clone();
 
         // Spawn thread to handle streaming response
         thread::spawn(move || {
-            if let Err(e) = Self::stream_get(&url, &headers, &tx) {
-                let _ = tx.send(SseEvent::Error(e));
+            if let Err(e) = Self::stream_get(&url, &headers, auth.as_deref(), &tx) {
+                let _ = tx.send(Self::error_event(e));
             }
             let _ = tx.send(SseEvent::Done);
         });
@@ -140,24 +155,33 @@ impl SseClient {
         Ok(rx)
     }
 
+    fn error_event(error: AuthError) -> SseEvent {
+        match error {
+            AuthError::LoginRequired(reason) => SseEvent::AuthRequired(reason),
+            AuthError::Failed(e) => SseEvent::Error(e),
+        }
+    }
+
     fn stream_get(
         url: &str,
         headers: &[(String, String)],
+        auth: Option<&dyn AuthProvider>,
         tx: &Sender<SseEvent>,
-    ) -> Result<(), String> {
-        let mut request = ureq::get(url)
-            .set("Accept", "text/event-stream");
+    ) -> Result<(), AuthError> {
+        let build = || {
+            let mut request = ureq::get(url)
+                .set("Accept", "text/event-stream");
 
-        for (key, value) in headers {
-            request = request.set(key, value);
-        }
+            for (key, value) in headers {
+                request = request.set(key, value);
+            }
+            request
+        };
 
-        let response = request
-            .call()
-            .map_err(|e| format!("HTTP GET failed: {}", e))?;
+        let response = call_with_auth(auth, build, None)?;
 
         if response.status() != 200 {
-            return Err(format!("HTTP error: {}", response.status()));
+            return Err(AuthError::Failed(format!("HTTP error: {}", response.status())));
         }
 
         let reader = response.into_reader();
@@ -190,30 +214,32 @@ impl SseClient {
     fn stream_post(
         url: &str,
         headers: &[(String, String)],
+        auth: Option<&dyn AuthProvider>,
         body: &str,
         tx: &Sender<SseEvent>,
-    ) -> Result<(), String> {
+    ) -> Result<(), AuthError> {
         // Build agent with read timeout to prevent thread leak on persistent connections
         let agent = ureq::AgentBuilder::new()
             .timeout_read(Duration::from_secs(10))
             .build();
 
-        let mut request = agent.post(url)
-            .set("Content-Type", "application/json")
-            .set("Accept", "text/event-stream");
+        let build = || {
+            let mut request = agent.post(url)
+                .set("Content-Type", "application/json")
+                .set("Accept", "text/event-stream");
 
-        for (key, value) in headers {
-            request = request.set(key, value);
-        }
+            for (key, value) in headers {
+                request = request.set(key, value);
+            }
+            request
+        };
 
-        // Send request
-        let response = request
-            .send_string(body)
-            .map_err(|e| format!("HTTP request failed: {}", e))?;
+        // Send request (retried once if the provider refreshes after a 401)
+        let response = call_with_auth(auth, build, Some(body))?;
 
         // Check status
         if response.status() != 200 {
-            return Err(format!("HTTP error: {}", response.status()));
+            return Err(AuthError::Failed(format!("HTTP error: {}", response.status())));
         }
 
         // Parse SSE stream
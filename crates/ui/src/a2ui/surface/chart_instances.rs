@@ -0,0 +1,113 @@
+//! Per-component plot widgets
+//!
+//! Interactive charts keep view, drag and selection state in their widget,
+//! so the surface gives every chart component its own instance instead of
+//! sharing one widget per chart type.
+
+use std::collections::HashMap;
+
+/// Plot widgets of one chart type, keyed by component id
+pub struct ChartInstances<T> {
+    widgets: HashMap<String, T>,
+    /// Components drawn since the last `begin_draw`, in draw order
+    drawn: Vec<String>,
+}
+
+impl<T> Default for ChartInstances<T> {
+    fn default() -> Self {
+        Self { widgets: HashMap::new(), drawn: Vec::new() }
+    }
+}
+
+impl<T> ChartInstances<T> {
+    /// Start a frame: no chart is drawn yet, widgets keep their state
+    pub fn begin_draw(&mut self) {
+        self.drawn.clear();
+    }
+
+    /// Widget of `component_id`, made by `create` the first time it is drawn
+    pub fn draw_instance(&mut self, component_id: &str, create: impl FnOnce() -> T) -> &mut T {
+        if !self.drawn.iter().any(|id| id == component_id) {
+            self.drawn.push(component_id.to_string());
+        }
+        self.widgets.entry(component_id.to_string()).or_insert_with(create)
+    }
+
+    /// Components drawn in the last frame; only these receive events
+    pub fn drawn(&self) -> &[String] {
+        &self.drawn
+    }
+
+    pub fn get_mut(&mut self, component_id: &str) -> Option<&mut T> {
+        self.widgets.get_mut(component_id)
+    }
+
+    /// Drop every widget, e.g. when the surface is reset
+    pub fn clear(&mut self) {
+        self.widgets.clear();
+        self.drawn.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Brush {
+        rows: Vec<usize>,
+    }
+
+    #[test]
+    fn test_state_per_component() {
+        let mut charts: ChartInstances<Brush> = ChartInstances::default();
+        charts.begin_draw();
+        charts.draw_instance("a", Brush::default).rows = vec![1, 2];
+        charts.draw_instance("b", Brush::default).rows = vec![7];
+        assert_eq!(charts.drawn(), ["a", "b"]);
+
+        // A redraw keeps each chart's brush and does not recreate it
+        charts.begin_draw();
+        assert!(charts.drawn().is_empty());
+        assert_eq!(charts.draw_instance("b", || panic!("recreated")).rows, [7]);
+        assert_eq!(charts.draw_instance("a", || panic!("recreated")).rows, [1, 2]);
+        assert_eq!(charts.drawn(), ["b", "a"]);
+    }
+
+    #[test]
+    fn test_drawn_once_per_frame() {
+        let mut charts: ChartInstances<Brush> = ChartInstances::default();
+        charts.begin_draw();
+        charts.draw_instance("a", Brush::default);
+        charts.draw_instance("a", Brush::default);
+        assert_eq!(charts.drawn(), ["a"]);
+
+        // Charts not drawn this frame keep their widget until cleared
+        charts.begin_draw();
+        charts.draw_instance("b", Brush::default);
+        assert_eq!(charts.drawn(), ["b"]);
+        assert!(charts.get_mut("a").is_some());
+        charts.clear();
+        assert!(charts.get_mut("a").is_none() && charts.drawn().is_empty());
+    }
+
+    #[test]
+    fn test_two_charts_brush_apart() {
+        use makepad_plot::{active_brushes, rows_in_brushes, PlotDimension};
+
+        // Two parallel charts of the same data, each with its own brushes
+        let data = vec![PlotDimension::new("a", vec![1.0, 2.0, 3.0, 4.0]), PlotDimension::new("b", vec![4.0, 3.0, 2.0, 1.0])];
+        let mut charts: ChartInstances<Vec<Option<(f64, f64)>>> = ChartInstances::default();
+        charts.begin_draw();
+        charts.draw_instance("left", || vec![None; 2])[0] = Some((1.0, 2.0));
+        charts.draw_instance("right", || vec![None; 2])[1] = Some((1.0, 3.0));
+
+        // Brushing one chart leaves the other's selection alone
+        let selected = |charts: &mut ChartInstances<Vec<_>>, id: &str| rows_in_brushes(&data, &active_brushes(charts.get_mut(id).unwrap()));
+        assert_eq!(selected(&mut charts, "left"), [0, 1]);
+        assert_eq!(selected(&mut charts, "right"), [1, 2, 3]);
+        charts.get_mut("left").unwrap()[0] = None;
+        assert_eq!(selected(&mut charts, "left"), [0, 1, 2, 3]);
+        assert_eq!(selected(&mut charts, "right"), [1, 2, 3]);
+    }
+}
@@ -1,4 +1,5 @@
 use makepad_widgets::*;
+use makepad_plot::PickedPoint;
 use crate::a2ui::message::UserAction;
 
 live_design! {
@@ -108,87 +109,6 @@ live_design! {
         }
     }
 
-    pub DrawA2uiChartLine = {{DrawA2uiChartLine}} {
-        fn pixel(self) -> vec4 {
-            let uv = self.pos;
-            let p1 = vec2(self.x1, self.y1);
-            let p2 = vec2(self.x2, self.y2);
-            let p = uv;
-            let line_vec = p2 - p1;
-            let line_len = length(line_vec);
-            if line_len < 0.001 {
-                return vec4(0.0, 0.0, 0.0, 0.0);
-            }
-            let t = clamp(dot(p - p1, line_vec) / (line_len * line_len), 0.0, 1.0);
-            let closest = p1 + t * line_vec;
-            let dist = length(p - closest);
-            let half_width = self.line_width * 0.5;
-            let aa = 0.02;
-            let alpha = 1.0 - smoothstep(half_width - aa, half_width + aa, dist);
-            if alpha < 0.01 {
-                return vec4(0.0, 0.0, 0.0, 0.0);
-            }
-            return vec4(self.color.rgb * self.color.a * alpha, self.color.a * alpha);
-        }
-    }
-
-    pub DrawA2uiArc = {{DrawA2uiArc}} {
-        fn pixel(self) -> vec4 {
-            let two_pi_val = 6.28318530;
-            let px = self.pos.x - 0.5;
-            let py = self.pos.y - 0.5;
-            let distance = sqrt(px * px + py * py);
-            let inner_rad = self.inner_radius * 0.5;
-            let outer_rad = 0.5;
-            let dist_mask = step(inner_rad, distance) * step(distance, outer_rad);
-            let pixel_ang = atan(py, px);
-            let sweep_val = self.end_angle - self.start_angle;
-            let rel_ang = pixel_ang - self.start_angle;
-            let norm_ang = rel_ang + two_pi_val * 4.0;
-            let wrap_ang = mod(norm_ang, two_pi_val);
-            let ang_mask = step(wrap_ang, sweep_val) * step(0.001, sweep_val);
-            let final_mask = dist_mask * ang_mask;
-            let edge_aa = 0.008;
-            let outer_aa = 1.0 - smoothstep(outer_rad - edge_aa, outer_rad + edge_aa, distance);
-            let inner_aa = smoothstep(inner_rad - edge_aa, inner_rad + edge_aa, distance);
-            let aa_alpha = outer_aa * inner_aa;
-            let alpha_val = final_mask * aa_alpha;
-            return vec4(self.color.rgb * alpha_val, self.color.a * alpha_val);
-        }
-    }
-
-    pub DrawA2uiQuad = {{DrawA2uiQuad}} {
-        fn pixel(self) -> vec4 {
-            let p = self.pos * self.rect_size;
-            let p0 = vec2(self.p0x, self.p0y);
-            let p1 = vec2(self.p1x, self.p1y);
-            let p2 = vec2(self.p2x, self.p2y);
-            let p3 = vec2(self.p3x, self.p3y);
-            let e0 = p1 - p0; let v0 = p - p0;
-            let e1 = p2 - p1; let v1 = p - p1;
-            let e2 = p3 - p2; let v2 = p - p2;
-            let e3 = p0 - p3; let v3 = p - p3;
-            let c0 = e0.x * v0.y - e0.y * v0.x;
-            let c1 = e1.x * v1.y - e1.y * v1.x;
-            let c2 = e2.x * v2.y - e2.y * v2.x;
-            let c3 = e3.x * v3.y - e3.y * v3.x;
-            let all_pos = step(0.0, c0) * step(0.0, c1) * step(0.0, c2) * step(0.0, c3);
-            let all_neg = step(c0, 0.0) * step(c1, 0.0) * step(c2, 0.0) * step(c3, 0.0);
-            let inside = min(all_pos + all_neg, 1.0);
-            if inside < 0.5 {
-                return vec4(0.0, 0.0, 0.0, 0.0);
-            }
-            let len0 = max(length(e0), 0.001);
-            let len2 = max(length(e2), 0.001);
-            let d0 = abs(c0) / len0;
-            let d2 = abs(c2) / len2;
-            let min_dist = min(d0, d2);
-            let aa = smoothstep(0.0, 1.5, min_dist);
-            let alpha = aa * self.opacity;
-            return vec4(self.color.rgb * self.color.a * alpha, self.color.a * alpha);
-        }
-    }
-
     pub DrawAudioBars = {{DrawAudioBars}} {
         fn pixel(self) -> vec4 {
             let sdf = Sdf2d::viewport(self.pos * self.rect_size);
@@ -333,6 +253,83 @@ pub enum A2uiSurfaceAction {
         url: String,
         title: String,
     },
+    /// Pointer moved over a hover-enabled chart (empty `points` when it left)
+    ChartHover {
+        surface_id: String,
+        component_id: String,
+        points: Vec<PickedPoint>,
+    },
+    /// Data point clicked on a hover-enabled chart
+    ChartPick {
+        surface_id: String,
+        component_id: String,
+        point: PickedPoint,
+    },
+    /// Treemap node clicked; `path` holds the labels from the top level down
+    TreemapClick {
+        surface_id: String,
+        component_id: String,
+        path: Vec<String>,
+    },
+    /// Treemap drilled into the node at `path`, or back out to it (empty at
+    /// the top level)
+    TreemapFocus {
+        surface_id: String,
+        component_id: String,
+        path: Vec<String>,
+    },
+    /// Network chart node clicked, reported by node id
+    NetworkNodeClick {
+        surface_id: String,
+        component_id: String,
+        node: String,
+    },
+    /// Gantt task dragged to new times, in Unix seconds
+    GanttReschedule {
+        surface_id: String,
+        component_id: String,
+        task: String,
+        start: f64,
+        end: f64,
+    },
+    /// Gantt task bar or milestone clicked
+    GanttTaskClick {
+        surface_id: String,
+        component_id: String,
+        task: String,
+    },
+    /// Map region clicked, reported by the key values are matched against
+    MapRegionClick {
+        surface_id: String,
+        component_id: String,
+        region: String,
+    },
+    /// Map marker clicked, by its index in `markers`
+    MapMarkerClick {
+        surface_id: String,
+        component_id: String,
+        index: usize,
+    },
+    /// Calendar day clicked, as `YYYY-MM-DD` with its value if it has one
+    CalendarDayClick {
+        surface_id: String,
+        component_id: String,
+        date: String,
+        value: Option<f64>,
+    },
+    /// Calendar switched to another year with its year buttons
+    CalendarYearChange {
+        surface_id: String,
+        component_id: String,
+        year: i32,
+    },
+    /// Rows brushed on a parallel coordinates or scatter matrix chart, as
+    /// indices into the series values; all rows when the brush was cleared
+    RowSelection {
+        surface_id: String,
+        component_id: String,
+        rows: Vec<usize>,
+    },
 }
 
 // ============================================================================
@@ -403,82 +400,9 @@ pub struct DrawA2uiSliderThumb {
 }
 
 // ============================================================================
-// DrawA2uiBar - for rendering bar chart bars
-// ============================================================================
-// DrawA2uiChartLine - for rendering line chart segments (chord chart)
-// ============================================================================
-
-#[derive(Live, LiveHook, LiveRegister)]
-#[repr(C)]
-pub struct DrawA2uiChartLine {
-    #[deref]
-    draw_super: DrawQuad,
-    #[live]
-    pub color: Vec4,
-    #[live(0.0)]
-    pub x1: f32,
-    #[live(0.0)]
-    pub y1: f32,
-    #[live(1.0)]
-    pub x2: f32,
-    #[live(1.0)]
-    pub y2: f32,
-    #[live(0.05)]
-    pub line_width: f32,
-}
-
-// ============================================================================
-// DrawA2uiArc - for rendering pie chart slices
-// ============================================================================
-
-#[derive(Live, LiveHook, LiveRegister)]
-#[repr(C)]
-pub struct DrawA2uiArc {
-    #[deref]
-    draw_super: DrawQuad,
-    #[live]
-    pub color: Vec4,
-    #[live(0.0)]
-    pub start_angle: f32,
-    #[live(1.0)]
-    pub end_angle: f32,
-    #[live(0.0)]
-    pub inner_radius: f32,
-}
-
-// ============================================================================
-// DrawA2uiQuad - arbitrary convex quadrilateral with AA edges (chord chart)
 // DrawAudioBars - for rendering audio waveform visualization
 // ============================================================================
 
-#[derive(Live, LiveHook, LiveRegister)]
-#[repr(C)]
-pub struct DrawA2uiQuad {
-    #[deref]
-    draw_super: DrawQuad,
-    #[live]
-    pub color: Vec4,
-    #[live(0.3)]
-    pub opacity: f32,
-    // Corner positions in pixel coords relative to the draw quad origin
-    #[live(0.0)]
-    pub p0x: f32,
-    #[live(0.0)]
-    pub p0y: f32,
-    #[live(1.0)]
-    pub p1x: f32,
-    #[live(0.0)]
-    pub p1y: f32,
-    #[live(1.0)]
-    pub p2x: f32,
-    #[live(1.0)]
-    pub p2y: f32,
-    #[live(0.0)]
-    pub p3x: f32,
-    #[live(1.0)]
-    pub p3y: f32,
-}
-
 #[derive(Live, LiveHook, LiveRegister)]
 #[repr(C)]
 pub struct DrawAudioBars {
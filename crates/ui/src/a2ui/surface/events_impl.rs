@@ -1,13 +1,194 @@
 impl Widget for A2uiSurface {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if self.skeleton_frame.is_event(event).is_some() {
+            self.redraw(cx);
+        }
+
         // Forward events to 3D chart widgets FIRST for interactive rotation/zoom
         self.plot_surface3d.handle_event(cx, event, scope);
         self.plot_scatter3d.handle_event(cx, event, scope);
         self.plot_line3d.handle_event(cx, event, scope);
+        // Sankey and chord hover highlight the flows through a node
+        self.plot_sankey.handle_event(cx, event, scope);
+        self.plot_chord.handle_event(cx, event, scope);
+        // Candlestick crosshair follows the pointer when hover is enabled
+        self.plot_candlestick.handle_event(cx, event, scope);
 
         let mut needs_redraw = false;
         let surface_id = self.get_surface_id();
 
+        // Forward pointer events to hover-enabled 2D charts and re-emit
+        // their hover/pick actions with the chart's component id
+        let mut plot_actions = Vec::new();
+        if let Some(component_id) = self.line_hover_chart.clone() {
+            let actions = cx.capture_actions(|cx| self.plot_line.handle_event(cx, event, scope));
+            plot_actions.extend(actions.iter().map(|a| (component_id.clone(), a.as_widget_action().cast::<PlotAction>())));
+        }
+        if let Some(component_id) = self.scatter_hover_chart.clone() {
+            let actions = cx.capture_actions(|cx| self.plot_scatter.handle_event(cx, event, scope));
+            plot_actions.extend(actions.iter().map(|a| (component_id.clone(), a.as_widget_action().cast::<PlotAction>())));
+        }
+        for (component_id, action) in plot_actions {
+            let surface_action = match action {
+                PlotAction::Hover(points) => A2uiSurfaceAction::ChartHover {
+                    surface_id: surface_id.clone(),
+                    component_id,
+                    points,
+                },
+                PlotAction::HoverEnd => A2uiSurfaceAction::ChartHover {
+                    surface_id: surface_id.clone(),
+                    component_id,
+                    points: Vec::new(),
+                },
+                PlotAction::Pick(point) => A2uiSurfaceAction::ChartPick {
+                    surface_id: surface_id.clone(),
+                    component_id,
+                    point,
+                },
+                PlotAction::None => continue,
+            };
+            cx.widget_action(self.widget_uid(), &scope.path, surface_action);
+        }
+
+        // Treemap clicks drill in and out; report them with the node labels
+        if let Some(component_id) = self.treemap_chart.clone() {
+            let actions = cx.capture_actions(|cx| self.plot_treemap.handle_event(cx, event, scope));
+            for action in actions.iter().map(|a| a.as_widget_action().cast::<TreemapAction>()) {
+                let surface_action = match action {
+                    TreemapAction::Clicked { labels, .. } => A2uiSurfaceAction::TreemapClick {
+                        surface_id: surface_id.clone(),
+                        component_id: component_id.clone(),
+                        path: labels,
+                    },
+                    TreemapAction::Focus { labels, .. } => A2uiSurfaceAction::TreemapFocus {
+                        surface_id: surface_id.clone(),
+                        component_id: component_id.clone(),
+                        path: labels,
+                    },
+                    TreemapAction::None => continue,
+                };
+                cx.widget_action(self.widget_uid(), &scope.path, surface_action);
+            }
+        }
+
+        // Network nodes drag, the view pans and zooms; clicks report node ids
+        for component_id in self.network_charts.drawn().to_vec() {
+            let Some(plot) = self.network_charts.get_mut(&component_id) else { continue };
+            let actions = cx.capture_actions(|cx| plot.handle_event(cx, event, scope));
+            for action in actions.iter().map(|a| a.as_widget_action().cast::<NetworkAction>()) {
+                let NetworkAction::NodeClicked { index, .. } = action else { continue };
+                let Some(node) = self.network_node_ids.get(&component_id).and_then(|ids| ids.get(index)) else { continue };
+                cx.widget_action(self.widget_uid(), &scope.path, A2uiSurfaceAction::NetworkNodeClick {
+                    surface_id: surface_id.clone(),
+                    component_id: component_id.clone(),
+                    node: node.clone(),
+                });
+            }
+        }
+
+        // Gantt bars drag to new times and group headers fold; report
+        // reschedules and clicks by task id
+        for component_id in self.gantt_charts.drawn().to_vec() {
+            let Some(plot) = self.gantt_charts.get_mut(&component_id) else { continue };
+            let actions = cx.capture_actions(|cx| plot.handle_event(cx, event, scope));
+            for action in actions.iter().map(|a| a.as_widget_action().cast::<GanttAction>()) {
+                let surface_action = match action {
+                    GanttAction::Rescheduled { id, start, end } => A2uiSurfaceAction::GanttReschedule {
+                        surface_id: surface_id.clone(),
+                        component_id: component_id.clone(),
+                        task: id,
+                        start,
+                        end,
+                    },
+                    GanttAction::TaskClicked { id } => A2uiSurfaceAction::GanttTaskClick {
+                        surface_id: surface_id.clone(),
+                        component_id: component_id.clone(),
+                        task: id,
+                    },
+                    GanttAction::GroupToggled { .. } | GanttAction::None => continue,
+                };
+                cx.widget_action(self.widget_uid(), &scope.path, surface_action);
+            }
+        }
+
+        // Maps pan, zoom and highlight regions; clicks report region keys
+        // and marker indices
+        for component_id in self.map_charts.drawn().to_vec() {
+            let Some(plot) = self.map_charts.get_mut(&component_id) else { continue };
+            let actions = cx.capture_actions(|cx| plot.handle_event(cx, event, scope));
+            for action in actions.iter().map(|a| a.as_widget_action().cast::<GeoMapAction>()) {
+                let surface_action = match action {
+                    GeoMapAction::RegionClicked { key: Some(region), .. } => A2uiSurfaceAction::MapRegionClick {
+                        surface_id: surface_id.clone(),
+                        component_id: component_id.clone(),
+                        region,
+                    },
+                    GeoMapAction::PointClicked { index } => A2uiSurfaceAction::MapMarkerClick {
+                        surface_id: surface_id.clone(),
+                        component_id: component_id.clone(),
+                        index,
+                    },
+                    GeoMapAction::RegionClicked { key: None, .. } | GeoMapAction::None => continue,
+                };
+                cx.widget_action(self.widget_uid(), &scope.path, surface_action);
+            }
+        }
+
+        // Calendars show day values on hover and switch years themselves;
+        // day clicks and year changes are reported
+        for component_id in self.calendar_charts.drawn().to_vec() {
+            let Some(plot) = self.calendar_charts.get_mut(&component_id) else { continue };
+            let actions = cx.capture_actions(|cx| plot.handle_event(cx, event, scope));
+            for action in actions.iter().map(|a| a.as_widget_action().cast::<CalendarAction>()) {
+                let surface_action = match action {
+                    CalendarAction::DayClicked { date, value, .. } => A2uiSurfaceAction::CalendarDayClick {
+                        surface_id: surface_id.clone(),
+                        component_id: component_id.clone(),
+                        date,
+                        value,
+                    },
+                    CalendarAction::YearChanged { year } => A2uiSurfaceAction::CalendarYearChange {
+                        surface_id: surface_id.clone(),
+                        component_id: component_id.clone(),
+                        year,
+                    },
+                    CalendarAction::None => continue,
+                };
+                cx.widget_action(self.widget_uid(), &scope.path, surface_action);
+            }
+        }
+
+        // Parallel coordinates reorder axes themselves; brushing on either
+        // multi-dimensional chart reports the selected rows
+        for component_id in self.parallel_charts.drawn().to_vec() {
+            let Some(plot) = self.parallel_charts.get_mut(&component_id) else { continue };
+            let actions = cx.capture_actions(|cx| plot.handle_event(cx, event, scope));
+            for action in actions.iter().map(|a| a.as_widget_action().cast::<ParallelAction>()) {
+                if let ParallelAction::SelectionChanged { rows } = action {
+                    let surface_action = A2uiSurfaceAction::RowSelection {
+                        surface_id: surface_id.clone(),
+                        component_id: component_id.clone(),
+                        rows,
+                    };
+                    cx.widget_action(self.widget_uid(), &scope.path, surface_action);
+                }
+            }
+        }
+        for component_id in self.scatter_matrix_charts.drawn().to_vec() {
+            let Some(plot) = self.scatter_matrix_charts.get_mut(&component_id) else { continue };
+            let actions = cx.capture_actions(|cx| plot.handle_event(cx, event, scope));
+            for action in actions.iter().map(|a| a.as_widget_action().cast::<ScatterMatrixAction>()) {
+                if let ScatterMatrixAction::SelectionChanged { rows } = action {
+                    let surface_action = A2uiSurfaceAction::RowSelection {
+                        surface_id: surface_id.clone(),
+                        component_id: component_id.clone(),
+                        rows,
+                    };
+                    cx.widget_action(self.widget_uid(), &scope.path, surface_action);
+                }
+            }
+        }
+
         // Handle text input events for focused text field
         if let Some(focused_idx) = self.focused_text_field_idx {
             if let Event::TextInput(te) = event {
@@ -355,6 +536,16 @@ impl Widget for A2uiSurface {
         self.checkbox_data.clear();
         self.slider_data.clear();
         self.audio_player_data.clear();
+        self.line_hover_chart = None;
+        self.scatter_hover_chart = None;
+        self.treemap_chart = None;
+        self.network_charts.begin_draw();
+        self.network_node_ids.clear();
+        self.gantt_charts.begin_draw();
+        self.map_charts.begin_draw();
+        self.calendar_charts.begin_draw();
+        self.parallel_charts.begin_draw();
+        self.scatter_matrix_charts.begin_draw();
 
         self.draw_bg.begin(cx, walk, self.layout);
 
@@ -6,6 +6,7 @@
 mod draw_types;
 mod widget;
 mod helpers;
+mod chart_instances;
 
 pub use draw_types::*;
 pub use widget::*;
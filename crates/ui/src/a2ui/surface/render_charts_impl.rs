@@ -10,9 +10,19 @@ impl A2uiSurface {
         let current_scope = self.current_scope.clone();
         let cs = current_scope.as_deref();
         match chart.chart_type {
-            ChartType::Line => chart_bridge::render_line(&mut self.plot_line, cx, scope, chart, data_model, cs),
+            ChartType::Line => {
+                if chart.hover == Some(true) {
+                    self.line_hover_chart = Some(component_id.to_string());
+                }
+                chart_bridge::render_line(&mut self.plot_line, cx, scope, chart, data_model, cs)
+            }
             ChartType::Bar => chart_bridge::render_bar(&mut self.plot_bar, cx, scope, chart, data_model, cs),
-            ChartType::Scatter => chart_bridge::render_scatter(&mut self.plot_scatter, cx, scope, chart, data_model, cs),
+            ChartType::Scatter => {
+                if chart.hover == Some(true) {
+                    self.scatter_hover_chart = Some(component_id.to_string());
+                }
+                chart_bridge::render_scatter(&mut self.plot_scatter, cx, scope, chart, data_model, cs)
+            }
             ChartType::Pie => chart_bridge::render_pie(&mut self.plot_pie, cx, scope, chart, data_model, cs),
             ChartType::Area => chart_bridge::render_area(&mut self.plot_area, cx, scope, chart, data_model, cs),
             ChartType::Radar => chart_bridge::render_radar(&mut self.plot_radar, cx, scope, chart, data_model, cs),
@@ -20,9 +30,12 @@ impl A2uiSurface {
             ChartType::Bubble => chart_bridge::render_bubble(&mut self.plot_bubble, cx, scope, chart, data_model, cs),
             ChartType::Candlestick => chart_bridge::render_candlestick(&mut self.plot_candlestick, cx, scope, chart, data_model, cs),
             ChartType::Heatmap => chart_bridge::render_heatmap(&mut self.plot_heatmap, cx, scope, chart, data_model, cs),
-            ChartType::Treemap => chart_bridge::render_treemap(&mut self.plot_treemap, cx, scope, chart, data_model, cs),
+            ChartType::Treemap => {
+                self.treemap_chart = Some(component_id.to_string());
+                chart_bridge::render_treemap(&mut self.plot_treemap, cx, scope, chart, data_model, cs)
+            }
             ChartType::Sankey => chart_bridge::render_sankey(&mut self.plot_sankey, cx, scope, chart, data_model, cs),
-            ChartType::Chord => self.render_chord_chart(cx, chart, data_model),
+            ChartType::Chord => chart_bridge::render_chord(&mut self.plot_chord, cx, scope, chart, data_model, cs),
             // New chart types from makepad-plot
             ChartType::Histogram => chart_bridge::render_histogram(&mut self.plot_histogram, cx, scope, chart, data_model, cs),
             ChartType::BoxPlot => chart_bridge::render_boxplot(&mut self.plot_boxplot, cx, scope, chart, data_model, cs),
@@ -41,250 +54,42 @@ impl A2uiSurface {
             ChartType::Surface3d => chart_bridge::render_surface3d(&mut self.plot_surface3d, cx, scope, chart, data_model, cs, component_id),
             ChartType::Scatter3d => chart_bridge::render_scatter3d(&mut self.plot_scatter3d, cx, scope, chart, data_model, cs),
             ChartType::Line3d => chart_bridge::render_line3d(&mut self.plot_line3d, cx, scope, chart, data_model, cs),
-        }
-    }
-
-
-    // ─── Chord diagram ────────────────────────────────────────────
-    // Data convention:
-    //   labels: entity names (["A", "B", "C", "D"])
-    //   series: flow matrix rows — series[i].values[j] = flow from i to j
-    fn render_chord_chart(
-        &mut self,
-        cx: &mut Cx2d,
-        chart: &ChartComponent,
-        data_model: &DataModel,
-    ) {
-        let chart_width = chart.width;
-        let chart_height = chart.height;
-
-        let walk = Walk::new(Size::Fixed(chart_width), Size::Fixed(chart_height));
-        cx.begin_turtle(walk, Layout::default());
-        let origin = cx.turtle().pos();
-
-        let mut title_height = 0.0;
-        if let Some(ref title_val) = chart.title {
-            let title = resolve_string_value_scoped(title_val, data_model, self.current_scope.as_deref());
-            if !title.is_empty() {
-                self.draw_chart_text.text_style.font_size = 14.0;
-                self.draw_chart_text.draw_walk(cx, Walk {
-                    abs_pos: Some(dvec2(origin.x + (chart_width - Self::estimate_text_width(&title, 14.0)) / 2.0, origin.y + 4.0)),
-                    ..Walk::fit()
-                }, Align::default(), &title);
-                title_height = 24.0;
+            ChartType::Spec => chart_bridge::render_spec(&mut self.plot_spec, cx, scope, chart, data_model, cs),
+            ChartType::Network => {
+                let template = self.plot_network;
+                let plot = self.network_charts.draw_instance(component_id, || NetworkGraph::new_from_ptr(cx, template));
+                let node_ids = chart_bridge::render_network(plot, cx, scope, chart, data_model, cs);
+                self.network_node_ids.insert(component_id.to_string(), node_ids);
             }
-        }
-
-        let n = chart.labels.len().min(chart.series.len());
-        if n < 2 { cx.end_turtle(); return; }
-
-        // Compute row sums for arc sizing
-        let mut row_sums: Vec<f64> = vec![0.0; n];
-        for i in 0..n {
-            let vals = &chart.series[i].values;
-            for j in 0..n.min(vals.len()) {
-                row_sums[i] += vals[j];
+            ChartType::Gantt => {
+                let template = self.plot_gantt;
+                let plot = self.gantt_charts.draw_instance(component_id, || GanttChart::new_from_ptr(cx, template));
+                chart_bridge::render_gantt(plot, cx, scope, chart, data_model, cs);
             }
-        }
-        let grand_total: f64 = row_sums.iter().sum();
-        if grand_total <= 0.0 { cx.end_turtle(); return; }
-
-        let center_x = origin.x + chart_width / 2.0;
-        let center_y = origin.y + title_height + (chart_height - title_height) / 2.0;
-        let radius = ((chart_width.min(chart_height - title_height)) / 2.0 - 40.0).max(30.0);
-        let inner_frac = 0.85; // inner_radius as fraction of outer
-        let gap_angle = 0.04_f64; // radians gap between arcs
-        let total_gap = gap_angle * n as f64;
-        let available = std::f64::consts::TAU - total_gap;
-        let pi = std::f64::consts::PI;
-
-        // Compute arc start/end angles (starting at -PI/2 = 12 o'clock)
-        let mut arc_starts: Vec<f64> = vec![0.0; n];
-        let mut arc_ends: Vec<f64> = vec![0.0; n];
-        let mut angle = -pi / 2.0;
-        for i in 0..n {
-            arc_starts[i] = angle;
-            let span = (row_sums[i] / grand_total) * available;
-            arc_ends[i] = angle + span;
-            angle += span + gap_angle;
-        }
-
-        // Draw outer arcs using DrawA2uiArc (proper GPU arc shader)
-        let arc_size = radius * 2.0 + 4.0;
-        for i in 0..n {
-            self.draw_chart_arc.color = self.get_chart_color(chart, i);
-            self.draw_chart_arc.start_angle = arc_starts[i] as f32;
-            self.draw_chart_arc.end_angle = arc_ends[i] as f32;
-            self.draw_chart_arc.inner_radius = inner_frac as f32;
-            self.draw_chart_arc.draw_walk(cx, Walk {
-                abs_pos: Some(dvec2(center_x - radius, center_y - radius)),
-                width: Size::Fixed(arc_size),
-                height: Size::Fixed(arc_size),
-                ..Walk::default()
-            });
-        }
-
-        // Draw chord ribbons using DrawA2uiQuad (proper quadrilateral shader)
-        let inner_r = radius * inner_frac;
-        let mut dst_angle_cursors: Vec<f64> = arc_starts.clone();
-        for i in 0..n {
-            let vals = &chart.series[i].values;
-            let mut src_angle_cursor = arc_starts[i];
-            for j in 0..n.min(vals.len()) {
-                let flow = vals[j];
-                if flow <= 0.0 || i == j {
-                    let span = (flow / grand_total) * available;
-                    src_angle_cursor += span;
-                    continue;
-                }
-                let src_span = (flow / grand_total) * available;
-                let src_a0 = src_angle_cursor;
-                let src_a1 = src_angle_cursor + src_span;
-                src_angle_cursor += src_span;
-
-                // Target position on target arc — consume from destination cursor
-                let dst_a0 = dst_angle_cursors[j];
-                let dst_a1 = dst_a0 + src_span;
-                dst_angle_cursors[j] += src_span;
-
-                let color = self.get_chart_color(chart, i);
-                let mut fill_color = color;
-                fill_color.w = 0.35;
-
-                // Boundary curves: top (src_a0→dst_a0) and bottom (src_a1→dst_a1)
-                // Both are quadratic beziers with control point at center
-                let s0x = center_x + inner_r * src_a0.cos();
-                let s0y = center_y + inner_r * src_a0.sin();
-                let s1x = center_x + inner_r * src_a1.cos();
-                let s1y = center_y + inner_r * src_a1.sin();
-                let d0x = center_x + inner_r * dst_a0.cos();
-                let d0y = center_y + inner_r * dst_a0.sin();
-                let d1x = center_x + inner_r * dst_a1.cos();
-                let d1y = center_y + inner_r * dst_a1.sin();
-
-                // Helper: evaluate quadratic bezier at t
-                #[inline]
-                fn qbez(t: f64, p0: f64, p1: f64, p2: f64) -> f64 {
-                    let u = 1.0 - t;
-                    u * u * p0 + 2.0 * u * t * p1 + t * t * p2
-                }
-
-                // Render ribbon fill as a strip of proper quadrilaterals
-                let t_steps = 64;
-                let pad = 2.0;
-                for step in 0..t_steps {
-                    let t = step as f64 / t_steps as f64;
-                    let t_next = (step + 1) as f64 / t_steps as f64;
-
-                    let tx0 = qbez(t, s0x, center_x, d0x);
-                    let ty0 = qbez(t, s0y, center_y, d0y);
-                    let tx1 = qbez(t_next, s0x, center_x, d0x);
-                    let ty1 = qbez(t_next, s0y, center_y, d0y);
-
-                    let bx0 = qbez(t, s1x, center_x, d1x);
-                    let by0 = qbez(t, s1y, center_y, d1y);
-                    let bx1 = qbez(t_next, s1x, center_x, d1x);
-                    let by1 = qbez(t_next, s1y, center_y, d1y);
-
-                    let corners = [(tx0, ty0), (tx1, ty1), (bx1, by1), (bx0, by0)];
-                    let min_x = corners.iter().map(|c| c.0).fold(f64::MAX, f64::min) - pad;
-                    let max_x = corners.iter().map(|c| c.0).fold(f64::MIN, f64::max) + pad;
-                    let min_y = corners.iter().map(|c| c.1).fold(f64::MAX, f64::min) - pad;
-                    let max_y = corners.iter().map(|c| c.1).fold(f64::MIN, f64::max) + pad;
-                    let w = (max_x - min_x).max(1.0);
-                    let h = (max_y - min_y).max(1.0);
-
-                    self.draw_chart_quad.color = fill_color;
-                    self.draw_chart_quad.opacity = 0.4;
-                    self.draw_chart_quad.p0x = (tx0 - min_x) as f32;
-                    self.draw_chart_quad.p0y = (ty0 - min_y) as f32;
-                    self.draw_chart_quad.p1x = (tx1 - min_x) as f32;
-                    self.draw_chart_quad.p1y = (ty1 - min_y) as f32;
-                    self.draw_chart_quad.p2x = (bx1 - min_x) as f32;
-                    self.draw_chart_quad.p2y = (by1 - min_y) as f32;
-                    self.draw_chart_quad.p3x = (bx0 - min_x) as f32;
-                    self.draw_chart_quad.p3y = (by0 - min_y) as f32;
-                    self.draw_chart_quad.draw_walk(cx, Walk {
-                        abs_pos: Some(dvec2(min_x, min_y)),
-                        width: Size::Fixed(w),
-                        height: Size::Fixed(h),
-                        ..Walk::default()
-                    });
-                }
-
-                // Draw smooth anti-aliased boundary curves on top
-                let border_color = Vec4 { x: color.x, y: color.y, z: color.z, w: 0.6 };
-                let edge_steps = 48;
-                for step in 0..edge_steps {
-                    let t = step as f64 / edge_steps as f64;
-                    let t_next = (step + 1) as f64 / edge_steps as f64;
-
-                    // Top boundary line segments
-                    let ax0 = qbez(t, s0x, center_x, d0x);
-                    let ay0 = qbez(t, s0y, center_y, d0y);
-                    let ax1 = qbez(t_next, s0x, center_x, d0x);
-                    let ay1 = qbez(t_next, s0y, center_y, d0y);
-
-                    let seg_w = ((ax1 - ax0).abs() + 2.0).max(3.0);
-                    let seg_h = ((ay1 - ay0).abs() + 2.0).max(3.0);
-                    let seg_x = ax0.min(ax1) - 1.0;
-                    let seg_y = ay0.min(ay1) - 1.0;
-
-                    self.draw_chart_line.color = border_color;
-                    self.draw_chart_line.line_width = 0.08;
-                    self.draw_chart_line.x1 = ((ax0 - seg_x) / seg_w) as f32;
-                    self.draw_chart_line.y1 = ((ay0 - seg_y) / seg_h) as f32;
-                    self.draw_chart_line.x2 = ((ax1 - seg_x) / seg_w) as f32;
-                    self.draw_chart_line.y2 = ((ay1 - seg_y) / seg_h) as f32;
-                    self.draw_chart_line.draw_walk(cx, Walk {
-                        abs_pos: Some(dvec2(seg_x, seg_y)),
-                        width: Size::Fixed(seg_w),
-                        height: Size::Fixed(seg_h),
-                        ..Walk::default()
-                    });
-
-                    // Bottom boundary line segments
-                    let bx0l = qbez(t, s1x, center_x, d1x);
-                    let by0l = qbez(t, s1y, center_y, d1y);
-                    let bx1l = qbez(t_next, s1x, center_x, d1x);
-                    let by1l = qbez(t_next, s1y, center_y, d1y);
-
-                    let seg_w2 = ((bx1l - bx0l).abs() + 2.0).max(3.0);
-                    let seg_h2 = ((by1l - by0l).abs() + 2.0).max(3.0);
-                    let seg_x2 = bx0l.min(bx1l) - 1.0;
-                    let seg_y2 = by0l.min(by1l) - 1.0;
-
-                    self.draw_chart_line.x1 = ((bx0l - seg_x2) / seg_w2) as f32;
-                    self.draw_chart_line.y1 = ((by0l - seg_y2) / seg_h2) as f32;
-                    self.draw_chart_line.x2 = ((bx1l - seg_x2) / seg_w2) as f32;
-                    self.draw_chart_line.y2 = ((by1l - seg_y2) / seg_h2) as f32;
-                    self.draw_chart_line.draw_walk(cx, Walk {
-                        abs_pos: Some(dvec2(seg_x2, seg_y2)),
-                        width: Size::Fixed(seg_w2),
-                        height: Size::Fixed(seg_h2),
-                        ..Walk::default()
-                    });
-                }
+            ChartType::Map => {
+                let template = self.plot_map;
+                let plot = self.map_charts.draw_instance(component_id, || GeoMap::new_from_ptr(cx, template));
+                chart_bridge::render_map(plot, cx, scope, chart, data_model, cs);
+            }
+            ChartType::Calendar => {
+                let template = self.plot_calendar;
+                let plot = self.calendar_charts.draw_instance(component_id, || CalendarHeatmap::new_from_ptr(cx, template));
+                chart_bridge::render_calendar(plot, cx, scope, chart, data_model, cs);
+            }
+            ChartType::Parallel => {
+                let template = self.plot_parallel;
+                let plot = self.parallel_charts.draw_instance(component_id, || ParallelCoordinates::new_from_ptr(cx, template));
+                chart_bridge::render_parallel(plot, cx, scope, chart, data_model, cs);
+            }
+            ChartType::ScatterMatrix => {
+                let template = self.plot_scatter_matrix;
+                let plot = self.scatter_matrix_charts.draw_instance(component_id, || ScatterMatrix::new_from_ptr(cx, template));
+                chart_bridge::render_scatter_matrix(plot, cx, scope, chart, data_model, cs);
             }
         }
-
-        // Draw entity labels around the outside
-        for i in 0..n {
-            let mid_angle = (arc_starts[i] + arc_ends[i]) / 2.0;
-            let label_r = radius + 18.0;
-            let lx = center_x + label_r * mid_angle.cos();
-            let ly = center_y + label_r * mid_angle.sin();
-            let label = &chart.labels[i];
-            self.draw_chart_text.text_style.font_size = 10.0;
-            self.draw_chart_text.draw_walk(cx, Walk {
-                abs_pos: Some(dvec2(lx - Self::estimate_text_width(label, 10.0) / 2.0, ly - 5.0)),
-                ..Walk::fit()
-            }, Align::default(), label);
-        }
-
-        cx.end_turtle();
     }
 
+
     // AudioPlayer Rendering
     // ============================================================================
 
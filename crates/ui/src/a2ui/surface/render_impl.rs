@@ -34,6 +34,9 @@ impl A2uiSurface {
             ComponentType::Image(img) => {
                 self.render_image(cx, img, data_model);
             }
+            ComponentType::Skeleton(skeleton) => {
+                self.render_skeleton(cx, skeleton, data_model);
+            }
             ComponentType::TextField(text_field) => {
                 self.render_text_field(cx, text_field, data_model, component_id);
             }
@@ -380,6 +383,35 @@ impl A2uiSurface {
         self.draw_image_placeholder.end(cx);
     }
 
+    fn render_skeleton(&mut self, cx: &mut Cx2d, skeleton: &SkeletonComponent, data_model: &DataModel) {
+        let height = skeleton.height.unwrap_or(40.0);
+        let walk = Walk::new(Size::fill(), Size::Fixed(height));
+        let layout = Layout {
+            padding: Padding {
+                left: 12.0,
+                right: 12.0,
+                top: 4.0,
+                bottom: 4.0,
+            },
+            align: Align { x: 0.0, y: 0.5 },
+            ..Layout::default()
+        };
+
+        self.draw_skeleton.begin(cx, walk, layout);
+        if let Some(label) = &skeleton.label {
+            let text = resolve_string_value_scoped(
+                label,
+                data_model,
+                self.current_scope.as_deref(),
+            );
+            self.draw_image_text.draw_walk(cx, Walk::fit(), Align::default(), &text);
+        }
+        self.draw_skeleton.end(cx);
+
+        // The shimmer is driven by time; keep redrawing while it is visible
+        self.skeleton_frame = cx.new_next_frame();
+    }
+
     fn render_card(
         &mut self,
         cx: &mut Cx2d,
@@ -853,49 +885,4 @@ impl A2uiSurface {
 
         cx.end_turtle();
     }
-
-    // ============================================================================
-    // Chart Rendering
-    // ============================================================================
-
-    /// Default color palette for charts
-    fn chart_palette(index: usize) -> Vec4 {
-        const COLORS: &[(f32, f32, f32)] = &[
-            (0.231, 0.510, 0.965),  // #3B82F6 blue
-            (0.161, 0.714, 0.467),  // #28B677 green
-            (0.937, 0.333, 0.314),  // #EF5550 red
-            (0.969, 0.643, 0.176),  // #F7A42D orange
-            (0.545, 0.361, 0.886),  // #8B5CE2 purple
-            (0.071, 0.741, 0.812),  // #12BDD0 teal
-            (0.957, 0.486, 0.667),  // #F47CAA pink
-            (0.400, 0.553, 0.200),  // #668D33 olive
-        ];
-        let (r, g, b) = COLORS[index % COLORS.len()];
-        Vec4 { x: r, y: g, z: b, w: 1.0 }
-    }
-
-    /// Parse a hex color string to Vec4
-    fn parse_hex_color(hex: &str) -> Option<Vec4> {
-        let hex = hex.trim_start_matches('#');
-        if hex.len() != 6 { return None; }
-        let r = u8::from_str_radix(&hex[0..2], 16).ok()? as f32 / 255.0;
-        let g = u8::from_str_radix(&hex[2..4], 16).ok()? as f32 / 255.0;
-        let b = u8::from_str_radix(&hex[4..6], 16).ok()? as f32 / 255.0;
-        Some(Vec4 { x: r, y: g, z: b, w: 1.0 })
-    }
-
-    fn get_chart_color(&self, chart: &ChartComponent, index: usize) -> Vec4 {
-        if index < chart.colors.len() {
-            if let Some(color) = Self::parse_hex_color(&chart.colors[index]) {
-                return color;
-            }
-        }
-        Self::chart_palette(index)
-    }
-
-    /// Estimate text width in pixels for chart layout
-    fn estimate_text_width(text: &str, font_size: f64) -> f64 {
-        let avg_char_width = font_size * 0.55;
-        text.len() as f64 * avg_char_width
-    }
 }
@@ -13,6 +13,7 @@ use crate::a2ui::{
     },
 };
 
+use super::chart_instances::ChartInstances;
 use super::draw_types::*;
 
 live_design! {
@@ -32,6 +33,7 @@ live_design! {
     use makepad_plot::plot::heatmap::HeatmapChart;
     use makepad_plot::plot::treemap::Treemap;
     use makepad_plot::plot::hexbin::SankeyDiagram;
+    use makepad_plot::plot::chord::ChordDiagram;
     use makepad_plot::plot::histogram::HistogramChart;
     use makepad_plot::plot::histogram::BoxPlotChart;
     use makepad_plot::plot::pie::DonutChart;
@@ -48,6 +50,13 @@ live_design! {
     use makepad_plot::plot::surface3d::Surface3D;
     use makepad_plot::plot::scatter3d::Scatter3D;
     use makepad_plot::plot::scatter3d::Line3D;
+    use makepad_plot::plot::spec_view::PlotSpecView;
+    use makepad_plot::plot::network::NetworkGraph;
+    use makepad_plot::plot::gantt::GanttChart;
+    use makepad_plot::plot::geomap::GeoMap;
+    use makepad_plot::plot::calendar::CalendarHeatmap;
+    use makepad_plot::plot::parallel::ParallelCoordinates;
+    use makepad_plot::plot::splom::ScatterMatrix;
 
     use crate::theme::colors::*;
 
@@ -56,9 +65,6 @@ live_design! {
     use crate::a2ui::surface::draw_types::DrawA2uiCheckBox;
     use crate::a2ui::surface::draw_types::DrawA2uiSliderTrack;
     use crate::a2ui::surface::draw_types::DrawA2uiSliderThumb;
-    use crate::a2ui::surface::draw_types::DrawA2uiChartLine;
-    use crate::a2ui::surface::draw_types::DrawA2uiArc;
-    use crate::a2ui::surface::draw_types::DrawA2uiQuad;
     use crate::a2ui::surface::draw_types::DrawAudioBars;
 
     pub A2uiSurface = {{A2uiSurface}} {
@@ -157,6 +163,21 @@ live_design! {
 
         draw_image: <DrawA2uiImage> {}
 
+        draw_skeleton: {
+            instance border_radius: 6.0
+            instance color_base: #2a3a5a
+            instance color_shimmer: #3a4a6a
+
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(1.0, 1.0, self.rect_size.x - 2.0, self.rect_size.y - 2.0, self.border_radius);
+                let shimmer_pos = fract(self.time * 0.8) * 1.6 - 0.3;
+                let shimmer = 1.0 - smoothstep(0.0, 0.3, abs(self.pos.x - shimmer_pos));
+                sdf.fill(mix(self.color_base, self.color_shimmer, shimmer));
+                return sdf.result;
+            }
+        }
+
         draw_text_field: <DrawA2uiTextField> {
             border_color: #5588bb
             bg_color: #2a3a5a
@@ -198,16 +219,6 @@ live_design! {
             thumb_color: #FFFFFF
         }
 
-        draw_chart_line: <DrawA2uiChartLine> {}
-        draw_chart_arc: <DrawA2uiArc> {}
-        draw_chart_text: {
-            text_style: <THEME_FONT_REGULAR> {
-                font_size: 10.0
-            }
-            color: #AABBCC
-        }
-        draw_chart_quad: <DrawA2uiQuad> {}
-
         plot_line: <LinePlot> {}
         plot_bar: <BarPlot> {}
         plot_scatter: <ScatterPlot> {}
@@ -220,6 +231,7 @@ live_design! {
         plot_heatmap: <HeatmapChart> {}
         plot_treemap: <Treemap> {}
         plot_sankey: <SankeyDiagram> {}
+        plot_chord: <ChordDiagram> {}
         plot_histogram: <HistogramChart> {}
         plot_boxplot: <BoxPlotChart> {}
         plot_donut: <DonutChart> {}
@@ -236,6 +248,13 @@ live_design! {
         plot_surface3d: <Surface3D> {}
         plot_scatter3d: <Scatter3D> {}
         plot_line3d: <Line3D> {}
+        plot_spec: <PlotSpecView> {}
+        plot_network: <NetworkGraph> {}
+        plot_gantt: <GanttChart> {}
+        plot_map: <GeoMap> {}
+        plot_calendar: <CalendarHeatmap> {}
+        plot_parallel: <ParallelCoordinates> {}
+        plot_scatter_matrix: <ScatterMatrix> {}
         draw_audio_bars: <DrawAudioBars> {}
 
         img_headphones: dep("crate://self/resources/headphones.jpg")
@@ -299,6 +318,11 @@ pub struct A2uiSurface {
     #[live]
     draw_image: DrawA2uiImage,
 
+    /// Draw skeleton placeholder (components still streaming)
+    #[redraw]
+    #[live]
+    draw_skeleton: DrawColor,
+
     /// Draw text field background
     #[redraw]
     #[live]
@@ -331,25 +355,6 @@ pub struct A2uiSurface {
     #[live]
     draw_slider_thumb: DrawA2uiSliderThumb,
 
-    /// Draw chart line segment (chord chart)
-    #[redraw]
-    #[live]
-    draw_chart_line: DrawA2uiChartLine,
-
-    /// Draw chart arc (chord chart)
-    #[redraw]
-    #[live]
-    draw_chart_arc: DrawA2uiArc,
-
-    /// Draw chart text (chord chart labels)
-    #[live]
-    draw_chart_text: DrawText,
-
-    /// Draw chart arbitrary quadrilateral (chord ribbons)
-    #[redraw]
-    #[live]
-    draw_chart_quad: DrawA2uiQuad,
-
     // makepad-plot chart widget instances
     #[live] plot_line: LinePlot,
     #[live] plot_bar: BarPlot,
@@ -363,6 +368,7 @@ pub struct A2uiSurface {
     #[live] plot_heatmap: HeatmapChart,
     #[live] plot_treemap: Treemap,
     #[live] plot_sankey: SankeyDiagram,
+    #[live] plot_chord: ChordDiagram,
     #[live] plot_histogram: HistogramChart,
     #[live] plot_boxplot: BoxPlotChart,
     #[live] plot_donut: DonutChart,
@@ -379,6 +385,19 @@ pub struct A2uiSurface {
     #[live] plot_surface3d: Surface3D,
     #[live] plot_scatter3d: Scatter3D,
     #[live] plot_line3d: Line3D,
+    #[live] plot_spec: PlotSpecView,
+    /// Template for the network widget of each network chart
+    #[live] plot_network: Option<LivePtr>,
+    /// Template for the gantt widget of each gantt chart
+    #[live] plot_gantt: Option<LivePtr>,
+    /// Template for the map widget of each map chart
+    #[live] plot_map: Option<LivePtr>,
+    /// Template for the calendar widget of each calendar chart
+    #[live] plot_calendar: Option<LivePtr>,
+    /// Templates for the widgets of each parallel coordinates and scatter
+    /// matrix chart
+    #[live] plot_parallel: Option<LivePtr>,
+    #[live] plot_scatter_matrix: Option<LivePtr>,
     /// Draw audio bars visualization
     #[redraw]
     #[live]
@@ -527,6 +546,54 @@ pub struct A2uiSurface {
     /// Currently playing audio component ID (for Play/Stop toggle)
     #[rust]
     playing_component_id: Option<String>,
+
+    /// Frame requested while skeletons are on screen, so their shimmer
+    /// keeps moving until the real components arrive
+    #[rust]
+    skeleton_frame: NextFrame,
+
+    /// Hover-enabled line chart, if any. Line charts share one plot widget,
+    /// so only the last one drawn receives pointer events.
+    #[rust]
+    line_hover_chart: Option<String>,
+
+    /// Hover-enabled scatter chart, if any (see `line_hover_chart`)
+    #[rust]
+    scatter_hover_chart: Option<String>,
+
+    /// Treemap chart receiving clicks for drill-down (see `line_hover_chart`)
+    #[rust]
+    treemap_chart: Option<String>,
+
+    /// Network charts by component id; each drags and zooms on its own
+    #[rust]
+    network_charts: ChartInstances<NetworkGraph>,
+
+    /// Node ids of each network chart, in widget order
+    #[rust]
+    network_node_ids: std::collections::HashMap<String, Vec<String>>,
+
+    /// Gantt charts by component id; a bar drag stays with its own chart
+    #[rust]
+    gantt_charts: ChartInstances<GanttChart>,
+
+    /// Map charts by component id; each loads its shapes once and keeps
+    /// its own pan and zoom
+    #[rust]
+    map_charts: ChartInstances<GeoMap>,
+
+    /// Calendar charts by component id; each keeps the year it shows
+    #[rust]
+    calendar_charts: ChartInstances<CalendarHeatmap>,
+
+    /// Parallel coordinates charts by component id; each keeps its own
+    /// axis order and brushes
+    #[rust]
+    parallel_charts: ChartInstances<ParallelCoordinates>,
+
+    /// Scatter matrix charts by component id; each keeps its own brush
+    #[rust]
+    scatter_matrix_charts: ChartInstances<ScatterMatrix>,
 }
 
 impl A2uiSurface {
@@ -541,6 +608,13 @@ impl A2uiSurface {
     pub fn clear(&mut self) {
         // Reset the processor to clear all surfaces and components
         self.processor = Some(A2uiMessageProcessor::with_standard_catalog());
+        self.network_charts.clear();
+        self.network_node_ids.clear();
+        self.gantt_charts.clear();
+        self.map_charts.clear();
+        self.calendar_charts.clear();
+        self.parallel_charts.clear();
+        self.scatter_matrix_charts.clear();
     }
 
     /// Apply theme colors to all A2UI components